@@ -0,0 +1,374 @@
+use std::collections::{HashSet, VecDeque};
+
+use zeroql::ast::{Ast, AstKind};
+use zeroutils_path::Path;
+
+use crate::{
+    store::{Key, KvStore},
+    ZerodbError, ZerodbResult,
+};
+
+use super::{SchemaChange, TableRelation};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Catalog` keeps the definitions that describe a database, as opposed to the data in it.
+///
+/// Every defined schema item is kept by its path, along with the catalog version it was last
+/// changed at. The catalog version counts the schema changes applied so far.
+///
+/// ```txt
+/// c / d / <path..> -> SchemaEntry
+/// c / v            -> u64
+/// ```
+///
+/// Table relations are schema changes too, and are stored in both directions under the database
+/// of their tables, so both the parents and the children of a table are a prefix scan away.
+///
+/// ```txt
+/// c / r / up / <database> / <child> / <kind> / <parent>   -> ()
+/// c / r / down / <database> / <parent> / <kind> / <child> -> ()
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Catalog;
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Catalog {
+    /// Creates a new catalog.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Stores a table relation between tables of `database`.
+    ///
+    /// Fails if the relation would make a table a kind of itself.
+    pub(super) fn relate_tables(
+        &self,
+        store: &mut impl KvStore,
+        database: &Path,
+        relation: &TableRelation,
+    ) -> ZerodbResult<()> {
+        if self
+            .subtables(store, database, &relation.child)?
+            .contains(&relation.parent)
+        {
+            return Err(ZerodbError::InvalidGraphOperation(format!(
+                "`{} ->> {} ->> {}` would make `{}` a kind of itself",
+                relation.child, relation.kind, relation.parent, relation.parent
+            )));
+        }
+
+        store.put(up_key(database, relation).as_bytes(), vec![])?;
+        store.put(down_key(database, relation).as_bytes(), vec![])?;
+
+        Ok(())
+    }
+
+    /// Removes a table relation between tables of `database`, returning whether it existed.
+    pub(super) fn unrelate_tables(
+        &self,
+        store: &mut impl KvStore,
+        database: &Path,
+        relation: &TableRelation,
+    ) -> ZerodbResult<bool> {
+        let existed = store.get(up_key(database, relation).as_bytes())?.is_some();
+
+        store.delete(up_key(database, relation).as_bytes())?;
+        store.delete(down_key(database, relation).as_bytes())?;
+
+        Ok(existed)
+    }
+
+    /// Gets the tables `table` is directly related to, optionally only through relations of type
+    /// `kind`.
+    pub fn parent_tables(
+        &self,
+        store: &impl KvStore,
+        database: &Path,
+        table: &str,
+        kind: Option<&str>,
+    ) -> ZerodbResult<Vec<TableRelation>> {
+        self.relations(store, database, "up", table, kind)
+    }
+
+    /// Gets the tables directly related to `table`, optionally only through relations of type
+    /// `kind`.
+    pub fn child_tables(
+        &self,
+        store: &impl KvStore,
+        database: &Path,
+        table: &str,
+        kind: Option<&str>,
+    ) -> ZerodbResult<Vec<TableRelation>> {
+        self.relations(store, database, "down", table, kind)
+    }
+
+    /// Gets `table` followed by every table that is a kind of it through any chain of table
+    /// relations, in breadth-first order.
+    pub fn subtables(
+        &self,
+        store: &impl KvStore,
+        database: &Path,
+        table: &str,
+    ) -> ZerodbResult<Vec<String>> {
+        let mut tables = vec![table.to_string()];
+        let mut seen = HashSet::from([table.to_string()]);
+        let mut queue = VecDeque::from([table.to_string()]);
+
+        while let Some(parent) = queue.pop_front() {
+            for relation in self.child_tables(store, database, &parent, None)? {
+                if seen.insert(relation.child.clone()) {
+                    tables.push(relation.child.clone());
+                    queue.push_back(relation.child);
+                }
+            }
+        }
+
+        Ok(tables)
+    }
+
+    /// Executes a `RELATE` expression with `->>` or `<<-` arrows, storing its table relations as a
+    /// schema change.
+    ///
+    /// ```txt
+    /// RELATE [bird, fish] ->> is_a ->> animal
+    /// ```
+    pub fn execute_relate(
+        &self,
+        store: &mut impl KvStore,
+        database: &Path,
+        ast: &Ast,
+    ) -> ZerodbResult<Vec<TableRelation>> {
+        let AstKind::Relate { relate_op, .. } = &ast.kind else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let relations = TableRelation::from_ast(relate_op)?;
+        self.apply_schema_change(
+            store,
+            &SchemaChange::Relate {
+                database: database.clone(),
+                relations: relations.clone(),
+            },
+        )?;
+
+        Ok(relations)
+    }
+
+    /// Executes a `DELETE` expression with `->>` or `<<-` arrows, removing its table relations as a
+    /// schema change and returning the ones that existed.
+    ///
+    /// ```txt
+    /// DELETE bird ->> is_a ->> animal
+    /// ```
+    pub fn execute_delete(
+        &self,
+        store: &mut impl KvStore,
+        database: &Path,
+        ast: &Ast,
+    ) -> ZerodbResult<Vec<TableRelation>> {
+        let AstKind::Delete { target, .. } = &ast.kind else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let mut existing = vec![];
+        for relation in TableRelation::from_ast(target)? {
+            if store.get(up_key(database, &relation).as_bytes())?.is_some() {
+                existing.push(relation);
+            }
+        }
+
+        self.apply_schema_change(
+            store,
+            &SchemaChange::Unrelate {
+                database: database.clone(),
+                relations: existing.clone(),
+            },
+        )?;
+
+        Ok(existing)
+    }
+
+    /// Renames a table of `database` in every table relation it is part of.
+    pub(super) fn rename_table(
+        &self,
+        store: &mut impl KvStore,
+        database: &Path,
+        name: &str,
+        new_name: &str,
+    ) -> ZerodbResult<()> {
+        let prefix = relations_prefix("up", database);
+        for (key, _) in store.scan_prefix(prefix.as_bytes())? {
+            let segments = Key::decode_str(&key);
+            let [_, _, _, _, child, kind, parent] = segments.as_slice() else {
+                continue;
+            };
+
+            if child != name && parent != name {
+                continue;
+            }
+
+            let rename = |table: &String| match table == name {
+                true => new_name.to_string(),
+                false => table.clone(),
+            };
+
+            let relation = TableRelation::new(child, kind, parent);
+            let renamed = TableRelation::new(rename(child), kind, rename(parent));
+
+            self.unrelate_tables(store, database, &relation)?;
+            store.put(up_key(database, &renamed).as_bytes(), vec![])?;
+            store.put(down_key(database, &renamed).as_bytes(), vec![])?;
+        }
+
+        Ok(())
+    }
+
+    fn relations(
+        &self,
+        store: &impl KvStore,
+        database: &Path,
+        direction: &str,
+        table: &str,
+        kind: Option<&str>,
+    ) -> ZerodbResult<Vec<TableRelation>> {
+        let mut prefix = relations_prefix(direction, database).with(table);
+        if let Some(kind) = kind {
+            prefix = prefix.with(kind);
+        }
+
+        let relations = store
+            .scan_prefix(prefix.as_bytes())?
+            .into_iter()
+            .filter_map(|(key, _)| match Key::decode_str(&key).as_slice() {
+                [_, _, _, _, table, kind, other] if direction == "up" => {
+                    Some(TableRelation::new(table, kind, other))
+                }
+                [_, _, _, _, table, kind, other] => Some(TableRelation::new(other, kind, table)),
+                _ => None,
+            })
+            .collect();
+
+        Ok(relations)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn relations_prefix(direction: &str, database: &Path) -> Key {
+    Key::new()
+        .with("c")
+        .with("r")
+        .with(direction)
+        .with(database.to_string())
+}
+
+fn up_key(database: &Path, relation: &TableRelation) -> Key {
+    relations_prefix("up", database)
+        .with(&relation.child)
+        .with(&relation.kind)
+        .with(&relation.parent)
+}
+
+fn down_key(database: &Path, relation: &TableRelation) -> Key {
+    relations_prefix("down", database)
+        .with(&relation.parent)
+        .with(&relation.kind)
+        .with(&relation.child)
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::parser::Parser;
+
+    use crate::store::MemoryKvStore;
+
+    use super::*;
+
+    #[test]
+    fn test_catalog_table_relations() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let catalog = Catalog::new();
+        let db: Path = "acme".parse()?;
+
+        for input in [
+            "RELATE animal ->> is_a ->> thing",
+            "RELATE [bird, fish] ->> is_a ->> animal",
+            "RELATE robin ->> is_a ->> bird",
+            "RELATE bird ->> eats ->> fish",
+        ] {
+            let ast = Parser::new(input, 20).parse_exp()?.unwrap();
+            catalog.execute_relate(&mut store, &db, &ast)?;
+        }
+
+        assert_eq!(
+            catalog.subtables(&store, &db, "animal")?,
+            ["animal", "bird", "fish", "robin"]
+        );
+        assert_eq!(
+            catalog.parent_tables(&store, &db, "bird", Some("is_a"))?,
+            vec![TableRelation::new("bird", "is_a", "animal")]
+        );
+        assert_eq!(catalog.child_tables(&store, &db, "fish", None)?.len(), 1);
+
+        // Relations only hold between the tables of their database.
+        let other: Path = "rate".parse()?;
+        assert_eq!(catalog.subtables(&store, &other, "animal")?, ["animal"]);
+        assert!(catalog
+            .parent_tables(&store, &other, "bird", None)?
+            .is_empty());
+
+        // Cycles are rejected.
+        let ast = Parser::new("RELATE thing ->> is_a ->> robin", 20)
+            .parse_exp()?
+            .unwrap();
+        assert!(catalog.execute_relate(&mut store, &db, &ast).is_err());
+
+        let ast = Parser::new("DELETE bird ->> is_a ->> animal", 20)
+            .parse_exp()?
+            .unwrap();
+        assert_eq!(catalog.execute_delete(&mut store, &db, &ast)?.len(), 1);
+        assert_eq!(catalog.execute_delete(&mut store, &db, &ast)?.len(), 0);
+        assert_eq!(
+            catalog.subtables(&store, &db, "animal")?,
+            ["animal", "fish", "bird", "robin"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_catalog_renames() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let catalog = Catalog::new();
+        let db: Path = "acme".parse()?;
+
+        for input in [
+            "RELATE bird ->> is_a ->> animal",
+            "RELATE robin ->> is_a ->> bird",
+        ] {
+            let ast = Parser::new(input, 20).parse_exp()?.unwrap();
+            catalog.execute_relate(&mut store, &db, &ast)?;
+        }
+
+        catalog.rename_table(&mut store, &db, "bird", "avian")?;
+        assert_eq!(
+            catalog.subtables(&store, &db, "animal")?,
+            ["animal", "avian", "robin"]
+        );
+        assert!(catalog.parent_tables(&store, &db, "bird", None)?.is_empty());
+
+        Ok(())
+    }
+}
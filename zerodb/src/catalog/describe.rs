@@ -0,0 +1,352 @@
+use serde::{Deserialize, Serialize};
+use zeroql::{
+    ast::{Ast, AstKind},
+    sema::SchemaMeta,
+};
+use zeroutils_path::Path;
+
+use crate::{store::KvStore, ZerodbError, ZerodbResult};
+
+use super::{
+    schema::{deserialize_path, item_database, schema_path, serialize_path},
+    Catalog, SchemaEntry,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `SchemaDescription` is what a `DESCRIBE` statement returns for a schema item.
+///
+/// It is made from the definition kept in the [`Catalog`], so tools and migration scripts can
+/// introspect a live database without parsing the statements that built it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaDescription {
+    /// The path of the schema item.
+    #[serde(serialize_with = "serialize_path")]
+    #[serde(deserialize_with = "deserialize_path")]
+    pub path: Path,
+
+    /// The kind of the schema item, e.g. `table`.
+    pub kind: String,
+
+    /// The catalog version the schema item was last changed at.
+    pub version: u64,
+
+    /// The definition of the schema item.
+    pub meta: SchemaMeta,
+
+    /// The schema items directly inside a namespace or database, or the indexes of a table or
+    /// edge, in key order.
+    pub items: Vec<SchemaDescription>,
+
+    /// The `DEFINE` statement that defines the schema item as it is now, if it was asked for.
+    pub definition: Option<String>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Catalog {
+    /// Describes the schema item a `DESCRIBE` statement names.
+    ///
+    /// The schema item is looked up in the database the statement names, or in `database` if it
+    /// names none. A schema item that does not exist, or is of another kind, is an error unless the
+    /// statement says `IF EXISTS`, in which case nothing is described. With `with_definition`, the
+    /// description and the descriptions of the items inside it carry their `DEFINE` statements.
+    ///
+    /// Statements that are not `DESCRIBE`s describe nothing.
+    pub fn describe(
+        &self,
+        store: &impl KvStore,
+        ast: &Ast,
+        database: &Path,
+        with_definition: bool,
+    ) -> ZerodbResult<Option<SchemaDescription>> {
+        use AstKind::*;
+
+        let (path, if_exists, table) = match &ast.kind {
+            DescribeNamespace { subject, if_exists } => (
+                schema_path(subject, &None, &Path::default())?,
+                if_exists,
+                None,
+            ),
+            DescribeDatabase {
+                subject,
+                if_exists,
+                namespace: in_database,
+            }
+            | DescribeTable {
+                subject,
+                if_exists,
+                database: in_database,
+            }
+            | DescribeEdge {
+                subject,
+                if_exists,
+                database: in_database,
+            }
+            | DescribeType {
+                subject,
+                if_exists,
+                database: in_database,
+            }
+            | DescribeEnum {
+                subject,
+                if_exists,
+                database: in_database,
+            }
+            | DescribeModule {
+                subject,
+                if_exists,
+                database: in_database,
+            }
+            | DescribeParam {
+                subject,
+                if_exists,
+                database: in_database,
+            }
+            | DescribeFunction {
+                subject,
+                if_exists,
+                database: in_database,
+            } => (
+                schema_path(subject, in_database, database)?,
+                if_exists,
+                None,
+            ),
+            DescribeIndex {
+                subject,
+                if_exists,
+                table,
+                database: in_database,
+            } => {
+                let AstKind::Identifier(table) = table.kind else {
+                    return Err(ZerodbError::UnsupportedExpression(table.kind.to_string()));
+                };
+
+                (
+                    schema_path(subject, in_database, database)?,
+                    if_exists,
+                    Some(table),
+                )
+            }
+            _ => return Ok(None),
+        };
+
+        // An index is only found on the table the statement names.
+        let existing = self.schema(store, &path)?.filter(|entry| {
+            Some(entry.meta.kind()) == described_kind(&ast.kind)
+                && match (&entry.meta, table) {
+                    (SchemaMeta::Index { table, .. }, Some(described)) => table == described,
+                    _ => true,
+                }
+        });
+
+        let Some(entry) = existing else {
+            if *if_exists {
+                return Ok(None);
+            }
+
+            return Err(ZerodbError::UndefinedSchemaItem(path.to_string()));
+        };
+
+        // Only the items the described one contains are read, and the indexes of a table are
+        // kept alongside it in its database.
+        let schemas = match entry.meta {
+            SchemaMeta::Namespace() | SchemaMeta::Database() => self.schemas_in(store, &path)?,
+            SchemaMeta::Table { .. } | SchemaMeta::Edge { .. } => self
+                .schemas_in(store, &item_database(&path)?)?
+                .into_iter()
+                .filter(|(_, entry)| matches!(entry.meta, SchemaMeta::Index { .. }))
+                .collect(),
+            _ => vec![],
+        };
+
+        Ok(Some(describe_entry(
+            path,
+            entry,
+            &schemas,
+            with_definition,
+        )?))
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn described_kind(kind: &AstKind) -> Option<&'static str> {
+    match kind {
+        AstKind::DescribeNamespace { .. } => Some("namespace"),
+        AstKind::DescribeDatabase { .. } => Some("database"),
+        AstKind::DescribeTable { .. } => Some("table"),
+        AstKind::DescribeEdge { .. } => Some("edge"),
+        AstKind::DescribeType { .. } => Some("type"),
+        AstKind::DescribeEnum { .. } => Some("enum"),
+        AstKind::DescribeIndex { .. } => Some("index"),
+        AstKind::DescribeModule { .. } => Some("module"),
+        AstKind::DescribeParam { .. } => Some("param"),
+        AstKind::DescribeFunction { .. } => Some("function"),
+        _ => None,
+    }
+}
+
+/// Describes a schema item, along with the items in `schemas` that are directly inside it.
+///
+/// Indexes are described with the table or edge they belong to rather than with their database.
+fn describe_entry(
+    path: Path,
+    entry: SchemaEntry,
+    schemas: &[(Path, SchemaEntry)],
+    with_definition: bool,
+) -> ZerodbResult<SchemaDescription> {
+    let parent = path.to_string();
+    let parent = parent.trim_start_matches('/');
+    let name = parent.rsplit('/').next().unwrap_or_default();
+
+    let definition = match with_definition {
+        true => entry.meta.definition(name)?,
+        false => None,
+    };
+
+    let database = parent.rsplit_once('/').map(|(database, _)| database);
+    let items = schemas
+        .iter()
+        .filter(|(item, item_entry)| {
+            let item = item.to_string();
+            let item = item.trim_start_matches('/');
+            let item_parent = item.rsplit_once('/').map(|(parent, _)| parent);
+
+            match (&entry.meta, &item_entry.meta) {
+                (
+                    SchemaMeta::Table { .. } | SchemaMeta::Edge { .. },
+                    SchemaMeta::Index { table, .. },
+                ) => table == name && item_parent == database,
+                (_, SchemaMeta::Index { .. }) => false,
+                _ => item_parent == Some(parent),
+            }
+        })
+        .map(|(item, entry)| describe_entry(item.clone(), entry.clone(), schemas, with_definition))
+        .collect::<ZerodbResult<_>>()?;
+
+    Ok(SchemaDescription {
+        kind: entry.meta.kind().to_string(),
+        version: entry.version,
+        meta: entry.meta,
+        items,
+        definition,
+        path,
+    })
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::parser::Parser;
+
+    use crate::store::MemoryKvStore;
+
+    use super::*;
+
+    fn apply(store: &mut MemoryKvStore, database: &Path, input: &str) -> anyhow::Result<()> {
+        let catalog = Catalog::new();
+        for statement in Parser::new(input, 50)
+            .parse_program()
+            .into_result()?
+            .kind
+            .unwrap_program()
+        {
+            if let Some(change) = catalog.schema_change(store, &statement, database)? {
+                catalog.apply_schema_change(store, &change)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn describe(
+        store: &MemoryKvStore,
+        database: &Path,
+        input: &str,
+        with_definition: bool,
+    ) -> anyhow::Result<Option<SchemaDescription>> {
+        let ast = Parser::new(input, 50).parse_program().into_result()?;
+        let statement = &ast.kind.unwrap_program()[0];
+        Ok(Catalog::new().describe(store, statement, database, with_definition)?)
+    }
+
+    #[test]
+    fn test_catalog_describe() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let shop = "shop".parse::<Path>()?;
+
+        apply(
+            &mut store,
+            &Path::default(),
+            "DEFINE DATABASE shop; DEFINE DATABASE shopping",
+        )?;
+        apply(
+            &mut store,
+            &shop,
+            "DEFINE TABLE person FIELDS name TYPE string UNIQUE; DEFINE INDEX idx_name ON TABLE person FIELDS name",
+        )?;
+        apply(&mut store, &"shopping".parse()?, "DEFINE TABLE cart")?;
+
+        let description = describe(&store, &shop, "DESCRIBE TABLE person", true)?.unwrap();
+        assert_eq!(description.path, "shop/person".parse()?);
+        assert_eq!(description.kind, "table");
+        assert_eq!(description.version, 3);
+        assert_eq!(
+            description.definition.as_deref(),
+            Some("DEFINE TABLE person FIELDS name TYPE string UNIQUE")
+        );
+
+        // Tables are described along with their indexes.
+        assert_eq!(description.items.len(), 1);
+        assert_eq!(description.items[0].path, "shop/idx_name".parse()?);
+        assert_eq!(
+            description.items[0].definition.as_deref(),
+            Some("DEFINE INDEX idx_name ON TABLE person FIELDS name")
+        );
+
+        // Databases are described along with the items inside them, and only those.
+        let description =
+            describe(&store, &Path::default(), "DESCRIBE DATABASE shop", false)?.unwrap();
+        assert_eq!(
+            description
+                .items
+                .iter()
+                .map(|item| (item.kind.as_str(), item.definition.is_none()))
+                .collect::<Vec<_>>(),
+            vec![("table", true)]
+        );
+        assert_eq!(description.items[0].items[0].kind, "index");
+
+        // Items are looked up in the database the statement names, and as the kind it names.
+        assert!(describe(
+            &store,
+            &Path::default(),
+            "DESCRIBE TABLE person ON DB shop",
+            false
+        )?
+        .is_some());
+        assert!(describe(
+            &store,
+            &shop,
+            "DESCRIBE INDEX idx_name ON TABLE person",
+            false
+        )?
+        .is_some());
+        assert!(describe(&store, &shop, "DESCRIBE INDEX idx_name ON TABLE pet", false).is_err());
+        assert!(describe(&store, &shop, "DESCRIBE EDGE person", false).is_err());
+        assert!(describe(&store, &shop, "DESCRIBE TABLE pet IF EXISTS", false)?.is_none());
+        assert!(describe(&store, &shop, "SELECT * FROM person", false)?.is_none());
+
+        Ok(())
+    }
+}
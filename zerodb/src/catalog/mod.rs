@@ -0,0 +1,28 @@
+//! The catalog of definitions that describe a database.
+//!
+//! Table relations written with `->>` and `<<-` are kept here and form a type hierarchy that the
+//! planner and graph traversals follow.
+//!
+//! ```txt
+//! RELATE bird ->> is_a ->> animal
+//!
+//! SELECT * FROM animal -- also reads the records of `bird`
+//! ```
+//!
+//! Every defined schema item is also kept here, so that analysis can resolve names against what
+//! earlier statements defined and `DESCRIBE` statements can report how items are defined now.
+
+#[allow(clippy::module_inception)]
+mod catalog;
+mod describe;
+mod relation;
+mod schema;
+
+//--------------------------------------------------------------------------------------------------
+// Exports
+//--------------------------------------------------------------------------------------------------
+
+pub use catalog::*;
+pub use describe::*;
+pub use relation::*;
+pub use schema::*;
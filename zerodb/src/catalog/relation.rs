@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use zeroql::ast::{Ast, AstKind, RelateArrow};
+
+use crate::{ZerodbError, ZerodbResult};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `TableRelation` is a relation between two tables rather than two records, written with `->>`
+/// or `<<-`, e.g. `RELATE bird ->> is_a ->> animal`.
+///
+/// Table relations form a type hierarchy: the child table is treated as a kind of the parent
+/// table, so querying the parent also reaches the records of the child.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TableRelation {
+    /// The table the relation starts from.
+    pub child: String,
+
+    /// The type of the relation, e.g. `is_a`.
+    pub kind: String,
+
+    /// The table the relation points to.
+    pub parent: String,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl TableRelation {
+    /// Creates a new table relation.
+    pub fn new(
+        child: impl Into<String>,
+        kind: impl Into<String>,
+        parent: impl Into<String>,
+    ) -> Self {
+        Self {
+            child: child.into(),
+            kind: kind.into(),
+            parent: parent.into(),
+        }
+    }
+
+    /// Checks if a relate operation uses `->>` or `<<-` arrows.
+    pub fn is_table_relate_op(ast: &Ast) -> bool {
+        matches!(
+            &ast.kind,
+            AstKind::RelateOp {
+                l_op: RelateArrow::MultiRight | RelateArrow::MultiLeft,
+                ..
+            } | AstKind::RelateOp {
+                r_op: RelateArrow::MultiRight | RelateArrow::MultiLeft,
+                ..
+            }
+        )
+    }
+
+    /// Gets the table relations written by a `->>` or `<<-` relate operation.
+    ///
+    /// Either side may be a list of tables, in which case every pair is related.
+    pub fn from_ast(ast: &Ast) -> ZerodbResult<Vec<Self>> {
+        let AstKind::RelateOp {
+            left,
+            l_op,
+            edge,
+            r_op,
+            right,
+        } = &ast.kind
+        else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let kind = match &edge.kind {
+            AstKind::RelateEdgeId {
+                subject,
+                depth: None,
+                ..
+            } => match subject.kind {
+                AstKind::Identifier(kind) => kind,
+                _ => return Err(ZerodbError::UnsupportedExpression(subject.kind.to_string())),
+            },
+            _ => {
+                return Err(ZerodbError::InvalidGraphOperation(format!(
+                    "expected a single relation type, found: {}",
+                    edge.kind
+                )))
+            }
+        };
+
+        let (children, parents) = match (l_op, r_op) {
+            (RelateArrow::MultiRight, RelateArrow::MultiRight) => (left, right),
+            (RelateArrow::MultiLeft, RelateArrow::MultiLeft) => (right, left),
+            _ => {
+                return Err(ZerodbError::InvalidGraphOperation(
+                    "a table relation must be written with matching `->>` or `<<-` arrows"
+                        .to_string(),
+                ))
+            }
+        };
+
+        let children = table_names(children)?;
+        let parents = table_names(parents)?;
+
+        let mut relations = vec![];
+        for child in children.iter() {
+            for parent in parents.iter() {
+                relations.push(TableRelation::new(*child, kind, *parent));
+            }
+        }
+
+        Ok(relations)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn table_names<'a>(ast: &Ast<'a>) -> ZerodbResult<Vec<&'a str>> {
+    match &ast.kind {
+        AstKind::ListLiteral(items) => items
+            .iter()
+            .map(table_names)
+            .collect::<ZerodbResult<Vec<_>>>()
+            .map(|names| names.concat()),
+        AstKind::SingleRelateId { subject, .. } => table_names(subject),
+        AstKind::Identifier(table) => Ok(vec![table]),
+        _ => Err(ZerodbError::InvalidGraphOperation(format!(
+            "table relations can only be written between tables, found: {}",
+            ast.kind
+        ))),
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::parser::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_table_relation_from_ast() -> anyhow::Result<()> {
+        let ast = Parser::new("[bird, fish] ->> is_a ->> animal", 20)
+            .parse_op()?
+            .unwrap();
+
+        assert!(TableRelation::is_table_relate_op(&ast));
+        assert_eq!(
+            TableRelation::from_ast(&ast)?,
+            vec![
+                TableRelation::new("bird", "is_a", "animal"),
+                TableRelation::new("fish", "is_a", "animal"),
+            ]
+        );
+
+        let ast = Parser::new("thing <<- is_a <<- animal", 20)
+            .parse_op()?
+            .unwrap();
+        assert_eq!(
+            TableRelation::from_ast(&ast)?,
+            vec![TableRelation::new("animal", "is_a", "thing")]
+        );
+
+        for input in [
+            "bird ->> is_a <<- animal",
+            "bird:tweety ->> is_a ->> animal",
+            "bird ->> is_a[2] ->> animal",
+        ] {
+            let ast = Parser::new(input, 20).parse_op()?.unwrap();
+            assert!(TableRelation::from_ast(&ast).is_err());
+        }
+
+        let ast = Parser::new("bird -> is_a -> animal", 20)
+            .parse_op()?
+            .unwrap();
+        assert!(!TableRelation::is_table_relate_op(&ast));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,995 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroql::{
+    ast::{Ast, AstKind},
+    sema::{SchemaMeta, SchemaSource},
+};
+use zeroutils_path::Path;
+
+use crate::{
+    index::FulltextIndex,
+    store::{Key, KvStore},
+    ZerodbError, ZerodbResult,
+};
+
+use super::{Catalog, TableRelation};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `SchemaChange` is a change to the schema items kept in the [`Catalog`].
+///
+/// Schema changes are replicated as [`Query::Schema`](crate::Query) Raft commands and applied as
+/// they are committed, so every node applies the same changes in the same order and ends up at the
+/// same catalog version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SchemaChange {
+    /// Defines a schema item, replacing any existing one unless `if_not_exists` is set.
+    Define {
+        /// The path of the schema item.
+        #[serde(serialize_with = "serialize_path")]
+        #[serde(deserialize_with = "deserialize_path")]
+        path: Path,
+
+        /// The definition of the schema item.
+        meta: SchemaMeta,
+
+        /// Whether an existing schema item is kept as is.
+        if_not_exists: bool,
+    },
+
+    /// Replaces the definition of a schema item as a `REDEFINE` does, moving it to `new_path` if it
+    /// is renamed.
+    Redefine {
+        /// The path of the schema item.
+        #[serde(serialize_with = "serialize_path")]
+        #[serde(deserialize_with = "deserialize_path")]
+        path: Path,
+
+        /// The path of the schema item after the change.
+        #[serde(serialize_with = "serialize_path")]
+        #[serde(deserialize_with = "deserialize_path")]
+        new_path: Path,
+
+        /// The definition of the schema item after the change.
+        meta: SchemaMeta,
+    },
+
+    /// Removes a schema item along with the schema items inside it.
+    Remove {
+        /// The path of the schema item.
+        #[serde(serialize_with = "serialize_path")]
+        #[serde(deserialize_with = "deserialize_path")]
+        path: Path,
+    },
+
+    /// Relates tables as a `RELATE` with `->>` or `<<-` arrows does.
+    Relate {
+        /// The database of the related tables.
+        #[serde(serialize_with = "serialize_path")]
+        #[serde(deserialize_with = "deserialize_path")]
+        database: Path,
+
+        /// The table relations to store.
+        relations: Vec<TableRelation>,
+    },
+
+    /// Removes table relations as a `DELETE` with `->>` or `<<-` arrows does.
+    Unrelate {
+        /// The database of the related tables.
+        #[serde(serialize_with = "serialize_path")]
+        #[serde(deserialize_with = "deserialize_path")]
+        database: Path,
+
+        /// The table relations to remove.
+        relations: Vec<TableRelation>,
+    },
+}
+
+/// `SchemaEntry` is a schema item as it is stored in the [`Catalog`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaEntry {
+    /// The catalog version the schema item was last changed at.
+    pub version: u64,
+
+    /// The definition of the schema item.
+    pub meta: SchemaMeta,
+}
+
+/// `SchemaSnapshot` is a copy of the schema items in the [`Catalog`] at one catalog version.
+///
+/// It is what semantic analysis looks up persisted schema items in.
+///
+/// ```txt
+/// let db_schema = DatabaseSchema::new(catalog.schema_snapshot(&store)?);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SchemaSnapshot {
+    /// The catalog version the snapshot was taken at.
+    version: u64,
+
+    /// The schema items by path.
+    schemas: HashMap<Path, SchemaMeta>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Catalog {
+    /// Applies a schema change, returning the new catalog version.
+    ///
+    /// Redefining or removing a schema item that does not exist leaves the catalog version as is,
+    /// and so does relating or unrelating no tables. A table that is renamed is renamed in its
+    /// table relations too.
+    ///
+    /// A full-text index is built from the records of its table as it is defined or redefined, and
+    /// its entries go when it is replaced or removed.
+    pub fn apply_schema_change(
+        &self,
+        store: &mut impl KvStore,
+        change: &SchemaChange,
+    ) -> ZerodbResult<u64> {
+        let version = self.schema_version(store)?;
+        let next = version + 1;
+
+        match change {
+            SchemaChange::Define {
+                path,
+                meta,
+                if_not_exists,
+            } => {
+                let key = schema_key(path);
+                let previous = self.schema(store, path)?;
+                if *if_not_exists && previous.is_some() {
+                    return Ok(version);
+                }
+
+                let entry = SchemaEntry {
+                    version: next,
+                    meta: meta.clone(),
+                };
+                store.put_encoded(key.as_bytes(), &entry)?;
+
+                if let Some(previous) = previous {
+                    clear_index(store, path, &previous.meta)?;
+                }
+                build_index(store, path, meta)?;
+            }
+            SchemaChange::Redefine {
+                path,
+                new_path,
+                meta,
+            } => {
+                let key = schema_key(path);
+                let Some(previous) = self.schema(store, path)? else {
+                    return Ok(version);
+                };
+
+                let entry = SchemaEntry {
+                    version: next,
+                    meta: meta.clone(),
+                };
+                store.delete(key.as_bytes())?;
+                store.put_encoded(schema_key(new_path).as_bytes(), &entry)?;
+
+                clear_index(store, path, &previous.meta)?;
+                build_index(store, new_path, meta)?;
+
+                if let (SchemaMeta::Table { .. }, true) = (meta, path != new_path) {
+                    self.rename_table(
+                        store,
+                        &item_database(path)?,
+                        &item_name(path),
+                        &item_name(new_path),
+                    )?;
+                }
+            }
+            SchemaChange::Remove { path } => {
+                let key = schema_key(path);
+                if store.get(key.as_bytes())?.is_none() {
+                    return Ok(version);
+                }
+
+                // The indexes removed along with the item take their entries with them.
+                for (key, value) in store.scan_prefix(key.as_bytes())? {
+                    let entry: SchemaEntry = cbor4ii::serde::from_slice(&value)?;
+                    clear_index(store, &path_of_key(&key)?, &entry.meta)?;
+                }
+
+                store.delete_prefix(key.as_bytes())?;
+            }
+            SchemaChange::Relate {
+                database,
+                relations,
+            } => {
+                if relations.is_empty() {
+                    return Ok(version);
+                }
+
+                for relation in relations.iter() {
+                    self.relate_tables(store, database, relation)?;
+                }
+            }
+            SchemaChange::Unrelate {
+                database,
+                relations,
+            } => {
+                let mut existed = false;
+                for relation in relations.iter() {
+                    existed |= self.unrelate_tables(store, database, relation)?;
+                }
+
+                if !existed {
+                    return Ok(version);
+                }
+            }
+        }
+
+        store.put_encoded(version_key().as_bytes(), &next)?;
+
+        Ok(next)
+    }
+
+    /// Gets a schema item.
+    pub fn schema(&self, store: &impl KvStore, path: &Path) -> ZerodbResult<Option<SchemaEntry>> {
+        store.get_decoded(schema_key(path).as_bytes())
+    }
+
+    /// Gets every schema item, in key order.
+    pub fn schemas(&self, store: &impl KvStore) -> ZerodbResult<Vec<(Path, SchemaEntry)>> {
+        self.schemas_in(store, &Path::default())
+    }
+
+    /// Gets the schema items inside the one at `path`, at any depth, in key order.
+    pub fn schemas_in(
+        &self,
+        store: &impl KvStore,
+        path: &Path,
+    ) -> ZerodbResult<Vec<(Path, SchemaEntry)>> {
+        let prefix = schema_key(path);
+        let mut schemas = vec![];
+        for (key, value) in store.scan_prefix(prefix.as_bytes())? {
+            if key == prefix.as_bytes() {
+                continue;
+            }
+
+            schemas.push((path_of_key(&key)?, cbor4ii::serde::from_slice(&value)?));
+        }
+
+        Ok(schemas)
+    }
+
+    /// Checks if an edge type is defined in `database`.
+    pub fn edge_defined(
+        &self,
+        store: &impl KvStore,
+        database: &Path,
+        name: &str,
+    ) -> ZerodbResult<bool> {
+        let path = format!("{database}/{name}");
+        let path = path
+            .parse()
+            .map_err(|_| ZerodbError::InvalidSchemaPath(path))?;
+
+        Ok(matches!(
+            self.schema(store, &path)?,
+            Some(SchemaEntry {
+                meta: SchemaMeta::Edge { .. },
+                ..
+            })
+        ))
+    }
+
+    /// Gets the variants of every enum defined in `database`, by enum name.
+    pub fn enums(
+        &self,
+        store: &impl KvStore,
+        database: &Path,
+    ) -> ZerodbResult<BTreeMap<String, Vec<String>>> {
+        Ok(self
+            .schema_snapshot(store)?
+            .items(database)
+            .into_iter()
+            .filter_map(|(name, meta)| match meta {
+                SchemaMeta::Enum { variants } => Some((name, variants)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Gets the catalog version, which is the number of schema changes applied so far.
+    pub fn schema_version(&self, store: &impl KvStore) -> ZerodbResult<u64> {
+        Ok(store
+            .get_decoded(version_key().as_bytes())?
+            .unwrap_or_default())
+    }
+
+    /// Takes a snapshot of the schema items at the current catalog version.
+    pub fn schema_snapshot(&self, store: &impl KvStore) -> ZerodbResult<SchemaSnapshot> {
+        let schemas = self
+            .schemas(store)?
+            .into_iter()
+            .map(|(path, entry)| (path, entry.meta))
+            .collect();
+
+        Ok(SchemaSnapshot {
+            version: self.schema_version(store)?,
+            schemas,
+        })
+    }
+
+    /// Creates the schema change a `DEFINE`, `REDEFINE` or `REMOVE` statement makes.
+    ///
+    /// Schema items are placed in the database the statement names, or in `database` if it names
+    /// none. A `REDEFINE` is applied to the stored definition, so the change carries the whole
+    /// definition it leaves behind. Statements that do not change the schema make no change.
+    pub fn schema_change(
+        &self,
+        store: &impl KvStore,
+        ast: &Ast,
+        database: &Path,
+    ) -> ZerodbResult<Option<SchemaChange>> {
+        use AstKind::*;
+
+        let change = match &ast.kind {
+            DefineNamespace {
+                name,
+                if_not_exists,
+            } => SchemaChange::define(
+                name,
+                &None,
+                &Path::default(),
+                *if_not_exists,
+                SchemaMeta::Namespace(),
+            )?,
+            DefineDatabase {
+                name,
+                if_not_exists,
+                namespace: in_database,
+            }
+            | DefineTable {
+                name,
+                if_not_exists,
+                database: in_database,
+                ..
+            }
+            | DefineEdge {
+                name,
+                if_not_exists,
+                database: in_database,
+                ..
+            }
+            | DefineType {
+                name,
+                if_not_exists,
+                database: in_database,
+                ..
+            }
+            | DefineEnum {
+                name,
+                if_not_exists,
+                database: in_database,
+                ..
+            }
+            | DefineIndex {
+                name,
+                if_not_exists,
+                database: in_database,
+                ..
+            }
+            | DefineModule {
+                name,
+                if_not_exists,
+                database: in_database,
+                ..
+            }
+            | DefineParam {
+                name,
+                if_not_exists,
+                database: in_database,
+                ..
+            }
+            | DefineFunction {
+                name,
+                if_not_exists,
+                database: in_database,
+                ..
+            } => {
+                let Some(meta) = SchemaMeta::from_definition(ast)? else {
+                    return Ok(None);
+                };
+
+                let change =
+                    SchemaChange::define(name, in_database, database, *if_not_exists, meta)?;
+                change.check_index()?;
+                change
+            }
+            RedefineTable {
+                name,
+                if_exists,
+                new_name,
+                database: in_database,
+                ..
+            }
+            | RedefineEdge {
+                name,
+                if_exists,
+                new_name,
+                database: in_database,
+                ..
+            }
+            | RedefineType {
+                name,
+                if_exists,
+                new_name,
+                database: in_database,
+                ..
+            }
+            | RedefineEnum {
+                name,
+                if_exists,
+                new_name,
+                database: in_database,
+                ..
+            }
+            | RedefineIndex {
+                name,
+                if_exists,
+                new_name,
+                database: in_database,
+                ..
+            }
+            | RedefineParam {
+                name,
+                if_exists,
+                new_name,
+                database: in_database,
+                ..
+            } => {
+                let path = schema_path(name, in_database, database)?;
+                let existing = self
+                    .schema(store, &path)?
+                    .filter(|entry| Some(entry.meta.kind()) == redefined_kind(&ast.kind));
+
+                let Some(SchemaEntry { mut meta, .. }) = existing else {
+                    if *if_exists {
+                        return Ok(None);
+                    }
+
+                    return Err(ZerodbError::UndefinedSchemaItem(path.to_string()));
+                };
+
+                meta.redefine(ast)?;
+
+                let new_path = match new_name {
+                    Some(new_name) => schema_path(new_name, in_database, database)?,
+                    None => path.clone(),
+                };
+
+                let change = SchemaChange::Redefine {
+                    path,
+                    new_path,
+                    meta,
+                };
+                change.check_index()?;
+                change
+            }
+            RemoveNamespace { subject, .. } => SchemaChange::Remove {
+                path: schema_path(subject, &None, &Path::default())?,
+            },
+            RemoveDatabase {
+                subject,
+                namespace: in_database,
+                ..
+            }
+            | RemoveTable {
+                subject,
+                database: in_database,
+                ..
+            }
+            | RemoveEdge {
+                subject,
+                database: in_database,
+                ..
+            }
+            | RemoveType {
+                subject,
+                database: in_database,
+                ..
+            }
+            | RemoveEnum {
+                subject,
+                database: in_database,
+                ..
+            }
+            | RemoveIndex {
+                subject,
+                database: in_database,
+                ..
+            }
+            | RemoveModule {
+                subject,
+                database: in_database,
+                ..
+            }
+            | RemoveParam {
+                subject,
+                database: in_database,
+                ..
+            }
+            | RemoveFunction {
+                subject,
+                database: in_database,
+                ..
+            } => SchemaChange::Remove {
+                path: schema_path(subject, in_database, database)?,
+            },
+            _ => return Ok(None),
+        };
+
+        Ok(Some(change))
+    }
+}
+
+impl SchemaChange {
+    /// Checks that the index the change defines can be built, so a bad `WITH` function is
+    /// rejected before the change is applied.
+    fn check_index(&self) -> ZerodbResult<()> {
+        if let Self::Define { path, meta, .. }
+        | Self::Redefine {
+            new_path: path,
+            meta,
+            ..
+        } = self
+        {
+            FulltextIndex::from_schema(&item_database(path)?, &item_name(path), meta)?;
+        }
+
+        Ok(())
+    }
+
+    fn define(
+        name: &Ast,
+        in_database: &Option<Box<Ast>>,
+        database: &Path,
+        if_not_exists: bool,
+        meta: SchemaMeta,
+    ) -> ZerodbResult<Self> {
+        Ok(Self::Define {
+            path: schema_path(name, in_database, database)?,
+            meta,
+            if_not_exists,
+        })
+    }
+}
+
+impl SchemaSnapshot {
+    /// Gets the catalog version the snapshot was taken at.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl SchemaSource for SchemaSnapshot {
+    fn get(&self, name: &Path) -> Option<SchemaMeta> {
+        self.schemas.get(name).cloned()
+    }
+
+    fn items(&self, database: &Path) -> Vec<(String, SchemaMeta)> {
+        self.schemas.items(database)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn redefined_kind(kind: &AstKind) -> Option<&'static str> {
+    match kind {
+        AstKind::RedefineTable { .. } => Some("table"),
+        AstKind::RedefineEdge { .. } => Some("edge"),
+        AstKind::RedefineType { .. } => Some("type"),
+        AstKind::RedefineEnum { .. } => Some("enum"),
+        AstKind::RedefineIndex { .. } => Some("index"),
+        AstKind::RedefineParam { .. } => Some("param"),
+        _ => None,
+    }
+}
+
+/// Gets the name of a schema item, which is the last segment of its path.
+fn item_name(path: &Path) -> String {
+    let path = path.to_string();
+    path.rsplit('/').next().unwrap_or_default().to_string()
+}
+
+/// Gets the database a schema item is defined in, which is its path without the last segment.
+pub(super) fn item_database(path: &Path) -> ZerodbResult<Path> {
+    let path = path.to_string();
+    let database = path.rsplit_once('/').unwrap_or_default().0;
+    database
+        .parse()
+        .map_err(|_| ZerodbError::InvalidSchemaPath(path))
+}
+
+/// Builds the full-text index a schema item defines from the records of its table. Does nothing
+/// for other schema items.
+fn build_index(store: &mut impl KvStore, path: &Path, meta: &SchemaMeta) -> ZerodbResult<()> {
+    if let Some(index) = FulltextIndex::from_schema(&item_database(path)?, &item_name(path), meta)?
+    {
+        index.build(store)?;
+    }
+
+    Ok(())
+}
+
+/// Removes the entries of the full-text index a schema item defined. Does nothing for other
+/// schema items.
+fn clear_index(store: &mut impl KvStore, path: &Path, meta: &SchemaMeta) -> ZerodbResult<()> {
+    if let Some(index) = FulltextIndex::from_schema(&item_database(path)?, &item_name(path), meta)?
+    {
+        index.clear(store)?;
+    }
+
+    Ok(())
+}
+
+fn schema_key(path: &Path) -> Key {
+    path.to_string()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .fold(Key::new().with("c").with("d"), |key, segment| {
+            key.with(segment)
+        })
+}
+
+fn path_of_key(key: &[u8]) -> ZerodbResult<Path> {
+    let path = Key::decode_str(key)[2..].join("/");
+    path.parse()
+        .map_err(|_| ZerodbError::InvalidSchemaPath(path))
+}
+
+fn version_key() -> Key {
+    Key::new().with("c").with("v")
+}
+
+pub(super) fn schema_path(
+    name: &Ast,
+    in_database: &Option<Box<Ast>>,
+    database: &Path,
+) -> ZerodbResult<Path> {
+    let AstKind::Identifier(name) = name.kind else {
+        return Err(ZerodbError::UnsupportedExpression(name.kind.to_string()));
+    };
+
+    let path = match in_database {
+        Some(in_database) => {
+            let AstKind::Identifier(in_database) = in_database.kind else {
+                return Err(ZerodbError::UnsupportedExpression(
+                    in_database.kind.to_string(),
+                ));
+            };
+
+            format!("{in_database}/{name}")
+        }
+        None => format!("{database}/{name}"),
+    };
+
+    path.parse()
+        .map_err(|_| ZerodbError::InvalidSchemaPath(path))
+}
+
+pub(super) fn serialize_path<S>(path: &Path, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&path.to_string())
+}
+
+pub(super) fn deserialize_path<'de, D>(deserializer: D) -> Result<Path, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let path = String::deserialize(deserializer)?;
+    path.parse().map_err(serde::de::Error::custom)
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::{
+        parser::Parser,
+        sema::{DatabaseSchema, FieldMeta, IndexKind, TableModeMeta, Type},
+    };
+
+    use crate::store::MemoryKvStore;
+
+    use super::*;
+
+    fn apply(store: &mut MemoryKvStore, database: &Path, input: &str) -> anyhow::Result<u64> {
+        let catalog = Catalog::new();
+        let mut version = catalog.schema_version(store)?;
+        for statement in Parser::new(input, 50)
+            .parse_program()
+            .into_result()?
+            .kind
+            .unwrap_program()
+        {
+            if let Some(change) = catalog.schema_change(store, &statement, database)? {
+                version = catalog.apply_schema_change(store, &change)?;
+            }
+        }
+
+        Ok(version)
+    }
+
+    #[test]
+    fn test_catalog_schema_changes() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let catalog = Catalog::new();
+        let shop = "shop".parse::<Path>()?;
+
+        let version = apply(
+            &mut store,
+            &Path::default(),
+            "DEFINE NAMESPACE acme; DEFINE DATABASE shop; DEFINE PARAM rate TYPE f64 VALUE 0.9",
+        )?;
+        assert_eq!(version, 3);
+
+        let version = apply(
+            &mut store,
+            &shop,
+            "DEFINE TABLE person FIELDS name TYPE string; DEFINE EDGE knows; DEFINE TABLE IF NOT EXISTS person",
+        )?;
+        assert_eq!(version, 5);
+
+        // Edges are defined in the database they are placed in, and only as edges.
+        assert!(catalog.edge_defined(&store, &shop, "knows")?);
+        assert!(!catalog.edge_defined(&store, &Path::default(), "knows")?);
+        assert!(!catalog.edge_defined(&store, &shop, "person")?);
+
+        assert_eq!(
+            catalog.schema(&store, &"shop/person".parse()?)?,
+            Some(SchemaEntry {
+                version: 4,
+                meta: SchemaMeta::Table {
+                    mode: TableModeMeta::Schemafull,
+                    fields: vec![FieldMeta {
+                        r#type: Type::String,
+                        ..FieldMeta::new("name")
+                    }]
+                }
+            })
+        );
+
+        // Redefinitions apply to the stored definition, and removes take the items inside the
+        // removed one along.
+        apply(
+            &mut store,
+            &shop,
+            "REDEFINE TABLE person AS people FIELDS name AS full_name",
+        )?;
+        assert_eq!(catalog.schema(&store, &"shop/person".parse()?)?, None);
+        assert_eq!(
+            catalog.schema(&store, &"shop/people".parse()?)?,
+            Some(SchemaEntry {
+                version: 6,
+                meta: SchemaMeta::Table {
+                    mode: TableModeMeta::Schemafull,
+                    fields: vec![FieldMeta {
+                        r#type: Type::String,
+                        ..FieldMeta::new("full_name")
+                    }]
+                }
+            })
+        );
+        assert!(apply(&mut store, &shop, "REDEFINE TABLE person").is_err());
+        assert!(apply(&mut store, &shop, "REDEFINE EDGE people").is_err());
+
+        apply(&mut store, &Path::default(), "REMOVE DATABASE shop")?;
+        assert_eq!(
+            catalog
+                .schemas(&store)?
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect::<Vec<_>>(),
+            vec!["acme".parse()?, "rate".parse::<Path>()?]
+        );
+        assert_eq!(catalog.schema_version(&store)?, 7);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_catalog_schema_relations() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let catalog = Catalog::new();
+
+        apply(&mut store, &Path::default(), "DEFINE TABLE bird")?;
+
+        // Table relations are versioned schema changes.
+        let ast = Parser::new("RELATE [bird, fish] ->> is_a ->> animal", 20)
+            .parse_exp()?
+            .unwrap();
+        catalog.execute_relate(&mut store, &Path::default(), &ast)?;
+        assert_eq!(catalog.schema_version(&store)?, 2);
+
+        // Renaming a table renames it in its table relations.
+        apply(&mut store, &Path::default(), "REDEFINE TABLE bird AS avian")?;
+        assert_eq!(catalog.schema_version(&store)?, 3);
+        assert_eq!(
+            catalog.subtables(&store, &Path::default(), "animal")?,
+            ["animal", "avian", "fish"]
+        );
+
+        let ast = Parser::new("DELETE [avian, bird] ->> is_a ->> animal", 20)
+            .parse_exp()?
+            .unwrap();
+        assert_eq!(
+            catalog
+                .execute_delete(&mut store, &Path::default(), &ast)?
+                .len(),
+            1
+        );
+        assert_eq!(catalog.schema_version(&store)?, 4);
+
+        // Removing relations that do not exist changes nothing.
+        assert!(catalog
+            .execute_delete(&mut store, &Path::default(), &ast)?
+            .is_empty());
+        assert_eq!(catalog.schema_version(&store)?, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_catalog_schema_snapshot() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let catalog = Catalog::new();
+
+        apply(&mut store, &Path::default(), "DEFINE DATABASE shop")?;
+        apply(&mut store, &"shop".parse()?, "DEFINE TABLE person")?;
+
+        let snapshot = catalog.schema_snapshot(&store)?;
+        assert_eq!(snapshot.version(), 2);
+
+        // Analysis sees the schema items that earlier statements persisted.
+        let db_schema = DatabaseSchema::new(snapshot);
+        assert_eq!(
+            db_schema.get(&"shop/person".parse()?),
+            Some(SchemaMeta::Table {
+                mode: TableModeMeta::Schemafull,
+                fields: vec![]
+            })
+        );
+        assert!(!db_schema.contains(&"shop/people".parse()?));
+
+        // Schema changes survive being sent as Raft commands.
+        let change = SchemaChange::Redefine {
+            path: "shop/person".parse()?,
+            new_path: "shop/people".parse()?,
+            meta: SchemaMeta::param(None, &Parser::new("[1, 2]", 50).parse_exp()?.unwrap())?,
+        };
+        let bytes = cbor4ii::serde::to_vec(vec![], &change)?;
+        assert_eq!(cbor4ii::serde::from_slice::<SchemaChange>(&bytes)?, change);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_catalog_schema_definitions() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let catalog = Catalog::new();
+        let root = Path::default();
+        let meta = |store: &MemoryKvStore, path: &str| -> anyhow::Result<SchemaMeta> {
+            Ok(catalog.schema(store, &path.parse()?)?.unwrap().meta)
+        };
+
+        apply(
+            &mut store,
+            &root,
+            r#"
+            DEFINE TABLE person FIELDS name TYPE string ASSERT $value != '' READONLY, email TYPE string UNIQUE, age TYPE u8 VALUE 0;
+            DEFINE ENUM color VARIANTS red, green, blue;
+            DEFINE INDEX idx_name ON TABLE person FIELDS name, age;
+            DEFINE PARAM rate TYPE f64 VALUE 0.9;
+            DEFINE MODULE greetings WITH
+                export function greet(name) { return name; }
+                function helper() { return 1; }
+            END;
+            "#,
+        )?;
+
+        let SchemaMeta::Table { fields, .. } = meta(&store, "person")? else {
+            panic!("person should be a table");
+        };
+        assert_eq!(
+            fields
+                .iter()
+                .map(|field| (
+                    field.name.as_str(),
+                    &field.r#type,
+                    field.readonly,
+                    field.unique
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("name", &Type::String, true, false),
+                ("email", &Type::String, false, true),
+                ("age", &Type::U8, false, false),
+            ]
+        );
+        assert_eq!(fields[0].assertions.len(), 1);
+        assert!(fields[0].default.is_none() && fields[2].default.is_some());
+
+        assert_eq!(
+            meta(&store, "color")?,
+            SchemaMeta::Enum {
+                variants: vec!["red".into(), "green".into(), "blue".into()]
+            }
+        );
+        assert_eq!(
+            meta(&store, "idx_name")?,
+            SchemaMeta::Index {
+                table: "person".into(),
+                columns: vec!["name".into(), "age".into()],
+                kind: IndexKind::Standard,
+            }
+        );
+        assert!(matches!(
+            meta(&store, "rate")?,
+            SchemaMeta::Param {
+                r#type: Some(Type::F64),
+                ..
+            }
+        ));
+        assert!(matches!(
+            meta(&store, "greetings")?,
+            SchemaMeta::Module { exports, .. } if exports == ["greet"]
+        ));
+
+        // Redefinitions update the stored payloads.
+        apply(
+            &mut store,
+            &root,
+            r#"
+            REDEFINE ENUM color VARIANTS red AS crimson, green, blue, purple;
+            REDEFINE INDEX idx_name ON TABLE person FIELDS email;
+            REDEFINE PARAM rate TYPE f32;
+            "#,
+        )?;
+        assert_eq!(
+            meta(&store, "color")?,
+            SchemaMeta::Enum {
+                variants: vec![
+                    "crimson".into(),
+                    "green".into(),
+                    "blue".into(),
+                    "purple".into()
+                ]
+            }
+        );
+        assert!(matches!(
+            meta(&store, "idx_name")?,
+            SchemaMeta::Index { columns, .. } if columns == ["email"]
+        ));
+        assert!(matches!(
+            meta(&store, "rate")?,
+            SchemaMeta::Param {
+                r#type: Some(Type::F32),
+                ..
+            }
+        ));
+
+        Ok(())
+    }
+}
@@ -59,6 +59,189 @@ pub enum ZerodbError {
     /// Channel closed.
     #[error("channel closed")]
     ChannelClosed,
+
+    /// Invalid record id.
+    #[error("invalid record id: {0}")]
+    InvalidRecordId(String),
+
+    /// Invalid index configuration.
+    #[error("invalid index configuration: {0}")]
+    InvalidIndexConfig(String),
+
+    /// A `<>` condition searches a field that has no full-text index.
+    #[error("no full-text index on field {0}")]
+    UnindexedField(String),
+
+    /// Unsupported expression.
+    #[error("unsupported expression: {0}")]
+    UnsupportedExpression(String),
+
+    /// Invalid graph operation.
+    #[error("invalid graph operation: {0}")]
+    InvalidGraphOperation(String),
+
+    /// A record has more edges than a traversal may follow.
+    #[error("record {record} has more than {limit} edges to follow")]
+    FanOutExceeded {
+        /// The record being expanded.
+        record: String,
+
+        /// The maximum number of edges that may be followed.
+        limit: usize,
+    },
+
+    /// A schema item is redefined without being defined.
+    #[error("undefined schema item: {0}")]
+    UndefinedSchemaItem(String),
+
+    /// Error from semantic analysis.
+    #[error(transparent)]
+    SemaError(#[from] zeroql::sema::SemaError),
+
+    /// A schema item path cannot be parsed.
+    #[error("invalid schema path: {0}")]
+    InvalidSchemaPath(String),
+
+    /// An edge type is used without being declared with `DEFINE EDGE`.
+    #[error("undefined edge: {0}")]
+    UndefinedEdge(String),
+
+    /// A function that does not exist is called.
+    #[error("unknown function: {0}")]
+    UnknownFunction(String),
+
+    /// A function is called with arguments it does not accept.
+    #[error("invalid arguments to {function}: {reason}")]
+    InvalidFunctionArgs {
+        /// The function being called.
+        function: String,
+
+        /// Why the arguments are not accepted.
+        reason: String,
+    },
+
+    /// A stored value cannot be coerced to the type a `REDEFINE` gives its field.
+    #[error("cannot coerce {value} to {r#type}")]
+    InvalidCoercion {
+        /// The value being coerced.
+        value: String,
+
+        /// The type the value is coerced to.
+        r#type: String,
+    },
+
+    /// A record is created with the id of a record that already exists.
+    #[error("record already exists: {0}")]
+    RecordExists(String),
+
+    /// A record that does not exist is updated.
+    #[error("undefined record: {0}")]
+    UndefinedRecord(String),
+
+    /// A value written to a field does not pass one of the field's `ASSERT`s.
+    #[error("field {table}.{field} does not accept {value}: ASSERT {assertion} failed")]
+    FieldAssertion {
+        /// The table of the field.
+        table: String,
+
+        /// The field being written.
+        field: String,
+
+        /// The value being written.
+        value: String,
+
+        /// The assertion the value does not pass.
+        assertion: String,
+    },
+
+    /// A value written to a field cannot be coerced to the field's type.
+    #[error("field {table}.{field} expects {r#type}, found {value}")]
+    FieldType {
+        /// The table of the field.
+        table: String,
+
+        /// The field being written.
+        field: String,
+
+        /// The value being written.
+        value: String,
+
+        /// The type of the field.
+        r#type: String,
+    },
+
+    /// A `READONLY` field of an existing record is changed.
+    #[error("field {table}.{field} is readonly")]
+    ReadonlyField {
+        /// The table of the field.
+        table: String,
+
+        /// The field being written.
+        field: String,
+    },
+
+    /// A field that a `SCHEMAFULL` table does not declare is written.
+    #[error("field {table}.{field} is not declared by the schemafull table")]
+    UndeclaredField {
+        /// The table being written.
+        table: String,
+
+        /// The undeclared field.
+        field: String,
+    },
+
+    /// A variant that its enum does not declare is used.
+    #[error("enum {r#enum} has no variant {variant}")]
+    UnknownVariant {
+        /// The enum.
+        r#enum: String,
+
+        /// The unknown variant.
+        variant: String,
+    },
+
+    /// A value written to a `UNIQUE` field is already held by another record.
+    #[error("field {table}.{field} is unique, but {value} is already held by {record}")]
+    UniqueField {
+        /// The table of the field.
+        table: String,
+
+        /// The field being written.
+        field: String,
+
+        /// The value being written.
+        value: String,
+
+        /// The record that already holds the value.
+        record: String,
+    },
+
+    /// A variable that is not bound is read.
+    #[error("undefined variable: ${0}")]
+    UndefinedVariable(String),
+
+    /// Invalid datetime.
+    #[error("invalid datetime: {0}")]
+    InvalidDatetime(String),
+
+    /// Invalid duration.
+    #[error("invalid duration: {0}")]
+    InvalidDuration(String),
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl ZerodbError {
+    /// Whether the error is a failure of the storage the data is kept in, rather than a request
+    /// that the data does not allow.
+    pub fn is_storage_error(&self) -> bool {
+        matches!(
+            self,
+            Self::Io(_) | Self::Cbor4iiDecodeError(_) | Self::Cbor4iiEncodeError(_)
+        )
+    }
 }
 
 //--------------------------------------------------------------------------------------------------
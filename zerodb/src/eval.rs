@@ -0,0 +1,406 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use zeroql::ast::{Ast, AstKind};
+
+use crate::{
+    field_name, functions,
+    index::{is_search_score_call, SearchHits, SimilarityPredicate},
+    record_id_from_ast, EnumValue, RecordId, Value, ZerodbError, ZerodbResult,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Scope` evaluates expressions against the variables and record fields they can refer to.
+///
+/// Variables are read with `$name` and the fields of the record being written or filtered are read
+/// by their bare names, so a field's `ASSERT $value > age` sees both. Variants like `color::red`
+/// are read from the enums the scope knows.
+///
+/// A `<>` condition is answered from the full-text search the statement ran for it, and
+/// `search::score()` reads the score the record being evaluated got in it.
+///
+/// ```txt
+/// let scope = Scope::new().with_variable("value", Value::Int(20));
+/// scope.evaluate(&ast)?; // $value >= 18 AND $value < 130
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    /// The variables by name, without the `$`.
+    variables: BTreeMap<String, Value>,
+
+    /// The fields of the record being evaluated against.
+    fields: BTreeMap<String, Value>,
+
+    /// The variants of the enums, by enum name.
+    enums: BTreeMap<String, Vec<String>>,
+
+    /// The id of the record being evaluated against.
+    record: Option<RecordId>,
+
+    /// The hits of the full-text searches run for the `<>` conditions being evaluated.
+    searches: Vec<(SimilarityPredicate, SearchHits)>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Scope {
+    /// Creates a scope without variables or fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds a variable, e.g. `value` for `$value`.
+    pub fn with_variable(mut self, name: impl Into<String>, value: Value) -> Self {
+        self.variables.insert(name.into(), value);
+        self
+    }
+
+    /// Sets the fields of the record that bare names refer to.
+    pub fn with_fields(mut self, fields: BTreeMap<String, Value>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Sets the enums whose variants can be read.
+    pub fn with_enums(mut self, enums: BTreeMap<String, Vec<String>>) -> Self {
+        self.enums = enums;
+        self
+    }
+
+    /// Sets the id of the record that `<>` conditions and `search::score()` are evaluated for.
+    pub fn with_record(mut self, record: RecordId) -> Self {
+        self.record = Some(record);
+        self
+    }
+
+    /// Adds the hits of the full-text search run for a `<>` condition.
+    pub fn with_search(mut self, predicate: SimilarityPredicate, hits: SearchHits) -> Self {
+        self.searches.push((predicate, hits));
+        self
+    }
+
+    /// Evaluates an expression.
+    ///
+    /// Fields that the record does not have are `NONE`, while variables that are not bound are an
+    /// error.
+    pub fn evaluate(&self, ast: &Ast) -> ZerodbResult<Value> {
+        use AstKind::*;
+
+        let value = match &ast.kind {
+            Identifier(name) => self.fields.get(*name).cloned().unwrap_or_default(),
+            Variable(name) => self
+                .variables
+                .get(*name)
+                .cloned()
+                .ok_or_else(|| ZerodbError::UndefinedVariable(name.to_string()))?,
+            IdOp(..) => Value::Record(record_id_from_ast(ast)?),
+            ListLiteral(items) | TupleLiteral(items) => Value::List(
+                items
+                    .iter()
+                    .map(|item| self.evaluate(item))
+                    .collect::<ZerodbResult<_>>()?,
+            ),
+            ObjectLiteral(entries) => Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| Ok((field_name(key)?, self.evaluate(value)?)))
+                    .collect::<ZerodbResult<_>>()?,
+            ),
+            FunctionCall { .. } if is_search_score_call(ast) => Value::Float(self.search_score()),
+            FunctionCall { .. } => {
+                functions::call_ast_with(ast, |argument| self.evaluate(argument))?
+            }
+            SimilarityOp(..) => Value::Bool(self.search_matches(ast)?),
+            ScopedIdentifier(parts) => match parts.as_slice() {
+                [Ast {
+                    kind: Identifier(name),
+                    ..
+                }, Ast {
+                    kind: Identifier(variant),
+                    ..
+                }] if self.enums.contains_key(*name) => self.variant(name, variant)?,
+                _ => return Err(unsupported(ast)),
+            },
+            DotAccessOp { subject, field } => match (&subject.kind, &field.kind) {
+                // `color.red` reads a variant, unless the record has a field named like the enum.
+                (Identifier(name), Identifier(variant))
+                    if self.enums.contains_key(*name) && !self.fields.contains_key(*name) =>
+                {
+                    self.variant(name, variant)?
+                }
+                _ => self.access(subject, field)?,
+            },
+            SafeNavigationAccessOp { subject, field } => self.access(subject, field)?,
+            LogicalNotOp(operand) => Value::Bool(!truthy(&self.evaluate(operand)?)),
+            MinusSignOp(operand) => match self.evaluate(operand)? {
+                Value::Int(integer) => Value::Int(-integer),
+                Value::Float(float) => Value::Float(-float),
+                _ => return Err(unsupported(ast)),
+            },
+            PlusSignOp(operand) => match self.evaluate(operand)? {
+                value @ (Value::Int(_) | Value::Float(_)) => value,
+                _ => return Err(unsupported(ast)),
+            },
+            LogicalAndOp(left, right) => {
+                Value::Bool(truthy(&self.evaluate(left)?) && truthy(&self.evaluate(right)?))
+            }
+            LogicalOrOp(left, right) => {
+                Value::Bool(truthy(&self.evaluate(left)?) || truthy(&self.evaluate(right)?))
+            }
+            NullCoalesceOp(left, right) => match self.evaluate(left)? {
+                Value::None => self.evaluate(right)?,
+                value => value,
+            },
+            EqualToOp(left, right) | IsOp(left, right) => {
+                Value::Bool(equal(&self.evaluate(left)?, &self.evaluate(right)?))
+            }
+            IsNotOp(left, right) => {
+                Value::Bool(!equal(&self.evaluate(left)?, &self.evaluate(right)?))
+            }
+            LessThanOp(left, right) => self.compare(left, right, Ordering::is_lt)?,
+            GreaterThanOp(left, right) => self.compare(left, right, Ordering::is_gt)?,
+            LessThanEqualToOp(left, right) => self.compare(left, right, Ordering::is_le)?,
+            GreaterThanEqualToOp(left, right) => self.compare(left, right, Ordering::is_ge)?,
+            InOp(left, right) => {
+                Value::Bool(contains(&self.evaluate(right)?, &self.evaluate(left)?))
+            }
+            NotInOp(left, right) => {
+                Value::Bool(!contains(&self.evaluate(right)?, &self.evaluate(left)?))
+            }
+            ContainsOp(left, right) => {
+                Value::Bool(contains(&self.evaluate(left)?, &self.evaluate(right)?))
+            }
+            NotContainsOp(left, right) => {
+                Value::Bool(!contains(&self.evaluate(left)?, &self.evaluate(right)?))
+            }
+            AdditionOp(left, right) => match (self.evaluate(left)?, self.evaluate(right)?) {
+                (Value::String(left), Value::String(right)) => Value::String(left + &right),
+                (Value::List(mut left), Value::List(right)) => {
+                    left.extend(right);
+                    Value::List(left)
+                }
+                (left, right) => arithmetic(ast, &left, &right)?,
+            },
+            SubtractionOp(left, right)
+            | MultiplicationOp(left, right)
+            | DivisionOp(left, right)
+            | ModulusOp(left, right)
+            | ExponentiationOp(left, right) => {
+                arithmetic(ast, &self.evaluate(left)?, &self.evaluate(right)?)?
+            }
+            _ => Value::from_ast(ast)?,
+        };
+
+        Ok(value)
+    }
+
+    fn access(&self, subject: &Ast, field: &Ast) -> ZerodbResult<Value> {
+        let value = match self.evaluate(subject)? {
+            Value::Object(mut fields) => fields.remove(&field_name(field)?).unwrap_or_default(),
+            _ => Value::None,
+        };
+
+        Ok(value)
+    }
+
+    fn variant(&self, name: &str, variant: &str) -> ZerodbResult<Value> {
+        Ok(Value::Enum(EnumValue::from_variant(
+            name,
+            &self.enums[name],
+            variant,
+        )?))
+    }
+
+    /// Checks if the record is among the hits of the search run for a `<>` condition.
+    fn search_matches(&self, ast: &Ast) -> ZerodbResult<bool> {
+        let predicate = SimilarityPredicate::from_ast(ast).ok_or_else(|| unsupported(ast))?;
+        let (_, hits) = self
+            .searches
+            .iter()
+            .find(|(candidate, _)| *candidate == predicate)
+            .ok_or_else(|| ZerodbError::UnindexedField(predicate.field.clone()))?;
+
+        Ok(self
+            .record
+            .as_ref()
+            .is_some_and(|record| hits.contains(record)))
+    }
+
+    /// Gets the score of the record in the searches it matched, added up, or `0.0` if it matched
+    /// none.
+    fn search_score(&self) -> f64 {
+        let Some(record) = &self.record else {
+            return 0.0;
+        };
+
+        self.searches
+            .iter()
+            .filter_map(|(_, hits)| hits.score(record))
+            .sum()
+    }
+
+    fn compare(&self, left: &Ast, right: &Ast, holds: fn(Ordering) -> bool) -> ZerodbResult<Value> {
+        let left = self.evaluate(left)?;
+        let right = self.evaluate(right)?;
+        Ok(Value::Bool(holds(left.total_cmp(&right))))
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Checks if a value counts as true in a condition, which is only `true` itself.
+pub fn truthy(value: &Value) -> bool {
+    matches!(value, Value::Bool(true))
+}
+
+/// Checks if two values are equal, comparing integers and floats as numbers.
+fn equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Int(_), Value::Float(_)) | (Value::Float(_), Value::Int(_)) => {
+            left.total_cmp(right).is_eq()
+        }
+        _ => left == right,
+    }
+}
+
+/// Checks if a list has an item, or a string has a substring.
+fn contains(container: &Value, item: &Value) -> bool {
+    match (container, item) {
+        (Value::List(items), item) => items.iter().any(|candidate| equal(candidate, item)),
+        (Value::String(string), Value::String(substring)) => string.contains(substring.as_str()),
+        (Value::Object(fields), Value::String(field)) => fields.contains_key(field),
+        _ => false,
+    }
+}
+
+/// Applies an arithmetic operator to two numbers, keeping integers as integers.
+fn arithmetic(ast: &Ast, left: &Value, right: &Value) -> ZerodbResult<Value> {
+    use AstKind::*;
+
+    let value = match (left, right) {
+        (Value::Int(left), Value::Int(right)) => {
+            let (left, right) = (*left, *right);
+            let result = match &ast.kind {
+                AdditionOp(..) => left.checked_add(right),
+                SubtractionOp(..) => left.checked_sub(right),
+                MultiplicationOp(..) => left.checked_mul(right),
+                DivisionOp(..) => left.checked_div(right),
+                ModulusOp(..) => left.checked_rem(right),
+                ExponentiationOp(..) => u32::try_from(right)
+                    .ok()
+                    .and_then(|right| left.checked_pow(right)),
+                _ => None,
+            };
+
+            Value::Int(result.ok_or_else(|| unsupported(ast))?)
+        }
+        (Value::Int(_) | Value::Float(_), Value::Int(_) | Value::Float(_)) => {
+            let (Some(left), Some(right)) = (float(left), float(right)) else {
+                return Err(unsupported(ast));
+            };
+
+            Value::Float(match &ast.kind {
+                AdditionOp(..) => left + right,
+                SubtractionOp(..) => left - right,
+                MultiplicationOp(..) => left * right,
+                DivisionOp(..) => left / right,
+                ModulusOp(..) => left % right,
+                ExponentiationOp(..) => left.powf(right),
+                _ => return Err(unsupported(ast)),
+            })
+        }
+        _ => return Err(unsupported(ast)),
+    };
+
+    Ok(value)
+}
+
+fn float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(integer) => Some(*integer as f64),
+        Value::Float(float) => Some(*float),
+        _ => None,
+    }
+}
+
+fn unsupported(ast: &Ast) -> ZerodbError {
+    ZerodbError::UnsupportedExpression(ast.kind.to_string())
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::parser::Parser;
+
+    use super::*;
+
+    fn evaluate(scope: &Scope, input: &str) -> anyhow::Result<Value> {
+        let ast = Parser::new(input, 20).parse_exp()?.unwrap();
+        Ok(scope.evaluate(&ast)?)
+    }
+
+    #[test]
+    fn test_scope_evaluate() -> anyhow::Result<()> {
+        let scope = Scope::new()
+            .with_variable("value", Value::Int(20))
+            .with_fields(BTreeMap::from([
+                ("name".to_string(), Value::String("alice".into())),
+                ("age".to_string(), Value::Int(30)),
+            ]));
+
+        assert_eq!(
+            evaluate(&scope, "$value >= 18 && $value < 130")?,
+            Value::Bool(true)
+        );
+        assert_eq!(evaluate(&scope, "$value * 2 + age")?, Value::Int(70));
+        assert_eq!(evaluate(&scope, "$value / 8.0")?, Value::Float(2.5));
+        assert_eq!(evaluate(&scope, "name IS 'alice'")?, Value::Bool(true));
+        assert_eq!(
+            evaluate(&scope, "name + '!'")?,
+            Value::String("alice!".into())
+        );
+        assert_eq!(evaluate(&scope, "'li' IN name")?, Value::Bool(true));
+        assert_eq!(
+            evaluate(&scope, "[1, 2, 3] CONTAINS $value")?,
+            Value::Bool(false)
+        );
+        assert_eq!(evaluate(&scope, "email")?, Value::None);
+        assert_eq!(
+            evaluate(&scope, "string::uppercase(name)")?,
+            Value::String("ALICE".into())
+        );
+        assert_eq!(
+            evaluate(&scope, "{ a: { b: $value } }.a.b")?,
+            Value::Int(20)
+        );
+
+        assert!(evaluate(&scope, "$missing").is_err());
+        assert!(evaluate(&scope, "name * 2").is_err());
+
+        // Variants are read from the enums the scope knows.
+        let scope = scope.with_enums(BTreeMap::from([(
+            "color".to_string(),
+            vec!["red".to_string(), "green".to_string()],
+        )]));
+        assert_eq!(
+            evaluate(&scope, "color::green")?,
+            Value::Enum(EnumValue::new("color", 1))
+        );
+        assert_eq!(
+            evaluate(&scope, "color::red < color.green")?,
+            Value::Bool(true)
+        );
+        assert!(evaluate(&scope, "color::blue").is_err());
+
+        Ok(())
+    }
+}
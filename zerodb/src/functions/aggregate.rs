@@ -0,0 +1,91 @@
+use crate::{Value, ZerodbResult};
+
+use super::{array::distinct_values, number, Args, Builtin};
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The aggregate functions, which take the list of the values their argument takes across the
+/// rows of a group. `NONE` values are skipped, except by `array_agg`.
+///
+/// `count()` without an argument counts the rows, so it is called with a value for every row.
+pub(super) const FUNCTIONS: &[(&str, Builtin)] = &[
+    ("count", count),
+    ("sum", sum),
+    ("avg", avg),
+    ("min", min),
+    ("max", max),
+    ("distinct", distinct),
+    ("array_agg", array_agg),
+];
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn count(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(present(args)?.count() as i64))
+}
+
+/// Sums integers into an integer, numbers with any float into a float, and durations into a
+/// duration. The sum of no values is `0`.
+fn sum(args: &Args) -> ZerodbResult<Value> {
+    let mut sum = Value::Int(0);
+    for value in present(args)? {
+        sum = match (&sum, value) {
+            (Value::Int(a), Value::Int(b)) => a.checked_add(*b).map(Value::Int),
+            (Value::Int(0), Value::Duration(b)) => Some(Value::Duration(*b)),
+            (Value::Duration(a), Value::Duration(b)) => a.checked_add(*b).map(Value::Duration),
+            (a, b) => match (number(a), number(b)) {
+                (Some(a), Some(b)) => Some(Value::Float(a + b)),
+                _ => return Err(args.invalid(format!("cannot sum `{b}`"))),
+            },
+        }
+        .ok_or_else(|| args.invalid("the sum is out of range"))?;
+    }
+
+    Ok(sum)
+}
+
+/// Averages numbers into a float. The average of no values is `NONE`.
+fn avg(args: &Args) -> ZerodbResult<Value> {
+    let (mut sum, mut count) = (0.0, 0);
+    for value in present(args)? {
+        sum += number(value).ok_or_else(|| args.invalid(format!("cannot average `{value}`")))?;
+        count += 1;
+    }
+
+    Ok(match count {
+        0 => Value::None,
+        count => Value::Float(sum / count as f64),
+    })
+}
+
+fn min(args: &Args) -> ZerodbResult<Value> {
+    Ok(present(args)?
+        .min_by(|a, b| a.total_cmp(b))
+        .cloned()
+        .unwrap_or_default())
+}
+
+fn max(args: &Args) -> ZerodbResult<Value> {
+    Ok(present(args)?
+        .max_by(|a, b| a.total_cmp(b))
+        .cloned()
+        .unwrap_or_default())
+}
+
+fn distinct(args: &Args) -> ZerodbResult<Value> {
+    let values = present(args)?.cloned().collect::<Vec<_>>();
+    Ok(Value::List(distinct_values(&values)))
+}
+
+fn array_agg(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::List(args.list(0)?.to_vec()))
+}
+
+/// Gets the values of the group that are not `NONE`.
+fn present<'a>(args: &Args<'a>) -> ZerodbResult<impl Iterator<Item = &'a Value>> {
+    Ok(args.list(0)?.iter().filter(|value| **value != Value::None))
+}
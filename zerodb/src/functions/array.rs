@@ -0,0 +1,162 @@
+use std::{cmp::Ordering, collections::BTreeSet};
+
+use crate::{Value, ZerodbResult};
+
+use super::{slice_range, text, Args, Builtin};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// A value ordered by [`Value::total_cmp`], to keep values in ordered sets.
+struct Ordered<'a>(&'a Value);
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The functions of the `array::` namespace.
+pub(super) const FUNCTIONS: &[(&str, Builtin)] = &[
+    ("len", len),
+    ("first", first),
+    ("last", last),
+    ("distinct", distinct),
+    ("flatten", flatten),
+    ("reverse", reverse),
+    ("sort", sort),
+    ("push", push),
+    ("contains", contains),
+    ("join", join),
+    ("slice", slice),
+    ("concat", concat),
+    ("range", range),
+];
+
+/// The most items `array::range` creates.
+const MAX_RANGE_LEN: i64 = 1 << 20;
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn len(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.list(0)?.len() as i64))
+}
+
+fn first(args: &Args) -> ZerodbResult<Value> {
+    Ok(args.list(0)?.first().cloned().unwrap_or_default())
+}
+
+fn last(args: &Args) -> ZerodbResult<Value> {
+    Ok(args.list(0)?.last().cloned().unwrap_or_default())
+}
+
+fn distinct(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::List(distinct_values(args.list(0)?)))
+}
+
+/// Flattens one level of nesting.
+fn flatten(args: &Args) -> ZerodbResult<Value> {
+    let mut flattened = vec![];
+    for item in args.list(0)? {
+        match item {
+            Value::List(items) => flattened.extend(items.iter().cloned()),
+            item => flattened.push(item.clone()),
+        }
+    }
+
+    Ok(Value::List(flattened))
+}
+
+fn reverse(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::List(args.list(0)?.iter().rev().cloned().collect()))
+}
+
+fn sort(args: &Args) -> ZerodbResult<Value> {
+    let mut items = args.list(0)?.to_vec();
+    items.sort_by(Value::total_cmp);
+
+    Ok(Value::List(items))
+}
+
+fn push(args: &Args) -> ZerodbResult<Value> {
+    let mut items = args.list(0)?.to_vec();
+    items.push(args.get(1).clone());
+
+    Ok(Value::List(items))
+}
+
+fn contains(args: &Args) -> ZerodbResult<Value> {
+    let value = args.get(1);
+    Ok(Value::Bool(
+        args.list(0)?
+            .iter()
+            .any(|item| item.total_cmp(value).is_eq()),
+    ))
+}
+
+fn join(args: &Args) -> ZerodbResult<Value> {
+    let items = args.list(0)?.iter().map(text).collect::<Vec<_>>();
+    Ok(Value::String(items.join(args.string(1)?)))
+}
+
+/// Slices with negative indices counting from the end.
+fn slice(args: &Args) -> ZerodbResult<Value> {
+    let items = args.list(0)?;
+    let range = slice_range(args.int(1)?, args.optional_int(2)?, items.len());
+
+    Ok(Value::List(items[range].to_vec()))
+}
+
+fn concat(args: &Args) -> ZerodbResult<Value> {
+    let mut items = vec![];
+    for index in 0..args.rest(0).len() {
+        items.extend(args.list(index)?.iter().cloned());
+    }
+
+    Ok(Value::List(items))
+}
+
+/// The integers from `start` up to but not including `end`.
+fn range(args: &Args) -> ZerodbResult<Value> {
+    let (start, end) = (args.int(0)?, args.int(1)?);
+    if end.saturating_sub(start) > MAX_RANGE_LEN {
+        return Err(args.invalid(format!("the range has more than {MAX_RANGE_LEN} items")));
+    }
+
+    Ok(Value::List((start..end).map(Value::Int).collect()))
+}
+
+/// Gets the values without duplicates, in the order they first appear.
+pub(super) fn distinct_values(values: &[Value]) -> Vec<Value> {
+    let mut seen = BTreeSet::new();
+    values
+        .iter()
+        .filter(|value| seen.insert(Ordered(value)))
+        .cloned()
+        .collect()
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl PartialEq for Ordered<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Ordered<'_> {}
+
+impl PartialOrd for Ordered<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ordered<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(other.0)
+    }
+}
@@ -0,0 +1,54 @@
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::{Value, ZerodbResult};
+
+use super::{Args, Builtin};
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The functions of the `crypto::` namespace, which hash strings or byte strings into lowercase
+/// hex digests.
+pub(super) const FUNCTIONS: &[(&str, Builtin)] = &[
+    ("md5", md5),
+    ("sha1", sha1),
+    ("sha256", sha256),
+    ("sha512", sha512),
+];
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn md5(args: &Args) -> ZerodbResult<Value> {
+    hash::<Md5>(args)
+}
+
+fn sha1(args: &Args) -> ZerodbResult<Value> {
+    hash::<Sha1>(args)
+}
+
+fn sha256(args: &Args) -> ZerodbResult<Value> {
+    hash::<Sha256>(args)
+}
+
+fn sha512(args: &Args) -> ZerodbResult<Value> {
+    hash::<Sha512>(args)
+}
+
+fn hash<D: Digest>(args: &Args) -> ZerodbResult<Value> {
+    let digest = match args.get(0) {
+        Value::String(string) => D::digest(string.as_bytes()),
+        Value::Bytes(bytes) => D::digest(bytes),
+        value => {
+            return Err(args.invalid(format!("expected a string or byte string, found `{value}`")))
+        }
+    };
+
+    Ok(Value::String(
+        digest.iter().map(|byte| format!("{byte:02x}")).collect(),
+    ))
+}
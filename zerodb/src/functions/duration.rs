@@ -0,0 +1,86 @@
+use crate::{Duration, Value, ZerodbResult};
+
+use super::{Args, Builtin};
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The functions of the `duration::` namespace.
+pub(super) const FUNCTIONS: &[(&str, Builtin)] = &[
+    ("nanos", nanos),
+    ("micros", micros),
+    ("millis", millis),
+    ("secs", secs),
+    ("mins", mins),
+    ("hours", hours),
+    ("days", days),
+    ("weeks", weeks),
+    ("as_nanos", as_nanos),
+    ("as_millis", as_millis),
+    ("as_secs", as_secs),
+    ("parse", parse),
+];
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn nanos(args: &Args) -> ZerodbResult<Value> {
+    of(args, Duration::nanosecond())
+}
+
+fn micros(args: &Args) -> ZerodbResult<Value> {
+    of(args, Duration::microsecond())
+}
+
+fn millis(args: &Args) -> ZerodbResult<Value> {
+    of(args, Duration::millisecond())
+}
+
+fn secs(args: &Args) -> ZerodbResult<Value> {
+    of(args, Duration::second())
+}
+
+fn mins(args: &Args) -> ZerodbResult<Value> {
+    of(args, Duration::minute())
+}
+
+fn hours(args: &Args) -> ZerodbResult<Value> {
+    of(args, Duration::hour())
+}
+
+fn days(args: &Args) -> ZerodbResult<Value> {
+    of(args, Duration::day())
+}
+
+fn weeks(args: &Args) -> ZerodbResult<Value> {
+    of(args, Duration::week())
+}
+
+fn as_nanos(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.duration(0)?.nanos()))
+}
+
+fn as_millis(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(
+        args.duration(0)?.in_units_of(Duration::millisecond()),
+    ))
+}
+
+fn as_secs(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(
+        args.duration(0)?.in_units_of(Duration::second()),
+    ))
+}
+
+fn parse(args: &Args) -> ZerodbResult<Value> {
+    Duration::parse(args.string(0)?).map(Value::Duration)
+}
+
+/// Creates a duration of a count of `unit`s.
+fn of(args: &Args, unit: Duration) -> ZerodbResult<Value> {
+    Duration::checked_from(args.int(0)?, unit)
+        .map(Value::Duration)
+        .ok_or_else(|| args.invalid("the duration is out of range"))
+}
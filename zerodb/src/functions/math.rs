@@ -0,0 +1,140 @@
+use std::cmp::Ordering;
+
+use crate::{Value, ZerodbResult};
+
+use super::{Args, Builtin};
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The functions of the `math::` namespace.
+///
+/// Functions that keep the kind of their number, like `math::abs`, take and return integers or
+/// floats, while the others always return floats.
+pub(super) const FUNCTIONS: &[(&str, Builtin)] = &[
+    ("abs", abs),
+    ("ceil", ceil),
+    ("floor", floor),
+    ("round", round),
+    ("sqrt", sqrt),
+    ("exp", exp),
+    ("ln", ln),
+    ("log", log),
+    ("pow", pow),
+    ("clamp", clamp),
+    ("min", min),
+    ("max", max),
+    ("pi", pi),
+    ("e", e),
+];
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn abs(args: &Args) -> ZerodbResult<Value> {
+    match args.get(0) {
+        Value::Int(integer) => integer
+            .checked_abs()
+            .map(Value::Int)
+            .ok_or_else(|| args.invalid("the result is out of range")),
+        _ => Ok(Value::Float(args.float(0)?.abs())),
+    }
+}
+
+fn ceil(args: &Args) -> ZerodbResult<Value> {
+    rounded(args, f64::ceil)
+}
+
+fn floor(args: &Args) -> ZerodbResult<Value> {
+    rounded(args, f64::floor)
+}
+
+fn round(args: &Args) -> ZerodbResult<Value> {
+    rounded(args, f64::round)
+}
+
+fn sqrt(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Float(args.float(0)?.sqrt()))
+}
+
+fn exp(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Float(args.float(0)?.exp()))
+}
+
+fn ln(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Float(args.float(0)?.ln()))
+}
+
+fn log(args: &Args) -> ZerodbResult<Value> {
+    let (number, base) = (args.float(0)?, args.float(1)?);
+    let log = match base {
+        10.0 => number.log10(),
+        2.0 => number.log2(),
+        base => number.log(base),
+    };
+
+    Ok(Value::Float(log))
+}
+
+fn pow(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Float(args.float(0)?.powf(args.float(1)?)))
+}
+
+fn clamp(args: &Args) -> ZerodbResult<Value> {
+    args.float(0)?;
+    let (min, max) = (args.float(1)?, args.float(2)?);
+    if min > max {
+        return Err(args.invalid("`min` is greater than `max`"));
+    }
+
+    let (number, min, max) = (args.get(0), args.get(1), args.get(2));
+    let clamped = if number.total_cmp(min).is_lt() {
+        min
+    } else if number.total_cmp(max).is_gt() {
+        max
+    } else {
+        number
+    };
+
+    Ok(clamped.clone())
+}
+
+fn min(args: &Args) -> ZerodbResult<Value> {
+    extreme(args, Ordering::Less)
+}
+
+fn max(args: &Args) -> ZerodbResult<Value> {
+    extreme(args, Ordering::Greater)
+}
+
+fn pi(_: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Float(std::f64::consts::PI))
+}
+
+fn e(_: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Float(std::f64::consts::E))
+}
+
+/// Rounds floats with `round`, leaving integers as they are.
+fn rounded(args: &Args, round: fn(f64) -> f64) -> ZerodbResult<Value> {
+    match args.get(0) {
+        Value::Int(integer) => Ok(Value::Int(*integer)),
+        _ => Ok(Value::Float(round(args.float(0)?))),
+    }
+}
+
+/// Gets the number that is furthest in the direction of `ordering`, or `NONE` if there are none.
+fn extreme(args: &Args, ordering: Ordering) -> ZerodbResult<Value> {
+    let mut extreme = &Value::None;
+    for index in 0..args.rest(0).len() {
+        args.float(index)?;
+        let number = args.get(index);
+        if extreme == &Value::None || number.total_cmp(extreme) == ordering {
+            extreme = number;
+        }
+    }
+
+    Ok(extreme.clone())
+}
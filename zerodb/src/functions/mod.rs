@@ -0,0 +1,540 @@
+//! The built-in functions of zeroql.
+//!
+//! Their signatures live in [`FunctionRegistry::builtins`], which the type checker checks calls
+//! against, and this module executes them. The functions of `graph::` need a graph to run on and
+//! are called through [`Graph::call_function`](crate::graph::Graph::call_function) instead, and
+//! `search::score()` is answered by the [`Scope`](crate::Scope) of a full-text search.
+//!
+//! ```txt
+//! string::uppercase("alice")
+//! math::max(1, 2.5, 3)
+//! time::now() + duration::weeks(2)
+//! crypto::sha256("secret")
+//! ```
+//!
+//! Aggregates like `sum` and `count` fold the values their argument takes across the rows of a
+//! group, so they are called with the list of those values.
+
+mod aggregate;
+mod array;
+mod crypto;
+mod duration;
+mod math;
+mod object;
+mod random;
+mod string;
+mod time;
+
+use std::{collections::BTreeMap, sync::OnceLock};
+
+use zeroql::{
+    ast::{Ast, AstKind},
+    parser::Parser,
+    sema::{FunctionRegistry, FunctionSignature, Param},
+    Span,
+};
+
+use crate::{Datetime, Duration, Value, ZerodbError, ZerodbResult};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// The implementation of a built-in function.
+pub type Builtin = fn(&Args) -> ZerodbResult<Value>;
+
+/// `Args` are the arguments of a call to a built-in function, one for each parameter of its
+/// signature in order, with defaults filled in, followed by the values of its variadic parameter.
+#[derive(Debug, Clone, Copy)]
+pub struct Args<'a> {
+    function: &'a str,
+    values: &'a [Value],
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl<'a> Args<'a> {
+    /// Creates the arguments of a call to `function`.
+    pub fn new(function: &'a str, values: &'a [Value]) -> Self {
+        Self { function, values }
+    }
+
+    /// Gets the argument at `index`, or `NONE` if there is none.
+    pub fn get(&self, index: usize) -> &'a Value {
+        const NONE: &Value = &Value::None;
+        self.values.get(index).unwrap_or(NONE)
+    }
+
+    /// Gets the arguments from `index` on, i.e. the values of the variadic parameter.
+    pub fn rest(&self, index: usize) -> &'a [Value] {
+        self.values.get(index..).unwrap_or_default()
+    }
+
+    /// Gets the argument at `index` as a string.
+    pub fn string(&self, index: usize) -> ZerodbResult<&'a str> {
+        match self.get(index) {
+            Value::String(string) => Ok(string),
+            value => Err(self.expected("a string", value)),
+        }
+    }
+
+    /// Gets the argument at `index` as an integer.
+    pub fn int(&self, index: usize) -> ZerodbResult<i64> {
+        match self.get(index) {
+            Value::Int(integer) => Ok(*integer),
+            value => Err(self.expected("an integer", value)),
+        }
+    }
+
+    /// Gets the argument at `index` as an integer, or `None` if it is `NONE`.
+    pub fn optional_int(&self, index: usize) -> ZerodbResult<Option<i64>> {
+        match self.get(index) {
+            Value::None => Ok(None),
+            _ => self.int(index).map(Some),
+        }
+    }
+
+    /// Gets the argument at `index` as a float, converting integers.
+    pub fn float(&self, index: usize) -> ZerodbResult<f64> {
+        number(self.get(index)).ok_or_else(|| self.expected("a number", self.get(index)))
+    }
+
+    /// Gets the argument at `index` as a list.
+    pub fn list(&self, index: usize) -> ZerodbResult<&'a [Value]> {
+        match self.get(index) {
+            Value::List(items) => Ok(items),
+            value => Err(self.expected("an array", value)),
+        }
+    }
+
+    /// Gets the argument at `index` as an object.
+    pub fn object(&self, index: usize) -> ZerodbResult<&'a BTreeMap<String, Value>> {
+        match self.get(index) {
+            Value::Object(entries) => Ok(entries),
+            value => Err(self.expected("an object", value)),
+        }
+    }
+
+    /// Gets the argument at `index` as a datetime.
+    pub fn datetime(&self, index: usize) -> ZerodbResult<Datetime> {
+        match self.get(index) {
+            Value::Datetime(datetime) => Ok(*datetime),
+            value => Err(self.expected("a datetime", value)),
+        }
+    }
+
+    /// Gets the argument at `index` as a duration.
+    pub fn duration(&self, index: usize) -> ZerodbResult<Duration> {
+        match self.get(index) {
+            Value::Duration(duration) => Ok(*duration),
+            value => Err(self.expected("a duration", value)),
+        }
+    }
+
+    /// Creates the error for arguments the function does not accept.
+    pub fn invalid(&self, reason: impl Into<String>) -> ZerodbError {
+        ZerodbError::InvalidFunctionArgs {
+            function: self.function.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    fn expected(&self, expected: &str, found: &Value) -> ZerodbError {
+        self.invalid(format!("expected {expected}, found `{found}`"))
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Gets the signatures of the built-in functions.
+pub fn registry() -> &'static FunctionRegistry {
+    static REGISTRY: OnceLock<FunctionRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(FunctionRegistry::builtins)
+}
+
+/// Gets the implementation of a built-in function by its full name, e.g. `string::len`.
+pub fn builtin(name: &str) -> Option<Builtin> {
+    let (namespace, function) = name.rsplit_once("::").unwrap_or(("", name));
+    let functions = match namespace {
+        "string" => string::FUNCTIONS,
+        "math" => math::FUNCTIONS,
+        "time" => time::FUNCTIONS,
+        "duration" => duration::FUNCTIONS,
+        "array" => array::FUNCTIONS,
+        "object" => object::FUNCTIONS,
+        "rand" => random::RAND_FUNCTIONS,
+        "uuid" => random::UUID_FUNCTIONS,
+        "crypto" => crypto::FUNCTIONS,
+        "" => aggregate::FUNCTIONS,
+        _ => return None,
+    };
+
+    functions
+        .iter()
+        .find(|(candidate, _)| *candidate == function)
+        .map(|(_, builtin)| *builtin)
+}
+
+/// Calls a built-in function with positional arguments.
+///
+/// Trailing parameters that are left out take their defaults, and arguments beyond the parameters
+/// go to the variadic parameter.
+pub fn call(name: &str, mut args: Vec<Value>) -> ZerodbResult<Value> {
+    let (signature, builtin) = lookup(name)?;
+    let invalid = |reason: String| ZerodbError::InvalidFunctionArgs {
+        function: name.to_string(),
+        reason,
+    };
+
+    if args.len() > signature.params.len() && signature.variadic.is_none() {
+        return Err(invalid(format!(
+            "expected at most {} arguments, found {}",
+            signature.params.len(),
+            args.len()
+        )));
+    }
+
+    for param in signature.params.iter().skip(args.len()) {
+        if param.default.is_none() {
+            return Err(invalid(format!("missing argument `{}`", param.name)));
+        }
+
+        args.push(default_value(param)?);
+    }
+
+    builtin(&Args::new(name, &args))
+}
+
+/// Calls a built-in function from a function call node whose arguments are constants, binding
+/// them to the parameters by position and by name.
+pub fn call_ast(ast: &Ast) -> ZerodbResult<Value> {
+    call_ast_with(ast, Value::from_ast)
+}
+
+/// Calls a built-in function from a function call node like [`call_ast`], getting the values of
+/// its arguments with `evaluate`.
+pub fn call_ast_with(
+    ast: &Ast,
+    mut evaluate: impl FnMut(&Ast) -> ZerodbResult<Value>,
+) -> ZerodbResult<Value> {
+    let AstKind::FunctionCall { subject, args } = &ast.kind else {
+        return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+    };
+
+    let name = FunctionRegistry::name_of(subject)
+        .ok_or_else(|| ZerodbError::UnsupportedExpression(subject.kind.to_string()))?;
+    let (signature, builtin) = lookup(&name)?;
+
+    let mut values = signature
+        .params
+        .iter()
+        .map(default_value)
+        .collect::<ZerodbResult<Vec<_>>>()?;
+
+    for (param, arg) in bind_args(signature, args, &ast.span)? {
+        let value = evaluate(arg)?;
+        match signature.params.iter().position(|p| std::ptr::eq(p, param)) {
+            Some(position) => values[position] = value,
+            None => values.push(value),
+        }
+    }
+
+    builtin(&Args::new(&name, &values))
+}
+
+/// Binds the arguments of a call to the parameters of `signature`, by position and then by name.
+///
+/// Gets each parameter that is given along with the value node of its argument.
+pub(crate) fn bind_args<'s, 'b, 'a>(
+    signature: &'s FunctionSignature,
+    args: &'b [Ast<'a>],
+    span: &Span,
+) -> ZerodbResult<Vec<(&'s Param, &'b Ast<'a>)>> {
+    let bound = signature
+        .bind(args, span)
+        .map_err(|error| ZerodbError::InvalidFunctionArgs {
+            function: signature.name.clone(),
+            reason: error.to_string(),
+        })?;
+
+    Ok(bound
+        .into_iter()
+        .map(|(param, index)| match &args[index].kind {
+            AstKind::FunctionArg { value, .. } => (param, value.as_ref()),
+            _ => (param, &args[index]),
+        })
+        .collect())
+}
+
+/// Gets the value a parameter takes when no argument is given for it, which is `NONE` if it has
+/// no default.
+pub(crate) fn default_value(param: &Param) -> ZerodbResult<Value> {
+    let source = param.default.as_deref().unwrap_or("NONE");
+    let ast = Parser::new(source, 20)
+        .parse_op()
+        .ok()
+        .flatten()
+        .ok_or_else(|| ZerodbError::UnsupportedExpression(source.to_string()))?;
+
+    Value::from_ast(&ast)
+}
+
+fn lookup(name: &str) -> ZerodbResult<(&'static FunctionSignature, Builtin)> {
+    registry()
+        .get(name)
+        .zip(builtin(name))
+        .ok_or_else(|| ZerodbError::UnknownFunction(name.to_string()))
+}
+
+/// Gets a number as a float, converting integers.
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(integer) => Some(*integer as f64),
+        Value::Float(float) => Some(*float),
+        _ => None,
+    }
+}
+
+/// Gets the text of a value for joining and concatenating, which is the string itself for
+/// strings.
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Resolves a possibly negative index, which counts from the end, against a length.
+fn position(index: i64, len: usize) -> usize {
+    if index < 0 {
+        len.saturating_sub(index.unsigned_abs() as usize)
+    } else {
+        (index as usize).min(len)
+    }
+}
+
+/// Gets the range of a slice from `start` up to `end`, both of which may count from the end.
+fn slice_range(start: i64, end: Option<i64>, len: usize) -> std::ops::Range<usize> {
+    let start = position(start, len);
+    let end = end.map_or(len, |end| position(end, len));
+    start..end.max(start)
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> ZerodbResult<Value> {
+        let ast = Parser::new(input, 20).parse_op().unwrap().unwrap();
+        call_ast(&ast)
+    }
+
+    fn string(string: &str) -> Value {
+        Value::String(string.to_string())
+    }
+
+    #[test]
+    fn test_functions_every_builtin_is_implemented() {
+        let missing = registry()
+            .functions()
+            .filter(|signature| !signature.name.starts_with("graph::"))
+            .filter(|signature| !signature.name.starts_with("index::"))
+            .filter(|signature| !signature.name.starts_with("search::"))
+            .filter(|signature| builtin(&signature.name).is_none())
+            .map(|signature| signature.name.clone())
+            .collect::<Vec<_>>();
+
+        assert!(missing.is_empty(), "not implemented: {missing:?}");
+    }
+
+    #[test]
+    fn test_functions_string_and_array() -> anyhow::Result<()> {
+        assert_eq!(eval(r#"string::uppercase("alice")"#)?, string("ALICE"));
+        assert_eq!(eval(r#"string::len("héllo")"#)?, Value::Int(5));
+        assert_eq!(eval(r#"string::slice("zeroql", 1, -1)"#)?, string("eroq"));
+        assert_eq!(
+            eval(r#"string::slice("zeroql", offset = -2)"#)?,
+            string("ql")
+        );
+        assert_eq!(
+            eval(r#"string::join(", ", "a", "b", "c")"#)?,
+            string("a, b, c")
+        );
+        assert_eq!(eval(r#"string::concat("n", 1, true)"#)?, string("n1true"));
+        assert_eq!(
+            eval(r#"string::split("a,b", ",")"#)?,
+            Value::List(vec![string("a"), string("b")])
+        );
+
+        assert_eq!(
+            eval("array::sort([3, 1.5, 2])")?,
+            Value::List(vec![Value::Float(1.5), Value::Int(2), Value::Int(3)])
+        );
+        assert_eq!(
+            eval("array::distinct([1, 2, 1, 3, 2])")?,
+            Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)])
+        );
+        assert_eq!(
+            eval("array::flatten([[1], 2, [3, [4]]])")?,
+            Value::List(vec![
+                Value::Int(1),
+                Value::Int(2),
+                Value::Int(3),
+                Value::List(vec![Value::Int(4)]),
+            ])
+        );
+        assert_eq!(
+            eval("array::range(1, 4)")?,
+            eval("array::concat([1, 2], [3])")?
+        );
+        assert_eq!(eval("array::first([])")?, Value::None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_functions_math_and_object() -> anyhow::Result<()> {
+        assert_eq!(eval("math::abs(-3)")?, Value::Int(3));
+        assert_eq!(eval("math::floor(2.7)")?, Value::Float(2.0));
+        assert_eq!(eval("math::max(1, 2.5, 2)")?, Value::Float(2.5));
+        assert_eq!(eval("math::clamp(12, 0, 10)")?, Value::Int(10));
+        assert_eq!(eval("math::log(1000)")?, Value::Float(3.0));
+        assert_eq!(eval("math::pow(2, exponent = 10)")?, Value::Float(1024.0));
+        assert!(eval("math::abs(-9223372036854775807 - 1)").is_err());
+
+        assert_eq!(
+            eval("object::keys({ b: 1, a: 2 })")?,
+            Value::List(vec![string("a"), string("b")])
+        );
+        assert_eq!(
+            eval("object::merge({ a: 1, b: 1 }, { b: 2 })")?,
+            Value::Object(BTreeMap::from([
+                ("a".to_string(), Value::Int(1)),
+                ("b".to_string(), Value::Int(2)),
+            ]))
+        );
+        assert_eq!(eval(r#"object::has({ a: 1 }, "b")"#)?, Value::Bool(false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_functions_time_and_duration() -> anyhow::Result<()> {
+        let datetime = eval(r#"time::parse("2024-03-10T08:45:30Z")"#)?;
+        let Value::Datetime(datetime) = datetime else {
+            panic!("expected a datetime, found {datetime}");
+        };
+
+        let at = |name: &str| call(name, vec![Value::Datetime(datetime)]);
+        assert_eq!(at("time::year")?, Value::Int(2024));
+        assert_eq!(at("time::hour")?, Value::Int(8));
+        assert_eq!(at("time::weekday")?, Value::Int(7));
+        assert_eq!(at("time::unix")?, Value::Int(1_710_060_330));
+        assert_eq!(at("time::format")?, string("2024-03-10T08:45:30Z"));
+        assert_eq!(
+            call(
+                "time::floor",
+                vec![Value::Datetime(datetime), eval("duration::hours(1)")?]
+            )?,
+            Value::Datetime(Datetime::parse("2024-03-10T08:00:00Z")?)
+        );
+
+        assert_eq!(eval("duration::mins(90)")?.to_string(), "1h30m");
+        assert_eq!(
+            call("duration::as_secs", vec![eval(r#"duration::parse("2h")"#)?])?,
+            Value::Int(7_200)
+        );
+        assert!(eval("duration::weeks(9223372036854775807)").is_err());
+        assert!(eval(r#"time::parse("tomorrow")"#).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_functions_random_and_crypto() -> anyhow::Result<()> {
+        assert_eq!(
+            eval(r#"crypto::md5("abc")"#)?,
+            string("900150983cd24fb0d6963f7d28e17f72")
+        );
+        assert_eq!(
+            eval(r#"crypto::sha256("abc")"#)?,
+            string("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            eval(r#"crypto::sha1("abc")"#)?,
+            string("a9993e364706816aba3e25717850c26c9cd0d89d")
+        );
+
+        let Value::Int(roll) = eval("rand::int(1, 6)")? else {
+            panic!("expected an integer");
+        };
+        assert!((1..=6).contains(&roll));
+        assert!(eval("rand::int(6, 1)").is_err());
+
+        let Value::String(token) = eval("rand::string()")? else {
+            panic!("expected a string");
+        };
+        assert_eq!(token.len(), 16);
+
+        assert_eq!(
+            eval("uuid::nil()")?,
+            string("00000000-0000-0000-0000-000000000000")
+        );
+        let uuid = eval("uuid::v4()")?;
+        assert_eq!(call("uuid::is_valid", vec![uuid])?, Value::Bool(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_functions_aggregates() -> anyhow::Result<()> {
+        let ages = Value::List(vec![
+            Value::Int(30),
+            Value::None,
+            Value::Int(20),
+            Value::Int(30),
+        ]);
+
+        assert_eq!(call("count", vec![ages.clone()])?, Value::Int(3));
+        assert_eq!(call("sum", vec![ages.clone()])?, Value::Int(80));
+        assert_eq!(call("avg", vec![ages.clone()])?, Value::Float(80.0 / 3.0));
+        assert_eq!(call("min", vec![ages.clone()])?, Value::Int(20));
+        assert_eq!(call("max", vec![ages.clone()])?, Value::Int(30));
+        assert_eq!(
+            call("distinct", vec![ages.clone()])?,
+            Value::List(vec![Value::Int(30), Value::Int(20)])
+        );
+        assert_eq!(call("avg", vec![Value::List(vec![])])?, Value::None);
+        assert!(call("sum", vec![Value::List(vec![string("a")])]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_functions_errors() {
+        assert!(matches!(
+            eval("string::shout(\"a\")"),
+            Err(ZerodbError::UnknownFunction(name)) if name == "string::shout"
+        ));
+        assert!(matches!(
+            eval("string::len(1)"),
+            Err(ZerodbError::InvalidFunctionArgs { function, .. }) if function == "string::len"
+        ));
+        assert!(matches!(
+            eval("string::len()"),
+            Err(ZerodbError::InvalidFunctionArgs { .. })
+        ));
+        assert!(matches!(
+            call("string::len", vec![]),
+            Err(ZerodbError::InvalidFunctionArgs { .. })
+        ));
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+
+use crate::{Value, ZerodbResult};
+
+use super::{Args, Builtin};
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The functions of the `object::` namespace. Keys are in order.
+pub(super) const FUNCTIONS: &[(&str, Builtin)] = &[
+    ("keys", keys),
+    ("values", values),
+    ("entries", entries),
+    ("len", len),
+    ("has", has),
+    ("merge", merge),
+];
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn keys(args: &Args) -> ZerodbResult<Value> {
+    let keys = args.object(0)?.keys().cloned().map(Value::String);
+    Ok(Value::List(keys.collect()))
+}
+
+fn values(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::List(args.object(0)?.values().cloned().collect()))
+}
+
+/// Gets the entries as `[key, value]` pairs.
+fn entries(args: &Args) -> ZerodbResult<Value> {
+    let entries = args
+        .object(0)?
+        .iter()
+        .map(|(key, value)| Value::List(vec![Value::String(key.clone()), value.clone()]));
+
+    Ok(Value::List(entries.collect()))
+}
+
+fn len(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.object(0)?.len() as i64))
+}
+
+fn has(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Bool(args.object(0)?.contains_key(args.string(1)?)))
+}
+
+/// Merges the objects from left to right, so later keys override earlier ones.
+fn merge(args: &Args) -> ZerodbResult<Value> {
+    let mut merged = BTreeMap::new();
+    for index in 0..args.rest(0).len() {
+        merged.extend(args.object(index)?.clone());
+    }
+
+    Ok(Value::Object(merged))
+}
@@ -0,0 +1,86 @@
+use rand::{distributions::Alphanumeric, seq::SliceRandom, Rng};
+use uuid::Uuid;
+
+use crate::{Value, ZerodbResult};
+
+use super::{Args, Builtin};
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The functions of the `rand::` namespace.
+pub(super) const RAND_FUNCTIONS: &[(&str, Builtin)] = &[
+    ("float", float),
+    ("bool", bool),
+    ("int", int),
+    ("string", string),
+    ("choice", choice),
+];
+
+/// The functions of the `uuid::` namespace.
+pub(super) const UUID_FUNCTIONS: &[(&str, Builtin)] =
+    &[("v4", v4), ("nil", nil), ("is_valid", is_valid)];
+
+/// The longest string `rand::string` creates.
+const MAX_STRING_LEN: i64 = 1 << 16;
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// A float from 0 up to but not including 1.
+fn float(_: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Float(rand::thread_rng().gen()))
+}
+
+fn bool(_: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Bool(rand::thread_rng().gen()))
+}
+
+/// An integer from `min` up to and including `max`.
+fn int(args: &Args) -> ZerodbResult<Value> {
+    let (min, max) = (args.int(0)?, args.int(1)?);
+    if min > max {
+        return Err(args.invalid("`min` is greater than `max`"));
+    }
+
+    Ok(Value::Int(rand::thread_rng().gen_range(min..=max)))
+}
+
+/// A string of random ASCII letters and digits.
+fn string(args: &Args) -> ZerodbResult<Value> {
+    let length = args.int(0)?;
+    if !(0..=MAX_STRING_LEN).contains(&length) {
+        return Err(args.invalid(format!("the length must be between 0 and {MAX_STRING_LEN}")));
+    }
+
+    let string = rand::thread_rng()
+        .sample_iter(Alphanumeric)
+        .take(length as usize)
+        .map(char::from)
+        .collect();
+
+    Ok(Value::String(string))
+}
+
+/// An item of the array, or `NONE` if it is empty.
+fn choice(args: &Args) -> ZerodbResult<Value> {
+    let items = args.list(0)?;
+    Ok(items
+        .choose(&mut rand::thread_rng())
+        .cloned()
+        .unwrap_or_default())
+}
+
+fn v4(_: &Args) -> ZerodbResult<Value> {
+    Ok(Value::String(Uuid::new_v4().to_string()))
+}
+
+fn nil(_: &Args) -> ZerodbResult<Value> {
+    Ok(Value::String(Uuid::nil().to_string()))
+}
+
+fn is_valid(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Bool(Uuid::parse_str(args.string(0)?).is_ok()))
+}
@@ -0,0 +1,113 @@
+use crate::{Value, ZerodbResult};
+
+use super::{slice_range, text, Args, Builtin};
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The functions of the `string::` namespace.
+pub(super) const FUNCTIONS: &[(&str, Builtin)] = &[
+    ("len", len),
+    ("lowercase", lowercase),
+    ("uppercase", uppercase),
+    ("trim", trim),
+    ("reverse", reverse),
+    ("contains", contains),
+    ("starts_with", starts_with),
+    ("ends_with", ends_with),
+    ("split", split),
+    ("replace", replace),
+    ("slice", slice),
+    ("repeat", repeat),
+    ("join", join),
+    ("concat", concat),
+];
+
+/// The longest string `string::repeat` builds, in bytes.
+const MAX_REPEAT_LEN: usize = 1 << 20;
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn len(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.string(0)?.chars().count() as i64))
+}
+
+fn lowercase(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::String(args.string(0)?.to_lowercase()))
+}
+
+fn uppercase(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::String(args.string(0)?.to_uppercase()))
+}
+
+fn trim(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::String(args.string(0)?.trim().to_string()))
+}
+
+fn reverse(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::String(args.string(0)?.chars().rev().collect()))
+}
+
+fn contains(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Bool(args.string(0)?.contains(args.string(1)?)))
+}
+
+fn starts_with(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Bool(args.string(0)?.starts_with(args.string(1)?)))
+}
+
+fn ends_with(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Bool(args.string(0)?.ends_with(args.string(1)?)))
+}
+
+fn split(args: &Args) -> ZerodbResult<Value> {
+    let parts = args
+        .string(0)?
+        .split(args.string(1)?)
+        .map(|part| Value::String(part.to_string()));
+
+    Ok(Value::List(parts.collect()))
+}
+
+fn replace(args: &Args) -> ZerodbResult<Value> {
+    let (string, search) = (args.string(0)?, args.string(1)?);
+    Ok(Value::String(string.replace(search, args.string(2)?)))
+}
+
+/// Slices by characters, with negative indices counting from the end.
+fn slice(args: &Args) -> ZerodbResult<Value> {
+    let chars = args.string(0)?.chars().collect::<Vec<_>>();
+    let range = slice_range(args.int(1)?, args.optional_int(2)?, chars.len());
+
+    Ok(Value::String(chars[range].iter().collect()))
+}
+
+fn repeat(args: &Args) -> ZerodbResult<Value> {
+    let string = args.string(0)?;
+    let count = usize::try_from(args.int(1)?)
+        .map_err(|_| args.invalid("the count must not be negative"))?;
+
+    if string.len().saturating_mul(count) > MAX_REPEAT_LEN {
+        return Err(args.invalid(format!(
+            "the result would be longer than {MAX_REPEAT_LEN} bytes"
+        )));
+    }
+
+    Ok(Value::String(string.repeat(count)))
+}
+
+fn join(args: &Args) -> ZerodbResult<Value> {
+    let separator = args.string(0)?;
+    let strings = (1..=args.rest(1).len())
+        .map(|index| args.string(index))
+        .collect::<ZerodbResult<Vec<_>>>()?;
+
+    Ok(Value::String(strings.join(separator)))
+}
+
+fn concat(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::String(args.rest(0).iter().map(text).collect()))
+}
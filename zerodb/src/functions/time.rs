@@ -0,0 +1,83 @@
+use crate::{Datetime, Value, ZerodbResult};
+
+use super::{Args, Builtin};
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The functions of the `time::` namespace. Datetimes are in UTC.
+pub(super) const FUNCTIONS: &[(&str, Builtin)] = &[
+    ("now", now),
+    ("parse", parse),
+    ("format", format),
+    ("from_unix", from_unix),
+    ("floor", floor),
+    ("unix", unix),
+    ("year", year),
+    ("month", month),
+    ("day", day),
+    ("hour", hour),
+    ("minute", minute),
+    ("second", second),
+    ("weekday", weekday),
+];
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn now(_: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Datetime(Datetime::now()))
+}
+
+fn parse(args: &Args) -> ZerodbResult<Value> {
+    Datetime::parse(args.string(0)?).map(Value::Datetime)
+}
+
+fn format(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::String(args.datetime(0)?.to_string()))
+}
+
+fn from_unix(args: &Args) -> ZerodbResult<Value> {
+    Datetime::from_unix(args.int(0)?).map(Value::Datetime)
+}
+
+fn floor(args: &Args) -> ZerodbResult<Value> {
+    args.datetime(0)?
+        .floor(args.duration(1)?)
+        .map(Value::Datetime)
+        .ok_or_else(|| args.invalid("the duration must be positive"))
+}
+
+fn unix(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.datetime(0)?.unix()))
+}
+
+fn year(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.datetime(0)?.date().0))
+}
+
+fn month(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.datetime(0)?.date().1))
+}
+
+fn day(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.datetime(0)?.date().2))
+}
+
+fn hour(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.datetime(0)?.time().0))
+}
+
+fn minute(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.datetime(0)?.time().1))
+}
+
+fn second(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.datetime(0)?.time().2))
+}
+
+fn weekday(args: &Args) -> ZerodbResult<Value> {
+    Ok(Value::Int(args.datetime(0)?.weekday()))
+}
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{RecordId, Value};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Edge` is a relationship between two records.
+///
+/// Edges are records in their own right. The table of an edge's id is the edge type, e.g. the edge
+/// created by `RELATE person:alice -> buys -> product:apple` has an id like `buys:<uuid>`, and it can
+/// carry fields of its own like any other record.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Edge {
+    /// The id of the edge.
+    id: RecordId,
+
+    /// The record the edge starts from.
+    from: RecordId,
+
+    /// The record the edge points to.
+    to: RecordId,
+
+    /// The fields of the edge.
+    fields: BTreeMap<String, Value>,
+}
+
+/// `Direction` is the direction an edge is followed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Follows edges from their `from` record to their `to` record, i.e. `->`.
+    Out,
+
+    /// Follows edges from their `to` record back to their `from` record, i.e. `<-`.
+    In,
+}
+
+/// `Adjacent` is a record reached from another record by following a single edge.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Adjacent {
+    /// The id of the edge that was followed.
+    pub edge: RecordId,
+
+    /// The record at the other end of the edge.
+    pub node: RecordId,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Edge {
+    /// Creates a new edge.
+    pub fn new(
+        id: RecordId,
+        from: RecordId,
+        to: RecordId,
+        fields: BTreeMap<String, Value>,
+    ) -> Self {
+        Self {
+            id,
+            from,
+            to,
+            fields,
+        }
+    }
+
+    /// Gets the id of the edge.
+    pub fn id(&self) -> &RecordId {
+        &self.id
+    }
+
+    /// Gets the type of the edge, which is the table of its id.
+    pub fn kind(&self) -> &str {
+        self.id.table()
+    }
+
+    /// Gets the record the edge starts from.
+    pub fn from(&self) -> &RecordId {
+        &self.from
+    }
+
+    /// Gets the record the edge points to.
+    pub fn to(&self) -> &RecordId {
+        &self.to
+    }
+
+    /// Gets the fields of the edge.
+    pub fn fields(&self) -> &BTreeMap<String, Value> {
+        &self.fields
+    }
+
+    /// Gets the value of a field of the edge.
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.fields.get(name)
+    }
+
+    /// Gets the record at the end of the edge when it is followed in `direction`.
+    pub fn target(&self, direction: Direction) -> &RecordId {
+        match direction {
+            Direction::Out => &self.to,
+            Direction::In => &self.from,
+        }
+    }
+}
+
+impl Direction {
+    /// Gets the opposite direction.
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::Out => Direction::In,
+            Direction::In => Direction::Out,
+        }
+    }
+}
@@ -0,0 +1,822 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use zeroql::{
+    ast::{Ast, AstKind},
+    sema::{FunctionSignature, Param},
+};
+
+use crate::{
+    catalog::Catalog, functions, store::KvStore, RecordId, Scope, Value, ZerodbError, ZerodbResult,
+};
+
+use super::{Depth, Direction, Graph, TraversalOptions};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `GraphFunction` is a function of the `graph::` namespace.
+///
+/// Every function works on edges of a single type declared with `DEFINE EDGE`. The edge argument
+/// takes the same depth syntax as the edge of a relate operation, e.g. `knows[0..3]`, and without
+/// a depth the edges are followed for any number of levels.
+///
+/// ```txt
+/// graph::shortest_path(person:alice, person:dave, knows)
+/// graph::all_paths(person:alice, person:dave, knows[0..3])
+/// graph::components(knows)
+/// graph::pagerank(knows, damping = 0.85, iterations = 20)
+/// graph::degree(person:alice, knows, direction = "in")
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFunction {
+    /// `graph::shortest_path(source, target, kind)`, the records along one of the shortest paths
+    /// from `source` to `target`, or `NONE` if there is no path.
+    ShortestPath,
+
+    /// `graph::all_paths(source, target, kind)`, every path from `source` to `target` that does
+    /// not visit a record twice. The edge must have a bounded depth.
+    AllPaths,
+
+    /// `graph::components(kind)`, the weakly connected components of the records with edges.
+    Components,
+
+    /// `graph::pagerank(kind, damping, iterations)`, the PageRank of the records with edges.
+    PageRank,
+
+    /// `graph::degree(record, kind, direction)`, the number of edges of a record.
+    Degree,
+}
+
+/// `EdgeArg` is the edge argument of a graph function, e.g. `knows` or `knows[0..3]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeArg {
+    /// The type of the edges.
+    pub kind: String,
+
+    /// The levels the edges are followed for.
+    pub depth: Depth,
+}
+
+/// The arguments of a call to a graph function, bound to the parameters of its signature.
+struct GraphArgs<'b, 'a> {
+    /// The function called.
+    function: GraphFunction,
+
+    /// The signature of the function.
+    signature: &'static FunctionSignature,
+
+    /// The parameters that are given, with the value nodes of their arguments.
+    bound: Vec<(&'static Param, &'b Ast<'a>)>,
+
+    /// The scope the arguments are evaluated in.
+    scope: &'b Scope,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl GraphFunction {
+    /// Gets the function named by the subject of a function call, e.g. `graph::pagerank`.
+    pub fn from_ast(ast: &Ast) -> Option<Self> {
+        let AstKind::ScopedIdentifier(segments) = &ast.kind else {
+            return None;
+        };
+
+        match segments.as_slice() {
+            [namespace, name] if namespace.kind == AstKind::Identifier("graph") => {
+                match name.kind {
+                    AstKind::Identifier("shortest_path") => Some(Self::ShortestPath),
+                    AstKind::Identifier("all_paths") => Some(Self::AllPaths),
+                    AstKind::Identifier("components") => Some(Self::Components),
+                    AstKind::Identifier("pagerank") => Some(Self::PageRank),
+                    AstKind::Identifier("degree") => Some(Self::Degree),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the full name of the function.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ShortestPath => "graph::shortest_path",
+            Self::AllPaths => "graph::all_paths",
+            Self::Components => "graph::components",
+            Self::PageRank => "graph::pagerank",
+            Self::Degree => "graph::degree",
+        }
+    }
+
+    /// Gets the signature of the function, which its arguments are bound to.
+    pub fn signature(&self) -> ZerodbResult<&'static FunctionSignature> {
+        functions::registry()
+            .get(self.name())
+            .ok_or_else(|| ZerodbError::UnknownFunction(self.name().to_string()))
+    }
+}
+
+impl EdgeArg {
+    /// Compiles an edge argument. Without a depth, edges are followed for any number of levels.
+    pub fn from_ast(ast: &Ast) -> ZerodbResult<Self> {
+        let (subject, depth) = match &ast.kind {
+            AstKind::Index { subject, index } => (subject.as_ref(), Depth::from_ast(index)?),
+            _ => (ast, Depth { min: 0, max: None }),
+        };
+
+        let AstKind::Identifier(kind) = subject.kind else {
+            return Err(ZerodbError::UnsupportedExpression(subject.kind.to_string()));
+        };
+
+        Ok(Self {
+            kind: kind.to_string(),
+            depth,
+        })
+    }
+}
+
+impl<S> Graph<S>
+where
+    S: KvStore,
+{
+    /// Calls a function of the `graph::` namespace.
+    ///
+    /// Arguments may be given by position or by name, e.g. `graph::pagerank(knows, damping = 0.5)`,
+    /// and parameters left out take the defaults of the function's signature. The arguments other
+    /// than the edge are evaluated in `scope`, so they can refer to its variables, e.g.
+    /// `graph::pagerank(knows, $damping)`.
+    pub fn call_function(
+        &self,
+        ast: &Ast,
+        scope: &Scope,
+        options: &TraversalOptions,
+    ) -> ZerodbResult<Value> {
+        let AstKind::FunctionCall { subject, args } = &ast.kind else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let function = GraphFunction::from_ast(subject)
+            .ok_or_else(|| ZerodbError::UnknownFunction(function_name(subject)))?;
+        let signature = function.signature()?;
+        let args = GraphArgs {
+            function,
+            signature,
+            bound: functions::bind_args(signature, args, &ast.span)?,
+            scope,
+        };
+
+        let value = match function {
+            GraphFunction::ShortestPath => {
+                let from = args.record("source")?;
+                let to = args.record("target")?;
+                let edge = self.edge_arg(&args, "kind")?;
+
+                match self.shortest_path(&from, &to, &edge.kind, &edge.depth, options)? {
+                    Some(path) => records_value(path),
+                    None => Value::None,
+                }
+            }
+            GraphFunction::AllPaths => {
+                let from = args.record("source")?;
+                let to = args.record("target")?;
+                let edge = self.edge_arg(&args, "kind")?;
+
+                Value::List(
+                    self.all_paths(&from, &to, &edge.kind, &edge.depth, options)?
+                        .into_iter()
+                        .map(records_value)
+                        .collect(),
+                )
+            }
+            GraphFunction::Components => {
+                let edge = self.edge_arg(&args, "kind")?;
+                no_depth(function, &edge)?;
+
+                Value::List(
+                    self.components(&edge.kind)?
+                        .into_iter()
+                        .map(records_value)
+                        .collect(),
+                )
+            }
+            GraphFunction::PageRank => {
+                let edge = self.edge_arg(&args, "kind")?;
+                no_depth(function, &edge)?;
+
+                let damping = args.number("damping")?;
+                let iterations = match args.value("iterations")? {
+                    Value::Int(iterations) if iterations >= 0 => iterations as usize,
+                    _ => return Err(invalid_args(function, "`iterations` must be a count")),
+                };
+
+                Value::List(
+                    self.pagerank(&edge.kind, damping, iterations)?
+                        .into_iter()
+                        .map(|(record, score)| {
+                            Value::Object(BTreeMap::from([
+                                ("record".to_string(), Value::Record(record)),
+                                ("score".to_string(), Value::Float(score)),
+                            ]))
+                        })
+                        .collect(),
+                )
+            }
+            GraphFunction::Degree => {
+                let record = args.record("record")?;
+                let edge = match args.get("kind") {
+                    Some(_) => Some(self.edge_arg(&args, "kind")?),
+                    None => None,
+                };
+                if let Some(edge) = &edge {
+                    no_depth(function, edge)?;
+                }
+
+                let direction = match args.value("direction")? {
+                    Value::String(direction) => match direction.as_str() {
+                        "out" => Some(Direction::Out),
+                        "in" => Some(Direction::In),
+                        "both" => None,
+                        _ => {
+                            return Err(invalid_args(
+                                function,
+                                "`direction` must be \"out\", \"in\" or \"both\"",
+                            ))
+                        }
+                    },
+                    _ => return Err(invalid_args(function, "`direction` must be a string")),
+                };
+
+                let degree = self.degree(
+                    &record,
+                    edge.as_ref().map(|edge| edge.kind.as_str()),
+                    direction,
+                )?;
+                Value::Int(degree as i64)
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Finds one of the shortest paths from `from` to `to` over edges of type `kind` within `depth`,
+    /// returning the records along it, both ends included.
+    ///
+    /// A path always follows at least one edge, so `from` only reaches itself through a cycle.
+    pub fn shortest_path(
+        &self,
+        from: &RecordId,
+        to: &RecordId,
+        kind: &str,
+        depth: &Depth,
+        options: &TraversalOptions,
+    ) -> ZerodbResult<Option<Vec<RecordId>>> {
+        // A record may have to be passed more than once to reach the minimum depth, so the search
+        // runs over (record, hops) states with the hops capped once the minimum is reached.
+        let cap = depth.min + 1;
+        let start = (from.clone(), 0);
+        let mut parents = HashMap::<(RecordId, usize), (RecordId, usize)>::new();
+        let mut visited = HashSet::from([start.clone()]);
+        let mut queue = VecDeque::from([(start, 0)]);
+
+        while let Some((state, hops)) = queue.pop_front() {
+            if depth.max.is_some_and(|max| hops > max) {
+                continue;
+            }
+
+            for next in self.adjacent_limited(&state.0, Direction::Out, Some(kind), options)? {
+                let next_state = (next, (hops + 1).min(cap));
+                if !visited.insert(next_state.clone()) {
+                    continue;
+                }
+
+                parents.insert(next_state.clone(), state.clone());
+                if &next_state.0 == to && next_state.1 == cap {
+                    let mut path = vec![next_state.0.clone()];
+                    let mut current = next_state;
+                    while let Some(parent) = parents.get(&current) {
+                        path.push(parent.0.clone());
+                        current = parent.clone();
+                    }
+
+                    path.reverse();
+                    return Ok(Some(path));
+                }
+
+                queue.push_back((next_state, hops + 1));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Finds every path from `from` to `to` over edges of type `kind` within `depth` that does not
+    /// visit a record twice, returning the records along each one.
+    pub fn all_paths(
+        &self,
+        from: &RecordId,
+        to: &RecordId,
+        kind: &str,
+        depth: &Depth,
+        options: &TraversalOptions,
+    ) -> ZerodbResult<Vec<Vec<RecordId>>> {
+        let Some(max) = depth.max else {
+            return Err(invalid_args(
+                GraphFunction::AllPaths,
+                "the edge must have a bounded depth, e.g. `knows[0..3]`",
+            ));
+        };
+
+        let mut paths = vec![];
+        let mut path = vec![from.clone()];
+        self.collect_paths(&mut path, to, kind, (depth.min, max), options, &mut paths)?;
+
+        Ok(paths)
+    }
+
+    /// Finds the weakly connected components of the records with edges of type `kind`.
+    ///
+    /// Each component is sorted, and the components are ordered by their first record.
+    pub fn components(&self, kind: &str) -> ZerodbResult<Vec<Vec<RecordId>>> {
+        let mut neighbours = BTreeMap::<RecordId, BTreeSet<RecordId>>::new();
+        for edge in self.edges_of_kind(kind)? {
+            neighbours
+                .entry(edge.from().clone())
+                .or_default()
+                .insert(edge.to().clone());
+            neighbours
+                .entry(edge.to().clone())
+                .or_default()
+                .insert(edge.from().clone());
+        }
+
+        let mut components = vec![];
+        let mut seen = HashSet::new();
+        for node in neighbours.keys() {
+            if !seen.insert(node.clone()) {
+                continue;
+            }
+
+            let mut component = vec![node.clone()];
+            let mut queue = VecDeque::from([node.clone()]);
+            while let Some(node) = queue.pop_front() {
+                for next in neighbours[&node].iter() {
+                    if seen.insert(next.clone()) {
+                        component.push(next.clone());
+                        queue.push_back(next.clone());
+                    }
+                }
+            }
+
+            component.sort();
+            components.push(component);
+        }
+
+        Ok(components)
+    }
+
+    /// Computes the PageRank of the records with edges of type `kind`, highest first.
+    ///
+    /// Parallel edges count once each, and records without outgoing edges spread their rank evenly
+    /// over every record.
+    pub fn pagerank(
+        &self,
+        kind: &str,
+        damping: f64,
+        iterations: usize,
+    ) -> ZerodbResult<Vec<(RecordId, f64)>> {
+        if !(0.0..=1.0).contains(&damping) {
+            return Err(invalid_args(
+                GraphFunction::PageRank,
+                "`damping` must be between 0 and 1",
+            ));
+        }
+
+        let edges = self.edges_of_kind(kind)?;
+        let nodes = edges
+            .iter()
+            .flat_map(|edge| [edge.from().clone(), edge.to().clone()])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if nodes.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let index = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node, i))
+            .collect::<HashMap<_, _>>();
+        let links = edges
+            .iter()
+            .map(|edge| (index[edge.from()], index[edge.to()]))
+            .collect::<Vec<_>>();
+
+        let mut out_degree = vec![0usize; nodes.len()];
+        for (from, _) in links.iter() {
+            out_degree[*from] += 1;
+        }
+
+        let n = nodes.len() as f64;
+        let mut ranks = vec![1.0 / n; nodes.len()];
+        for _ in 0..iterations {
+            let dangling = (0..nodes.len())
+                .filter(|i| out_degree[*i] == 0)
+                .map(|i| ranks[i])
+                .sum::<f64>();
+
+            let mut next = vec![(1.0 - damping) / n + damping * dangling / n; nodes.len()];
+            for (from, to) in links.iter() {
+                next[*to] += damping * ranks[*from] / out_degree[*from] as f64;
+            }
+
+            ranks = next;
+        }
+
+        let mut scores = nodes.into_iter().zip(ranks).collect::<Vec<_>>();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Ok(scores)
+    }
+
+    /// Counts the edges of `node`, optionally only of type `kind` and only in `direction`. Without
+    /// a direction, edges in both directions are counted.
+    pub fn degree(
+        &self,
+        node: &RecordId,
+        kind: Option<&str>,
+        direction: Option<Direction>,
+    ) -> ZerodbResult<usize> {
+        let directions = match direction {
+            Some(direction) => vec![direction],
+            None => vec![Direction::Out, Direction::In],
+        };
+
+        let mut degree = 0;
+        for direction in directions {
+            degree += self.adjacent(node, direction, kind)?.len();
+        }
+
+        Ok(degree)
+    }
+
+    fn collect_paths(
+        &self,
+        path: &mut Vec<RecordId>,
+        to: &RecordId,
+        kind: &str,
+        (min, max): (usize, usize),
+        options: &TraversalOptions,
+        paths: &mut Vec<Vec<RecordId>>,
+    ) -> ZerodbResult<()> {
+        let last = path[path.len() - 1].clone();
+        for next in self.adjacent_limited(&last, Direction::Out, Some(kind), options)? {
+            if path.contains(&next) {
+                continue;
+            }
+
+            // Levels count from 0, so a path at level `n` has followed `n + 1` edges.
+            let level = path.len() - 1;
+            path.push(next.clone());
+            if &next == to {
+                if level >= min {
+                    paths.push(path.clone());
+                }
+            } else if level < max {
+                self.collect_paths(path, to, kind, (min, max), options, paths)?;
+            }
+            path.pop();
+        }
+
+        Ok(())
+    }
+
+    fn edge_arg(&self, args: &GraphArgs, name: &str) -> ZerodbResult<EdgeArg> {
+        let edge = EdgeArg::from_ast(args.required(name)?)?;
+        if !Catalog::new().edge_defined(self.store(), self.database(), &edge.kind)? {
+            return Err(ZerodbError::UndefinedEdge(edge.kind));
+        }
+
+        Ok(edge)
+    }
+}
+
+impl<'b, 'a> GraphArgs<'b, 'a> {
+    /// Gets the value node of the argument given for a parameter, if one is given.
+    fn get(&self, name: &str) -> Option<&'b Ast<'a>> {
+        self.bound
+            .iter()
+            .find(|(param, _)| param.name == name)
+            .map(|(_, arg)| *arg)
+    }
+
+    /// Gets the value node of the argument given for a parameter without a default.
+    fn required(&self, name: &str) -> ZerodbResult<&'b Ast<'a>> {
+        self.get(name)
+            .ok_or_else(|| invalid_args(self.function, &format!("missing argument `{name}`")))
+    }
+
+    /// Gets the value of the argument given for a parameter, evaluated in the scope of the call,
+    /// or the default of the parameter if none is given.
+    fn value(&self, name: &str) -> ZerodbResult<Value> {
+        if let Some(arg) = self.get(name) {
+            return self.scope.evaluate(arg);
+        }
+
+        match self
+            .signature
+            .params
+            .iter()
+            .find(|param| param.name == name)
+        {
+            Some(param) => functions::default_value(param),
+            None => Err(invalid_args(
+                self.function,
+                &format!("unknown argument `{name}`"),
+            )),
+        }
+    }
+
+    fn record(&self, name: &str) -> ZerodbResult<RecordId> {
+        match self.scope.evaluate(self.required(name)?)? {
+            Value::Record(record) => Ok(record),
+            _ => Err(invalid_args(
+                self.function,
+                &format!("`{name}` must be a record"),
+            )),
+        }
+    }
+
+    fn number(&self, name: &str) -> ZerodbResult<f64> {
+        match self.value(name)? {
+            Value::Int(integer) => Ok(integer as f64),
+            Value::Float(float) => Ok(float),
+            value => Err(invalid_args(
+                self.function,
+                &format!("`{name}` must be a number, found: {value}"),
+            )),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn function_name(ast: &Ast) -> String {
+    match &ast.kind {
+        AstKind::ScopedIdentifier(segments) => segments
+            .iter()
+            .map(function_name)
+            .collect::<Vec<_>>()
+            .join("::"),
+        AstKind::Identifier(name) => name.to_string(),
+        kind => kind.to_string(),
+    }
+}
+
+fn no_depth(function: GraphFunction, edge: &EdgeArg) -> ZerodbResult<()> {
+    if edge.depth != (Depth { min: 0, max: None }) {
+        return Err(invalid_args(
+            function,
+            &format!("`{}` does not take an edge depth", edge.kind),
+        ));
+    }
+
+    Ok(())
+}
+
+fn records_value(records: Vec<RecordId>) -> Value {
+    Value::List(records.into_iter().map(Value::Record).collect())
+}
+
+fn invalid_args(function: GraphFunction, reason: &str) -> ZerodbError {
+    ZerodbError::InvalidFunctionArgs {
+        function: function.name().to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::parser::Parser;
+
+    use crate::store::MemoryKvStore;
+
+    use super::*;
+
+    fn graph(edges: &[(&str, &str, &str)]) -> anyhow::Result<Graph<MemoryKvStore>> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+        let catalog = Catalog::new();
+        let ast = Parser::new("DEFINE EDGE knows; DEFINE EDGE likes", 20)
+            .parse_program()
+            .into_result()?;
+        for statement in ast.kind.unwrap_program() {
+            let change = catalog.schema_change(graph.store(), &statement, graph.database())?;
+            catalog.apply_schema_change(graph.store_mut(), &change.unwrap())?;
+        }
+
+        for (from, kind, to) in edges {
+            graph.relate(
+                &RecordId::new("person", *from),
+                kind,
+                &RecordId::new("person", *to),
+                BTreeMap::new(),
+            )?;
+        }
+
+        Ok(graph)
+    }
+
+    fn call(graph: &Graph<MemoryKvStore>, input: &str) -> anyhow::Result<Value> {
+        let ast = Parser::new(input, 20).parse_op()?.unwrap();
+        Ok(graph.call_function(&ast, &Scope::new(), &TraversalOptions::default())?)
+    }
+
+    fn people(ids: &[&str]) -> Value {
+        Value::List(
+            ids.iter()
+                .map(|id| Value::Record(RecordId::new("person", *id)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_graph_function_paths() -> anyhow::Result<()> {
+        // a -> b -> c -> d, with a shortcut a -> c.
+        let graph = graph(&[
+            ("a", "knows", "b"),
+            ("b", "knows", "c"),
+            ("c", "knows", "d"),
+            ("a", "knows", "c"),
+        ])?;
+
+        assert_eq!(
+            call(&graph, "graph::shortest_path(person:a, person:d, knows)")?,
+            people(&["a", "c", "d"])
+        );
+        assert_eq!(
+            call(&graph, "graph::shortest_path(person:a, person:d, knows[2])")?,
+            people(&["a", "b", "c", "d"])
+        );
+        assert_eq!(
+            call(&graph, "graph::shortest_path(person:d, person:a, knows)")?,
+            Value::None
+        );
+        assert_eq!(
+            call(
+                &graph,
+                "graph::shortest_path(source = person:a, target = person:d, kind = knows[0..1])"
+            )?,
+            Value::None
+        );
+
+        assert_eq!(
+            call(&graph, "graph::all_paths(person:a, person:d, knows[0..3])")?,
+            Value::List(vec![
+                people(&["a", "b", "c", "d"]),
+                people(&["a", "c", "d"])
+            ])
+        );
+        assert_eq!(
+            call(&graph, "graph::all_paths(person:a, person:d, knows[1])")?,
+            Value::List(vec![people(&["a", "c", "d"])])
+        );
+        assert!(call(&graph, "graph::all_paths(person:a, person:d, knows)").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_function_components_and_degree() -> anyhow::Result<()> {
+        let graph = graph(&[
+            ("a", "knows", "b"),
+            ("c", "knows", "b"),
+            ("d", "knows", "e"),
+            ("a", "likes", "d"),
+        ])?;
+
+        assert_eq!(
+            call(&graph, "graph::components(knows)")?,
+            Value::List(vec![people(&["a", "b", "c"]), people(&["d", "e"])])
+        );
+
+        assert_eq!(call(&graph, "graph::degree(person:b)")?, Value::Int(2));
+        assert_eq!(call(&graph, "graph::degree(person:a)")?, Value::Int(2));
+        assert_eq!(
+            call(&graph, "graph::degree(person:a, knows)")?,
+            Value::Int(1)
+        );
+        assert_eq!(
+            call(
+                &graph,
+                r#"graph::degree(person:b, knows, direction = "out")"#
+            )?,
+            Value::Int(0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_function_pagerank() -> anyhow::Result<()> {
+        let graph = graph(&[
+            ("a", "knows", "c"),
+            ("b", "knows", "c"),
+            ("c", "knows", "a"),
+        ])?;
+
+        let Value::List(scores) = call(&graph, "graph::pagerank(knows, iterations = 50)")? else {
+            panic!("expected a list");
+        };
+
+        let scores = scores
+            .into_iter()
+            .map(|score| match score {
+                Value::Object(entry) => match (&entry["record"], &entry["score"]) {
+                    (Value::Record(record), Value::Float(score)) => {
+                        (record.id().to_string(), *score)
+                    }
+                    _ => panic!("unexpected entry"),
+                },
+                _ => panic!("unexpected score"),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            scores.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>(),
+            ["c", "a", "b"]
+        );
+        assert!((scores.iter().map(|(_, score)| score).sum::<f64>() - 1.0).abs() < 1e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_function_scope() -> anyhow::Result<()> {
+        let graph = graph(&[("a", "knows", "b"), ("b", "knows", "c")])?;
+        let scope = Scope::new()
+            .with_variable("damping", Value::Float(0.5))
+            .with_variable("from", Value::Record(RecordId::new("person", "a")));
+
+        let ast = Parser::new("graph::pagerank(knows, $damping)", 20)
+            .parse_op()?
+            .unwrap();
+        assert_eq!(
+            graph.call_function(&ast, &scope, &TraversalOptions::default())?,
+            call(&graph, "graph::pagerank(knows, damping = 0.5)")?
+        );
+
+        let ast = Parser::new("graph::degree($from)", 20).parse_op()?.unwrap();
+        assert_eq!(
+            graph.call_function(&ast, &scope, &TraversalOptions::default())?,
+            Value::Int(1)
+        );
+
+        let ast = Parser::new("graph::pagerank(knows, $missing)", 20)
+            .parse_op()?
+            .unwrap();
+        assert!(graph
+            .call_function(&ast, &scope, &TraversalOptions::default())
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_function_errors() -> anyhow::Result<()> {
+        let graph = graph(&[("a", "knows", "b")])?;
+
+        for input in [
+            "graph::components(follows)",
+            "graph::components(knows[1])",
+            "graph::shortest_path(person:a, knows)",
+            "graph::shortest_path(person:a, person:b, knows, person:c)",
+            "graph::pagerank(knows, damping = 2)",
+            "graph::pagerank(knows, alpha = 0.5)",
+            r#"graph::degree(person:a, direction = "up")"#,
+            "graph::unknown(knows)",
+        ] {
+            assert!(call(&graph, input).is_err(), "{input}");
+        }
+
+        assert!(matches!(
+            graph.call_function(
+                &Parser::new("graph::components(follows)", 20)
+                    .parse_op()?
+                    .unwrap(),
+                &Scope::new(),
+                &TraversalOptions::default()
+            ),
+            Err(ZerodbError::UndefinedEdge(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::BTreeMap;
+
+use uuid::Uuid;
+
+use zeroutils_path::Path;
+
+use crate::{
+    store::{Key, KvStore},
+    table::release_unique,
+    RecordId, Value, ZerodbResult,
+};
+
+use super::{Adjacent, Direction, Edge};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Graph` stores edges between records in a key-value store.
+///
+/// Every edge is written three times: once as a record, and once in the adjacency list of each of
+/// its ends, so that following edges in either direction is a prefix scan.
+///
+/// ```txt
+/// g / <db> / e / <edge table> / <edge id>                                                -> Edge
+/// g / <db> / o / <from table> / <from id> / <edge table> / <to table> / <to id> / <edge id> -> ()
+/// g / <db> / i / <to table> / <to id> / <edge table> / <from table> / <from id> / <edge id> -> ()
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Graph<S> {
+    /// The store the edges are kept in.
+    store: S,
+
+    /// The database whose edges are kept, and whose edge definitions apply.
+    database: Path,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl<S> Graph<S>
+where
+    S: KvStore,
+{
+    /// Creates a new graph over the given store.
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            database: Path::default(),
+        }
+    }
+
+    /// Sets the database whose edges are read and written, and whose edge definitions apply to the
+    /// edges `RELATE` creates.
+    pub fn with_database(mut self, database: Path) -> Self {
+        self.database = database;
+        self
+    }
+
+    /// Gets the database whose edge definitions apply.
+    pub fn database(&self) -> &Path {
+        &self.database
+    }
+
+    /// Gets the underlying store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Gets the underlying store mutably.
+    pub fn store_mut(&mut self) -> &mut S {
+        &mut self.store
+    }
+
+    /// Consumes the graph and returns the underlying store.
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    /// Creates an edge of type `kind` from `from` to `to` with a newly generated id.
+    pub fn relate(
+        &mut self,
+        from: &RecordId,
+        kind: &str,
+        to: &RecordId,
+        fields: BTreeMap<String, Value>,
+    ) -> ZerodbResult<Edge> {
+        let id = RecordId::new(kind, Uuid::new_v4().simple().to_string());
+        let edge = Edge::new(id, from.clone(), to.clone(), fields);
+        self.insert_edge(&edge)?;
+        Ok(edge)
+    }
+
+    /// Stores an edge, replacing any edge that already has the same id.
+    pub fn insert_edge(&mut self, edge: &Edge) -> ZerodbResult<()> {
+        self.delete_edge(edge.id())?;
+
+        let out_key = adjacency_key(
+            &self.database,
+            Direction::Out,
+            edge.from(),
+            edge.id(),
+            edge.to(),
+        );
+        let in_key = adjacency_key(
+            &self.database,
+            Direction::In,
+            edge.to(),
+            edge.id(),
+            edge.from(),
+        );
+
+        self.store
+            .put_encoded(edge_key(&self.database, edge.id()).as_bytes(), edge)?;
+        self.store.put(out_key.as_bytes(), vec![])?;
+        self.store.put(in_key.as_bytes(), vec![])?;
+
+        Ok(())
+    }
+
+    /// Gets an edge by its id.
+    pub fn edge(&self, id: &RecordId) -> ZerodbResult<Option<Edge>> {
+        self.store
+            .get_decoded(edge_key(&self.database, id).as_bytes())
+    }
+
+    /// Gets the records adjacent to `node` in `direction`, optionally only over edges of type `kind`.
+    pub fn adjacent(
+        &self,
+        node: &RecordId,
+        direction: Direction,
+        kind: Option<&str>,
+    ) -> ZerodbResult<Vec<Adjacent>> {
+        let mut prefix = adjacency_prefix(&self.database, direction, node);
+        if let Some(kind) = kind {
+            prefix = prefix.with(kind);
+        }
+
+        let adjacent = self
+            .store
+            .scan_prefix(prefix.as_bytes())?
+            .into_iter()
+            .filter_map(|(key, _)| match Key::decode_str(&key).as_slice() {
+                [_, _, _, _, _, kind, table, id, edge_id] => Some(Adjacent {
+                    edge: RecordId::new(kind, edge_id),
+                    node: RecordId::new(table, id),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        Ok(adjacent)
+    }
+
+    /// Gets the records that have at least one edge in `direction`, optionally only from `table`.
+    pub fn nodes(&self, direction: Direction, table: Option<&str>) -> ZerodbResult<Vec<RecordId>> {
+        let mut prefix = graph_key(&self.database).with(direction_segment(direction));
+        if let Some(table) = table {
+            prefix = prefix.with(table);
+        }
+
+        let mut nodes = Vec::<RecordId>::new();
+        for (key, _) in self.store.scan_prefix(prefix.as_bytes())? {
+            if let [_, _, _, table, id, ..] = Key::decode_str(&key).as_slice() {
+                // Keys are ordered, so the adjacency entries of a record are all next to each other.
+                let node = RecordId::new(table, id);
+                if nodes.last() != Some(&node) {
+                    nodes.push(node);
+                }
+            }
+        }
+
+        Ok(nodes)
+    }
+
+    /// Deletes an edge by its id, returning it if it existed.
+    pub fn delete_edge(&mut self, id: &RecordId) -> ZerodbResult<Option<Edge>> {
+        let Some(edge) = self.edge(id)? else {
+            return Ok(None);
+        };
+
+        let out_key = adjacency_key(
+            &self.database,
+            Direction::Out,
+            edge.from(),
+            edge.id(),
+            edge.to(),
+        );
+        let in_key = adjacency_key(
+            &self.database,
+            Direction::In,
+            edge.to(),
+            edge.id(),
+            edge.from(),
+        );
+
+        self.store.delete(out_key.as_bytes())?;
+        self.store.delete(in_key.as_bytes())?;
+        self.store.delete(edge_key(&self.database, id).as_bytes())?;
+        release_unique(
+            &mut self.store,
+            &self.database,
+            edge.kind(),
+            id,
+            edge.fields(),
+        )?;
+
+        Ok(Some(edge))
+    }
+
+    /// Deletes the edges of type `kind` that leave `node` in `direction`, returning them.
+    ///
+    /// If `other` is given, only the edges whose other end is `other` are deleted.
+    pub fn delete_edges(
+        &mut self,
+        node: &RecordId,
+        direction: Direction,
+        kind: &str,
+        other: Option<&RecordId>,
+    ) -> ZerodbResult<Vec<Edge>> {
+        let mut deleted = vec![];
+        for adjacent in self.adjacent(node, direction, Some(kind))? {
+            if other.is_some_and(|other| other != &adjacent.node) {
+                continue;
+            }
+
+            if let Some(edge) = self.delete_edge(&adjacent.edge)? {
+                deleted.push(edge);
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Gets all the edges of type `kind`.
+    pub fn edges_of_kind(&self, kind: &str) -> ZerodbResult<Vec<Edge>> {
+        self.store
+            .scan_prefix(graph_key(&self.database).with("e").with(kind).as_bytes())?
+            .into_iter()
+            .map(|(_, value)| Ok(cbor4ii::serde::from_slice(&value)?))
+            .collect()
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Gets the key all the edges and adjacency lists of a database are under.
+pub(crate) fn graph_key(database: &Path) -> Key {
+    Key::new().with("g").with(database.to_string())
+}
+
+fn edge_key(database: &Path, id: &RecordId) -> Key {
+    graph_key(database).with("e").with(id.table()).with(id.id())
+}
+
+fn direction_segment(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Out => "o",
+        Direction::In => "i",
+    }
+}
+
+fn adjacency_prefix(database: &Path, direction: Direction, node: &RecordId) -> Key {
+    graph_key(database)
+        .with(direction_segment(direction))
+        .with(node.table())
+        .with(node.id())
+}
+
+fn adjacency_key(
+    database: &Path,
+    direction: Direction,
+    node: &RecordId,
+    edge: &RecordId,
+    other: &RecordId,
+) -> Key {
+    adjacency_prefix(database, direction, node)
+        .with(edge.table())
+        .with(other.table())
+        .with(other.id())
+        .with(edge.id())
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::store::MemoryKvStore;
+
+    use super::*;
+
+    #[test]
+    fn test_graph_relate_and_adjacent() -> anyhow::Result<()> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+        let alice = RecordId::new("person", "alice");
+        let apple = RecordId::new("product", "apple");
+        let pear = RecordId::new("product", "pear");
+
+        let fields = BTreeMap::from([("quantity".to_string(), Value::Int(2))]);
+        let buys_apple = graph.relate(&alice, "buys", &apple, fields)?;
+        let buys_pear = graph.relate(&alice, "buys", &pear, BTreeMap::new())?;
+        let likes_apple = graph.relate(&alice, "likes", &apple, BTreeMap::new())?;
+
+        assert_eq!(buys_apple.kind(), "buys");
+        assert_eq!(graph.edge(buys_apple.id())?, Some(buys_apple.clone()));
+        assert_eq!(
+            graph.edge(buys_apple.id())?.unwrap().field("quantity"),
+            Some(&Value::Int(2))
+        );
+
+        let mut bought = graph.adjacent(&alice, Direction::Out, Some("buys"))?;
+        bought.sort();
+        let mut expected = vec![
+            Adjacent {
+                edge: buys_apple.id().clone(),
+                node: apple.clone(),
+            },
+            Adjacent {
+                edge: buys_pear.id().clone(),
+                node: pear.clone(),
+            },
+        ];
+        expected.sort();
+        assert_eq!(bought, expected);
+
+        assert_eq!(graph.adjacent(&alice, Direction::Out, None)?.len(), 3);
+        assert_eq!(graph.adjacent(&apple, Direction::In, None)?.len(), 2);
+        assert_eq!(
+            graph.adjacent(&apple, Direction::In, Some("likes"))?,
+            vec![Adjacent {
+                edge: likes_apple.id().clone(),
+                node: alice.clone(),
+            }]
+        );
+        assert!(graph.adjacent(&apple, Direction::Out, None)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_databases() -> anyhow::Result<()> {
+        let alice = RecordId::new("person", "alice");
+        let apple = RecordId::new("product", "apple");
+
+        // Edges between records of the same ids in two databases are kept apart.
+        let mut shop = Graph::new(MemoryKvStore::new()).with_database("shop".parse()?);
+        let buys = shop.relate(&alice, "buys", &apple, BTreeMap::new())?;
+
+        let mut blog = Graph::new(shop.into_store()).with_database("blog".parse()?);
+        assert_eq!(blog.edge(buys.id())?, None);
+        assert!(blog.adjacent(&alice, Direction::Out, None)?.is_empty());
+        assert!(blog.nodes(Direction::Out, None)?.is_empty());
+        assert!(blog.edges_of_kind("buys")?.is_empty());
+
+        blog.insert_edge(&buys)?;
+        blog.delete_edge(buys.id())?;
+
+        let shop = Graph::new(blog.into_store()).with_database("shop".parse()?);
+        assert_eq!(shop.edge(buys.id())?, Some(buys.clone()));
+        assert_eq!(shop.nodes(Direction::Out, None)?, vec![alice.clone()]);
+        assert_eq!(
+            shop.adjacent(&apple, Direction::In, Some("buys"))?,
+            vec![Adjacent {
+                edge: buys.id().clone(),
+                node: alice,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_delete_edges() -> anyhow::Result<()> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+        let alice = RecordId::new("person", "alice");
+        let apple = RecordId::new("product", "apple");
+        let pear = RecordId::new("product", "pear");
+
+        let buys_apple = graph.relate(&alice, "buys", &apple, BTreeMap::new())?;
+        graph.relate(&alice, "buys", &pear, BTreeMap::new())?;
+        graph.relate(&alice, "likes", &apple, BTreeMap::new())?;
+
+        let deleted = graph.delete_edges(&alice, Direction::Out, "buys", Some(&apple))?;
+        assert_eq!(deleted, vec![buys_apple.clone()]);
+        assert_eq!(graph.edge(buys_apple.id())?, None);
+        assert_eq!(graph.adjacent(&apple, Direction::In, None)?.len(), 1);
+
+        let deleted = graph.delete_edges(&alice, Direction::Out, "buys", None)?;
+        assert_eq!(deleted.len(), 1);
+        assert!(graph
+            .adjacent(&alice, Direction::Out, Some("buys"))?
+            .is_empty());
+        assert!(graph.edges_of_kind("buys")?.is_empty());
+        assert_eq!(graph.edges_of_kind("likes")?.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,447 @@
+use zeroql::ast::Ast;
+
+use crate::{
+    catalog::{Catalog, SchemaChange},
+    store::{Key, KvStore},
+    table::{claim_unique, migration_key, unique_fields, Migration, MigrationTarget},
+    RecordId, ZerodbResult,
+};
+
+use super::{graph_key, Edge, Graph};
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Migration {
+    /// Gets the edge as it is after the migration.
+    fn migrate_edge(&self, edge: &Edge) -> ZerodbResult<Edge> {
+        let rename = |record: &RecordId| match record.table() == self.name {
+            true => RecordId::new(&self.new_name, record.id()),
+            false => record.clone(),
+        };
+
+        if self.target == MigrationTarget::Table {
+            return Ok(Edge::new(
+                edge.id().clone(),
+                rename(edge.from()),
+                rename(edge.to()),
+                edge.fields().clone(),
+            ));
+        }
+
+        Ok(Edge::new(
+            RecordId::new(&self.new_name, edge.id().id()),
+            edge.from().clone(),
+            edge.to().clone(),
+            self.migrate_fields(edge.fields().clone())?,
+        ))
+    }
+}
+
+impl<S> Graph<S>
+where
+    S: KvStore,
+{
+    /// Executes a `REDEFINE TABLE` or `REDEFINE EDGE` statement.
+    ///
+    /// The definition in the catalog is changed right away as a schema change, and the migration
+    /// of the stored records is started, to be run with [`Graph::migrate`]. The migration keeps the
+    /// definition it replaces, to put back if it is aborted.
+    ///
+    /// Fails if the table or edge is not defined in the database of the graph, unless the statement
+    /// says `IF EXISTS`.
+    ///
+    /// ```txt
+    /// REDEFINE TABLE IF EXISTS person AS people
+    /// ```
+    pub fn execute_redefine(&mut self, ast: &Ast) -> ZerodbResult<Migration> {
+        let mut migration = Migration::from_ast(ast)?;
+        let catalog = Catalog::new();
+
+        if let Some(change) = catalog.schema_change(self.store(), ast, self.database())? {
+            if let SchemaChange::Redefine { path, new_path, .. } = &change {
+                if let Some(previous) = catalog.schema(self.store(), path)? {
+                    migration = migration.with_undo(SchemaChange::Redefine {
+                        path: new_path.clone(),
+                        new_path: path.clone(),
+                        meta: previous.meta,
+                    });
+                }
+            }
+
+            catalog.apply_schema_change(self.store_mut(), &change)?;
+        }
+
+        let key = migration_key(self.database(), migration.target, &migration.name);
+        self.store_mut().put_encoded(key.as_bytes(), &migration)?;
+
+        Ok(migration)
+    }
+
+    /// Checks or migrates up to `batch_size` records and edges of the migration of the table or
+    /// edge `name`, returning whether the migration is done.
+    ///
+    /// All the records and edges are checked before any is migrated. If one cannot be coerced to
+    /// its new shape, the migration is aborted with the definition it replaced put back, and the
+    /// coercion error is returned. A migration that is done or aborted is removed from the store.
+    pub fn migrate(
+        &mut self,
+        target: MigrationTarget,
+        name: &str,
+        batch_size: usize,
+    ) -> ZerodbResult<bool> {
+        let database = self.database().clone();
+        let key = migration_key(&database, target, name);
+        let Some(mut migration) = self.store().get_decoded::<Migration>(key.as_bytes())? else {
+            return Ok(true);
+        };
+
+        if !migration.checked {
+            self.check_batch(&mut migration, batch_size)?;
+            self.store_mut().put_encoded(key.as_bytes(), &migration)?;
+            return Ok(false);
+        }
+
+        // The records of a table are migrated before the edges that start or end at them.
+        let records = match migration.target {
+            MigrationTarget::Table => {
+                migration.migrate_records(self.store_mut(), &database, batch_size)?
+            }
+            MigrationTarget::Edge => 0,
+        };
+
+        let remaining = batch_size - records;
+        let batch = self.migration_batch(&migration, remaining)?;
+        for edge in batch.iter() {
+            let migrated = migration.migrate_edge(edge)?;
+            let unique = unique_fields(self.store(), &database, edge.id(), edge.fields())?
+                .into_iter()
+                .map(|field| match migration.target {
+                    MigrationTarget::Table => field,
+                    MigrationTarget::Edge => migration.new_field_name(&field).to_string(),
+                })
+                .collect::<Vec<_>>();
+
+            if migrated.id() != edge.id() {
+                self.delete_edge(edge.id())?;
+            }
+
+            self.insert_edge(&migrated)?;
+            claim_unique(
+                self.store_mut(),
+                &database,
+                migrated.id(),
+                migrated.fields(),
+                &unique,
+            )?;
+            migration.cursor = Some(edge.id().id().to_string());
+        }
+
+        if batch.len() < remaining {
+            self.store_mut().delete(key.as_bytes())?;
+            return Ok(true);
+        }
+
+        self.store_mut().put_encoded(key.as_bytes(), &migration)?;
+        Ok(false)
+    }
+
+    /// Gets the migrations of the database of the graph that are not done yet.
+    pub fn migrations(&self) -> ZerodbResult<Vec<Migration>> {
+        Migration::pending(self.store(), self.database())
+    }
+
+    /// Checks that up to `batch_size` more of the records or edges of a migration can be coerced
+    /// to their new shape, aborting the migration if one cannot.
+    ///
+    /// The migration is marked as checked once there are no more to check.
+    fn check_batch(&mut self, migration: &mut Migration, batch_size: usize) -> ZerodbResult<()> {
+        let batch = match migration.target {
+            MigrationTarget::Table => {
+                migration.record_batch(self.store(), self.database(), batch_size)?
+            }
+            MigrationTarget::Edge => self
+                .migration_batch(migration, batch_size)?
+                .into_iter()
+                .map(|edge| (edge.id().clone(), edge.fields().clone()))
+                .collect(),
+        };
+
+        for (id, fields) in batch.iter() {
+            if let Err(error) = migration.migrate_fields(fields.clone()) {
+                self.abort(migration)?;
+                return Err(error);
+            }
+
+            migration.cursor = Some(id.id().to_string());
+        }
+
+        if batch.len() < batch_size {
+            migration.checked = true;
+            migration.cursor = None;
+        }
+
+        Ok(())
+    }
+
+    /// Aborts a migration before it has moved anything, putting back the definition it replaced
+    /// and removing it from the store.
+    fn abort(&mut self, migration: &Migration) -> ZerodbResult<()> {
+        if let Some(undo) = &migration.undo {
+            Catalog::new().apply_schema_change(self.store_mut(), undo)?;
+        }
+
+        let key = migration_key(self.database(), migration.target, &migration.name);
+        self.store_mut().delete(key.as_bytes())
+    }
+
+    /// Gets the next edges a migration checks or moves.
+    fn migration_batch(&self, migration: &Migration, batch_size: usize) -> ZerodbResult<Vec<Edge>> {
+        match migration.target {
+            // The edges of a table that keeps its name stay where they are.
+            MigrationTarget::Table if !migration.renames() => Ok(vec![]),
+            // Edges that are moved no longer start or end at the table, so the next batch is
+            // always at the front.
+            MigrationTarget::Table => {
+                let mut ids = Vec::<RecordId>::new();
+                for direction in ["o", "i"] {
+                    let prefix = graph_key(self.database())
+                        .with(direction)
+                        .with(&migration.name);
+                    for (key, _) in self.store().scan_prefix(prefix.as_bytes())? {
+                        if let [_, _, _, _, _, kind, _, _, edge_id] =
+                            Key::decode_str(&key).as_slice()
+                        {
+                            let id = RecordId::new(kind, edge_id);
+                            if !ids.contains(&id) {
+                                ids.push(id);
+                            }
+                        }
+                    }
+                }
+
+                ids.into_iter()
+                    .take(batch_size)
+                    .filter_map(|id| self.edge(&id).transpose())
+                    .collect()
+            }
+            // Edges are checked and moved in id order, so the ones already checked or moved in
+            // place are skipped with the cursor, and renamed ones leave the kind they are read
+            // from.
+            MigrationTarget::Edge => Ok(self
+                .edges_of_kind(&migration.name)?
+                .into_iter()
+                .filter(|edge| match &migration.cursor {
+                    Some(cursor) => edge.id().id() > cursor.as_str(),
+                    None => true,
+                })
+                .take(batch_size)
+                .collect()),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use zeroql::parser::Parser;
+
+    use zeroql::sema::SchemaMeta;
+    use zeroutils_path::Path;
+
+    use crate::{catalog::SchemaEntry, graph::Direction, store::MemoryKvStore, Value, ZerodbError};
+
+    use super::*;
+
+    fn stmt(input: &str) -> anyhow::Result<Ast<'_>> {
+        Ok(Parser::new(input, 20).parse_redefine_stmt()?.unwrap())
+    }
+
+    fn define(graph: &mut Graph<MemoryKvStore>, input: &str) -> anyhow::Result<()> {
+        let catalog = Catalog::new();
+        let ast = Parser::new(input, 50).parse_program().into_result()?;
+        for statement in ast.kind.unwrap_program() {
+            let change = catalog.schema_change(graph.store(), &statement, &Path::default())?;
+            catalog.apply_schema_change(graph.store_mut(), &change.unwrap())?;
+        }
+
+        Ok(())
+    }
+
+    fn relate_follows(graph: &mut Graph<MemoryKvStore>, count: usize) -> anyhow::Result<()> {
+        for i in 0..count {
+            graph.insert_edge(&Edge::new(
+                RecordId::new("follows", format!("e{i}")),
+                RecordId::new("person", format!("p{i}")),
+                RecordId::new("person", "alice"),
+                BTreeMap::from([("since".to_string(), Value::Int(2000 + i as i64))]),
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_migrate_edge() -> anyhow::Result<()> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+        define(&mut graph, "DEFINE EDGE follows FIELDS since TYPE u64")?;
+        relate_follows(&mut graph, 5)?;
+
+        let ast = stmt("REDEFINE EDGE follows AS knows FIELDS since AS known_since TYPE string")?;
+        graph.execute_redefine(&ast)?;
+
+        let root = Path::default();
+        assert!(Catalog::new().edge_defined(graph.store(), &root, "knows")?);
+        assert!(!Catalog::new().edge_defined(graph.store(), &root, "follows")?);
+
+        // The migration checks every edge before it moves any, then runs a batch at a time and
+        // survives being picked up again.
+        assert!(!graph.migrate(MigrationTarget::Edge, "follows", 10)?);
+        assert!(!graph.migrate(MigrationTarget::Edge, "follows", 2)?);
+        assert_eq!(graph.migrations()?.len(), 1);
+        assert_eq!(graph.edges_of_kind("knows")?.len(), 2);
+        assert_eq!(graph.edges_of_kind("follows")?.len(), 3);
+
+        while !graph.migrate(MigrationTarget::Edge, "follows", 2)? {}
+        assert!(graph.migrations()?.is_empty());
+        assert!(graph.edges_of_kind("follows")?.is_empty());
+
+        let edge = graph.edge(&RecordId::new("knows", "e1"))?.unwrap();
+        assert_eq!(
+            edge.fields(),
+            &BTreeMap::from([("known_since".to_string(), Value::String("2001".to_string()))])
+        );
+
+        let alice = RecordId::new("person", "alice");
+        let adjacent = graph.adjacent(&alice, Direction::In, Some("knows"))?;
+        assert_eq!(adjacent.len(), 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_migrate_edge_in_place() -> anyhow::Result<()> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+        define(&mut graph, "DEFINE EDGE follows FIELDS since TYPE u64")?;
+        relate_follows(&mut graph, 3)?;
+
+        graph.execute_redefine(&stmt("REDEFINE EDGE follows FIELDS since TYPE f64")?)?;
+        while !graph.migrate(MigrationTarget::Edge, "follows", 2)? {}
+
+        for edge in graph.edges_of_kind("follows")? {
+            assert!(matches!(edge.field("since"), Some(Value::Float(_))));
+        }
+
+        // Values that cannot be coerced abort the migration and put the definition back.
+        let catalog = Catalog::new();
+        let path = "follows".parse()?;
+        let before = catalog.schema(graph.store(), &path)?.unwrap();
+
+        graph.execute_redefine(&stmt("REDEFINE EDGE follows FIELDS since TYPE u8")?)?;
+        assert!(matches!(
+            graph.migrate(MigrationTarget::Edge, "follows", 10),
+            Err(ZerodbError::InvalidCoercion { .. })
+        ));
+        assert!(graph.migrations()?.is_empty());
+        assert_eq!(
+            catalog.schema(graph.store(), &path)?.unwrap().meta,
+            before.meta
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_migrate_abort() -> anyhow::Result<()> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+        define(&mut graph, "DEFINE EDGE follows FIELDS since TYPE u64")?;
+        relate_follows(&mut graph, 5)?;
+
+        // The edge that cannot be coerced comes after the first batch is checked.
+        graph.insert_edge(&Edge::new(
+            RecordId::new("follows", "e9"),
+            RecordId::new("person", "bob"),
+            RecordId::new("person", "alice"),
+            BTreeMap::from([("since".to_string(), Value::String("soon".to_string()))]),
+        ))?;
+
+        graph.execute_redefine(&stmt(
+            "REDEFINE EDGE follows AS knows FIELDS since TYPE u32",
+        )?)?;
+        assert!(!graph.migrate(MigrationTarget::Edge, "follows", 2)?);
+        assert!(!graph.migrate(MigrationTarget::Edge, "follows", 2)?);
+        assert!(matches!(
+            graph.migrate(MigrationTarget::Edge, "follows", 2),
+            Err(ZerodbError::InvalidCoercion { .. })
+        ));
+
+        // Nothing was moved, and the edge is defined under its old name again.
+        let root = Path::default();
+        assert!(Catalog::new().edge_defined(graph.store(), &root, "follows")?);
+        assert!(!Catalog::new().edge_defined(graph.store(), &root, "knows")?);
+        assert_eq!(graph.edges_of_kind("follows")?.len(), 6);
+        assert!(graph.edges_of_kind("knows")?.is_empty());
+        assert!(graph.migrations()?.is_empty());
+        assert!(graph.migrate(MigrationTarget::Edge, "follows", 2)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_migrate_table() -> anyhow::Result<()> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+        define(&mut graph, "DEFINE TABLE person FIELDS name TYPE string")?;
+        relate_follows(&mut graph, 3)?;
+
+        let ast = Parser::new("RELATE person ->> is_a ->> animal", 20)
+            .parse_exp()?
+            .unwrap();
+        let database = graph.database().clone();
+        Catalog::new().execute_relate(graph.store_mut(), &database, &ast)?;
+
+        graph.execute_redefine(&stmt("REDEFINE TABLE person AS people")?)?;
+        while !graph.migrate(MigrationTarget::Table, "person", 2)? {}
+
+        // The definition and the table relations move to the new name along with the records.
+        let catalog = Catalog::new();
+        assert!(catalog.schema(graph.store(), &"person".parse()?)?.is_none());
+        assert!(matches!(
+            catalog.schema(graph.store(), &"people".parse()?)?,
+            Some(SchemaEntry {
+                meta: SchemaMeta::Table { .. },
+                ..
+            })
+        ));
+        assert_eq!(
+            catalog.subtables(graph.store(), graph.database(), "animal")?,
+            ["animal", "people"]
+        );
+
+        let edge = graph.edge(&RecordId::new("follows", "e2"))?.unwrap();
+        assert_eq!(edge.from(), &RecordId::new("people", "p2"));
+        assert_eq!(edge.to(), &RecordId::new("people", "alice"));
+        assert!(graph.nodes(Direction::Out, Some("person"))?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_redefine_undefined() -> anyhow::Result<()> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+
+        assert!(matches!(
+            graph.execute_redefine(&stmt("REDEFINE TABLE person AS people")?),
+            Err(ZerodbError::UndefinedSchemaItem(_))
+        ));
+        graph.execute_redefine(&stmt("REDEFINE TABLE IF EXISTS person AS people")?)?;
+        assert!(Catalog::new().schemas(graph.store())?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+//! Graph storage and traversal for the edges created by `RELATE`.
+//!
+//! Edges are records with their own fields, and each edge is indexed in the adjacency lists of both
+//! of its ends, so following `->` and `<-` from a record are both prefix scans.
+//!
+//! ```txt
+//! RELATE person:alice -> buys -> product:apple SET { quantity: 2 }
+//!
+//! person:alice -> likes OR plays -> person
+//! bird -> is_a[..] -> thing
+//!
+//! DELETE person:alice -> buys -> *
+//!
+//! graph::shortest_path(person:alice, person:dave, knows[0..3])
+//! ```
+
+mod edge;
+mod functions;
+#[allow(clippy::module_inception)]
+mod graph;
+mod migration;
+mod statement;
+mod traversal;
+
+//--------------------------------------------------------------------------------------------------
+// Exports
+//--------------------------------------------------------------------------------------------------
+
+pub use edge::*;
+pub use functions::*;
+pub use graph::*;
+pub use statement::*;
+pub use traversal::*;
@@ -0,0 +1,414 @@
+use std::collections::BTreeMap;
+
+use uuid::Uuid;
+use zeroql::ast::{Ast, AstKind, RelateArrow};
+
+use crate::{
+    field_name, record_id_from_ast,
+    store::KvStore,
+    table::{FieldRules, UniqueClaims},
+    RecordId, ZerodbError, ZerodbResult,
+};
+
+use super::{Direction, Edge, Graph};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `EdgePattern` is a single `from -> kind -> to` hop of a relate operation, with `<-` arrows
+/// already turned around so that `from` is always where the edge starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgePattern {
+    /// The records the edge starts from.
+    pub from: Vec<NodePattern>,
+
+    /// The type of the edge.
+    pub kind: String,
+
+    /// The records the edge points to.
+    pub to: Vec<NodePattern>,
+}
+
+/// `NodePattern` is one end of an [`EdgePattern`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodePattern {
+    /// A specific record, e.g. `person:alice`.
+    Record(RecordId),
+
+    /// Any record of a table, e.g. `person` or `person:*`.
+    Table(String),
+
+    /// Any record at all, i.e. `*`.
+    Any,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl<S> Graph<S>
+where
+    S: KvStore,
+{
+    /// Executes a `RELATE` expression, creating an edge between every pair of records it names.
+    ///
+    /// The fields of the edges are checked against the `DEFINE EDGE` of their type, and if any
+    /// edge fails, none is created.
+    ///
+    /// ```txt
+    /// RELATE person:alice -> buys -> [product:apple, product:pear] SET { quantity: 2 }
+    /// ```
+    pub fn execute_relate(&mut self, ast: &Ast) -> ZerodbResult<Vec<Edge>> {
+        let AstKind::Relate {
+            relate_op,
+            columns,
+            value,
+        } = &ast.kind
+        else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let pattern = EdgePattern::from_ast(relate_op)?;
+        let rules = FieldRules::load(self.store(), self.database(), &pattern.kind)?;
+        let fields = columns
+            .iter()
+            .zip(value)
+            .map(|(column, value)| Ok((field_name(column)?, rules.scope().evaluate(value)?)))
+            .collect::<ZerodbResult<BTreeMap<_, _>>>()?;
+
+        let from = pattern.records(&pattern.from)?;
+        let to = pattern.records(&pattern.to)?;
+
+        // Every edge is checked against the fields of its edge type before any is stored.
+        let mut claims = UniqueClaims::new();
+        let mut edges = vec![];
+        for from in from.iter() {
+            for to in to.iter() {
+                let id = RecordId::new(&pattern.kind, Uuid::new_v4().simple().to_string());
+                let fields = rules.apply(None, fields.clone())?;
+                rules.check_unique(self.store(), &id, &fields, &mut claims)?;
+                edges.push(Edge::new(id, from.clone(), to.clone(), fields));
+            }
+        }
+
+        for edge in edges.iter() {
+            self.insert_edge(edge)?;
+        }
+
+        claims.write(self.store_mut())?;
+
+        Ok(edges)
+    }
+
+    /// Executes a `DELETE` expression whose target is a relate operation, deleting the matching
+    /// edges and returning them.
+    ///
+    /// ```txt
+    /// DELETE person:alice -> buys -> *
+    /// ```
+    pub fn execute_delete(&mut self, ast: &Ast) -> ZerodbResult<Vec<Edge>> {
+        let AstKind::Delete {
+            target,
+            where_guard,
+        } = &ast.kind
+        else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        if where_guard.is_some() {
+            return Err(ZerodbError::InvalidGraphOperation(
+                "WHERE is not supported when deleting edges".to_string(),
+            ));
+        }
+
+        let pattern = EdgePattern::from_ast(target)?;
+        let matching = self.matching_edges(&pattern)?;
+
+        let mut deleted = vec![];
+        for id in matching {
+            if let Some(edge) = self.delete_edge(&id)? {
+                deleted.push(edge);
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Gets the ids of the edges that match a pattern.
+    ///
+    /// The adjacency lists of whichever end names specific records are used, and only when neither
+    /// does are all the edges of the pattern's type scanned.
+    fn matching_edges(&self, pattern: &EdgePattern) -> ZerodbResult<Vec<RecordId>> {
+        let anchored = |nodes: &[NodePattern]| {
+            nodes
+                .iter()
+                .all(|node| matches!(node, NodePattern::Record(_)))
+        };
+
+        let (anchors, others, direction) = if anchored(&pattern.from) {
+            (&pattern.from, &pattern.to, Direction::Out)
+        } else if anchored(&pattern.to) {
+            (&pattern.to, &pattern.from, Direction::In)
+        } else {
+            return Ok(self
+                .edges_of_kind(&pattern.kind)?
+                .into_iter()
+                .filter(|edge| {
+                    NodePattern::any_matches(&pattern.from, edge.from())
+                        && NodePattern::any_matches(&pattern.to, edge.to())
+                })
+                .map(|edge| edge.id().clone())
+                .collect());
+        };
+
+        let mut ids = vec![];
+        for anchor in anchors {
+            let NodePattern::Record(anchor) = anchor else {
+                continue;
+            };
+
+            for adjacent in self.adjacent(anchor, direction, Some(&pattern.kind))? {
+                if NodePattern::any_matches(others, &adjacent.node) && !ids.contains(&adjacent.edge)
+                {
+                    ids.push(adjacent.edge);
+                }
+            }
+        }
+
+        Ok(ids)
+    }
+}
+
+impl EdgePattern {
+    /// Creates a pattern from a single hop `RelateOp` node.
+    pub fn from_ast(ast: &Ast) -> ZerodbResult<Self> {
+        let AstKind::RelateOp {
+            left,
+            l_op,
+            edge,
+            r_op,
+            right,
+        } = &ast.kind
+        else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        if matches!(left.kind, AstKind::RelateOp { .. }) {
+            return Err(ZerodbError::InvalidGraphOperation(
+                "only a single edge can be written at a time".to_string(),
+            ));
+        }
+
+        let kind = match &edge.kind {
+            AstKind::RelateEdgeId {
+                subject,
+                depth: None,
+                ..
+            } => match subject.kind {
+                AstKind::Identifier(kind) => kind.to_string(),
+                _ => return Err(ZerodbError::UnsupportedExpression(subject.kind.to_string())),
+            },
+            _ => {
+                return Err(ZerodbError::InvalidGraphOperation(format!(
+                    "expected a single edge type, found: {}",
+                    edge.kind
+                )))
+            }
+        };
+
+        let left = NodePattern::from_ast_list(left)?;
+        let right = NodePattern::from_ast_list(right)?;
+
+        match (l_op, r_op) {
+            (RelateArrow::Right, RelateArrow::Right) => Ok(Self {
+                from: left,
+                kind,
+                to: right,
+            }),
+            (RelateArrow::Left, RelateArrow::Left) => Ok(Self {
+                from: right,
+                kind,
+                to: left,
+            }),
+            _ => Err(ZerodbError::InvalidGraphOperation(
+                "an edge must be written with matching `->` or `<-` arrows".to_string(),
+            )),
+        }
+    }
+
+    fn records(&self, nodes: &[NodePattern]) -> ZerodbResult<Vec<RecordId>> {
+        nodes
+            .iter()
+            .map(|node| match node {
+                NodePattern::Record(record) => Ok(record.clone()),
+                _ => Err(ZerodbError::InvalidGraphOperation(format!(
+                    "`{}` edges can only be created between specific records",
+                    self.kind
+                ))),
+            })
+            .collect()
+    }
+}
+
+impl NodePattern {
+    /// Creates the node patterns of one end of a relate operation, which may be a list.
+    pub fn from_ast_list(ast: &Ast) -> ZerodbResult<Vec<Self>> {
+        match &ast.kind {
+            AstKind::ListLiteral(items) => items.iter().map(Self::from_ast).collect(),
+            _ => Ok(vec![Self::from_ast(ast)?]),
+        }
+    }
+
+    /// Creates a node pattern from a relate id.
+    pub fn from_ast(ast: &Ast) -> ZerodbResult<Self> {
+        match &ast.kind {
+            AstKind::SingleRelateId { subject, .. } => Self::from_ast(subject),
+            AstKind::Wildcard => Ok(NodePattern::Any),
+            AstKind::Identifier(table) => Ok(NodePattern::Table(table.to_string())),
+            AstKind::IdOp(table, id) => match (&table.kind, &id.kind) {
+                (AstKind::Identifier(table), AstKind::Wildcard) => {
+                    Ok(NodePattern::Table(table.to_string()))
+                }
+                _ => Ok(NodePattern::Record(record_id_from_ast(ast)?)),
+            },
+            _ => Err(ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+        }
+    }
+
+    /// Checks if `record` matches the pattern.
+    pub fn matches(&self, record: &RecordId) -> bool {
+        match self {
+            NodePattern::Record(expected) => expected == record,
+            NodePattern::Table(table) => record.table() == table,
+            NodePattern::Any => true,
+        }
+    }
+
+    /// Checks if `record` matches any of the patterns.
+    pub fn any_matches(patterns: &[Self], record: &RecordId) -> bool {
+        patterns.iter().any(|pattern| pattern.matches(record))
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::parser::Parser;
+    use zeroutils_path::Path;
+
+    use crate::{catalog::Catalog, store::MemoryKvStore, Value};
+
+    use super::*;
+
+    fn exp(input: &str) -> anyhow::Result<Ast<'_>> {
+        Ok(Parser::new(input, 20).parse_exp()?.unwrap())
+    }
+
+    #[test]
+    fn test_graph_execute_relate() -> anyhow::Result<()> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+        let alice = RecordId::new("person", "alice");
+
+        let edges = graph.execute_relate(&exp(
+            "RELATE person:alice -> buys -> [product:apple, product:pear] SET { quantity: 2 }",
+        )?)?;
+
+        assert_eq!(edges.len(), 2);
+        for edge in edges.iter() {
+            assert_eq!(edge.from(), &alice);
+            assert_eq!(edge.field("quantity"), Some(&Value::Int(2)));
+            assert_eq!(graph.edge(edge.id())?.as_ref(), Some(edge));
+        }
+
+        let edges = graph.execute_relate(&exp("RELATE product:apple <- likes <- person:bob")?)?;
+        assert_eq!(edges[0].from(), &RecordId::new("person", "bob"));
+        assert_eq!(edges[0].to(), &RecordId::new("product", "apple"));
+
+        assert!(graph
+            .execute_relate(&exp("RELATE person:alice -> buys -> product")?)
+            .is_err());
+        assert!(graph
+            .execute_relate(&exp("RELATE person:alice -> buys <- product:apple")?)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_execute_delete() -> anyhow::Result<()> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+        let alice = RecordId::new("person", "alice");
+        let bob = RecordId::new("person", "bob");
+
+        graph.execute_relate(&exp(
+            "RELATE [person:alice, person:bob] -> buys -> [product:apple, product:pear]",
+        )?)?;
+        graph.execute_relate(&exp("RELATE person:alice -> likes -> product:apple")?)?;
+
+        let deleted = graph.execute_delete(&exp("DELETE person:alice -> buys -> *")?)?;
+        assert_eq!(deleted.len(), 2);
+        assert!(deleted.iter().all(|edge| edge.from() == &alice));
+        assert!(graph
+            .adjacent(&alice, Direction::Out, Some("buys"))?
+            .is_empty());
+        assert_eq!(graph.adjacent(&alice, Direction::Out, None)?.len(), 1);
+
+        let deleted = graph.execute_delete(&exp("DELETE product:pear <- buys <- person")?)?;
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].from(), &bob);
+
+        let deleted = graph.execute_delete(&exp("DELETE person -> buys -> product:*")?)?;
+        assert_eq!(deleted.len(), 1);
+        assert!(graph.edges_of_kind("buys")?.is_empty());
+        assert_eq!(graph.edges_of_kind("likes")?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_execute_relate_field_rules() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let catalog = Catalog::new();
+        let define = Parser::new(
+            "DEFINE EDGE buys FIELDS quantity TYPE u8 VALUE 1 ASSERT $value > 0, receipt TYPE string UNIQUE",
+            50,
+        )
+        .parse_program()
+        .into_result()?;
+        let change =
+            catalog.schema_change(&store, &define.kind.unwrap_program()[0], &Path::default())?;
+        catalog.apply_schema_change(&mut store, &change.unwrap())?;
+
+        let mut graph = Graph::new(store);
+        let edges = graph.execute_relate(&exp(
+            "RELATE person:alice -> buys -> product:apple SET { receipt: 'r1' }",
+        )?)?;
+        assert_eq!(edges[0].field("quantity"), Some(&Value::Int(1)));
+
+        assert!(matches!(
+            graph.execute_relate(&exp("RELATE person:bob -> buys -> product:pear SET { quantity: 0 }")?),
+            Err(ZerodbError::FieldAssertion { field, .. }) if field == "quantity"
+        ));
+
+        // Unique values are checked across all the edges of the statement and are released when
+        // edges are deleted.
+        assert!(matches!(
+            graph.execute_relate(&exp(
+                "RELATE person:bob -> buys -> [product:apple, product:pear] SET { receipt: 'r2' }"
+            )?),
+            Err(ZerodbError::UniqueField { .. })
+        ));
+        assert_eq!(graph.edges_of_kind("buys")?.len(), 1);
+
+        graph.execute_delete(&exp("DELETE person:alice -> buys -> *")?)?;
+        graph.execute_relate(&exp(
+            "RELATE person:bob -> buys -> product:pear SET { receipt: 'r1' }",
+        )?)?;
+
+        Ok(())
+    }
+}
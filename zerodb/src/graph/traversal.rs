@@ -0,0 +1,694 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use zeroql::ast::{Ast, AstKind, RelateArrow};
+use zeroutils_path::Path;
+
+use crate::{catalog::Catalog, store::KvStore, RecordId, ZerodbError, ZerodbResult};
+
+use super::{Direction, Graph, NodePattern};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `TraversalOptions` are the limits a traversal runs under.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraversalOptions {
+    /// The most edges that may be followed out of a single record in a single step. A traversal
+    /// that reaches a record with more edges than this fails instead of expanding it.
+    pub max_fan_out: usize,
+}
+
+/// `PathPattern` is a compiled relate operation like `person:alice -> likes OR plays -> person`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathPattern {
+    /// The records the path starts from.
+    pub start: Vec<NodePattern>,
+
+    /// The hops of the path, in order.
+    pub hops: Vec<Hop>,
+}
+
+/// `Hop` is a single `-> edge ->` step of a [`PathPattern`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hop {
+    /// The direction edges are followed in.
+    pub direction: HopDirection,
+
+    /// The edges that are followed.
+    pub edge: EdgeExpr,
+
+    /// The records the hop may end on.
+    pub target: Vec<NodePattern>,
+}
+
+/// `HopDirection` is the direction of a [`Hop`], given by its pair of arrows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HopDirection {
+    /// `-> edge ->`
+    Out,
+
+    /// `<- edge <-`
+    In,
+
+    /// `-> edge <-` or `<- edge ->`, i.e. reflection. Reaches the records that are related to the
+    /// current record in both directions.
+    Both,
+}
+
+/// `EdgeExpr` is the edge expression of a [`Hop`].
+///
+/// It is evaluated from a single record to the set of records it reaches. `AND`, `OR` and `NOT`
+/// are the intersection, union and complement of those sets, where the complement is relative to
+/// every record one edge away.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdgeExpr {
+    /// Edges of a single type, followed for a range of levels, e.g. `is_a[0..2]`.
+    Kind {
+        /// The type of the edges.
+        kind: String,
+
+        /// The levels the edges are followed for.
+        depth: Depth,
+    },
+
+    /// `NOT expr`
+    Not(Box<EdgeExpr>),
+
+    /// `expr AND expr`
+    And(Box<EdgeExpr>, Box<EdgeExpr>),
+
+    /// `expr OR expr`
+    Or(Box<EdgeExpr>, Box<EdgeExpr>),
+}
+
+/// `Depth` is the range of levels an edge is followed for.
+///
+/// Level 0 is one edge away, level 1 is two edges away, and so on. So `is_a[1]` reaches what
+/// `is_a -> * -> is_a` reaches, and `is_a[0..2]` reaches what either `is_a` or `is_a[1]` reaches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Depth {
+    /// The lowest level, inclusive.
+    pub min: usize,
+
+    /// The highest level, inclusive, or `None` if unbounded.
+    pub max: Option<usize>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl<S> Graph<S>
+where
+    S: KvStore,
+{
+    /// Runs a relate operation like `person:alice -> likes -> person` against the graph and
+    /// returns the records at the end of the path, deduplicated, in breadth-first order.
+    ///
+    /// Tables in the path also match the records of the tables that are a kind of them in the
+    /// catalog.
+    pub fn traverse(&self, ast: &Ast, options: &TraversalOptions) -> ZerodbResult<Vec<RecordId>> {
+        let pattern = PathPattern::from_ast(ast)?.with_subtables(
+            &Catalog::new(),
+            self.store(),
+            self.database(),
+        )?;
+        self.traverse_pattern(&pattern, options)
+    }
+
+    /// Runs a compiled path pattern against the graph.
+    pub fn traverse_pattern(
+        &self,
+        pattern: &PathPattern,
+        options: &TraversalOptions,
+    ) -> ZerodbResult<Vec<RecordId>> {
+        let mut current = self.start_nodes(pattern)?;
+
+        for hop in pattern.hops.iter() {
+            let mut next = vec![];
+            let mut seen = HashSet::new();
+
+            for node in current.iter() {
+                for reached in self.expand_hop(node, hop, options)? {
+                    if NodePattern::any_matches(&hop.target, &reached)
+                        && seen.insert(reached.clone())
+                    {
+                        next.push(reached);
+                    }
+                }
+            }
+
+            current = next;
+        }
+
+        Ok(current)
+    }
+
+    /// Gets the records a path can start from.
+    ///
+    /// Specific records are used as they are. Tables and `*` are resolved from the adjacency lists
+    /// of the first hop's direction, as only records with edges there can start a path.
+    fn start_nodes(&self, pattern: &PathPattern) -> ZerodbResult<Vec<RecordId>> {
+        let directions = match pattern.hops.first().map(|hop| hop.direction) {
+            Some(HopDirection::Out) => vec![Direction::Out],
+            Some(HopDirection::In) => vec![Direction::In],
+            Some(HopDirection::Both) | None => vec![Direction::Out, Direction::In],
+        };
+
+        let mut nodes = vec![];
+        let mut seen = HashSet::new();
+        for start in pattern.start.iter() {
+            let found = match start {
+                NodePattern::Record(record) => vec![record.clone()],
+                NodePattern::Table(table) => {
+                    let mut found = vec![];
+                    for direction in directions.iter() {
+                        found.extend(self.nodes(*direction, Some(table))?);
+                    }
+                    found
+                }
+                NodePattern::Any => {
+                    let mut found = vec![];
+                    for direction in directions.iter() {
+                        found.extend(self.nodes(*direction, None)?);
+                    }
+                    found
+                }
+            };
+
+            for node in found {
+                if seen.insert(node.clone()) {
+                    nodes.push(node);
+                }
+            }
+        }
+
+        Ok(nodes)
+    }
+
+    fn expand_hop(
+        &self,
+        node: &RecordId,
+        hop: &Hop,
+        options: &TraversalOptions,
+    ) -> ZerodbResult<BTreeSet<RecordId>> {
+        match hop.direction {
+            HopDirection::Out => self.expand_edge(node, &hop.edge, Direction::Out, options),
+            HopDirection::In => self.expand_edge(node, &hop.edge, Direction::In, options),
+            HopDirection::Both => {
+                let outgoing = self.expand_edge(node, &hop.edge, Direction::Out, options)?;
+                let incoming = self.expand_edge(node, &hop.edge, Direction::In, options)?;
+                Ok(outgoing.intersection(&incoming).cloned().collect())
+            }
+        }
+    }
+
+    fn expand_edge(
+        &self,
+        node: &RecordId,
+        edge: &EdgeExpr,
+        direction: Direction,
+        options: &TraversalOptions,
+    ) -> ZerodbResult<BTreeSet<RecordId>> {
+        match edge {
+            EdgeExpr::Kind { kind, depth } => {
+                self.expand_kind(node, kind, *depth, direction, options)
+            }
+            EdgeExpr::Or(a, b) => {
+                let mut reached = self.expand_edge(node, a, direction, options)?;
+                reached.extend(self.expand_edge(node, b, direction, options)?);
+                Ok(reached)
+            }
+            EdgeExpr::And(a, b) => {
+                let a = self.expand_edge(node, a, direction, options)?;
+                let b = self.expand_edge(node, b, direction, options)?;
+                Ok(a.intersection(&b).cloned().collect())
+            }
+            EdgeExpr::Not(expr) => {
+                let excluded = self.expand_edge(node, expr, direction, options)?;
+                Ok(self
+                    .adjacent_limited(node, direction, None, options)?
+                    .into_iter()
+                    .filter(|record| !excluded.contains(record))
+                    .collect())
+            }
+        }
+    }
+
+    /// Follows edges of a single type breadth-first for the levels in `depth`.
+    ///
+    /// Every record is expanded at most once per level below `depth.min`, and at most once from
+    /// there on, so cycles end the expansion even when the depth is unbounded.
+    fn expand_kind(
+        &self,
+        node: &RecordId,
+        kind: &str,
+        depth: Depth,
+        direction: Direction,
+        options: &TraversalOptions,
+    ) -> ZerodbResult<BTreeSet<RecordId>> {
+        let mut reached = BTreeSet::new();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([(node.clone(), 0)]);
+
+        while let Some((current, hops)) = queue.pop_front() {
+            if depth.max.is_some_and(|max| hops > max) {
+                continue;
+            }
+
+            for next in self.adjacent_limited(&current, direction, Some(kind), options)? {
+                let level = hops;
+                if level >= depth.min {
+                    reached.insert(next.clone());
+                }
+
+                if !visited.insert((next.clone(), (level + 1).min(depth.min))) {
+                    continue;
+                }
+
+                queue.push_back((next, hops + 1));
+            }
+        }
+
+        Ok(reached)
+    }
+
+    pub(super) fn adjacent_limited(
+        &self,
+        node: &RecordId,
+        direction: Direction,
+        kind: Option<&str>,
+        options: &TraversalOptions,
+    ) -> ZerodbResult<BTreeSet<RecordId>> {
+        let adjacent = self.adjacent(node, direction, kind)?;
+        if adjacent.len() > options.max_fan_out {
+            return Err(ZerodbError::FanOutExceeded {
+                record: node.to_string(),
+                limit: options.max_fan_out,
+            });
+        }
+
+        Ok(adjacent.into_iter().map(|adjacent| adjacent.node).collect())
+    }
+}
+
+impl PathPattern {
+    /// Compiles a relate operation into a path pattern.
+    pub fn from_ast(ast: &Ast) -> ZerodbResult<Self> {
+        let AstKind::RelateOp {
+            left,
+            l_op,
+            edge,
+            r_op,
+            right,
+        } = &ast.kind
+        else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let mut pattern = match &left.kind {
+            AstKind::RelateOp { .. } => PathPattern::from_ast(left)?,
+            _ => PathPattern {
+                start: NodePattern::from_ast_list(left)?,
+                hops: vec![],
+            },
+        };
+
+        let direction = match (l_op, r_op) {
+            (RelateArrow::Right, RelateArrow::Right) => HopDirection::Out,
+            (RelateArrow::Left, RelateArrow::Left) => HopDirection::In,
+            (RelateArrow::Right, RelateArrow::Left) | (RelateArrow::Left, RelateArrow::Right) => {
+                HopDirection::Both
+            }
+            _ => {
+                return Err(ZerodbError::InvalidGraphOperation(
+                    "`->>` and `<<-` relate tables, which cannot be traversed".to_string(),
+                ))
+            }
+        };
+
+        pattern.hops.push(Hop {
+            direction,
+            edge: EdgeExpr::from_ast(edge)?,
+            target: NodePattern::from_ast_list(right)?,
+        });
+
+        Ok(pattern)
+    }
+
+    /// Expands every table in the pattern into the table followed by the tables of `database` that
+    /// are a kind of it in the catalog.
+    pub fn with_subtables(
+        &self,
+        catalog: &Catalog,
+        store: &impl KvStore,
+        database: &Path,
+    ) -> ZerodbResult<Self> {
+        let expand = |nodes: &[NodePattern]| -> ZerodbResult<Vec<NodePattern>> {
+            let mut expanded = vec![];
+            for node in nodes {
+                match node {
+                    NodePattern::Table(table) => expanded.extend(
+                        catalog
+                            .subtables(store, database, table)?
+                            .into_iter()
+                            .map(NodePattern::Table),
+                    ),
+                    node => expanded.push(node.clone()),
+                }
+            }
+
+            Ok(expanded)
+        };
+
+        Ok(Self {
+            start: expand(&self.start)?,
+            hops: self
+                .hops
+                .iter()
+                .map(|hop| {
+                    Ok(Hop {
+                        target: expand(&hop.target)?,
+                        ..hop.clone()
+                    })
+                })
+                .collect::<ZerodbResult<_>>()?,
+        })
+    }
+}
+
+impl EdgeExpr {
+    /// Compiles the edge expression of a relate operation.
+    pub fn from_ast(ast: &Ast) -> ZerodbResult<Self> {
+        match &ast.kind {
+            AstKind::RelateEdgeId { subject, depth, .. } => {
+                let AstKind::Identifier(kind) = subject.kind else {
+                    return Err(ZerodbError::UnsupportedExpression(subject.kind.to_string()));
+                };
+
+                let depth = match depth {
+                    Some(depth) => Depth::from_ast(depth)?,
+                    None => Depth::default(),
+                };
+
+                Ok(EdgeExpr::Kind {
+                    kind: kind.to_string(),
+                    depth,
+                })
+            }
+            AstKind::LogicalNotOp(expr) => Ok(EdgeExpr::Not(Box::new(Self::from_ast(expr)?))),
+            AstKind::LogicalAndOp(a, b) => Ok(EdgeExpr::And(
+                Box::new(Self::from_ast(a)?),
+                Box::new(Self::from_ast(b)?),
+            )),
+            AstKind::LogicalOrOp(a, b) => Ok(EdgeExpr::Or(
+                Box::new(Self::from_ast(a)?),
+                Box::new(Self::from_ast(b)?),
+            )),
+            _ => Err(ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+        }
+    }
+}
+
+impl Depth {
+    /// Compiles the depth of an edge, e.g. the `0..2` in `is_a[0..2]`.
+    pub fn from_ast(ast: &Ast) -> ZerodbResult<Self> {
+        let depth = match &ast.kind {
+            AstKind::IntegerLiteral(_) => {
+                let level = level(ast)?;
+                Depth {
+                    min: level,
+                    max: Some(level),
+                }
+            }
+            AstKind::RangeOp(start, end) => Depth {
+                min: level(start)?,
+                max: Some(level(end)?.checked_sub(1).ok_or_else(|| empty_depth(ast))?),
+            },
+            AstKind::RangeInclusiveOp(start, end) => Depth {
+                min: level(start)?,
+                max: Some(level(end)?),
+            },
+            AstKind::UnboundedRangeOp(start) => Depth {
+                min: match start {
+                    Some(start) => level(start)?,
+                    None => 0,
+                },
+                max: None,
+            },
+            _ => return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+        };
+
+        if depth.max.is_some_and(|max| max < depth.min) {
+            return Err(empty_depth(ast));
+        }
+
+        Ok(depth)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn level(ast: &Ast) -> ZerodbResult<usize> {
+    match ast.kind {
+        AstKind::IntegerLiteral(level) => usize::try_from(level)
+            .map_err(|_| ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+        _ => Err(ZerodbError::InvalidGraphOperation(format!(
+            "edge depth must be an integer, found: {}",
+            ast.kind
+        ))),
+    }
+}
+
+fn empty_depth(ast: &Ast) -> ZerodbError {
+    ZerodbError::InvalidGraphOperation(format!("edge depth is empty: {}", ast.kind))
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Default for TraversalOptions {
+    fn default() -> Self {
+        Self {
+            max_fan_out: 10_000,
+        }
+    }
+}
+
+impl Default for Depth {
+    fn default() -> Self {
+        Self {
+            min: 0,
+            max: Some(0),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use zeroql::parser::Parser;
+
+    use crate::{
+        catalog::{SchemaChange, TableRelation},
+        store::MemoryKvStore,
+    };
+
+    use super::*;
+
+    fn graph(edges: &[(&str, &str, &str)]) -> anyhow::Result<Graph<MemoryKvStore>> {
+        let mut graph = Graph::new(MemoryKvStore::new());
+        for (from, kind, to) in edges {
+            graph.relate(&from.parse()?, kind, &to.parse()?, BTreeMap::new())?;
+        }
+
+        Ok(graph)
+    }
+
+    fn traverse(graph: &Graph<MemoryKvStore>, input: &str) -> anyhow::Result<Vec<String>> {
+        let ast = Parser::new(input, 20).parse_op()?.unwrap();
+        let mut records = graph
+            .traverse(&ast, &TraversalOptions::default())?
+            .into_iter()
+            .map(|record| record.to_string())
+            .collect::<Vec<_>>();
+
+        records.sort();
+        Ok(records)
+    }
+
+    #[test]
+    fn test_graph_traverse_directions() -> anyhow::Result<()> {
+        let graph = graph(&[
+            ("person:alice", "likes", "person:bob"),
+            ("person:bob", "likes", "person:alice"),
+            ("person:alice", "likes", "person:carol"),
+            ("person:dave", "likes", "person:alice"),
+            ("person:carol", "likes", "animal:rex"),
+        ])?;
+
+        assert_eq!(
+            traverse(&graph, "person:alice -> likes -> *")?,
+            ["person:bob", "person:carol"]
+        );
+        assert_eq!(
+            traverse(&graph, "person:alice <- likes <- person")?,
+            ["person:bob", "person:dave"]
+        );
+        assert_eq!(
+            traverse(&graph, "person:alice -> likes <- person")?,
+            ["person:bob"]
+        );
+        assert_eq!(
+            traverse(&graph, "person:alice -> likes -> person -> likes -> animal")?,
+            ["animal:rex"]
+        );
+        assert_eq!(
+            traverse(&graph, "person -> likes -> animal:rex")?,
+            ["animal:rex"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_traverse_conjunctions() -> anyhow::Result<()> {
+        let graph = graph(&[
+            ("person:alice", "likes", "person:bob"),
+            ("person:alice", "plays", "person:bob"),
+            ("person:alice", "plays", "person:carol"),
+            ("person:alice", "knows", "person:dave"),
+        ])?;
+
+        assert_eq!(
+            traverse(&graph, "person:alice -> likes AND plays -> person")?,
+            ["person:bob"]
+        );
+        assert_eq!(
+            traverse(&graph, "person:alice -> likes OR plays -> person")?,
+            ["person:bob", "person:carol"]
+        );
+        assert_eq!(
+            traverse(&graph, "person:alice -> NOT likes -> person")?,
+            ["person:carol", "person:dave"]
+        );
+        assert_eq!(
+            traverse(&graph, "person:alice -> NOT likes AND NOT knows -> person")?,
+            ["person:carol"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_traverse_depth() -> anyhow::Result<()> {
+        let graph = graph(&[
+            ("class:bird", "is_a", "class:animal"),
+            ("class:animal", "is_a", "class:organism"),
+            ("class:organism", "is_a", "class:thing"),
+        ])?;
+
+        assert_eq!(
+            traverse(&graph, "class:bird -> is_a -> *")?,
+            ["class:animal"]
+        );
+        assert_eq!(
+            traverse(&graph, "class:bird -> is_a[1] -> *")?,
+            ["class:organism"]
+        );
+        assert_eq!(
+            traverse(&graph, "class:bird -> is_a[0..2] -> *")?,
+            ["class:animal", "class:organism"]
+        );
+        assert_eq!(
+            traverse(&graph, "class:bird -> is_a[1..=2] -> *")?,
+            ["class:organism", "class:thing"]
+        );
+        assert_eq!(
+            traverse(&graph, "class:bird -> is_a[..] -> class:thing")?,
+            ["class:thing"]
+        );
+        assert_eq!(
+            traverse(&graph, "class:thing <- is_a[1..] <- *")?,
+            ["class:animal", "class:bird"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_traverse_cycles_and_limits() -> anyhow::Result<()> {
+        let graph = graph(&[
+            ("node:a", "next", "node:b"),
+            ("node:b", "next", "node:c"),
+            ("node:c", "next", "node:a"),
+            ("node:a", "next", "node:d"),
+        ])?;
+
+        assert_eq!(
+            traverse(&graph, "node:a -> next[..] -> *")?,
+            ["node:a", "node:b", "node:c", "node:d"]
+        );
+        assert_eq!(
+            traverse(&graph, "node:a -> next[3] -> *")?,
+            ["node:b", "node:d"]
+        );
+        assert_eq!(
+            traverse(&graph, "node:b -> next[2..] -> *")?,
+            ["node:a", "node:b", "node:c", "node:d"]
+        );
+
+        let ast = Parser::new("node:a -> next -> *", 20).parse_op()?.unwrap();
+        let options = TraversalOptions { max_fan_out: 1 };
+        assert!(matches!(
+            graph.traverse(&ast, &options),
+            Err(ZerodbError::FanOutExceeded { limit: 1, .. })
+        ));
+
+        let ast = Parser::new("node:a -> next[2..1] -> *", 20)
+            .parse_op()?
+            .unwrap();
+        assert!(graph.traverse(&ast, &TraversalOptions::default()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_traverse_table_relations() -> anyhow::Result<()> {
+        let mut graph = graph(&[
+            ("driver:james", "owns", "car:tesla"),
+            ("person:alice", "owns", "toy:ball"),
+            ("person:alice", "owns", "pet:rex"),
+        ])?;
+
+        let relations = [("driver", "person"), ("car", "thing"), ("toy", "thing")]
+            .into_iter()
+            .map(|(child, parent)| TableRelation::new(child, "is_a", parent))
+            .collect();
+        let change = SchemaChange::Relate {
+            database: graph.database().clone(),
+            relations,
+        };
+        Catalog::new().apply_schema_change(graph.store_mut(), &change)?;
+
+        assert_eq!(
+            traverse(&graph, "person -> owns -> thing")?,
+            ["car:tesla", "toy:ball"]
+        );
+        assert_eq!(
+            traverse(&graph, "thing <- owns <- driver")?,
+            ["driver:james"]
+        );
+
+        Ok(())
+    }
+}
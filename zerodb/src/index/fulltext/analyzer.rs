@@ -0,0 +1,186 @@
+use std::collections::BTreeSet;
+
+use rust_stemmers::{Algorithm, Stemmer};
+use serde::{Deserialize, Serialize};
+
+use crate::ZerodbError;
+
+use super::Tokenizer;
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Analyzer` turns text into the terms that are stored in, and looked up from, a full-text index.
+///
+/// The text is first split by the [`Tokenizer`] and each token is then passed through the filters
+/// in order. A filter may rewrite a token or drop it entirely.
+///
+/// The same analyzer must be used for documents and queries so that their terms line up.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Analyzer {
+    /// The tokenizer that splits text into tokens.
+    tokenizer: Tokenizer,
+
+    /// The filters applied to each token.
+    filters: Vec<TokenFilter>,
+}
+
+/// `TokenFilter` is a single step in an [`Analyzer`] pipeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TokenFilter {
+    /// Converts tokens to lowercase.
+    Lowercase,
+
+    /// Drops tokens found in the stop word set.
+    StopWords(BTreeSet<String>),
+
+    /// Reduces tokens to their stem using the snowball algorithm for a language.
+    Stemmer(Algorithm),
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Analyzer {
+    /// Creates a new analyzer.
+    pub fn new(tokenizer: Tokenizer, filters: Vec<TokenFilter>) -> Self {
+        Self { tokenizer, filters }
+    }
+
+    /// Gets the tokenizer of the analyzer.
+    pub fn tokenizer(&self) -> Tokenizer {
+        self.tokenizer
+    }
+
+    /// Gets the filters of the analyzer.
+    pub fn filters(&self) -> &[TokenFilter] {
+        &self.filters
+    }
+
+    /// Analyzes `text` into a list of terms, keeping duplicates.
+    pub fn analyze(&self, text: &str) -> Vec<String> {
+        let stemmers = self
+            .filters
+            .iter()
+            .map(|filter| match filter {
+                TokenFilter::Stemmer(algorithm) => Some(Stemmer::create(*algorithm)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        self.tokenizer
+            .tokenize(text)
+            .into_iter()
+            .filter_map(|token| {
+                let mut term = token.to_string();
+                for (filter, stemmer) in self.filters.iter().zip(&stemmers) {
+                    match filter {
+                        TokenFilter::Lowercase => term = term.to_lowercase(),
+                        TokenFilter::StopWords(words) => {
+                            if words.contains(&term) {
+                                return None;
+                            }
+                        }
+                        TokenFilter::Stemmer(_) => {
+                            if let Some(stemmer) = stemmer {
+                                term = stemmer.stem(&term).into_owned();
+                            }
+                        }
+                    }
+                }
+
+                Some(term)
+            })
+            .collect()
+    }
+}
+
+impl TokenFilter {
+    /// Creates a stemmer filter for the given language.
+    pub fn stemmer(language: &str) -> Result<Self, ZerodbError> {
+        let algorithm = match language.to_lowercase().as_str() {
+            "arabic" => Algorithm::Arabic,
+            "danish" => Algorithm::Danish,
+            "dutch" => Algorithm::Dutch,
+            "english" => Algorithm::English,
+            "finnish" => Algorithm::Finnish,
+            "french" => Algorithm::French,
+            "german" => Algorithm::German,
+            "greek" => Algorithm::Greek,
+            "hungarian" => Algorithm::Hungarian,
+            "italian" => Algorithm::Italian,
+            "norwegian" => Algorithm::Norwegian,
+            "portuguese" => Algorithm::Portuguese,
+            "romanian" => Algorithm::Romanian,
+            "russian" => Algorithm::Russian,
+            "spanish" => Algorithm::Spanish,
+            "swedish" => Algorithm::Swedish,
+            "tamil" => Algorithm::Tamil,
+            "turkish" => Algorithm::Turkish,
+            _ => {
+                return Err(ZerodbError::InvalidIndexConfig(format!(
+                    "unsupported stemmer language: {language}"
+                )))
+            }
+        };
+
+        Ok(TokenFilter::Stemmer(algorithm))
+    }
+
+    /// Creates a stop word filter from the built-in list for the given language.
+    pub fn stop_words(language: &str) -> Result<Self, ZerodbError> {
+        match language.to_lowercase().as_str() {
+            "english" => Ok(TokenFilter::StopWords(
+                ENGLISH_STOP_WORDS.iter().map(|w| w.to_string()).collect(),
+            )),
+            _ => Err(ZerodbError::InvalidIndexConfig(format!(
+                "no built-in stop words for language: {language}"
+            ))),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The built-in English stop words.
+const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyzer_analyze() -> anyhow::Result<()> {
+        let analyzer = Analyzer::new(
+            Tokenizer::Simple,
+            vec![
+                TokenFilter::Lowercase,
+                TokenFilter::stop_words("english")?,
+                TokenFilter::stemmer("english")?,
+            ],
+        );
+
+        assert_eq!(
+            analyzer.analyze("The Quick foxes are Jumping over the lazy dogs"),
+            vec!["quick", "fox", "jump", "over", "lazi", "dog"]
+        );
+
+        // Stop words are compared after earlier filters have run.
+        let analyzer = Analyzer::new(Tokenizer::Simple, vec![TokenFilter::stop_words("english")?]);
+        assert_eq!(analyzer.analyze("The cat"), vec!["The", "cat"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Bm25` holds the parameters of the [Okapi BM25][bm25] ranking function.
+///
+/// [bm25]: https://en.wikipedia.org/wiki/Okapi_BM25
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bm25 {
+    /// Controls how quickly repeated occurrences of a term stop adding to the score.
+    pub k1: f64,
+
+    /// Controls how much the score is normalized by document length, from `0.0` (none) to `1.0`.
+    pub b: f64,
+}
+
+/// `CorpusStats` are the statistics of all the documents in an index that BM25 needs.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct CorpusStats {
+    /// The number of documents in the index.
+    pub doc_count: u64,
+
+    /// The sum of the lengths, in terms, of all the documents in the index.
+    pub total_length: u64,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Bm25 {
+    /// Creates new BM25 parameters.
+    pub fn new(k1: f64, b: f64) -> Self {
+        Self { k1, b }
+    }
+
+    /// Computes the inverse document frequency of a term that appears in `doc_freq` documents.
+    pub fn idf(&self, doc_freq: u64, stats: &CorpusStats) -> f64 {
+        let n = stats.doc_count as f64;
+        let df = doc_freq as f64;
+        (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+    }
+
+    /// Computes the contribution of a single term to the score of a document.
+    pub fn term_score(
+        &self,
+        term_freq: u32,
+        doc_freq: u64,
+        doc_length: u64,
+        stats: &CorpusStats,
+    ) -> f64 {
+        let tf = term_freq as f64;
+        let norm = 1.0 - self.b + self.b * (doc_length as f64 / stats.avg_length());
+        self.idf(doc_freq, stats) * (tf * (self.k1 + 1.0)) / (tf + self.k1 * norm)
+    }
+}
+
+impl CorpusStats {
+    /// Gets the average document length, which is never zero.
+    pub fn avg_length(&self) -> f64 {
+        if self.doc_count == 0 || self.total_length == 0 {
+            return 1.0;
+        }
+
+        self.total_length as f64 / self.doc_count as f64
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Default for Bm25 {
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bm25_term_score() {
+        let bm25 = Bm25::default();
+        let stats = CorpusStats {
+            doc_count: 10,
+            total_length: 100,
+        };
+
+        // Rare terms score higher than common ones.
+        assert!(bm25.term_score(1, 1, 10, &stats) > bm25.term_score(1, 5, 10, &stats));
+
+        // More occurrences score higher, but with diminishing returns.
+        let once = bm25.term_score(1, 1, 10, &stats);
+        let twice = bm25.term_score(2, 1, 10, &stats);
+        let thrice = bm25.term_score(3, 1, 10, &stats);
+        assert!(twice > once && thrice - twice < twice - once);
+
+        // Shorter documents score higher for the same term frequency.
+        assert!(bm25.term_score(1, 1, 5, &stats) > bm25.term_score(1, 1, 20, &stats));
+    }
+}
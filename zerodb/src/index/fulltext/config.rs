@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use zeroql::ast::{Ast, AstKind};
+
+use crate::{ZerodbError, ZerodbResult};
+
+use super::{Analyzer, Bm25, TokenFilter, Tokenizer};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `FulltextConfig` is the configuration of a full-text index.
+///
+/// It is built from the arguments of the `index::fulltext` function in a `DEFINE INDEX` statement.
+/// All arguments are optional and named.
+///
+/// ```txt
+/// DEFINE INDEX idx_bio ON TABLE person FIELDS bio WITH index::fulltext(
+///     tokenizer = "simple",
+///     lowercase = true,
+///     stopwords = "english",
+///     stemmer = "english",
+///     k1 = 1.2,
+///     b = 0.75
+/// )
+/// ```
+///
+/// `stopwords` may also be a list of words, e.g. `stopwords = ["a", "the"]`, which are lowercased
+/// along with the text unless `lowercase = false`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct FulltextConfig {
+    /// The analyzer used for both documents and queries.
+    pub analyzer: Analyzer,
+
+    /// The BM25 ranking parameters.
+    pub bm25: Bm25,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl FulltextConfig {
+    /// Creates a config from the arguments of an `index::fulltext` function call.
+    pub fn from_args(args: &[Ast]) -> ZerodbResult<Self> {
+        let mut tokenizer = Tokenizer::default();
+        let mut lowercase = true;
+        let mut stop_words = None;
+        let mut stemmer = None;
+        let mut bm25 = Bm25::default();
+
+        for arg in args {
+            let AstKind::FunctionArg {
+                name: Some(name),
+                value,
+            } = &arg.kind
+            else {
+                return Err(ZerodbError::InvalidIndexConfig(
+                    "index::fulltext only takes named arguments".to_string(),
+                ));
+            };
+
+            let AstKind::Identifier(name) = name.kind else {
+                return Err(ZerodbError::InvalidIndexConfig(format!(
+                    "invalid argument name: {}",
+                    name.kind
+                )));
+            };
+
+            match name {
+                "tokenizer" => tokenizer = expect_string(name, value)?.parse()?,
+                "lowercase" => lowercase = expect_bool(name, value)?,
+                "stemmer" => stemmer = Some(TokenFilter::stemmer(expect_string(name, value)?)?),
+                "stopwords" => {
+                    stop_words = Some(match &value.kind {
+                        AstKind::ListLiteral(words) => TokenFilter::StopWords(
+                            words
+                                .iter()
+                                .map(|word| expect_string(name, word).map(str::to_string))
+                                .collect::<ZerodbResult<_>>()?,
+                        ),
+                        _ => TokenFilter::stop_words(expect_string(name, value)?)?,
+                    })
+                }
+                "k1" => bm25.k1 = expect_number(name, value)?,
+                "b" => bm25.b = expect_number(name, value)?,
+                _ => {
+                    return Err(ZerodbError::InvalidIndexConfig(format!(
+                        "unknown index::fulltext argument: {name}"
+                    )))
+                }
+            }
+        }
+
+        // Stop words are matched after the tokens are lowercased, so listed ones must be too.
+        if let (true, Some(TokenFilter::StopWords(words))) = (lowercase, &mut stop_words) {
+            *words = words.iter().map(|word| word.to_lowercase()).collect();
+        }
+
+        let filters = [
+            lowercase.then_some(TokenFilter::Lowercase),
+            stop_words,
+            stemmer,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Ok(Self {
+            analyzer: Analyzer::new(tokenizer, filters),
+            bm25,
+        })
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn expect_string<'a>(name: &str, value: &Ast<'a>) -> ZerodbResult<&'a str> {
+    match value.kind {
+        AstKind::StringLiteral(string) => Ok(string),
+        _ => Err(ZerodbError::InvalidIndexConfig(format!(
+            "expected a string for `{name}`"
+        ))),
+    }
+}
+
+fn expect_bool(name: &str, value: &Ast) -> ZerodbResult<bool> {
+    match value.kind {
+        AstKind::BooleanLiteral(boolean) => Ok(boolean),
+        _ => Err(ZerodbError::InvalidIndexConfig(format!(
+            "expected a boolean for `{name}`"
+        ))),
+    }
+}
+
+fn expect_number(name: &str, value: &Ast) -> ZerodbResult<f64> {
+    match value.kind {
+        AstKind::FloatLiteral(float) => Ok(float),
+        AstKind::IntegerLiteral(integer) => Ok(integer as f64),
+        _ => Err(ZerodbError::InvalidIndexConfig(format!(
+            "expected a number for `{name}`"
+        ))),
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::{ast::EncodedAst, parser::Parser, sema::IndexKind};
+
+    use crate::index::fulltext_config;
+
+    use super::*;
+
+    #[test]
+    fn test_fulltext_config_from_define_index() -> anyhow::Result<()> {
+        let mut parser = Parser::new(
+            r#"DEFINE INDEX idx_bio ON TABLE person FIELDS bio WITH index::fulltext(tokenizer = "whitespace", stopwords = ["a", "The"], stemmer = "english", k1 = 2, b = 0.5)"#,
+            20,
+        );
+        let ast = parser.parse_define_index_stmt()?.unwrap();
+        let AstKind::DefineIndex { function, .. } = &ast.kind else {
+            panic!("expected DefineIndex");
+        };
+
+        let kind = IndexKind::Function(EncodedAst::encode(function.as_ref().unwrap())?);
+        let Some(config) = fulltext_config(&kind)? else {
+            panic!("expected a fulltext index");
+        };
+
+        assert_eq!(config.bm25, Bm25::new(2.0, 0.5));
+        assert_eq!(config.analyzer.tokenizer(), Tokenizer::Whitespace);
+        assert_eq!(
+            config.analyzer.filters(),
+            &[
+                TokenFilter::Lowercase,
+                TokenFilter::StopWords(["a".to_string(), "the".to_string()].into()),
+                TokenFilter::stemmer("english")?,
+            ]
+        );
+
+        assert_eq!(fulltext_config(&IndexKind::Standard)?, None);
+
+        // Listed stop words keep their case when the text does.
+        let ast = Parser::new(
+            r#"index::fulltext(lowercase = false, stopwords = ["The"])"#,
+            20,
+        )
+        .parse_op()?
+        .unwrap();
+        let Some(config) = fulltext_config(&IndexKind::Function(EncodedAst::encode(&ast)?))? else {
+            panic!("expected a fulltext index");
+        };
+        assert_eq!(
+            config.analyzer.filters(),
+            &[TokenFilter::StopWords(["The".to_string()].into())]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fulltext_config_invalid_args() -> anyhow::Result<()> {
+        for input in [
+            r#"DEFINE INDEX i ON TABLE t FIELDS f WITH index::fulltext("simple")"#,
+            r#"DEFINE INDEX i ON TABLE t FIELDS f WITH index::fulltext(tokenizer = "unknown")"#,
+            r#"DEFINE INDEX i ON TABLE t FIELDS f WITH index::fulltext(lowercase = "yes")"#,
+            r#"DEFINE INDEX i ON TABLE t FIELDS f WITH index::fulltext(colour = "red")"#,
+            r#"DEFINE INDEX i ON TABLE t FIELDS f WITH index::btree()"#,
+        ] {
+            let ast = Parser::new(input, 20).parse_define_index_stmt()?.unwrap();
+            let AstKind::DefineIndex { function, .. } = &ast.kind else {
+                panic!("expected DefineIndex");
+            };
+
+            let kind = IndexKind::Function(EncodedAst::encode(function.as_ref().unwrap())?);
+            assert!(fulltext_config(&kind).is_err());
+        }
+
+        Ok(())
+    }
+}
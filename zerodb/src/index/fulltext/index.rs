@@ -0,0 +1,442 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use zeroql::sema::{SchemaMeta, SchemaSource};
+use zeroutils_path::Path;
+
+use crate::{
+    catalog::Catalog,
+    index::fulltext_config,
+    store::{Key, KvStore},
+    table::table_key,
+    RecordId, Value, ZerodbError, ZerodbResult,
+};
+
+use super::{CorpusStats, FulltextConfig, SearchHits};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `FulltextIndex` is an inverted index over the text of a table field, ranked with BM25.
+///
+/// It is laid out in the key-value store as follows:
+///
+/// ```txt
+/// ft / <database> / <table> / <index> / s                 -> CorpusStats
+/// ft / <database> / <table> / <index> / d / <id>          -> DocEntry
+/// ft / <database> / <table> / <index> / t / <term> / <id> -> term frequency
+/// ```
+///
+/// The postings of a term are read with a single prefix scan, and the number of postings gives the
+/// document frequency of the term. The doc entry remembers the terms of a document so they can be
+/// removed when the document changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FulltextIndex {
+    /// The database the table belongs to.
+    database: Path,
+
+    /// The table the index belongs to.
+    table: String,
+
+    /// The name of the index.
+    name: String,
+
+    /// The path of the indexed field, e.g. `bio` or `profile.bio`.
+    field: String,
+
+    /// The configuration of the index.
+    config: FulltextConfig,
+}
+
+/// `DocEntry` is what a full-text index stores about each indexed document.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DocEntry {
+    /// The number of terms in the document.
+    pub length: u64,
+
+    /// The frequency of each term in the document.
+    pub terms: BTreeMap<String, u32>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl FulltextIndex {
+    /// Creates a new full-text index over a field of a table.
+    pub fn new(
+        table: impl Into<String>,
+        name: impl Into<String>,
+        field: impl Into<String>,
+        config: FulltextConfig,
+    ) -> Self {
+        Self {
+            database: Path::default(),
+            table: table.into(),
+            name: name.into(),
+            field: field.into(),
+            config,
+        }
+    }
+
+    /// Sets the database the table belongs to, whose entries are kept apart from the ones of
+    /// other databases.
+    pub fn with_database(mut self, database: Path) -> Self {
+        self.database = database;
+        self
+    }
+
+    /// Creates the full-text index that a `DEFINE INDEX` in `database` defines, or `None` if it
+    /// defines another kind of index.
+    ///
+    /// A full-text index covers a single field, so one with more columns is rejected.
+    pub fn from_schema(
+        database: &Path,
+        name: &str,
+        meta: &SchemaMeta,
+    ) -> ZerodbResult<Option<Self>> {
+        let SchemaMeta::Index {
+            table,
+            columns,
+            kind,
+        } = meta
+        else {
+            return Ok(None);
+        };
+
+        let Some(config) = fulltext_config(kind)? else {
+            return Ok(None);
+        };
+
+        let [field] = columns.as_slice() else {
+            return Err(ZerodbError::InvalidIndexConfig(format!(
+                "full-text index {name} must cover a single field, found {}",
+                columns.len()
+            )));
+        };
+
+        Ok(Some(
+            Self::new(table, name, field, config).with_database(database.clone()),
+        ))
+    }
+
+    /// Loads the full-text indexes defined on a table of `database` from the catalog.
+    pub fn load(store: &impl KvStore, database: &Path, table: &str) -> ZerodbResult<Vec<Self>> {
+        let mut indexes = vec![];
+        for (name, meta) in Catalog::new().schema_snapshot(store)?.items(database) {
+            if let Some(index) = Self::from_schema(database, &name, &meta)? {
+                if index.table == table {
+                    indexes.push(index);
+                }
+            }
+        }
+
+        Ok(indexes)
+    }
+
+    /// Gets the table the index belongs to.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Gets the name of the index.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the path of the indexed field.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Gets the configuration of the index.
+    pub fn config(&self) -> &FulltextConfig {
+        &self.config
+    }
+
+    /// Indexes the text of a record, replacing whatever was indexed for it before.
+    pub fn index_document(
+        &self,
+        store: &mut impl KvStore,
+        record: &RecordId,
+        text: &str,
+    ) -> ZerodbResult<()> {
+        self.remove_document(store, record)?;
+
+        let mut entry = DocEntry::default();
+        for term in self.config.analyzer.analyze(text) {
+            *entry.terms.entry(term).or_default() += 1;
+            entry.length += 1;
+        }
+
+        for (term, freq) in entry.terms.iter() {
+            store.put_encoded(self.posting_key(term, record.id()).as_bytes(), freq)?;
+        }
+
+        let mut stats = self.stats(store)?;
+        stats.doc_count += 1;
+        stats.total_length += entry.length;
+
+        store.put_encoded(self.doc_key(record.id()).as_bytes(), &entry)?;
+        store.put_encoded(self.stats_key().as_bytes(), &stats)?;
+
+        Ok(())
+    }
+
+    /// Indexes the text of a record as it is after a write, or removes the record from the index
+    /// if it was deleted or has no text in the indexed field.
+    ///
+    /// The text of a field is its string, or the strings of its list joined by spaces.
+    pub fn index_record(
+        &self,
+        store: &mut impl KvStore,
+        id: &RecordId,
+        record: Option<&BTreeMap<String, Value>>,
+    ) -> ZerodbResult<()> {
+        match record.and_then(|record| self.text(record)) {
+            Some(text) => self.index_document(store, id, &text),
+            None => self.remove_document(store, id),
+        }
+    }
+
+    /// Indexes every record of the table, replacing whatever the index held before.
+    pub fn build(&self, store: &mut impl KvStore) -> ZerodbResult<()> {
+        self.clear(store)?;
+
+        let prefix = table_key(&self.database, &self.table);
+        for (key, value) in store.scan_prefix(prefix.as_bytes())? {
+            let Some(id) = Key::decode_str(&key).pop() else {
+                continue;
+            };
+
+            let record: BTreeMap<String, Value> = cbor4ii::serde::from_slice(&value)?;
+            self.index_record(store, &RecordId::new(&self.table, id), Some(&record))?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes a record from the index. Does nothing if the record is not indexed.
+    pub fn remove_document(&self, store: &mut impl KvStore, record: &RecordId) -> ZerodbResult<()> {
+        let doc_key = self.doc_key(record.id());
+        let Some(entry) = store.get_decoded::<DocEntry>(doc_key.as_bytes())? else {
+            return Ok(());
+        };
+
+        for term in entry.terms.keys() {
+            store.delete(self.posting_key(term, record.id()).as_bytes())?;
+        }
+
+        let mut stats = self.stats(store)?;
+        stats.doc_count = stats.doc_count.saturating_sub(1);
+        stats.total_length = stats.total_length.saturating_sub(entry.length);
+
+        store.delete(doc_key.as_bytes())?;
+        store.put_encoded(self.stats_key().as_bytes(), &stats)?;
+
+        Ok(())
+    }
+
+    /// Searches the index for records matching any of the terms in `query`, scored with BM25.
+    pub fn search(&self, store: &impl KvStore, query: &str) -> ZerodbResult<SearchHits> {
+        let stats = self.stats(store)?;
+        let mut scores = HashMap::<String, f64>::new();
+        let mut lengths = HashMap::<String, u64>::new();
+
+        let mut terms = self.config.analyzer.analyze(query);
+        terms.sort();
+        terms.dedup();
+
+        for term in terms {
+            let postings = store.scan_prefix(self.term_prefix(&term).as_bytes())?;
+            let doc_freq = postings.len() as u64;
+
+            for (key, value) in postings {
+                let Some(id) = Key::decode_str(&key).pop() else {
+                    continue;
+                };
+
+                let term_freq: u32 = cbor4ii::serde::from_slice(&value)?;
+                let doc_length = match lengths.get(&id) {
+                    Some(length) => *length,
+                    None => {
+                        let length = store
+                            .get_decoded::<DocEntry>(self.doc_key(&id).as_bytes())?
+                            .map(|entry| entry.length)
+                            .unwrap_or_default();
+                        lengths.insert(id.clone(), length);
+                        length
+                    }
+                };
+
+                *scores.entry(id).or_default() += self
+                    .config
+                    .bm25
+                    .term_score(term_freq, doc_freq, doc_length, &stats);
+            }
+        }
+
+        Ok(scores
+            .into_iter()
+            .map(|(id, score)| (RecordId::new(&self.table, id), score))
+            .collect())
+    }
+
+    /// Gets the corpus statistics of the index.
+    pub fn stats(&self, store: &impl KvStore) -> ZerodbResult<CorpusStats> {
+        Ok(store
+            .get_decoded(self.stats_key().as_bytes())?
+            .unwrap_or_default())
+    }
+
+    /// Removes every entry of the index from the store.
+    pub fn clear(&self, store: &mut impl KvStore) -> ZerodbResult<()> {
+        store.delete_prefix(self.prefix().as_bytes())
+    }
+
+    fn text(&self, record: &BTreeMap<String, Value>) -> Option<String> {
+        let mut segments = self.field.split('.');
+        let mut value = record.get(segments.next()?)?;
+        for segment in segments {
+            let Value::Object(fields) = value else {
+                return None;
+            };
+
+            value = fields.get(segment)?;
+        }
+
+        match value {
+            Value::String(text) => Some(text.clone()),
+            Value::List(items) => Some(
+                items
+                    .iter()
+                    .filter_map(|item| match item {
+                        Value::String(text) => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            _ => None,
+        }
+    }
+
+    fn prefix(&self) -> Key {
+        Key::new()
+            .with("ft")
+            .with(self.database.to_string())
+            .with(&self.table)
+            .with(&self.name)
+    }
+
+    fn stats_key(&self) -> Key {
+        self.prefix().with("s")
+    }
+
+    fn doc_key(&self, id: &str) -> Key {
+        self.prefix().with("d").with(id)
+    }
+
+    fn term_prefix(&self, term: &str) -> Key {
+        self.prefix().with("t").with(term)
+    }
+
+    fn posting_key(&self, term: &str, id: &str) -> Key {
+        self.term_prefix(term).with(id)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        index::{Analyzer, TokenFilter, Tokenizer},
+        store::MemoryKvStore,
+    };
+
+    use super::*;
+
+    fn index() -> anyhow::Result<FulltextIndex> {
+        let config = FulltextConfig {
+            analyzer: Analyzer::new(
+                Tokenizer::Simple,
+                vec![
+                    TokenFilter::Lowercase,
+                    TokenFilter::stop_words("english")?,
+                    TokenFilter::stemmer("english")?,
+                ],
+            ),
+            ..Default::default()
+        };
+
+        Ok(FulltextIndex::new("post", "idx_body", "body", config))
+    }
+
+    #[test]
+    fn test_fulltext_index_search_ranking() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let index = index()?;
+
+        let rust = RecordId::new("post", "rust");
+        let cats = RecordId::new("post", "cats");
+        let both = RecordId::new("post", "both");
+
+        index.index_document(&mut store, &rust, "Rust databases are fast databases")?;
+        index.index_document(&mut store, &cats, "Cats sleep all day")?;
+        index.index_document(
+            &mut store,
+            &both,
+            "A database of cats, written in a weekend",
+        )?;
+
+        let hits = index.search(&store, "the Database")?;
+        assert_eq!(
+            hits.ranked()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>(),
+            vec![rust.clone(), both.clone()]
+        );
+
+        let hits = index.search(&store, "cats databases")?;
+        assert_eq!(hits.ranked()[0].0, both);
+        assert!(hits.contains(&cats));
+
+        assert!(index.search(&store, "the")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fulltext_index_reindex_and_remove() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let index = index()?;
+        let record = RecordId::new("post", "a");
+
+        index.index_document(&mut store, &record, "hello world")?;
+        index.index_document(&mut store, &record, "goodbye world")?;
+
+        assert!(index.search(&store, "hello")?.is_empty());
+        assert!(index.search(&store, "goodbye")?.contains(&record));
+        assert_eq!(
+            index.stats(&store)?,
+            CorpusStats {
+                doc_count: 1,
+                total_length: 2
+            }
+        );
+
+        index.remove_document(&mut store, &record)?;
+        assert!(index.search(&store, "world")?.is_empty());
+        assert_eq!(index.stats(&store)?, CorpusStats::default());
+
+        index.clear(&mut store)?;
+        assert!(store.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+//! Full-text search indexes.
+//!
+//! A full-text index is declared with the `index::fulltext` function and queried with the `<>`
+//! similarity operator. Matches are ranked with BM25 and the score of a matched record is exposed
+//! to queries through `search::score()`.
+//!
+//! ```txt
+//! DEFINE INDEX idx_bio ON TABLE person FIELDS bio WITH index::fulltext(stemmer = "english")
+//!
+//! SELECT name, search::score() AS score FROM person WHERE bio <> "distributed databases"
+//! ```
+
+mod analyzer;
+mod bm25;
+mod config;
+mod index;
+mod search;
+mod tokenizer;
+
+//--------------------------------------------------------------------------------------------------
+// Exports
+//--------------------------------------------------------------------------------------------------
+
+pub use analyzer::*;
+pub use bm25::*;
+pub use config::*;
+pub use index::*;
+pub use search::*;
+pub use tokenizer::*;
@@ -0,0 +1,214 @@
+use std::{collections::HashMap, convert::Infallible};
+
+use zeroql::ast::{walk_ast, Ast, AstKind, Visitor};
+
+use crate::RecordId;
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `SearchHits` are the records matched by a full-text search, each with its BM25 score.
+///
+/// While a query with a `<>` condition runs, its hits are what `search::score()` reads the score
+/// of the current record from.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchHits {
+    /// The score of each matched record.
+    scores: HashMap<RecordId, f64>,
+}
+
+/// `SimilarityPredicate` is a `<>` condition on a field, e.g. `bio <> "rust databases"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarityPredicate {
+    /// The path of the field being searched, e.g. `bio` or `profile.bio`.
+    pub field: String,
+
+    /// The text to search for.
+    pub query: String,
+}
+
+/// Collects the `<>` conditions of an expression.
+struct Predicates(Vec<SimilarityPredicate>);
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl SearchHits {
+    /// Gets the score of a record, if it matched.
+    pub fn score(&self, record: &RecordId) -> Option<f64> {
+        self.scores.get(record).copied()
+    }
+
+    /// Checks if a record matched.
+    pub fn contains(&self, record: &RecordId) -> bool {
+        self.scores.contains_key(record)
+    }
+
+    /// Gets the number of matched records.
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// Checks if no record matched.
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Gets the matched records ordered from the highest score to the lowest.
+    pub fn ranked(&self) -> Vec<(RecordId, f64)> {
+        let mut ranked = self
+            .scores
+            .iter()
+            .map(|(record, score)| (record.clone(), *score))
+            .collect::<Vec<_>>();
+
+        ranked.sort_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then_with(|| a_id.cmp(b_id)));
+        ranked
+    }
+}
+
+impl SimilarityPredicate {
+    /// Extracts the predicate from a `SimilarityOp` node.
+    ///
+    /// Returns `None` if the node is not a `<>` operation between a field path and a string.
+    pub fn from_ast(ast: &Ast) -> Option<Self> {
+        let AstKind::SimilarityOp(lhs, rhs) = &ast.kind else {
+            return None;
+        };
+
+        let AstKind::StringLiteral(query) = rhs.kind else {
+            return None;
+        };
+
+        Some(Self {
+            field: field_path(lhs)?,
+            query: query.to_string(),
+        })
+    }
+
+    /// Gets the `<>` conditions anywhere in an expression, e.g. in a `WHERE` guard.
+    pub fn collect(ast: &Ast) -> Vec<Self> {
+        let mut predicates = Predicates(vec![]);
+        let Ok(()) = predicates.visit_ast(ast);
+        predicates.0
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Checks if `ast` is a call to `search::score()`.
+pub fn is_search_score_call(ast: &Ast) -> bool {
+    let AstKind::FunctionCall { subject, args } = &ast.kind else {
+        return false;
+    };
+
+    let AstKind::ScopedIdentifier(segments) = &subject.kind else {
+        return false;
+    };
+
+    args.is_empty()
+        && matches!(
+            segments.as_slice(),
+            [
+                Ast {
+                    kind: AstKind::Identifier("search"),
+                    ..
+                },
+                Ast {
+                    kind: AstKind::Identifier("score"),
+                    ..
+                }
+            ]
+        )
+}
+
+fn field_path(ast: &Ast) -> Option<String> {
+    match &ast.kind {
+        AstKind::Identifier(name) => Some(name.to_string()),
+        AstKind::DotAccessOp { subject, field } => {
+            Some(format!("{}.{}", field_path(subject)?, field_path(field)?))
+        }
+        _ => None,
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl<'a> Visitor<'a> for Predicates {
+    type Error = Infallible;
+
+    fn visit_ast(&mut self, ast: &Ast<'a>) -> Result<(), Self::Error> {
+        if let Some(predicate) = SimilarityPredicate::from_ast(ast) {
+            self.0.push(predicate);
+        }
+
+        walk_ast(self, ast)
+    }
+}
+
+impl FromIterator<(RecordId, f64)> for SearchHits {
+    fn from_iter<T: IntoIterator<Item = (RecordId, f64)>>(iter: T) -> Self {
+        Self {
+            scores: iter.into_iter().collect(),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::parser::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_similarity_predicate_from_ast() -> anyhow::Result<()> {
+        let ast = Parser::new(r#"profile.bio <> "rust databases""#, 20)
+            .parse_op()?
+            .unwrap();
+
+        assert_eq!(
+            SimilarityPredicate::from_ast(&ast),
+            Some(SimilarityPredicate {
+                field: "profile.bio".to_string(),
+                query: "rust databases".to_string(),
+            })
+        );
+
+        let ast = Parser::new(r#"bio <> $query"#, 20).parse_op()?.unwrap();
+        assert_eq!(SimilarityPredicate::from_ast(&ast), None);
+
+        let ast = Parser::new(r#"age > 18 && (bio <> "rust" || title <> "db")"#, 20)
+            .parse_op()?
+            .unwrap();
+        assert_eq!(
+            SimilarityPredicate::collect(&ast)
+                .into_iter()
+                .map(|predicate| predicate.field)
+                .collect::<Vec<_>>(),
+            ["bio", "title"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_search_score_call() -> anyhow::Result<()> {
+        let ast = Parser::new("search::score()", 20).parse_op()?.unwrap();
+        assert!(is_search_score_call(&ast));
+
+        let ast = Parser::new("search::highlight()", 20).parse_op()?.unwrap();
+        assert!(!is_search_score_call(&ast));
+
+        Ok(())
+    }
+}
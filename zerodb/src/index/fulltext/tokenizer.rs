@@ -0,0 +1,78 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ZerodbError;
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Tokenizer` splits text into the raw tokens that analyzers work on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Tokenizer {
+    /// Splits on whitespace only, so punctuation stays attached to words.
+    Whitespace,
+
+    /// Splits on any character that is not alphanumeric.
+    #[default]
+    Simple,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Tokenizer {
+    /// Splits `text` into tokens.
+    pub fn tokenize<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        match self {
+            Tokenizer::Whitespace => text.split_whitespace().collect(),
+            Tokenizer::Simple => text
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|token| !token.is_empty())
+                .collect(),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl FromStr for Tokenizer {
+    type Err = ZerodbError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "whitespace" | "blank" => Ok(Tokenizer::Whitespace),
+            "simple" => Ok(Tokenizer::Simple),
+            _ => Err(ZerodbError::InvalidIndexConfig(format!(
+                "unknown tokenizer: {s}"
+            ))),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenizer_tokenize() {
+        let text = "Hello, world! It's zero-db.";
+
+        assert_eq!(
+            Tokenizer::Whitespace.tokenize(text),
+            vec!["Hello,", "world!", "It's", "zero-db."]
+        );
+        assert_eq!(
+            Tokenizer::Simple.tokenize(text),
+            vec!["Hello", "world", "It", "s", "zero", "db"]
+        );
+    }
+}
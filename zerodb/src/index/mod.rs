@@ -0,0 +1,58 @@
+//! # Indexes
+
+mod fulltext;
+
+use zeroql::{ast::AstKind, sema::IndexKind};
+
+use crate::{ZerodbError, ZerodbResult};
+
+//--------------------------------------------------------------------------------------------------
+// Exports
+//--------------------------------------------------------------------------------------------------
+
+pub use fulltext::*;
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Gets the full-text configuration of an index, or `None` if the index is not a full-text index.
+///
+/// Full-text indexes are the ones created with `WITH index::fulltext(...)`, and no other `WITH`
+/// function is supported.
+pub fn fulltext_config(kind: &IndexKind) -> ZerodbResult<Option<FulltextConfig>> {
+    let IndexKind::Function(function) = kind else {
+        return Ok(None);
+    };
+
+    let function = function
+        .decode()
+        .map_err(|error| ZerodbError::InvalidIndexConfig(error.to_string()))?;
+
+    let AstKind::FunctionCall { subject, args } = &function.kind else {
+        return Err(ZerodbError::InvalidIndexConfig(format!(
+            "expected a function call, found {}",
+            function.kind
+        )));
+    };
+
+    let name = match &subject.kind {
+        AstKind::ScopedIdentifier(segments) => segments
+            .iter()
+            .map(|segment| match segment.kind {
+                AstKind::Identifier(name) => name,
+                _ => "?",
+            })
+            .collect::<Vec<_>>()
+            .join("::"),
+        AstKind::Identifier(name) => name.to_string(),
+        _ => String::new(),
+    };
+
+    match name.as_str() {
+        "index::fulltext" => Ok(Some(FulltextConfig::from_args(args)?)),
+        _ => Err(ZerodbError::InvalidIndexConfig(format!(
+            "unsupported index kind: {name}"
+        ))),
+    }
+}
@@ -2,23 +2,37 @@
 //! `zerodb` is a multi-model database query engine for multi-tenant applications
 
 mod error;
+mod eval;
 mod init;
 mod query;
+mod record;
 mod service;
+mod temporal;
+mod value;
 
 //--------------------------------------------------------------------------------------------------
 // Exports
 //--------------------------------------------------------------------------------------------------
 
+pub mod catalog;
 pub mod config;
+pub mod functions;
+pub mod graph;
+pub mod index;
+pub mod plan;
 pub mod store;
+pub mod table;
 pub mod utils;
 
 pub use error::*;
+pub use eval::*;
 pub use init::*;
 pub use query::*;
+pub use record::*;
 pub use service::*;
 pub use store::*;
+pub use temporal::*;
+pub use value::*;
 
 //--------------------------------------------------------------------------------------------------
 // Re-exports
@@ -0,0 +1,12 @@
+//! Query planning.
+//!
+//! The planner turns the parts of a parsed query into a description of what has to be read, with
+//! the table relations in the catalog already taken into account.
+
+mod planner;
+
+//--------------------------------------------------------------------------------------------------
+// Exports
+//--------------------------------------------------------------------------------------------------
+
+pub use planner::*;
@@ -0,0 +1,161 @@
+use zeroql::ast::{Ast, AstKind};
+use zeroutils_path::Path;
+
+use crate::{
+    catalog::Catalog, graph::PathPattern, record_id_from_ast, store::KvStore, RecordId,
+    ZerodbError, ZerodbResult,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Planner` plans queries against the definitions in a [`Catalog`].
+#[derive(Debug, Clone, Default)]
+pub struct Planner {
+    /// The catalog the planner reads definitions from.
+    catalog: Catalog,
+
+    /// The database the tables of the planned queries are in.
+    database: Path,
+}
+
+/// `SelectPlan` is the plan of a `SELECT` expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectPlan {
+    /// The sources the records are read from, in the order of the `FROM` clause.
+    pub sources: Vec<Source>,
+}
+
+/// `Source` is a single entry of a `FROM` clause.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// Every record of a table and of the tables that are a kind of it, e.g. `FROM animal` reads
+    /// `animal` and, after `RELATE bird ->> is_a ->> animal`, `bird` too.
+    Tables(Vec<String>),
+
+    /// A single record, e.g. `FROM person:alice`.
+    Record(RecordId),
+
+    /// The records at the end of a path, e.g. `FROM person:alice -> likes -> animal`.
+    Traversal(PathPattern),
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Planner {
+    /// Creates a new planner.
+    pub fn new(catalog: Catalog) -> Self {
+        Self {
+            catalog,
+            database: Path::default(),
+        }
+    }
+
+    /// Sets the database the tables of the planned queries are in.
+    pub fn with_database(mut self, database: Path) -> Self {
+        self.database = database;
+        self
+    }
+
+    /// Plans a `SELECT` expression.
+    pub fn plan_select(&self, store: &impl KvStore, ast: &Ast) -> ZerodbResult<SelectPlan> {
+        let AstKind::Select { from, .. } = &ast.kind else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let sources = from
+            .iter()
+            .map(|source| self.plan_source(store, source))
+            .collect::<ZerodbResult<_>>()?;
+
+        Ok(SelectPlan { sources })
+    }
+
+    /// Plans a single entry of a `FROM` clause.
+    pub fn plan_source(&self, store: &impl KvStore, ast: &Ast) -> ZerodbResult<Source> {
+        match &ast.kind {
+            AstKind::Identifier(table) => Ok(Source::Tables(self.catalog.subtables(
+                store,
+                &self.database,
+                table,
+            )?)),
+            AstKind::IdOp(..) => Ok(Source::Record(record_id_from_ast(ast)?)),
+            AstKind::RelateOp { .. } => Ok(Source::Traversal(
+                PathPattern::from_ast(ast)?.with_subtables(&self.catalog, store, &self.database)?,
+            )),
+            _ => Err(ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::parser::Parser;
+
+    use crate::{
+        catalog::{SchemaChange, TableRelation},
+        graph::NodePattern,
+        store::MemoryKvStore,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_planner_plan_select() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+        let catalog = Catalog::new();
+        catalog.apply_schema_change(
+            &mut store,
+            &SchemaChange::Relate {
+                database: Path::default(),
+                relations: vec![
+                    TableRelation::new("bird", "is_a", "animal"),
+                    TableRelation::new("robin", "is_a", "bird"),
+                ],
+            },
+        )?;
+
+        let ast = Parser::new(
+            "SELECT * FROM animal, person:alice, person:alice -> likes -> animal",
+            20,
+        )
+        .parse_exp()?
+        .unwrap();
+
+        let plan = Planner::new(catalog).plan_select(&store, &ast)?;
+        assert_eq!(plan.sources.len(), 3);
+        assert_eq!(
+            plan.sources[0],
+            Source::Tables(vec![
+                "animal".to_string(),
+                "bird".to_string(),
+                "robin".to_string()
+            ])
+        );
+        assert_eq!(
+            plan.sources[1],
+            Source::Record(RecordId::new("person", "alice"))
+        );
+
+        let Source::Traversal(pattern) = &plan.sources[2] else {
+            panic!("expected a traversal");
+        };
+        assert_eq!(
+            pattern.hops[0].target,
+            vec![
+                NodePattern::Table("animal".to_string()),
+                NodePattern::Table("bird".to_string()),
+                NodePattern::Table("robin".to_string()),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use zeroraft::{Request, Response};
 
+use crate::{
+    catalog::{Catalog, SchemaChange},
+    store::{Apply, MemoryKvStore},
+    ZerodbResult,
+};
+
 //--------------------------------------------------------------------------------------------------
 // Types
 //--------------------------------------------------------------------------------------------------
@@ -14,6 +20,8 @@ pub enum Query {
     Set(String, String),
     /// Get a key.
     Get(String),
+    /// Apply a change to the schema items in the catalog.
+    Schema(SchemaChange),
 }
 
 /// TODO(appcypher): To be replaced with the right command variants.
@@ -26,4 +34,14 @@ pub enum QueryResponse {}
 
 impl Request for Query {}
 
+impl Apply for Query {
+    fn apply(&self, store: &mut MemoryKvStore) -> ZerodbResult<()> {
+        if let Query::Schema(change) = self {
+            Catalog::new().apply_schema_change(store, change)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Response for QueryResponse {}
@@ -0,0 +1,91 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ZerodbError;
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `RecordId` uniquely identifies a record within a database.
+///
+/// It is made up of the table the record belongs to and the id of the record in that table, and it
+/// is written as `table:id`, e.g. `person:alice`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RecordId {
+    /// The table the record belongs to.
+    table: String,
+
+    /// The id of the record within the table.
+    id: String,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl RecordId {
+    /// Creates a new `RecordId`.
+    pub fn new(table: impl Into<String>, id: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            id: id.into(),
+        }
+    }
+
+    /// Gets the table the record belongs to.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Gets the id of the record within the table.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Display for RecordId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.table, self.id)
+    }
+}
+
+impl FromStr for RecordId {
+    type Err = ZerodbError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((table, id)) if !table.is_empty() && !id.is_empty() => Ok(Self::new(table, id)),
+            _ => Err(ZerodbError::InvalidRecordId(s.to_string())),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_id_from_str() -> anyhow::Result<()> {
+        let record_id: RecordId = "person:alice".parse()?;
+
+        assert_eq!(record_id.table(), "person");
+        assert_eq!(record_id.id(), "alice");
+        assert_eq!(record_id.to_string(), "person:alice");
+
+        assert!("person".parse::<RecordId>().is_err());
+        assert!(":alice".parse::<RecordId>().is_err());
+        assert!("person:".parse::<RecordId>().is_err());
+
+        Ok(())
+    }
+}
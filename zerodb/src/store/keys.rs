@@ -0,0 +1,122 @@
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Key` is a key in the key-value store made up of one or more segments.
+///
+/// Segments are escaped and terminated by a separator byte so that keys sharing leading segments
+/// sort next to each other. This lets related entries, like the postings of a term, be read with a
+/// single prefix scan.
+///
+/// ```txt
+/// ft / person / idx_bio / t / <term> / <id>
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key(Vec<u8>);
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Key {
+    /// Creates a new empty key.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a segment to the key.
+    pub fn with(mut self, segment: impl AsRef<[u8]>) -> Self {
+        for byte in segment.as_ref() {
+            match *byte {
+                SEPARATOR => self.0.extend([ESCAPE, ESCAPED_SEPARATOR]),
+                ESCAPE => self.0.extend([ESCAPE, ESCAPED_ESCAPE]),
+                byte => self.0.push(byte),
+            }
+        }
+
+        self.0.push(SEPARATOR);
+        self
+    }
+
+    /// Gets the encoded bytes of the key.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Decodes the segments of an encoded key.
+    pub fn decode(bytes: &[u8]) -> Vec<Vec<u8>> {
+        let mut segments = vec![];
+        let mut segment = vec![];
+        let mut bytes = bytes.iter();
+
+        while let Some(byte) = bytes.next() {
+            match *byte {
+                SEPARATOR => segments.push(std::mem::take(&mut segment)),
+                ESCAPE => match bytes.next() {
+                    Some(&ESCAPED_SEPARATOR) => segment.push(SEPARATOR),
+                    Some(&ESCAPED_ESCAPE) => segment.push(ESCAPE),
+                    _ => segment.push(ESCAPE),
+                },
+                byte => segment.push(byte),
+            }
+        }
+
+        segments
+    }
+
+    /// Decodes the segments of an encoded key as strings.
+    pub fn decode_str(bytes: &[u8]) -> Vec<String> {
+        Self::decode(bytes)
+            .into_iter()
+            .map(|segment| String::from_utf8_lossy(&segment).into_owned())
+            .collect()
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl AsRef<[u8]> for Key {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+const SEPARATOR: u8 = 0x00;
+const ESCAPE: u8 = 0x01;
+const ESCAPED_SEPARATOR: u8 = 0x01;
+const ESCAPED_ESCAPE: u8 = 0x02;
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_roundtrip() {
+        let key = Key::new().with("ft").with("per\0son").with([0x01, 0x02]);
+
+        assert_eq!(
+            Key::decode(key.as_bytes()),
+            vec![b"ft".to_vec(), b"per\0son".to_vec(), vec![0x01, 0x02]]
+        );
+    }
+
+    #[test]
+    fn test_key_prefix_ordering() {
+        let prefix = Key::new().with("ft").with("a");
+        let child = prefix.clone().with("b");
+        let sibling = Key::new().with("ft").with("ab");
+
+        assert!(child.as_bytes().starts_with(prefix.as_bytes()));
+        assert!(!sibling.as_bytes().starts_with(prefix.as_bytes()));
+    }
+}
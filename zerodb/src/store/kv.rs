@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::ZerodbResult;
+
+//--------------------------------------------------------------------------------------------------
+// Traits
+//--------------------------------------------------------------------------------------------------
+
+/// `KvStore` is the ordered key-value engine that records, edges and indexes are stored in.
+///
+/// Keys are compared bytewise, which means entries that share a prefix can be read together with
+/// [`KvStore::scan_prefix`].
+pub trait KvStore {
+    /// Gets the value stored at `key`.
+    fn get(&self, key: &[u8]) -> ZerodbResult<Option<Vec<u8>>>;
+
+    /// Stores `value` at `key`, replacing any existing value.
+    fn put(&mut self, key: &[u8], value: Vec<u8>) -> ZerodbResult<()>;
+
+    /// Deletes the value stored at `key`.
+    fn delete(&mut self, key: &[u8]) -> ZerodbResult<()>;
+
+    /// Gets all the entries whose key starts with `prefix`, in key order.
+    fn scan_prefix(&self, prefix: &[u8]) -> ZerodbResult<Vec<(Vec<u8>, Vec<u8>)>>;
+
+    /// Gets the value stored at `key` and decodes it from CBOR.
+    fn get_decoded<T>(&self, key: &[u8]) -> ZerodbResult<Option<T>>
+    where
+        Self: Sized,
+        T: DeserializeOwned,
+    {
+        match self.get(key)? {
+            Some(bytes) => Ok(Some(cbor4ii::serde::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Encodes `value` as CBOR and stores it at `key`.
+    fn put_encoded<T>(&mut self, key: &[u8], value: &T) -> ZerodbResult<()>
+    where
+        Self: Sized,
+        T: Serialize,
+    {
+        let bytes = cbor4ii::serde::to_vec(vec![], value)?;
+        self.put(key, bytes)
+    }
+
+    /// Deletes all the entries whose key starts with `prefix`.
+    fn delete_prefix(&mut self, prefix: &[u8]) -> ZerodbResult<()>
+    where
+        Self: Sized,
+    {
+        for (key, _) in self.scan_prefix(prefix)? {
+            self.delete(&key)?;
+        }
+
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `MemoryKvStore` is a [`KvStore`] that keeps its entries in memory.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryKvStore {
+    /// The entries of the store.
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl MemoryKvStore {
+    /// Creates a new empty in-memory store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the number of entries in the store.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if the store has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl KvStore for MemoryKvStore {
+    fn get(&self, key: &[u8]) -> ZerodbResult<Option<Vec<u8>>> {
+        Ok(self.entries.get(key).cloned())
+    }
+
+    fn put(&mut self, key: &[u8], value: Vec<u8>) -> ZerodbResult<()> {
+        self.entries.insert(key.to_vec(), value);
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &[u8]) -> ZerodbResult<()> {
+        self.entries.remove(key);
+        Ok(())
+    }
+
+    fn scan_prefix(&self, prefix: &[u8]) -> ZerodbResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        Ok(self
+            .entries
+            .range(prefix.to_vec()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::store::Key;
+
+    use super::*;
+
+    #[test]
+    fn test_memory_kv_store_scan_prefix() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+
+        store.put(Key::new().with("a").with("1").as_bytes(), vec![1])?;
+        store.put(Key::new().with("a").with("2").as_bytes(), vec![2])?;
+        store.put(Key::new().with("ab").with("3").as_bytes(), vec![3])?;
+
+        let entries = store.scan_prefix(Key::new().with("a").as_bytes())?;
+        assert_eq!(
+            entries.into_iter().map(|(_, v)| v).collect::<Vec<_>>(),
+            vec![vec![1], vec![2]]
+        );
+
+        store.delete_prefix(Key::new().with("a").as_bytes())?;
+        assert_eq!(store.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_kv_store_encoded_values() -> anyhow::Result<()> {
+        let mut store = MemoryKvStore::new();
+
+        store.put_encoded(b"key", &(1u64, "value".to_string()))?;
+
+        assert_eq!(
+            store.get_decoded::<(u64, String)>(b"key")?,
+            Some((1, "value".to_string()))
+        );
+        assert_eq!(store.get_decoded::<(u64, String)>(b"missing")?, None);
+
+        Ok(())
+    }
+}
@@ -1,9 +1,25 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     net::SocketAddr,
 };
 
-use zeroraft::{LogEntry, NodeId, Request, Snapshot, State, ZeroraftError};
+use zeroraft::{Command, LogEntry, NodeId, Request, Snapshot, State, ZeroraftError};
+
+use crate::ZerodbResult;
+
+use super::MemoryKvStore;
+
+//--------------------------------------------------------------------------------------------------
+// Traits
+//--------------------------------------------------------------------------------------------------
+
+/// `Apply` is a request that changes the data of the state machine once it is committed.
+///
+/// Committed requests are applied in log order, so every node ends up with the same data.
+pub trait Apply {
+    /// Applies the request to the data of the state machine.
+    fn apply(&self, store: &mut MemoryKvStore) -> ZerodbResult<()>;
+}
 
 //--------------------------------------------------------------------------------------------------
 // Types
@@ -37,6 +53,13 @@ where
 
     /// The leader voted for in the current term.
     voted_for: Option<NodeId>,
+
+    /// The data the committed requests are applied to.
+    store: MemoryKvStore,
+
+    /// The results of applying the committed requests by log index, kept until they are taken to
+    /// answer the clients.
+    results: BTreeMap<u64, ZerodbResult<()>>,
 }
 
 /// The `MemorySnapshot` struct represents an in-memory snapshot of the state machine and the log at a certain point in time.
@@ -55,6 +78,27 @@ pub struct MemorySnapshot {
     _data: Vec<u8>,
 }
 
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl<R> MemoryState<R>
+where
+    R: Request,
+{
+    /// Gets the data the committed requests have been applied to.
+    pub fn store(&self) -> &MemoryKvStore {
+        &self.store
+    }
+
+    /// Takes the result of applying the request at a log index, once it has been applied.
+    ///
+    /// Log indexes count from 1, as the commit index does.
+    pub fn take_result(&mut self, index: u64) -> Option<ZerodbResult<()>> {
+        self.results.remove(&index)
+    }
+}
+
 //--------------------------------------------------------------------------------------------------
 // Trait Implementations
 //--------------------------------------------------------------------------------------------------
@@ -63,7 +107,7 @@ impl Snapshot for MemorySnapshot {}
 
 impl<R> State<R> for MemoryState<R>
 where
-    R: Request + Send + Sync,
+    R: Request + Apply + Send + Sync,
 {
     type Snapshot = MemorySnapshot;
 
@@ -133,9 +177,28 @@ where
     }
 
     fn set_last_commit_index(&mut self, index: u64) -> zeroraft::Result<()> {
-        // let old_commit_index = self.commit_index;
         self.commit_index = index;
-        // TDDO: Apply commands up to this index
+
+        // Entries that are committed but not received yet are applied once they are.
+        let committed = self.commit_index.min(self.entries.len() as u64);
+        while self.applied_index < committed {
+            let entry = &self.entries[self.applied_index as usize];
+            if let Command::ClientRequest(request) = &entry.command {
+                // A request the data does not allow fails on its own and its error goes back to
+                // the client, while a storage failure stops the log from being applied.
+                let result = request.apply(&mut self.store);
+                if let Err(error) = &result {
+                    if error.is_storage_error() {
+                        return Err(ZeroraftError::custom(error.to_string()));
+                    }
+                }
+
+                self.results.insert(self.applied_index + 1, result);
+            }
+
+            self.applied_index += 1;
+        }
+
         Ok(())
     }
 
@@ -174,6 +237,8 @@ where
             applied_index: 0,
             current_term: 0,
             voted_for: None,
+            store: MemoryKvStore::new(),
+            results: BTreeMap::new(),
         }
     }
 }
@@ -187,7 +252,15 @@ mod tests {
     use zeroraft::Command;
     use Ok;
 
-    use crate::Query;
+    use zeroql::{
+        parser::Parser,
+        sema::{SchemaMeta, TableModeMeta},
+    };
+
+    use crate::{
+        catalog::{Catalog, SchemaChange},
+        Query, ZerodbError,
+    };
 
     use super::*;
 
@@ -223,4 +296,91 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_memstore_apply_committed_entries() -> anyhow::Result<()> {
+        let mut store = MemoryState::default();
+        let change = SchemaChange::Define {
+            path: "person".parse()?,
+            meta: SchemaMeta::Table {
+                mode: TableModeMeta::Schemafull,
+                fields: vec![],
+            },
+            if_not_exists: false,
+        };
+
+        store.append_entries(vec![
+            LogEntry {
+                term: 1,
+                command: Command::ClientRequest(Query::Schema(change)),
+            },
+            LogEntry {
+                term: 1,
+                command: Command::ClientRequest(Query::Get("a".to_string())),
+            },
+        ])?;
+
+        // Schema changes reach the catalog only once they are committed.
+        let catalog = Catalog::new();
+        assert_eq!(catalog.schema_version(store.store())?, 0);
+
+        store.set_last_commit_index(1)?;
+        assert_eq!(store.get_last_applied_index(), 1);
+        assert_eq!(catalog.schema_version(store.store())?, 1);
+        assert!(catalog.schema(store.store(), &"person".parse()?)?.is_some());
+
+        // A commit index past the received entries applies what there is.
+        store.set_last_commit_index(5)?;
+        assert_eq!(store.get_last_applied_index(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memstore_apply_failed_request() -> anyhow::Result<()> {
+        let mut store = MemoryState::default();
+        let input = "DEFINE INDEX idx_bio ON TABLE person FIELDS bio, name \
+            WITH index::fulltext(stemmer = \"english\")";
+        let ast = Parser::new(input, 50).parse_program().into_result()?;
+        let statement = ast.kind.unwrap_program().remove(0);
+        let invalid = SchemaChange::Define {
+            path: "idx_bio".parse()?,
+            meta: SchemaMeta::from_definition(&statement)?.unwrap(),
+            if_not_exists: false,
+        };
+        let valid = SchemaChange::Define {
+            path: "person".parse()?,
+            meta: SchemaMeta::Table {
+                mode: TableModeMeta::Schemafull,
+                fields: vec![],
+            },
+            if_not_exists: false,
+        };
+
+        store.append_entries(vec![
+            LogEntry {
+                term: 1,
+                command: Command::ClientRequest(Query::Schema(invalid)),
+            },
+            LogEntry {
+                term: 1,
+                command: Command::ClientRequest(Query::Schema(valid)),
+            },
+        ])?;
+
+        // The failed request is answered with its error, and the entries after it still apply.
+        store.set_last_commit_index(2)?;
+        assert_eq!(store.get_last_applied_index(), 2);
+        assert!(matches!(
+            store.take_result(1),
+            Some(Err(ZerodbError::InvalidIndexConfig(_)))
+        ));
+        assert!(matches!(store.take_result(2), Some(Ok(()))));
+        assert!(store.take_result(2).is_none());
+
+        let catalog = Catalog::new();
+        assert!(catalog.schema(store.store(), &"person".parse()?)?.is_some());
+
+        Ok(())
+    }
 }
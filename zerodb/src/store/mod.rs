@@ -1,9 +1,13 @@
 //! # Stores
 
+mod keys;
+mod kv;
 mod memstate;
 
 //--------------------------------------------------------------------------------------------------
 // Exports
 //--------------------------------------------------------------------------------------------------
 
+pub use keys::*;
+pub use kv::*;
 pub use memstate::*;
@@ -0,0 +1,395 @@
+use std::collections::{BTreeMap, HashMap};
+
+use zeroql::{
+    ast::{Ast, EncodedAst},
+    fmt::{FormatOptions, Formatter},
+    sema::{FieldMeta, SchemaMeta, TableModeMeta, Type},
+};
+use zeroutils_path::Path;
+
+use crate::{
+    catalog::Catalog,
+    store::{Key, KvStore},
+    truthy, EnumValue, RecordId, Scope, Value, ZerodbError, ZerodbResult,
+};
+
+use super::coerce;
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `FieldRules` are the fields a `DEFINE TABLE` or `DEFINE EDGE` declares, applied to the records
+/// written to it.
+///
+/// A write gets the `VALUE` of every declared field it leaves out, has its values coerced to the
+/// declared types and checked against every `ASSERT` with `$value` bound, and may not change a
+/// `READONLY` field once the record exists. `UNIQUE` fields are claimed in the store under their
+/// values, so no two records of the table can hold the same one.
+///
+/// Fields the table does not declare are rejected by a `SCHEMAFULL` table, kept as they are by a
+/// `SCHEMALESS` one, and coerced to the catch-all type of a `FLEXIBLE` one.
+///
+/// A field typed with an enum takes the name of one of its variants, or the variant itself, and
+/// is stored as the position of the variant.
+///
+/// ```txt
+/// u / <database> / <table> / <field> / <value> -> RecordId
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FieldRules {
+    /// The database the table or edge belongs to.
+    database: Path,
+
+    /// The table or edge the fields belong to.
+    table: String,
+
+    /// What is done with the fields the table does not declare.
+    mode: TableModeMeta,
+
+    /// The declared fields.
+    fields: Vec<FieldMeta>,
+
+    /// The variants of the enums of the database, by enum name.
+    enums: BTreeMap<String, Vec<String>>,
+}
+
+/// `UniqueClaims` are the unique values the records of one statement claim, checked all together
+/// before any record of the statement is written.
+///
+/// The values the records of the statement held before it are released first, so records can
+/// swap values in one statement.
+#[derive(Debug, Clone, Default)]
+pub struct UniqueClaims {
+    /// The record each claimed key is claimed by, or `None` if a record of the statement released
+    /// it and no other record has claimed it.
+    claims: HashMap<Key, Option<RecordId>>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl FieldRules {
+    /// Creates the rules of the given fields of a schemafull table.
+    pub fn new(table: impl Into<String>, fields: Vec<FieldMeta>) -> Self {
+        Self {
+            database: Path::default(),
+            table: table.into(),
+            mode: TableModeMeta::Schemafull,
+            fields,
+            enums: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the database the table belongs to, whose records claim their unique values apart from
+    /// the records of other databases.
+    pub fn with_database(mut self, database: Path) -> Self {
+        self.database = database;
+        self
+    }
+
+    /// Sets what is done with the fields the table does not declare.
+    pub fn with_mode(mut self, mode: TableModeMeta) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the enums that field types and expressions can name.
+    pub fn with_enums(mut self, enums: BTreeMap<String, Vec<String>>) -> Self {
+        self.enums = enums;
+        self
+    }
+
+    /// Loads the rules of a table or edge of `database` from the catalog.
+    ///
+    /// A table that is not defined has no rules, so its records are written as they are given.
+    /// Edges have no mode, and keep the fields they do not declare.
+    pub fn load(store: &impl KvStore, database: &Path, table: &str) -> ZerodbResult<Self> {
+        let path = format!("{database}/{table}");
+        let path = path
+            .parse()
+            .map_err(|_| ZerodbError::InvalidSchemaPath(path))?;
+
+        let catalog = Catalog::new();
+        let (mode, fields) = match catalog.schema(store, &path)? {
+            Some(entry) => match entry.meta {
+                SchemaMeta::Table { mode, fields } => (mode, fields),
+                SchemaMeta::Edge { fields } => (TableModeMeta::Schemaless, fields),
+                _ => (TableModeMeta::Schemaless, vec![]),
+            },
+            None => (TableModeMeta::Schemaless, vec![]),
+        };
+
+        Ok(Self::new(table, fields)
+            .with_database(database.clone())
+            .with_mode(mode)
+            .with_enums(catalog.enums(store, database)?))
+    }
+
+    /// Creates a scope that knows the enums of the database, to evaluate the values written.
+    pub fn scope(&self) -> Scope {
+        Scope::new().with_enums(self.enums.clone())
+    }
+
+    /// Applies the rules to a record being written, returning the record to store.
+    ///
+    /// `record` is the whole record after the write, and `previous` is the record before it, if it
+    /// already exists.
+    pub fn apply(
+        &self,
+        previous: Option<&BTreeMap<String, Value>>,
+        mut record: BTreeMap<String, Value>,
+    ) -> ZerodbResult<BTreeMap<String, Value>> {
+        // Fields set to `NONE` are left out, so they are never undeclared.
+        record.retain(|_, value| *value != Value::None);
+
+        for (name, value) in record.iter_mut() {
+            if self.fields.iter().any(|field| field.name == *name) {
+                continue;
+            }
+
+            *value = match self.mode.catch_all() {
+                Some(r#type) => self.coerce(name, &r#type, std::mem::take(value))?,
+                None => {
+                    return Err(ZerodbError::UndeclaredField {
+                        table: self.table.clone(),
+                        field: name.clone(),
+                    })
+                }
+            };
+        }
+
+        for field in self.fields.iter() {
+            let name = &field.name;
+
+            // The value written is coerced before it is compared, so writing a readonly field
+            // the value it already holds, like the name of the enum variant it holds, is allowed.
+            if let Some(previous) = previous.filter(|_| field.readonly) {
+                let written = match record.get(name) {
+                    Some(value) => self.coerce(name, &field.r#type, value.clone())?,
+                    None => Value::None,
+                };
+
+                if previous.get(name).cloned().unwrap_or_default() != written {
+                    return Err(ZerodbError::ReadonlyField {
+                        table: self.table.clone(),
+                        field: name.clone(),
+                    });
+                }
+            }
+
+            let mut value = record.remove(name).unwrap_or_default();
+            if let (Value::None, Some(default)) = (&value, &field.default) {
+                let scope = self
+                    .scope()
+                    .with_variable("value", Value::None)
+                    .with_fields(record.clone());
+                value = scope.evaluate(&decode(default)?)?;
+            }
+
+            let value = self.coerce(name, &field.r#type, value)?;
+
+            let scope = self
+                .scope()
+                .with_variable("value", value.clone())
+                .with_fields(record.clone());
+            for assertion in field.assertions.iter() {
+                let assertion = decode(assertion)?;
+                if !truthy(&scope.evaluate(&assertion)?) {
+                    return Err(ZerodbError::FieldAssertion {
+                        table: self.table.clone(),
+                        field: name.clone(),
+                        value: value.to_string(),
+                        assertion: Formatter::new(FormatOptions::default())
+                            .format(&assertion)
+                            .trim_end()
+                            .to_string(),
+                    });
+                }
+            }
+
+            if value != Value::None {
+                record.insert(name.clone(), value);
+            }
+        }
+
+        Ok(record)
+    }
+
+    /// Claims the unique values of a record in `claims`, failing if another record of the
+    /// statement or of the store already holds one of them.
+    pub fn check_unique(
+        &self,
+        store: &impl KvStore,
+        id: &RecordId,
+        record: &BTreeMap<String, Value>,
+        claims: &mut UniqueClaims,
+    ) -> ZerodbResult<()> {
+        for field in self.fields.iter().filter(|field| field.unique) {
+            let Some(value) = record
+                .get(&field.name)
+                .filter(|value| **value != Value::None)
+            else {
+                continue;
+            };
+
+            let key = unique_key(&self.database, &self.table, &field.name, value)?;
+            let holder = match claims.claims.get(&key) {
+                Some(holder) => holder.clone(),
+                None => store.get_decoded::<RecordId>(key.as_bytes())?,
+            };
+
+            if let Some(holder) = holder.filter(|holder| holder != id) {
+                return Err(ZerodbError::UniqueField {
+                    table: self.table.clone(),
+                    field: field.name.clone(),
+                    value: value.to_string(),
+                    record: holder.to_string(),
+                });
+            }
+
+            claims.claims.insert(key, Some(id.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Releases the unique values a record held before a write in `claims`, so other records of
+    /// the statement can claim them.
+    pub fn release_claims(
+        &self,
+        store: &impl KvStore,
+        id: &RecordId,
+        previous: &BTreeMap<String, Value>,
+        claims: &mut UniqueClaims,
+    ) -> ZerodbResult<()> {
+        for (field, value) in previous.iter() {
+            let key = unique_key(&self.database, &self.table, field, value)?;
+            if store.get_decoded::<RecordId>(key.as_bytes())?.as_ref() == Some(id) {
+                claims.claims.entry(key).or_insert(None);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn coerce(&self, field: &str, r#type: &Type, value: Value) -> ZerodbResult<Value> {
+        if value == Value::None || *r#type == Type::Any {
+            return Ok(value);
+        }
+
+        let invalid = |value: &Value| ZerodbError::FieldType {
+            table: self.table.clone(),
+            field: field.to_string(),
+            value: value.to_string(),
+            r#type: r#type.to_string(),
+        };
+
+        match (r#type, value) {
+            (Type::Named(name), value) if self.enums.contains_key(name) => {
+                let variants = &self.enums[name];
+                match value {
+                    Value::Enum(variant)
+                        if variant.r#enum == *name && (variant.index as usize) < variants.len() =>
+                    {
+                        Ok(Value::Enum(variant))
+                    }
+                    Value::String(variant) => Ok(Value::Enum(EnumValue::from_variant(
+                        name, variants, &variant,
+                    )?)),
+                    value => Err(invalid(&value)),
+                }
+            }
+            (Type::Option(r#type), value) if self.names_enum(r#type) => {
+                self.coerce(field, r#type, value)
+            }
+            (Type::List(r#type), Value::List(items)) if self.names_enum(r#type) => Ok(Value::List(
+                items
+                    .into_iter()
+                    .map(|item| self.coerce(field, r#type, item))
+                    .collect::<ZerodbResult<_>>()?,
+            )),
+            (r#type, value) => {
+                let r#type = r#type.to_string();
+                coerce(value.clone(), &r#type).map_err(|_| invalid(&value))
+            }
+        }
+    }
+
+    /// Checks if a type is an enum, or is made of one.
+    fn names_enum(&self, r#type: &Type) -> bool {
+        match r#type {
+            Type::Named(name) => self.enums.contains_key(name),
+            Type::Option(r#type) | Type::List(r#type) => self.names_enum(r#type),
+            _ => false,
+        }
+    }
+}
+
+impl UniqueClaims {
+    /// Creates an empty set of claims.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the claims to the store.
+    pub fn write(self, store: &mut impl KvStore) -> ZerodbResult<()> {
+        for (key, id) in self.claims {
+            if let Some(id) = id {
+                store.put_encoded(key.as_bytes(), &id)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Releases the unique values a record of `table` in `database` holds, so other records can claim
+/// them.
+///
+/// Only the values the record itself holds are released, whatever the current rules of the table
+/// say, so values stay claimed by the right records when a field stops being unique.
+pub fn release_unique(
+    store: &mut impl KvStore,
+    database: &Path,
+    table: &str,
+    id: &RecordId,
+    record: &BTreeMap<String, Value>,
+) -> ZerodbResult<()> {
+    for (field, value) in record.iter() {
+        let key = unique_key(database, table, field, value)?;
+        if store.get_decoded::<RecordId>(key.as_bytes())?.as_ref() == Some(id) {
+            store.delete(key.as_bytes())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets the key a unique value of a field of `table` in `database` is claimed at.
+pub(crate) fn unique_key(
+    database: &Path,
+    table: &str,
+    field: &str,
+    value: &Value,
+) -> ZerodbResult<Key> {
+    let value = cbor4ii::serde::to_vec(vec![], value)?;
+
+    Ok(Key::new()
+        .with("u")
+        .with(database.to_string())
+        .with(table)
+        .with(field)
+        .with(value))
+}
+
+fn decode(encoded: &EncodedAst) -> ZerodbResult<Ast<'_>> {
+    encoded
+        .decode()
+        .map_err(|error| ZerodbError::UnsupportedExpression(error.to_string()))
+}
@@ -0,0 +1,597 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use zeroql::ast::{Ast, AstKind, TypeSig};
+use zeroutils_path::Path;
+
+use crate::{
+    catalog::SchemaChange,
+    field_name,
+    store::{Key, KvStore},
+    Datetime, Duration, RecordId, Value, ZerodbError, ZerodbResult,
+};
+
+use super::{record_key, release_unique, table_key, unique_key};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Migration` moves the records and edges a `REDEFINE TABLE` or `REDEFINE EDGE` changes over to
+/// their new shape.
+///
+/// Records are migrated a batch at a time with [`Graph::migrate`](crate::graph::Graph::migrate),
+/// so the database keeps serving queries while a migration runs. The progress of a migration is
+/// kept in the store, so one that is interrupted picks up where it left off.
+///
+/// ```txt
+/// m / <database> / <table | edge> / <name> -> Migration
+/// ```
+///
+/// A migration first checks that every value it retypes can be coerced to its new type, and is
+/// aborted with the definition the `REDEFINE` replaced put back if one cannot, before anything is
+/// moved. It then renames the records of a table and renames and coerces their fields, and moves
+/// the edges that start or end at them. An edge migration renames the edges and renames and
+/// coerces their fields. Either way, the unique values the records and edges hold are claimed
+/// again under their new table and field names.
+///
+/// Until a table migration is done, [`Records`](super::Records) reads the records it has not
+/// moved yet as they are after the migration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Migration {
+    /// What is being redefined.
+    pub(crate) target: MigrationTarget,
+
+    /// The name of the table or edge before the migration.
+    pub(crate) name: String,
+
+    /// The name of the table or edge after the migration.
+    pub(crate) new_name: String,
+
+    /// The fields that are renamed or retyped.
+    pub(crate) fields: Vec<FieldChange>,
+
+    /// The schema change that puts back the definition the `REDEFINE` replaced, applied if the
+    /// migration is aborted.
+    pub(crate) undo: Option<SchemaChange>,
+
+    /// Whether every record or edge has been checked to coerce to its new shape.
+    pub(crate) checked: bool,
+
+    /// The id of the last record or edge checked, or migrated once all are checked.
+    pub(crate) cursor: Option<String>,
+}
+
+/// `MigrationTarget` is what a [`Migration`] redefines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MigrationTarget {
+    /// A table, redefined with `REDEFINE TABLE`.
+    Table,
+
+    /// An edge, redefined with `REDEFINE EDGE`.
+    Edge,
+}
+
+/// `FieldChange` is a field renamed or retyped by a [`Migration`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// The name of the field.
+    pub name: String,
+
+    /// The name the field is renamed to.
+    pub new_name: Option<String>,
+
+    /// The type the values of the field are coerced to, e.g. `u8` or `string?`.
+    pub r#type: Option<String>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Migration {
+    /// Creates the migration of a `REDEFINE TABLE` or `REDEFINE EDGE` statement.
+    ///
+    /// ```txt
+    /// REDEFINE EDGE follows AS knows FIELDS since AS known_since TYPE datetime
+    /// ```
+    pub fn from_ast(ast: &Ast) -> ZerodbResult<Self> {
+        let (target, name, new_name, fields) = match &ast.kind {
+            AstKind::RedefineTable {
+                name,
+                new_name,
+                fields,
+                ..
+            } => (MigrationTarget::Table, name, new_name, fields),
+            AstKind::RedefineEdge {
+                name,
+                new_name,
+                fields,
+                ..
+            } => (MigrationTarget::Edge, name, new_name, fields),
+            _ => return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+        };
+
+        let name = field_name(name)?;
+        let new_name = match new_name {
+            Some(new_name) => field_name(new_name)?,
+            None => name.clone(),
+        };
+
+        let fields = fields
+            .iter()
+            .map(|field| {
+                Ok(FieldChange {
+                    name: field_name(&field.name)?,
+                    new_name: field.new_name.as_deref().map(field_name).transpose()?,
+                    r#type: field.r#type.as_ref().map(type_name).transpose()?,
+                })
+            })
+            .collect::<ZerodbResult<_>>()?;
+
+        Ok(Self {
+            target,
+            name,
+            new_name,
+            fields,
+            undo: None,
+            checked: false,
+            cursor: None,
+        })
+    }
+
+    /// Sets the schema change that puts back the definition the `REDEFINE` replaced.
+    pub fn with_undo(mut self, undo: SchemaChange) -> Self {
+        self.undo = Some(undo);
+        self
+    }
+
+    /// Gets the migrations of `database` that are not done yet.
+    pub fn pending(store: &impl KvStore, database: &Path) -> ZerodbResult<Vec<Self>> {
+        store
+            .scan_prefix(Key::new().with("m").with(database.to_string()).as_bytes())?
+            .into_iter()
+            .map(|(_, value)| Ok(cbor4ii::serde::from_slice(&value)?))
+            .collect()
+    }
+
+    /// Gets the migration of `database` that moves records to `table`, if one is not done yet.
+    pub(crate) fn of_table(
+        store: &impl KvStore,
+        database: &Path,
+        table: &str,
+    ) -> ZerodbResult<Option<Self>> {
+        let prefix = Key::new()
+            .with("m")
+            .with(database.to_string())
+            .with("table");
+        for (_, value) in store.scan_prefix(prefix.as_bytes())? {
+            let migration: Self = cbor4ii::serde::from_slice(&value)?;
+            if migration.new_name == table {
+                return Ok(Some(migration));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Gets what the migration redefines.
+    pub fn target(&self) -> MigrationTarget {
+        self.target
+    }
+
+    /// Gets the name of the table or edge before the migration.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the name of the table or edge after the migration.
+    pub fn new_name(&self) -> &str {
+        &self.new_name
+    }
+
+    /// Gets the fields that are renamed or retyped.
+    pub fn fields(&self) -> &[FieldChange] {
+        &self.fields
+    }
+
+    /// Whether the table or edge is renamed.
+    pub fn renames(&self) -> bool {
+        self.name != self.new_name
+    }
+
+    /// Whether the record stored at `id` under the name of the table before the migration has yet
+    /// to be moved to its new shape.
+    pub(crate) fn is_pending(&self, id: &RecordId) -> bool {
+        match &self.cursor {
+            Some(cursor) if self.checked => id.id() > cursor.as_str(),
+            _ => true,
+        }
+    }
+
+    /// Gets the name a field has after the migration.
+    pub(crate) fn new_field_name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.fields
+            .iter()
+            .find(|change| change.name == name)
+            .and_then(|change| change.new_name.as_deref())
+            .unwrap_or(name)
+    }
+
+    /// Gets the fields of a record or edge as they are after the migration.
+    pub(crate) fn migrate_fields(
+        &self,
+        mut fields: BTreeMap<String, Value>,
+    ) -> ZerodbResult<BTreeMap<String, Value>> {
+        for change in self.fields.iter() {
+            let Some(mut value) = fields.remove(&change.name) else {
+                continue;
+            };
+
+            if let Some(r#type) = &change.r#type {
+                value = coerce(value, r#type)?;
+            }
+
+            let name = change.new_name.as_ref().unwrap_or(&change.name);
+            fields.insert(name.clone(), value);
+        }
+
+        Ok(fields)
+    }
+
+    /// Gets the next records of the table of the migration after the cursor, up to `batch_size` of
+    /// them.
+    ///
+    /// Renamed records leave the prefix they are read from, and records migrated in place are
+    /// skipped with the cursor.
+    pub(crate) fn record_batch(
+        &self,
+        store: &impl KvStore,
+        database: &Path,
+        batch_size: usize,
+    ) -> ZerodbResult<Vec<(RecordId, BTreeMap<String, Value>)>> {
+        let mut batch = vec![];
+        let prefix = table_key(database, &self.name);
+        for (key, value) in store.scan_prefix(prefix.as_bytes())? {
+            if batch.len() == batch_size {
+                break;
+            }
+
+            let segments = Key::decode_str(&key);
+            let id = RecordId::new(&self.name, &segments[3]);
+            if matches!(&self.cursor, Some(cursor) if id.id() <= cursor.as_str()) {
+                continue;
+            }
+
+            batch.push((id, cbor4ii::serde::from_slice(&value)?));
+        }
+
+        Ok(batch)
+    }
+
+    /// Migrates up to `batch_size` records of the table of the migration, returning how many were
+    /// migrated.
+    pub(crate) fn migrate_records(
+        &mut self,
+        store: &mut impl KvStore,
+        database: &Path,
+        batch_size: usize,
+    ) -> ZerodbResult<usize> {
+        let batch = self.record_batch(store, database, batch_size)?;
+        for (id, record) in batch.iter() {
+            let migrated = self.migrate_fields(record.clone())?;
+            let migrated_id = RecordId::new(&self.new_name, id.id());
+            let unique = unique_fields(store, database, id, record)?
+                .into_iter()
+                .map(|field| self.new_field_name(&field).to_string())
+                .collect::<Vec<_>>();
+
+            release_unique(store, database, id.table(), id, record)?;
+            store.delete(record_key(database, id).as_bytes())?;
+            store.put_encoded(record_key(database, &migrated_id).as_bytes(), &migrated)?;
+            claim_unique(store, database, &migrated_id, &migrated, &unique)?;
+
+            self.cursor = Some(id.id().to_string());
+        }
+
+        Ok(batch.len())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Gets the key the migration of a table or edge of `database` is kept at.
+pub(crate) fn migration_key(database: &Path, target: MigrationTarget, name: &str) -> Key {
+    let target = match target {
+        MigrationTarget::Table => "table",
+        MigrationTarget::Edge => "edge",
+    };
+
+    Key::new()
+        .with("m")
+        .with(database.to_string())
+        .with(target)
+        .with(name)
+}
+
+/// Gets the fields whose values a record or edge of `database` holds as unique values.
+pub(crate) fn unique_fields(
+    store: &impl KvStore,
+    database: &Path,
+    id: &RecordId,
+    fields: &BTreeMap<String, Value>,
+) -> ZerodbResult<Vec<String>> {
+    let mut unique = vec![];
+    for (field, value) in fields.iter() {
+        let key = unique_key(database, id.table(), field, value)?;
+        if store.get_decoded::<RecordId>(key.as_bytes())?.as_ref() == Some(id) {
+            unique.push(field.clone());
+        }
+    }
+
+    Ok(unique)
+}
+
+/// Claims the values of the given fields of a record or edge of `database` as unique values.
+pub(crate) fn claim_unique(
+    store: &mut impl KvStore,
+    database: &Path,
+    id: &RecordId,
+    fields: &BTreeMap<String, Value>,
+    unique: &[String],
+) -> ZerodbResult<()> {
+    for field in unique.iter() {
+        if let Some(value) = fields.get(field) {
+            let key = unique_key(database, id.table(), field, value)?;
+            store.put_encoded(key.as_bytes(), id)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets the name of a type signature, e.g. `u8` or `string?`.
+fn type_name(type_sig: &TypeSig) -> ZerodbResult<String> {
+    match type_sig {
+        TypeSig::Basic(name) => field_name(name),
+        TypeSig::Option(r#type) => Ok(format!("{}?", type_name(r#type)?)),
+        _ => Err(ZerodbError::UnsupportedExpression(format!("{type_sig:?}"))),
+    }
+}
+
+/// Coerces a stored value to the type a `REDEFINE` gives its field.
+///
+/// Values of types that are not known here, like the types in the schema, are kept as they are.
+pub fn coerce(value: Value, r#type: &str) -> ZerodbResult<Value> {
+    let (r#type, optional) = match r#type.strip_suffix('?') {
+        Some(r#type) => (r#type, true),
+        None => (r#type, false),
+    };
+
+    let invalid = |value: &Value| ZerodbError::InvalidCoercion {
+        value: value.to_string(),
+        r#type: r#type.to_string(),
+    };
+
+    let coerced = match (r#type, value) {
+        (_, Value::None) if optional => Value::None,
+        ("bool", value @ Value::Bool(_)) => value,
+        ("string", Value::String(string)) => Value::String(string),
+        ("string", value) => Value::String(value.to_string()),
+        ("bytes", Value::String(string)) => Value::Bytes(string.into_bytes()),
+        ("bytes", value @ Value::Bytes(_)) => value,
+        ("f32" | "f64", Value::Int(integer)) => Value::Float(integer as f64),
+        ("f32" | "f64", value @ Value::Float(_)) => value,
+        ("f32" | "f64", Value::String(string)) => match string.parse() {
+            Ok(float) => Value::Float(float),
+            Err(_) => return Err(invalid(&Value::String(string))),
+        },
+        ("datetime", value @ Value::Datetime(_)) => value,
+        ("datetime", Value::String(string)) => Value::Datetime(Datetime::parse(&string)?),
+        ("duration", value @ Value::Duration(_)) => value,
+        ("duration", Value::String(string)) => Value::Duration(Duration::parse(&string)?),
+        (r#type, value) => match integer_range(r#type) {
+            Some((min, max)) => {
+                let integer = match &value {
+                    Value::Int(integer) => Some(*integer),
+                    Value::Float(float) if float.fract() == 0.0 => Some(*float as i64),
+                    Value::String(string) => string.parse().ok(),
+                    _ => None,
+                };
+
+                match integer {
+                    Some(integer) if (min..=max).contains(&(integer as i128)) => {
+                        Value::Int(integer)
+                    }
+                    _ => return Err(invalid(&value)),
+                }
+            }
+            None if is_known_type(r#type) => return Err(invalid(&value)),
+            None => value,
+        },
+    };
+
+    Ok(coerced)
+}
+
+/// Gets the smallest and largest values of an integer type.
+fn integer_range(r#type: &str) -> Option<(i128, i128)> {
+    let range = match r#type {
+        "u8" => (0, u8::MAX as i128),
+        "u16" => (0, u16::MAX as i128),
+        "u32" => (0, u32::MAX as i128),
+        "u64" | "u128" => (0, i64::MAX as i128),
+        "i8" => (i8::MIN as i128, i8::MAX as i128),
+        "i16" => (i16::MIN as i128, i16::MAX as i128),
+        "i32" => (i32::MIN as i128, i32::MAX as i128),
+        "i64" | "i128" => (i64::MIN as i128, i64::MAX as i128),
+        _ => return None,
+    };
+
+    Some(range)
+}
+
+fn is_known_type(r#type: &str) -> bool {
+    matches!(
+        r#type,
+        "bool" | "string" | "bytes" | "f32" | "f64" | "datetime" | "duration"
+    )
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::parser::Parser;
+
+    use crate::{catalog::Catalog, store::MemoryKvStore, table::Records};
+
+    use super::*;
+
+    fn records_with(schema: &str) -> anyhow::Result<Records<MemoryKvStore>> {
+        let mut store = MemoryKvStore::new();
+        let catalog = Catalog::new();
+        let ast = Parser::new(schema, 50).parse_program().into_result()?;
+        for statement in ast.kind.unwrap_program() {
+            let change = catalog.schema_change(&store, &statement, &Path::default())?;
+            catalog.apply_schema_change(&mut store, &change.unwrap())?;
+        }
+
+        Ok(Records::new(store))
+    }
+
+    fn exp(input: &str) -> anyhow::Result<Ast<'_>> {
+        Ok(Parser::new(input, 20).parse_exp()?.unwrap())
+    }
+
+    /// Starts the migration of a `REDEFINE TABLE`, changing the definition in the catalog.
+    fn redefine(store: &mut MemoryKvStore, input: &str) -> anyhow::Result<Migration> {
+        let ast = Parser::new(input, 20).parse_redefine_stmt()?.unwrap();
+        let migration = Migration::from_ast(&ast)?;
+        let catalog = Catalog::new();
+        let change = catalog.schema_change(store, &ast, &Path::default())?;
+        catalog.apply_schema_change(store, &change.unwrap())?;
+
+        let key = migration_key(&Path::default(), migration.target, &migration.name);
+        store.put_encoded(key.as_bytes(), &migration)?;
+        Ok(migration)
+    }
+
+    #[test]
+    fn test_migration_records() -> anyhow::Result<()> {
+        let mut records =
+            records_with("DEFINE TABLE person FIELDS email TYPE string UNIQUE, age TYPE u8")?;
+        for (id, email, age) in [("alice", "a@x.nz", 30), ("bob", "b@x.nz", 40)] {
+            let input = format!("CREATE person:{id} SET email = '{email}', age = {age}");
+            records.execute_create(&exp(&input)?)?;
+        }
+
+        let claims = Key::new()
+            .with("u")
+            .with(Path::default().to_string())
+            .with("person");
+        assert_eq!(records.store().scan_prefix(claims.as_bytes())?.len(), 2);
+
+        let mut store = records.into_store();
+        let mut migration = redefine(
+            &mut store,
+            "REDEFINE TABLE person AS people FIELDS age AS years",
+        )?;
+        migration.checked = true;
+
+        let root = Path::default();
+        assert_eq!(migration.migrate_records(&mut store, &root, 1)?, 1);
+        assert_eq!(migration.migrate_records(&mut store, &root, 1)?, 1);
+        assert_eq!(migration.migrate_records(&mut store, &root, 1)?, 0);
+
+        // The records move to the new name with their fields renamed.
+        let mut records = Records::new(store);
+        assert!(records.records("person")?.is_empty());
+        assert_eq!(
+            records.record(&RecordId::new("people", "bob"))?,
+            Some(BTreeMap::from([
+                ("email".to_string(), Value::String("b@x.nz".to_string())),
+                ("years".to_string(), Value::Int(40)),
+            ]))
+        );
+
+        // The unique values are claimed under the new name.
+        let ast = exp("CREATE people:carol SET email = 'a@x.nz', years = 20")?;
+        assert!(matches!(
+            records.execute_create(&ast),
+            Err(ZerodbError::UniqueField { .. })
+        ));
+        assert!(records.store().scan_prefix(claims.as_bytes())?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migration_read_through() -> anyhow::Result<()> {
+        let mut records = records_with("DEFINE TABLE person FIELDS age TYPE u8")?;
+        for (id, age) in [("alice", 30), ("bob", 40), ("carol", 50)] {
+            records.execute_create(&exp(&format!("CREATE person:{id} SET age = {age}"))?)?;
+        }
+
+        let mut store = records.into_store();
+        let mut migration = redefine(
+            &mut store,
+            "REDEFINE TABLE person AS people FIELDS age AS years",
+        )?;
+
+        // Records the migration has not moved yet are read under the new name and in their new
+        // shape.
+        let mut records = Records::new(store);
+        let bob = RecordId::new("people", "bob");
+        assert_eq!(
+            records.record(&bob)?,
+            Some(BTreeMap::from([("years".to_string(), Value::Int(40))]))
+        );
+        let ids = records
+            .records("people")?
+            .into_iter()
+            .map(|(id, _)| id.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["people:alice", "people:bob", "people:carol"]);
+
+        // A record written before it is moved is not moved over by the migration.
+        records.update(
+            &bob,
+            BTreeMap::from([("years".to_string(), Value::Int(41))]),
+        )?;
+
+        let mut store = records.into_store();
+        migration.checked = true;
+        assert_eq!(
+            migration.migrate_records(&mut store, &Path::default(), 10)?,
+            2
+        );
+
+        let records = Records::new(store);
+        assert_eq!(
+            records.record(&bob)?,
+            Some(BTreeMap::from([("years".to_string(), Value::Int(41))]))
+        );
+        assert_eq!(records.records("people")?.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_coerce() -> anyhow::Result<()> {
+        assert_eq!(coerce(Value::Int(1), "f64")?, Value::Float(1.0));
+        assert_eq!(coerce(Value::String("7".into()), "u8")?, Value::Int(7));
+        assert_eq!(coerce(Value::None, "u8?")?, Value::None);
+        assert_eq!(coerce(Value::Int(3), "string")?, Value::String("3".into()));
+        assert_eq!(coerce(Value::Int(3), "color")?, Value::Int(3));
+        assert!(coerce(Value::Int(300), "u8").is_err());
+        assert!(coerce(Value::Float(1.5), "i32").is_err());
+        assert!(coerce(Value::Int(1), "bool").is_err());
+
+        Ok(())
+    }
+}
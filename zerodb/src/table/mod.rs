@@ -0,0 +1,26 @@
+//! Record storage for the tables written by `CREATE` and `UPDATE`.
+//!
+//! The fields a `DEFINE TABLE` or `DEFINE EDGE` declares are enforced on every write to the table,
+//! including the edges created by `RELATE`.
+//!
+//! ```txt
+//! DEFINE TABLE person FIELDS
+//!     email TYPE string UNIQUE,
+//!     age TYPE u8 ASSERT $value >= 18,
+//!     created TYPE datetime VALUE time::now() READONLY
+//!
+//! CREATE person:alice SET email = 'alice@example.com', age = 30
+//! UPDATE person:alice SET age += 1
+//! ```
+
+mod fields;
+mod migration;
+mod records;
+
+//--------------------------------------------------------------------------------------------------
+// Exports
+//--------------------------------------------------------------------------------------------------
+
+pub use fields::*;
+pub use migration::*;
+pub use records::*;
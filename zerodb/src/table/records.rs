@@ -0,0 +1,1214 @@
+use std::collections::BTreeMap;
+
+use uuid::Uuid;
+use zeroql::{
+    ast::{Ast, AstKind, Direction, SelectColumn, SelectTransform, UpdateAssign},
+    fmt::{FormatOptions, Formatter},
+};
+use zeroutils_path::Path;
+
+use crate::{
+    catalog::Catalog,
+    field_name,
+    index::{FulltextIndex, SimilarityPredicate},
+    plan::{Planner, Source},
+    record_id_from_ast,
+    store::{Key, KvStore},
+    truthy, RecordId, Scope, Value, ZerodbError, ZerodbResult,
+};
+
+use super::{release_unique, FieldRules, Migration, UniqueClaims};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Records` stores the records of tables in a key-value store, applying the [`FieldRules`] of
+/// their tables to every write.
+///
+/// The records a statement writes are checked all together before any of them is stored, so a
+/// statement that fails on one record writes none. The full-text indexes of a table are kept in
+/// step with every write and delete of its records.
+///
+/// ```txt
+/// r / <database> / <table> / <id> -> { <field>: <value>, ... }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Records<S> {
+    /// The store the records are kept in.
+    store: S,
+
+    /// The database the records belong to, whose table definitions apply.
+    database: Path,
+}
+
+/// A record checked and ready to be stored.
+struct Write {
+    id: RecordId,
+    previous: Option<BTreeMap<String, Value>>,
+    record: BTreeMap<String, Value>,
+}
+
+/// The table an `UPDATE` or `DELETE` targets and the records of it that it targets.
+type Targets = (String, Vec<(RecordId, BTreeMap<String, Value>)>);
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl<S> Records<S>
+where
+    S: KvStore,
+{
+    /// Creates a new record store over the given store.
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            database: Path::default(),
+        }
+    }
+
+    /// Sets the database the records belong to, whose table definitions apply to writes.
+    pub fn with_database(mut self, database: Path) -> Self {
+        self.database = database;
+        self
+    }
+
+    /// Gets the underlying store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Consumes the record store and returns the underlying store.
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    /// Gets a record by its id.
+    ///
+    /// A record of a table that is being migrated is read as it is after the migration, even if
+    /// the migration has not moved it yet.
+    pub fn record(&self, id: &RecordId) -> ZerodbResult<Option<BTreeMap<String, Value>>> {
+        let record = self
+            .store
+            .get_decoded(record_key(&self.database, id).as_bytes())?;
+        let Some(migration) = Migration::of_table(&self.store, &self.database, id.table())? else {
+            return Ok(record);
+        };
+
+        match record {
+            Some(record) if !migration.renames() && migration.is_pending(id) => {
+                Ok(Some(migration.migrate_fields(record)?))
+            }
+            Some(record) => Ok(Some(record)),
+            None if migration.renames() => {
+                let id = RecordId::new(migration.name(), id.id());
+                self.store
+                    .get_decoded(record_key(&self.database, &id).as_bytes())?
+                    .map(|record| migration.migrate_fields(record))
+                    .transpose()
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Gets the records of a table, in id order.
+    ///
+    /// The records of a table that is being migrated are read as they are after the migration,
+    /// even if the migration has not moved them yet.
+    pub fn records(&self, table: &str) -> ZerodbResult<Vec<(RecordId, BTreeMap<String, Value>)>> {
+        let mut records = self.stored_records(table)?;
+        let Some(migration) = Migration::of_table(&self.store, &self.database, table)? else {
+            return Ok(records);
+        };
+
+        if migration.renames() {
+            for (id, record) in self.stored_records(migration.name())? {
+                let id = RecordId::new(table, id.id());
+                records.push((id, migration.migrate_fields(record)?));
+            }
+
+            records.sort_by(|(a, _), (b, _)| a.id().cmp(b.id()));
+            return Ok(records);
+        }
+
+        records
+            .into_iter()
+            .map(|(id, record)| match migration.is_pending(&id) {
+                true => Ok((id, migration.migrate_fields(record)?)),
+                false => Ok((id, record)),
+            })
+            .collect()
+    }
+
+    /// Creates a record, failing if it already exists.
+    pub fn create(
+        &mut self,
+        id: &RecordId,
+        fields: BTreeMap<String, Value>,
+    ) -> ZerodbResult<BTreeMap<String, Value>> {
+        let rules = FieldRules::load(&self.store, &self.database, id.table())?;
+        let write = self.check_create(&rules, id.clone(), fields)?;
+        Ok(self.write(vec![(rules, write)])?.remove(0).1)
+    }
+
+    /// Sets fields of an existing record, leaving its other fields as they are.
+    pub fn update(
+        &mut self,
+        id: &RecordId,
+        changes: BTreeMap<String, Value>,
+    ) -> ZerodbResult<BTreeMap<String, Value>> {
+        let rules = FieldRules::load(&self.store, &self.database, id.table())?;
+        let previous = self
+            .record(id)?
+            .ok_or_else(|| ZerodbError::UndefinedRecord(id.to_string()))?;
+
+        let mut record = previous.clone();
+        record.extend(changes);
+
+        let write = Write {
+            record: rules.apply(Some(&previous), record)?,
+            id: id.clone(),
+            previous: Some(previous),
+        };
+        Ok(self.write(vec![(rules, write)])?.remove(0).1)
+    }
+
+    /// Executes a `CREATE` expression, creating a record for every row of values it gives.
+    ///
+    /// ```txt
+    /// CREATE person:alice SET name = 'Alice', age = 30
+    /// CREATE person SET (name, age) VALUES ('Bob', 25), ('Carol', 41)
+    /// ```
+    ///
+    /// Records created without an id get a newly generated one.
+    pub fn execute_create(
+        &mut self,
+        ast: &Ast,
+    ) -> ZerodbResult<Vec<(RecordId, BTreeMap<String, Value>)>> {
+        let AstKind::Create {
+            subject,
+            columns,
+            values,
+        } = &ast.kind
+        else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let (table, id) = match &subject.kind {
+            AstKind::Identifier(table) => (table.to_string(), None),
+            AstKind::IdOp(..) => {
+                let id = record_id_from_ast(subject)?;
+                (id.table().to_string(), Some(id))
+            }
+            _ => return Err(ZerodbError::UnsupportedExpression(subject.kind.to_string())),
+        };
+
+        if let Some(id) = id.as_ref().filter(|_| values.len() > 1) {
+            return Err(ZerodbError::RecordExists(id.to_string()));
+        }
+
+        let rules = FieldRules::load(&self.store, &self.database, &table)?;
+        let mut writes = vec![];
+        for row in values {
+            let mut fields = BTreeMap::new();
+            for (column, value) in columns.iter().zip(row) {
+                fields.insert(field_name(column)?, rules.scope().evaluate(value)?);
+            }
+
+            let id = id
+                .clone()
+                .unwrap_or_else(|| RecordId::new(&table, Uuid::new_v4().simple().to_string()));
+            writes.push((rules.clone(), self.check_create(&rules, id, fields)?));
+        }
+
+        self.write(writes)
+    }
+
+    /// Executes an `UPDATE` expression on a record, or on the records of a table that pass its
+    /// `WHERE` guard.
+    ///
+    /// ```txt
+    /// UPDATE person:alice SET age += 1
+    /// UPDATE person SET verified = true WHERE age >= 18
+    /// ```
+    pub fn execute_update(
+        &mut self,
+        ast: &Ast,
+    ) -> ZerodbResult<Vec<(RecordId, BTreeMap<String, Value>)>> {
+        let AstKind::Update {
+            target,
+            where_guard,
+            column_ops,
+        } = &ast.kind
+        else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let (table, targets) = self.targets(target)?;
+        if let (AstKind::IdOp(..), []) = (&target.kind, targets.as_slice()) {
+            return Err(ZerodbError::UndefinedRecord(target.kind.to_string()));
+        }
+
+        let rules = FieldRules::load(&self.store, &self.database, &table)?;
+        let searches = self.with_searches(rules.scope(), &table, where_guard.as_deref())?;
+        let mut writes = vec![];
+        for (id, previous) in targets {
+            let scope = searches
+                .clone()
+                .with_record(id.clone())
+                .with_fields(previous.clone());
+            if let Some(where_guard) = where_guard {
+                if !truthy(&scope.evaluate(where_guard)?) {
+                    continue;
+                }
+            }
+
+            // Every assignment sees the record as it was before the update.
+            let mut record = previous.clone();
+            for (column, assign, value) in column_ops {
+                let field = field_name(column)?;
+                let value = scope.evaluate(value)?;
+                let value = match assign {
+                    UpdateAssign::Direct => value,
+                    assign => {
+                        let current = previous.get(&field).cloned().unwrap_or_default();
+                        compound(assign, current, value)?
+                    }
+                };
+
+                record.insert(field, value);
+            }
+
+            writes.push((
+                rules.clone(),
+                Write {
+                    record: rules.apply(Some(&previous), record)?,
+                    id,
+                    previous: Some(previous),
+                },
+            ));
+        }
+
+        self.write(writes)
+    }
+
+    /// Deletes a record, returning it if it existed.
+    pub fn delete(&mut self, id: &RecordId) -> ZerodbResult<Option<BTreeMap<String, Value>>> {
+        let Some(record) = self.record(id)? else {
+            return Ok(None);
+        };
+
+        release_unique(&mut self.store, &self.database, id.table(), id, &record)?;
+        self.store
+            .delete(record_key(&self.database, id).as_bytes())?;
+        self.remove_unmigrated(id)?;
+        self.index_record(&mut BTreeMap::new(), id, None)?;
+
+        Ok(Some(record))
+    }
+
+    /// Executes a `DELETE` expression on a record, or on the records of a table that pass its
+    /// `WHERE` guard, returning the deleted records.
+    ///
+    /// ```txt
+    /// DELETE person:alice
+    /// DELETE person WHERE age < 18
+    /// ```
+    pub fn execute_delete(
+        &mut self,
+        ast: &Ast,
+    ) -> ZerodbResult<Vec<(RecordId, BTreeMap<String, Value>)>> {
+        let AstKind::Delete {
+            target,
+            where_guard,
+        } = &ast.kind
+        else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let (table, targets) = self.targets(target)?;
+        let rules = FieldRules::load(&self.store, &self.database, &table)?;
+        let searches = self.with_searches(rules.scope(), &table, where_guard.as_deref())?;
+
+        let mut deleted = vec![];
+        for (id, record) in targets {
+            if let Some(where_guard) = where_guard {
+                let scope = searches
+                    .clone()
+                    .with_record(id.clone())
+                    .with_fields(record.clone());
+                if !truthy(&scope.evaluate(where_guard)?) {
+                    continue;
+                }
+            }
+
+            if let Some(record) = self.delete(&id)? {
+                deleted.push((id, record));
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Executes a `SELECT` expression over the records of tables or single records, returning a
+    /// row for every record that passes its `WHERE` guard.
+    ///
+    /// ```txt
+    /// SELECT name, search::score() AS score FROM person WHERE bio <> 'rust' ORDER BY score DESC
+    /// ```
+    ///
+    /// Every record has its `id` as a field. Columns are named by the alias they are given or the
+    /// expression they read, and `*` takes every field of the record. `ORDER BY` orders the rows by
+    /// the values of their columns or the fields of their records. Folds, `GROUP BY` and traversals
+    /// in `FROM` are not supported.
+    pub fn execute_select(&self, ast: &Ast) -> ZerodbResult<Vec<BTreeMap<String, Value>>> {
+        let AstKind::Select {
+            fields,
+            omit,
+            from,
+            transforms,
+        } = &ast.kind
+        else {
+            return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string()));
+        };
+
+        let mut where_guard = None;
+        let mut order_by = None;
+        let (mut start, mut limit) = (0, usize::MAX);
+        for transform in transforms {
+            match transform {
+                SelectTransform::WhereGuard(guard) => where_guard = Some(guard.as_ref()),
+                SelectTransform::OrderBy { fields, direction } => {
+                    order_by = Some((fields, direction))
+                }
+                SelectTransform::StartAt(count) => start = self::count(count)?,
+                SelectTransform::LimitTo(count) => limit = self::count(count)?,
+                SelectTransform::WithNoIndex | SelectTransform::WithIndexes(_) => {}
+                SelectTransform::GroupBy(_) => {
+                    return Err(ZerodbError::UnsupportedExpression("GROUP BY".to_string()))
+                }
+            }
+        }
+
+        let planner = Planner::new(Catalog::new());
+        let mut rows = vec![];
+        for source in from {
+            let sources = match planner.plan_source(&self.store, source)? {
+                Source::Tables(tables) => tables
+                    .into_iter()
+                    .map(|table| Ok((self.records(&table)?, table)))
+                    .collect::<ZerodbResult<Vec<_>>>()?,
+                Source::Record(id) => {
+                    let records = self.record(&id)?.map(|record| (id.clone(), record));
+                    vec![(records.into_iter().collect(), id.table().to_string())]
+                }
+                Source::Traversal(_) => {
+                    return Err(ZerodbError::UnsupportedExpression(source.kind.to_string()))
+                }
+            };
+
+            for (records, table) in sources {
+                let rules = FieldRules::load(&self.store, &self.database, &table)?;
+                let searches = self.with_searches(rules.scope(), &table, where_guard)?;
+                for (id, mut record) in records {
+                    record.insert("id".to_string(), Value::Record(id.clone()));
+                    let scope = searches.clone().with_record(id).with_fields(record.clone());
+
+                    if let Some(where_guard) = where_guard {
+                        if !truthy(&scope.evaluate(where_guard)?) {
+                            continue;
+                        }
+                    }
+
+                    let mut row = BTreeMap::new();
+                    for field in fields {
+                        let SelectColumn::Column(column) = field else {
+                            return Err(ZerodbError::UnsupportedExpression("FOLD".to_string()));
+                        };
+
+                        match &column.kind {
+                            AstKind::Wildcard => row.extend(record.clone()),
+                            AstKind::AliasOp { subject, alias } => {
+                                row.insert(field_name(alias)?, scope.evaluate(subject)?);
+                            }
+                            _ => {
+                                row.insert(column_name(column), scope.evaluate(column)?);
+                            }
+                        }
+                    }
+
+                    // Rows are ordered by their columns, or by the fields of their records.
+                    let mut fields = record;
+                    fields.extend(row.clone());
+                    let order_scope = scope.with_fields(fields);
+                    let key = order_by
+                        .map(|(fields, _)| fields.iter().map(|field| order_scope.evaluate(field)))
+                        .into_iter()
+                        .flatten()
+                        .collect::<ZerodbResult<Vec<_>>>()?;
+
+                    for field in omit {
+                        row.remove(&field_name(field)?);
+                    }
+
+                    rows.push((key, row));
+                }
+            }
+        }
+
+        if let Some((_, direction)) = order_by {
+            rows.sort_by(|(a, _), (b, _)| {
+                let ordering = a
+                    .iter()
+                    .zip(b)
+                    .map(|(a, b)| a.total_cmp(b))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(std::cmp::Ordering::Equal);
+
+                match direction {
+                    Direction::Ascending => ordering,
+                    Direction::Descending => ordering.reverse(),
+                }
+            });
+        }
+
+        Ok(rows
+            .into_iter()
+            .skip(start)
+            .take(limit)
+            .map(|(_, row)| row)
+            .collect())
+    }
+
+    /// Gets the table and the records an `UPDATE` or `DELETE` targets, which are the records of a
+    /// table or a single record if it exists.
+    fn targets(&self, target: &Ast) -> ZerodbResult<Targets> {
+        match &target.kind {
+            AstKind::Identifier(table) => Ok((table.to_string(), self.records(table)?)),
+            AstKind::IdOp(..) => {
+                let id = record_id_from_ast(target)?;
+                let record = self.record(&id)?.map(|record| (id.clone(), record));
+                Ok((id.table().to_string(), record.into_iter().collect()))
+            }
+            _ => Err(ZerodbError::UnsupportedExpression(target.kind.to_string())),
+        }
+    }
+
+    /// Gets the records stored under the name of a table, in id order.
+    fn stored_records(
+        &self,
+        table: &str,
+    ) -> ZerodbResult<Vec<(RecordId, BTreeMap<String, Value>)>> {
+        let prefix = table_key(&self.database, table);
+        self.store
+            .scan_prefix(prefix.as_bytes())?
+            .into_iter()
+            .map(|(key, value)| {
+                let segments = Key::decode_str(&key);
+                let id = RecordId::new(table, &segments[3]);
+                Ok((id, cbor4ii::serde::from_slice(&value)?))
+            })
+            .collect()
+    }
+
+    /// Removes the copy of a record that a migration renaming its table has not moved yet, along
+    /// with the unique values it holds, so the migration does not move it over the record written
+    /// or deleted in its place.
+    fn remove_unmigrated(&mut self, id: &RecordId) -> ZerodbResult<()> {
+        let Some(migration) = Migration::of_table(&self.store, &self.database, id.table())? else {
+            return Ok(());
+        };
+
+        if !migration.renames() {
+            return Ok(());
+        }
+
+        let unmigrated = RecordId::new(migration.name(), id.id());
+        let key = record_key(&self.database, &unmigrated);
+        if let Some(record) = self.store.get_decoded(key.as_bytes())? {
+            release_unique(
+                &mut self.store,
+                &self.database,
+                unmigrated.table(),
+                &unmigrated,
+                &record,
+            )?;
+            self.store.delete(key.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs the full-text searches that the `<>` conditions of a `WHERE` guard make on a table,
+    /// adding their hits to the scope the guard is evaluated in.
+    ///
+    /// A condition on a field without a full-text index is left out, and fails when evaluated.
+    fn with_searches(
+        &self,
+        mut scope: Scope,
+        table: &str,
+        where_guard: Option<&Ast>,
+    ) -> ZerodbResult<Scope> {
+        let Some(where_guard) = where_guard else {
+            return Ok(scope);
+        };
+
+        let predicates = SimilarityPredicate::collect(where_guard);
+        if predicates.is_empty() {
+            return Ok(scope);
+        }
+
+        let indexes = FulltextIndex::load(&self.store, &self.database, table)?;
+        for predicate in predicates {
+            if let Some(index) = indexes
+                .iter()
+                .find(|index| index.field() == predicate.field)
+            {
+                let hits = index.search(&self.store, &predicate.query)?;
+                scope = scope.with_search(predicate, hits);
+            }
+        }
+
+        Ok(scope)
+    }
+
+    /// Updates the full-text indexes of a record's table with the record as it is after a write,
+    /// or as deleted if `record` is `None`.
+    ///
+    /// The indexes of every table are loaded once into `indexes`.
+    fn index_record(
+        &mut self,
+        indexes: &mut BTreeMap<String, Vec<FulltextIndex>>,
+        id: &RecordId,
+        record: Option<&BTreeMap<String, Value>>,
+    ) -> ZerodbResult<()> {
+        if !indexes.contains_key(id.table()) {
+            let loaded = FulltextIndex::load(&self.store, &self.database, id.table())?;
+            indexes.insert(id.table().to_string(), loaded);
+        }
+
+        for index in indexes[id.table()].iter() {
+            index.index_record(&mut self.store, id, record)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_create(
+        &self,
+        rules: &FieldRules,
+        id: RecordId,
+        fields: BTreeMap<String, Value>,
+    ) -> ZerodbResult<Write> {
+        if self.record(&id)?.is_some() {
+            return Err(ZerodbError::RecordExists(id.to_string()));
+        }
+
+        Ok(Write {
+            record: rules.apply(None, fields)?,
+            id,
+            previous: None,
+        })
+    }
+
+    /// Releases the unique values the records held before the writes, claims the unique values of
+    /// all the writes, then stores them.
+    fn write(
+        &mut self,
+        writes: Vec<(FieldRules, Write)>,
+    ) -> ZerodbResult<Vec<(RecordId, BTreeMap<String, Value>)>> {
+        let mut claims = UniqueClaims::new();
+        for (rules, write) in writes.iter() {
+            if let Some(previous) = &write.previous {
+                rules.release_claims(&self.store, &write.id, previous, &mut claims)?;
+            }
+        }
+
+        for (rules, write) in writes.iter() {
+            rules.check_unique(&self.store, &write.id, &write.record, &mut claims)?;
+        }
+
+        let mut indexes = BTreeMap::new();
+        let mut written = vec![];
+        for (_, write) in writes {
+            if let Some(previous) = &write.previous {
+                release_unique(
+                    &mut self.store,
+                    &self.database,
+                    write.id.table(),
+                    &write.id,
+                    previous,
+                )?;
+                self.remove_unmigrated(&write.id)?;
+            }
+
+            self.store.put_encoded(
+                record_key(&self.database, &write.id).as_bytes(),
+                &write.record,
+            )?;
+            self.index_record(&mut indexes, &write.id, Some(&write.record))?;
+            written.push((write.id, write.record));
+        }
+
+        claims.write(&mut self.store)?;
+
+        Ok(written)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Applies a compound assignment like `+=` to the current value of a field.
+fn compound(assign: &UpdateAssign, current: Value, value: Value) -> ZerodbResult<Value> {
+    let invalid =
+        || ZerodbError::UnsupportedExpression(format!("{assign:?} of {value} to {current}"));
+
+    let result = match (assign, &current, &value) {
+        (UpdateAssign::Plus, Value::String(current), Value::String(value)) => {
+            Value::String(format!("{current}{value}"))
+        }
+        (UpdateAssign::Plus, Value::List(current), value) => {
+            let mut list = current.clone();
+            match value {
+                Value::List(items) => list.extend(items.iter().cloned()),
+                item => list.push(item.clone()),
+            }
+
+            Value::List(list)
+        }
+        (UpdateAssign::Minus, Value::List(current), value) => Value::List(
+            current
+                .iter()
+                .filter(|item| match value {
+                    Value::List(items) => !items.contains(item),
+                    value => *item != value,
+                })
+                .cloned()
+                .collect(),
+        ),
+        (_, Value::Int(a), Value::Int(b)) => Value::Int(
+            match assign {
+                UpdateAssign::Plus => a.checked_add(*b),
+                UpdateAssign::Minus => a.checked_sub(*b),
+                UpdateAssign::Mul => a.checked_mul(*b),
+                UpdateAssign::Div => a.checked_div(*b),
+                UpdateAssign::Mod => a.checked_rem(*b),
+                UpdateAssign::Pow => u32::try_from(*b).ok().and_then(|b| a.checked_pow(b)),
+                _ => None,
+            }
+            .ok_or_else(invalid)?,
+        ),
+        (_, Value::Int(_) | Value::Float(_), Value::Int(_) | Value::Float(_)) => {
+            let as_float = |value: &Value| match value {
+                Value::Int(integer) => *integer as f64,
+                Value::Float(float) => *float,
+                _ => 0.0,
+            };
+            let (a, b) = (as_float(&current), as_float(&value));
+
+            Value::Float(match assign {
+                UpdateAssign::Plus => a + b,
+                UpdateAssign::Minus => a - b,
+                UpdateAssign::Mul => a * b,
+                UpdateAssign::Div => a / b,
+                UpdateAssign::Mod => a % b,
+                UpdateAssign::Pow => a.powf(b),
+                _ => return Err(invalid()),
+            })
+        }
+        _ => return Err(invalid()),
+    };
+
+    Ok(result)
+}
+
+/// Gets the number a `START AT` or `LIMIT TO` gives.
+fn count(ast: &Ast) -> ZerodbResult<usize> {
+    match Scope::new().evaluate(ast)? {
+        Value::Int(count) if count >= 0 => Ok(count as usize),
+        _ => Err(ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+    }
+}
+
+/// Gets the name of a `SELECT` column without an alias, which is the field it reads or the source
+/// of its expression.
+fn column_name(ast: &Ast) -> String {
+    match ast.kind {
+        AstKind::Identifier(name) => name.to_string(),
+        _ => Formatter::new(FormatOptions::default())
+            .format(ast)
+            .trim_end()
+            .to_string(),
+    }
+}
+
+/// Gets the key the records of a table in `database` are stored under.
+pub(crate) fn table_key(database: &Path, table: &str) -> Key {
+    Key::new().with("r").with(database.to_string()).with(table)
+}
+
+/// Gets the key a record in `database` is stored at.
+pub(crate) fn record_key(database: &Path, id: &RecordId) -> Key {
+    table_key(database, id.table()).with(id.id())
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::{parser::Parser, sema::SemaError};
+
+    use crate::{catalog::Catalog, store::MemoryKvStore, EnumValue};
+
+    use super::*;
+
+    const SCHEMA: &str = "DEFINE TABLE person FIELDS \
+        email TYPE string UNIQUE, \
+        age TYPE u8 ASSERT $value >= 18, \
+        country TYPE string VALUE 'NZ' READONLY, \
+        score TYPE f64 VALUE 0";
+
+    fn records() -> anyhow::Result<Records<MemoryKvStore>> {
+        records_with(SCHEMA)
+    }
+
+    fn records_with(schema: &str) -> anyhow::Result<Records<MemoryKvStore>> {
+        let mut store = MemoryKvStore::new();
+        let catalog = Catalog::new();
+        let ast = Parser::new(schema, 50).parse_program().into_result()?;
+        for statement in ast.kind.unwrap_program() {
+            let change = catalog.schema_change(&store, &statement, &Path::default())?;
+            catalog.apply_schema_change(&mut store, &change.unwrap())?;
+        }
+
+        Ok(Records::new(store))
+    }
+
+    fn exp(input: &str) -> anyhow::Result<Ast<'_>> {
+        Ok(Parser::new(input, 20).parse_exp()?.unwrap())
+    }
+
+    #[test]
+    fn test_records_field_rules() -> anyhow::Result<()> {
+        let mut records = records()?;
+        let alice = RecordId::new("person", "alice");
+
+        // Left out fields get their defaults, and values are coerced to the declared types.
+        let created = records.execute_create(&exp(
+            "CREATE person:alice SET email = 'alice@example.com', age = '30'",
+        )?)?;
+        assert_eq!(
+            created[0].1,
+            BTreeMap::from([
+                ("age".to_string(), Value::Int(30)),
+                ("country".to_string(), Value::String("NZ".into())),
+                (
+                    "email".to_string(),
+                    Value::String("alice@example.com".into())
+                ),
+                ("score".to_string(), Value::Float(0.0)),
+            ])
+        );
+
+        assert!(matches!(
+            records.execute_create(&exp("CREATE person:bob SET email = 'bob@example.com', age = 12")?),
+            Err(ZerodbError::FieldAssertion { field, value, .. }) if field == "age" && value == "12"
+        ));
+        assert!(matches!(
+            records.execute_create(&exp("CREATE person:bob SET age = 'old'")?),
+            Err(ZerodbError::FieldType { field, .. }) if field == "age"
+        ));
+        assert!(records
+            .execute_create(&exp("CREATE person:alice SET age = 40")?)
+            .is_err());
+
+        // Readonly fields keep the value they were created with.
+        let updated =
+            records.execute_update(&exp("UPDATE person:alice SET age += 1, score = 2")?)?;
+        assert_eq!(updated[0].1.get("age"), Some(&Value::Int(31)));
+        assert!(matches!(
+            records.execute_update(&exp("UPDATE person:alice SET country = 'AU'")?),
+            Err(ZerodbError::ReadonlyField { field, .. }) if field == "country"
+        ));
+        assert!(matches!(
+            records.execute_update(&exp("UPDATE person SET age = 17 WHERE age > 30")?),
+            Err(ZerodbError::FieldAssertion { .. })
+        ));
+        assert_eq!(
+            records.record(&alice)?.unwrap().get("age"),
+            Some(&Value::Int(31))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_unique_fields() -> anyhow::Result<()> {
+        let mut records = records()?;
+
+        records.execute_create(&exp(
+            "CREATE person:alice SET email = 'a@example.com', age = 30",
+        )?)?;
+        assert!(matches!(
+            records.execute_create(&exp("CREATE person:bob SET email = 'a@example.com', age = 30")?),
+            Err(ZerodbError::UniqueField { field, record, .. })
+                if field == "email" && record == "person:alice"
+        ));
+
+        // A statement whose records claim the same value twice writes none of them.
+        assert!(records
+            .execute_create(&exp(
+                "CREATE person SET (email, age) VALUES ('b@example.com', 20), ('b@example.com', 21)"
+            )?)
+            .is_err());
+        assert!(records.records("person")?.len() == 1);
+
+        // Values are released when a record lets go of them.
+        records.execute_update(&exp("UPDATE person:alice SET email = 'c@example.com'")?)?;
+        records.execute_create(&exp(
+            "CREATE person:bob SET email = 'a@example.com', age = 30",
+        )?)?;
+        assert!(records
+            .execute_update(&exp("UPDATE person:bob SET email = 'c@example.com'")?)
+            .is_err());
+
+        // Records can swap values in one statement, but not both take the same one.
+        let mut records = records_with("DEFINE TABLE player FIELDS seat TYPE u8 UNIQUE")?;
+        records.execute_create(&exp("CREATE player:a SET seat = 1")?)?;
+        records.execute_create(&exp("CREATE player:b SET seat = 2")?)?;
+        records.execute_update(&exp("UPDATE player SET seat = 3 - seat")?)?;
+
+        let seat = |id: &str| -> anyhow::Result<Option<Value>> {
+            let record = records.record(&RecordId::new("player", id))?;
+            Ok(record.and_then(|record| record.get("seat").cloned()))
+        };
+        assert_eq!(seat("a")?, Some(Value::Int(2)));
+        assert_eq!(seat("b")?, Some(Value::Int(1)));
+        assert!(matches!(
+            records.execute_update(&exp("UPDATE player SET seat = 1")?),
+            Err(ZerodbError::UniqueField { .. })
+        ));
+        records.execute_create(&exp("CREATE player SET seat = 3")?)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_table_modes() -> anyhow::Result<()> {
+        let mut records = records_with(
+            "DEFINE TABLE invoice FIELDS total TYPE f64; \
+            DEFINE TABLE document SCHEMALESS FIELDS title TYPE string; \
+            DEFINE TABLE event FLEXIBLE TYPE u8 FIELDS starts TYPE u64",
+        )?;
+
+        // Schemafull tables reject fields they do not declare.
+        assert!(matches!(
+            records.execute_create(&exp("CREATE invoice:i1 SET total = 10, note = 'late'")?),
+            Err(ZerodbError::UndeclaredField { field, .. }) if field == "note"
+        ));
+        records.execute_create(&exp("CREATE invoice:i1 SET total = 10")?)?;
+        assert!(records
+            .execute_update(&exp("UPDATE invoice:i1 SET note = 'late'")?)
+            .is_err());
+
+        // Schemaless tables keep them as they are.
+        let created = records.execute_create(&exp(
+            "CREATE document:d1 SET title = 'notes', tags = ['a', 'b']",
+        )?)?;
+        assert_eq!(
+            created[0].1.get("tags"),
+            Some(&Value::List(vec![
+                Value::String("a".into()),
+                Value::String("b".into())
+            ]))
+        );
+
+        // Flexible tables keep them as values of the catch-all type.
+        let created =
+            records.execute_create(&exp("CREATE event:e1 SET starts = 1, source = '42'")?)?;
+        assert_eq!(created[0].1.get("source"), Some(&Value::Int(42)));
+        assert!(matches!(
+            records.execute_update(&exp("UPDATE event:e1 SET source = 'web'")?),
+            Err(ZerodbError::FieldType { field, .. }) if field == "source"
+        ));
+
+        // Tables that are not defined take any field.
+        records.execute_create(&exp("CREATE pet:rex SET name = 'rex'")?)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_databases() -> anyhow::Result<()> {
+        let records = records_with(
+            "DEFINE DATABASE shop; DEFINE DATABASE blog; \
+            DEFINE TABLE person ON DB shop FIELDS email TYPE string UNIQUE; \
+            DEFINE TABLE person ON DB blog FIELDS email TYPE string UNIQUE",
+        )?;
+        let alice = RecordId::new("person", "alice");
+        let create = exp("CREATE person:alice SET email = 'alice@example.com'")?;
+
+        // Records and unique values of one database are kept apart from the ones of another.
+        let mut shop = records.with_database("shop".parse()?);
+        shop.execute_create(&create)?;
+
+        let mut blog = Records::new(shop.into_store()).with_database("blog".parse()?);
+        blog.execute_create(&create)?;
+        blog.execute_create(&exp("CREATE person:bob SET email = 'bob@example.com'")?)?;
+        assert_eq!(blog.records("person")?.len(), 2);
+
+        let mut shop = Records::new(blog.into_store()).with_database("shop".parse()?);
+        assert_eq!(shop.records("person")?.len(), 1);
+        assert_eq!(
+            shop.record(&alice)?.unwrap().get("email"),
+            Some(&Value::String("alice@example.com".into()))
+        );
+        shop.execute_create(&exp("CREATE person:bob SET email = 'bob@example.com'")?)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_enum_fields() -> anyhow::Result<()> {
+        let mut records = records_with(
+            "DEFINE ENUM priority VARIANTS low, medium, high; \
+            DEFINE TABLE task FIELDS level TYPE priority ASSERT $value != priority::low, \
+            previous TYPE priority?, history TYPE [priority]",
+        )?;
+
+        // Variants are written by name or as variants, and stored as their positions.
+        let created = records.execute_create(&exp(
+            "CREATE task:t1 SET level = 'high', history = [priority::low, 'medium']",
+        )?)?;
+        assert_eq!(
+            created[0].1.get("level"),
+            Some(&Value::Enum(EnumValue::new("priority", 2)))
+        );
+        assert_eq!(
+            created[0].1.get("history"),
+            Some(&Value::List(vec![
+                Value::Enum(EnumValue::new("priority", 0)),
+                Value::Enum(EnumValue::new("priority", 1)),
+            ]))
+        );
+        assert_eq!(created[0].1.get("previous"), None);
+
+        let updated = records.execute_update(&exp(
+            "UPDATE task:t1 SET previous = level, level = priority.medium WHERE level == priority::high",
+        )?)?;
+        assert_eq!(
+            updated[0].1.get("previous"),
+            Some(&Value::Enum(EnumValue::new("priority", 2)))
+        );
+
+        // Variants are ordered by declaration, not by name.
+        let (level, previous) = (&updated[0].1["level"], &updated[0].1["previous"]);
+        assert!(level.total_cmp(previous).is_lt());
+
+        // Unknown variants and values of other types are rejected.
+        assert!(matches!(
+            records.execute_create(&exp("CREATE task:t2 SET level = 'urgent'")?),
+            Err(ZerodbError::UnknownVariant { r#enum, variant }) if r#enum == "priority" && variant == "urgent"
+        ));
+        assert!(matches!(
+            records.execute_create(&exp("CREATE task:t2 SET level = priority::urgent")?),
+            Err(ZerodbError::UnknownVariant { variant, .. }) if variant == "urgent"
+        ));
+        assert!(matches!(
+            records.execute_create(&exp("CREATE task:t2 SET level = 2")?),
+            Err(ZerodbError::FieldType { field, .. }) if field == "level"
+        ));
+        assert!(matches!(
+            records.execute_create(&exp("CREATE task:t2 SET level = 'low'")?),
+            Err(ZerodbError::FieldAssertion { field, .. }) if field == "level"
+        ));
+
+        // Redefined enums keep the positions of their variants, so stored values read back as the
+        // same variants under their new names.
+        let mut store = records.into_store();
+        let catalog = Catalog::new();
+        for (source, moves) in [
+            ("REDEFINE ENUM priority VARIANTS high, medium, low", true),
+            ("REDEFINE ENUM priority VARIANTS low, medium", true),
+            (
+                "REDEFINE ENUM priority VARIANTS low, medium AS normal, high, urgent",
+                false,
+            ),
+        ] {
+            let ast = Parser::new(source, 50).parse_program().into_result()?;
+            let statement = ast.kind.unwrap_program().remove(0);
+            match catalog.schema_change(&store, &statement, &Path::default()) {
+                Err(ZerodbError::SemaError(SemaError::MovedVariant { .. })) => assert!(moves),
+                result => {
+                    assert!(!moves);
+                    catalog.apply_schema_change(&mut store, &result?.unwrap())?;
+                }
+            }
+        }
+
+        let mut records = Records::new(store);
+        let t1 = RecordId::new("task", "t1");
+        assert_eq!(
+            records.record(&t1)?.unwrap().get("level"),
+            Some(&Value::Enum(EnumValue::new("priority", 1)))
+        );
+        let updated = records.execute_update(&exp(
+            "UPDATE task:t1 SET level = 'urgent' WHERE level == priority::normal",
+        )?)?;
+        assert_eq!(
+            updated[0].1.get("level"),
+            Some(&Value::Enum(EnumValue::new("priority", 3)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_readonly_enum_fields() -> anyhow::Result<()> {
+        let mut records = records_with(
+            "DEFINE ENUM color VARIANTS red, green; \
+            DEFINE TABLE car FIELDS color TYPE color READONLY, name TYPE string",
+        )?;
+        records.execute_create(&exp("CREATE car:c1 SET color = 'red', name = 'a'")?)?;
+
+        // The value written is compared with the stored variant once it is coerced.
+        records.execute_update(&exp("UPDATE car:c1 SET color = 'red', name = 'b'")?)?;
+        records.execute_update(&exp("UPDATE car:c1 SET color = color::red")?)?;
+        assert!(matches!(
+            records.execute_update(&exp("UPDATE car:c1 SET color = 'green'")?),
+            Err(ZerodbError::ReadonlyField { field, .. }) if field == "color"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_select_and_delete() -> anyhow::Result<()> {
+        let mut records = records_with("DEFINE TABLE person FIELDS name TYPE string, age TYPE u8")?;
+        for (id, name, age) in [
+            ("alice", "Alice", 30),
+            ("bob", "Bob", 15),
+            ("carol", "Carol", 41),
+        ] {
+            records.create(
+                &RecordId::new("person", id),
+                BTreeMap::from([
+                    ("name".to_string(), Value::String(name.into())),
+                    ("age".to_string(), Value::Int(age)),
+                ]),
+            )?;
+        }
+
+        let rows = records.execute_select(&exp(
+            "SELECT name FROM person WHERE age >= 18 ORDER BY name DESC",
+        )?)?;
+        let names = rows.iter().map(|row| &row["name"]).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                &Value::String("Carol".into()),
+                &Value::String("Alice".into())
+            ]
+        );
+
+        let rows = records.execute_select(&exp(
+            "SELECT *, age + 1 AS next OMIT age FROM person:alice",
+        )?)?;
+        assert_eq!(
+            rows,
+            [BTreeMap::from([
+                (
+                    "id".to_string(),
+                    Value::Record(RecordId::new("person", "alice"))
+                ),
+                ("name".to_string(), Value::String("Alice".into())),
+                ("next".to_string(), Value::Int(31)),
+            ])]
+        );
+
+        let rows = records.execute_select(&exp(
+            "SELECT id FROM person ORDER BY age START AT 1 LIMIT TO 1",
+        )?)?;
+        assert_eq!(
+            rows[0]["id"],
+            Value::Record(RecordId::new("person", "alice"))
+        );
+
+        let deleted = records.execute_delete(&exp("DELETE person WHERE age < 18")?)?;
+        assert_eq!(deleted[0].0, RecordId::new("person", "bob"));
+        records.execute_delete(&exp("DELETE person:alice")?)?;
+        assert_eq!(records.records("person")?.len(), 1);
+        assert!(records
+            .execute_delete(&exp("DELETE person:alice")?)?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_fulltext_search() -> anyhow::Result<()> {
+        let mut records = records_with(
+            "DEFINE TABLE post FIELDS body TYPE string; DEFINE TABLE note FIELDS text TYPE string",
+        )?;
+        for (id, body) in [
+            ("a", "Rust makes systems programming safe"),
+            ("b", "Cooking with cast iron"),
+            ("c", "rust, rust and more rust"),
+        ] {
+            records.execute_create(&exp(&format!("CREATE post:{id} SET body = '{body}'"))?)?;
+        }
+
+        // An index defined after the records exist is built from them.
+        let catalog = Catalog::new();
+        let define = Parser::new(
+            "DEFINE INDEX idx_body ON TABLE post FIELDS body WITH index::fulltext()",
+            20,
+        )
+        .parse_program()
+        .into_result()?;
+        let define = define.kind.unwrap_program().remove(0);
+        let change = catalog.schema_change(&records.store, &define, &Path::default())?;
+        catalog.apply_schema_change(&mut records.store, &change.unwrap())?;
+
+        let select = exp(
+            "SELECT id, search::score() AS score FROM post WHERE body <> 'rust' ORDER BY score DESC",
+        )?;
+        let rows = records.execute_select(&select)?;
+        let ids = rows.iter().map(|row| &row["id"]).collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [
+                &Value::Record(RecordId::new("post", "c")),
+                &Value::Record(RecordId::new("post", "a")),
+            ]
+        );
+        assert!(matches!(rows[1]["score"], Value::Float(score) if score > 0.0));
+
+        // Writes and deletes of records are reflected in the index.
+        records.execute_update(&exp("UPDATE post:b SET body = 'Rust on cast iron'")?)?;
+        records.execute_delete(&exp("DELETE post WHERE body <> 'more'")?)?;
+        let rows = records.execute_select(&exp("SELECT id FROM post WHERE body <> 'rust'")?)?;
+        let ids = rows.iter().map(|row| &row["id"]).collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [
+                &Value::Record(RecordId::new("post", "a")),
+                &Value::Record(RecordId::new("post", "b")),
+            ]
+        );
+
+        // Fields without a full-text index can't be searched.
+        records.execute_create(&exp("CREATE note:a SET text = 'rust'")?)?;
+        assert!(matches!(
+            records.execute_select(&exp("SELECT * FROM note WHERE text <> 'rust'")?),
+            Err(ZerodbError::UnindexedField(_))
+        ));
+
+        Ok(())
+    }
+}
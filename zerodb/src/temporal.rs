@@ -0,0 +1,480 @@
+use std::{
+    fmt::Display,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ZerodbError, ZerodbResult};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Datetime` is a point in time in UTC, with nanosecond precision.
+///
+/// It is stored as the nanoseconds since the Unix epoch, so it covers the years 1677 to 2262, and
+/// is written in RFC 3339 form, e.g. `2024-05-01T12:30:00Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Datetime {
+    nanos: i64,
+}
+
+/// `Duration` is a signed span of time, with nanosecond precision.
+///
+/// It is written as a sequence of amounts and units from weeks down to nanoseconds, e.g. `1h30m`
+/// or `2w`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+pub struct Duration {
+    nanos: i64,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+const NANOS_PER_SEC: i64 = 1_000_000_000;
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// The units of durations, from the largest to the smallest, with their length in nanoseconds.
+const UNITS: [(&str, i64); 8] = [
+    ("w", 7 * SECS_PER_DAY * NANOS_PER_SEC),
+    ("d", SECS_PER_DAY * NANOS_PER_SEC),
+    ("h", 3_600 * NANOS_PER_SEC),
+    ("m", 60 * NANOS_PER_SEC),
+    ("s", NANOS_PER_SEC),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Datetime {
+    /// Creates a datetime from the nanoseconds since the Unix epoch.
+    pub fn from_nanos(nanos: i64) -> Self {
+        Self { nanos }
+    }
+
+    /// Gets the current time.
+    pub fn now() -> Self {
+        let nanos = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(since) => i64::try_from(since.as_nanos()).unwrap_or(i64::MAX),
+            Err(before) => i64::try_from(before.duration().as_nanos()).map_or(i64::MIN, |n| -n),
+        };
+
+        Self { nanos }
+    }
+
+    /// Creates a datetime from the seconds since the Unix epoch.
+    pub fn from_unix(seconds: i64) -> ZerodbResult<Self> {
+        seconds
+            .checked_mul(NANOS_PER_SEC)
+            .map(Self::from_nanos)
+            .ok_or_else(|| out_of_range(seconds))
+    }
+
+    /// Parses an RFC 3339 datetime, e.g. `2024-05-01T12:30:00.5+01:00`.
+    ///
+    /// The time may be left out for midnight, and the offset for UTC.
+    pub fn parse(string: &str) -> ZerodbResult<Self> {
+        let invalid = || ZerodbError::InvalidDatetime(string.to_string());
+        let number = |part: &str| part.parse::<i64>().map_err(|_| invalid());
+
+        let (date, rest) = string.split_at(string.len().min(10));
+        let [year, month, day] = split_exact::<3>(date, '-').ok_or_else(invalid)?;
+        let (year, month, day) = (number(year)?, number(month)?, number(day)?);
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return Err(invalid());
+        }
+
+        let rest = rest.strip_prefix(['T', 't', ' ']).unwrap_or(rest);
+        let (time, offset) = match rest.find(['Z', 'z', '+', '-']) {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+
+        let mut nanos = 0;
+        if !time.is_empty() {
+            let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+            let [hour, minute, second] = split_exact::<3>(time, ':').ok_or_else(invalid)?;
+            let (hour, minute, second) = (number(hour)?, number(minute)?, number(second)?);
+            if hour > 23 || minute > 59 || second > 60 || fraction.len() > 9 {
+                return Err(invalid());
+            }
+
+            let fraction = match fraction {
+                "" => 0,
+                digits => number(digits)? * 10_i64.pow(9 - digits.len() as u32),
+            };
+            nanos = ((hour * 60 + minute) * 60 + second) * NANOS_PER_SEC + fraction;
+        }
+
+        let offset = match offset {
+            "" | "Z" | "z" => 0,
+            offset => {
+                let sign = if offset.starts_with('-') { -1 } else { 1 };
+                let [hours, minutes] = split_exact::<2>(&offset[1..], ':').ok_or_else(invalid)?;
+                sign * (number(hours)? * 60 + number(minutes)?) * 60 * NANOS_PER_SEC
+            }
+        };
+
+        days_from_civil(year, month, day)
+            .checked_mul(SECS_PER_DAY * NANOS_PER_SEC)
+            .and_then(|days| days.checked_add(nanos))
+            .and_then(|local| local.checked_sub(offset))
+            .map(Self::from_nanos)
+            .ok_or_else(invalid)
+    }
+
+    /// Gets the nanoseconds since the Unix epoch.
+    pub fn nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    /// Gets the whole seconds since the Unix epoch.
+    pub fn unix(&self) -> i64 {
+        self.nanos.div_euclid(NANOS_PER_SEC)
+    }
+
+    /// Gets the year, month and day of the date.
+    pub fn date(&self) -> (i64, i64, i64) {
+        civil_from_days(self.days())
+    }
+
+    /// Gets the hour, minute and second of the time of day.
+    pub fn time(&self) -> (i64, i64, i64) {
+        let seconds = self.unix().rem_euclid(SECS_PER_DAY);
+        (seconds / 3_600, seconds / 60 % 60, seconds % 60)
+    }
+
+    /// Gets the day of the week, from 1 for Monday to 7 for Sunday.
+    pub fn weekday(&self) -> i64 {
+        // The Unix epoch was a Thursday.
+        (self.days() + 3).rem_euclid(7) + 1
+    }
+
+    /// Adds a duration to the datetime, or gets `None` if the result is out of range.
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        self.nanos.checked_add(duration.nanos).map(Self::from_nanos)
+    }
+
+    /// Subtracts a duration from the datetime, or gets `None` if the result is out of range.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        self.nanos.checked_sub(duration.nanos).map(Self::from_nanos)
+    }
+
+    /// Gets the duration from `earlier` to the datetime, or `None` if it is out of range.
+    pub fn since(&self, earlier: Datetime) -> Option<Duration> {
+        self.nanos
+            .checked_sub(earlier.nanos)
+            .map(Duration::from_nanos)
+    }
+
+    /// Rounds the datetime down to a multiple of the duration since the Unix epoch, e.g. to the
+    /// start of its day with `1d`. The duration must be positive.
+    pub fn floor(&self, duration: Duration) -> Option<Self> {
+        (duration.nanos > 0)
+            .then(|| Self::from_nanos(self.nanos - self.nanos.rem_euclid(duration.nanos)))
+    }
+
+    fn days(&self) -> i64 {
+        self.unix().div_euclid(SECS_PER_DAY)
+    }
+}
+
+impl Duration {
+    /// Creates a duration of the given number of nanoseconds.
+    pub fn from_nanos(nanos: i64) -> Self {
+        Self { nanos }
+    }
+
+    /// Creates a duration of `count` times `unit`, or gets `None` if it is out of range.
+    pub fn checked_from(count: i64, unit: Duration) -> Option<Self> {
+        count.checked_mul(unit.nanos).map(Self::from_nanos)
+    }
+
+    /// Parses a duration written as amounts and units, e.g. `1h30m` or `-2w`.
+    ///
+    /// The units are `w`, `d`, `h`, `m`, `s`, `ms`, `us` and `ns`.
+    pub fn parse(string: &str) -> ZerodbResult<Self> {
+        let invalid = || ZerodbError::InvalidDuration(string.to_string());
+        let (negative, mut rest) = match string.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, string),
+        };
+
+        if rest.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut nanos = 0_i64;
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .ok_or_else(invalid)?;
+            let amount = rest[..digits].parse::<i64>().map_err(|_| invalid())?;
+            rest = &rest[digits..];
+
+            let unit_len = rest
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let unit = UNITS
+                .iter()
+                .find(|(name, _)| *name == &rest[..unit_len])
+                .ok_or_else(invalid)?;
+            rest = &rest[unit_len..];
+
+            nanos = amount
+                .checked_mul(unit.1)
+                .and_then(|amount| nanos.checked_add(amount))
+                .ok_or_else(invalid)?;
+        }
+
+        Ok(Self::from_nanos(if negative { -nanos } else { nanos }))
+    }
+
+    /// A nanosecond.
+    pub const fn nanosecond() -> Self {
+        Self { nanos: 1 }
+    }
+
+    /// A microsecond.
+    pub const fn microsecond() -> Self {
+        Self { nanos: 1_000 }
+    }
+
+    /// A millisecond.
+    pub const fn millisecond() -> Self {
+        Self { nanos: 1_000_000 }
+    }
+
+    /// A second.
+    pub const fn second() -> Self {
+        Self {
+            nanos: NANOS_PER_SEC,
+        }
+    }
+
+    /// A minute.
+    pub const fn minute() -> Self {
+        Self {
+            nanos: 60 * NANOS_PER_SEC,
+        }
+    }
+
+    /// An hour.
+    pub const fn hour() -> Self {
+        Self {
+            nanos: 3_600 * NANOS_PER_SEC,
+        }
+    }
+
+    /// A day.
+    pub const fn day() -> Self {
+        Self {
+            nanos: SECS_PER_DAY * NANOS_PER_SEC,
+        }
+    }
+
+    /// A week.
+    pub const fn week() -> Self {
+        Self {
+            nanos: 7 * SECS_PER_DAY * NANOS_PER_SEC,
+        }
+    }
+
+    /// Gets the length of the duration in nanoseconds.
+    pub fn nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    /// Gets the length of the duration in whole units, rounded towards zero.
+    pub fn in_units_of(&self, unit: Duration) -> i64 {
+        self.nanos / unit.nanos
+    }
+
+    /// Adds two durations, or gets `None` if the result is out of range.
+    pub fn checked_add(&self, other: Duration) -> Option<Self> {
+        self.nanos.checked_add(other.nanos).map(Self::from_nanos)
+    }
+
+    /// Subtracts two durations, or gets `None` if the result is out of range.
+    pub fn checked_sub(&self, other: Duration) -> Option<Self> {
+        self.nanos.checked_sub(other.nanos).map(Self::from_nanos)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Gets the days since the Unix epoch of a date in the proleptic Gregorian calendar.
+///
+/// This is Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Gets the year, month and day of a number of days since the Unix epoch.
+///
+/// This is Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn split_exact<const N: usize>(string: &str, separator: char) -> Option<[&str; N]> {
+    let parts = string.split(separator).collect::<Vec<_>>();
+    parts.try_into().ok()
+}
+
+fn out_of_range(seconds: i64) -> ZerodbError {
+    ZerodbError::InvalidDatetime(format!("{seconds} seconds since the Unix epoch"))
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Display for Datetime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (year, month, day) = self.date();
+        let (hour, minute, second) = self.time();
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}"
+        )?;
+
+        let fraction = self.nanos.rem_euclid(NANOS_PER_SEC);
+        if fraction > 0 {
+            let digits = format!("{fraction:09}");
+            write!(f, ".{}", digits.trim_end_matches('0'))?;
+        }
+
+        write!(f, "Z")
+    }
+}
+
+impl Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.nanos == 0 {
+            return write!(f, "0ns");
+        }
+
+        if self.nanos < 0 {
+            write!(f, "-")?;
+        }
+
+        let mut rest = self.nanos.unsigned_abs();
+        for (name, length) in UNITS {
+            let length = length as u64;
+            if rest >= length {
+                write!(f, "{}{name}", rest / length)?;
+                rest %= length;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_datetime_parse_and_display() -> anyhow::Result<()> {
+        let datetime = Datetime::parse("2024-02-29T23:59:58.25Z")?;
+        assert_eq!(datetime.to_string(), "2024-02-29T23:59:58.25Z");
+        assert_eq!(datetime.date(), (2024, 2, 29));
+        assert_eq!(datetime.time(), (23, 59, 58));
+        assert_eq!(datetime.weekday(), 4);
+
+        assert_eq!(Datetime::parse("1970-01-01")?, Datetime::from_nanos(0));
+        assert_eq!(
+            Datetime::parse("2024-05-01T12:30:00+01:30")?.to_string(),
+            "2024-05-01T11:00:00Z"
+        );
+        assert_eq!(
+            Datetime::parse("1969-12-31T23:59:59Z")?.to_string(),
+            "1969-12-31T23:59:59Z"
+        );
+
+        assert!(Datetime::parse("2023-02-29").is_err());
+        assert!(Datetime::parse("2024-13-01").is_err());
+        assert!(Datetime::parse("yesterday").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_datetime_arithmetic() -> anyhow::Result<()> {
+        let start = Datetime::parse("2024-01-31T10:15:00Z")?;
+        let end = start.checked_add(Duration::parse("2w1d")?).unwrap();
+
+        assert_eq!(end.to_string(), "2024-02-15T10:15:00Z");
+        assert_eq!(end.since(start), Some(Duration::parse("15d")?));
+        assert_eq!(
+            end.floor(Duration::day()).unwrap().to_string(),
+            "2024-02-15T00:00:00Z"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_duration_parse_and_display() -> anyhow::Result<()> {
+        assert_eq!(Duration::parse("1h30m")?.to_string(), "1h30m");
+        assert_eq!(Duration::parse("90m")?.to_string(), "1h30m");
+        assert_eq!(Duration::parse("-1500ms")?.to_string(), "-1s500ms");
+        assert_eq!(
+            Duration::parse("2w")?,
+            Duration::checked_from(2, Duration::week()).unwrap()
+        );
+        assert_eq!(Duration::default().to_string(), "0ns");
+
+        assert!(Duration::parse("").is_err());
+        assert!(Duration::parse("5").is_err());
+        assert!(Duration::parse("5y").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,315 @@
+use std::{cmp::Ordering, collections::BTreeMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use zeroql::ast::{Ast, AstKind};
+
+use crate::{Datetime, Duration, RecordId, ZerodbError, ZerodbResult};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `Value` is a value stored in a record field.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Value {
+    /// The absence of a value.
+    #[default]
+    None,
+
+    /// A boolean.
+    Bool(bool),
+
+    /// A signed integer.
+    Int(i64),
+
+    /// A floating-point number.
+    Float(f64),
+
+    /// A string.
+    String(String),
+
+    /// A byte string.
+    Bytes(Vec<u8>),
+
+    /// A reference to another record.
+    Record(RecordId),
+
+    /// A list of values.
+    List(Vec<Value>),
+
+    /// A map of field names to values.
+    Object(BTreeMap<String, Value>),
+
+    /// A point in time.
+    Datetime(Datetime),
+
+    /// A span of time.
+    Duration(Duration),
+
+    /// A variant of an enum.
+    Enum(EnumValue),
+}
+
+/// `EnumValue` is a variant of an enum, kept as its position among the variants the enum declares
+/// rather than as its name.
+///
+/// Variants of the same enum are ordered by their position, so they sort in declaration order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct EnumValue {
+    /// The name of the enum.
+    pub r#enum: String,
+
+    /// The position of the variant among the variants of the enum.
+    pub index: u32,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Value {
+    /// Evaluates a constant expression, like a literal or a record id, into a value.
+    pub fn from_ast(ast: &Ast) -> ZerodbResult<Self> {
+        let value = match &ast.kind {
+            AstKind::NoneLiteral => Value::None,
+            AstKind::BooleanLiteral(boolean) => Value::Bool(*boolean),
+            AstKind::IntegerLiteral(integer) => Value::Int(
+                i64::try_from(*integer)
+                    .map_err(|_| ZerodbError::UnsupportedExpression(integer.to_string()))?,
+            ),
+            AstKind::FloatLiteral(float) => Value::Float(*float),
+            AstKind::StringLiteral(string) => Value::String(string.to_string()),
+            AstKind::ByteStringLiteral(bytes) => Value::Bytes(bytes.as_bytes().to_vec()),
+            AstKind::IdOp(..) => Value::Record(record_id_from_ast(ast)?),
+            AstKind::MinusSignOp(operand) => match Value::from_ast(operand)? {
+                Value::Int(integer) => Value::Int(-integer),
+                Value::Float(float) => Value::Float(-float),
+                _ => return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+            },
+            AstKind::PlusSignOp(operand) => match Value::from_ast(operand)? {
+                value @ (Value::Int(_) | Value::Float(_)) => value,
+                _ => return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+            },
+            AstKind::ListLiteral(items) | AstKind::TupleLiteral(items) => Value::List(
+                items
+                    .iter()
+                    .map(Value::from_ast)
+                    .collect::<ZerodbResult<_>>()?,
+            ),
+            AstKind::ObjectLiteral(entries) => Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| Ok((field_name(key)?, Value::from_ast(value)?)))
+                    .collect::<ZerodbResult<_>>()?,
+            ),
+            _ => return Err(ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+        };
+
+        Ok(value)
+    }
+
+    /// Compares two values, ordering values of different types by the order of their variants,
+    /// except that integers and floats compare as numbers.
+    pub fn total_cmp(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::Int(a), Value::Float(b)) => (*a as f64).total_cmp(b),
+            (Value::Float(a), Value::Int(b)) => a.total_cmp(&(*b as f64)),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+            (Value::Record(a), Value::Record(b)) => a.cmp(b),
+            (Value::List(a), Value::List(b)) => a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| a.total_cmp(b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (Value::Object(a), Value::Object(b)) => a
+                .iter()
+                .zip(b)
+                .map(|((ak, av), (bk, bv))| ak.cmp(bk).then_with(|| av.total_cmp(bv)))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (Value::Datetime(a), Value::Datetime(b)) => a.cmp(b),
+            (Value::Duration(a), Value::Duration(b)) => a.cmp(b),
+            (Value::Enum(a), Value::Enum(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+
+    /// Gets the record id if the value is a record reference.
+    pub fn as_record(&self) -> Option<&RecordId> {
+        match self {
+            Value::Record(record) => Some(record),
+            _ => None,
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Value::None => 0,
+            Value::Bool(_) => 1,
+            Value::Int(_) | Value::Float(_) => 2,
+            Value::String(_) => 3,
+            Value::Bytes(_) => 4,
+            Value::Datetime(_) => 5,
+            Value::Duration(_) => 6,
+            Value::Record(_) => 7,
+            Value::List(_) => 8,
+            Value::Object(_) => 9,
+            Value::Enum(_) => 10,
+        }
+    }
+}
+
+impl EnumValue {
+    /// Creates the variant at the given position of an enum.
+    pub fn new(r#enum: impl Into<String>, index: u32) -> Self {
+        Self {
+            r#enum: r#enum.into(),
+            index,
+        }
+    }
+
+    /// Gets the variant of an enum by its name, given the variants the enum declares.
+    pub fn from_variant(r#enum: &str, variants: &[String], variant: &str) -> ZerodbResult<Self> {
+        let index = variants
+            .iter()
+            .position(|candidate| candidate == variant)
+            .ok_or_else(|| ZerodbError::UnknownVariant {
+                r#enum: r#enum.to_string(),
+                variant: variant.to_string(),
+            })?;
+
+        Ok(Self::new(r#enum, index as u32))
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Gets the record id from an `IdOp` node like `person:alice`, `person:1` or `person:"alice"`.
+pub fn record_id_from_ast(ast: &Ast) -> ZerodbResult<RecordId> {
+    let AstKind::IdOp(table, id) = &ast.kind else {
+        return Err(ZerodbError::InvalidRecordId(ast.kind.to_string()));
+    };
+
+    let AstKind::Identifier(table) = table.kind else {
+        return Err(ZerodbError::InvalidRecordId(ast.kind.to_string()));
+    };
+
+    let id = match &id.kind {
+        AstKind::Identifier(id) | AstKind::StringLiteral(id) => id.to_string(),
+        AstKind::IntegerLiteral(id) => id.to_string(),
+        _ => return Err(ZerodbError::InvalidRecordId(ast.kind.to_string())),
+    };
+
+    Ok(RecordId::new(table, id))
+}
+
+/// Gets a field name from an identifier or a string literal.
+pub fn field_name(ast: &Ast) -> ZerodbResult<String> {
+    match ast.kind {
+        AstKind::Identifier(name) | AstKind::StringLiteral(name) => Ok(name.to_string()),
+        _ => Err(ZerodbError::UnsupportedExpression(ast.kind.to_string())),
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::None => write!(f, "none"),
+            Value::Bool(boolean) => write!(f, "{boolean}"),
+            Value::Int(integer) => write!(f, "{integer}"),
+            Value::Float(float) => write!(f, "{float:?}"),
+            Value::String(string) => write!(f, "{string:?}"),
+            Value::Bytes(bytes) => write!(f, "b{:?}", String::from_utf8_lossy(bytes)),
+            Value::Record(record) => write!(f, "{record}"),
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key}: {value}")?;
+                }
+                write!(f, "}}")
+            }
+            Value::Datetime(datetime) => write!(f, "d\"{datetime}\""),
+            Value::Duration(duration) => write!(f, "{duration}"),
+            Value::Enum(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl Display for EnumValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}", self.r#enum, self.index)
+    }
+}
+
+impl From<RecordId> for Value {
+    fn from(record: RecordId) -> Self {
+        Value::Record(record)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use zeroql::parser::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_value_from_ast() -> anyhow::Result<()> {
+        let ast = Parser::new(
+            r#"{ name: "alice", age: 30, score: -1.5, tags: ["a", true], friend: person:bob }"#,
+            20,
+        )
+        .parse_op()?
+        .unwrap();
+
+        assert_eq!(
+            Value::from_ast(&ast)?,
+            Value::Object(BTreeMap::from([
+                ("name".to_string(), Value::String("alice".to_string())),
+                ("age".to_string(), Value::Int(30)),
+                ("score".to_string(), Value::Float(-1.5)),
+                (
+                    "tags".to_string(),
+                    Value::List(vec![Value::String("a".to_string()), Value::Bool(true)])
+                ),
+                (
+                    "friend".to_string(),
+                    Value::Record(RecordId::new("person", "bob"))
+                ),
+            ]))
+        );
+
+        let ast = Parser::new("1 + 2", 20).parse_op()?.unwrap();
+        assert!(Value::from_ast(&ast).is_err());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use std::io::Write;
 
-use zeroql::parser::Parser;
+use zeroql::{diagnostic::Diagnostic, parser::Parser};
 
 //--------------------------------------------------------------------------------------------------
 // Main
@@ -15,8 +15,11 @@ fn main() {
         std::io::stdin().read_line(&mut input).unwrap();
 
         let mut parser = Parser::new(&input, 100);
-        let ast = parser.parse_program().unwrap();
+        let program = parser.parse_program();
+        for e in program.errors.iter() {
+            print!("{}", Diagnostic::from(e).render(&input));
+        }
 
-        println!("ast: {:#?}", ast);
+        println!("ast: {:#?}", program.ast);
     }
 }
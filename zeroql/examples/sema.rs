@@ -1,6 +1,6 @@
 use std::io::Write;
 
-use zeroql::{parser::Parser, sema::SemanticAnalyzer};
+use zeroql::{diagnostic::Diagnostic, parser::Parser, sema::SemanticAnalyzer};
 
 //--------------------------------------------------------------------------------------------------
 // Main
@@ -15,18 +15,20 @@ fn main() {
         std::io::stdin().read_line(&mut input).unwrap();
 
         let mut parser = Parser::new(&input, 100);
-        let Ok(ast) = parser.parse_program() else {
+        let program = parser.parse_program();
+        if program.has_errors() {
+            for e in program.errors.iter() {
+                print!("{}", Diagnostic::from(e).render(&input));
+            }
             continue;
-        };
+        }
 
-        if let Some(mut ast) = ast {
-            let mut sema = SemanticAnalyzer::new(&mut ast);
-            match sema.analyze() {
-                Ok(_) => println!("Analysis successful: {:#?}", sema.get_ast()),
-                Err(e) => println!("Error: {}", e),
-            }
-        } else {
-            println!("Failed to parse input");
+        let mut ast = program.ast;
+
+        let mut sema = SemanticAnalyzer::new(&mut ast);
+        match sema.analyze() {
+            Ok(_) => println!("Analysis successful: {:#?}", sema.get_ast()),
+            Err(e) => print!("{}", Diagnostic::from(&e).render(&input)),
         }
     }
 }
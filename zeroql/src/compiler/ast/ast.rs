@@ -1,9 +1,11 @@
 use std::fmt::Display;
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     lexer::RegexFlags,
     parser::Combinator,
-    sema::{Symbols, VersionedSchema},
+    sema::{Symbols, Type, VersionedSchema},
     Span,
 };
 
@@ -14,23 +16,29 @@ use super::AnalysisTag;
 //--------------------------------------------------------------------------------------------------
 
 /// The abstract syntax tree (AST) of the zeroql language.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ast<'a> {
     /// The span of the AST node in the input string.
     pub span: Span,
 
     /// The kind of the AST node.
+    #[serde(borrow)]
     pub kind: AstKind<'a>,
 
     /// The tag of the AST node.
+    ///
+    /// Tags are not serialized, so a deserialized AST is untagged until the analysis passes are
+    /// run on it again.
+    #[serde(skip)]
     pub tag: Option<AnalysisTag>,
 }
 
 /// The kind of an AST node.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AstKind<'a> {
     /// For intermediate nodes representing partial syntax that may not necessarily make it into
     /// the final AST.
+    #[serde(skip)]
     Temp(Option<Box<Combinator<Ast<'a>>>>),
 
     /// A wildcard expression.
@@ -249,6 +257,9 @@ pub enum AstKind<'a> {
     /// A range inclusive operation.
     RangeInclusiveOp(Box<Ast<'a>>, Box<Ast<'a>>),
 
+    /// A range operation without an upper bound, like `1..` or `..`.
+    UnboundedRangeOp(Option<Box<Ast<'a>>>),
+
     /// A relate id operation.
     SingleRelateId {
         /// The subject of the relate id operation.
@@ -453,6 +464,18 @@ pub enum AstKind<'a> {
         database: Option<Box<Ast<'a>>>,
     },
 
+    /// A `REMOVE FUNCTION` expression.
+    RemoveFunction {
+        /// The subject of the remove function operation.
+        subject: Box<Ast<'a>>,
+
+        /// The function existing flag.
+        if_exists: bool,
+
+        /// The database the function belongs to.
+        database: Option<Box<Ast<'a>>>,
+    },
+
     /// A `REMOVE PARAM` expression.
     RemoveParam {
         /// The subject of the remove parameter operation.
@@ -561,6 +584,18 @@ pub enum AstKind<'a> {
         database: Option<Box<Ast<'a>>>,
     },
 
+    /// A `DESCRIBE FUNCTION` expression.
+    DescribeFunction {
+        /// The subject of the describe function operation.
+        subject: Box<Ast<'a>>,
+
+        /// The function existing flag.
+        if_exists: bool,
+
+        /// The database the function belongs to.
+        database: Option<Box<Ast<'a>>>,
+    },
+
     /// A `DESCRIBE PARAM` expression.
     DescribeParam {
         /// The subject of the describe parameter operation.
@@ -674,6 +709,9 @@ pub enum AstKind<'a> {
         /// The database the table belongs to.
         database: Option<Box<Ast<'a>>>,
 
+        /// What the table does with fields it does not declare.
+        mode: TableMode<'a>,
+
         /// The fields of the table.
         fields: Vec<Field<'a>>,
     },
@@ -759,6 +797,27 @@ pub enum AstKind<'a> {
         block: Box<Ast<'a>>,
     },
 
+    /// A `DEFINE FUNCTION` statement.
+    DefineFunction {
+        /// The name of the function.
+        name: Box<Ast<'a>>,
+
+        /// The if not exists flag.
+        if_not_exists: bool,
+
+        /// The database the function belongs to.
+        database: Option<Box<Ast<'a>>>,
+
+        /// The parameters of the function.
+        params: Vec<(Ast<'a>, TypeSig<'a>)>,
+
+        /// The type of the values the function returns.
+        r#type: Option<TypeSig<'a>>,
+
+        /// The body of the function.
+        body: Box<Ast<'a>>,
+    },
+
     /// A `DEFINE PARAM` statement.
     DefineParam {
         /// The name of the parameter.
@@ -777,6 +836,124 @@ pub enum AstKind<'a> {
         value: Box<Ast<'a>>,
     },
 
+    /// A `REDEFINE TABLE` statement.
+    RedefineTable {
+        /// The name of the table.
+        name: Box<Ast<'a>>,
+
+        /// The if exists flag.
+        if_exists: bool,
+
+        /// The name the table is renamed to.
+        new_name: Option<Box<Ast<'a>>>,
+
+        /// The database the table belongs to.
+        database: Option<Box<Ast<'a>>>,
+
+        /// The fields that are renamed, retyped or added.
+        fields: Vec<RedefineField<'a>>,
+    },
+
+    /// A `REDEFINE EDGE` statement.
+    RedefineEdge {
+        /// The name of the edge.
+        name: Box<Ast<'a>>,
+
+        /// The if exists flag.
+        if_exists: bool,
+
+        /// The name the edge is renamed to.
+        new_name: Option<Box<Ast<'a>>>,
+
+        /// The database the edge belongs to.
+        database: Option<Box<Ast<'a>>>,
+
+        /// The fields that are renamed, retyped or added.
+        fields: Vec<RedefineField<'a>>,
+    },
+
+    /// A `REDEFINE TYPE` statement.
+    RedefineType {
+        /// The name of the type.
+        name: Box<Ast<'a>>,
+
+        /// The if exists flag.
+        if_exists: bool,
+
+        /// The name the type is renamed to.
+        new_name: Option<Box<Ast<'a>>>,
+
+        /// The database the type belongs to.
+        database: Option<Box<Ast<'a>>>,
+
+        /// The fields that are renamed, retyped or added.
+        fields: Vec<RedefineField<'a>>,
+    },
+
+    /// A `REDEFINE ENUM` statement.
+    RedefineEnum {
+        /// The name of the enum.
+        name: Box<Ast<'a>>,
+
+        /// The if exists flag.
+        if_exists: bool,
+
+        /// The name the enum is renamed to.
+        new_name: Option<Box<Ast<'a>>>,
+
+        /// The database the enum belongs to.
+        database: Option<Box<Ast<'a>>>,
+
+        /// The new variants of the enum, each with the variant it renames if any, e.g. `b` in
+        /// `VARIANTS a AS b`.
+        variants: Option<Vec<(Ast<'a>, Option<Ast<'a>>)>>,
+    },
+
+    /// A `REDEFINE INDEX` statement.
+    RedefineIndex {
+        /// The name of the index.
+        name: Box<Ast<'a>>,
+
+        /// The if exists flag.
+        if_exists: bool,
+
+        /// The name the index is renamed to.
+        new_name: Option<Box<Ast<'a>>>,
+
+        /// The database the index belongs to.
+        database: Option<Box<Ast<'a>>>,
+
+        /// The table the index belongs to.
+        table: Box<Ast<'a>>,
+
+        /// The new columns of the index.
+        columns: Option<Vec<Ast<'a>>>,
+
+        /// The new function to call when the index is created.
+        function: Option<Box<Ast<'a>>>,
+    },
+
+    /// A `REDEFINE PARAM` statement.
+    RedefineParam {
+        /// The name of the parameter.
+        name: Box<Ast<'a>>,
+
+        /// The if exists flag.
+        if_exists: bool,
+
+        /// The name the parameter is renamed to.
+        new_name: Option<Box<Ast<'a>>>,
+
+        /// The database the parameter belongs to.
+        database: Option<Box<Ast<'a>>>,
+
+        /// The new type of the parameter.
+        r#type: Option<TypeSig<'a>>,
+
+        /// The new value of the parameter.
+        value: Option<Box<Ast<'a>>>,
+    },
+
     /// A `USE` statement.
     Use {
         /// The database the parameter belongs to.
@@ -789,12 +966,20 @@ pub enum AstKind<'a> {
     /// A `CONTINUE` statement.
     Continue,
 
+    /// A `RETURN` statement, with the value it returns.
+    Return(Option<Box<Ast<'a>>>),
+
     /// A program.
     Program(Vec<Ast<'a>>),
+
+    /// A statement or expression that could not be parsed, left in place of it when the parser
+    /// recovers from a syntax error.
+    Error,
 }
 
 /// A field of a table or edge.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct Field<'a> {
     /// The name of the field.
     pub name: Box<Ast<'a>>,
@@ -815,8 +1000,42 @@ pub struct Field<'a> {
     pub unique: bool,
 }
 
+/// The mode of a table, which says what is done with the fields a record has but the table does
+/// not declare.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub enum TableMode<'a> {
+    /// A `SCHEMAFULL` table, which rejects undeclared fields. This is the default.
+    #[default]
+    Schemafull,
+
+    /// A `SCHEMALESS` table, which stores undeclared fields as they are given.
+    Schemaless,
+
+    /// A `FLEXIBLE` table, e.g. `FLEXIBLE TYPE string`, which stores undeclared fields as values of
+    /// the catch-all type, or of any type if it has none.
+    Flexible(Option<TypeSig<'a>>),
+}
+
+/// A field of a `REDEFINE TABLE`, `REDEFINE EDGE` or `REDEFINE TYPE`.
+///
+/// A field that is not already defined is added, e.g. `age` in `FIELDS name AS full_name, age TYPE u8`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "'de: 'a"))]
+pub struct RedefineField<'a> {
+    /// The name of the field.
+    pub name: Box<Ast<'a>>,
+
+    /// The name the field is renamed to.
+    pub new_name: Option<Box<Ast<'a>>>,
+
+    /// The new type of the field.
+    pub r#type: Option<TypeSig<'a>>,
+}
+
 /// A type signature.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub enum TypeSig<'a> {
     /// An array type, e.g. `[i32: 10]`.
     Array {
@@ -850,7 +1069,8 @@ pub enum TypeSig<'a> {
 }
 
 /// A partial else if part of an if expression.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub struct ElseIfPart<'a> {
     /// The condition of the else if part.
     pub condition: Box<Ast<'a>>,
@@ -860,7 +1080,8 @@ pub struct ElseIfPart<'a> {
 }
 
 /// A column or fold column of a `SELECT` expression.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub enum SelectColumn<'a> {
     /// A column of the select operation.
     Column(Box<Ast<'a>>),
@@ -876,7 +1097,8 @@ pub enum SelectColumn<'a> {
 }
 
 /// The transform of a select operation.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "'de: 'a"))]
 pub enum SelectTransform<'a> {
     /// A `WITH NO INDEX` transform.
     WithNoIndex,
@@ -907,7 +1129,7 @@ pub enum SelectTransform<'a> {
 }
 
 /// The direction of an ordering.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Direction {
     /// An ascending ordering.
     Ascending,
@@ -917,7 +1139,7 @@ pub enum Direction {
 }
 
 /// The arrow direction of a relate operation.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum RelateArrow {
     /// A `<-` arrow.
     Left,
@@ -933,7 +1155,7 @@ pub enum RelateArrow {
 }
 
 /// The assignment operator of an update operation.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum UpdateAssign {
     /// A direct assignment.
     Direct,
@@ -1024,6 +1246,22 @@ impl<'a> Ast<'a> {
         }
     }
 
+    /// Sets the type of the AST node.
+    pub fn set_tag_type(&mut self, r#type: Type) {
+        if let Some(tag) = self.tag.as_mut() {
+            tag.set_type(r#type);
+        } else {
+            let mut tag = AnalysisTag::default();
+            tag.set_type(r#type);
+            self.tag = Some(tag);
+        }
+    }
+
+    /// Gets the type of the AST node if type inference has run on it.
+    pub fn get_type(&self) -> Option<&Type> {
+        self.tag.as_ref().and_then(AnalysisTag::get_type)
+    }
+
     /// Gets the tag of the AST node.
     pub fn get_tag(&self) -> Option<&AnalysisTag> {
         self.tag.as_ref()
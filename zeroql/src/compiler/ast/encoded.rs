@@ -0,0 +1,191 @@
+use std::fmt;
+
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Ast, AstResult};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// An [`Ast`] encoded as CBOR.
+///
+/// An `Ast` borrows its strings from the source it was parsed from. An `EncodedAst` owns its
+/// bytes instead, so a statement can be parsed once and then be cached or put in a log entry
+/// without keeping its source around. Decoding borrows the strings of the AST from the encoded
+/// bytes rather than copying them.
+///
+/// Only the syntax is encoded. Analysis tags hold the symbols and schemas of the analyzer, which
+/// are not serializable, so a decoded AST is untagged and has to be analyzed again, against the
+/// schema of wherever it is decoded, before it is executed.
+///
+/// An `EncodedAst` is itself serialized as a CBOR byte string, so it can be nested in other
+/// serialized values.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EncodedAst {
+    bytes: Vec<u8>,
+}
+
+/// Deserializes the bytes of an [`EncodedAst`].
+struct EncodedAstVisitor;
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl EncodedAst {
+    /// Encodes an AST.
+    ///
+    /// Fails if the AST contains a `Temp` node, which only the parser produces while parsing.
+    pub fn encode(ast: &Ast) -> AstResult<Self> {
+        let bytes = cbor4ii::serde::to_vec(vec![], ast)?;
+        Ok(Self { bytes })
+    }
+
+    /// Decodes the AST, borrowing its strings from the encoded bytes.
+    pub fn decode(&self) -> AstResult<Ast<'_>> {
+        Ok(cbor4ii::serde::from_slice(&self.bytes)?)
+    }
+
+    /// Creates an encoded AST from bytes produced by [`Self::encode`].
+    ///
+    /// The bytes are not checked until they are decoded.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+
+    /// Gets the encoded bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Serialize for EncodedAst {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for EncodedAst {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_byte_buf(EncodedAstVisitor)
+    }
+}
+
+impl<'de> Visitor<'de> for EncodedAstVisitor {
+    type Value = EncodedAst;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte string")
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        Ok(EncodedAst::from_bytes(bytes.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(EncodedAst::from_bytes(bytes))
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{AstError, AstKind},
+        parser::Parser,
+        sema::NameResolver,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_encoded_ast_round_trips() -> anyhow::Result<()> {
+        let ast = Parser::new(
+            r#"
+            DEFINE TABLE person FIELDS name TYPE string ASSERT name != '', age TYPE u8? VALUE 0
+            LET $people TYPE [person] = SELECT *, fold age AS total FROM person WHERE name ~ //^a//i
+            FOR $p IN $people DO
+                IF $p.age > 65 THEN
+                    UPDATE `person` WHERE name == b'abc' SET age += 1
+                ELSE IF $p?.age <= 3.5 THEN
+                    CREATE person SET (name, tags) VALUES ({ first: 'baby' }, [1, 2..=3])
+                ELSE
+                    person:1 -> likes -> person:2
+                END
+            END
+            "#,
+            50,
+        )
+        .parse_program()
+        .into_result()?;
+
+        let encoded = EncodedAst::encode(&ast)?;
+        assert_eq!(encoded.decode()?, ast);
+
+        // The encoded AST can be nested in other serialized values.
+        let nested = cbor4ii::serde::to_vec(vec![], &(1, &encoded))?;
+        let (_, decoded): (u8, EncodedAst) = cbor4ii::serde::from_slice(&nested)?;
+        assert_eq!(decoded, encoded);
+        assert_eq!(decoded.decode()?, ast);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encoded_ast_borrows_from_bytes() -> anyhow::Result<()> {
+        let ast = Parser::new("person", 10).parse_program().into_result()?;
+        let encoded = EncodedAst::encode(&ast)?;
+        let decoded = encoded.decode()?;
+
+        let AstKind::Identifier(name) = decoded.kind.unwrap_program()[0].kind else {
+            panic!("identifier expected");
+        };
+
+        assert_eq!(name, "person");
+        assert!(encoded.as_bytes().as_ptr_range().contains(&name.as_ptr()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encoded_ast_leaves_out_tags() -> anyhow::Result<()> {
+        let mut ast = Parser::new("LET $a = 1; $a", 10)
+            .parse_program()
+            .into_result()?;
+
+        NameResolver::new().analyze(&mut ast)?;
+        assert!(ast.kind.clone().unwrap_program()[0].get_tag().is_some());
+
+        let encoded = EncodedAst::encode(&ast)?;
+        let decoded = encoded.decode()?;
+        assert!(decoded.kind.unwrap_program()[0].get_tag().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encoded_ast_fails() {
+        let temp = Ast::new(0..0, AstKind::Temp(None));
+        assert!(matches!(
+            EncodedAst::encode(&temp),
+            Err(AstError::Encode(_))
+        ));
+
+        let garbage = EncodedAst::from_bytes(vec![0xff, 0x00]);
+        assert!(matches!(garbage.decode(), Err(AstError::Decode(_))));
+    }
+}
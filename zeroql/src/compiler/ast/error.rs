@@ -0,0 +1,42 @@
+use thiserror::Error;
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// The result of encoding or decoding an AST.
+pub type AstResult<T> = Result<T, AstError>;
+
+/// An error that occurred while encoding or decoding an AST.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AstError {
+    /// The AST could not be encoded, e.g. because it still contains a `Temp` node.
+    #[error("failed to encode ast: {0}")]
+    Encode(String),
+
+    /// The bytes could not be decoded as an AST.
+    #[error("failed to decode ast: {0}")]
+    Decode(String),
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl<T> From<cbor4ii::serde::EncodeError<T>> for AstError
+where
+    T: std::fmt::Debug,
+{
+    fn from(err: cbor4ii::serde::EncodeError<T>) -> Self {
+        Self::Encode(format!("{:?}", err))
+    }
+}
+
+impl<T> From<cbor4ii::serde::DecodeError<T>> for AstError
+where
+    T: std::fmt::Debug,
+{
+    fn from(err: cbor4ii::serde::DecodeError<T>) -> Self {
+        Self::Decode(format!("{:?}", err))
+    }
+}
@@ -2,11 +2,17 @@
 
 #[allow(clippy::module_inception)]
 mod ast;
+mod encoded;
+mod error;
 mod tag;
+mod visitor;
 
 //--------------------------------------------------------------------------------------------------
 // Exports
 //--------------------------------------------------------------------------------------------------
 
 pub use ast::*;
+pub use encoded::*;
+pub use error::*;
 pub use tag::*;
+pub use visitor::*;
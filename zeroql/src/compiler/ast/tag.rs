@@ -1,4 +1,5 @@
-use crate::sema::{Symbols, VersionedSchema};
+use crate::sema::{Symbols, Type, VersionedSchema};
+
 //--------------------------------------------------------------------------------------------------
 // Types
 //--------------------------------------------------------------------------------------------------
@@ -8,6 +9,7 @@ use crate::sema::{Symbols, VersionedSchema};
 pub struct AnalysisTag {
     symbols: Option<Symbols>,
     schema: Option<VersionedSchema>,
+    r#type: Option<Type>,
 }
 
 //--------------------------------------------------------------------------------------------------
@@ -25,6 +27,11 @@ impl AnalysisTag {
         self.schema = Some(schema);
     }
 
+    /// Sets the type for the analysis tag.
+    pub fn set_type(&mut self, r#type: Type) {
+        self.r#type = Some(r#type);
+    }
+
     /// Gets the symbols for the analysis tag.
     pub fn get_symbols(&self) -> Option<&Symbols> {
         self.symbols.as_ref()
@@ -34,4 +41,9 @@ impl AnalysisTag {
     pub fn get_schema(&self) -> Option<&VersionedSchema> {
         self.schema.as_ref()
     }
+
+    /// Gets the type for the analysis tag.
+    pub fn get_type(&self) -> Option<&Type> {
+        self.r#type.as_ref()
+    }
 }
@@ -0,0 +1,1830 @@
+use super::{
+    Ast, AstKind::*, ElseIfPart, Field, SelectColumn, SelectTransform, TableMode, TypeSig,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Traits
+//--------------------------------------------------------------------------------------------------
+
+/// A pass that visits the nodes of an [`Ast`] without changing them.
+///
+/// Every method defaults to the `walk_*` function of the same name, which visits the children of
+/// the node in the order they appear in the source. A pass overrides the methods of the nodes it
+/// cares about and calls the matching `walk_*` function wherever it wants the walk to continue.
+///
+/// `Temp` nodes are intermediates of the parser and have no children to visit.
+pub trait Visitor<'a> {
+    /// The error that stops the walk.
+    type Error;
+
+    /// Visits an AST node.
+    fn visit_ast(&mut self, ast: &Ast<'a>) -> Result<(), Self::Error> {
+        walk_ast(self, ast)
+    }
+
+    /// Visits a type signature.
+    fn visit_type_sig(&mut self, type_sig: &TypeSig<'a>) -> Result<(), Self::Error> {
+        walk_type_sig(self, type_sig)
+    }
+
+    /// Visits a field of a table or edge.
+    fn visit_field(&mut self, field: &Field<'a>) -> Result<(), Self::Error> {
+        walk_field(self, field)
+    }
+
+    /// Visits a column of a `SELECT` expression.
+    fn visit_select_column(&mut self, column: &SelectColumn<'a>) -> Result<(), Self::Error> {
+        walk_select_column(self, column)
+    }
+
+    /// Visits a transform of a `SELECT` expression.
+    fn visit_select_transform(
+        &mut self,
+        transform: &SelectTransform<'a>,
+    ) -> Result<(), Self::Error> {
+        walk_select_transform(self, transform)
+    }
+
+    /// Visits an else if part of an `IF` expression.
+    fn visit_else_if_part(&mut self, else_if: &ElseIfPart<'a>) -> Result<(), Self::Error> {
+        walk_else_if_part(self, else_if)
+    }
+}
+
+/// A pass that visits the nodes of an [`Ast`] and may change them.
+///
+/// This is the mutable counterpart of [`Visitor`], with `walk_*_mut` functions in place of the
+/// `walk_*` ones.
+pub trait VisitorMut<'a> {
+    /// The error that stops the walk.
+    type Error;
+
+    /// Visits an AST node.
+    fn visit_ast(&mut self, ast: &mut Ast<'a>) -> Result<(), Self::Error> {
+        walk_ast_mut(self, ast)
+    }
+
+    /// Visits a type signature.
+    fn visit_type_sig(&mut self, type_sig: &mut TypeSig<'a>) -> Result<(), Self::Error> {
+        walk_type_sig_mut(self, type_sig)
+    }
+
+    /// Visits a field of a table or edge.
+    fn visit_field(&mut self, field: &mut Field<'a>) -> Result<(), Self::Error> {
+        walk_field_mut(self, field)
+    }
+
+    /// Visits a column of a `SELECT` expression.
+    fn visit_select_column(&mut self, column: &mut SelectColumn<'a>) -> Result<(), Self::Error> {
+        walk_select_column_mut(self, column)
+    }
+
+    /// Visits a transform of a `SELECT` expression.
+    fn visit_select_transform(
+        &mut self,
+        transform: &mut SelectTransform<'a>,
+    ) -> Result<(), Self::Error> {
+        walk_select_transform_mut(self, transform)
+    }
+
+    /// Visits an else if part of an `IF` expression.
+    fn visit_else_if_part(&mut self, else_if: &mut ElseIfPart<'a>) -> Result<(), Self::Error> {
+        walk_else_if_part_mut(self, else_if)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Visits the children of an AST node.
+pub fn walk_ast<'a, V>(visitor: &mut V, ast: &Ast<'a>) -> Result<(), V::Error>
+where
+    V: Visitor<'a> + ?Sized,
+{
+    match &ast.kind {
+        Temp(_)
+        | Wildcard
+        | Identifier(_)
+        | Variable(_)
+        | IntegerLiteral(_)
+        | FloatLiteral(_)
+        | StringLiteral(_)
+        | ByteStringLiteral(_)
+        | RegexLiteral { .. }
+        | BooleanLiteral(_)
+        | NoneLiteral
+        | ModuleBlock(_)
+        | BeginTransaction
+        | CommitTransaction
+        | CancelTransaction
+        | Break
+        | Continue
+        | Return(None)
+        | Error => {}
+        ScopedIdentifier(asts) | ListLiteral(asts) | TupleLiteral(asts) | Program(asts) => {
+            for ast in asts {
+                visitor.visit_ast(ast)?;
+            }
+        }
+        ObjectLiteral(entries) => {
+            for (key, value) in entries {
+                visitor.visit_ast(key)?;
+                visitor.visit_ast(value)?;
+            }
+        }
+        Index { subject, index } => {
+            visitor.visit_ast(subject)?;
+            visitor.visit_ast(index)?;
+        }
+        FunctionArg { name, value } => {
+            if let Some(name) = name {
+                visitor.visit_ast(name)?;
+            }
+            visitor.visit_ast(value)?;
+        }
+        FunctionCall { subject, args } => {
+            visitor.visit_ast(subject)?;
+            for arg in args {
+                visitor.visit_ast(arg)?;
+            }
+        }
+        LogicalNotOp(ast) | BitwiseNotOp(ast) | PlusSignOp(ast) | MinusSignOp(ast)
+        | Return(Some(ast)) => {
+            visitor.visit_ast(ast)?;
+        }
+        DotAccessOp { subject, field } | SafeNavigationAccessOp { subject, field } => {
+            visitor.visit_ast(subject)?;
+            visitor.visit_ast(field)?;
+        }
+        DotAccessWildcardOp { subject } => {
+            visitor.visit_ast(subject)?;
+        }
+        IdOp(a, b)
+        | ExponentiationOp(a, b)
+        | MultiplicationOp(a, b)
+        | DivisionOp(a, b)
+        | ModulusOp(a, b)
+        | AdditionOp(a, b)
+        | SubtractionOp(a, b)
+        | LeftShiftOp(a, b)
+        | RightShiftOp(a, b)
+        | MatchOp(a, b)
+        | NotMatchOp(a, b)
+        | SimilarityOp(a, b)
+        | LessThanOp(a, b)
+        | GreaterThanOp(a, b)
+        | LessThanEqualToOp(a, b)
+        | GreaterThanEqualToOp(a, b)
+        | InOp(a, b)
+        | NotInOp(a, b)
+        | ContainsOp(a, b)
+        | NotContainsOp(a, b)
+        | ContainsNoneOp(a, b)
+        | ContainsAllOp(a, b)
+        | ContainsAnyOp(a, b)
+        | EqualToOp(a, b)
+        | IsOp(a, b)
+        | IsNotOp(a, b)
+        | BitwiseAndOp(a, b)
+        | BitwiseXorOp(a, b)
+        | BitwiseOrOp(a, b)
+        | LogicalAndOp(a, b)
+        | LogicalOrOp(a, b)
+        | NullCoalesceOp(a, b)
+        | RangeOp(a, b)
+        | RangeInclusiveOp(a, b) => {
+            visitor.visit_ast(a)?;
+            visitor.visit_ast(b)?;
+        }
+        UnboundedRangeOp(start) => {
+            if let Some(start) = start {
+                visitor.visit_ast(start)?;
+            }
+        }
+        SingleRelateId { subject, alias } => {
+            visitor.visit_ast(subject)?;
+            if let Some(alias) = alias {
+                visitor.visit_ast(alias)?;
+            }
+        }
+        RelateEdgeId {
+            subject,
+            depth,
+            alias,
+        } => {
+            visitor.visit_ast(subject)?;
+            if let Some(depth) = depth {
+                visitor.visit_ast(depth)?;
+            }
+            if let Some(alias) = alias {
+                visitor.visit_ast(alias)?;
+            }
+        }
+        RelateOp {
+            left, edge, right, ..
+        } => {
+            visitor.visit_ast(left)?;
+            visitor.visit_ast(edge)?;
+            visitor.visit_ast(right)?;
+        }
+        AliasOp { subject, alias } => {
+            visitor.visit_ast(subject)?;
+            visitor.visit_ast(alias)?;
+        }
+        Create {
+            subject,
+            columns,
+            values,
+        } => {
+            visitor.visit_ast(subject)?;
+            for column in columns {
+                visitor.visit_ast(column)?;
+            }
+            for ast in values.iter().flatten() {
+                visitor.visit_ast(ast)?;
+            }
+        }
+        Relate {
+            relate_op,
+            columns,
+            value,
+        } => {
+            visitor.visit_ast(relate_op)?;
+            for column in columns {
+                visitor.visit_ast(column)?;
+            }
+            for ast in value {
+                visitor.visit_ast(ast)?;
+            }
+        }
+        Delete {
+            target,
+            where_guard,
+        } => {
+            visitor.visit_ast(target)?;
+            if let Some(where_guard) = where_guard {
+                visitor.visit_ast(where_guard)?;
+            }
+        }
+        Update {
+            target,
+            where_guard,
+            column_ops,
+        } => {
+            visitor.visit_ast(target)?;
+            if let Some(where_guard) = where_guard {
+                visitor.visit_ast(where_guard)?;
+            }
+            for (column, _, value) in column_ops {
+                visitor.visit_ast(column)?;
+                visitor.visit_ast(value)?;
+            }
+        }
+        Select {
+            fields,
+            omit,
+            from,
+            transforms,
+        } => {
+            for field in fields {
+                visitor.visit_select_column(field)?;
+            }
+            for ast in omit.iter().chain(from) {
+                visitor.visit_ast(ast)?;
+            }
+            for transform in transforms {
+                visitor.visit_select_transform(transform)?;
+            }
+        }
+        RemoveNamespace { subject, .. } | DescribeNamespace { subject, .. } => {
+            visitor.visit_ast(subject)?;
+        }
+        RemoveDatabase {
+            subject,
+            namespace: database,
+            ..
+        }
+        | RemoveTable {
+            subject, database, ..
+        }
+        | RemoveEdge {
+            subject, database, ..
+        }
+        | RemoveType {
+            subject, database, ..
+        }
+        | RemoveEnum {
+            subject, database, ..
+        }
+        | RemoveModule {
+            subject, database, ..
+        }
+        | RemoveFunction {
+            subject, database, ..
+        }
+        | RemoveParam {
+            subject, database, ..
+        }
+        | DescribeDatabase {
+            subject,
+            namespace: database,
+            ..
+        }
+        | DescribeTable {
+            subject, database, ..
+        }
+        | DescribeEdge {
+            subject, database, ..
+        }
+        | DescribeType {
+            subject, database, ..
+        }
+        | DescribeEnum {
+            subject, database, ..
+        }
+        | DescribeModule {
+            subject, database, ..
+        }
+        | DescribeFunction {
+            subject, database, ..
+        }
+        | DescribeParam {
+            subject, database, ..
+        } => {
+            visitor.visit_ast(subject)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+        }
+        RemoveIndex {
+            subject,
+            table,
+            database,
+            ..
+        }
+        | DescribeIndex {
+            subject,
+            table,
+            database,
+            ..
+        } => {
+            visitor.visit_ast(subject)?;
+            visitor.visit_ast(table)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+        }
+        For {
+            variable,
+            iterator,
+            body,
+        } => {
+            visitor.visit_ast(variable)?;
+            visitor.visit_ast(iterator)?;
+            visitor.visit_ast(body)?;
+        }
+        While { condition, body } => {
+            visitor.visit_ast(condition)?;
+            visitor.visit_ast(body)?;
+        }
+        If {
+            condition,
+            then,
+            else_ifs,
+            r#else,
+        } => {
+            visitor.visit_ast(condition)?;
+            visitor.visit_ast(then)?;
+            for else_if in else_ifs {
+                visitor.visit_else_if_part(else_if)?;
+            }
+            if let Some(r#else) = r#else {
+                visitor.visit_ast(r#else)?;
+            }
+        }
+        Let {
+            name,
+            r#type,
+            value,
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(r#type) = r#type {
+                visitor.visit_type_sig(r#type)?;
+            }
+            visitor.visit_ast(value)?;
+        }
+        Set {
+            variable, value, ..
+        } => {
+            visitor.visit_ast(variable)?;
+            visitor.visit_ast(value)?;
+        }
+        DefineNamespace { name, .. } => {
+            visitor.visit_ast(name)?;
+        }
+        DefineDatabase {
+            name,
+            namespace: database,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+        }
+        DefineTable {
+            name,
+            database,
+            mode,
+            fields,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            if let TableMode::Flexible(Some(r#type)) = mode {
+                visitor.visit_type_sig(r#type)?;
+            }
+            for field in fields {
+                visitor.visit_field(field)?;
+            }
+        }
+        DefineEdge {
+            name,
+            database,
+            fields,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for field in fields {
+                visitor.visit_field(field)?;
+            }
+        }
+        DefineType {
+            name,
+            database,
+            fields,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for (name, r#type) in fields {
+                visitor.visit_ast(name)?;
+                visitor.visit_type_sig(r#type)?;
+            }
+        }
+        DefineEnum {
+            name,
+            database,
+            variants,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for variant in variants {
+                visitor.visit_ast(variant)?;
+            }
+        }
+        DefineIndex {
+            name,
+            database,
+            table,
+            columns,
+            function,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            visitor.visit_ast(table)?;
+            for column in columns {
+                visitor.visit_ast(column)?;
+            }
+            if let Some(function) = function {
+                visitor.visit_ast(function)?;
+            }
+        }
+        DefineModule {
+            name,
+            database,
+            block,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            visitor.visit_ast(block)?;
+        }
+        DefineFunction {
+            name,
+            database,
+            params,
+            r#type,
+            body,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for (param, r#type) in params {
+                visitor.visit_ast(param)?;
+                visitor.visit_type_sig(r#type)?;
+            }
+            if let Some(r#type) = r#type {
+                visitor.visit_type_sig(r#type)?;
+            }
+            visitor.visit_ast(body)?;
+        }
+        DefineParam {
+            name,
+            database,
+            r#type,
+            value,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            if let Some(r#type) = r#type {
+                visitor.visit_type_sig(r#type)?;
+            }
+            visitor.visit_ast(value)?;
+        }
+        RedefineTable {
+            name,
+            new_name,
+            database,
+            fields,
+            ..
+        }
+        | RedefineEdge {
+            name,
+            new_name,
+            database,
+            fields,
+            ..
+        }
+        | RedefineType {
+            name,
+            new_name,
+            database,
+            fields,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(new_name) = new_name {
+                visitor.visit_ast(new_name)?;
+            }
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for field in fields {
+                visitor.visit_ast(&field.name)?;
+                if let Some(new_name) = &field.new_name {
+                    visitor.visit_ast(new_name)?;
+                }
+                if let Some(r#type) = &field.r#type {
+                    visitor.visit_type_sig(r#type)?;
+                }
+            }
+        }
+        RedefineEnum {
+            name,
+            new_name,
+            database,
+            variants,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(new_name) = new_name {
+                visitor.visit_ast(new_name)?;
+            }
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for (variant, new_name) in variants.iter().flatten() {
+                visitor.visit_ast(variant)?;
+                if let Some(new_name) = new_name {
+                    visitor.visit_ast(new_name)?;
+                }
+            }
+        }
+        RedefineIndex {
+            name,
+            new_name,
+            database,
+            table,
+            columns,
+            function,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(new_name) = new_name {
+                visitor.visit_ast(new_name)?;
+            }
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            visitor.visit_ast(table)?;
+            for column in columns.iter().flatten() {
+                visitor.visit_ast(column)?;
+            }
+            if let Some(function) = function {
+                visitor.visit_ast(function)?;
+            }
+        }
+        RedefineParam {
+            name,
+            new_name,
+            database,
+            r#type,
+            value,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(new_name) = new_name {
+                visitor.visit_ast(new_name)?;
+            }
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            if let Some(r#type) = r#type {
+                visitor.visit_type_sig(r#type)?;
+            }
+            if let Some(value) = value {
+                visitor.visit_ast(value)?;
+            }
+        }
+        Use { database } => {
+            visitor.visit_ast(database)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Visits the children of a type signature.
+pub fn walk_type_sig<'a, V>(visitor: &mut V, type_sig: &TypeSig<'a>) -> Result<(), V::Error>
+where
+    V: Visitor<'a> + ?Sized,
+{
+    match type_sig {
+        TypeSig::Array { r#type, length } => {
+            visitor.visit_type_sig(r#type)?;
+            visitor.visit_ast(length)?;
+        }
+        TypeSig::List(r#type) | TypeSig::Option(r#type) => {
+            visitor.visit_type_sig(r#type)?;
+        }
+        TypeSig::Tuple(types) => {
+            for r#type in types {
+                visitor.visit_type_sig(r#type)?;
+            }
+        }
+        TypeSig::Generic { name, parameters } => {
+            visitor.visit_ast(name)?;
+            for parameter in parameters {
+                visitor.visit_type_sig(parameter)?;
+            }
+        }
+        TypeSig::Basic(name) => {
+            visitor.visit_ast(name)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Visits the children of a field of a table or edge.
+pub fn walk_field<'a, V>(visitor: &mut V, field: &Field<'a>) -> Result<(), V::Error>
+where
+    V: Visitor<'a> + ?Sized,
+{
+    visitor.visit_ast(&field.name)?;
+    visitor.visit_type_sig(&field.r#type)?;
+    if let Some(default) = &field.default {
+        visitor.visit_ast(default)?;
+    }
+    for assertion in &field.assertions {
+        visitor.visit_ast(assertion)?;
+    }
+
+    Ok(())
+}
+
+/// Visits the children of a column of a `SELECT` expression.
+pub fn walk_select_column<'a, V>(visitor: &mut V, column: &SelectColumn<'a>) -> Result<(), V::Error>
+where
+    V: Visitor<'a> + ?Sized,
+{
+    match column {
+        SelectColumn::Column(ast) => {
+            visitor.visit_ast(ast)?;
+        }
+        SelectColumn::Fold { subject, alias } => {
+            visitor.visit_ast(subject)?;
+            if let Some(alias) = alias {
+                visitor.visit_ast(alias)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Visits the children of a transform of a `SELECT` expression.
+pub fn walk_select_transform<'a, V>(
+    visitor: &mut V,
+    transform: &SelectTransform<'a>,
+) -> Result<(), V::Error>
+where
+    V: Visitor<'a> + ?Sized,
+{
+    match transform {
+        SelectTransform::WithNoIndex => {}
+        SelectTransform::WithIndexes(asts)
+        | SelectTransform::GroupBy(asts)
+        | SelectTransform::OrderBy { fields: asts, .. } => {
+            for ast in asts {
+                visitor.visit_ast(ast)?;
+            }
+        }
+        SelectTransform::WhereGuard(ast)
+        | SelectTransform::LimitTo(ast)
+        | SelectTransform::StartAt(ast) => {
+            visitor.visit_ast(ast)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Visits the children of an else if part of an `IF` expression.
+pub fn walk_else_if_part<'a, V>(visitor: &mut V, else_if: &ElseIfPart<'a>) -> Result<(), V::Error>
+where
+    V: Visitor<'a> + ?Sized,
+{
+    visitor.visit_ast(&else_if.condition)?;
+    visitor.visit_ast(&else_if.body)
+}
+
+/// Mutably visits the children of an AST node.
+pub fn walk_ast_mut<'a, V>(visitor: &mut V, ast: &mut Ast<'a>) -> Result<(), V::Error>
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    match &mut ast.kind {
+        Temp(_)
+        | Wildcard
+        | Identifier(_)
+        | Variable(_)
+        | IntegerLiteral(_)
+        | FloatLiteral(_)
+        | StringLiteral(_)
+        | ByteStringLiteral(_)
+        | RegexLiteral { .. }
+        | BooleanLiteral(_)
+        | NoneLiteral
+        | ModuleBlock(_)
+        | BeginTransaction
+        | CommitTransaction
+        | CancelTransaction
+        | Break
+        | Continue
+        | Return(None)
+        | Error => {}
+        ScopedIdentifier(asts) | ListLiteral(asts) | TupleLiteral(asts) | Program(asts) => {
+            for ast in asts {
+                visitor.visit_ast(ast)?;
+            }
+        }
+        ObjectLiteral(entries) => {
+            for (key, value) in entries {
+                visitor.visit_ast(key)?;
+                visitor.visit_ast(value)?;
+            }
+        }
+        Index { subject, index } => {
+            visitor.visit_ast(subject)?;
+            visitor.visit_ast(index)?;
+        }
+        FunctionArg { name, value } => {
+            if let Some(name) = name {
+                visitor.visit_ast(name)?;
+            }
+            visitor.visit_ast(value)?;
+        }
+        FunctionCall { subject, args } => {
+            visitor.visit_ast(subject)?;
+            for arg in args {
+                visitor.visit_ast(arg)?;
+            }
+        }
+        LogicalNotOp(ast) | BitwiseNotOp(ast) | PlusSignOp(ast) | MinusSignOp(ast)
+        | Return(Some(ast)) => {
+            visitor.visit_ast(ast)?;
+        }
+        DotAccessOp { subject, field } | SafeNavigationAccessOp { subject, field } => {
+            visitor.visit_ast(subject)?;
+            visitor.visit_ast(field)?;
+        }
+        DotAccessWildcardOp { subject } => {
+            visitor.visit_ast(subject)?;
+        }
+        IdOp(a, b)
+        | ExponentiationOp(a, b)
+        | MultiplicationOp(a, b)
+        | DivisionOp(a, b)
+        | ModulusOp(a, b)
+        | AdditionOp(a, b)
+        | SubtractionOp(a, b)
+        | LeftShiftOp(a, b)
+        | RightShiftOp(a, b)
+        | MatchOp(a, b)
+        | NotMatchOp(a, b)
+        | SimilarityOp(a, b)
+        | LessThanOp(a, b)
+        | GreaterThanOp(a, b)
+        | LessThanEqualToOp(a, b)
+        | GreaterThanEqualToOp(a, b)
+        | InOp(a, b)
+        | NotInOp(a, b)
+        | ContainsOp(a, b)
+        | NotContainsOp(a, b)
+        | ContainsNoneOp(a, b)
+        | ContainsAllOp(a, b)
+        | ContainsAnyOp(a, b)
+        | EqualToOp(a, b)
+        | IsOp(a, b)
+        | IsNotOp(a, b)
+        | BitwiseAndOp(a, b)
+        | BitwiseXorOp(a, b)
+        | BitwiseOrOp(a, b)
+        | LogicalAndOp(a, b)
+        | LogicalOrOp(a, b)
+        | NullCoalesceOp(a, b)
+        | RangeOp(a, b)
+        | RangeInclusiveOp(a, b) => {
+            visitor.visit_ast(a)?;
+            visitor.visit_ast(b)?;
+        }
+        UnboundedRangeOp(start) => {
+            if let Some(start) = start {
+                visitor.visit_ast(start)?;
+            }
+        }
+        SingleRelateId { subject, alias } => {
+            visitor.visit_ast(subject)?;
+            if let Some(alias) = alias {
+                visitor.visit_ast(alias)?;
+            }
+        }
+        RelateEdgeId {
+            subject,
+            depth,
+            alias,
+        } => {
+            visitor.visit_ast(subject)?;
+            if let Some(depth) = depth {
+                visitor.visit_ast(depth)?;
+            }
+            if let Some(alias) = alias {
+                visitor.visit_ast(alias)?;
+            }
+        }
+        RelateOp {
+            left, edge, right, ..
+        } => {
+            visitor.visit_ast(left)?;
+            visitor.visit_ast(edge)?;
+            visitor.visit_ast(right)?;
+        }
+        AliasOp { subject, alias } => {
+            visitor.visit_ast(subject)?;
+            visitor.visit_ast(alias)?;
+        }
+        Create {
+            subject,
+            columns,
+            values,
+        } => {
+            visitor.visit_ast(subject)?;
+            for column in columns {
+                visitor.visit_ast(column)?;
+            }
+            for ast in values.iter_mut().flatten() {
+                visitor.visit_ast(ast)?;
+            }
+        }
+        Relate {
+            relate_op,
+            columns,
+            value,
+        } => {
+            visitor.visit_ast(relate_op)?;
+            for column in columns {
+                visitor.visit_ast(column)?;
+            }
+            for ast in value {
+                visitor.visit_ast(ast)?;
+            }
+        }
+        Delete {
+            target,
+            where_guard,
+        } => {
+            visitor.visit_ast(target)?;
+            if let Some(where_guard) = where_guard {
+                visitor.visit_ast(where_guard)?;
+            }
+        }
+        Update {
+            target,
+            where_guard,
+            column_ops,
+        } => {
+            visitor.visit_ast(target)?;
+            if let Some(where_guard) = where_guard {
+                visitor.visit_ast(where_guard)?;
+            }
+            for (column, _, value) in column_ops {
+                visitor.visit_ast(column)?;
+                visitor.visit_ast(value)?;
+            }
+        }
+        Select {
+            fields,
+            omit,
+            from,
+            transforms,
+        } => {
+            for field in fields {
+                visitor.visit_select_column(field)?;
+            }
+            for ast in omit.iter_mut().chain(from) {
+                visitor.visit_ast(ast)?;
+            }
+            for transform in transforms {
+                visitor.visit_select_transform(transform)?;
+            }
+        }
+        RemoveNamespace { subject, .. } | DescribeNamespace { subject, .. } => {
+            visitor.visit_ast(subject)?;
+        }
+        RemoveDatabase {
+            subject,
+            namespace: database,
+            ..
+        }
+        | RemoveTable {
+            subject, database, ..
+        }
+        | RemoveEdge {
+            subject, database, ..
+        }
+        | RemoveType {
+            subject, database, ..
+        }
+        | RemoveEnum {
+            subject, database, ..
+        }
+        | RemoveModule {
+            subject, database, ..
+        }
+        | RemoveFunction {
+            subject, database, ..
+        }
+        | RemoveParam {
+            subject, database, ..
+        }
+        | DescribeDatabase {
+            subject,
+            namespace: database,
+            ..
+        }
+        | DescribeTable {
+            subject, database, ..
+        }
+        | DescribeEdge {
+            subject, database, ..
+        }
+        | DescribeType {
+            subject, database, ..
+        }
+        | DescribeEnum {
+            subject, database, ..
+        }
+        | DescribeModule {
+            subject, database, ..
+        }
+        | DescribeFunction {
+            subject, database, ..
+        }
+        | DescribeParam {
+            subject, database, ..
+        } => {
+            visitor.visit_ast(subject)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+        }
+        RemoveIndex {
+            subject,
+            table,
+            database,
+            ..
+        }
+        | DescribeIndex {
+            subject,
+            table,
+            database,
+            ..
+        } => {
+            visitor.visit_ast(subject)?;
+            visitor.visit_ast(table)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+        }
+        For {
+            variable,
+            iterator,
+            body,
+        } => {
+            visitor.visit_ast(variable)?;
+            visitor.visit_ast(iterator)?;
+            visitor.visit_ast(body)?;
+        }
+        While { condition, body } => {
+            visitor.visit_ast(condition)?;
+            visitor.visit_ast(body)?;
+        }
+        If {
+            condition,
+            then,
+            else_ifs,
+            r#else,
+        } => {
+            visitor.visit_ast(condition)?;
+            visitor.visit_ast(then)?;
+            for else_if in else_ifs {
+                visitor.visit_else_if_part(else_if)?;
+            }
+            if let Some(r#else) = r#else {
+                visitor.visit_ast(r#else)?;
+            }
+        }
+        Let {
+            name,
+            r#type,
+            value,
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(r#type) = r#type {
+                visitor.visit_type_sig(r#type)?;
+            }
+            visitor.visit_ast(value)?;
+        }
+        Set {
+            variable, value, ..
+        } => {
+            visitor.visit_ast(variable)?;
+            visitor.visit_ast(value)?;
+        }
+        DefineNamespace { name, .. } => {
+            visitor.visit_ast(name)?;
+        }
+        DefineDatabase {
+            name,
+            namespace: database,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+        }
+        DefineTable {
+            name,
+            database,
+            mode,
+            fields,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            if let TableMode::Flexible(Some(r#type)) = mode {
+                visitor.visit_type_sig(r#type)?;
+            }
+            for field in fields {
+                visitor.visit_field(field)?;
+            }
+        }
+        DefineEdge {
+            name,
+            database,
+            fields,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for field in fields {
+                visitor.visit_field(field)?;
+            }
+        }
+        DefineType {
+            name,
+            database,
+            fields,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for (name, r#type) in fields {
+                visitor.visit_ast(name)?;
+                visitor.visit_type_sig(r#type)?;
+            }
+        }
+        DefineEnum {
+            name,
+            database,
+            variants,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for variant in variants {
+                visitor.visit_ast(variant)?;
+            }
+        }
+        DefineIndex {
+            name,
+            database,
+            table,
+            columns,
+            function,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            visitor.visit_ast(table)?;
+            for column in columns {
+                visitor.visit_ast(column)?;
+            }
+            if let Some(function) = function {
+                visitor.visit_ast(function)?;
+            }
+        }
+        DefineModule {
+            name,
+            database,
+            block,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            visitor.visit_ast(block)?;
+        }
+        DefineFunction {
+            name,
+            database,
+            params,
+            r#type,
+            body,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for (param, r#type) in params {
+                visitor.visit_ast(param)?;
+                visitor.visit_type_sig(r#type)?;
+            }
+            if let Some(r#type) = r#type {
+                visitor.visit_type_sig(r#type)?;
+            }
+            visitor.visit_ast(body)?;
+        }
+        DefineParam {
+            name,
+            database,
+            r#type,
+            value,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            if let Some(r#type) = r#type {
+                visitor.visit_type_sig(r#type)?;
+            }
+            visitor.visit_ast(value)?;
+        }
+        RedefineTable {
+            name,
+            new_name,
+            database,
+            fields,
+            ..
+        }
+        | RedefineEdge {
+            name,
+            new_name,
+            database,
+            fields,
+            ..
+        }
+        | RedefineType {
+            name,
+            new_name,
+            database,
+            fields,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(new_name) = new_name {
+                visitor.visit_ast(new_name)?;
+            }
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for field in fields {
+                visitor.visit_ast(&mut field.name)?;
+                if let Some(new_name) = &mut field.new_name {
+                    visitor.visit_ast(new_name)?;
+                }
+                if let Some(r#type) = &mut field.r#type {
+                    visitor.visit_type_sig(r#type)?;
+                }
+            }
+        }
+        RedefineEnum {
+            name,
+            new_name,
+            database,
+            variants,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(new_name) = new_name {
+                visitor.visit_ast(new_name)?;
+            }
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            for (variant, new_name) in variants.iter_mut().flatten() {
+                visitor.visit_ast(variant)?;
+                if let Some(new_name) = new_name {
+                    visitor.visit_ast(new_name)?;
+                }
+            }
+        }
+        RedefineIndex {
+            name,
+            new_name,
+            database,
+            table,
+            columns,
+            function,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(new_name) = new_name {
+                visitor.visit_ast(new_name)?;
+            }
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            visitor.visit_ast(table)?;
+            for column in columns.iter_mut().flatten() {
+                visitor.visit_ast(column)?;
+            }
+            if let Some(function) = function {
+                visitor.visit_ast(function)?;
+            }
+        }
+        RedefineParam {
+            name,
+            new_name,
+            database,
+            r#type,
+            value,
+            ..
+        } => {
+            visitor.visit_ast(name)?;
+            if let Some(new_name) = new_name {
+                visitor.visit_ast(new_name)?;
+            }
+            if let Some(database) = database {
+                visitor.visit_ast(database)?;
+            }
+            if let Some(r#type) = r#type {
+                visitor.visit_type_sig(r#type)?;
+            }
+            if let Some(value) = value {
+                visitor.visit_ast(value)?;
+            }
+        }
+        Use { database } => {
+            visitor.visit_ast(database)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Mutably visits the children of a type signature.
+pub fn walk_type_sig_mut<'a, V>(visitor: &mut V, type_sig: &mut TypeSig<'a>) -> Result<(), V::Error>
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    match type_sig {
+        TypeSig::Array { r#type, length } => {
+            visitor.visit_type_sig(r#type)?;
+            visitor.visit_ast(length)?;
+        }
+        TypeSig::List(r#type) | TypeSig::Option(r#type) => {
+            visitor.visit_type_sig(r#type)?;
+        }
+        TypeSig::Tuple(types) => {
+            for r#type in types {
+                visitor.visit_type_sig(r#type)?;
+            }
+        }
+        TypeSig::Generic { name, parameters } => {
+            visitor.visit_ast(name)?;
+            for parameter in parameters {
+                visitor.visit_type_sig(parameter)?;
+            }
+        }
+        TypeSig::Basic(name) => {
+            visitor.visit_ast(name)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Mutably visits the children of a field of a table or edge.
+pub fn walk_field_mut<'a, V>(visitor: &mut V, field: &mut Field<'a>) -> Result<(), V::Error>
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    visitor.visit_ast(&mut field.name)?;
+    visitor.visit_type_sig(&mut field.r#type)?;
+    if let Some(default) = &mut field.default {
+        visitor.visit_ast(default)?;
+    }
+    for assertion in &mut field.assertions {
+        visitor.visit_ast(assertion)?;
+    }
+
+    Ok(())
+}
+
+/// Mutably visits the children of a column of a `SELECT` expression.
+pub fn walk_select_column_mut<'a, V>(
+    visitor: &mut V,
+    column: &mut SelectColumn<'a>,
+) -> Result<(), V::Error>
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    match column {
+        SelectColumn::Column(ast) => {
+            visitor.visit_ast(ast)?;
+        }
+        SelectColumn::Fold { subject, alias } => {
+            visitor.visit_ast(subject)?;
+            if let Some(alias) = alias {
+                visitor.visit_ast(alias)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Mutably visits the children of a transform of a `SELECT` expression.
+pub fn walk_select_transform_mut<'a, V>(
+    visitor: &mut V,
+    transform: &mut SelectTransform<'a>,
+) -> Result<(), V::Error>
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    match transform {
+        SelectTransform::WithNoIndex => {}
+        SelectTransform::WithIndexes(asts)
+        | SelectTransform::GroupBy(asts)
+        | SelectTransform::OrderBy { fields: asts, .. } => {
+            for ast in asts {
+                visitor.visit_ast(ast)?;
+            }
+        }
+        SelectTransform::WhereGuard(ast)
+        | SelectTransform::LimitTo(ast)
+        | SelectTransform::StartAt(ast) => {
+            visitor.visit_ast(ast)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Mutably visits the children of an else if part of an `IF` expression.
+pub fn walk_else_if_part_mut<'a, V>(
+    visitor: &mut V,
+    else_if: &mut ElseIfPart<'a>,
+) -> Result<(), V::Error>
+where
+    V: VisitorMut<'a> + ?Sized,
+{
+    visitor.visit_ast(&mut else_if.condition)?;
+    visitor.visit_ast(&mut else_if.body)
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use crate::parser::Parser;
+
+    use super::*;
+
+    /// Collects the names of the variables it visits.
+    #[derive(Default)]
+    struct Variables<'a>(Vec<&'a str>);
+
+    /// Renames every variable it visits to `$x`.
+    struct Rename;
+
+    impl<'a> Visitor<'a> for Variables<'a> {
+        type Error = Infallible;
+
+        fn visit_ast(&mut self, ast: &Ast<'a>) -> Result<(), Self::Error> {
+            if let Variable(name) = ast.kind {
+                self.0.push(name);
+            }
+
+            walk_ast(self, ast)
+        }
+    }
+
+    impl<'a> VisitorMut<'a> for Rename {
+        type Error = Infallible;
+
+        fn visit_ast(&mut self, ast: &mut Ast<'a>) -> Result<(), Self::Error> {
+            if let Variable(name) = &mut ast.kind {
+                *name = "x";
+            }
+
+            walk_ast_mut(self, ast)
+        }
+    }
+
+    /// Records the names of the identifiers and variables it visits, with a marker for every
+    /// column, transform, else if part and field hook it enters.
+    ///
+    /// It walks both ways so the tests can check that `walk_*` and `walk_*_mut` agree.
+    #[derive(Debug, Default, PartialEq)]
+    struct Trace<'a>(Vec<&'a str>);
+
+    impl<'a> Trace<'a> {
+        fn record(&mut self, ast: &Ast<'a>) {
+            if let Identifier(name) | Variable(name) = ast.kind {
+                self.0.push(name);
+            }
+        }
+    }
+
+    impl<'a> Visitor<'a> for Trace<'a> {
+        type Error = Infallible;
+
+        fn visit_ast(&mut self, ast: &Ast<'a>) -> Result<(), Self::Error> {
+            self.record(ast);
+            walk_ast(self, ast)
+        }
+
+        fn visit_field(&mut self, field: &Field<'a>) -> Result<(), Self::Error> {
+            self.0.push("<field>");
+            walk_field(self, field)
+        }
+
+        fn visit_select_column(&mut self, column: &SelectColumn<'a>) -> Result<(), Self::Error> {
+            self.0.push("<column>");
+            walk_select_column(self, column)
+        }
+
+        fn visit_select_transform(
+            &mut self,
+            transform: &SelectTransform<'a>,
+        ) -> Result<(), Self::Error> {
+            self.0.push("<transform>");
+            walk_select_transform(self, transform)
+        }
+
+        fn visit_else_if_part(&mut self, else_if: &ElseIfPart<'a>) -> Result<(), Self::Error> {
+            self.0.push("<else if>");
+            walk_else_if_part(self, else_if)
+        }
+    }
+
+    impl<'a> VisitorMut<'a> for Trace<'a> {
+        type Error = Infallible;
+
+        fn visit_ast(&mut self, ast: &mut Ast<'a>) -> Result<(), Self::Error> {
+            self.record(ast);
+            walk_ast_mut(self, ast)
+        }
+
+        fn visit_field(&mut self, field: &mut Field<'a>) -> Result<(), Self::Error> {
+            self.0.push("<field>");
+            walk_field_mut(self, field)
+        }
+
+        fn visit_select_column(
+            &mut self,
+            column: &mut SelectColumn<'a>,
+        ) -> Result<(), Self::Error> {
+            self.0.push("<column>");
+            walk_select_column_mut(self, column)
+        }
+
+        fn visit_select_transform(
+            &mut self,
+            transform: &mut SelectTransform<'a>,
+        ) -> Result<(), Self::Error> {
+            self.0.push("<transform>");
+            walk_select_transform_mut(self, transform)
+        }
+
+        fn visit_else_if_part(&mut self, else_if: &mut ElseIfPart<'a>) -> Result<(), Self::Error> {
+            self.0.push("<else if>");
+            walk_else_if_part_mut(self, else_if)
+        }
+    }
+
+    /// Traces the program with both walks, checks they agree and returns the trace.
+    fn trace(source: &str) -> anyhow::Result<Vec<&str>> {
+        let mut ast = Parser::new(source, 50).parse_program().into_result()?;
+
+        let mut trace = Trace::default();
+        let Ok(()) = Visitor::visit_ast(&mut trace, &ast);
+
+        let mut trace_mut = Trace::default();
+        let Ok(()) = VisitorMut::visit_ast(&mut trace_mut, &mut ast);
+
+        assert_eq!(trace, trace_mut);
+
+        Ok(trace.0)
+    }
+
+    #[test]
+    fn test_visitor_walks_in_source_order() -> anyhow::Result<()> {
+        let ast = Parser::new(
+            r#"
+            LET $a TYPE [u8 4] = [$b, { key: $c }];
+            IF $d THEN $e ELSE IF $f THEN $g ELSE $h END;
+            SELECT $i, fold $j AS k FROM person WHERE $l ORDER BY $m;
+            DEFINE TABLE person FIELDS age TYPE u8 VALUE $n ASSERT $o
+            "#,
+            50,
+        )
+        .parse_program()
+        .into_result()?;
+
+        let mut variables = Variables::default();
+        let Ok(()) = variables.visit_ast(&ast);
+
+        assert_eq!(
+            variables.0,
+            ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "l", "m", "n", "o"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor_mut_changes_nodes() -> anyhow::Result<()> {
+        let mut ast = Parser::new("FOR $a IN $b DO SET $c = $a + 1 END", 50)
+            .parse_program()
+            .into_result()?;
+
+        let Ok(()) = Rename.visit_ast(&mut ast);
+
+        let mut variables = Variables::default();
+        let Ok(()) = variables.visit_ast(&ast);
+
+        assert_eq!(variables.0, ["x", "x", "x", "x"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor_walks_select_columns_and_transforms() -> anyhow::Result<()> {
+        assert_eq!(
+            trace(
+                "SELECT $a, FOLD $b AS c OMIT $d FROM $e WHERE $f GROUP BY $g, $h \
+                 ORDER BY $i WITH INDEX j, k START AT $l LIMIT TO $m"
+            )?,
+            [
+                "<column>",
+                "a",
+                "<column>",
+                "b",
+                "c",
+                "d",
+                "e",
+                "<transform>",
+                "f",
+                "<transform>",
+                "g",
+                "h",
+                "<transform>",
+                "i",
+                "<transform>",
+                "j",
+                "k",
+                "<transform>",
+                "l",
+                "<transform>",
+                "m",
+            ]
+        );
+
+        assert_eq!(
+            trace("SELECT * FROM $a WITH NO INDEX")?,
+            ["<column>", "a", "<transform>"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor_walks_else_if_parts() -> anyhow::Result<()> {
+        assert_eq!(
+            trace("IF $a THEN $b ELSE IF $c THEN $d ELSE IF $e THEN $f ELSE $g END")?,
+            ["a", "b", "<else if>", "c", "d", "<else if>", "e", "f", "g"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor_walks_field_defaults_and_assertions() -> anyhow::Result<()> {
+        assert_eq!(
+            trace(
+                "DEFINE TABLE a ON DATABASE b \
+                 FIELDS c TYPE d VALUE $e ASSERT $f ASSERT $g, h TYPE option[i] \
+                 FLEXIBLE TYPE j"
+            )?,
+            ["a", "b", "j", "<field>", "c", "d", "e", "f", "g", "<field>", "h", "option", "i",]
+        );
+
+        assert_eq!(
+            trace("DEFINE EDGE a FIELDS b TYPE c VALUE $d ASSERT $e")?,
+            ["a", "<field>", "b", "c", "d", "e"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor_walks_data_expressions() -> anyhow::Result<()> {
+        assert_eq!(
+            trace("CREATE a SET b = $c, d = $e")?,
+            ["a", "b", "d", "c", "e"]
+        );
+        assert_eq!(
+            trace("CREATE a SET (b, c) VALUES ($d, $e), ($f, $g)")?,
+            ["a", "b", "c", "d", "e", "f", "g"]
+        );
+        assert_eq!(
+            trace("UPDATE a SET b = $c, d += $e WHERE $f")?,
+            ["a", "f", "b", "c", "d", "e"]
+        );
+        assert_eq!(trace("DELETE a WHERE $b")?, ["a", "b"]);
+        assert_eq!(
+            trace("DELETE a -> b[$c..] AS d -> e")?,
+            ["a", "b", "c", "d", "e"]
+        );
+        assert_eq!(
+            trace("RELATE a:b -> c -> d:e SET f = $g")?,
+            ["a", "b", "c", "d", "e", "f", "g"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor_walks_control_flow() -> anyhow::Result<()> {
+        assert_eq!(
+            trace("FOR $a IN $b DO WHILE $c DO SET $d = $e END END")?,
+            ["a", "b", "c", "d", "e"]
+        );
+        assert_eq!(
+            trace("LET $a TYPE b = c::d($e, f = $g)")?,
+            ["a", "b", "c", "d", "e", "f", "g"]
+        );
+        assert_eq!(trace("LET $a = $b[$c].d?.e.*")?, ["a", "b", "c", "d", "e"]);
+        assert_eq!(
+            trace("LET $a = [$b, ($c, $d), { e: $f }, $g..=$h]")?,
+            ["a", "b", "c", "d", "e", "f", "g", "h"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor_walks_definitions() -> anyhow::Result<()> {
+        assert_eq!(
+            trace("DEFINE TYPE a ON DATABASE b FIELDS c TYPE d, e TYPE [f 4]")?,
+            ["a", "b", "c", "d", "e", "f"]
+        );
+        assert_eq!(
+            trace("DEFINE ENUM a ON DATABASE b VARIANTS c, d")?,
+            ["a", "b", "c", "d"]
+        );
+        assert_eq!(
+            trace("DEFINE INDEX a FIELDS b, c WITH d($e) ON TABLE f ON DATABASE g")?,
+            ["a", "g", "f", "b", "c", "d", "e"]
+        );
+        assert_eq!(
+            trace("DEFINE FUNCTION a($b TYPE c) TYPE d ON DATABASE e DO RETURN $f END")?,
+            ["a", "e", "b", "c", "d", "f"]
+        );
+        assert_eq!(
+            trace("DEFINE PARAM a TYPE b VALUE $c ON DATABASE d")?,
+            ["a", "d", "b", "c"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor_walks_redefinitions() -> anyhow::Result<()> {
+        assert_eq!(
+            trace("REDEFINE TABLE a AS b ON DATABASE c FIELDS d AS e, f TYPE g")?,
+            ["a", "b", "c", "d", "e", "f", "g"]
+        );
+        assert_eq!(
+            trace("REDEFINE ENUM a AS b VARIANTS c AS d, e")?,
+            ["a", "b", "c", "d", "e"]
+        );
+        assert_eq!(
+            trace("REDEFINE INDEX a ON TABLE b AS c FIELDS d WITH e($f)")?,
+            ["a", "c", "b", "d", "e", "f"]
+        );
+        assert_eq!(
+            trace("REDEFINE PARAM a AS b TYPE c VALUE $d")?,
+            ["a", "b", "c", "d"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_visitor_walks_removals_and_descriptions() -> anyhow::Result<()> {
+        assert_eq!(trace("REMOVE TABLE a ON DATABASE b")?, ["a", "b"]);
+        assert_eq!(
+            trace("REMOVE INDEX a ON TABLE b ON DATABASE c")?,
+            ["a", "b", "c"]
+        );
+        assert_eq!(trace("DESCRIBE DATABASE a ON NAMESPACE b")?, ["a", "b"]);
+        assert_eq!(trace("USE DATABASE a")?, ["a"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,400 @@
+use crate::{
+    lexer::LexerError,
+    parser::{describe_expected, ParserError},
+    sema::SemaError,
+};
+
+use super::Diagnostic;
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// Codes of lexer diagnostics.
+mod lexer_codes {
+    pub const UNEXPECTED_CHARACTER: &str = "E0101";
+    pub const MISMATCHED_BRACKET: &str = "E0102";
+    pub const UNTERMINATED_MODULE_BLOCK: &str = "E0103";
+}
+
+/// Codes of parser diagnostics.
+mod parser_codes {
+    pub const UNEXPECTED_TOKEN: &str = "E0201";
+    pub const INVALID_INTEGER_LITERAL: &str = "E0202";
+    pub const INVALID_FLOAT_LITERAL: &str = "E0203";
+    pub const NESTING_TOO_DEEP: &str = "E0204";
+}
+
+/// Codes of semantic analysis diagnostics.
+mod sema_codes {
+    pub const UNEXPECTED_AST_KIND: &str = "E0300";
+    pub const UNDEFINED_VARIABLE_OR_PARAM: &str = "E0301";
+    pub const UNDEFINED_SCHEMA_ITEM: &str = "E0302";
+    pub const UNDEFINED_DATABASE: &str = "E0303";
+    pub const UNSPECIFIED_DATABASE: &str = "E0304";
+    pub const DUPLICATE_SCHEMA_ITEM_DEFINITION: &str = "E0305";
+    pub const DUPLICATE_TYPE_OR_ENUM_DEFINITION: &str = "E0306";
+    pub const INVALID_PATH: &str = "E0307";
+    pub const NESTING_TOO_DEEP: &str = "E0308";
+    pub const MISMATCHED_TYPES: &str = "E0309";
+    pub const INVALID_OPERAND_TYPE: &str = "E0310";
+    pub const INVALID_OPERAND_TYPES: &str = "E0311";
+    pub const NOT_ITERABLE: &str = "E0312";
+    pub const INVALID_INDEX: &str = "E0313";
+    pub const INTEGER_OUT_OF_RANGE: &str = "E0314";
+    pub const UNKNOWN_FIELD: &str = "E0315";
+    pub const UNKNOWN_VARIANT: &str = "E0316";
+    pub const OPTIONAL_FIELD_ACCESS: &str = "E0317";
+    pub const NO_FIELDS: &str = "E0318";
+    pub const UNDEFINED_FUNCTION: &str = "E0319";
+    pub const UNKNOWN_ARGUMENT: &str = "E0320";
+    pub const TOO_MANY_ARGUMENTS: &str = "E0321";
+    pub const DUPLICATE_ARGUMENT: &str = "E0322";
+    pub const MISSING_ARGUMENT: &str = "E0323";
+    pub const RETURN_OUTSIDE_FUNCTION: &str = "E0324";
+    pub const NON_EXHAUSTIVE_MATCH: &str = "E0325";
+    pub const MISSING_RETURN: &str = "E0326";
+    pub const MOVED_VARIANT: &str = "E0327";
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl From<&LexerError> for Diagnostic {
+    fn from(error: &LexerError) -> Self {
+        use lexer_codes::*;
+        match error {
+            LexerError::UnexpectedCharacter { span, character } => Diagnostic::error(
+                UNEXPECTED_CHARACTER,
+                format!("unexpected character `{character}`"),
+            )
+            .with_primary(span.clone(), "not valid here"),
+            LexerError::MismatchedBracket {
+                span,
+                expected: Some(expected),
+                found,
+            } => Diagnostic::error(
+                MISMATCHED_BRACKET,
+                format!("mismatched closing bracket `{found}`"),
+            )
+            .with_primary(span.clone(), format!("expected `{expected}`")),
+            LexerError::MismatchedBracket {
+                span,
+                expected: None,
+                found,
+            } => Diagnostic::error(
+                MISMATCHED_BRACKET,
+                format!("unmatched closing bracket `{found}`"),
+            )
+            .with_primary(span.clone(), "no bracket to close"),
+            LexerError::UnableToLexModuleBlock { span } => {
+                Diagnostic::error(UNTERMINATED_MODULE_BLOCK, "unterminated module block")
+                    .with_primary(span.clone(), "module block starts here")
+                    .with_help("close the module block with `END`")
+            }
+        }
+    }
+}
+
+impl From<&ParserError> for Diagnostic {
+    fn from(error: &ParserError) -> Self {
+        use parser_codes::*;
+        match error {
+            ParserError::LexerError(error) => error.into(),
+            ParserError::UnexpectedToken {
+                span,
+                found,
+                expected,
+            } => Diagnostic::error(UNEXPECTED_TOKEN, format!("unexpected {found}")).with_primary(
+                span.clone(),
+                format!("expected {}", describe_expected(expected)),
+            ),
+            ParserError::InvalidIntegerLiteral { error, value, span } => Diagnostic::error(
+                INVALID_INTEGER_LITERAL,
+                format!("invalid integer literal `{value}`"),
+            )
+            .with_primary(span.clone(), error.to_string())
+            .with_note("integer literals must fit in 128 unsigned bits"),
+            ParserError::InvalidFloatLiteral { error, value, span } => Diagnostic::error(
+                INVALID_FLOAT_LITERAL,
+                format!("invalid float literal `{value}`"),
+            )
+            .with_primary(span.clone(), error.to_string()),
+            ParserError::NestingTooDeep { span, max_depth } => {
+                Diagnostic::error(NESTING_TOO_DEEP, "nesting too deep")
+                    .with_primary(span.clone(), format!("goes past a depth of {max_depth}"))
+                    .with_help("split the query into smaller parts, e.g. with `LET`")
+            }
+        }
+    }
+}
+
+impl From<&SemaError> for Diagnostic {
+    fn from(error: &SemaError) -> Self {
+        use sema_codes::*;
+        let message = error.to_string();
+        match error {
+            SemaError::UnexpectedAstKind { span, .. } => {
+                Diagnostic::error(UNEXPECTED_AST_KIND, message)
+                    .with_primary(span.clone(), "not expected here")
+                    .with_note("the parser should never produce this, so it is likely a bug")
+            }
+            SemaError::UndefinedVariableOrParam { name, span } => {
+                Diagnostic::error(UNDEFINED_VARIABLE_OR_PARAM, message)
+                    .with_primary(span.clone(), "not found in this scope")
+                    .with_help(format!(
+                        "declare it with `LET ${name} = ...` or `DEFINE PARAM ${name}` first"
+                    ))
+            }
+            SemaError::UndefinedSchemaItem { span, .. } => {
+                Diagnostic::error(UNDEFINED_SCHEMA_ITEM, message)
+                    .with_primary(span.clone(), "not defined in the database")
+            }
+            SemaError::UndefinedDatabase { span, .. } => {
+                Diagnostic::error(UNDEFINED_DATABASE, message)
+                    .with_primary(span.clone(), "no such database")
+                    .with_help("define it with `DEFINE DATABASE` first")
+            }
+            SemaError::UnspecifiedDatabase { span } => {
+                Diagnostic::error(UNSPECIFIED_DATABASE, message)
+                    .with_primary(span.clone(), "this needs a database")
+                    .with_help("select one with `USE DATABASE <name>`")
+            }
+            SemaError::DuplicateSchemaItemDefinition { span, .. } => {
+                Diagnostic::error(DUPLICATE_SCHEMA_ITEM_DEFINITION, message)
+                    .with_primary(span.clone(), "already defined")
+                    .with_help("add `IF NOT EXISTS` to skip definitions that already exist")
+            }
+            SemaError::DuplicateTypeOrEnumDefinition { span, .. } => {
+                Diagnostic::error(DUPLICATE_TYPE_OR_ENUM_DEFINITION, message)
+                    .with_primary(span.clone(), "already defined")
+            }
+            SemaError::PathError { span, .. } => Diagnostic::error(INVALID_PATH, message)
+                .with_primary(span.clone(), "not a valid path"),
+            SemaError::NestingTooDeep { span, .. } => Diagnostic::error(NESTING_TOO_DEEP, message)
+                .with_primary(span.clone(), "nested too deep")
+                .with_help("split the query into smaller parts, e.g. with `LET`"),
+            SemaError::MismatchedTypes { expected, span, .. } => {
+                Diagnostic::error(MISMATCHED_TYPES, message)
+                    .with_primary(span.clone(), format!("expected {expected}"))
+            }
+            SemaError::InvalidOperandType { found, span, .. } => {
+                Diagnostic::error(INVALID_OPERAND_TYPE, message)
+                    .with_primary(span.clone(), format!("this is {found}"))
+            }
+            SemaError::InvalidOperandTypes { span, .. } => {
+                Diagnostic::error(INVALID_OPERAND_TYPES, message)
+                    .with_primary(span.clone(), "operand types do not match the operator")
+            }
+            SemaError::NotIterable { span, .. } => Diagnostic::error(NOT_ITERABLE, message)
+                .with_primary(span.clone(), "cannot be iterated over")
+                .with_help("iterate over a list, an array, a range or a string"),
+            SemaError::InvalidIndex { span, .. } => Diagnostic::error(INVALID_INDEX, message)
+                .with_primary(span.clone(), "invalid index"),
+            SemaError::IntegerOutOfRange { r#type, span, .. } => {
+                Diagnostic::error(INTEGER_OUT_OF_RANGE, message)
+                    .with_primary(span.clone(), format!("out of range for {type}"))
+                    .with_help("use a wider integer type")
+            }
+            SemaError::UnknownField {
+                suggestion, span, ..
+            } => did_you_mean(
+                Diagnostic::error(UNKNOWN_FIELD, message)
+                    .with_primary(span.clone(), "unknown field"),
+                suggestion,
+            ),
+            SemaError::UnknownVariant {
+                suggestion, span, ..
+            } => did_you_mean(
+                Diagnostic::error(UNKNOWN_VARIANT, message)
+                    .with_primary(span.clone(), "unknown variant"),
+                suggestion,
+            ),
+            SemaError::NonExhaustiveMatch { span, .. } => {
+                Diagnostic::error(NON_EXHAUSTIVE_MATCH, message)
+                    .with_primary(span.clone(), "not every variant is matched")
+                    .with_help("match the missing variants, or add an `ELSE`")
+            }
+            SemaError::OptionalFieldAccess { field, span, .. } => {
+                Diagnostic::error(OPTIONAL_FIELD_ACCESS, message)
+                    .with_primary(span.clone(), "this may be none")
+                    .with_help(format!("use `?.{field}` to get none instead"))
+            }
+            SemaError::NoFields { span, .. } => Diagnostic::error(NO_FIELDS, message)
+                .with_primary(span.clone(), "cannot access fields of this"),
+            SemaError::UndefinedFunction {
+                suggestion, span, ..
+            } => did_you_mean(
+                Diagnostic::error(UNDEFINED_FUNCTION, message)
+                    .with_primary(span.clone(), "not found"),
+                suggestion,
+            ),
+            SemaError::UnknownArgument {
+                suggestion, span, ..
+            } => did_you_mean(
+                Diagnostic::error(UNKNOWN_ARGUMENT, message)
+                    .with_primary(span.clone(), "unknown argument"),
+                suggestion,
+            ),
+            SemaError::TooManyArguments { span, .. } => {
+                Diagnostic::error(TOO_MANY_ARGUMENTS, message)
+                    .with_primary(span.clone(), "unexpected argument")
+            }
+            SemaError::DuplicateArgument { span, .. } => {
+                Diagnostic::error(DUPLICATE_ARGUMENT, message)
+                    .with_primary(span.clone(), "given again here")
+            }
+            SemaError::MissingArgument { param, span, .. } => {
+                Diagnostic::error(MISSING_ARGUMENT, message)
+                    .with_primary(span.clone(), format!("`{param}` not given"))
+            }
+            SemaError::ReturnOutsideFunction { span } => {
+                Diagnostic::error(RETURN_OUTSIDE_FUNCTION, message)
+                    .with_primary(span.clone(), "cannot return from here")
+                    .with_help("only the body of a `DEFINE FUNCTION` can return")
+            }
+            SemaError::MissingReturn { span, .. } => Diagnostic::error(MISSING_RETURN, message)
+                .with_primary(span.clone(), "can end without a `RETURN`")
+                .with_help("return a value at the end of the body"),
+            SemaError::MovedVariant { variant, span } => Diagnostic::error(MOVED_VARIANT, message)
+                .with_primary(span.clone(), format!("expected `{variant}` here"))
+                .with_help("keep the existing variants in order, and add new ones after them"),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Adds a "did you mean" help to the diagnostic if there is a suggestion.
+fn did_you_mean(diagnostic: Diagnostic, suggestion: &Option<String>) -> Diagnostic {
+    match suggestion {
+        Some(suggestion) => diagnostic.with_help(format!("did you mean `{suggestion}`?")),
+        None => diagnostic,
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parser::Parser,
+        sema::{MemberResolver, NameResolver, TypeChecker, TypeInferencer},
+    };
+
+    use super::*;
+
+    fn parser_diagnostics(source: &str) -> Vec<String> {
+        let mut parser = Parser::new(source, 20);
+        parser
+            .parse_program()
+            .errors
+            .iter()
+            .map(|error| Diagnostic::from(error).render(source))
+            .collect()
+    }
+
+    #[test]
+    fn test_diagnostic_from_parser_errors() {
+        assert_eq!(
+            parser_diagnostics("LET = 1;\nLET $y = 2;\n$y ] 1"),
+            vec![
+                concat!(
+                    "error[E0201]: unexpected `=`\n",
+                    " --> 1:5\n",
+                    "  |\n",
+                    "1 | LET = 1;\n",
+                    "  |     ^ expected variable\n",
+                ),
+                concat!(
+                    "error[E0102]: unmatched closing bracket `]`\n",
+                    " --> 3:4\n",
+                    "  |\n",
+                    "3 | $y ] 1\n",
+                    "  |    ^ no bracket to close\n",
+                ),
+            ]
+        );
+
+        assert_eq!(
+            parser_diagnostics("1 + 340282366920938463463374607431768211456"),
+            vec![concat!(
+                "error[E0202]: invalid integer literal `340282366920938463463374607431768211456`\n",
+                " --> 1:5\n",
+                "  |\n",
+                "1 | 1 + 340282366920938463463374607431768211456\n",
+                "  |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ number too large to fit in target type\n",
+                "  |\n",
+                "  = note: integer literals must fit in 128 unsigned bits\n",
+            )]
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_from_sema_errors() {
+        let source = "LET $x = 1;\nLET $y = $x + $z;";
+        let mut ast = Parser::new(source, 20)
+            .parse_program()
+            .into_result()
+            .unwrap();
+        let error = NameResolver::new().analyze(&mut ast).unwrap_err();
+
+        assert_eq!(
+            Diagnostic::from(&error).render(source),
+            concat!(
+                "error[E0301]: undefined variable or parameter: z\n",
+                " --> 2:15\n",
+                "  |\n",
+                "2 | LET $y = $x + $z;\n",
+                "  |               ^^ not found in this scope\n",
+                "  |\n",
+                "  = help: declare it with `LET $z = ...` or `DEFINE PARAM $z` first\n",
+            )
+        );
+
+        let source = "LET $x TYPE u8 = 1;\nSET $x = 'a';";
+        let mut ast = Parser::new(source, 20)
+            .parse_program()
+            .into_result()
+            .unwrap();
+        TypeInferencer::new().analyze(&mut ast).unwrap();
+        let error = TypeChecker::new().analyze(&ast).unwrap_err();
+
+        assert_eq!(
+            Diagnostic::from(&error).render(source),
+            concat!(
+                "error[E0309]: mismatched types: expected u8, found string\n",
+                " --> 2:10\n",
+                "  |\n",
+                "2 | SET $x = 'a';\n",
+                "  |          ^^^ expected u8\n",
+            )
+        );
+
+        let source = "DEFINE TYPE address FIELDS city TYPE string;\nLET $a TYPE address = $x;\nLET $c = $a.ctiy;";
+        let mut ast = Parser::new(source, 20)
+            .parse_program()
+            .into_result()
+            .unwrap();
+        TypeInferencer::new().analyze(&mut ast).unwrap();
+        let error = MemberResolver::new().analyze(&ast).unwrap_err();
+
+        assert_eq!(
+            Diagnostic::from(&error).render(source),
+            concat!(
+                "error[E0315]: no field `ctiy` on address\n",
+                " --> 3:13\n",
+                "  |\n",
+                "3 | LET $c = $a.ctiy;\n",
+                "  |             ^^^^ unknown field\n",
+                "  |\n",
+                "  = help: did you mean `city`?\n",
+            )
+        );
+    }
+}
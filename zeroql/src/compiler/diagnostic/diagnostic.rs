@@ -0,0 +1,167 @@
+use std::fmt::{self, Display};
+
+use crate::Span;
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// A message about the source, such as an error or a warning, pointing at the spans it is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the diagnostic is.
+    pub severity: Severity,
+
+    /// A stable code identifying the kind of diagnostic, e.g. `E0201`.
+    pub code: &'static str,
+
+    /// The main message.
+    pub message: String,
+
+    /// The spans the diagnostic is about.
+    ///
+    /// There is usually a single primary label pointing at the cause and any number of secondary
+    /// labels giving context.
+    pub labels: Vec<Label>,
+
+    /// Additional notes shown after the source snippet.
+    pub notes: Vec<String>,
+
+    /// A suggestion on how to fix the problem.
+    pub help: Option<String>,
+}
+
+/// The severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Informational message.
+    Note,
+
+    /// Something that is likely a mistake but does not stop compilation.
+    Warning,
+
+    /// Something that stops compilation.
+    Error,
+}
+
+/// A span of the source with an optional message attached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    /// Whether this is the primary or a secondary label.
+    pub style: LabelStyle,
+
+    /// The span of the source the label points at.
+    pub span: Span,
+
+    /// The message shown next to the underline.
+    pub message: String,
+}
+
+/// Whether a [`Label`] points at the cause of a diagnostic or gives extra context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LabelStyle {
+    /// Points at the cause. Underlined with `^`.
+    Primary,
+
+    /// Gives extra context. Underlined with `-`.
+    Secondary,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Diagnostic {
+    /// Creates a new diagnostic without labels, notes or help.
+    pub fn new(severity: Severity, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+            labels: vec![],
+            notes: vec![],
+            help: None,
+        }
+    }
+
+    /// Creates a new error diagnostic.
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    /// Creates a new warning diagnostic.
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+
+    /// Adds a primary label.
+    pub fn with_primary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label::primary(span, message));
+        self
+    }
+
+    /// Adds a secondary label.
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label::secondary(span, message));
+        self
+    }
+
+    /// Adds a note.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Sets the help text.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Gets the first primary label, if any.
+    pub fn primary_label(&self) -> Option<&Label> {
+        self.labels
+            .iter()
+            .find(|label| label.style == LabelStyle::Primary)
+    }
+}
+
+impl Label {
+    /// Creates a new primary label.
+    pub fn primary(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            style: LabelStyle::Primary,
+            span,
+            message: message.into(),
+        }
+    }
+
+    /// Creates a new secondary label.
+    pub fn secondary(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            style: LabelStyle::Secondary,
+            span,
+            message: message.into(),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Note => write!(f, "note"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)
+    }
+}
@@ -0,0 +1,25 @@
+//! Diagnostics reported by the compiler.
+//!
+//! Every error produced by the lexer, the parser and semantic analysis can be turned into a
+//! [`Diagnostic`], which can then be rendered against the source it came from:
+//!
+//! ```text
+//! error[E0301]: undefined variable or parameter: $y
+//!  --> 1:12
+//!   |
+//! 1 | LET $x = 1 + $y
+//!   |              ^^ not found in this scope
+//!   |
+//!   = help: declare it with `LET $y = ...` before using it
+//! ```
+
+mod convert;
+mod diagnostic;
+mod render;
+
+//--------------------------------------------------------------------------------------------------
+// Exports
+//--------------------------------------------------------------------------------------------------
+
+pub use diagnostic::*;
+pub use render::*;
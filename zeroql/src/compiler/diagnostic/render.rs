@@ -0,0 +1,252 @@
+use std::fmt::Write;
+
+use super::{Diagnostic, Label, LabelStyle};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// A 1-based line and column in the source.
+///
+/// Columns count characters rather than bytes so they line up with what an editor shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// The line number, starting at 1.
+    pub line: usize,
+
+    /// The column number, starting at 1.
+    pub column: usize,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Position {
+    /// Gets the position of a byte offset in the source.
+    ///
+    /// Offsets past the end of the source are clamped to the end, and offsets inside a multi-byte
+    /// character are moved back to the start of the character.
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let offset = floor_char_boundary(source, offset);
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: source[..line_start].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Renders the diagnostic with the lines of `source` its labels point at.
+    ///
+    /// ```text
+    /// error[E0201]: unexpected `=`
+    ///  --> 1:5
+    ///   |
+    /// 1 | LET = 1
+    ///   |     ^ expected variable
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{self}");
+
+        let mut labels = self
+            .labels
+            .iter()
+            .map(|label| (Position::from_offset(source, label.span.start), label))
+            .collect::<Vec<_>>();
+        labels.sort_by_key(|(position, _)| *position);
+
+        let gutter = labels
+            .last()
+            .map_or(1, |(position, _)| position.line.to_string().len());
+        let pad = " ".repeat(gutter);
+
+        if let Some(position) = self
+            .primary_label()
+            .map(|label| Position::from_offset(source, label.span.start))
+            .or_else(|| labels.first().map(|(position, _)| *position))
+        {
+            let _ = writeln!(out, "{pad}--> {}:{}", position.line, position.column);
+            let _ = writeln!(out, "{pad} |");
+        }
+
+        let mut current_line = None;
+        for (position, label) in &labels {
+            let line = source.split('\n').nth(position.line - 1).unwrap_or("");
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if current_line != Some(position.line) {
+                let _ = match line {
+                    "" => writeln!(out, "{:>gutter$} |", position.line),
+                    _ => writeln!(out, "{:>gutter$} | {line}", position.line),
+                };
+                current_line = Some(position.line);
+            }
+
+            let _ = writeln!(out, "{pad} | {}", underline(source, line, *position, label));
+        }
+
+        if !labels.is_empty() && (!self.notes.is_empty() || self.help.is_some()) {
+            let _ = writeln!(out, "{pad} |");
+        }
+
+        for note in &self.notes {
+            let _ = writeln!(out, "{pad} = note: {note}");
+        }
+
+        if let Some(help) = &self.help {
+            let _ = writeln!(out, "{pad} = help: {help}");
+        }
+
+        out
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Builds the underline for a label on `line`, which starts at `position`.
+///
+/// A label spanning several lines is underlined up to the end of its first line, and an empty
+/// label still gets a single marker so there is something to point at.
+fn underline(source: &str, line: &str, position: Position, label: &Label) -> String {
+    let marker = match label.style {
+        LabelStyle::Primary => '^',
+        LabelStyle::Secondary => '-',
+    };
+
+    let end = Position::from_offset(source, label.span.end.max(label.span.start));
+    let line_width = line.chars().count() + 1;
+    let end_column = if end.line == position.line {
+        end.column
+    } else {
+        line_width
+    };
+
+    // Keep tabs so the underline lines up with the source line above it.
+    let mut underline = line
+        .chars()
+        .take(position.column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    let width = end_column.saturating_sub(position.column).max(1);
+    underline.extend(std::iter::repeat_n(marker, width));
+
+    if !label.message.is_empty() {
+        underline.push(' ');
+        underline.push_str(&label.message);
+    }
+
+    underline
+}
+
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    offset
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_from_offset() {
+        let source = "LET $x = 1;\nLET $é = 2;\n";
+
+        assert_eq!(
+            Position::from_offset(source, 0),
+            Position { line: 1, column: 1 }
+        );
+        assert_eq!(
+            Position::from_offset(source, 4),
+            Position { line: 1, column: 5 }
+        );
+        assert_eq!(
+            Position::from_offset(source, 12),
+            Position { line: 2, column: 1 }
+        );
+
+        // `é` is two bytes wide, so the `=` after it is at byte 20 but column 8.
+        assert_eq!(
+            Position::from_offset(source, 20),
+            Position { line: 2, column: 8 }
+        );
+
+        // Offsets inside a character or past the end are clamped.
+        assert_eq!(
+            Position::from_offset(source, 17),
+            Position { line: 2, column: 6 }
+        );
+        assert_eq!(
+            Position::from_offset(source, 100),
+            Position { line: 3, column: 1 }
+        );
+    }
+
+    #[test]
+    fn test_render_labels_notes_and_help() {
+        let source = "LET $x = 1;\nLET $y = $x + $z;";
+        let diagnostic = Diagnostic::error("E0000", "something went wrong")
+            .with_primary(26..28, "this one")
+            .with_secondary(16..18, "")
+            .with_secondary(4..6, "defined here")
+            .with_note("a note")
+            .with_help("some help");
+
+        assert_eq!(
+            diagnostic.render(source),
+            concat!(
+                "error[E0000]: something went wrong\n",
+                " --> 2:15\n",
+                "  |\n",
+                "1 | LET $x = 1;\n",
+                "  |     -- defined here\n",
+                "2 | LET $y = $x + $z;\n",
+                "  |     --\n",
+                "  |               ^^ this one\n",
+                "  |\n",
+                "  = note: a note\n",
+                "  = help: some help\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_edge_spans() {
+        // An empty span at the end of the input still gets a marker, and a span that runs onto
+        // the next line is cut at the end of its first line.
+        let source = "IF $x THEN\n\t1\n";
+        let diagnostic = Diagnostic::error("E0000", "oops")
+            .with_primary(14..14, "expected `END`")
+            .with_secondary(6..13, "block starts here");
+
+        assert_eq!(
+            diagnostic.render(source),
+            concat!(
+                "error[E0000]: oops\n",
+                " --> 3:1\n",
+                "  |\n",
+                "1 | IF $x THEN\n",
+                "  |       ---- block starts here\n",
+                "3 |\n",
+                "  | ^ expected `END`\n",
+            )
+        );
+
+        let diagnostic = Diagnostic::warning("W0000", "no labels").with_note("just a note");
+        assert_eq!(
+            diagnostic.render(source),
+            "warning[W0000]: no labels\n  = note: just a note\n"
+        );
+    }
+}
@@ -0,0 +1,1656 @@
+use crate::{
+    ast::{
+        Ast, AstKind, Direction, Field, RedefineField, RelateArrow, SelectColumn, SelectTransform,
+        TableMode, TypeSig, UpdateAssign,
+    },
+    lexer::{Lexeme, TokenKind, Trivia, TriviaLexer},
+    parser::{is_keyword, Parser, ParserResult},
+};
+
+use super::FormatOptions;
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// A formatter that prints an [`Ast`] back to canonical source.
+///
+/// Every statement is printed on a line of its own, with the bodies of `FOR`, `WHILE` and `IF`
+/// indented one level. When given the source the AST was parsed from, the formatter also keeps
+/// its comments and single blank lines. A comment is printed on a line of its own before the
+/// statement it appears in, or after the statement if it follows it on the same line.
+pub struct Formatter<'a> {
+    /// The options to format with.
+    options: FormatOptions,
+
+    /// The source the AST was parsed from, or an empty string if there is none.
+    source: &'a str,
+
+    /// The comments in the source, in the order they appear.
+    comments: Vec<Trivia<'a>>,
+
+    /// The index of the first comment that has not been printed yet.
+    next_comment: usize,
+
+    /// The formatted output so far.
+    output: String,
+
+    /// The indentation level of the current block.
+    indent_level: usize,
+
+    /// The end in the source of the last statement or comment printed in the current block.
+    last_end: Option<usize>,
+}
+
+/// How tightly an operation binds, from loosest to tightest.
+///
+/// An operand is parenthesized when it binds more loosely than its position in the grammar allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Exp,
+    Op,
+    Range,
+    Or,
+    And,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Eq,
+    Rel,
+    MatchSim,
+    Shift,
+    Add,
+    Mul,
+    Pow,
+    Access,
+    Sign,
+    Not,
+    Call,
+    Index,
+    Atom,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The size of the parser cache used by [`format`].
+const PARSER_CACHE_SIZE: usize = 100;
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl<'a> Formatter<'a> {
+    /// Creates a new formatter.
+    pub fn new(options: FormatOptions) -> Self {
+        Self {
+            options,
+            source: "",
+            comments: vec![],
+            next_comment: 0,
+            output: String::new(),
+            indent_level: 0,
+            last_end: None,
+        }
+    }
+
+    /// Sets the source the AST was parsed from, so its comments and blank lines are kept.
+    ///
+    /// Comments after the first character the lexer cannot make sense of are dropped.
+    pub fn with_source(mut self, source: &'a str) -> Self {
+        self.source = source;
+        self.comments = TriviaLexer::from(source)
+            .map_while(Result::ok)
+            .filter_map(|lexeme| match lexeme {
+                Lexeme::Trivia(trivia) if trivia.is_comment() => Some(trivia),
+                _ => None,
+            })
+            .collect();
+        self
+    }
+
+    /// Formats the AST.
+    pub fn format(&mut self, ast: &Ast) -> String {
+        self.next_comment = 0;
+        self.indent_level = 0;
+        self.last_end = None;
+
+        match &ast.kind {
+            AstKind::Program(statements) => self.write_block(statements, self.source.len()),
+            _ => {
+                self.write_statement(ast);
+                self.write_comments_before(self.source.len());
+            }
+        }
+
+        std::mem::take(&mut self.output)
+    }
+
+    /// Writes the statements of a block, then the comments left before `end`.
+    fn write_block(&mut self, statements: &[Ast], end: usize) {
+        self.last_end = None;
+
+        for statement in statements {
+            self.write_comments_before(statement.span.start);
+            self.write_blank_line_before(statement.span.start);
+            self.write_statement(statement);
+        }
+
+        self.write_comments_before(end);
+    }
+
+    /// Writes the body of a `FOR`, `WHILE` or `IF` one level deeper.
+    fn write_body(&mut self, body: &Ast, end: usize) {
+        self.indent_level += 1;
+
+        match &body.kind {
+            AstKind::Program(statements) => self.write_block(statements, end),
+            _ => self.write_block(std::slice::from_ref(body), end),
+        }
+
+        self.indent_level -= 1;
+    }
+
+    /// Writes a statement along with the comment that follows it on the same line.
+    fn write_statement(&mut self, ast: &Ast) {
+        self.write_node(ast, String::new());
+        self.last_end = Some(ast.span.end);
+        self.write_trailing_comment(ast.span.end);
+    }
+
+    /// Writes a statement, starting its first line with `prefix`.
+    fn write_node(&mut self, ast: &Ast, prefix: String) {
+        match &ast.kind {
+            AstKind::Let {
+                name,
+                r#type,
+                value,
+            } => {
+                let r#type = r#type
+                    .as_ref()
+                    .map(|r#type| format!(" {} {}", self.kw("TYPE"), self.type_sig(r#type)))
+                    .unwrap_or_default();
+                let prefix = format!("{prefix}{} {}{type} = ", self.kw("LET"), self.op(name));
+                self.write_node(value, prefix);
+            }
+            AstKind::Set {
+                variable,
+                op,
+                value,
+            } => {
+                let prefix = format!(
+                    "{prefix}{} {} {} ",
+                    self.kw("SET"),
+                    self.op(variable),
+                    assign_op(op)
+                );
+                self.write_node(value, prefix);
+            }
+            AstKind::For {
+                variable,
+                iterator,
+                body,
+            } => {
+                self.write_line(format!(
+                    "{prefix}{} {} {} {} {}",
+                    self.kw("FOR"),
+                    self.op(variable),
+                    self.kw("IN"),
+                    self.operand(iterator, Precedence::Range),
+                    self.kw("DO")
+                ));
+                self.write_body(body, ast.span.end);
+                self.write_line(self.kw("END"));
+            }
+            AstKind::While { condition, body } => {
+                self.write_line(format!(
+                    "{prefix}{} {} {}",
+                    self.kw("WHILE"),
+                    self.operand(condition, Precedence::Range),
+                    self.kw("DO")
+                ));
+                self.write_body(body, ast.span.end);
+                self.write_line(self.kw("END"));
+            }
+            AstKind::If {
+                condition,
+                then,
+                else_ifs,
+                r#else,
+            } => {
+                self.write_line(format!(
+                    "{prefix}{} {} {}",
+                    self.kw("IF"),
+                    self.operand(condition, Precedence::Range),
+                    self.kw("THEN")
+                ));
+
+                let mut body = then;
+                for part in else_ifs {
+                    let end = self.find_else(body.span.end, part.condition.span.start);
+                    self.write_body(body, end);
+                    self.write_line(format!(
+                        "{} {} {}",
+                        self.kw("ELSE IF"),
+                        self.operand(&part.condition, Precedence::Range),
+                        self.kw("THEN")
+                    ));
+                    body = &part.body;
+                }
+
+                if let Some(r#else) = r#else {
+                    let end = self.find_else(body.span.end, r#else.span.start);
+                    self.write_body(body, end);
+                    self.write_line(self.kw("ELSE"));
+                    body = r#else;
+                }
+
+                self.write_body(body, ast.span.end);
+                self.write_line(self.kw("END"));
+            }
+            AstKind::DefineFunction { body, .. } => {
+                self.write_line(format!(
+                    "{prefix}{} {}",
+                    self.define_function(ast),
+                    self.kw("DO")
+                ));
+                self.write_body(body, ast.span.end);
+                self.write_line(self.kw("END"));
+            }
+            AstKind::Error => {
+                // The comments inside are printed as part of the source left as it is.
+                while self
+                    .comments
+                    .get(self.next_comment)
+                    .is_some_and(|comment| comment.span.start < ast.span.end)
+                {
+                    self.next_comment += 1;
+                }
+
+                let text = self.source.get(ast.span.clone()).unwrap_or_default();
+                self.write_line(format!("{prefix}{}", text.trim()));
+            }
+            _ => {
+                // Comments inside a statement that fits on one line go before it.
+                self.last_end = Some(ast.span.start);
+                self.write_comments_before(ast.span.end);
+                self.write_line(format!("{prefix}{}", self.exp(ast)));
+            }
+        }
+    }
+
+    /// Writes the comments that start before `position` on lines of their own.
+    fn write_comments_before(&mut self, position: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.span.start >= position {
+                break;
+            }
+
+            let span = comment.span.clone();
+            self.write_blank_line_before(span.start);
+            self.write_line(self.source[span.clone()].trim_end().to_string());
+            self.last_end = Some(span.end);
+            self.next_comment += 1;
+        }
+    }
+
+    /// Writes the comment that follows `end` on the same line, if any, at the end of the last line.
+    fn write_trailing_comment(&mut self, end: usize) {
+        let Some(comment) = self.comments.get(self.next_comment) else {
+            return;
+        };
+
+        let on_same_line = comment.span.start >= end
+            && !self.source[end..comment.span.start].contains(['\r', '\n']);
+
+        if on_same_line {
+            self.output.pop();
+            self.output.push(' ');
+            self.output
+                .push_str(self.source[comment.span.clone()].trim_end());
+            self.output.push('\n');
+            self.last_end = Some(comment.span.end);
+            self.next_comment += 1;
+        }
+    }
+
+    /// Writes a blank line if the source has one between the last thing written and `start`.
+    fn write_blank_line_before(&mut self, start: usize) {
+        let Some(gap) = self
+            .last_end
+            .and_then(|last_end| self.source.get(last_end..start))
+        else {
+            return;
+        };
+
+        if gap.matches('\n').count() >= 2 && !self.output.ends_with("\n\n") {
+            self.output.push('\n');
+        }
+    }
+
+    /// Writes a line at the current indentation.
+    fn write_line(&mut self, line: String) {
+        for _ in 0..self.indent_level {
+            self.output.push_str(&self.options.indent.as_string());
+        }
+
+        self.output.push_str(&line);
+        self.output.push('\n');
+    }
+
+    /// Finds where the `ELSE` between `start` and `end` in the source is, so the comments before
+    /// it stay in the block it ends.
+    fn find_else(&self, start: usize, end: usize) -> usize {
+        let Some(gap) = self.source.get(start..end) else {
+            return end;
+        };
+
+        TriviaLexer::from(gap)
+            .map_while(Result::ok)
+            .find_map(|lexeme| match lexeme {
+                Lexeme::Token(token) => match token.kind {
+                    TokenKind::PlainIdentifier(ident) if ident.eq_ignore_ascii_case("else") => {
+                        Some(start + token.span.start)
+                    }
+                    _ => None,
+                },
+                Lexeme::Trivia(_) => None,
+            })
+            .unwrap_or(end)
+    }
+
+    /// Prints a keyword, or several separated by spaces, in the configured case.
+    fn kw(&self, keyword: &str) -> String {
+        self.options.keyword_case.apply(keyword)
+    }
+
+    /// Prints an identifier, escaping it if it is a keyword.
+    fn identifier(&self, name: &str) -> String {
+        if is_keyword(name) {
+            format!("`{name}`")
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Prints an expression on a single line.
+    fn exp(&self, ast: &Ast) -> String {
+        match &ast.kind {
+            AstKind::Create {
+                subject,
+                columns,
+                values,
+            } => {
+                let create = format!("{} {}", self.kw("CREATE"), self.op(subject));
+                match values.as_slice() {
+                    [row] => format!("{create} {}", self.set_columns(columns, row)),
+                    _ => {
+                        let values = values
+                            .iter()
+                            .map(|row| self.tuple(row))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        format!(
+                            "{create} {} ({}) {} {values}",
+                            self.kw("SET"),
+                            self.list(columns),
+                            self.kw("VALUES")
+                        )
+                    }
+                }
+            }
+            AstKind::Relate {
+                relate_op,
+                columns,
+                value,
+            } => {
+                let relate = format!("{} {}", self.kw("RELATE"), self.op(relate_op));
+                if columns.is_empty() {
+                    relate
+                } else {
+                    format!("{relate} {}", self.set_columns(columns, value))
+                }
+            }
+            AstKind::Delete {
+                target,
+                where_guard,
+            } => {
+                let mut parts = vec![self.kw("DELETE"), self.op(target)];
+                if let Some(guard) = where_guard {
+                    parts.push(self.where_guard(guard));
+                }
+
+                parts.join(" ")
+            }
+            AstKind::Update {
+                target,
+                where_guard,
+                column_ops,
+            } => {
+                let mut parts = vec![self.kw("UPDATE"), self.op(target), self.kw("SET")];
+                if column_ops.is_empty() {
+                    parts.push("{}".to_string());
+                } else {
+                    let assignments = column_ops
+                        .iter()
+                        .map(|(column, op, value)| {
+                            format!(
+                                "{} {} {}",
+                                self.op(column),
+                                assign_op(op),
+                                self.operand(value, Precedence::Op)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    parts.push(assignments);
+                }
+
+                if let Some(guard) = where_guard {
+                    parts.push(self.where_guard(guard));
+                }
+
+                parts.join(" ")
+            }
+            AstKind::Select {
+                fields,
+                omit,
+                from,
+                transforms,
+            } => self.select(fields, omit, from, transforms),
+            AstKind::RemoveNamespace { subject, if_exists }
+            | AstKind::DescribeNamespace { subject, if_exists } => {
+                self.remove_or_describe(ast, "NAMESPACE", subject, *if_exists, &[])
+            }
+            AstKind::RemoveDatabase {
+                subject,
+                if_exists,
+                namespace,
+            }
+            | AstKind::DescribeDatabase {
+                subject,
+                if_exists,
+                namespace,
+            } => self.remove_or_describe(
+                ast,
+                "DATABASE",
+                subject,
+                *if_exists,
+                &[("ON NAMESPACE", namespace.as_deref())],
+            ),
+            AstKind::RemoveTable {
+                subject,
+                if_exists,
+                database,
+            }
+            | AstKind::DescribeTable {
+                subject,
+                if_exists,
+                database,
+            } => self.remove_or_describe(
+                ast,
+                "TABLE",
+                subject,
+                *if_exists,
+                &[("ON DATABASE", database.as_deref())],
+            ),
+            AstKind::RemoveEdge {
+                subject,
+                if_exists,
+                database,
+            }
+            | AstKind::DescribeEdge {
+                subject,
+                if_exists,
+                database,
+            } => self.remove_or_describe(
+                ast,
+                "EDGE",
+                subject,
+                *if_exists,
+                &[("ON DATABASE", database.as_deref())],
+            ),
+            AstKind::RemoveType {
+                subject,
+                if_exists,
+                database,
+            }
+            | AstKind::DescribeType {
+                subject,
+                if_exists,
+                database,
+            } => self.remove_or_describe(
+                ast,
+                "TYPE",
+                subject,
+                *if_exists,
+                &[("ON DATABASE", database.as_deref())],
+            ),
+            AstKind::RemoveEnum {
+                subject,
+                if_exists,
+                database,
+            }
+            | AstKind::DescribeEnum {
+                subject,
+                if_exists,
+                database,
+            } => self.remove_or_describe(
+                ast,
+                "ENUM",
+                subject,
+                *if_exists,
+                &[("ON DATABASE", database.as_deref())],
+            ),
+            AstKind::RemoveIndex {
+                subject,
+                if_exists,
+                table,
+                database,
+            }
+            | AstKind::DescribeIndex {
+                subject,
+                if_exists,
+                table,
+                database,
+            } => self.remove_or_describe(
+                ast,
+                "INDEX",
+                subject,
+                *if_exists,
+                &[
+                    ("ON TABLE", Some(table.as_ref())),
+                    ("ON DATABASE", database.as_deref()),
+                ],
+            ),
+            AstKind::RemoveModule {
+                subject,
+                if_exists,
+                database,
+            }
+            | AstKind::DescribeModule {
+                subject,
+                if_exists,
+                database,
+            } => self.remove_or_describe(
+                ast,
+                "MODULE",
+                subject,
+                *if_exists,
+                &[("ON DATABASE", database.as_deref())],
+            ),
+            AstKind::RemoveFunction {
+                subject,
+                if_exists,
+                database,
+            }
+            | AstKind::DescribeFunction {
+                subject,
+                if_exists,
+                database,
+            } => self.remove_or_describe(
+                ast,
+                "FUNCTION",
+                subject,
+                *if_exists,
+                &[("ON DATABASE", database.as_deref())],
+            ),
+            AstKind::RemoveParam {
+                subject,
+                if_exists,
+                database,
+            }
+            | AstKind::DescribeParam {
+                subject,
+                if_exists,
+                database,
+            } => self.remove_or_describe(
+                ast,
+                "PARAM",
+                subject,
+                *if_exists,
+                &[("ON DATABASE", database.as_deref())],
+            ),
+            AstKind::BeginTransaction => self.kw("BEGIN TRANSACTION"),
+            AstKind::CommitTransaction => self.kw("COMMIT TRANSACTION"),
+            AstKind::CancelTransaction => self.kw("CANCEL TRANSACTION"),
+            AstKind::For {
+                variable,
+                iterator,
+                body,
+            } => format!(
+                "{} {} {} {} {} {} {}",
+                self.kw("FOR"),
+                self.op(variable),
+                self.kw("IN"),
+                self.operand(iterator, Precedence::Range),
+                self.kw("DO"),
+                self.exp(body),
+                self.kw("END")
+            ),
+            AstKind::While { condition, body } => format!(
+                "{} {} {} {} {}",
+                self.kw("WHILE"),
+                self.operand(condition, Precedence::Range),
+                self.kw("DO"),
+                self.exp(body),
+                self.kw("END")
+            ),
+            AstKind::If {
+                condition,
+                then,
+                else_ifs,
+                r#else,
+            } => {
+                let mut parts = vec![
+                    self.kw("IF"),
+                    self.operand(condition, Precedence::Range),
+                    self.kw("THEN"),
+                    self.exp(then),
+                ];
+
+                for part in else_ifs {
+                    parts.push(self.kw("ELSE IF"));
+                    parts.push(self.operand(&part.condition, Precedence::Range));
+                    parts.push(self.kw("THEN"));
+                    parts.push(self.exp(&part.body));
+                }
+
+                if let Some(r#else) = r#else {
+                    parts.push(self.kw("ELSE"));
+                    parts.push(self.exp(r#else));
+                }
+
+                parts.push(self.kw("END"));
+                parts.join(" ")
+            }
+            AstKind::Let {
+                name,
+                r#type,
+                value,
+            } => {
+                let mut parts = vec![self.kw("LET"), self.op(name)];
+                if let Some(r#type) = r#type {
+                    parts.push(self.kw("TYPE"));
+                    parts.push(self.type_sig(r#type));
+                }
+
+                parts.push("=".to_string());
+                parts.push(self.exp(value));
+                parts.join(" ")
+            }
+            AstKind::Set {
+                variable,
+                op,
+                value,
+            } => format!(
+                "{} {} {} {}",
+                self.kw("SET"),
+                self.op(variable),
+                assign_op(op),
+                self.exp(value)
+            ),
+            AstKind::DefineNamespace {
+                name,
+                if_not_exists,
+            } => self.define("NAMESPACE", name, *if_not_exists, vec![]),
+            AstKind::DefineDatabase {
+                name,
+                if_not_exists,
+                namespace,
+            } => {
+                let clauses = self.on_clause("ON NAMESPACE", namespace.as_deref());
+                self.define("DATABASE", name, *if_not_exists, clauses)
+            }
+            AstKind::DefineTable {
+                name,
+                if_not_exists,
+                database,
+                fields,
+                ..
+            }
+            | AstKind::DefineEdge {
+                name,
+                if_not_exists,
+                database,
+                fields,
+            } => {
+                let mut clauses = self.on_clause("ON DATABASE", database.as_deref());
+                if let AstKind::DefineTable { mode, .. } = &ast.kind {
+                    match mode {
+                        TableMode::Schemafull => {}
+                        TableMode::Schemaless => clauses.push(self.kw("SCHEMALESS")),
+                        TableMode::Flexible(None) => clauses.push(self.kw("FLEXIBLE")),
+                        TableMode::Flexible(Some(r#type)) => clauses.push(format!(
+                            "{} {} {}",
+                            self.kw("FLEXIBLE"),
+                            self.kw("TYPE"),
+                            self.type_sig(r#type)
+                        )),
+                    }
+                }
+                if !fields.is_empty() {
+                    let fields = fields
+                        .iter()
+                        .map(|field| self.field(field))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    clauses.push(format!("{} {fields}", self.kw("FIELDS")));
+                }
+
+                let object = match &ast.kind {
+                    AstKind::DefineTable { .. } => "TABLE",
+                    _ => "EDGE",
+                };
+
+                self.define(object, name, *if_not_exists, clauses)
+            }
+            AstKind::DefineType {
+                name,
+                if_not_exists,
+                database,
+                fields,
+            } => {
+                let mut clauses = self.on_clause("ON DATABASE", database.as_deref());
+                if !fields.is_empty() {
+                    let fields = fields
+                        .iter()
+                        .map(|(name, r#type)| {
+                            format!(
+                                "{} {} {}",
+                                self.op(name),
+                                self.kw("TYPE"),
+                                self.type_sig(r#type)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    clauses.push(format!("{} {fields}", self.kw("FIELDS")));
+                }
+
+                self.define("TYPE", name, *if_not_exists, clauses)
+            }
+            AstKind::DefineEnum {
+                name,
+                if_not_exists,
+                database,
+                variants,
+            } => {
+                let mut clauses = self.on_clause("ON DATABASE", database.as_deref());
+                if !variants.is_empty() {
+                    clauses.push(format!("{} {}", self.kw("VARIANTS"), self.list(variants)));
+                }
+
+                self.define("ENUM", name, *if_not_exists, clauses)
+            }
+            AstKind::DefineIndex {
+                name,
+                if_not_exists,
+                database,
+                table,
+                columns,
+                function,
+            } => {
+                let mut clauses = self.on_clause("ON TABLE", Some(table));
+                clauses.extend(self.on_clause("ON DATABASE", database.as_deref()));
+                clauses.push(format!("{} {}", self.kw("FIELDS"), self.list(columns)));
+                if let Some(function) = function {
+                    clauses.push(format!(
+                        "{} {}",
+                        self.kw("WITH"),
+                        self.operand(function, Precedence::Call)
+                    ));
+                }
+
+                self.define("INDEX", name, *if_not_exists, clauses)
+            }
+            AstKind::DefineModule {
+                name,
+                if_not_exists,
+                database,
+                block,
+            } => {
+                // The block is kept as it is, right up against the keywords around it.
+                let mut clauses = vec![format!(
+                    "{}{}{}",
+                    self.kw("WITH"),
+                    self.op(block),
+                    self.kw("END")
+                )];
+                clauses.extend(self.on_clause("ON DATABASE", database.as_deref()));
+                self.define("MODULE", name, *if_not_exists, clauses)
+            }
+            AstKind::DefineParam {
+                name,
+                if_not_exists,
+                database,
+                r#type,
+                value,
+            } => {
+                let mut clauses = self.on_clause("ON DATABASE", database.as_deref());
+                if let Some(r#type) = r#type {
+                    clauses.push(format!("{} {}", self.kw("TYPE"), self.type_sig(r#type)));
+                }
+
+                clauses.push(format!(
+                    "{} {}",
+                    self.kw("VALUE"),
+                    self.operand(value, Precedence::Range)
+                ));
+                self.define("PARAM", name, *if_not_exists, clauses)
+            }
+            AstKind::DefineFunction { body, .. } => format!(
+                "{} {} {} {}",
+                self.define_function(ast),
+                self.kw("DO"),
+                self.exp(body),
+                self.kw("END")
+            ),
+            AstKind::RedefineTable {
+                name,
+                if_exists,
+                new_name,
+                database,
+                fields,
+            }
+            | AstKind::RedefineEdge {
+                name,
+                if_exists,
+                new_name,
+                database,
+                fields,
+            }
+            | AstKind::RedefineType {
+                name,
+                if_exists,
+                new_name,
+                database,
+                fields,
+            } => {
+                let mut clauses = self.on_clause("ON DATABASE", database.as_deref());
+                if !fields.is_empty() {
+                    let fields = fields
+                        .iter()
+                        .map(|field| self.redefine_field(field))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    clauses.push(format!("{} {fields}", self.kw("FIELDS")));
+                }
+
+                let object = match &ast.kind {
+                    AstKind::RedefineTable { .. } => "TABLE",
+                    AstKind::RedefineEdge { .. } => "EDGE",
+                    _ => "TYPE",
+                };
+
+                self.redefine(object, name, *if_exists, new_name.as_deref(), clauses)
+            }
+            AstKind::RedefineEnum {
+                name,
+                if_exists,
+                new_name,
+                database,
+                variants,
+            } => {
+                let mut clauses = self.on_clause("ON DATABASE", database.as_deref());
+                if let Some(variants) = variants {
+                    let variants = variants
+                        .iter()
+                        .map(|(variant, new_name)| match new_name {
+                            Some(new_name) => format!(
+                                "{} {} {}",
+                                self.op(variant),
+                                self.kw("AS"),
+                                self.op(new_name)
+                            ),
+                            None => self.op(variant),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    clauses.push(format!("{} {variants}", self.kw("VARIANTS")));
+                }
+
+                self.redefine("ENUM", name, *if_exists, new_name.as_deref(), clauses)
+            }
+            AstKind::RedefineIndex {
+                name,
+                if_exists,
+                new_name,
+                database,
+                table,
+                columns,
+                function,
+            } => {
+                let mut clauses = self.on_clause("ON TABLE", Some(table));
+                clauses.extend(self.on_clause("ON DATABASE", database.as_deref()));
+                if let Some(columns) = columns {
+                    clauses.push(format!("{} {}", self.kw("FIELDS"), self.list(columns)));
+                }
+
+                if let Some(function) = function {
+                    clauses.push(format!(
+                        "{} {}",
+                        self.kw("WITH"),
+                        self.operand(function, Precedence::Call)
+                    ));
+                }
+
+                self.redefine("INDEX", name, *if_exists, new_name.as_deref(), clauses)
+            }
+            AstKind::RedefineParam {
+                name,
+                if_exists,
+                new_name,
+                database,
+                r#type,
+                value,
+            } => {
+                let mut clauses = self.on_clause("ON DATABASE", database.as_deref());
+                if let Some(r#type) = r#type {
+                    clauses.push(format!("{} {}", self.kw("TYPE"), self.type_sig(r#type)));
+                }
+
+                if let Some(value) = value {
+                    clauses.push(format!(
+                        "{} {}",
+                        self.kw("VALUE"),
+                        self.operand(value, Precedence::Range)
+                    ));
+                }
+
+                self.redefine("PARAM", name, *if_exists, new_name.as_deref(), clauses)
+            }
+            AstKind::Use { database } => {
+                format!("{} {}", self.kw("USE DATABASE"), self.op(database))
+            }
+            AstKind::Break => self.kw("BREAK"),
+            AstKind::Continue => self.kw("CONTINUE"),
+            AstKind::Return(value) => match value {
+                Some(value) => format!("{} {}", self.kw("RETURN"), self.exp(value)),
+                None => self.kw("RETURN"),
+            },
+            AstKind::Program(statements) => statements
+                .iter()
+                .map(|statement| self.exp(statement))
+                .collect::<Vec<_>>()
+                .join("; "),
+            AstKind::Error => self
+                .source
+                .get(ast.span.clone())
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            _ => self.op(ast),
+        }
+    }
+
+    /// Prints an operation, parenthesizing it if it binds more loosely than `min`.
+    fn operand(&self, ast: &Ast, min: Precedence) -> String {
+        if precedence(ast) < min {
+            format!("({})", self.op(ast))
+        } else {
+            self.op(ast)
+        }
+    }
+
+    /// Prints an operation.
+    fn op(&self, ast: &Ast) -> String {
+        if let Some((left, op, precedence, right)) = binary(&ast.kind) {
+            let (left_min, right_min) = match precedence {
+                Precedence::Pow => (Precedence::Access, Precedence::Pow),
+                Precedence::Range => (Precedence::Or, Precedence::Or),
+                _ => (precedence, precedence.tighter()),
+            };
+
+            let op = if op.starts_with(char::is_alphabetic) {
+                self.kw(op)
+            } else {
+                op.to_string()
+            };
+
+            // Ranges read better without spaces, like `1..10`.
+            let space = if precedence == Precedence::Range {
+                ""
+            } else {
+                " "
+            };
+
+            return format!(
+                "{}{space}{op}{space}{}",
+                self.operand(left, left_min),
+                self.operand(right, right_min)
+            );
+        }
+
+        match &ast.kind {
+            AstKind::Wildcard => "*".to_string(),
+            AstKind::Identifier(name) => self.identifier(name),
+            AstKind::Variable(name) => format!("${name}"),
+            AstKind::IdOp(ident, value) => format!("{}:{}", self.op(ident), self.op(value)),
+            AstKind::ScopedIdentifier(segments) => segments
+                .iter()
+                .map(|segment| self.op(segment))
+                .collect::<Vec<_>>()
+                .join("::"),
+            AstKind::IntegerLiteral(value) => value.to_string(),
+            AstKind::FloatLiteral(value) => {
+                let value = value.to_string();
+                if value.contains('.') {
+                    value
+                } else {
+                    format!("{value}.0")
+                }
+            }
+            AstKind::StringLiteral(value) => quote(value),
+            AstKind::ByteStringLiteral(value) => format!("b{}", quote(value)),
+            AstKind::RegexLiteral { pattern, flags } => format!("//{pattern}//{flags}"),
+            AstKind::BooleanLiteral(value) => self.kw(&value.to_string()),
+            AstKind::NoneLiteral => self.kw("NONE"),
+            AstKind::ModuleBlock(block) => block.to_string(),
+            AstKind::ListLiteral(items) => format!("[{}]", self.list(items)),
+            AstKind::TupleLiteral(items) => self.tuple(items),
+            AstKind::ObjectLiteral(entries) if entries.is_empty() => "{}".to_string(),
+            AstKind::ObjectLiteral(entries) => {
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| {
+                        format!("{}: {}", self.op(key), self.operand(value, Precedence::Op))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{{ {entries} }}")
+            }
+            AstKind::Index { subject, index } => format!(
+                "{}[{}]",
+                self.operand(subject, Precedence::Atom),
+                self.operand(index, Precedence::Op)
+            ),
+            AstKind::FunctionArg { name, value } => match name {
+                Some(name) => format!(
+                    "{} = {}",
+                    self.op(name),
+                    self.operand(value, Precedence::Op)
+                ),
+                None => self.operand(value, Precedence::Op),
+            },
+            AstKind::FunctionCall { subject, args } => format!(
+                "{}({})",
+                self.operand(subject, Precedence::Index),
+                self.list(args)
+            ),
+            AstKind::LogicalNotOp(subject) => {
+                format!(
+                    "{} {}",
+                    self.kw("NOT"),
+                    self.operand(subject, Precedence::Call)
+                )
+            }
+            AstKind::BitwiseNotOp(subject) => {
+                format!("~{}", self.operand(subject, Precedence::Call))
+            }
+            AstKind::PlusSignOp(subject) => format!("+{}", self.operand(subject, Precedence::Not)),
+            AstKind::MinusSignOp(subject) => {
+                format!("-{}", self.operand(subject, Precedence::Not))
+            }
+            AstKind::DotAccessOp { subject, field } => format!(
+                "{}.{}",
+                self.operand(subject, Precedence::Access),
+                self.op(field)
+            ),
+            AstKind::SafeNavigationAccessOp { subject, field } => format!(
+                "{}?.{}",
+                self.operand(subject, Precedence::Access),
+                self.op(field)
+            ),
+            AstKind::DotAccessWildcardOp { subject } => {
+                format!("{}.*", self.operand(subject, Precedence::Access))
+            }
+            AstKind::UnboundedRangeOp(start) => match start {
+                Some(start) => format!("{}..", self.operand(start, Precedence::Or)),
+                None => "..".to_string(),
+            },
+            AstKind::SingleRelateId { subject, alias } => {
+                self.aliased(self.operand(subject, Precedence::Range), alias.as_deref())
+            }
+            AstKind::RelateEdgeId {
+                subject,
+                depth,
+                alias,
+            } => {
+                let depth = depth
+                    .as_ref()
+                    .map(|depth| match depth.kind {
+                        AstKind::UnboundedRangeOp(_) => format!("[{}]", self.op(depth)),
+                        _ => format!("[{}]", self.operand(depth, Precedence::Op)),
+                    })
+                    .unwrap_or_default();
+                self.aliased(format!("{}{depth}", self.op(subject)), alias.as_deref())
+            }
+            AstKind::RelateOp {
+                left,
+                l_op,
+                edge,
+                r_op,
+                right,
+            } => format!(
+                "{} {} {} {} {}",
+                self.op(left),
+                arrow(l_op),
+                self.edge(edge),
+                arrow(r_op),
+                self.op(right)
+            ),
+            AstKind::AliasOp { subject, alias } => {
+                self.aliased(self.operand(subject, Precedence::Range), Some(alias))
+            }
+            _ => self.exp(ast),
+        }
+    }
+
+    /// Prints the edge of a relate operation, which may combine edges with `NOT`, `AND` and `OR`.
+    fn edge(&self, ast: &Ast) -> String {
+        match &ast.kind {
+            AstKind::LogicalOrOp(left, right) => {
+                format!("{} {} {}", self.edge(left), self.kw("OR"), self.edge(right))
+            }
+            AstKind::LogicalAndOp(left, right) => {
+                format!(
+                    "{} {} {}",
+                    self.edge(left),
+                    self.kw("AND"),
+                    self.edge(right)
+                )
+            }
+            AstKind::LogicalNotOp(subject) => format!("{} {}", self.kw("NOT"), self.edge(subject)),
+            _ => self.op(ast),
+        }
+    }
+
+    /// Prints a subject followed by its alias, if any.
+    fn aliased(&self, subject: String, alias: Option<&Ast>) -> String {
+        match alias {
+            Some(alias) => format!("{subject} {} {}", self.kw("AS"), self.op(alias)),
+            None => subject,
+        }
+    }
+
+    /// Prints operations separated by commas.
+    fn list(&self, items: &[Ast]) -> String {
+        items
+            .iter()
+            .map(|item| self.operand(item, Precedence::Op))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Prints a tuple literal, with the trailing comma a tuple of one needs.
+    fn tuple(&self, items: &[Ast]) -> String {
+        match items {
+            [item] => format!("({},)", self.operand(item, Precedence::Op)),
+            _ => format!("({})", self.list(items)),
+        }
+    }
+
+    /// Prints the `SET` clause of a `CREATE` or `RELATE`.
+    fn set_columns(&self, columns: &[Ast], values: &[Ast]) -> String {
+        if columns.is_empty() {
+            return format!("{} {{}}", self.kw("SET"));
+        }
+
+        let assignments = columns
+            .iter()
+            .zip(values)
+            .map(|(column, value)| {
+                format!(
+                    "{} = {}",
+                    self.op(column),
+                    self.operand(value, Precedence::Op)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("{} {assignments}", self.kw("SET"))
+    }
+
+    /// Prints a `WHERE` guard.
+    fn where_guard(&self, guard: &Ast) -> String {
+        format!(
+            "{} {}",
+            self.kw("WHERE"),
+            self.operand(guard, Precedence::Op)
+        )
+    }
+
+    /// Prints a `SELECT` expression.
+    fn select(
+        &self,
+        fields: &[SelectColumn],
+        omit: &[Ast],
+        from: &[Ast],
+        transforms: &[SelectTransform],
+    ) -> String {
+        let fields = fields
+            .iter()
+            .map(|field| match field {
+                SelectColumn::Column(column) => self.operand(column, Precedence::Op),
+                SelectColumn::Fold { subject, alias } => self.aliased(
+                    format!(
+                        "{} {}",
+                        self.kw("FOLD"),
+                        self.operand(subject, Precedence::Range)
+                    ),
+                    alias.as_deref(),
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut parts = vec![self.kw("SELECT"), fields];
+        if !omit.is_empty() {
+            parts.push(self.kw("OMIT"));
+            parts.push(self.list(omit));
+        }
+
+        parts.push(self.kw("FROM"));
+        parts.push(self.list(from));
+
+        let ranges = |items: &[Ast]| {
+            items
+                .iter()
+                .map(|item| self.operand(item, Precedence::Range))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        for transform in transforms {
+            parts.push(match transform {
+                SelectTransform::WithNoIndex => self.kw("WITH NO INDEX"),
+                SelectTransform::WithIndexes(indexes) => {
+                    format!("{} {}", self.kw("WITH INDEXES"), self.list(indexes))
+                }
+                SelectTransform::WhereGuard(guard) => self.where_guard(guard),
+                SelectTransform::GroupBy(fields) => {
+                    format!("{} {}", self.kw("GROUP BY"), ranges(fields))
+                }
+                SelectTransform::OrderBy { fields, direction } => match direction {
+                    Direction::Ascending => format!("{} {}", self.kw("ORDER BY"), ranges(fields)),
+                    Direction::Descending => format!(
+                        "{} {} {}",
+                        self.kw("ORDER BY"),
+                        ranges(fields),
+                        self.kw("DESC")
+                    ),
+                },
+                SelectTransform::StartAt(start) => format!(
+                    "{} {}",
+                    self.kw("START AT"),
+                    self.operand(start, Precedence::Range)
+                ),
+                SelectTransform::LimitTo(limit) => format!(
+                    "{} {}",
+                    self.kw("LIMIT"),
+                    self.operand(limit, Precedence::Range)
+                ),
+            });
+        }
+
+        parts.join(" ")
+    }
+
+    /// Prints a `REMOVE` or `DESCRIBE` expression.
+    fn remove_or_describe(
+        &self,
+        ast: &Ast,
+        object: &str,
+        subject: &Ast,
+        if_exists: bool,
+        parents: &[(&str, Option<&Ast>)],
+    ) -> String {
+        let verb = match ast.kind {
+            AstKind::RemoveNamespace { .. }
+            | AstKind::RemoveDatabase { .. }
+            | AstKind::RemoveTable { .. }
+            | AstKind::RemoveEdge { .. }
+            | AstKind::RemoveType { .. }
+            | AstKind::RemoveEnum { .. }
+            | AstKind::RemoveIndex { .. }
+            | AstKind::RemoveModule { .. }
+            | AstKind::RemoveParam { .. }
+            | AstKind::RemoveFunction { .. } => "REMOVE",
+            _ => "DESCRIBE",
+        };
+
+        let mut parts = vec![self.kw(verb), self.kw(object)];
+        if if_exists {
+            parts.push(self.kw("IF EXISTS"));
+        }
+
+        parts.push(self.op(subject));
+        for (keyword, parent) in parents {
+            parts.extend(self.on_clause(keyword, *parent));
+        }
+
+        parts.join(" ")
+    }
+
+    /// Prints a `DEFINE` statement with the clauses that follow its name.
+    fn define(
+        &self,
+        object: &str,
+        name: &Ast,
+        if_not_exists: bool,
+        clauses: Vec<String>,
+    ) -> String {
+        let mut parts = vec![self.kw("DEFINE"), self.kw(object)];
+        if if_not_exists {
+            parts.push(self.kw("IF NOT EXISTS"));
+        }
+
+        parts.push(self.op(name));
+        parts.extend(clauses);
+        parts.join(" ")
+    }
+
+    /// Prints a `REDEFINE` statement with the clauses that follow its new name.
+    fn redefine(
+        &self,
+        object: &str,
+        name: &Ast,
+        if_exists: bool,
+        new_name: Option<&Ast>,
+        clauses: Vec<String>,
+    ) -> String {
+        let mut parts = vec![self.kw("REDEFINE"), self.kw(object)];
+        if if_exists {
+            parts.push(self.kw("IF EXISTS"));
+        }
+
+        parts.push(self.op(name));
+        parts.extend(self.on_clause("AS", new_name));
+        parts.extend(clauses);
+        parts.join(" ")
+    }
+
+    /// Prints a field of a `REDEFINE TABLE`, `REDEFINE EDGE` or `REDEFINE TYPE`.
+    fn redefine_field(&self, field: &RedefineField) -> String {
+        let mut parts = vec![self.op(&field.name)];
+        parts.extend(self.on_clause("AS", field.new_name.as_deref()));
+        if let Some(r#type) = &field.r#type {
+            parts.push(self.kw("TYPE"));
+            parts.push(self.type_sig(r#type));
+        }
+
+        parts.join(" ")
+    }
+
+    /// Prints a `DEFINE FUNCTION` statement up to the `DO` of its body.
+    fn define_function(&self, ast: &Ast) -> String {
+        let AstKind::DefineFunction {
+            name,
+            if_not_exists,
+            database,
+            params,
+            r#type,
+            ..
+        } = &ast.kind
+        else {
+            return self.exp(ast);
+        };
+
+        let params = params
+            .iter()
+            .map(|(param, r#type)| {
+                format!(
+                    "{} {} {}",
+                    self.op(param),
+                    self.kw("TYPE"),
+                    self.type_sig(r#type)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // The parameters follow the name directly, so they are printed as part of it.
+        let mut parts = vec![self.kw("DEFINE"), self.kw("FUNCTION")];
+        if *if_not_exists {
+            parts.push(self.kw("IF NOT EXISTS"));
+        }
+
+        parts.push(format!("{}({params})", self.op(name)));
+        if let Some(r#type) = r#type {
+            parts.push(format!("{} {}", self.kw("TYPE"), self.type_sig(r#type)));
+        }
+
+        parts.extend(self.on_clause("ON DATABASE", database.as_deref()));
+        parts.join(" ")
+    }
+
+    /// Prints a clause like `ON DATABASE db`, if there is a parent to print.
+    fn on_clause(&self, keyword: &str, parent: Option<&Ast>) -> Vec<String> {
+        parent
+            .map(|parent| format!("{} {}", self.kw(keyword), self.op(parent)))
+            .into_iter()
+            .collect()
+    }
+
+    /// Prints a field of a `DEFINE TABLE` or `DEFINE EDGE`.
+    fn field(&self, field: &Field) -> String {
+        let mut parts = vec![
+            self.op(&field.name),
+            self.kw("TYPE"),
+            self.type_sig(&field.r#type),
+        ];
+
+        if let Some(default) = &field.default {
+            parts.push(self.kw("VALUE"));
+            parts.push(self.operand(default, Precedence::Range));
+        }
+
+        for assertion in &field.assertions {
+            parts.push(self.kw("ASSERT"));
+            parts.push(self.operand(assertion, Precedence::Range));
+        }
+
+        if field.readonly {
+            parts.push(self.kw("READONLY"));
+        }
+
+        if field.unique {
+            parts.push(self.kw("UNIQUE"));
+        }
+
+        parts.join(" ")
+    }
+
+    /// Prints a type signature.
+    fn type_sig(&self, sig: &TypeSig) -> String {
+        match sig {
+            TypeSig::Array { r#type, length } => {
+                format!("[{} {}]", self.type_sig(r#type), self.op(length))
+            }
+            TypeSig::List(r#type) => format!("[{}]", self.type_sig(r#type)),
+            TypeSig::Tuple(types) => format!("({})", self.type_sigs(types)),
+            TypeSig::Option(r#type) => format!("{}?", self.type_sig(r#type)),
+            TypeSig::Generic { name, parameters } => {
+                format!("{}[{}]", self.op(name), self.type_sigs(parameters))
+            }
+            TypeSig::Basic(name) => self.op(name),
+        }
+    }
+
+    /// Prints type signatures separated by commas.
+    fn type_sigs(&self, sigs: &[TypeSig]) -> String {
+        sigs.iter()
+            .map(|sig| self.type_sig(sig))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Precedence {
+    /// Gets the precedence that binds one step more tightly.
+    fn tighter(self) -> Self {
+        match self {
+            Self::Exp => Self::Op,
+            Self::Op => Self::Range,
+            Self::Range => Self::Or,
+            Self::Or => Self::And,
+            Self::And => Self::BitOr,
+            Self::BitOr => Self::BitXor,
+            Self::BitXor => Self::BitAnd,
+            Self::BitAnd => Self::Eq,
+            Self::Eq => Self::Rel,
+            Self::Rel => Self::MatchSim,
+            Self::MatchSim => Self::Shift,
+            Self::Shift => Self::Add,
+            Self::Add => Self::Mul,
+            Self::Mul => Self::Pow,
+            Self::Pow => Self::Access,
+            Self::Access => Self::Sign,
+            Self::Sign => Self::Not,
+            Self::Not => Self::Call,
+            Self::Call => Self::Index,
+            Self::Index | Self::Atom => Self::Atom,
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Parses `source` and formats it, keeping its comments.
+///
+/// Source that does not parse is returned as the first error found in it.
+pub fn format(source: &str, options: &FormatOptions) -> ParserResult<String> {
+    let ast = Parser::new(source, PARSER_CACHE_SIZE)
+        .parse_program()
+        .into_result()?;
+
+    Ok(Formatter::new(options.clone())
+        .with_source(source)
+        .format(&ast))
+}
+
+/// Gets how tightly an AST node binds.
+fn precedence(ast: &Ast) -> Precedence {
+    if let Some((_, _, precedence, _)) = binary(&ast.kind) {
+        return precedence;
+    }
+
+    match &ast.kind {
+        AstKind::Wildcard
+        | AstKind::Identifier(_)
+        | AstKind::Variable(_)
+        | AstKind::IdOp(..)
+        | AstKind::ScopedIdentifier(_)
+        | AstKind::IntegerLiteral(_)
+        | AstKind::FloatLiteral(_)
+        | AstKind::StringLiteral(_)
+        | AstKind::ByteStringLiteral(_)
+        | AstKind::RegexLiteral { .. }
+        | AstKind::BooleanLiteral(_)
+        | AstKind::NoneLiteral
+        | AstKind::ListLiteral(_)
+        | AstKind::TupleLiteral(_)
+        | AstKind::ObjectLiteral(_) => Precedence::Atom,
+        AstKind::Index { .. } => Precedence::Index,
+        AstKind::FunctionCall { .. } => Precedence::Call,
+        AstKind::LogicalNotOp(_) | AstKind::BitwiseNotOp(_) => Precedence::Not,
+        AstKind::PlusSignOp(_) | AstKind::MinusSignOp(_) => Precedence::Sign,
+        AstKind::DotAccessOp { .. }
+        | AstKind::SafeNavigationAccessOp { .. }
+        | AstKind::DotAccessWildcardOp { .. } => Precedence::Access,
+        AstKind::UnboundedRangeOp(_) => Precedence::Range,
+        AstKind::FunctionArg { .. }
+        | AstKind::SingleRelateId { .. }
+        | AstKind::RelateEdgeId { .. }
+        | AstKind::RelateOp { .. }
+        | AstKind::AliasOp { .. } => Precedence::Op,
+        _ => Precedence::Exp,
+    }
+}
+
+/// Splits a binary operation into its operands, operator and precedence.
+fn binary<'b, 'a>(
+    kind: &'b AstKind<'a>,
+) -> Option<(&'b Ast<'a>, &'static str, Precedence, &'b Ast<'a>)> {
+    let (left, op, precedence, right) = match kind {
+        AstKind::ExponentiationOp(l, r) => (l, "**", Precedence::Pow, r),
+        AstKind::MultiplicationOp(l, r) => (l, "*", Precedence::Mul, r),
+        AstKind::DivisionOp(l, r) => (l, "/", Precedence::Mul, r),
+        AstKind::ModulusOp(l, r) => (l, "%", Precedence::Mul, r),
+        AstKind::AdditionOp(l, r) => (l, "+", Precedence::Add, r),
+        AstKind::SubtractionOp(l, r) => (l, "-", Precedence::Add, r),
+        AstKind::LeftShiftOp(l, r) => (l, "<<", Precedence::Shift, r),
+        AstKind::RightShiftOp(l, r) => (l, ">>", Precedence::Shift, r),
+        AstKind::MatchOp(l, r) => (l, "MATCH", Precedence::MatchSim, r),
+        AstKind::NotMatchOp(l, r) => (l, "NOT MATCH", Precedence::MatchSim, r),
+        AstKind::SimilarityOp(l, r) => (l, "<>", Precedence::MatchSim, r),
+        AstKind::LessThanOp(l, r) => (l, "<", Precedence::Rel, r),
+        AstKind::GreaterThanOp(l, r) => (l, ">", Precedence::Rel, r),
+        AstKind::LessThanEqualToOp(l, r) => (l, "<=", Precedence::Rel, r),
+        AstKind::GreaterThanEqualToOp(l, r) => (l, ">=", Precedence::Rel, r),
+        AstKind::InOp(l, r) => (l, "IN", Precedence::Rel, r),
+        AstKind::NotInOp(l, r) => (l, "NOT IN", Precedence::Rel, r),
+        AstKind::ContainsOp(l, r) => (l, "CONTAINS", Precedence::Rel, r),
+        AstKind::NotContainsOp(l, r) => (l, "NOT CONTAINS", Precedence::Rel, r),
+        AstKind::ContainsNoneOp(l, r) => (l, "CONTAINS NONE", Precedence::Rel, r),
+        AstKind::ContainsAllOp(l, r) => (l, "CONTAINS ALL", Precedence::Rel, r),
+        AstKind::ContainsAnyOp(l, r) => (l, "CONTAINS ANY", Precedence::Rel, r),
+        AstKind::EqualToOp(l, r) => (l, "==", Precedence::Eq, r),
+        AstKind::IsOp(l, r) => (l, "IS", Precedence::Eq, r),
+        AstKind::IsNotOp(l, r) => (l, "IS NOT", Precedence::Eq, r),
+        AstKind::BitwiseAndOp(l, r) => (l, "&", Precedence::BitAnd, r),
+        AstKind::BitwiseXorOp(l, r) => (l, "^", Precedence::BitXor, r),
+        AstKind::BitwiseOrOp(l, r) => (l, "|", Precedence::BitOr, r),
+        AstKind::LogicalAndOp(l, r) => (l, "AND", Precedence::And, r),
+        AstKind::LogicalOrOp(l, r) => (l, "OR", Precedence::Or, r),
+        AstKind::NullCoalesceOp(l, r) => (l, "?:", Precedence::Or, r),
+        AstKind::RangeOp(l, r) => (l, "..", Precedence::Range, r),
+        AstKind::RangeInclusiveOp(l, r) => (l, "..=", Precedence::Range, r),
+        _ => return None,
+    };
+
+    Some((left, op, precedence, right))
+}
+
+/// Quotes the content of a string literal, which is kept with its escapes as written.
+fn quote(content: &str) -> String {
+    if content.contains('\'') {
+        format!("\"{content}\"")
+    } else {
+        format!("'{content}'")
+    }
+}
+
+/// Prints an assignment operator.
+fn assign_op(op: &UpdateAssign) -> &'static str {
+    match op {
+        UpdateAssign::Direct => "=",
+        UpdateAssign::Plus => "+=",
+        UpdateAssign::Minus => "-=",
+        UpdateAssign::Mul => "*=",
+        UpdateAssign::Div => "/=",
+        UpdateAssign::Mod => "%=",
+        UpdateAssign::Pow => "**=",
+        UpdateAssign::BitAnd => "&=",
+        UpdateAssign::BitOr => "|=",
+        UpdateAssign::BitXor => "^=",
+        UpdateAssign::BitNot => "~=",
+        UpdateAssign::Shl => "<<=",
+        UpdateAssign::Shr => ">>=",
+    }
+}
+
+/// Prints the arrow of a relate operation.
+fn arrow(arrow: &RelateArrow) -> &'static str {
+    match arrow {
+        RelateArrow::Left => "<-",
+        RelateArrow::Right => "->",
+        RelateArrow::MultiLeft => "<<-",
+        RelateArrow::MultiRight => "->>",
+    }
+}
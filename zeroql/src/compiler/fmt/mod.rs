@@ -0,0 +1,29 @@
+//! A formatter for the ZeroQL language.
+//!
+//! The formatter prints an AST back to source in a canonical style: one statement per line,
+//! keywords in a single case, operators spaced out and parentheses only where the grammar needs
+//! them. Formatting source keeps its comments, which the lexer otherwise skips:
+//!
+//! ```
+//! use zeroql::fmt::{self, FormatOptions};
+//!
+//! let source = "let $x=1 -- the answer\nselect * from person where age>$x";
+//! let formatted = fmt::format(source, &FormatOptions::default()).unwrap();
+//!
+//! assert_eq!(
+//!     formatted,
+//!     "LET $x = 1 -- the answer\nSELECT * FROM person WHERE age > $x\n"
+//! );
+//! ```
+
+mod formatter;
+mod options;
+#[cfg(test)]
+mod tests;
+
+//--------------------------------------------------------------------------------------------------
+// Exports
+//--------------------------------------------------------------------------------------------------
+
+pub use formatter::*;
+pub use options::*;
@@ -0,0 +1,84 @@
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// Options that control how the [`Formatter`][super::Formatter] prints source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// How each level of a block is indented.
+    pub indent: Indent,
+
+    /// The case keywords are printed in.
+    pub keyword_case: KeywordCase,
+}
+
+/// How each level of a block is indented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    /// Indent with the given number of spaces.
+    Spaces(usize),
+
+    /// Indent with a tab.
+    Tab,
+}
+
+/// The case keywords are printed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeywordCase {
+    /// Keywords are printed in upper case, e.g. `SELECT`.
+    Upper,
+
+    /// Keywords are printed in lower case, e.g. `select`.
+    Lower,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl FormatOptions {
+    /// Sets how each level of a block is indented.
+    pub fn with_indent(mut self, indent: Indent) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Sets the case keywords are printed in.
+    pub fn with_keyword_case(mut self, keyword_case: KeywordCase) -> Self {
+        self.keyword_case = keyword_case;
+        self
+    }
+}
+
+impl Indent {
+    /// Gets the text of one level of indentation.
+    pub fn as_string(&self) -> String {
+        match self {
+            Self::Spaces(width) => " ".repeat(*width),
+            Self::Tab => "\t".to_string(),
+        }
+    }
+}
+
+impl KeywordCase {
+    /// Prints a keyword in this case.
+    pub fn apply(&self, keyword: &str) -> String {
+        match self {
+            Self::Upper => keyword.to_uppercase(),
+            Self::Lower => keyword.to_lowercase(),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: Indent::Spaces(4),
+            keyword_case: KeywordCase::Upper,
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{
+    ast::Ast,
+    fmt::{format, FormatOptions, Formatter, Indent, KeywordCase},
+    parser::Parser,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The parser tests, whose inputs make up the round-trip corpus.
+const PARSER_TESTS: &[&str] = &[
+    include_str!("../parser/tests/expressions.rs"),
+    include_str!("../parser/tests/keywords.rs"),
+    include_str!("../parser/tests/limits.rs"),
+    include_str!("../parser/tests/literals.rs"),
+    include_str!("../parser/tests/meta.rs"),
+    include_str!("../parser/tests/operations.rs"),
+    include_str!("../parser/tests/operators.rs"),
+    include_str!("../parser/tests/statements.rs"),
+];
+
+lazy_static! {
+    static ref SPAN_REGEX: Regex = Regex::new(r"span: \d+\.\.\d+").unwrap();
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn test_fmt_round_trips_parser_corpus() {
+    let options = [
+        FormatOptions::default(),
+        FormatOptions::default()
+            .with_indent(Indent::Tab)
+            .with_keyword_case(KeywordCase::Lower),
+    ];
+
+    let snippets = corpus();
+    assert!(
+        snippets.len() > 200,
+        "corpus has {} snippets",
+        snippets.len()
+    );
+
+    for snippet in &snippets {
+        let expected = parse(snippet).unwrap();
+
+        for options in &options {
+            let formatted = format(snippet, options)
+                .unwrap_or_else(|error| panic!("failed to format {snippet:?}: {error}"));
+
+            let actual = parse(&formatted).unwrap_or_else(|| {
+                panic!("formatting {snippet:?} gave {formatted:?}, which does not parse")
+            });
+
+            assert_eq!(
+                actual, expected,
+                "formatting {snippet:?} gave {formatted:?}, which parses differently"
+            );
+
+            assert_eq!(
+                format(&formatted, options).unwrap(),
+                formatted,
+                "formatting {snippet:?} is not idempotent"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_fmt_canonical_layout() {
+    let source = r#"let $people=select * from person where age>=18 and name!=''
+for $p in $people do
+if $p.age>65 then
+set $total+=2
+else if $p.age>40 then
+set $total += 1
+else
+continue
+end
+end
+define table if not exists person on db app fields name type string assert name!='' unique,age type u8? value 0
+"#;
+
+    assert_eq!(
+        format(source, &FormatOptions::default()).unwrap(),
+        r#"LET $people = SELECT * FROM person WHERE age >= 18 AND name IS NOT ''
+FOR $p IN $people DO
+    IF $p.age > 65 THEN
+        SET $total += 2
+    ELSE IF $p.age > 40 THEN
+        SET $total += 1
+    ELSE
+        CONTINUE
+    END
+END
+DEFINE TABLE IF NOT EXISTS person ON DATABASE app FIELDS name TYPE string ASSERT name IS NOT '' UNIQUE, age TYPE u8? VALUE 0
+"#
+    );
+}
+
+#[test]
+fn test_fmt_define_function_layout() {
+    let source = r#"define function discount($price type f64,$rate type f64?) type f64 on db app do
+if $rate==none then
+return $price
+end
+return $price*0.9
+end
+"#;
+
+    assert_eq!(
+        format(source, &FormatOptions::default()).unwrap(),
+        r#"DEFINE FUNCTION discount($price TYPE f64, $rate TYPE f64?) TYPE f64 ON DATABASE app DO
+    IF $rate == NONE THEN
+        RETURN $price
+    END
+    RETURN $price * 0.9
+END
+"#
+    );
+}
+
+#[test]
+fn test_fmt_options() {
+    let source = "WHILE $a < 10 DO SET $a += 1 END";
+
+    assert_eq!(
+        format(
+            source,
+            &FormatOptions::default()
+                .with_indent(Indent::Tab)
+                .with_keyword_case(KeywordCase::Lower)
+        )
+        .unwrap(),
+        "while $a < 10 do\n\tset $a += 1\nend\n"
+    );
+
+    assert_eq!(
+        format(
+            source,
+            &FormatOptions::default().with_indent(Indent::Spaces(2))
+        )
+        .unwrap(),
+        "WHILE $a < 10 DO\n  SET $a += 1\nEND\n"
+    );
+}
+
+#[test]
+fn test_fmt_parenthesizes_by_precedence() {
+    let cases = [
+        ("(a + b) * c", "(a + b) * c"),
+        ("a + (b * c)", "a + b * c"),
+        ("a - (b - c)", "a - (b - c)"),
+        ("(a - b) - c", "a - b - c"),
+        ("(a ** b) ** c", "(a ** b) ** c"),
+        ("a ** (b ** c)", "a ** b ** c"),
+        ("-(a.b)", "-(a.b)"),
+        ("(-a).b", "-a.b"),
+        ("!(a && b) || c", "NOT (a AND b) OR c"),
+        ("(a..b)[0]", "(a..b)[0]"),
+        ("f(x = 1, (y = 2))", "f(x = 1, y IS 2)"),
+        ("(1,)", "(1,)"),
+        ("`select`.`from`", "`select`.`from`"),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(
+            format(source, &FormatOptions::default()).unwrap(),
+            format!("{expected}\n"),
+            "formatting {source:?}"
+        );
+    }
+}
+
+#[test]
+fn test_fmt_keeps_comments() {
+    let source = r#"-- Migration 1
+
+-- People.
+define table person -- with no fields yet
+select * from person where (
+    -- only adults
+    age >= 18)
+if $a then -- check
+    -- nothing to do
+    break
+
+    -- really
+    continue
+    -- done
+else
+    -- otherwise
+    continue
+end -- if
+-- The end.
+"#;
+
+    assert_eq!(
+        format(source, &FormatOptions::default()).unwrap(),
+        r#"-- Migration 1
+
+-- People.
+DEFINE TABLE person -- with no fields yet
+-- only adults
+SELECT * FROM person WHERE age >= 18
+IF $a THEN
+    -- check
+    -- nothing to do
+    BREAK
+
+    -- really
+    CONTINUE
+    -- done
+ELSE
+    -- otherwise
+    CONTINUE
+END -- if
+-- The end.
+"#
+    );
+}
+
+#[test]
+fn test_fmt_without_source() {
+    let source = "LET $a = 1 -- dropped";
+    let ast = Parser::new(source, 20)
+        .parse_program()
+        .into_result()
+        .unwrap();
+
+    assert_eq!(
+        Formatter::new(FormatOptions::default()).format(&ast),
+        "LET $a = 1\n"
+    );
+}
+
+#[test]
+fn test_fmt_rejects_invalid_source() {
+    assert!(format("LET $a = ", &FormatOptions::default()).is_err());
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Parses a program, leaving out the spans so ASTs of different sources can be compared.
+fn parse(source: &str) -> Option<String> {
+    let program = Parser::new(source, 20).parse_program();
+    if program.has_errors() {
+        return None;
+    }
+
+    Some(without_spans(&program.ast))
+}
+
+/// Prints an AST without its spans.
+fn without_spans(ast: &Ast) -> String {
+    SPAN_REGEX.replace_all(&format!("{ast:?}"), "").into_owned()
+}
+
+/// Collects the statements and expressions in the inputs of the parser tests.
+///
+/// Most inputs hold several expressions side by side for a test to parse one after another, so
+/// they are split up the same way.
+fn corpus() -> Vec<String> {
+    let mut snippets = vec![];
+    for input in PARSER_TESTS.iter().flat_map(|test| string_args(test)) {
+        if parse(&input).is_some() {
+            snippets.push(input);
+            continue;
+        }
+
+        let parser = &mut Parser::new(&input, 20);
+        loop {
+            while let Ok(Some(_)) = parser.parse_terminator() {}
+
+            let ast = match parser.parse_stmt() {
+                Ok(Some(ast)) => ast,
+                _ => match parser.parse_exp() {
+                    Ok(Some(ast)) => ast,
+                    _ => break,
+                },
+            };
+
+            let snippet = &input[ast.span];
+            if parse(snippet).is_some() {
+                snippets.push(snippet.to_string());
+            }
+        }
+    }
+
+    snippets
+}
+
+/// Gets the string literals passed to `Parser::new` in the source of a test.
+fn string_args(test: &str) -> Vec<String> {
+    let mut args = vec![];
+    for (start, _) in test.match_indices("Parser::new(") {
+        let rest = test[start + "Parser::new(".len()..].trim_start();
+        if let Some(rest) = rest.strip_prefix("r#\"") {
+            if let Some(end) = rest.find("\"#") {
+                args.push(rest[..end].to_string());
+            }
+        } else if let Some(rest) = rest.strip_prefix('"') {
+            args.push(unescape(rest));
+        }
+    }
+
+    args
+}
+
+/// Unescapes the rest of a string literal that starts at `rest`.
+fn unescape(rest: &str) -> String {
+    let mut string = String::new();
+    let mut chars = rest.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => match chars.next() {
+                Some('n') => string.push('\n'),
+                Some('r') => string.push('\r'),
+                Some('t') => string.push('\t'),
+                Some('0') => string.push('\0'),
+                Some('\n') => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+                Some(c) => string.push(c),
+                None => break,
+            },
+            c => string.push(c),
+        }
+    }
+
+    string
+}
@@ -42,3 +42,18 @@ pub enum LexerError {
         span: std::ops::Range<usize>,
     },
 }
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl LexerError {
+    /// Gets the span of the input the error is about.
+    pub fn span(&self) -> &std::ops::Range<usize> {
+        match self {
+            LexerError::UnexpectedCharacter { span, .. }
+            | LexerError::MismatchedBracket { span, .. }
+            | LexerError::UnableToLexModuleBlock { span } => span,
+        }
+    }
+}
@@ -43,6 +43,19 @@ pub struct LexerState {
     pub(crate) module_block_precedent: u8,
 }
 
+/// The parts of a [`LexerState`] other than its bracket stack, which are cheap to save and restore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LexerPosition {
+    /// The current position in the input string.
+    pub(crate) cursor: usize,
+
+    /// Whether the last token was a comma or assignment operator.
+    continuation_precedent: bool,
+
+    /// How many of the tokens that start a module block have been seen.
+    module_block_precedent: u8,
+}
+
 /// A bracket.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Bracket {
@@ -82,8 +95,20 @@ impl<'a> Lexer<'a> {
             return Ok(Some(self.lex_module_block()?));
         }
 
-        // Skip continuations, whitespaces and comments.
-        self.state.cursor += self.skip_superfluous();
+        // Skip continuations, whitespaces and comments. A comment inside brackets or after a
+        // continuation can be followed by more whitespace to skip.
+        loop {
+            let skipped = self.skip_superfluous();
+            if skipped == 0 {
+                break;
+            }
+
+            self.state.cursor += skipped;
+        }
+
+        // A "," or "=" only continues the line it ends, not one after the token that follows it.
+        self.state.continuation_precedent = false;
+
         let remainder = &self.string[self.state.cursor..];
 
         // Check for the end of the input string.
@@ -458,6 +483,24 @@ impl<'a> Lexer<'a> {
     }
 }
 
+impl LexerState {
+    /// Gets the position of the lexer, leaving out its bracket stack.
+    pub(crate) fn position(&self) -> LexerPosition {
+        LexerPosition {
+            cursor: self.cursor,
+            continuation_precedent: self.continuation_precedent,
+            module_block_precedent: self.module_block_precedent,
+        }
+    }
+
+    /// Moves the lexer back to `position`, keeping its bracket stack as it is.
+    pub(crate) fn set_position(&mut self, position: LexerPosition) {
+        self.cursor = position.cursor;
+        self.continuation_precedent = position.continuation_precedent;
+        self.module_block_precedent = position.module_block_precedent;
+    }
+}
+
 impl Bracket {
     fn opposite(&self) -> Self {
         match self {
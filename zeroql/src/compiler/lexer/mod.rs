@@ -5,6 +5,7 @@ mod lexer;
 #[cfg(test)]
 mod tests;
 mod token;
+mod trivia;
 
 //--------------------------------------------------------------------------------------------------
 // Exports
@@ -13,3 +14,4 @@ mod token;
 pub use errors::*;
 pub use lexer::*;
 pub use token::*;
+pub use trivia::*;
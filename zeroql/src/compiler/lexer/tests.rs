@@ -1,4 +1,6 @@
-use crate::lexer::{Lexer, RegexFlags, Token, TokenKind};
+use crate::lexer::{
+    Lexeme, Lexer, LexerResult, RegexFlags, Token, TokenKind, Trivia, TriviaKind, TriviaLexer,
+};
 
 //--------------------------------------------------------------------------------------------------
 // Tests
@@ -34,6 +36,31 @@ fn test_lexer_whitespace() {
 
     assert!(lexer.next().is_none());
 
+    // Comments inside brackets
+    let mut lexer = Lexer::from("(a -- first\n  -- second\n  b)");
+
+    assert_eq!(
+        lexer.next().unwrap().unwrap(),
+        Token::new(0..1, TokenKind::OpOpenParen)
+    );
+
+    assert_eq!(
+        lexer.next().unwrap().unwrap(),
+        Token::new(1..2, TokenKind::PlainIdentifier("a"))
+    );
+
+    assert_eq!(
+        lexer.next().unwrap().unwrap(),
+        Token::new(26..27, TokenKind::PlainIdentifier("b"))
+    );
+
+    assert_eq!(
+        lexer.next().unwrap().unwrap(),
+        Token::new(27..28, TokenKind::OpCloseParen)
+    );
+
+    assert!(lexer.next().is_none());
+
     // Comma continuation
     let mut lexer = Lexer::from("CommaContinuation,\n\t\n \tNext");
 
@@ -78,6 +105,29 @@ fn test_lexer_whitespace() {
 
     assert!(lexer.next().is_none());
 
+    // Only the line an assignment ends continues
+    let mut lexer = Lexer::from("a=1\nb");
+
+    assert_eq!(
+        lexer.next().unwrap().unwrap(),
+        Token::new(0..1, TokenKind::PlainIdentifier("a"))
+    );
+
+    assert_eq!(
+        lexer.next().unwrap().unwrap(),
+        Token::new(1..2, TokenKind::OpIsLexer)
+    );
+
+    assert_eq!(
+        lexer.next().unwrap().unwrap(),
+        Token::new(2..3, TokenKind::DecIntegerLiteral("1"))
+    );
+
+    assert_eq!(
+        lexer.next().unwrap().unwrap(),
+        Token::new(3..4, TokenKind::Terminator)
+    );
+
     // Bracket continuation
     let mut lexer = Lexer::from("BracketContinuation[()\n\t\n \tNext]\r\nNext");
 
@@ -1036,3 +1086,61 @@ fn test_lexer_module_block() {
 
     assert!(lexer.next().is_none());
 }
+
+#[test]
+fn test_lexer_trivia() {
+    let input = "-- Leading comment\nLET $a = 1 -- trailing\n\n  \\\n  $a -- last";
+    let lexemes = TriviaLexer::from(input)
+        .collect::<LexerResult<Vec<_>>>()
+        .unwrap();
+
+    // The lexemes cover the whole input string.
+    let mut end = 0;
+    for lexeme in &lexemes {
+        assert_eq!(lexeme.get_span().start, end);
+        end = lexeme.get_span().end;
+    }
+
+    assert_eq!(end, input.len());
+
+    // Comments are kept without their line breaks.
+    let comments = lexemes
+        .iter()
+        .filter_map(|lexeme| match lexeme {
+            Lexeme::Trivia(Trivia {
+                kind: TriviaKind::Comment(text),
+                ..
+            }) => Some(*text),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        comments,
+        vec!["-- Leading comment", "-- trailing", "-- last"]
+    );
+
+    // The tokens are the same as the plain lexer's.
+    let tokens = lexemes
+        .into_iter()
+        .filter_map(|lexeme| match lexeme {
+            Lexeme::Token(token) => Some(token),
+            Lexeme::Trivia(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        tokens,
+        Lexer::from(input).collect::<LexerResult<Vec<_>>>().unwrap()
+    );
+
+    // Errors stop the lexer.
+    let mut lexer = TriviaLexer::from("a -- comment\n#");
+
+    assert!(matches!(lexer.next(), Some(Ok(Lexeme::Token(_)))));
+    assert!(matches!(lexer.next(), Some(Ok(Lexeme::Trivia(_)))));
+    assert!(matches!(lexer.next(), Some(Ok(Lexeme::Trivia(_)))));
+    assert!(matches!(lexer.next(), Some(Ok(Lexeme::Token(_)))));
+    assert!(matches!(lexer.next(), Some(Err(_))));
+    assert!(lexer.next().is_none());
+}
@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
 
 use crate::Span;
 
@@ -246,7 +247,7 @@ pub enum TokenKind<'a> {
 
 bitflags! {
     /// Flags for a regular expression literal.
-    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Serialize, Deserialize)]
     pub struct RegexFlags: u8 {
         /// Global flag.
         const G_GLOBAL = 0b00000001;
@@ -315,8 +316,8 @@ impl<'a> Display for TokenKind<'a> {
             TokenKind::OpCloseParen => write!(f, ")"),
             TokenKind::OpOpenBrace => write!(f, "{{"),
             TokenKind::OpCloseBrace => write!(f, "}}"),
-            TokenKind::OpOpenSquareBracket => write!(f, "[["),
-            TokenKind::OpCloseSquareBracket => write!(f, "]]"),
+            TokenKind::OpOpenSquareBracket => write!(f, "["),
+            TokenKind::OpCloseSquareBracket => write!(f, "]"),
             TokenKind::OpComma => write!(f, ","),
             TokenKind::OpScope => write!(f, "::"),
             TokenKind::OpColon => write!(f, ":"),
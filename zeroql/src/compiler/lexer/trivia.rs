@@ -0,0 +1,165 @@
+use std::collections::VecDeque;
+
+use crate::{
+    lexer::{Lexer, LexerResult, Token},
+    Span,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// A lexer that produces the whitespace and comments it passes over along with the tokens.
+///
+/// The spans of the lexemes it produces cover the whole input string, so the source can be
+/// rebuilt from them exactly. This is what tools like the formatter use to find the comments
+/// the plain [`Lexer`] skips.
+#[derive(Debug, Clone)]
+pub struct TriviaLexer<'a> {
+    /// The underlying lexer.
+    lexer: Lexer<'a>,
+
+    /// Lexemes that have been lexed but not yet produced.
+    pending: VecDeque<Lexeme<'a>>,
+
+    /// Whether the underlying lexer has run out of tokens or failed.
+    done: bool,
+}
+
+/// A lexeme produced by the [`TriviaLexer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lexeme<'a> {
+    /// A token, as produced by the plain lexer.
+    Token(Token<'a>),
+
+    /// Whitespace or a comment between tokens.
+    Trivia(Trivia<'a>),
+}
+
+/// Whitespace or a comment the plain lexer skips.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trivia<'a> {
+    /// The span of the trivia in the input string.
+    pub span: Span,
+
+    /// The kind of the trivia.
+    pub kind: TriviaKind<'a>,
+}
+
+/// The kind of a trivia.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriviaKind<'a> {
+    /// Whitespace, including `\` line continuations.
+    Whitespace(&'a str),
+
+    /// A comment like "-- foo", without the line break that ends it.
+    Comment(&'a str),
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl<'a> Lexer<'a> {
+    /// Turns the lexer into one that also produces the trivia between tokens.
+    pub fn with_trivia(self) -> TriviaLexer<'a> {
+        TriviaLexer {
+            lexer: self,
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+impl<'a> TriviaLexer<'a> {
+    /// Queues the trivia found in the input string between `start` and `end`.
+    fn queue_trivia(&mut self, start: usize, end: usize) {
+        let string = self.lexer.string;
+        let mut cursor = start;
+
+        while cursor < end {
+            let remainder = &string[cursor..end];
+            let len = if remainder.starts_with("--") {
+                remainder.find(['\r', '\n']).unwrap_or(remainder.len())
+            } else {
+                remainder.find("--").unwrap_or(remainder.len())
+            };
+
+            let text = &remainder[..len];
+            let kind = if text.starts_with("--") {
+                TriviaKind::Comment(text)
+            } else {
+                TriviaKind::Whitespace(text)
+            };
+
+            self.pending
+                .push_back(Lexeme::Trivia(Trivia::new(cursor..cursor + len, kind)));
+
+            cursor += len;
+        }
+    }
+}
+
+impl<'a> Trivia<'a> {
+    /// Creates a new trivia.
+    pub fn new(span: Span, kind: TriviaKind<'a>) -> Self {
+        Self { span, kind }
+    }
+
+    /// Whether the trivia is a comment.
+    pub fn is_comment(&self) -> bool {
+        matches!(self.kind, TriviaKind::Comment(_))
+    }
+}
+
+impl<'a> Lexeme<'a> {
+    /// Gets the span of the lexeme.
+    pub fn get_span(&self) -> Span {
+        match self {
+            Self::Token(token) => token.span.clone(),
+            Self::Trivia(trivia) => trivia.span.clone(),
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl<'a> From<&'a str> for TriviaLexer<'a> {
+    fn from(string: &'a str) -> Self {
+        Lexer::from(string).with_trivia()
+    }
+}
+
+impl<'a> Iterator for TriviaLexer<'a> {
+    type Item = LexerResult<Lexeme<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(lexeme) = self.pending.pop_front() {
+            return Some(Ok(lexeme));
+        }
+
+        if self.done {
+            return None;
+        }
+
+        let start = self.lexer.state.cursor;
+        match self.lexer.next_token() {
+            Ok(Some(token)) => {
+                self.queue_trivia(start, token.span.start);
+                self.pending.push_back(Lexeme::Token(token));
+            }
+            Ok(None) => {
+                self.done = true;
+                self.queue_trivia(start, self.lexer.string.len());
+            }
+            Err(error) => {
+                self.done = true;
+                return Some(Err(error));
+            }
+        }
+
+        self.next()
+    }
+}
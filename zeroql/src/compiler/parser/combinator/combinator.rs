@@ -266,6 +266,57 @@ impl<T> Combinator<T> {
             _ => panic!("Combinator::unwrap_seq8: combinator is not a sequence of eight values"),
         }
     }
+
+    /// Gets the length of the longest vector of repeated values in the combinator.
+    ///
+    /// This does not look inside the values themselves.
+    pub fn longest_many(&self) -> usize {
+        match self {
+            Combinator::Single(_) | Combinator::Void => 0,
+            Combinator::Choice(choice) => match choice {
+                Choice::A(x)
+                | Choice::B(x)
+                | Choice::C(x)
+                | Choice::D(x)
+                | Choice::E(x)
+                | Choice::F(x)
+                | Choice::G(x)
+                | Choice::H(x)
+                | Choice::I(x)
+                | Choice::J(x) => x.longest_many(),
+            },
+            Combinator::Many(xs) => xs
+                .iter()
+                .map(Combinator::longest_many)
+                .fold(xs.len(), usize::max),
+            Combinator::Indexed(_, x) => x.longest_many(),
+            Combinator::Seq2(a, b) => a.longest_many().max(b.longest_many()),
+            Combinator::Seq3(a, b, c) => [a, b, c].iter().map(|x| x.longest_many()).max().unwrap(),
+            Combinator::Seq4(a, b, c, d) => {
+                [a, b, c, d].iter().map(|x| x.longest_many()).max().unwrap()
+            }
+            Combinator::Seq5(a, b, c, d, e) => [a, b, c, d, e]
+                .iter()
+                .map(|x| x.longest_many())
+                .max()
+                .unwrap(),
+            Combinator::Seq6(a, b, c, d, e, f) => [a, b, c, d, e, f]
+                .iter()
+                .map(|x| x.longest_many())
+                .max()
+                .unwrap(),
+            Combinator::Seq7(a, b, c, d, e, f, g) => [a, b, c, d, e, f, g]
+                .iter()
+                .map(|x| x.longest_many())
+                .max()
+                .unwrap(),
+            Combinator::Seq8(a, b, c, d, e, f, g, h) => [a, b, c, d, e, f, g, h]
+                .iter()
+                .map(|x| x.longest_many())
+                .max()
+                .unwrap(),
+        }
+    }
 }
 
 //--------------------------------------------------------------------------------------------------
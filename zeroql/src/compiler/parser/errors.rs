@@ -2,7 +2,7 @@ use std::num::{ParseFloatError, ParseIntError};
 
 use thiserror::Error;
 
-use crate::lexer::LexerError;
+use crate::{lexer::LexerError, Span};
 
 //--------------------------------------------------------------------------------------------------
 // Types
@@ -19,10 +19,88 @@ pub enum ParserError {
     LexerError(#[from] LexerError),
 
     /// An error parsing u128 integer literal.
-    #[error("Invalid integer literal: {0}, value = {1}")]
-    InvalidIntegerLiteral(ParseIntError, String),
+    #[error("Invalid integer literal: {error}, value = {value}")]
+    InvalidIntegerLiteral {
+        /// The underlying parse error.
+        error: ParseIntError,
+
+        /// The literal with its digit separators removed.
+        value: String,
+
+        /// The span of the literal.
+        span: Span,
+    },
 
     /// An error parsing f64 float literal.
-    #[error("Invalid float literal: {0}, value = {1}")]
-    InvalidFloatLiteral(ParseFloatError, String),
+    #[error("Invalid float literal: {error}, value = {value}")]
+    InvalidFloatLiteral {
+        /// The underlying parse error.
+        error: ParseFloatError,
+
+        /// The literal with its digit separators removed.
+        value: String,
+
+        /// The span of the literal.
+        span: Span,
+    },
+
+    /// The input does not match the grammar.
+    ///
+    /// This points at the furthest token the parser reached, since that is usually where the
+    /// mistake is, and lists everything that would have been accepted there.
+    #[error(
+        "Unexpected {found} at {}..{}, expected {}",
+        span.start,
+        span.end,
+        describe_expected(expected)
+    )]
+    UnexpectedToken {
+        /// The span of the unexpected token.
+        span: Span,
+
+        /// A description of the unexpected token.
+        found: String,
+
+        /// Descriptions of the tokens and rules that would have been accepted, in sorted order.
+        expected: Vec<String>,
+    },
+
+    /// The input nests operations, expressions or blocks deeper than the parser allows.
+    #[error("Nesting too deep at {}..{}, the maximum depth is {max_depth}", span.start, span.end)]
+    NestingTooDeep {
+        /// The span of the token that went too deep.
+        span: Span,
+
+        /// The maximum depth the parser allows.
+        max_depth: usize,
+    },
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl ParserError {
+    /// Gets the span of the input the error is about.
+    pub fn span(&self) -> &Span {
+        match self {
+            ParserError::LexerError(error) => error.span(),
+            ParserError::InvalidIntegerLiteral { span, .. }
+            | ParserError::InvalidFloatLiteral { span, .. }
+            | ParserError::UnexpectedToken { span, .. }
+            | ParserError::NestingTooDeep { span, .. } => span,
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+pub(crate) fn describe_expected(expected: &[String]) -> String {
+    match expected {
+        [] => "nothing".to_string(),
+        [one] => one.clone(),
+        [rest @ .., last] => format!("one of {} or {}", rest.join(", "), last),
+    }
 }
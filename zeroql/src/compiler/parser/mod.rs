@@ -20,3 +20,5 @@ mod tests;
 pub use combinator::*;
 pub use errors::*;
 pub use parser::*;
+
+pub(crate) use parse_literals::is_keyword;
@@ -1933,6 +1933,113 @@ impl<'a> Parser<'a> {
         Ok(ast)
     }
 
+    /// Parses `REMOVE FUNCTION` expression.
+    ///
+    /// ```txt
+    /// remove_function_exp =
+    ///     | kw_remove kw_function partial_if_exists identifier partial_on_database?
+    ///     | kw_remove kw_function identifier << partial_if_exists? partial_on_database? >>
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_remove_function_exp(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (alt
+            (seq
+                parse_kw_remove
+                parse_kw_function
+                parse_partial_if_exists
+                parse_identifier
+                (opt parse_partial_on_database)
+            )
+            (seq
+                parse_kw_remove
+                parse_kw_function
+                parse_identifier
+                (perm
+                    (opt parse_partial_if_exists)
+                    (opt parse_partial_on_database)
+                )
+            )
+        ));
+
+        let ast = result.map(|x| match x.unwrap_choice() {
+            Choice::A(x) => {
+                let (kw_remove, _, partial_if_exists, ident, opt_partial_on_database) =
+                    x.unwrap_seq5();
+
+                let kw_remove = kw_remove.unwrap_single();
+                let ident = ident.unwrap_single();
+
+                let span_start = kw_remove.span.start;
+                let mut span_end = ident.span.end;
+
+                let if_exists = match extract_opt_partial_if_exists(*partial_if_exists) {
+                    Some(end) => {
+                        span_end = end;
+                        true
+                    }
+                    None => false,
+                };
+
+                let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
+                    Some(ident) => {
+                        span_end = usize::max(span_end, ident.span.end);
+                        Some(Box::new(ident))
+                    }
+                    None => None,
+                };
+
+                Ast::new(
+                    span_start..span_end,
+                    RemoveFunction {
+                        subject: Box::new(ident),
+                        if_exists,
+                        database,
+                    },
+                )
+            }
+            Choice::B(x) => {
+                let (kw_remove, _, ident, perm) = x.unwrap_seq4();
+
+                let kw_remove = kw_remove.unwrap_single();
+                let ident = ident.unwrap_single();
+
+                let span_start = kw_remove.span.start;
+                let mut span_end = ident.span.end;
+
+                let (opt_partial_if_exists, opt_partial_on_database) = perm.unwrap_seq2();
+
+                let if_exists = match extract_opt_partial_if_exists(*opt_partial_if_exists) {
+                    Some(end) => {
+                        span_end = end;
+                        true
+                    }
+                    None => false,
+                };
+
+                let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
+                    Some(ident) => {
+                        span_end = usize::max(span_end, ident.span.end);
+                        Some(Box::new(ident))
+                    }
+                    None => None,
+                };
+
+                Ast::new(
+                    span_start..span_end,
+                    RemoveFunction {
+                        subject: Box::new(ident),
+                        if_exists,
+                        database,
+                    },
+                )
+            }
+            _ => unreachable!(),
+        });
+
+        Ok(ast)
+    }
+
     /// Parses `REMOVE *` expression.
     ///
     /// ```txt
@@ -1946,6 +2053,7 @@ impl<'a> Parser<'a> {
     ///     | remove_index_exp
     ///     | remove_module_exp
     ///     | remove_param_exp
+    ///     | remove_function_exp
     /// ```
     #[memoize]
     #[backtrack]
@@ -1960,6 +2068,7 @@ impl<'a> Parser<'a> {
             parse_remove_index_exp
             parse_remove_module_exp
             parse_remove_param_exp
+            parse_remove_function_exp
         ));
 
         let ast = result.map(|x| match x.unwrap_choice() {
@@ -1972,7 +2081,7 @@ impl<'a> Parser<'a> {
             Choice::G(x) => x.unwrap_single(),
             Choice::H(x) => x.unwrap_single(),
             Choice::I(x) => x.unwrap_single(),
-            _ => unreachable!(),
+            Choice::J(x) => x.unwrap_single(),
         });
 
         Ok(ast)
@@ -2906,6 +3015,113 @@ impl<'a> Parser<'a> {
         Ok(ast)
     }
 
+    /// Parses `DESCRIBE FUNCTION` expression.
+    ///
+    /// ```txt
+    /// describe_function_exp =
+    ///     | kw_describe kw_function partial_if_exists identifier partial_on_database?
+    ///     | kw_describe kw_function identifier << partial_if_exists? partial_on_database? >>
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_describe_function_exp(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (alt
+            (seq
+                parse_kw_describe
+                parse_kw_function
+                parse_partial_if_exists
+                parse_identifier
+                (opt parse_partial_on_database)
+            )
+            (seq
+                parse_kw_describe
+                parse_kw_function
+                parse_identifier
+                (perm
+                    (opt parse_partial_if_exists)
+                    (opt parse_partial_on_database)
+                )
+            )
+        ));
+
+        let ast = result.map(|x| match x.unwrap_choice() {
+            Choice::A(x) => {
+                let (kw_describe, _, partial_if_exists, ident, opt_partial_on_database) =
+                    x.unwrap_seq5();
+
+                let kw_describe = kw_describe.unwrap_single();
+                let ident = ident.unwrap_single();
+
+                let span_start = kw_describe.span.start;
+                let mut span_end = ident.span.end;
+
+                let if_exists = match extract_opt_partial_if_exists(*partial_if_exists) {
+                    Some(end) => {
+                        span_end = end;
+                        true
+                    }
+                    None => false,
+                };
+
+                let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
+                    Some(ident) => {
+                        span_end = usize::max(span_end, ident.span.end);
+                        Some(Box::new(ident))
+                    }
+                    None => None,
+                };
+
+                Ast::new(
+                    span_start..span_end,
+                    DescribeFunction {
+                        subject: Box::new(ident),
+                        if_exists,
+                        database,
+                    },
+                )
+            }
+            Choice::B(x) => {
+                let (kw_describe, _, ident, perm) = x.unwrap_seq4();
+
+                let kw_describe = kw_describe.unwrap_single();
+                let ident = ident.unwrap_single();
+
+                let span_start = kw_describe.span.start;
+                let mut span_end = ident.span.end;
+
+                let (opt_partial_if_exists, opt_partial_on_database) = perm.unwrap_seq2();
+
+                let if_exists = match extract_opt_partial_if_exists(*opt_partial_if_exists) {
+                    Some(end) => {
+                        span_end = end;
+                        true
+                    }
+                    None => false,
+                };
+
+                let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
+                    Some(ident) => {
+                        span_end = usize::max(span_end, ident.span.end);
+                        Some(Box::new(ident))
+                    }
+                    None => None,
+                };
+
+                Ast::new(
+                    span_start..span_end,
+                    DescribeFunction {
+                        subject: Box::new(ident),
+                        if_exists,
+                        database,
+                    },
+                )
+            }
+            _ => unreachable!(),
+        });
+
+        Ok(ast)
+    }
+
     /// Parses `DESCRIBE *` expression.
     ///
     /// ```txt
@@ -2919,6 +3135,7 @@ impl<'a> Parser<'a> {
     ///     | describe_index_exp
     ///     | describe_module_exp
     ///     | describe_param_exp
+    ///     | describe_function_exp
     /// ```
     #[memoize]
     #[backtrack]
@@ -2933,6 +3150,7 @@ impl<'a> Parser<'a> {
             parse_describe_index_exp
             parse_describe_module_exp
             parse_describe_param_exp
+            parse_describe_function_exp
         ));
 
         let ast = result.map(|x| match x.unwrap_choice() {
@@ -2945,7 +3163,7 @@ impl<'a> Parser<'a> {
             Choice::G(x) => x.unwrap_single(),
             Choice::H(x) => x.unwrap_single(),
             Choice::I(x) => x.unwrap_single(),
-            _ => unreachable!(),
+            Choice::J(x) => x.unwrap_single(),
         });
 
         Ok(ast)
@@ -3048,7 +3266,7 @@ impl<'a> Parser<'a> {
     ///
     /// ```txt
     /// for_exp =
-    ///     | kw_for variable op_in range_op kw_do program kw_end
+    ///     | kw_for variable op_in range_op kw_do block kw_end
     /// ```
     #[memoize]
     #[backtrack]
@@ -3059,7 +3277,7 @@ impl<'a> Parser<'a> {
             parse_op_in
             parse_range_op
             parse_kw_do
-            parse_program
+            parse_block
             parse_kw_end
         ));
 
@@ -3088,7 +3306,7 @@ impl<'a> Parser<'a> {
     ///
     /// ```txt
     /// while_exp =
-    ///     | kw_while range_op kw_do program kw_end
+    ///     | kw_while range_op kw_do block kw_end
     /// ```
     #[memoize]
     #[backtrack]
@@ -3097,7 +3315,7 @@ impl<'a> Parser<'a> {
             parse_kw_while
             parse_range_op
             parse_kw_do
-            parse_program
+            parse_block
             parse_kw_end
         ));
 
@@ -3124,7 +3342,7 @@ impl<'a> Parser<'a> {
     ///
     /// ```txt
     /// partial_else_if_part =
-    ///     | kw_else kw_if range_op kw_then program
+    ///     | kw_else kw_if range_op kw_then block
     /// ```
     #[memoize]
     #[backtrack]
@@ -3134,7 +3352,7 @@ impl<'a> Parser<'a> {
             parse_kw_if
             parse_range_op
             parse_kw_then
-            parse_program
+            parse_block
         ));
 
         let ast = result.map(|x| Ast::new(0..0, Temp(Some(Box::new(x)))));
@@ -3146,7 +3364,7 @@ impl<'a> Parser<'a> {
     ///
     /// ```txt
     /// if_else_exp  =
-    ///     | kw_if range_op kw_then program partial_else_if_part* (kw_else program)? kw_end
+    ///     | kw_if range_op kw_then block partial_else_if_part* (kw_else block)? kw_end
     /// ```
     #[memoize]
     #[backtrack]
@@ -3155,11 +3373,11 @@ impl<'a> Parser<'a> {
             parse_kw_if
             parse_range_op
             parse_kw_then
-            parse_program
+            parse_block
             (many_0 parse_partial_else_if_part)
             (opt (seq
                 parse_kw_else
-                parse_program
+                parse_block
             ))
             parse_kw_end
         ));
@@ -3394,38 +3612,29 @@ impl<'a> Parser<'a> {
     #[memoize]
     #[backtrack]
     pub fn parse_exp(&mut self) -> ParserResult<Option<Ast<'a>>> {
-        let result = parse!(self, Self => (alt
-            parse_relate_exp
-            parse_create_exp
-            parse_delete_exp
-            parse_update_exp
-            parse_select_exp
-            parse_remove_exp
-            parse_describe_exp
-            parse_begin_exp
-            parse_commit_exp
-            (alt
-                parse_cancel_exp
-                parse_for_exp
-                parse_while_exp
-                parse_if_else_exp
-                parse_let_exp
-                parse_set_exp
-                parse_op
-            )
-        ));
+        self.nested(|parser| {
+            let result = parse!(parser, Self => (alt
+                parse_relate_exp
+                parse_create_exp
+                parse_delete_exp
+                parse_update_exp
+                parse_select_exp
+                parse_remove_exp
+                parse_describe_exp
+                parse_begin_exp
+                parse_commit_exp
+                (alt
+                    parse_cancel_exp
+                    parse_for_exp
+                    parse_while_exp
+                    parse_if_else_exp
+                    parse_let_exp
+                    parse_set_exp
+                    parse_op
+                )
+            ));
 
-        let ast = result.map(|x| match x.unwrap_choice() {
-            Choice::A(x) => x.unwrap_single(),
-            Choice::B(x) => x.unwrap_single(),
-            Choice::C(x) => x.unwrap_single(),
-            Choice::D(x) => x.unwrap_single(),
-            Choice::E(x) => x.unwrap_single(),
-            Choice::F(x) => x.unwrap_single(),
-            Choice::G(x) => x.unwrap_single(),
-            Choice::H(x) => x.unwrap_single(),
-            Choice::I(x) => x.unwrap_single(),
-            Choice::J(x) => match x.unwrap_choice() {
+            let ast = result.map(|x| match x.unwrap_choice() {
                 Choice::A(x) => x.unwrap_single(),
                 Choice::B(x) => x.unwrap_single(),
                 Choice::C(x) => x.unwrap_single(),
@@ -3433,11 +3642,22 @@ impl<'a> Parser<'a> {
                 Choice::E(x) => x.unwrap_single(),
                 Choice::F(x) => x.unwrap_single(),
                 Choice::G(x) => x.unwrap_single(),
-                _ => unreachable!(),
-            },
-        });
+                Choice::H(x) => x.unwrap_single(),
+                Choice::I(x) => x.unwrap_single(),
+                Choice::J(x) => match x.unwrap_choice() {
+                    Choice::A(x) => x.unwrap_single(),
+                    Choice::B(x) => x.unwrap_single(),
+                    Choice::C(x) => x.unwrap_single(),
+                    Choice::D(x) => x.unwrap_single(),
+                    Choice::E(x) => x.unwrap_single(),
+                    Choice::F(x) => x.unwrap_single(),
+                    Choice::G(x) => x.unwrap_single(),
+                    _ => unreachable!(),
+                },
+            });
 
-        Ok(ast)
+            Ok(ast)
+        })
     }
 }
 
@@ -18,16 +18,18 @@ impl<'a> Parser<'a> {
     #[memoize]
     #[backtrack]
     pub(super) fn parse_kw(&mut self, string: &'a str) -> ParserResult<Option<Ast<'a>>> {
+        let token = self.eat_token()?;
         if let Some(Token {
             span,
             kind: TokenKind::PlainIdentifier(ident),
-        }) = self.eat_token()?
+        }) = &token
         {
             if ident.to_uppercase() == string.to_uppercase() {
-                return Ok(Some(Ast::new(span, AstKind::Temp(None))));
+                return Ok(Some(Ast::new(span.clone(), AstKind::Temp(None))));
             }
         }
 
+        self.expected(token.as_ref(), format!("`{}`", string.to_uppercase()));
         Ok(None)
     }
 
@@ -446,6 +448,30 @@ impl<'a> Parser<'a> {
         self.parse_kw("param")
     }
 
+    /// Parses the `kw_function` rule.
+    ///
+    /// ```txt
+    /// kw_function =
+    ///     | plain_identifier["function"]
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_kw_function(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        self.parse_kw("function")
+    }
+
+    /// Parses the `kw_return` rule.
+    ///
+    /// ```txt
+    /// kw_return =
+    ///     | plain_identifier["return"]
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_kw_return(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        self.parse_kw("return")
+    }
+
     /// Parses the `kw_mod` rule.
     ///
     /// ```txt
@@ -543,6 +569,42 @@ impl<'a> Parser<'a> {
         self.parse_kw("unique")
     }
 
+    /// Parses the `kw_schemafull` rule.
+    ///
+    /// ```txt
+    /// kw_schemafull =
+    ///     | plain_identifier["schemafull"]
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_kw_schemafull(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        self.parse_kw("schemafull")
+    }
+
+    /// Parses the `kw_schemaless` rule.
+    ///
+    /// ```txt
+    /// kw_schemaless =
+    ///     | plain_identifier["schemaless"]
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_kw_schemaless(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        self.parse_kw("schemaless")
+    }
+
+    /// Parses the `kw_flexible` rule.
+    ///
+    /// ```txt
+    /// kw_flexible =
+    ///     | plain_identifier["flexible"]
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_kw_flexible(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        self.parse_kw("flexible")
+    }
+
     /// Parses the `kw_use` rule.
     ///
     /// ```txt
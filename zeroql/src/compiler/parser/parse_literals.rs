@@ -7,7 +7,7 @@ use crate::{
         Token,
         TokenKind::{self, *},
     },
-    parse,
+    parse, Span,
 };
 
 use super::{Choice, Parser, ParserError, ParserResult};
@@ -31,28 +31,30 @@ impl<'a> Parser<'a> {
     #[memoize]
     #[backtrack]
     pub fn parse_integer_lit(&mut self) -> ParserResult<Option<Ast<'a>>> {
-        if let Some(Token { span, kind }) = self.eat_token()? {
+        let token = self.eat_token()?;
+        if let Some(Token { span, kind }) = token.clone() {
             match kind {
                 TokenKind::DecIntegerLiteral(lit) => {
-                    let int = convert_string_to_int(lit, 10)?;
+                    let int = convert_string_to_int(lit, 10, &span)?;
                     return Ok(Some(Ast::new(span, AstKind::IntegerLiteral(int))));
                 }
                 TokenKind::HexIntegerLiteral(lit) => {
-                    let int = convert_string_to_int(lit, 16)?;
+                    let int = convert_string_to_int(lit, 16, &span)?;
                     return Ok(Some(Ast::new(span, AstKind::IntegerLiteral(int))));
                 }
                 TokenKind::BinIntegerLiteral(lit) => {
-                    let int = convert_string_to_int(lit, 2)?;
+                    let int = convert_string_to_int(lit, 2, &span)?;
                     return Ok(Some(Ast::new(span, AstKind::IntegerLiteral(int))));
                 }
                 TokenKind::OctIntegerLiteral(lit) => {
-                    let int = convert_string_to_int(lit, 8)?;
+                    let int = convert_string_to_int(lit, 8, &span)?;
                     return Ok(Some(Ast::new(span, AstKind::IntegerLiteral(int))));
                 }
                 _ => {}
             }
         }
 
+        self.expected(token.as_ref(), "integer literal");
         Ok(None)
     }
 
@@ -66,7 +68,8 @@ impl<'a> Parser<'a> {
     #[memoize]
     #[backtrack]
     pub fn parse_identifier(&mut self) -> ParserResult<Option<Ast<'a>>> {
-        if let Some(Token { span, kind }) = self.eat_token()? {
+        let token = self.eat_token()?;
+        if let Some(Token { span, kind }) = token.clone() {
             match kind {
                 TokenKind::PlainIdentifier(ident) => {
                     if !is_keyword(ident) {
@@ -80,6 +83,33 @@ impl<'a> Parser<'a> {
             }
         }
 
+        self.expected(token.as_ref(), "identifier");
+        Ok(None)
+    }
+
+    /// Parses a segment of a scoped identifier.
+    ///
+    /// Unlike `identifier`, keywords are allowed here because the surrounding `::` already makes
+    /// them unambiguous, e.g. `index::fulltext`.
+    ///
+    /// ```txt
+    /// scope_segment =
+    ///     | plain_identifier
+    ///     | escaped_identifier
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_scope_segment(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let token = self.eat_token()?;
+        if let Some(Token {
+            span,
+            kind: TokenKind::PlainIdentifier(ident) | TokenKind::EscapedIdentifier(ident),
+        }) = token.clone()
+        {
+            return Ok(Some(Ast::new(span, AstKind::Identifier(ident))));
+        }
+
+        self.expected(token.as_ref(), "identifier");
         Ok(None)
     }
 
@@ -92,14 +122,16 @@ impl<'a> Parser<'a> {
     #[memoize]
     #[backtrack]
     pub fn parse_variable(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let token = self.eat_token()?;
         if let Some(Token {
             span,
             kind: TokenKind::Variable(ident),
-        }) = self.eat_token()?
+        }) = token.clone()
         {
             return Ok(Some(Ast::new(span, AstKind::Variable(ident))));
         }
 
+        self.expected(token.as_ref(), "variable");
         Ok(None)
     }
 
@@ -152,14 +184,16 @@ impl<'a> Parser<'a> {
     #[memoize]
     #[backtrack]
     pub fn parse_module_block(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let token = self.eat_token()?;
         if let Some(Token {
             span,
             kind: TokenKind::ModuleBlock(code),
-        }) = self.eat_token()?
+        }) = token.clone()
         {
             return Ok(Some(Ast::new(span, AstKind::ModuleBlock(code))));
         }
 
+        self.expected(token.as_ref(), "module block");
         Ok(None)
     }
 
@@ -179,26 +213,27 @@ impl<'a> Parser<'a> {
     #[backtrack]
     pub fn parse_raw_lit(&mut self) -> ParserResult<Option<Ast<'a>>> {
         let state = self.get_state();
-        if let Some(Token { span, kind }) = self.eat_token()? {
+        let token = self.eat_token()?;
+        if let Some(Token { span, kind }) = token.clone() {
             match kind {
                 TokenKind::DecIntegerLiteral(lit) => {
-                    let int = convert_string_to_int(lit, 10)?;
+                    let int = convert_string_to_int(lit, 10, &span)?;
                     return Ok(Some(Ast::new(span, AstKind::IntegerLiteral(int))));
                 }
                 TokenKind::FloatLiteral(lit) => {
-                    let float = convert_string_to_float(lit)?;
+                    let float = convert_string_to_float(lit, &span)?;
                     return Ok(Some(Ast::new(span, AstKind::FloatLiteral(float))));
                 }
                 TokenKind::HexIntegerLiteral(lit) => {
-                    let int = convert_string_to_int(lit, 16)?;
+                    let int = convert_string_to_int(lit, 16, &span)?;
                     return Ok(Some(Ast::new(span, AstKind::IntegerLiteral(int))));
                 }
                 TokenKind::BinIntegerLiteral(lit) => {
-                    let int = convert_string_to_int(lit, 2)?;
+                    let int = convert_string_to_int(lit, 2, &span)?;
                     return Ok(Some(Ast::new(span, AstKind::IntegerLiteral(int))));
                 }
                 TokenKind::OctIntegerLiteral(lit) => {
-                    let int = convert_string_to_int(lit, 8)?;
+                    let int = convert_string_to_int(lit, 8, &span)?;
                     return Ok(Some(Ast::new(span, AstKind::IntegerLiteral(int))));
                 }
                 TokenKind::StringLiteral(lit) => {
@@ -227,6 +262,7 @@ impl<'a> Parser<'a> {
             }
         }
 
+        self.expected(token.as_ref(), "literal");
         Ok(None)
     }
 
@@ -425,18 +461,27 @@ impl<'a> Parser<'a> {
 // Functions
 //--------------------------------------------------------------------------------------------------
 
-fn convert_string_to_int(str: &str, radix: u32) -> Result<u128, ParserError> {
+fn convert_string_to_int(str: &str, radix: u32, span: &Span) -> Result<u128, ParserError> {
     let cleaned = str.replace('_', "");
-    let int = u128::from_str_radix(&cleaned, radix)
-        .map_err(|e| ParserError::InvalidIntegerLiteral(e, cleaned))?;
+    let int = u128::from_str_radix(&cleaned, radix).map_err(|error| {
+        ParserError::InvalidIntegerLiteral {
+            error,
+            value: cleaned,
+            span: span.clone(),
+        }
+    })?;
     Ok(int)
 }
 
-fn convert_string_to_float(str: &str) -> Result<f64, ParserError> {
+fn convert_string_to_float(str: &str, span: &Span) -> Result<f64, ParserError> {
     let cleaned = str.replace('_', "");
     let float = cleaned
         .parse::<f64>()
-        .map_err(|e| ParserError::InvalidFloatLiteral(e, cleaned))?;
+        .map_err(|error| ParserError::InvalidFloatLiteral {
+            error,
+            value: cleaned,
+            span: span.clone(),
+        })?;
     Ok(float)
 }
 
@@ -480,6 +525,8 @@ pub(crate) fn is_keyword(str: &str) -> bool {
             | "EDGE"
             | "MODULE"
             | "PARAM"
+            | "FUNCTION"
+            | "RETURN"
             | "MOD"
             | "VALUE"
             | "VALUES"
@@ -488,6 +535,9 @@ pub(crate) fn is_keyword(str: &str) -> bool {
             | "ASSERT"
             | "READONLY"
             | "UNIQUE"
+            | "SCHEMAFULL"
+            | "SCHEMALESS"
+            | "FLEXIBLE"
             | "USE"
             | "IF"
             | "ELSE"
@@ -23,14 +23,16 @@ impl<'a> Parser<'a> {
     #[memoize]
     #[backtrack]
     pub fn parse_terminator(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let token = self.eat_token()?;
         if let Some(Token {
             span,
             kind: TokenKind::Terminator,
-        }) = self.eat_token()?
+        }) = &token
         {
-            return Ok(Some(Ast::new(span, AstKind::Temp(None))));
+            return Ok(Some(Ast::new(span.clone(), AstKind::Temp(None))));
         }
 
+        self.expected(token.as_ref(), "terminator");
         Ok(None)
     }
 }
@@ -2,6 +2,7 @@ use zeroql_macros::{backtrack, memoize};
 
 use crate::{
     ast::{Ast, AstKind::*, RelateArrow},
+    compiler::reversible::Reversible,
     lexer::TokenKind::*,
     parse,
     parser::{Parser, ParserResult},
@@ -73,20 +74,32 @@ impl<'a> Parser<'a> {
     ///
     /// ```txt
     /// identifier_scope_op =
-    ///     | identifier (op_scope identifier)+
+    ///     | scope_segment (op_scope scope_segment)+
     ///     | identifier
     /// ```
     #[memoize]
     #[backtrack]
     pub fn parse_identifier_scope_op(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let state = self.get_state();
+        let failure = self.failure.clone();
+
         let result = parse!(self, Self => (alt
             (seq
-                parse_identifier
-                (many_1 (seq (arg parse_tok OpScope) parse_identifier))
+                parse_scope_segment
+                (many_1 (seq (arg parse_tok OpScope) parse_scope_segment))
             )
             parse_identifier
         ));
 
+        if result.is_none() {
+            // Any keyword is a scope segment, so a keyword in the wrong place would otherwise be
+            // reported as a missing `::` after it rather than as the keyword itself.
+            self.failure = failure;
+            self.set_state(state);
+            let token = self.eat_token()?;
+            self.expected(token.as_ref(), "identifier");
+        }
+
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
                 let (ident, scopes) = x.unwrap_seq2();
@@ -387,6 +400,7 @@ impl<'a> Parser<'a> {
             )
             parse_sign_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -475,6 +489,7 @@ impl<'a> Parser<'a> {
             )
             parse_access_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -525,6 +540,7 @@ impl<'a> Parser<'a> {
             )
             parse_pow_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -580,6 +596,7 @@ impl<'a> Parser<'a> {
             )
             parse_mul_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -634,6 +651,7 @@ impl<'a> Parser<'a> {
             )
             parse_add_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -689,6 +707,7 @@ impl<'a> Parser<'a> {
             )
             parse_shift_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -767,6 +786,7 @@ impl<'a> Parser<'a> {
             )
             parse_match_sim_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -787,11 +807,11 @@ impl<'a> Parser<'a> {
                         Choice::E(x) => match x.unwrap_choice() {
                             Choice::A(_) => InOp(Box::new(l), Box::new(r)),
                             Choice::B(_) => NotInOp(Box::new(l), Box::new(r)),
-                            Choice::C(_) => ContainsOp(Box::new(l), Box::new(r)),
-                            Choice::D(_) => NotContainsOp(Box::new(l), Box::new(r)),
-                            Choice::E(_) => ContainsNoneOp(Box::new(l), Box::new(r)),
-                            Choice::F(_) => ContainsAllOp(Box::new(l), Box::new(r)),
-                            Choice::G(_) => ContainsAnyOp(Box::new(l), Box::new(r)),
+                            Choice::C(_) => NotContainsOp(Box::new(l), Box::new(r)),
+                            Choice::D(_) => ContainsNoneOp(Box::new(l), Box::new(r)),
+                            Choice::E(_) => ContainsAllOp(Box::new(l), Box::new(r)),
+                            Choice::F(_) => ContainsAnyOp(Box::new(l), Box::new(r)),
+                            Choice::G(_) => ContainsOp(Box::new(l), Box::new(r)),
                             _ => unreachable!(),
                         },
                         _ => unreachable!(),
@@ -834,6 +854,7 @@ impl<'a> Parser<'a> {
             )
             parse_rel_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -885,6 +906,7 @@ impl<'a> Parser<'a> {
             )
             parse_eq_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -930,6 +952,7 @@ impl<'a> Parser<'a> {
             )
             parse_bit_and_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -975,6 +998,7 @@ impl<'a> Parser<'a> {
             )
             parse_bit_xor_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -1021,6 +1045,7 @@ impl<'a> Parser<'a> {
             )
             parse_bit_or_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -1070,6 +1095,7 @@ impl<'a> Parser<'a> {
             )
             parse_and_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -1304,6 +1330,7 @@ impl<'a> Parser<'a> {
     /// ```txt
     /// relate_edge_id =
     ///     | identifier "[" op "]" partial_as?
+    ///     | identifier "[" or_null_coalesce_op? op_range "]" partial_as?
     ///     | identifier partial_as?
     /// ```
     #[memoize]
@@ -1317,6 +1344,14 @@ impl<'a> Parser<'a> {
                 (arg parse_tok OpCloseSquareBracket)
                 (opt parse_partial_as)
             )
+            (seq
+                parse_identifier
+                (arg parse_tok OpOpenSquareBracket)
+                (opt parse_or_null_coalesce_op)
+                (arg parse_tok OpRange)
+                (arg parse_tok OpCloseSquareBracket)
+                (opt parse_partial_as)
+            )
             (seq
                 parse_identifier
                 (opt parse_partial_as)
@@ -1344,6 +1379,38 @@ impl<'a> Parser<'a> {
                 )
             }
             Choice::B(x) => {
+                let (ident, open, opt_start, range, close, opt_partial_as) = x.unwrap_seq6();
+                let ident = ident.unwrap_single();
+                let open = open.unwrap_single();
+                let range = range.unwrap_single();
+                let close = close.unwrap_single();
+                let start = match *opt_start {
+                    Combinator::Single(x) => Some(Box::new(x)),
+                    _ => None,
+                };
+                let (alias, span_end) = match self::extract_opt_partial_as(*opt_partial_as) {
+                    Some((alias, span_end)) => (Some(alias), span_end),
+                    None => (None, close.span.end),
+                };
+
+                let depth_start = match &start {
+                    Some(start) => start.span.start,
+                    None => open.span.end,
+                };
+
+                Ast::new(
+                    ident.span.start..span_end,
+                    RelateEdgeId {
+                        subject: Box::new(ident),
+                        depth: Some(Box::new(Ast::new(
+                            depth_start..range.span.end,
+                            UnboundedRangeOp(start),
+                        ))),
+                        alias,
+                    },
+                )
+            }
+            Choice::C(x) => {
                 let (ident, opt_partial_as) = x.unwrap_seq2();
                 let ident = ident.unwrap_single();
 
@@ -1415,6 +1482,7 @@ impl<'a> Parser<'a> {
             )
             parse_relate_edge_not_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -1460,6 +1528,7 @@ impl<'a> Parser<'a> {
             )
             parse_relate_edge_and_op
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| match x.unwrap_choice() {
             Choice::A(x) => {
@@ -1513,6 +1582,7 @@ impl<'a> Parser<'a> {
                 parse_relate_id
             ))
         ));
+        self.check_chain(&result)?;
 
         let ast = result.map(|x| {
             let (l, rest) = x.unwrap_seq2();
@@ -1568,31 +1638,33 @@ impl<'a> Parser<'a> {
     #[memoize]
     #[backtrack]
     pub fn parse_op(&mut self) -> ParserResult<Option<Ast<'a>>> {
-        let result = parse!(self, Self => (alt
-            parse_relate_op
-            (seq parse_range_op (opt parse_partial_as))
-        ));
+        self.nested(|parser| {
+            let result = parse!(parser, Self => (alt
+                parse_relate_op
+                (seq parse_range_op (opt parse_partial_as))
+            ));
 
-        let ast = result.map(|x| match x.unwrap_choice() {
-            Choice::A(x) => x.unwrap_single(),
-            Choice::B(x) => {
-                let (subject, opt_partial_as) = x.unwrap_seq2();
-                let subject = subject.unwrap_single();
-                match self::extract_opt_partial_as(*opt_partial_as) {
-                    Some((alias, span_end)) => Ast::new(
-                        subject.get_span().start..span_end,
-                        AliasOp {
-                            subject: Box::new(subject),
-                            alias,
-                        },
-                    ),
-                    None => subject,
+            let ast = result.map(|x| match x.unwrap_choice() {
+                Choice::A(x) => x.unwrap_single(),
+                Choice::B(x) => {
+                    let (subject, opt_partial_as) = x.unwrap_seq2();
+                    let subject = subject.unwrap_single();
+                    match self::extract_opt_partial_as(*opt_partial_as) {
+                        Some((alias, span_end)) => Ast::new(
+                            subject.get_span().start..span_end,
+                            AliasOp {
+                                subject: Box::new(subject),
+                                alias,
+                            },
+                        ),
+                        None => subject,
+                    }
                 }
-            }
-            _ => unreachable!(),
-        });
+                _ => unreachable!(),
+            });
 
-        Ok(ast)
+            Ok(ast)
+        })
     }
 }
 
@@ -1609,6 +1681,12 @@ pub(crate) fn extract_opt_partial_as(comb: Combinator<Ast<'_>>) -> Option<(Box<A
             let span_end = identifier.span.end;
             Some((Box::new(identifier), span_end))
         }
+        Combinator::Indexed(_, x) => {
+            let (_, identifier) = x.unwrap_single().unwrap_temp().unwrap_seq2();
+            let identifier = identifier.unwrap_single();
+            let span_end = identifier.span.end;
+            Some((Box::new(identifier), span_end))
+        }
         _ => unreachable!(),
     }
 }
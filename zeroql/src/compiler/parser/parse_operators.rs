@@ -22,12 +22,14 @@ impl<'a> Parser<'a> {
     #[memoize]
     #[backtrack]
     pub fn parse_tok(&mut self, token_kind: TokenKind<'static>) -> ParserResult<Option<Ast<'a>>> {
-        if let Some(Token { span, kind }) = self.eat_token()? {
-            if kind == token_kind {
-                return Ok(Some(Ast::new(span, AstKind::Temp(None))));
+        let token = self.eat_token()?;
+        if let Some(Token { span, kind }) = &token {
+            if *kind == token_kind {
+                return Ok(Some(Ast::new(span.clone(), AstKind::Temp(None))));
             }
         }
 
+        self.expected(token.as_ref(), format!("`{token_kind}`"));
         Ok(None)
     }
 
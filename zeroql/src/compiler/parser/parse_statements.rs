@@ -1,14 +1,17 @@
 use zeroql_macros::{backtrack, memoize};
 
 use crate::{
-    ast::{Ast, AstKind::*, Field, TypeSig},
+    ast::{Ast, AstKind::*, Field, RedefineField, TableMode, TypeSig},
     lexer::TokenKind::*,
     parse,
     parser::parse_expressions::{extract_opt_partial_on_database, extract_partial_type_sig},
 };
 
 use super::{
-    parse_expressions::{extract_opt_partial_on_namespace, extract_partial_on_table},
+    parse_expressions::{
+        extract_opt_partial_if_exists, extract_opt_partial_on_namespace, extract_partial_on_table,
+    },
+    parse_operations::extract_opt_partial_as,
     Choice, Combinator, Parser, ParserResult,
 };
 
@@ -289,12 +292,35 @@ impl<'a> Parser<'a> {
         Ok(ast)
     }
 
+    /// Parses a partial `table_mode` syntax.
+    ///
+    /// ```txt
+    /// partial_table_mode =
+    ///     | kw_schemafull
+    ///     | kw_schemaless
+    ///     | kw_flexible partial_field_type?
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_partial_table_mode(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (alt
+            parse_kw_schemafull
+            parse_kw_schemaless
+            (seq
+                parse_kw_flexible
+                (opt parse_partial_field_type)
+            )
+        ));
+        let ast = result.map(|x| Ast::new(0..0, Temp(Some(Box::new(x)))));
+        Ok(ast)
+    }
+
     /// Parses a `DEFINE TABLE` statement.
     ///
     /// ```txt
     /// define_table_stmt =
-    ///     | kw_define kw_table partial_if_not_exists identifier << partial_on_database? partial_table_fields? >>
-    ///     | kw_define kw_table identifier << partial_if_not_exists? partial_on_database? partial_table_fields? >>
+    ///     | kw_define kw_table partial_if_not_exists identifier << partial_on_database? partial_table_mode? partial_table_fields? >>
+    ///     | kw_define kw_table identifier << partial_if_not_exists? partial_on_database? partial_table_mode? partial_table_fields? >>
     /// ```
     #[memoize]
     #[backtrack]
@@ -307,6 +333,7 @@ impl<'a> Parser<'a> {
                 parse_identifier
                 (perm
                     (opt parse_partial_on_database)
+                    (opt parse_partial_table_mode)
                     (opt parse_partial_table_fields)
                 )
             )
@@ -317,6 +344,7 @@ impl<'a> Parser<'a> {
                 (perm
                     (opt parse_partial_if_not_exists)
                     (opt parse_partial_on_database)
+                    (opt parse_partial_table_mode)
                     (opt parse_partial_table_fields)
                 )
             )
@@ -335,7 +363,8 @@ impl<'a> Parser<'a> {
                 let if_not_exists =
                     extract_opt_partial_if_not_exists(*partial_if_not_exists).is_some();
 
-                let (opt_partial_on_database, opt_partial_table_fields) = perm.unwrap_seq2();
+                let (opt_partial_on_database, opt_partial_table_mode, opt_partial_table_fields) =
+                    perm.unwrap_seq3();
 
                 let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
                     Some(ident) => {
@@ -345,6 +374,14 @@ impl<'a> Parser<'a> {
                     None => None,
                 };
 
+                let mode = match extract_opt_partial_table_mode(*opt_partial_table_mode) {
+                    Some((mode, end)) => {
+                        span_end = usize::max(span_end, end);
+                        mode
+                    }
+                    None => TableMode::default(),
+                };
+
                 let fields = match extract_opt_partial_table_fields(*opt_partial_table_fields) {
                     Some((fields, end)) => {
                         span_end = usize::max(span_end, end);
@@ -359,6 +396,7 @@ impl<'a> Parser<'a> {
                         name: Box::new(ident),
                         if_not_exists,
                         database,
+                        mode,
                         fields,
                     },
                 )
@@ -372,8 +410,12 @@ impl<'a> Parser<'a> {
                 let span_start = kw_define.span.start;
                 let mut span_end = ident.span.end;
 
-                let (opt_partial_if_not_exists, opt_partial_on_database, opt_partial_table_fields) =
-                    perm.unwrap_seq3();
+                let (
+                    opt_partial_if_not_exists,
+                    opt_partial_on_database,
+                    opt_partial_table_mode,
+                    opt_partial_table_fields,
+                ) = perm.unwrap_seq4();
 
                 let if_not_exists =
                     match extract_opt_partial_if_not_exists(*opt_partial_if_not_exists) {
@@ -392,6 +434,14 @@ impl<'a> Parser<'a> {
                     None => None,
                 };
 
+                let mode = match extract_opt_partial_table_mode(*opt_partial_table_mode) {
+                    Some((mode, end)) => {
+                        span_end = usize::max(span_end, end);
+                        mode
+                    }
+                    None => TableMode::default(),
+                };
+
                 let fields = match extract_opt_partial_table_fields(*opt_partial_table_fields) {
                     Some((fields, end)) => {
                         span_end = usize::max(span_end, end);
@@ -406,6 +456,7 @@ impl<'a> Parser<'a> {
                         name: Box::new(ident),
                         if_not_exists,
                         database,
+                        mode,
                         fields,
                     },
                 )
@@ -1345,6 +1396,171 @@ impl<'a> Parser<'a> {
         Ok(ast)
     }
 
+    /// Parses a partial `function_param` syntax.
+    ///
+    /// ```txt
+    /// partial_function_param =
+    ///     | variable partial_field_type
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_partial_function_param(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_variable
+            parse_partial_field_type
+        ));
+        let ast = result.map(|x| Ast::new(0..0, Temp(Some(Box::new(x)))));
+        Ok(ast)
+    }
+
+    /// Parses a partial `function_params` syntax.
+    ///
+    /// ```txt
+    /// partial_function_params =
+    ///     | "(" (partial_function_param ("," partial_function_param)* ","?)? ")"
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_partial_function_params(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            (arg parse_tok OpOpenParen)
+            (opt (seq
+                parse_partial_function_param
+                (many_0 (seq
+                    (arg parse_tok OpComma)
+                    parse_partial_function_param
+                ))
+                (opt (arg parse_tok OpComma))
+            ))
+            (arg parse_tok OpCloseParen)
+        ));
+        let ast = result.map(|x| Ast::new(0..0, Temp(Some(Box::new(x)))));
+        Ok(ast)
+    }
+
+    /// Parses a partial `function_body` syntax.
+    ///
+    /// ```txt
+    /// partial_function_body =
+    ///     | kw_do block kw_end
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_partial_function_body(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_kw_do
+            parse_block
+            parse_kw_end
+        ));
+        let ast = result.map(|x| Ast::new(0..0, Temp(Some(Box::new(x)))));
+        Ok(ast)
+    }
+
+    /// Parses a `DEFINE FUNCTION` statement.
+    ///
+    /// ```txt
+    /// define_function_stmt =
+    ///     | kw_define kw_function partial_if_not_exists identifier partial_function_params << partial_on_database? partial_field_type? >> partial_function_body
+    ///     | kw_define kw_function identifier partial_function_params << partial_if_not_exists? partial_on_database? partial_field_type? >> partial_function_body
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_define_function_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (alt
+            (seq
+                parse_kw_define
+                parse_kw_function
+                parse_partial_if_not_exists
+                parse_identifier
+                parse_partial_function_params
+                (perm
+                    (opt parse_partial_on_database)
+                    (opt parse_partial_field_type)
+                )
+                parse_partial_function_body
+            )
+            (seq
+                parse_kw_define
+                parse_kw_function
+                parse_identifier
+                parse_partial_function_params
+                (perm
+                    (opt parse_partial_if_not_exists)
+                    (opt parse_partial_on_database)
+                    (opt parse_partial_field_type)
+                )
+                parse_partial_function_body
+            )
+        ));
+
+        let ast = result.map(|x| match x.unwrap_choice() {
+            Choice::A(x) => {
+                let (kw_define, _, partial_if_not_exists, ident, params, perm, body) =
+                    x.unwrap_seq7();
+
+                let kw_define = kw_define.unwrap_single();
+                let ident = ident.unwrap_single();
+
+                let if_not_exists =
+                    extract_opt_partial_if_not_exists(*partial_if_not_exists).is_some();
+
+                let (opt_partial_on_database, opt_partial_field_type) = perm.unwrap_seq2();
+
+                let database =
+                    extract_opt_partial_on_database(*opt_partial_on_database).map(Box::new);
+                let r#type =
+                    extract_opt_partial_field_type(*opt_partial_field_type).map(|(t, _)| t);
+                let params = extract_partial_function_params(*params);
+                let (body, span_end) = extract_partial_function_body(*body);
+
+                Ast::new(
+                    kw_define.span.start..span_end,
+                    DefineFunction {
+                        name: Box::new(ident),
+                        if_not_exists,
+                        database,
+                        params,
+                        r#type,
+                        body: Box::new(body),
+                    },
+                )
+            }
+            Choice::B(x) => {
+                let (kw_define, _, ident, params, perm, body) = x.unwrap_seq6();
+
+                let kw_define = kw_define.unwrap_single();
+                let ident = ident.unwrap_single();
+
+                let (opt_partial_if_not_exists, opt_partial_on_database, opt_partial_field_type) =
+                    perm.unwrap_seq3();
+
+                let if_not_exists =
+                    extract_opt_partial_if_not_exists(*opt_partial_if_not_exists).is_some();
+                let database =
+                    extract_opt_partial_on_database(*opt_partial_on_database).map(Box::new);
+                let r#type =
+                    extract_opt_partial_field_type(*opt_partial_field_type).map(|(t, _)| t);
+                let params = extract_partial_function_params(*params);
+                let (body, span_end) = extract_partial_function_body(*body);
+
+                Ast::new(
+                    kw_define.span.start..span_end,
+                    DefineFunction {
+                        name: Box::new(ident),
+                        if_not_exists,
+                        database,
+                        params,
+                        r#type,
+                        body: Box::new(body),
+                    },
+                )
+            }
+            _ => unreachable!(),
+        });
+
+        Ok(ast)
+    }
+
     /// Parses a `DEFINE` statement.
     ///
     /// ```txt
@@ -1358,6 +1574,7 @@ impl<'a> Parser<'a> {
     ///     | define_index_stmt
     ///     | define_module_stmt
     ///     | define_param_stmt
+    ///     | define_function_stmt
     /// ```
     #[memoize]
     #[backtrack]
@@ -1372,6 +1589,7 @@ impl<'a> Parser<'a> {
             parse_define_index_stmt
             parse_define_module_stmt
             parse_define_param_stmt
+            parse_define_function_stmt
         ));
 
         let ast = result.map(|x| match x.unwrap_choice() {
@@ -1384,37 +1602,120 @@ impl<'a> Parser<'a> {
             Choice::G(x) => x.unwrap_single(),
             Choice::H(x) => x.unwrap_single(),
             Choice::I(x) => x.unwrap_single(),
-            _ => unreachable!(),
+            Choice::J(x) => x.unwrap_single(),
         });
 
         Ok(ast)
     }
 
-    /// Parses a `USE` statement.
+    /// Parses a partial `redefine_field` syntax.
     ///
     /// ```txt
-    /// use_stmt =
-    ///     | kw_use (kw_database | kw_db) identifier
+    /// partial_redefine_field =
+    ///     | identifier << partial_as? partial_field_type? >>
     /// ```
     #[memoize]
     #[backtrack]
-    pub fn parse_use_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+    pub fn parse_partial_redefine_field(&mut self) -> ParserResult<Option<Ast<'a>>> {
         let result = parse!(self, Self => (seq
-            parse_kw_use
-            (alt parse_kw_database parse_kw_db)
             parse_identifier
+            (perm
+                (opt parse_partial_as)
+                (opt parse_partial_field_type)
+            )
+        ));
+        let ast = result.map(|x| Ast::new(0..0, Temp(Some(Box::new(x)))));
+        Ok(ast)
+    }
+
+    /// Parses a partial `redefine_fields` syntax.
+    ///
+    /// ```txt
+    /// partial_redefine_fields =
+    ///     | kw_fields partial_redefine_field ("," partial_redefine_field)*
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_partial_redefine_fields(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_kw_fields
+            parse_partial_redefine_field
+            (many_0 (seq
+                (arg parse_tok OpComma)
+                parse_partial_redefine_field
+            ))
+        ));
+        let ast = result.map(|x| Ast::new(0..0, Temp(Some(Box::new(x)))));
+        Ok(ast)
+    }
+
+    /// Parses a `REDEFINE TABLE` statement.
+    ///
+    /// ```txt
+    /// redefine_table_stmt =
+    ///     | kw_redefine kw_table partial_if_exists? identifier << partial_as? partial_on_database? partial_redefine_fields? >>
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_redefine_table_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_kw_redefine
+            parse_kw_table
+            (opt parse_partial_if_exists)
+            parse_identifier
+            (perm
+                (opt parse_partial_as)
+                (opt parse_partial_on_database)
+                (opt parse_partial_redefine_fields)
+            )
         ));
 
         let ast = result.map(|x| {
-            let (kw_use, _, ident) = x.unwrap_seq3();
+            let (kw_redefine, _, opt_partial_if_exists, ident, perm) = x.unwrap_seq5();
 
-            let kw_use = kw_use.unwrap_single();
+            let kw_redefine = kw_redefine.unwrap_single();
             let ident = ident.unwrap_single();
 
+            let span_start = kw_redefine.span.start;
+            let mut span_end = ident.span.end;
+
+            let if_exists = extract_opt_partial_if_exists(*opt_partial_if_exists).is_some();
+
+            let (opt_partial_as, opt_partial_on_database, opt_partial_redefine_fields) =
+                perm.unwrap_seq3();
+
+            let new_name = match extract_opt_partial_as(*opt_partial_as) {
+                Some((new_name, end)) => {
+                    span_end = usize::max(span_end, end);
+                    Some(new_name)
+                }
+                None => None,
+            };
+
+            let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
+                Some(ident) => {
+                    span_end = usize::max(span_end, ident.span.end);
+                    Some(Box::new(ident))
+                }
+                None => None,
+            };
+
+            let fields = match extract_opt_partial_redefine_fields(*opt_partial_redefine_fields) {
+                Some((fields, end)) => {
+                    span_end = usize::max(span_end, end);
+                    fields
+                }
+                None => vec![],
+            };
+
             Ast::new(
-                kw_use.span.start..ident.span.end,
-                Use {
-                    database: Box::new(ident),
+                span_start..span_end,
+                RedefineTable {
+                    name: Box::new(ident),
+                    if_exists,
+                    new_name,
+                    database,
+                    fields,
                 },
             )
         });
@@ -1422,81 +1723,629 @@ impl<'a> Parser<'a> {
         Ok(ast)
     }
 
-    /// Parses a statement.
+    /// Parses a `REDEFINE EDGE` statement.
     ///
     /// ```txt
-    /// stmt =
-    ///     | define_stmt
-    ///     | use_stmt
-    ///     | kw_break
-    ///     | kw_continue
-    ///     | kw_return
+    /// redefine_edge_stmt =
+    ///     | kw_redefine kw_edge partial_if_exists? identifier << partial_as? partial_on_database? partial_redefine_fields? >>
     /// ```
     #[memoize]
     #[backtrack]
-    pub fn parse_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
-        let result = parse!(self, Self => (alt
-            parse_define_stmt
-            parse_use_stmt
-            parse_kw_break
-            parse_kw_continue
+    pub fn parse_redefine_edge_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_kw_redefine
+            parse_kw_edge
+            (opt parse_partial_if_exists)
+            parse_identifier
+            (perm
+                (opt parse_partial_as)
+                (opt parse_partial_on_database)
+                (opt parse_partial_redefine_fields)
+            )
         ));
 
-        let ast = result.map(|x| match x.unwrap_choice() {
-            Choice::A(x) => x.unwrap_single(),
-            Choice::B(x) => x.unwrap_single(),
-            Choice::C(x) => {
-                let break_stmt = x.unwrap_single();
-                Ast::new(break_stmt.span, Break)
-            }
-            Choice::D(x) => {
-                let continue_stmt = x.unwrap_single();
-                Ast::new(continue_stmt.span, Continue)
-            }
-            _ => unreachable!(),
-        });
+        let ast = result.map(|x| {
+            let (kw_redefine, _, opt_partial_if_exists, ident, perm) = x.unwrap_seq5();
 
-        Ok(ast)
-    }
-}
+            let kw_redefine = kw_redefine.unwrap_single();
+            let ident = ident.unwrap_single();
 
-//--------------------------------------------------------------------------------------------------
-// Functions
-//--------------------------------------------------------------------------------------------------
+            let span_start = kw_redefine.span.start;
+            let mut span_end = ident.span.end;
 
-pub(crate) fn extract_opt_partial_if_not_exists(comb: Combinator<Ast<'_>>) -> Option<usize> {
-    match comb {
-        Combinator::Void => None,
-        Combinator::Indexed(_, partial_if_not_exists) => {
-            let (_, _, kw_exists) = partial_if_not_exists
-                .unwrap_single()
-                .unwrap_temp()
-                .unwrap_seq3();
+            let if_exists = extract_opt_partial_if_exists(*opt_partial_if_exists).is_some();
 
-            match kw_exists.unwrap_choice() {
-                Choice::A(kw_exists) => Some(kw_exists.unwrap_single().span.end),
-                Choice::B(kw_exist) => Some(kw_exist.unwrap_single().span.end),
-                _ => unreachable!(),
-            }
-        }
-        Combinator::Single(partial_if_not_exists) => {
-            let (_, _, kw_exists) = partial_if_not_exists.unwrap_temp().unwrap_seq3();
+            let (opt_partial_as, opt_partial_on_database, opt_partial_redefine_fields) =
+                perm.unwrap_seq3();
 
-            match kw_exists.unwrap_choice() {
-                Choice::A(kw_exists) => Some(kw_exists.unwrap_single().span.end),
-                Choice::B(kw_exists) => Some(kw_exists.unwrap_single().span.end),
-                _ => unreachable!(),
-            }
-        }
-        _ => unreachable!(),
-    }
-}
+            let new_name = match extract_opt_partial_as(*opt_partial_as) {
+                Some((new_name, end)) => {
+                    span_end = usize::max(span_end, end);
+                    Some(new_name)
+                }
+                None => None,
+            };
 
-pub(crate) fn extract_opt_partial_table_fields(
-    comb: Combinator<Ast<'_>>,
-) -> Option<(Vec<Field<'_>>, usize)> {
-    match comb {
-        Combinator::Void => None,
+            let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
+                Some(ident) => {
+                    span_end = usize::max(span_end, ident.span.end);
+                    Some(Box::new(ident))
+                }
+                None => None,
+            };
+
+            let fields = match extract_opt_partial_redefine_fields(*opt_partial_redefine_fields) {
+                Some((fields, end)) => {
+                    span_end = usize::max(span_end, end);
+                    fields
+                }
+                None => vec![],
+            };
+
+            Ast::new(
+                span_start..span_end,
+                RedefineEdge {
+                    name: Box::new(ident),
+                    if_exists,
+                    new_name,
+                    database,
+                    fields,
+                },
+            )
+        });
+
+        Ok(ast)
+    }
+
+    /// Parses a `REDEFINE TYPE` statement.
+    ///
+    /// ```txt
+    /// redefine_type_stmt =
+    ///     | kw_redefine kw_type partial_if_exists? identifier << partial_as? partial_on_database? partial_redefine_fields? >>
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_redefine_type_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_kw_redefine
+            parse_kw_type
+            (opt parse_partial_if_exists)
+            parse_identifier
+            (perm
+                (opt parse_partial_as)
+                (opt parse_partial_on_database)
+                (opt parse_partial_redefine_fields)
+            )
+        ));
+
+        let ast = result.map(|x| {
+            let (kw_redefine, _, opt_partial_if_exists, ident, perm) = x.unwrap_seq5();
+
+            let kw_redefine = kw_redefine.unwrap_single();
+            let ident = ident.unwrap_single();
+
+            let span_start = kw_redefine.span.start;
+            let mut span_end = ident.span.end;
+
+            let if_exists = extract_opt_partial_if_exists(*opt_partial_if_exists).is_some();
+
+            let (opt_partial_as, opt_partial_on_database, opt_partial_redefine_fields) =
+                perm.unwrap_seq3();
+
+            let new_name = match extract_opt_partial_as(*opt_partial_as) {
+                Some((new_name, end)) => {
+                    span_end = usize::max(span_end, end);
+                    Some(new_name)
+                }
+                None => None,
+            };
+
+            let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
+                Some(ident) => {
+                    span_end = usize::max(span_end, ident.span.end);
+                    Some(Box::new(ident))
+                }
+                None => None,
+            };
+
+            let fields = match extract_opt_partial_redefine_fields(*opt_partial_redefine_fields) {
+                Some((fields, end)) => {
+                    span_end = usize::max(span_end, end);
+                    fields
+                }
+                None => vec![],
+            };
+
+            Ast::new(
+                span_start..span_end,
+                RedefineType {
+                    name: Box::new(ident),
+                    if_exists,
+                    new_name,
+                    database,
+                    fields,
+                },
+            )
+        });
+
+        Ok(ast)
+    }
+
+    /// Parses a partial `redefine_variants` syntax.
+    ///
+    /// ```txt
+    /// partial_redefine_variants =
+    ///     | kw_variants identifier partial_as? ("," identifier partial_as?)*
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_partial_redefine_variants(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_kw_variants
+            parse_identifier
+            (opt parse_partial_as)
+            (many_0 (seq
+                (arg parse_tok OpComma)
+                parse_identifier
+                (opt parse_partial_as)
+            ))
+        ));
+        let ast = result.map(|x| Ast::new(0..0, Temp(Some(Box::new(x)))));
+        Ok(ast)
+    }
+
+    /// Parses a `REDEFINE ENUM` statement.
+    ///
+    /// ```txt
+    /// redefine_enum_stmt =
+    ///     | kw_redefine kw_enum partial_if_exists? identifier << partial_as? partial_on_database? partial_redefine_variants? >>
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_redefine_enum_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_kw_redefine
+            parse_kw_enum
+            (opt parse_partial_if_exists)
+            parse_identifier
+            (perm
+                (opt parse_partial_as)
+                (opt parse_partial_on_database)
+                (opt parse_partial_redefine_variants)
+            )
+        ));
+
+        let ast = result.map(|x| {
+            let (kw_redefine, _, opt_partial_if_exists, ident, perm) = x.unwrap_seq5();
+
+            let kw_redefine = kw_redefine.unwrap_single();
+            let ident = ident.unwrap_single();
+
+            let span_start = kw_redefine.span.start;
+            let mut span_end = ident.span.end;
+
+            let if_exists = extract_opt_partial_if_exists(*opt_partial_if_exists).is_some();
+
+            let (opt_partial_as, opt_partial_on_database, opt_partial_redefine_variants) =
+                perm.unwrap_seq3();
+
+            let new_name = match extract_opt_partial_as(*opt_partial_as) {
+                Some((new_name, end)) => {
+                    span_end = usize::max(span_end, end);
+                    Some(new_name)
+                }
+                None => None,
+            };
+
+            let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
+                Some(ident) => {
+                    span_end = usize::max(span_end, ident.span.end);
+                    Some(Box::new(ident))
+                }
+                None => None,
+            };
+
+            let variants =
+                match extract_opt_partial_redefine_variants(*opt_partial_redefine_variants) {
+                    Some((variants, end)) => {
+                        span_end = usize::max(span_end, end);
+                        Some(variants)
+                    }
+                    None => None,
+                };
+
+            Ast::new(
+                span_start..span_end,
+                RedefineEnum {
+                    name: Box::new(ident),
+                    if_exists,
+                    new_name,
+                    database,
+                    variants,
+                },
+            )
+        });
+
+        Ok(ast)
+    }
+
+    /// Parses a `REDEFINE INDEX` statement.
+    ///
+    /// ```txt
+    /// redefine_index_stmt =
+    ///     | kw_redefine kw_index partial_if_exists? identifier << partial_as? partial_on_database? partial_on_table partial_index_fields? partial_index_with? >>
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_redefine_index_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_kw_redefine
+            parse_kw_index
+            (opt parse_partial_if_exists)
+            parse_identifier
+            (perm
+                (opt parse_partial_as)
+                (opt parse_partial_on_database)
+                parse_partial_on_table
+                (opt parse_partial_index_fields)
+                (opt parse_partial_index_with)
+            )
+        ));
+
+        let ast = result.map(|x| {
+            let (kw_redefine, _, opt_partial_if_exists, ident, perm) = x.unwrap_seq5();
+
+            let kw_redefine = kw_redefine.unwrap_single();
+            let ident = ident.unwrap_single();
+
+            let span_start = kw_redefine.span.start;
+            let mut span_end = ident.span.end;
+
+            let if_exists = extract_opt_partial_if_exists(*opt_partial_if_exists).is_some();
+
+            let (
+                opt_partial_as,
+                opt_partial_on_database,
+                partial_on_table,
+                opt_partial_index_fields,
+                opt_partial_index_with,
+            ) = perm.unwrap_seq5();
+
+            let new_name = match extract_opt_partial_as(*opt_partial_as) {
+                Some((new_name, end)) => {
+                    span_end = usize::max(span_end, end);
+                    Some(new_name)
+                }
+                None => None,
+            };
+
+            let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
+                Some(ident) => {
+                    span_end = usize::max(span_end, ident.span.end);
+                    Some(Box::new(ident))
+                }
+                None => None,
+            };
+
+            let table = extract_partial_on_table(*partial_on_table);
+            span_end = usize::max(span_end, table.span.end);
+
+            let columns = match extract_partial_index_fields(*opt_partial_index_fields) {
+                Some((columns, end)) => {
+                    span_end = usize::max(span_end, end);
+                    Some(columns)
+                }
+                None => None,
+            };
+
+            let function = match extract_opt_partial_index_with(*opt_partial_index_with) {
+                Some(function_call_op) => {
+                    span_end = usize::max(span_end, function_call_op.span.end);
+                    Some(Box::new(function_call_op))
+                }
+                None => None,
+            };
+
+            Ast::new(
+                span_start..span_end,
+                RedefineIndex {
+                    name: Box::new(ident),
+                    if_exists,
+                    new_name,
+                    database,
+                    table: Box::new(table),
+                    columns,
+                    function,
+                },
+            )
+        });
+
+        Ok(ast)
+    }
+
+    /// Parses a `REDEFINE PARAM` statement.
+    ///
+    /// ```txt
+    /// redefine_param_stmt =
+    ///     | kw_redefine kw_param partial_if_exists? identifier << partial_as? partial_on_database? partial_field_type? partial_field_value? >>
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_redefine_param_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_kw_redefine
+            parse_kw_param
+            (opt parse_partial_if_exists)
+            parse_identifier
+            (perm
+                (opt parse_partial_as)
+                (opt parse_partial_on_database)
+                (opt parse_partial_field_type)
+                (opt parse_partial_field_value)
+            )
+        ));
+
+        let ast = result.map(|x| {
+            let (kw_redefine, _, opt_partial_if_exists, ident, perm) = x.unwrap_seq5();
+
+            let kw_redefine = kw_redefine.unwrap_single();
+            let ident = ident.unwrap_single();
+
+            let span_start = kw_redefine.span.start;
+            let mut span_end = ident.span.end;
+
+            let if_exists = extract_opt_partial_if_exists(*opt_partial_if_exists).is_some();
+
+            let (
+                opt_partial_as,
+                opt_partial_on_database,
+                opt_partial_field_type,
+                opt_partial_field_value,
+            ) = perm.unwrap_seq4();
+
+            let new_name = match extract_opt_partial_as(*opt_partial_as) {
+                Some((new_name, end)) => {
+                    span_end = usize::max(span_end, end);
+                    Some(new_name)
+                }
+                None => None,
+            };
+
+            let database = match extract_opt_partial_on_database(*opt_partial_on_database) {
+                Some(ident) => {
+                    span_end = usize::max(span_end, ident.span.end);
+                    Some(Box::new(ident))
+                }
+                None => None,
+            };
+
+            let r#type = match extract_opt_partial_field_type(*opt_partial_field_type) {
+                Some((r#type, end)) => {
+                    span_end = usize::max(span_end, end);
+                    Some(r#type)
+                }
+                None => None,
+            };
+
+            let value = match extract_opt_partial_field_value(*opt_partial_field_value) {
+                Some((value, end)) => {
+                    span_end = usize::max(span_end, end);
+                    Some(Box::new(value))
+                }
+                None => None,
+            };
+
+            Ast::new(
+                span_start..span_end,
+                RedefineParam {
+                    name: Box::new(ident),
+                    if_exists,
+                    new_name,
+                    database,
+                    r#type,
+                    value,
+                },
+            )
+        });
+
+        Ok(ast)
+    }
+
+    /// Parses a `REDEFINE` statement.
+    ///
+    /// ```txt
+    /// redefine_stmt =
+    ///     | redefine_table_stmt
+    ///     | redefine_edge_stmt
+    ///     | redefine_type_stmt
+    ///     | redefine_enum_stmt
+    ///     | redefine_index_stmt
+    ///     | redefine_param_stmt
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_redefine_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (alt
+            parse_redefine_table_stmt
+            parse_redefine_edge_stmt
+            parse_redefine_type_stmt
+            parse_redefine_enum_stmt
+            parse_redefine_index_stmt
+            parse_redefine_param_stmt
+        ));
+
+        let ast = result.map(|x| match x.unwrap_choice() {
+            Choice::A(x) => x.unwrap_single(),
+            Choice::B(x) => x.unwrap_single(),
+            Choice::C(x) => x.unwrap_single(),
+            Choice::D(x) => x.unwrap_single(),
+            Choice::E(x) => x.unwrap_single(),
+            Choice::F(x) => x.unwrap_single(),
+            _ => unreachable!(),
+        });
+
+        Ok(ast)
+    }
+
+    /// Parses a `USE` statement.
+    ///
+    /// ```txt
+    /// use_stmt =
+    ///     | kw_use (kw_database | kw_db) identifier
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_use_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (seq
+            parse_kw_use
+            (alt parse_kw_database parse_kw_db)
+            parse_identifier
+        ));
+
+        let ast = result.map(|x| {
+            let (kw_use, _, ident) = x.unwrap_seq3();
+
+            let kw_use = kw_use.unwrap_single();
+            let ident = ident.unwrap_single();
+
+            Ast::new(
+                kw_use.span.start..ident.span.end,
+                Use {
+                    database: Box::new(ident),
+                },
+            )
+        });
+
+        Ok(ast)
+    }
+
+    /// Parses a statement.
+    ///
+    /// ```txt
+    /// stmt =
+    ///     | define_stmt
+    ///     | redefine_stmt
+    ///     | use_stmt
+    ///     | kw_break
+    ///     | kw_continue
+    ///     | kw_return exp?
+    /// ```
+    #[memoize]
+    #[backtrack]
+    pub fn parse_stmt(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        let result = parse!(self, Self => (alt
+            parse_define_stmt
+            parse_redefine_stmt
+            parse_use_stmt
+            parse_kw_break
+            parse_kw_continue
+            (seq
+                parse_kw_return
+                (opt parse_exp)
+            )
+        ));
+
+        let ast = result.map(|x| match x.unwrap_choice() {
+            Choice::A(x) => x.unwrap_single(),
+            Choice::B(x) => x.unwrap_single(),
+            Choice::C(x) => x.unwrap_single(),
+            Choice::D(x) => {
+                let break_stmt = x.unwrap_single();
+                Ast::new(break_stmt.span, Break)
+            }
+            Choice::E(x) => {
+                let continue_stmt = x.unwrap_single();
+                Ast::new(continue_stmt.span, Continue)
+            }
+            Choice::F(x) => {
+                let (kw_return, opt_exp) = x.unwrap_seq2();
+                let kw_return = kw_return.unwrap_single();
+                match *opt_exp {
+                    Combinator::Single(exp) => Ast::new(
+                        kw_return.span.start..exp.span.end,
+                        Return(Some(Box::new(exp))),
+                    ),
+                    _ => Ast::new(kw_return.span, Return(None)),
+                }
+            }
+            _ => unreachable!(),
+        });
+
+        Ok(ast)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+pub(crate) fn extract_opt_partial_if_not_exists(comb: Combinator<Ast<'_>>) -> Option<usize> {
+    match comb {
+        Combinator::Void => None,
+        Combinator::Indexed(_, partial_if_not_exists) => {
+            let (_, _, kw_exists) = partial_if_not_exists
+                .unwrap_single()
+                .unwrap_temp()
+                .unwrap_seq3();
+
+            match kw_exists.unwrap_choice() {
+                Choice::A(kw_exists) => Some(kw_exists.unwrap_single().span.end),
+                Choice::B(kw_exist) => Some(kw_exist.unwrap_single().span.end),
+                _ => unreachable!(),
+            }
+        }
+        Combinator::Single(partial_if_not_exists) => {
+            let (_, _, kw_exists) = partial_if_not_exists.unwrap_temp().unwrap_seq3();
+
+            match kw_exists.unwrap_choice() {
+                Choice::A(kw_exists) => Some(kw_exists.unwrap_single().span.end),
+                Choice::B(kw_exists) => Some(kw_exists.unwrap_single().span.end),
+                _ => unreachable!(),
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+pub(crate) fn extract_opt_partial_table_mode(
+    comb: Combinator<Ast<'_>>,
+) -> Option<(TableMode<'_>, usize)> {
+    let partial_table_mode = match comb {
+        Combinator::Void => return None,
+        Combinator::Indexed(_, partial_table_mode) => partial_table_mode.unwrap_single(),
+        Combinator::Single(partial_table_mode) => partial_table_mode,
+        _ => unreachable!(),
+    };
+
+    let mode = match partial_table_mode.unwrap_temp().unwrap_choice() {
+        Choice::A(kw_schemafull) => (
+            TableMode::Schemafull,
+            kw_schemafull.unwrap_single().span.end,
+        ),
+        Choice::B(kw_schemaless) => (
+            TableMode::Schemaless,
+            kw_schemaless.unwrap_single().span.end,
+        ),
+        Choice::C(x) => {
+            let (kw_flexible, opt_partial_field_type) = x.unwrap_seq2();
+            let span_end = kw_flexible.unwrap_single().span.end;
+
+            match extract_opt_partial_field_type(*opt_partial_field_type) {
+                Some((r#type, end)) => (TableMode::Flexible(Some(r#type)), end),
+                None => (TableMode::Flexible(None), span_end),
+            }
+        }
+        _ => unreachable!(),
+    };
+
+    Some(mode)
+}
+
+pub(crate) fn extract_opt_partial_table_fields(
+    comb: Combinator<Ast<'_>>,
+) -> Option<(Vec<Field<'_>>, usize)> {
+    match comb {
+        Combinator::Void => None,
         Combinator::Indexed(_, partial_table_fields) => {
             let (_, partial_table_field, partial_table_fields) = partial_table_fields
                 .unwrap_single()
@@ -1740,6 +2589,136 @@ pub(crate) fn extract_opt_partial_index_with(comb: Combinator<Ast<'_>>) -> Optio
     }
 }
 
+pub(crate) fn extract_partial_function_params(
+    comb: Combinator<Ast<'_>>,
+) -> Vec<(Ast<'_>, TypeSig<'_>)> {
+    let (_, params, _) = comb.unwrap_single().unwrap_temp().unwrap_seq3();
+
+    let mut function_params = vec![];
+    if let Combinator::Seq3(param, rest, _) = *params {
+        function_params.push(extract_partial_function_param(*param));
+        for param in rest.unwrap_many() {
+            let (_, param) = param.unwrap_seq2();
+            function_params.push(extract_partial_function_param(*param));
+        }
+    }
+
+    function_params
+}
+
+pub(crate) fn extract_partial_function_param(comb: Combinator<Ast<'_>>) -> (Ast<'_>, TypeSig<'_>) {
+    let (variable, partial_field_type) = comb.unwrap_single().unwrap_temp().unwrap_seq2();
+    let (r#type, _) = extract_opt_partial_field_type(*partial_field_type).unwrap();
+
+    (variable.unwrap_single(), r#type)
+}
+
+pub(crate) fn extract_partial_function_body(comb: Combinator<Ast<'_>>) -> (Ast<'_>, usize) {
+    let (_, block, end) = comb.unwrap_single().unwrap_temp().unwrap_seq3();
+
+    (block.unwrap_single(), end.unwrap_single().span.end)
+}
+
+pub(crate) fn extract_opt_partial_redefine_fields(
+    comb: Combinator<Ast<'_>>,
+) -> Option<(Vec<RedefineField<'_>>, usize)> {
+    match comb {
+        Combinator::Void => None,
+        Combinator::Indexed(_, partial_redefine_fields) => {
+            let (_, partial_redefine_field, partial_redefine_fields) = partial_redefine_fields
+                .unwrap_single()
+                .unwrap_temp()
+                .unwrap_seq3();
+
+            let (field, mut span_end) = extract_partial_redefine_field(*partial_redefine_field);
+            let mut fields = vec![field];
+            for partial_redefine_field in partial_redefine_fields.unwrap_many() {
+                let (_, partial_redefine_field) = partial_redefine_field.unwrap_seq2();
+                let (field, end) = extract_partial_redefine_field(*partial_redefine_field);
+                fields.push(field);
+                span_end = end;
+            }
+
+            Some((fields, span_end))
+        }
+        _ => unreachable!(),
+    }
+}
+
+pub(crate) fn extract_partial_redefine_field(
+    comb: Combinator<Ast<'_>>,
+) -> (RedefineField<'_>, usize) {
+    let (ident, perm) = comb.unwrap_single().unwrap_temp().unwrap_seq2();
+    let (opt_partial_as, opt_partial_field_type) = perm.unwrap_seq2();
+
+    let ident = ident.unwrap_single();
+    let mut span_end = ident.span.end;
+
+    let new_name = match extract_opt_partial_as(*opt_partial_as) {
+        Some((new_name, end)) => {
+            span_end = usize::max(span_end, end);
+            Some(new_name)
+        }
+        None => None,
+    };
+
+    let r#type = match extract_opt_partial_field_type(*opt_partial_field_type) {
+        Some((r#type, end)) => {
+            span_end = usize::max(span_end, end);
+            Some(r#type)
+        }
+        None => None,
+    };
+
+    let field = RedefineField {
+        name: Box::new(ident),
+        new_name,
+        r#type,
+    };
+
+    (field, span_end)
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn extract_opt_partial_redefine_variants(
+    comb: Combinator<Ast<'_>>,
+) -> Option<(Vec<(Ast<'_>, Option<Ast<'_>>)>, usize)> {
+    match comb {
+        Combinator::Void => None,
+        Combinator::Indexed(_, partial_redefine_variants) => {
+            let (_, ident, opt_partial_as, rest) = partial_redefine_variants
+                .unwrap_single()
+                .unwrap_temp()
+                .unwrap_seq4();
+
+            let ident = ident.unwrap_single();
+            let mut span_end = ident.span.end;
+
+            let new_name = extract_opt_partial_as(*opt_partial_as).map(|(new_name, end)| {
+                span_end = end;
+                *new_name
+            });
+
+            let mut variants = vec![(ident, new_name)];
+            for x in rest.unwrap_many() {
+                let (_, ident, opt_partial_as) = x.unwrap_seq3();
+                let ident = ident.unwrap_single();
+                span_end = ident.span.end;
+
+                let new_name = extract_opt_partial_as(*opt_partial_as).map(|(new_name, end)| {
+                    span_end = end;
+                    *new_name
+                });
+
+                variants.push((ident, new_name));
+            }
+
+            Some((variants, span_end))
+        }
+        _ => unreachable!(),
+    }
+}
+
 pub(crate) fn extract_partial_module_block(comb: Combinator<Ast<'_>>) -> (Ast<'_>, usize) {
     let (_, module_block, end) = comb.unwrap_single().unwrap_temp().unwrap_seq3();
 
@@ -1,17 +1,21 @@
-use std::num::NonZeroUsize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroUsize,
+};
 
 use lru::LruCache;
 use zeroql_macros::{anykey::AnyKey, backtrack, memoize};
 
 use crate::{
     ast::{Ast, AstKind},
-    compiler::reversible::Reversible,
-    lexer::{Lexer, LexerState, Token},
+    compiler::{reversible::Reversible, stack},
+    lexer::{Lexer, LexerState, Token, TokenKind},
     parse,
-    parser::ParserResult,
+    parser::{ParserError, ParserResult},
+    Span,
 };
 
-use super::Choice;
+use super::{Choice, Combinator};
 
 //--------------------------------------------------------------------------------------------------
 // Types
@@ -25,13 +29,17 @@ use super::Choice;
 ///
 /// The grammar rules are defined in the [`./parser.grammar`](./parser.grammar) file.
 ///
-/// ## Important
+/// ## Nesting
+///
+/// Due to its recursive descent nature, this parser is not tail-recursive and its stack usage grows
+/// with how deeply the input nests. To keep untrusted input from overflowing the stack, operations,
+/// expressions and blocks may only nest [`DEFAULT_MAX_DEPTH`] levels deep, which can be changed with
+/// [`Parser::with_max_depth`]. Going deeper fails with [`ParserError::NestingTooDeep`]. The parser
+/// also uses the [stacker][stacker] crate to continue on the heap when the stack runs low, so the
+/// limit holds no matter how small the stack of the calling thread is.
 ///
-/// Due to its recursive descent nature, this parser is not tail-recursive and may cause stack overflows
-/// with large inputs. This limitation is known and there are no immediate plans to address it. To mitigate
-/// this risk, it is recommended to run the parser in a separate thread to isolate potential faults and/or
-/// use the [stacker][stacker] crate to dynamically increase the stack size when it is needed.
-/// when it is needed.
+/// Nested input is only parsed in linear time when the cache can hold the results of every rule
+/// the nesting goes through, so the cache should be sized for the nesting that is expected.
 ///
 /// [packrat]: https://en.wikipedia.org/wiki/Packrat_parser
 /// [stacker]: https://docs.rs/stacker/latest/stacker/
@@ -41,11 +49,60 @@ pub struct Parser<'a> {
 
     /// The lexer that produces tokens from the input stream.
     pub(crate) lexer: Lexer<'a>,
+
+    /// The furthest point in the input where a token did not match, kept across backtracking so
+    /// that a failed parse can say where it went wrong.
+    pub(crate) failure: Option<Failure>,
+
+    /// Whether blocks recover from statements that fail to parse instead of failing themselves.
+    pub(crate) recovering: bool,
+
+    /// The errors recovered from so far, by the start of the statement they replaced.
+    pub(crate) errors: BTreeMap<usize, ParserError>,
+
+    /// How deeply the rule being parsed is nested.
+    pub(crate) depth: usize,
+
+    /// How deeply rules may nest before parsing fails.
+    pub(crate) max_depth: usize,
+}
+
+/// A program parsed by [`Parser::parse_program`], along with every error found in it.
+#[derive(Debug, Clone)]
+pub struct ParsedProgram<'a> {
+    /// The program. Statements that could not be parsed are replaced by [`AstKind::Error`] nodes.
+    pub ast: Ast<'a>,
+
+    /// The errors found, in the order they appear in the input.
+    pub errors: Vec<ParserError>,
+}
+
+/// The furthest point in the input where the parser failed to match a token.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Failure {
+    /// The span of the token that did not match.
+    pub(crate) span: Span,
+
+    /// A description of the token that did not match, or `None` at the end of the input.
+    pub(crate) found: Option<String>,
+
+    /// Descriptions of everything that was tried at that point.
+    pub(crate) expected: BTreeSet<String>,
 }
 
 /// The value stored in the cache.
 type CacheValue<'a> = (ParserResult<Option<Ast<'a>>>, LexerState);
 
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// How deeply operations, expressions and blocks may nest by default.
+///
+/// A program starts out three levels deep, in a block, an expression and an operation. Every pair
+/// of brackets, block and operator in a chain adds about one level from there.
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
 //--------------------------------------------------------------------------------------------------
 // Methods
 //--------------------------------------------------------------------------------------------------
@@ -55,7 +112,21 @@ impl<'a> Parser<'a> {
     pub fn new(input: &'a str, cache_size: usize) -> Self {
         let lexer = Lexer::from(input);
         let cache = LruCache::new(NonZeroUsize::new(cache_size).unwrap());
-        Self { cache, lexer }
+        Self {
+            cache,
+            lexer,
+            failure: None,
+            recovering: false,
+            errors: BTreeMap::new(),
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Sets how deeply operations, expressions and blocks may nest.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
     }
 
     /// Eats a token from the lexer.
@@ -63,53 +134,443 @@ impl<'a> Parser<'a> {
         Ok(self.lexer.next_token()?)
     }
 
-    /// Parses a program.
+    /// Records that a token did not match what was `expected`.
+    ///
+    /// `token` is the token that was eaten in its place, or `None` at the end of the input. Only
+    /// the furthest such point is kept, along with everything that was expected there.
+    pub(crate) fn expected(&mut self, token: Option<&Token<'a>>, expected: impl Into<String>) {
+        let (span, found) = match token {
+            Some(token) => (token.span.clone(), Some(describe_token(&token.kind))),
+            None => (self.lexer.string.len()..self.lexer.string.len(), None),
+        };
+
+        match &mut self.failure {
+            Some(failure) if failure.span.start > span.start => {}
+            Some(failure) if failure.span.start == span.start => {
+                failure.expected.insert(expected.into());
+            }
+            _ => {
+                self.failure = Some(Failure {
+                    span,
+                    found,
+                    expected: BTreeSet::from([expected.into()]),
+                })
+            }
+        }
+    }
+
+    /// Parses a complete program.
+    ///
+    /// A statement that fails to parse does not fail the whole program. The parser reports it,
+    /// skips ahead to the next terminator, or to the `END` or `ELSE` that closes the enclosing
+    /// block, and carries on, leaving an [`AstKind::Error`] node in place of the statement.
+    ///
+    /// Each error is a [`ParserError::UnexpectedToken`] pointing at the furthest token the parser
+    /// got to in the statement, or the lexer error that stopped it.
     ///
     /// ```txt
     /// program =
+    ///     | block end_of_input
+    /// ```
+    pub fn parse_program(&mut self) -> ParsedProgram<'a> {
+        let start = self.get_state();
+        let error = match self.parse_complete_program() {
+            Ok(ast) => {
+                return ParsedProgram {
+                    ast,
+                    errors: vec![],
+                }
+            }
+            // Skipping past input that nests too deep costs as much as the nesting it is meant to
+            // stop, so there is no recovering from it.
+            Err(error @ ParserError::NestingTooDeep { .. }) => {
+                return ParsedProgram {
+                    ast: Ast::new(0..0, AstKind::Program(vec![])),
+                    errors: vec![error],
+                }
+            }
+            Err(error) => error,
+        };
+
+        // Parse again, this time recovering from the statements that fail. The cache is cleared as
+        // blocks parse differently while recovering.
+        self.set_state(start);
+        self.cache.clear();
+        self.failure = None;
+        self.errors.clear();
+        self.recovering = true;
+
+        let ast = self
+            .parse_recovering_block(false)
+            .map(|ast| ast.unwrap_or_else(|| Ast::new(0..0, AstKind::Program(vec![]))));
+
+        self.recovering = false;
+        self.cache.clear();
+
+        let mut errors = std::mem::take(&mut self.errors)
+            .into_values()
+            .collect::<Vec<_>>();
+        let ast = match ast {
+            Ok(ast) => ast,
+            Err(error) => {
+                errors.push(error);
+                Ast::new(0..0, AstKind::Program(vec![]))
+            }
+        };
+
+        // An empty program has nothing to recover from, so the error of the first parse stands.
+        if errors.is_empty() {
+            errors.push(error);
+        }
+
+        ParsedProgram { ast, errors }
+    }
+
+    /// Parses a complete program, failing at the first error.
+    fn parse_complete_program(&mut self) -> ParserResult<Ast<'a>> {
+        let ast = self.parse_block()?;
+
+        let state = self.get_state();
+        let token = self.eat_token()?;
+        self.set_state(state);
+
+        match (ast, token) {
+            (Some(ast), None) => Ok(ast),
+            (ast, token) => {
+                if ast.is_some() {
+                    self.expected(token.as_ref(), "end of input");
+                }
+
+                Err(self.unexpected_token())
+            }
+        }
+    }
+
+    /// Parses a block of statements and expressions, as found in a program or in the body of a
+    /// `FOR`, `WHILE` or `IF` expression.
+    ///
+    /// ```txt
+    /// block =
     ///     | terminator* (stmt | exp) (terminator+ (stmt | exp))* terminator*
     /// ```
     #[memoize(cache = self.cache, state = self.lexer.state)]
     #[backtrack(state = self.lexer.state, condition = |r| matches!(r, Ok(None)))]
-    pub fn parse_program(&mut self) -> ParserResult<Option<Ast<'a>>> {
-        let result = parse!(self, Self => (seq
-            (many_0 parse_terminator)
-            (alt parse_stmt parse_exp)
-            (many_0 (seq
-                (many_1 parse_terminator)
-                (alt parse_stmt parse_exp)
-            ))
-            (many_0 parse_terminator)
-        ));
-
-        let ast = result.map(|x| {
-            let (_, stmt_or_exp, rest, _) = x.unwrap_seq4();
+    pub fn parse_block(&mut self) -> ParserResult<Option<Ast<'a>>> {
+        self.nested(|parser| {
+            if parser.recovering {
+                return parser.parse_recovering_block(true);
+            }
 
-            let ast = match stmt_or_exp.unwrap_choice() {
-                Choice::A(x) => x.unwrap_single(),
-                Choice::B(x) => x.unwrap_single(),
-                _ => unreachable!(),
-            };
+            let result = parse!(parser, Self => (seq
+                (many_0 parse_terminator)
+                (alt parse_stmt parse_exp)
+                (many_0 (seq
+                    (many_1 parse_terminator)
+                    (alt parse_stmt parse_exp)
+                ))
+                (many_0 parse_terminator)
+            ));
 
-            let span_start = ast.span.start;
-            let mut span_end = ast.span.end;
+            let ast = result.map(|x| {
+                let (_, stmt_or_exp, rest, _) = x.unwrap_seq4();
 
-            let mut asts = vec![ast];
-            for x in rest.unwrap_many() {
-                let (_, stmt_or_exp) = x.unwrap_seq2();
                 let ast = match stmt_or_exp.unwrap_choice() {
                     Choice::A(x) => x.unwrap_single(),
                     Choice::B(x) => x.unwrap_single(),
                     _ => unreachable!(),
                 };
-                span_end = ast.span.end;
-                asts.push(ast);
+
+                let span_start = ast.span.start;
+                let mut span_end = ast.span.end;
+
+                let mut asts = vec![ast];
+                for x in rest.unwrap_many() {
+                    let (_, stmt_or_exp) = x.unwrap_seq2();
+                    let ast = match stmt_or_exp.unwrap_choice() {
+                        Choice::A(x) => x.unwrap_single(),
+                        Choice::B(x) => x.unwrap_single(),
+                        _ => unreachable!(),
+                    };
+                    span_end = ast.span.end;
+                    asts.push(ast);
+                }
+
+                Ast::new(span_start..span_end, AstKind::Program(asts))
+            });
+
+            Ok(ast)
+        })
+    }
+
+    /// Parses a block, replacing the statements that fail to parse with [`AstKind::Error`] nodes.
+    ///
+    /// A `nested` block stops before an `END` or `ELSE`, leaving it to the enclosing expression.
+    fn parse_recovering_block(&mut self, nested: bool) -> ParserResult<Option<Ast<'a>>> {
+        let mut asts = vec![];
+        loop {
+            let state = self.get_state();
+            let token = match self.eat_token() {
+                Ok(Some(token)) => token,
+                Ok(None) => break,
+                Err(ParserError::LexerError(error)) => {
+                    self.set_state(state);
+                    let start = error.span().start;
+                    asts.push(self.recover_statement(start, nested, error.into())?);
+                    continue;
+                }
+                Err(error) => return Err(error),
+            };
+
+            if token.kind == TokenKind::Terminator {
+                continue;
+            }
+
+            self.set_state(state.clone());
+            if nested && is_block_end(&token) {
+                break;
+            }
+
+            let outer_failure = self.failure.take();
+            let ast = match self.parse_terminated_statement(nested) {
+                Ok(Some(ast)) => ast,
+                result => {
+                    let error = match result {
+                        Err(error) => error,
+                        _ => self.unexpected_token(),
+                    };
+
+                    self.set_state(state);
+                    self.recover_statement(token.span.start, nested, error)?
+                }
+            };
+
+            self.failure = outer_failure;
+            asts.push(ast);
+        }
+
+        let (Some(first), Some(last)) = (asts.first(), asts.last()) else {
+            return Ok(None);
+        };
+
+        Ok(Some(Ast::new(
+            first.span.start..last.span.end,
+            AstKind::Program(asts),
+        )))
+    }
+
+    /// Parses a statement or an expression that is followed by a terminator, the end of the input
+    /// or, in a `nested` block, the `END` or `ELSE` that closes it.
+    fn parse_terminated_statement(&mut self, nested: bool) -> ParserResult<Option<Ast<'a>>> {
+        let ast = match self.parse_stmt()? {
+            Some(ast) => ast,
+            None => match self.parse_exp()? {
+                Some(ast) => ast,
+                None => return Ok(None),
+            },
+        };
+
+        let state = self.get_state();
+        let token = self.eat_token()?;
+        self.set_state(state);
+
+        match &token {
+            None => Ok(Some(ast)),
+            Some(Token {
+                kind: TokenKind::Terminator,
+                ..
+            }) => Ok(Some(ast)),
+            Some(token) if nested && is_block_end(token) => Ok(Some(ast)),
+            Some(_) => {
+                self.expected(token.as_ref(), "terminator");
+                self.expected(
+                    token.as_ref(),
+                    if nested { "`END`" } else { "end of input" },
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Records `error` for the statement starting at `start` and skips over the statement,
+    /// returning the [`AstKind::Error`] node that takes its place.
+    ///
+    /// The statement ends at the next terminator outside of any `IF`, `FOR`, `WHILE` or module
+    /// block it opens. In a `nested` block, it also ends before the `END` or `ELSE` of the
+    /// enclosing block. At the top level, a stray `END` is skipped as part of the statement.
+    fn recover_statement(
+        &mut self,
+        start: usize,
+        nested: bool,
+        error: ParserError,
+    ) -> ParserResult<Ast<'a>> {
+        let mut end = start;
+        let mut depth = 0usize;
+        let mut after_else = false;
+        let mut in_else_if = false;
+        loop {
+            // Only the position is saved rather than the whole state, as cloning the bracket stack
+            // for every token would make skipping deeply nested input quadratic. The tokens the
+            // loop stops before do not touch the bracket stack.
+            let position = self.lexer.state.position();
+            let token = match self.eat_token() {
+                Ok(Some(token)) => token,
+                Ok(None) => break,
+                Err(ParserError::LexerError(error)) => {
+                    // Skip the offending input and carry on lexing after it.
+                    end = error.span().end.max(position.cursor + 1);
+                    self.lexer.state.cursor = end.min(self.lexer.string.len());
+                    continue;
+                }
+                Err(error) => return Err(error),
+            };
+
+            let keyword = match &token.kind {
+                TokenKind::PlainIdentifier(ident) => ident.to_uppercase(),
+                _ => String::new(),
+            };
+
+            match (token.kind.clone(), keyword.as_str()) {
+                (TokenKind::Terminator, _) if depth == 0 => {
+                    self.lexer.state.set_position(position);
+                    break;
+                }
+                (_, "END" | "ELSE") if depth == 0 && nested => {
+                    self.lexer.state.set_position(position);
+                    break;
+                }
+                (_, "END") if depth == 0 => {
+                    end = token.span.end;
+                    break;
+                }
+                (_, "END") => depth -= 1,
+                (_, "IF") if after_else => in_else_if = true,
+                (_, "THEN") if in_else_if => in_else_if = false,
+                (_, "THEN" | "DO") | (TokenKind::ModuleBlock(_), _) => depth += 1,
+                _ => {}
             }
 
-            Ast::new(span_start..span_end, AstKind::Program(asts))
+            after_else = keyword == "ELSE";
+            end = token.span.end;
+        }
+
+        // Errors found inside the statement belong to a parse of it that has been thrown away.
+        self.errors
+            .retain(|position, _| !(start..end).contains(position));
+        self.errors.insert(start, error);
+
+        Ok(Ast::new(start..end, AstKind::Error))
+    }
+
+    /// Runs `f` one level of nesting deeper, failing if that goes past the maximum depth.
+    ///
+    /// This wraps the rules every recursion in the grammar goes through: operations, expressions
+    /// and blocks.
+    pub(crate) fn nested<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> ParserResult<T>,
+    ) -> ParserResult<T> {
+        if self.depth >= self.max_depth {
+            let state = self.get_state();
+            let cursor = self.lexer.state.cursor;
+            let span = match self.eat_token() {
+                Ok(Some(token)) => token.span,
+                _ => cursor..cursor,
+            };
+            self.set_state(state);
+
+            return Err(ParserError::NestingTooDeep {
+                span,
+                max_depth: self.max_depth,
+            });
+        }
+
+        self.depth += 1;
+        let result = stack::ensure_stack(|| f(self));
+        self.depth -= 1;
+
+        result
+    }
+
+    /// Fails if the operator chain in `result` nests deeper than the maximum depth once folded.
+    ///
+    /// Operator chains are parsed in a loop rather than recursively, but folding them still builds
+    /// a tree as deep as the chain is long, which has to be limited like any other nesting.
+    pub(crate) fn check_chain(&mut self, result: &Option<Combinator<Ast<'a>>>) -> ParserResult<()> {
+        let length = result.as_ref().map_or(0, Combinator::longest_many);
+        if self.depth + length > self.max_depth {
+            let cursor = self.lexer.state.cursor;
+            return Err(ParserError::NestingTooDeep {
+                span: cursor..cursor,
+                max_depth: self.max_depth,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Builds the error for the furthest failure recorded so far.
+    fn unexpected_token(&self) -> ParserError {
+        let end = self.lexer.string.len();
+        let failure = self.failure.clone().unwrap_or(Failure {
+            span: end..end,
+            found: None,
+            expected: BTreeSet::new(),
         });
 
-        Ok(ast)
+        ParserError::UnexpectedToken {
+            span: failure.span,
+            found: failure.found.unwrap_or_else(|| "end of input".to_string()),
+            expected: failure.expected.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> ParsedProgram<'a> {
+    /// Gets the program, or the first error if there were any.
+    pub fn into_result(self) -> ParserResult<Ast<'a>> {
+        match self.errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(self.ast),
+        }
+    }
+
+    /// Checks if any errors were found.
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Checks if a token closes a block, i.e. it is an `END` or an `ELSE`.
+fn is_block_end(token: &Token) -> bool {
+    matches!(
+        token.kind,
+        TokenKind::PlainIdentifier(ident)
+            if ident.eq_ignore_ascii_case("end") || ident.eq_ignore_ascii_case("else")
+    )
+}
+
+/// Describes a token for an error message.
+fn describe_token(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::Terminator => "terminator".to_string(),
+        TokenKind::PlainIdentifier(ident) | TokenKind::EscapedIdentifier(ident) => {
+            format!("identifier `{ident}`")
+        }
+        TokenKind::Variable(_) => format!("variable `{kind}`"),
+        TokenKind::ModuleBlock(_) => "module block".to_string(),
+        TokenKind::BinIntegerLiteral(_)
+        | TokenKind::OctIntegerLiteral(_)
+        | TokenKind::HexIntegerLiteral(_)
+        | TokenKind::DecIntegerLiteral(_)
+        | TokenKind::FloatLiteral(_)
+        | TokenKind::StringLiteral(_)
+        | TokenKind::ByteStringLiteral(_)
+        | TokenKind::RegexLiteral(..) => format!("literal `{kind}`"),
+        _ => format!("`{kind}`"),
     }
 }
 
@@ -137,7 +598,7 @@ impl<'a> Reversible for Parser<'a> {
 mod tests {
     use tracing::info;
 
-    use crate::ast::{AstKind::*, Field, TypeSig::*};
+    use crate::ast::{AstKind::*, Field, TableMode, TypeSig::*};
 
     use super::*;
 
@@ -163,7 +624,7 @@ mod tests {
             20,
         );
 
-        let result = parser.parse_program()?;
+        let result = parser.parse_program().into_result()?;
 
         info!(
             r#"input = {:?} | parse_program = {:#?}"#,
@@ -172,7 +633,7 @@ mod tests {
 
         assert_eq!(
             result,
-            Some(Ast {
+            Ast {
                 span: 14..315,
                 kind: Program(vec![
                     Ast {
@@ -279,6 +740,7 @@ mod tests {
                             }),
                             if_not_exists: false,
                             database: None,
+                            mode: TableMode::Schemafull,
                             fields: vec![
                                 Field {
                                     name: Box::new(Ast {
@@ -345,9 +807,138 @@ mod tests {
                     },
                 ]),
                 tag: Default::default(),
-            })
+            }
         );
 
         Ok(())
     }
+
+    #[test_log::test]
+    fn test_parser_program_errors() -> anyhow::Result<()> {
+        // Trailing input that does not continue the program.
+        let result = Parser::new("LET $x = 1; 2 +", 20)
+            .parse_program()
+            .into_result();
+        let Err(ParserError::UnexpectedToken {
+            span,
+            found,
+            expected,
+        }) = result
+        else {
+            panic!("expected an unexpected token error, got {result:?}");
+        };
+
+        assert_eq!(span, 15..15);
+        assert_eq!(found, "end of input");
+        assert!(expected.contains(&"identifier".to_string()));
+        assert!(expected.contains(&"literal".to_string()));
+
+        // The furthest failure is reported, not the first alternative that failed.
+        let result = Parser::new("DEFINE TABLE 5", 20)
+            .parse_program()
+            .into_result();
+        let Err(ParserError::UnexpectedToken {
+            span,
+            found,
+            expected,
+        }) = result
+        else {
+            panic!("expected an unexpected token error, got {result:?}");
+        };
+
+        assert_eq!(span, 13..14);
+        assert_eq!(found, "literal `5`");
+        assert_eq!(expected, ["`IF`", "identifier"]);
+
+        // Two expressions without a terminator between them.
+        let result = Parser::new("1 2", 20).parse_program().into_result();
+        let Err(ParserError::UnexpectedToken { span, expected, .. }) = result else {
+            panic!("expected an unexpected token error, got {result:?}");
+        };
+
+        assert_eq!(span, 2..3);
+        assert!(expected.contains(&"end of input".to_string()));
+        assert!(expected.contains(&"terminator".to_string()));
+
+        // Empty input.
+        let result = Parser::new("", 20).parse_program().into_result();
+        assert!(matches!(
+            result,
+            Err(ParserError::UnexpectedToken { span, .. }) if span == (0..0)
+        ));
+
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_parser_program_recovery() -> anyhow::Result<()> {
+        // Three broken statements among good ones.
+        let program = Parser::new("LET = 1; LET $y = 2; SELECT FROM; 3 +; $y", 20).parse_program();
+
+        assert_eq!(program.errors.len(), 3);
+        assert!(matches!(
+            &program.errors[0],
+            ParserError::UnexpectedToken { span, .. } if *span == (4..5)
+        ));
+
+        let kinds = program
+            .ast
+            .kind
+            .unwrap_program()
+            .into_iter()
+            .map(|ast| (ast.span, ast.kind))
+            .collect::<Vec<_>>();
+
+        assert_eq!(kinds.len(), 5);
+        assert_eq!(kinds[0], (0..7, Error));
+        assert!(matches!(kinds[1].1, Let { .. }));
+        assert_eq!(kinds[2], (21..32, Error));
+        assert_eq!(kinds[3], (34..37, Error));
+        assert_eq!(kinds[4], (39..41, Variable("y")));
+
+        // Errors inside a block are recovered from within the block, up to its `ELSE` or `END`.
+        let program = Parser::new(
+            "IF $a THEN\n    LET = 1\n    LET $b = 2\nELSE\n    1 +\nEND\nLET $c = 3",
+            20,
+        )
+        .parse_program();
+
+        assert_eq!(program.errors.len(), 2);
+
+        let asts = program.ast.kind.unwrap_program();
+        assert_eq!(asts.len(), 2);
+        assert!(matches!(asts[1].kind, Let { .. }));
+
+        let If { then, r#else, .. } = &asts[0].kind else {
+            panic!("expected an if expression, got {:?}", asts[0].kind);
+        };
+
+        let then = then.kind.clone().unwrap_program();
+        assert_eq!(then.len(), 2);
+        assert_eq!(then[0].kind, Error);
+        assert!(matches!(then[1].kind, Let { .. }));
+
+        let r#else = r#else.as_ref().unwrap().kind.clone().unwrap_program();
+        assert_eq!(r#else.len(), 1);
+        assert_eq!(r#else[0].kind, Error);
+
+        // Lexer errors and stray `END`s are skipped too.
+        let program = Parser::new("1 ) 2; END; 3", 20).parse_program();
+
+        assert_eq!(program.errors.len(), 2);
+        assert!(matches!(program.errors[0], ParserError::LexerError(_)));
+
+        let asts = program.ast.kind.unwrap_program();
+        assert_eq!(asts.len(), 3);
+        assert_eq!(asts[0].kind, Error);
+        assert_eq!(asts[1].kind, Error);
+        assert_eq!(asts[2].kind, IntegerLiteral(3));
+
+        // A program without errors is returned as is.
+        let program = Parser::new("1; 2", 20).parse_program();
+        assert!(!program.has_errors());
+        assert_eq!(program.ast.kind.unwrap_program().len(), 2);
+
+        Ok(())
+    }
 }
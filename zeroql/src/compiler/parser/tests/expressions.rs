@@ -2193,6 +2193,83 @@ fn test_parser_remove_param_exp() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test_log::test]
+fn test_parser_remove_function_exp() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(
+        "remove function IF EXISTS `func` on db `db` REMOVE FUNCTION discount REMOVE FUNCTION discount IF EXISTS ON DATABASE my_db",
+        20,
+    );
+    let result_a = parser.parse_remove_function_exp()?;
+    let result_b = parser.parse_remove_function_exp()?;
+    let result_c = parser.parse_remove_function_exp()?;
+
+    info!(
+        r#"input = {:?} | parse_remove_function_exp parse_remove_function_exp parse_remove_function_exp = {:#?} {:#?} {:#?} "#,
+        parser.lexer.string, result_a, result_b, result_c,
+    );
+
+    assert_eq!(
+        result_a,
+        Some(Ast {
+            span: 0..43,
+            kind: RemoveFunction {
+                subject: Box::new(Ast {
+                    span: 26..32,
+                    kind: Identifier("func"),
+                    tag: Default::default(),
+                }),
+                if_exists: true,
+                database: Some(Box::new(Ast {
+                    span: 39..43,
+                    kind: Identifier("db"),
+                    tag: Default::default(),
+                })),
+            },
+            tag: Default::default(),
+        })
+    );
+
+    assert_eq!(
+        result_b,
+        Some(Ast {
+            span: 44..68,
+            kind: RemoveFunction {
+                subject: Box::new(Ast {
+                    span: 60..68,
+                    kind: Identifier("discount"),
+                    tag: Default::default(),
+                }),
+                if_exists: false,
+                database: None,
+            },
+            tag: Default::default(),
+        })
+    );
+
+    assert_eq!(
+        result_c,
+        Some(Ast {
+            span: 69..121,
+            kind: RemoveFunction {
+                subject: Box::new(Ast {
+                    span: 85..93,
+                    kind: Identifier("discount"),
+                    tag: Default::default(),
+                }),
+                if_exists: true,
+                database: Some(Box::new(Ast {
+                    span: 116..121,
+                    kind: Identifier("my_db"),
+                    tag: Default::default(),
+                })),
+            },
+            tag: Default::default(),
+        })
+    );
+
+    Ok(())
+}
+
 #[test_log::test]
 fn test_parser_remove_exp() -> anyhow::Result<()> {
     let parser = &mut Parser::new(
@@ -2205,6 +2282,7 @@ fn test_parser_remove_exp() -> anyhow::Result<()> {
         REMOVE INDEX my_index ON DATABASE my_db IF EXISTS ON TABLE my_table\
         REMOVE MODULE my_module ON DATABASE my_db IF EXISTS\
         REMOVE PARAM $my_param ON DATABASE my_db IF EXISTS\
+        REMOVE FUNCTION my_function ON DATABASE my_db IF EXISTS\
         "#,
         20,
     );
@@ -2218,9 +2296,10 @@ fn test_parser_remove_exp() -> anyhow::Result<()> {
     let result_g = parser.parse_remove_exp()?;
     let result_h = parser.parse_remove_exp()?;
     let result_i = parser.parse_remove_exp()?;
+    let result_j = parser.parse_remove_exp()?;
 
     info!(
-        r#"input = {:?} | = {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?}"#,
+        r#"input = {:?} | = {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?}"#,
         parser.lexer.string,
         result_a,
         result_b,
@@ -2231,6 +2310,7 @@ fn test_parser_remove_exp() -> anyhow::Result<()> {
         result_g,
         result_h,
         result_i,
+        result_j,
     );
 
     assert!(result_a.is_some());
@@ -2242,6 +2322,7 @@ fn test_parser_remove_exp() -> anyhow::Result<()> {
     assert!(result_g.is_some());
     assert!(result_h.is_some());
     assert!(result_i.is_some());
+    assert!(result_j.is_some());
 
     Ok(())
 }
@@ -2939,6 +3020,83 @@ fn test_parser_describe_param_exp() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test_log::test]
+fn test_parser_describe_function_exp() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(
+        "describe function IF EXISTS `func` on db `db` DESCRIBE FUNCTION discount DESCRIBE FUNCTION discount IF EXISTS ON DATABASE my_db",
+        20,
+    );
+    let result_a = parser.parse_describe_function_exp()?;
+    let result_b = parser.parse_describe_function_exp()?;
+    let result_c = parser.parse_describe_function_exp()?;
+
+    info!(
+        r#"input = {:?} | parse_describe_function_exp parse_describe_function_exp parse_describe_function_exp = {:#?} {:#?} {:#?} "#,
+        parser.lexer.string, result_a, result_b, result_c,
+    );
+
+    assert_eq!(
+        result_a,
+        Some(Ast {
+            span: 0..45,
+            kind: DescribeFunction {
+                subject: Box::new(Ast {
+                    span: 28..34,
+                    kind: Identifier("func"),
+                    tag: Default::default(),
+                }),
+                if_exists: true,
+                database: Some(Box::new(Ast {
+                    span: 41..45,
+                    kind: Identifier("db"),
+                    tag: Default::default(),
+                })),
+            },
+            tag: Default::default(),
+        })
+    );
+
+    assert_eq!(
+        result_b,
+        Some(Ast {
+            span: 46..72,
+            kind: DescribeFunction {
+                subject: Box::new(Ast {
+                    span: 64..72,
+                    kind: Identifier("discount"),
+                    tag: Default::default(),
+                }),
+                if_exists: false,
+                database: None,
+            },
+            tag: Default::default(),
+        })
+    );
+
+    assert_eq!(
+        result_c,
+        Some(Ast {
+            span: 73..127,
+            kind: DescribeFunction {
+                subject: Box::new(Ast {
+                    span: 91..99,
+                    kind: Identifier("discount"),
+                    tag: Default::default(),
+                }),
+                if_exists: true,
+                database: Some(Box::new(Ast {
+                    span: 122..127,
+                    kind: Identifier("my_db"),
+                    tag: Default::default(),
+                })),
+            },
+            tag: Default::default(),
+        })
+    );
+
+    Ok(())
+}
+
 #[test_log::test]
 fn test_parser_describe_exp() -> anyhow::Result<()> {
     let parser = &mut Parser::new(
@@ -2951,6 +3109,7 @@ fn test_parser_describe_exp() -> anyhow::Result<()> {
         DESCRIBE INDEX my_index ON DATABASE my_db IF EXISTS ON TABLE my_table\
         DESCRIBE MODULE my_module ON DATABASE my_db IF EXISTS\
         DESCRIBE PARAM $my_param ON DATABASE my_db IF EXISTS\
+        DESCRIBE FUNCTION my_function ON DATABASE my_db IF EXISTS\
         "#,
         20,
     );
@@ -2964,9 +3123,10 @@ fn test_parser_describe_exp() -> anyhow::Result<()> {
     let result_g = parser.parse_describe_exp()?;
     let result_h = parser.parse_describe_exp()?;
     let result_i = parser.parse_describe_exp()?;
+    let result_j = parser.parse_describe_exp()?;
 
     info!(
-        r#"input = {:?} | = {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?}"#,
+        r#"input = {:?} | = {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?}"#,
         parser.lexer.string,
         result_a,
         result_b,
@@ -2977,6 +3137,7 @@ fn test_parser_describe_exp() -> anyhow::Result<()> {
         result_g,
         result_h,
         result_i,
+        result_j,
     );
 
     assert!(result_a.is_some());
@@ -2988,6 +3149,7 @@ fn test_parser_describe_exp() -> anyhow::Result<()> {
     assert!(result_g.is_some());
     assert!(result_h.is_some());
     assert!(result_i.is_some());
+    assert!(result_j.is_some());
 
     Ok(())
 }
@@ -0,0 +1,173 @@
+use crate::parser::{Parser, ParserError, DEFAULT_MAX_DEPTH};
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// A cache big enough to hold every rule result the nesting goes through, so that the tests parse
+/// in linear time.
+const CACHE_SIZE: usize = 100_000;
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[test_log::test]
+fn test_parser_nesting_within_limit() -> anyhow::Result<()> {
+    for input in [
+        nest("(", "1", ")", 50),
+        nest("[", "1", "]", 100),
+        nest("{ a: ", "1", " }", 100),
+        nest("IF $a THEN ", "1", " END", 100),
+        format!("1{}", " + 1".repeat(200)),
+        format!("1{}", " ** 1".repeat(200)),
+    ] {
+        let program = Parser::new(&input, CACHE_SIZE).parse_program();
+        assert!(program.errors.is_empty(), "{:?}", program.errors);
+    }
+
+    Ok(())
+}
+
+#[test_log::test]
+fn test_parser_nesting_too_deep() -> anyhow::Result<()> {
+    // These run on the test thread's small stack, so they also show that going deep does not
+    // overflow it before the limit is reached.
+    for input in [
+        nest("(", "1", ")", 100_000),
+        nest("[", "1", "]", 100_000),
+        nest("{ a: ", "1", " }", 100_000),
+        nest("IF $a THEN ", "1", " END", 100_000),
+        nest("FOR $a IN $b DO ", "1", " END", 100_000),
+        nest("foo(", "1", ")", 100_000),
+        nest("$a[", "1", "]", 100_000),
+        nest("[1, ", "1", "]", 100_000),
+        format!("1{}", " + 1".repeat(2_000)),
+        format!("1{}", " ** 1".repeat(2_000)),
+        format!("$a{}", ".b".repeat(2_000)),
+        format!("a{}", " -> b -> c".repeat(1_000)),
+    ] {
+        assert_nesting_too_deep(&input, DEFAULT_MAX_DEPTH);
+    }
+
+    Ok(())
+}
+
+#[test_log::test]
+fn test_parser_nesting_fuzz() -> anyhow::Result<()> {
+    // Randomly nest a mix of constructs, some of them unbalanced. The parser must always return
+    // instead of overflowing the stack, and reject anything nested past the limit.
+    let openers = [
+        ("(", ")"),
+        ("[", "]"),
+        ("{ a: ", " }"),
+        ("1 + (", ")"),
+        ("[1, ", "]"),
+        ("foo(", ")"),
+        ("- (", ")"),
+        ("NOT (", ")"),
+        ("$a[", "]"),
+    ];
+
+    let mut random = XorShift(0x2545_f491_4f6c_dd1d);
+    for _ in 0..12 {
+        let depth = 100 + random.next() % 600;
+        let mut input = String::new();
+        let mut closers = vec![];
+        for _ in 0..depth {
+            let (open, close) = openers[random.next() % openers.len()];
+            input.push_str(open);
+            closers.push(close);
+        }
+
+        input.push('1');
+        let unbalanced = random.next().is_multiple_of(4);
+        while let Some(close) = closers.pop() {
+            if unbalanced && random.next().is_multiple_of(10) {
+                continue;
+            }
+            input.push_str(close);
+        }
+
+        let program = Parser::new(&input, CACHE_SIZE).parse_program();
+        // Directly nested lists share a level, so only twice the limit is sure to be too deep.
+        if depth >= 2 * DEFAULT_MAX_DEPTH {
+            assert!(
+                matches!(&program.errors[..], [ParserError::NestingTooDeep { .. }]),
+                "{input}: {:?}",
+                program.errors
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test_log::test]
+fn test_parser_with_max_depth() -> anyhow::Result<()> {
+    // A program starts out three levels deep, in a block, an expression and an operation. Each
+    // pair of brackets adds a level.
+    let input = "((1))";
+    let program = Parser::new(input, 20).with_max_depth(5).parse_program();
+    assert!(program.errors.is_empty(), "{:?}", program.errors);
+
+    assert_nesting_too_deep(input, 4);
+
+    // So does each operator in a chain.
+    let program = Parser::new("1 + 2 + 3", 20)
+        .with_max_depth(5)
+        .parse_program();
+    assert!(program.errors.is_empty(), "{:?}", program.errors);
+
+    let program = Parser::new("1 + 2 + 3 + 4", 20)
+        .with_max_depth(5)
+        .parse_program();
+    assert!(matches!(
+        &program.errors[..],
+        [ParserError::NestingTooDeep { max_depth: 5, .. }]
+    ));
+
+    Ok(())
+}
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// A small deterministic random number generator.
+struct XorShift(u64);
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl XorShift {
+    fn next(&mut self) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 as usize
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn nest(open: &str, inner: &str, close: &str, depth: usize) -> String {
+    format!("{}{inner}{}", open.repeat(depth), close.repeat(depth))
+}
+
+fn assert_nesting_too_deep(input: &str, max_depth: usize) {
+    let program = Parser::new(input, CACHE_SIZE)
+        .with_max_depth(max_depth)
+        .parse_program();
+
+    match &program.errors[..] {
+        [ParserError::NestingTooDeep { max_depth: m, .. }] if *m == max_depth => {}
+        errors => panic!(
+            "expected nesting too deep for {}...: {errors:?}",
+            &input[..input.len().min(40)]
+        ),
+    }
+}
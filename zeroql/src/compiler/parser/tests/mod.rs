@@ -1,5 +1,6 @@
 mod expressions;
 mod keywords;
+mod limits;
 mod literals;
 mod meta;
 mod operations;
@@ -158,6 +158,27 @@ fn test_parser_identifier_scope_op() -> anyhow::Result<()> {
         ))
     );
 
+    let parser = &mut Parser::new("index::fulltext select", 20);
+    let result_a = parser.parse_identifier_scope_op()?;
+    let result_b = parser.parse_identifier_scope_op()?;
+
+    info!(
+        r#"input = {:?} | parse_identifier_scope_op parse_identifier_scope_op = {:?} {:?}"#,
+        parser.lexer.string, result_a, result_b,
+    );
+
+    assert_eq!(
+        result_a,
+        Some(Ast::new(
+            0..15,
+            ScopedIdentifier(vec![
+                Ast::new(0..5, Identifier("index")),
+                Ast::new(7..15, Identifier("fulltext")),
+            ])
+        ))
+    );
+    assert_eq!(result_b, None);
+
     Ok(())
 }
 
@@ -1688,7 +1709,7 @@ fn test_parser_rel_op() -> anyhow::Result<()> {
         result_g,
         Some(Ast {
             span: 72..86,
-            kind: ContainsAnyOp(
+            kind: ContainsOp(
                 Box::new(Ast {
                     span: 72..75,
                     kind: Identifier("two"),
@@ -1708,7 +1729,7 @@ fn test_parser_rel_op() -> anyhow::Result<()> {
         result_h,
         Some(Ast {
             span: 87..105,
-            kind: ContainsOp(
+            kind: NotContainsOp(
                 Box::new(Ast {
                     span: 87..90,
                     kind: Identifier("two"),
@@ -1728,7 +1749,7 @@ fn test_parser_rel_op() -> anyhow::Result<()> {
         result_i,
         Some(Ast {
             span: 106..133,
-            kind: NotContainsOp(
+            kind: ContainsNoneOp(
                 Box::new(Ast {
                     span: 106..109,
                     kind: Identifier("two"),
@@ -1764,7 +1785,7 @@ fn test_parser_rel_op() -> anyhow::Result<()> {
         result_j,
         Some(Ast {
             span: 134..160,
-            kind: ContainsNoneOp(
+            kind: ContainsAllOp(
                 Box::new(Ast {
                     span: 134..137,
                     kind: Identifier("two"),
@@ -1800,7 +1821,7 @@ fn test_parser_rel_op() -> anyhow::Result<()> {
         result_k,
         Some(Ast {
             span: 161..187,
-            kind: ContainsAllOp(
+            kind: ContainsAnyOp(
                 Box::new(Ast {
                     span: 161..164,
                     kind: Identifier("two"),
@@ -3234,6 +3255,70 @@ fn test_parser_relate_edge_id() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test_log::test]
+fn test_parser_relate_edge_id_unbounded() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(r#"is_a[..] is_a[1 ..] as i"#, 20);
+    let result_a = parser.parse_relate_edge_id()?;
+    let result_b = parser.parse_relate_edge_id()?;
+
+    info!(
+        r#"input = {:?} | parse_relate_edge_id parse_relate_edge_id = {:#?} {:#?}"#,
+        parser.lexer.string, result_a, result_b,
+    );
+
+    assert_eq!(
+        result_a,
+        Some(Ast {
+            span: 0..8,
+            kind: RelateEdgeId {
+                subject: Box::new(Ast {
+                    span: 0..4,
+                    kind: Identifier("is_a"),
+                    tag: Default::default(),
+                }),
+                depth: Some(Box::new(Ast {
+                    span: 5..7,
+                    kind: UnboundedRangeOp(None),
+                    tag: Default::default(),
+                })),
+                alias: None,
+            },
+            tag: Default::default(),
+        })
+    );
+
+    assert_eq!(
+        result_b,
+        Some(Ast {
+            span: 9..24,
+            kind: RelateEdgeId {
+                subject: Box::new(Ast {
+                    span: 9..13,
+                    kind: Identifier("is_a"),
+                    tag: Default::default(),
+                }),
+                depth: Some(Box::new(Ast {
+                    span: 14..18,
+                    kind: UnboundedRangeOp(Some(Box::new(Ast {
+                        span: 14..15,
+                        kind: IntegerLiteral(1),
+                        tag: Default::default(),
+                    }))),
+                    tag: Default::default(),
+                })),
+                alias: Some(Box::new(Ast {
+                    span: 23..24,
+                    kind: Identifier("i"),
+                    tag: Default::default(),
+                })),
+            },
+            tag: Default::default(),
+        })
+    );
+
+    Ok(())
+}
+
 #[test_log::test]
 fn test_parser_relate_edge_not_op() -> anyhow::Result<()> {
     let parser = &mut Parser::new(r#"NOT friend !likes not`drives`*"#, 20);
@@ -1,7 +1,7 @@
 use tracing::info;
 
 use crate::{
-    ast::{Ast, AstKind::*, Field, TypeSig::*},
+    ast::{Ast, AstKind::*, Field, RedefineField, TableMode, TypeSig::*},
     parser::Parser,
 };
 
@@ -295,6 +295,7 @@ fn test_parser_define_table_stmt() -> anyhow::Result<()> {
                     kind: Identifier("db"),
                     tag: Default::default(),
                 })),
+                mode: TableMode::Schemafull,
                 fields: vec![
                     Field {
                         name: Box::new(Ast {
@@ -379,6 +380,7 @@ fn test_parser_define_table_stmt() -> anyhow::Result<()> {
                 }),
                 if_not_exists: false,
                 database: None,
+                mode: TableMode::Schemafull,
                 fields: vec![],
             },
             tag: Default::default(),
@@ -401,6 +403,7 @@ fn test_parser_define_table_stmt() -> anyhow::Result<()> {
                     kind: Identifier("db"),
                     tag: Default::default(),
                 })),
+                mode: TableMode::Schemafull,
                 fields: vec![],
             },
             tag: Default::default(),
@@ -423,6 +426,7 @@ fn test_parser_define_table_stmt() -> anyhow::Result<()> {
                     kind: Identifier("db"),
                     tag: Default::default(),
                 }),),
+                mode: TableMode::Schemafull,
                 fields: vec![Field {
                     name: Box::new(Ast {
                         span: 292..296,
@@ -447,6 +451,39 @@ fn test_parser_define_table_stmt() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test_log::test]
+fn test_parser_define_table_stmt_mode() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(
+        r#"DEFINE TABLE invoice SCHEMAFULL FIELDS total TYPE f64\
+        DEFINE TABLE document SCHEMALESS\
+        DEFINE TABLE event FIELDS starts TYPE datetime FLEXIBLE TYPE string\
+        DEFINE TABLE note FLEXIBLE\
+        "#,
+        20,
+    );
+
+    let mut modes = vec![];
+    for _ in 0..4 {
+        let result = parser.parse_define_table_stmt()?.unwrap();
+        info!("parse_define_table_stmt = {result:#?}");
+
+        let DefineTable { mode, fields, .. } = result.kind else {
+            panic!("expected a DEFINE TABLE");
+        };
+        modes.push((mode, fields.len()));
+    }
+
+    assert_eq!(modes[0], (TableMode::Schemafull, 1));
+    assert_eq!(modes[1], (TableMode::Schemaless, 0));
+    assert!(matches!(
+        &modes[2],
+        (TableMode::Flexible(Some(Basic(r#type))), 1) if r#type.kind == Identifier("string")
+    ));
+    assert_eq!(modes[3], (TableMode::Flexible(None), 0));
+
+    Ok(())
+}
+
 #[test_log::test]
 fn test_parser_define_edge_stmt() -> anyhow::Result<()> {
     let parser = &mut Parser::new(
@@ -1250,6 +1287,131 @@ fn test_parser_define_param_stmt() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test_log::test]
+fn test_parser_define_function_stmt() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(
+        r#"DEFINE FUNCTION discount($price TYPE f64, $rate TYPE f64) TYPE f64 ON DATABASE shop DO
+            RETURN $price * $rate
+        END\
+        DEFINE FUNCTION noop() IF NOT EXISTS DO RETURN END
+        "#,
+        20,
+    );
+    let result_a = parser.parse_define_function_stmt()?;
+    let result_b = parser.parse_define_function_stmt()?;
+
+    info!(
+        r#"input = {:?} | parse_define_function_stmt parse_define_function_stmt = {:#?} {:#?}"#,
+        parser.lexer.string, result_a, result_b,
+    );
+
+    assert_eq!(
+        result_a,
+        Some(Ast {
+            span: 0..132,
+            kind: DefineFunction {
+                name: Box::new(Ast {
+                    span: 16..24,
+                    kind: Identifier("discount"),
+                    tag: Default::default(),
+                }),
+                if_not_exists: false,
+                database: Some(Box::new(Ast {
+                    span: 79..83,
+                    kind: Identifier("shop"),
+                    tag: Default::default(),
+                })),
+                params: vec![
+                    (
+                        Ast {
+                            span: 25..31,
+                            kind: Variable("price"),
+                            tag: Default::default(),
+                        },
+                        Basic(Box::new(Ast {
+                            span: 37..40,
+                            kind: Identifier("f64"),
+                            tag: Default::default(),
+                        })),
+                    ),
+                    (
+                        Ast {
+                            span: 42..47,
+                            kind: Variable("rate"),
+                            tag: Default::default(),
+                        },
+                        Basic(Box::new(Ast {
+                            span: 53..56,
+                            kind: Identifier("f64"),
+                            tag: Default::default(),
+                        })),
+                    ),
+                ],
+                r#type: Some(Basic(Box::new(Ast {
+                    span: 63..66,
+                    kind: Identifier("f64"),
+                    tag: Default::default(),
+                }))),
+                body: Box::new(Ast {
+                    span: 99..120,
+                    kind: Program(vec![Ast {
+                        span: 99..120,
+                        kind: Return(Some(Box::new(Ast {
+                            span: 106..120,
+                            kind: MultiplicationOp(
+                                Box::new(Ast {
+                                    span: 106..112,
+                                    kind: Variable("price"),
+                                    tag: Default::default(),
+                                }),
+                                Box::new(Ast {
+                                    span: 115..120,
+                                    kind: Variable("rate"),
+                                    tag: Default::default(),
+                                }),
+                            ),
+                            tag: Default::default(),
+                        }))),
+                        tag: Default::default(),
+                    }]),
+                    tag: Default::default(),
+                }),
+            },
+            tag: Default::default(),
+        })
+    );
+
+    assert_eq!(
+        result_b,
+        Some(Ast {
+            span: 142..192,
+            kind: DefineFunction {
+                name: Box::new(Ast {
+                    span: 158..162,
+                    kind: Identifier("noop"),
+                    tag: Default::default(),
+                }),
+                if_not_exists: true,
+                database: None,
+                params: vec![],
+                r#type: None,
+                body: Box::new(Ast {
+                    span: 182..188,
+                    kind: Program(vec![Ast {
+                        span: 182..188,
+                        kind: Return(None),
+                        tag: Default::default(),
+                    }]),
+                    tag: Default::default(),
+                }),
+            },
+            tag: Default::default(),
+        })
+    );
+
+    Ok(())
+}
+
 #[test_log::test]
 fn test_parser_define_stmt() -> anyhow::Result<()> {
     let parser = &mut Parser::new(
@@ -1261,7 +1423,8 @@ fn test_parser_define_stmt() -> anyhow::Result<()> {
         DEFINE ENUM `enum` ON DATABASE `database` VARIANTS a, b, c IF NOT EXISTS\
         DEFINE INDEX `index` FIELDS name, age WITH std::foo(a = $value) ON TABLE `table` ON DATABASE `database`\
         DEFINE MODULE m IF NOT EXIST WITH function foo() { return 20; } END ON DATABASE `database`\
-        DEFINE PARAM p TYPE string? VALUE "hello" ON DATABASE my_db IF NOT EXISTS
+        DEFINE PARAM p TYPE string? VALUE "hello" ON DATABASE my_db IF NOT EXISTS\
+        DEFINE FUNCTION f($a TYPE u8) TYPE u8 DO RETURN $a END
         "#,
         20,
     );
@@ -1274,9 +1437,10 @@ fn test_parser_define_stmt() -> anyhow::Result<()> {
     let result_g = parser.parse_define_stmt()?;
     let result_h = parser.parse_define_stmt()?;
     let result_i = parser.parse_define_stmt()?;
+    let result_j = parser.parse_define_stmt()?;
 
     info!(
-        r#"input = {:?} | parse_define_stmt = {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?}"#,
+        r#"input = {:?} | parse_define_stmt = {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?} {:#?}"#,
         parser.lexer.string,
         result_a,
         result_b,
@@ -1287,6 +1451,7 @@ fn test_parser_define_stmt() -> anyhow::Result<()> {
         result_g,
         result_h,
         result_i,
+        result_j,
     );
 
     assert!(result_a.is_some());
@@ -1298,6 +1463,363 @@ fn test_parser_define_stmt() -> anyhow::Result<()> {
     assert!(result_g.is_some());
     assert!(result_h.is_some());
     assert!(result_i.is_some());
+    assert!(result_j.is_some());
+
+    Ok(())
+}
+
+#[test_log::test]
+fn test_parser_redefine_table_stmt() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(
+        r#"REDEFINE TABLE IF EXISTS person AS people ON DATABASE my_db FIELDS name AS full_name, age TYPE u8\
+        REDEFINE TABLE person
+        "#,
+        20,
+    );
+    let result_a = parser.parse_redefine_table_stmt()?;
+    let result_b = parser.parse_redefine_table_stmt()?;
+
+    info!(
+        r#"input = {:?} | parse_redefine_table_stmt parse_redefine_table_stmt = {:#?} {:#?}"#,
+        parser.lexer.string, result_a, result_b,
+    );
+
+    assert_eq!(
+        result_a,
+        Some(Ast {
+            span: 0..97,
+            kind: RedefineTable {
+                name: Box::new(Ast {
+                    span: 25..31,
+                    kind: Identifier("person"),
+                    tag: Default::default(),
+                }),
+                if_exists: true,
+                new_name: Some(Box::new(Ast {
+                    span: 35..41,
+                    kind: Identifier("people"),
+                    tag: Default::default(),
+                })),
+                database: Some(Box::new(Ast {
+                    span: 54..59,
+                    kind: Identifier("my_db"),
+                    tag: Default::default(),
+                })),
+                fields: vec![
+                    RedefineField {
+                        name: Box::new(Ast {
+                            span: 67..71,
+                            kind: Identifier("name"),
+                            tag: Default::default(),
+                        }),
+                        new_name: Some(Box::new(Ast {
+                            span: 75..84,
+                            kind: Identifier("full_name"),
+                            tag: Default::default(),
+                        })),
+                        r#type: None,
+                    },
+                    RedefineField {
+                        name: Box::new(Ast {
+                            span: 86..89,
+                            kind: Identifier("age"),
+                            tag: Default::default(),
+                        }),
+                        new_name: None,
+                        r#type: Some(Basic(Box::new(Ast {
+                            span: 95..97,
+                            kind: Identifier("u8"),
+                            tag: Default::default(),
+                        }))),
+                    },
+                ],
+            },
+            tag: Default::default(),
+        })
+    );
+
+    assert_eq!(
+        result_b,
+        Some(Ast {
+            span: 107..128,
+            kind: RedefineTable {
+                name: Box::new(Ast {
+                    span: 122..128,
+                    kind: Identifier("person"),
+                    tag: Default::default(),
+                }),
+                if_exists: false,
+                new_name: None,
+                database: None,
+                fields: vec![],
+            },
+            tag: Default::default(),
+        })
+    );
+
+    Ok(())
+}
+
+#[test_log::test]
+fn test_parser_redefine_edge_stmt() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(
+        r#"REDEFINE EDGE follows FIELDS since AS followed_at TYPE datetime"#,
+        20,
+    );
+    let result = parser.parse_redefine_edge_stmt()?;
+
+    info!(
+        r#"input = {:?} | parse_redefine_edge_stmt = {:#?}"#,
+        parser.lexer.string, result,
+    );
+
+    assert_eq!(
+        result,
+        Some(Ast {
+            span: 0..63,
+            kind: RedefineEdge {
+                name: Box::new(Ast {
+                    span: 14..21,
+                    kind: Identifier("follows"),
+                    tag: Default::default(),
+                }),
+                if_exists: false,
+                new_name: None,
+                database: None,
+                fields: vec![RedefineField {
+                    name: Box::new(Ast {
+                        span: 29..34,
+                        kind: Identifier("since"),
+                        tag: Default::default(),
+                    }),
+                    new_name: Some(Box::new(Ast {
+                        span: 38..49,
+                        kind: Identifier("followed_at"),
+                        tag: Default::default(),
+                    })),
+                    r#type: Some(Basic(Box::new(Ast {
+                        span: 55..63,
+                        kind: Identifier("datetime"),
+                        tag: Default::default(),
+                    }))),
+                }],
+            },
+            tag: Default::default(),
+        })
+    );
+
+    Ok(())
+}
+
+#[test_log::test]
+fn test_parser_redefine_type_stmt() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(r#"REDEFINE TYPE point FIELDS x AS lat TYPE f64"#, 20);
+    let result = parser.parse_redefine_type_stmt()?;
+
+    info!(
+        r#"input = {:?} | parse_redefine_type_stmt = {:#?}"#,
+        parser.lexer.string, result,
+    );
+
+    assert_eq!(
+        result,
+        Some(Ast {
+            span: 0..44,
+            kind: RedefineType {
+                name: Box::new(Ast {
+                    span: 14..19,
+                    kind: Identifier("point"),
+                    tag: Default::default(),
+                }),
+                if_exists: false,
+                new_name: None,
+                database: None,
+                fields: vec![RedefineField {
+                    name: Box::new(Ast {
+                        span: 27..28,
+                        kind: Identifier("x"),
+                        tag: Default::default(),
+                    }),
+                    new_name: Some(Box::new(Ast {
+                        span: 32..35,
+                        kind: Identifier("lat"),
+                        tag: Default::default(),
+                    })),
+                    r#type: Some(Basic(Box::new(Ast {
+                        span: 41..44,
+                        kind: Identifier("f64"),
+                        tag: Default::default(),
+                    }))),
+                }],
+            },
+            tag: Default::default(),
+        })
+    );
+
+    Ok(())
+}
+
+#[test_log::test]
+fn test_parser_redefine_enum_stmt() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(
+        r#"REDEFINE ENUM color AS colour VARIANTS red AS crimson, green, blue"#,
+        20,
+    );
+    let result = parser.parse_redefine_enum_stmt()?;
+
+    info!(
+        r#"input = {:?} | parse_redefine_enum_stmt = {:#?}"#,
+        parser.lexer.string, result,
+    );
+
+    assert_eq!(
+        result,
+        Some(Ast {
+            span: 0..66,
+            kind: RedefineEnum {
+                name: Box::new(Ast {
+                    span: 14..19,
+                    kind: Identifier("color"),
+                    tag: Default::default(),
+                }),
+                if_exists: false,
+                new_name: Some(Box::new(Ast {
+                    span: 23..29,
+                    kind: Identifier("colour"),
+                    tag: Default::default(),
+                })),
+                database: None,
+                variants: Some(vec![
+                    (
+                        Ast {
+                            span: 39..42,
+                            kind: Identifier("red"),
+                            tag: Default::default(),
+                        },
+                        Some(Ast {
+                            span: 46..53,
+                            kind: Identifier("crimson"),
+                            tag: Default::default(),
+                        }),
+                    ),
+                    (
+                        Ast {
+                            span: 55..60,
+                            kind: Identifier("green"),
+                            tag: Default::default(),
+                        },
+                        None,
+                    ),
+                    (
+                        Ast {
+                            span: 62..66,
+                            kind: Identifier("blue"),
+                            tag: Default::default(),
+                        },
+                        None,
+                    ),
+                ]),
+            },
+            tag: Default::default(),
+        })
+    );
+
+    Ok(())
+}
+
+#[test_log::test]
+fn test_parser_redefine_index_stmt() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(
+        r#"REDEFINE INDEX idx_name ON TABLE person AS idx_full_name FIELDS full_name"#,
+        20,
+    );
+    let result = parser.parse_redefine_index_stmt()?;
+
+    info!(
+        r#"input = {:?} | parse_redefine_index_stmt = {:#?}"#,
+        parser.lexer.string, result,
+    );
+
+    assert_eq!(
+        result,
+        Some(Ast {
+            span: 0..73,
+            kind: RedefineIndex {
+                name: Box::new(Ast {
+                    span: 15..23,
+                    kind: Identifier("idx_name"),
+                    tag: Default::default(),
+                }),
+                if_exists: false,
+                new_name: Some(Box::new(Ast {
+                    span: 43..56,
+                    kind: Identifier("idx_full_name"),
+                    tag: Default::default(),
+                })),
+                database: None,
+                table: Box::new(Ast {
+                    span: 33..39,
+                    kind: Identifier("person"),
+                    tag: Default::default(),
+                }),
+                columns: Some(vec![Ast {
+                    span: 64..73,
+                    kind: Identifier("full_name"),
+                    tag: Default::default(),
+                }]),
+                function: None,
+            },
+            tag: Default::default(),
+        })
+    );
+
+    Ok(())
+}
+
+#[test_log::test]
+fn test_parser_redefine_param_stmt() -> anyhow::Result<()> {
+    let parser = &mut Parser::new(
+        r#"REDEFINE PARAM IF EXISTS p AS greeting TYPE string VALUE "hi""#,
+        20,
+    );
+    let result = parser.parse_redefine_param_stmt()?;
+
+    info!(
+        r#"input = {:?} | parse_redefine_param_stmt = {:#?}"#,
+        parser.lexer.string, result,
+    );
+
+    assert_eq!(
+        result,
+        Some(Ast {
+            span: 0..61,
+            kind: RedefineParam {
+                name: Box::new(Ast {
+                    span: 25..26,
+                    kind: Identifier("p"),
+                    tag: Default::default(),
+                }),
+                if_exists: true,
+                new_name: Some(Box::new(Ast {
+                    span: 30..38,
+                    kind: Identifier("greeting"),
+                    tag: Default::default(),
+                })),
+                database: None,
+                r#type: Some(Basic(Box::new(Ast {
+                    span: 44..50,
+                    kind: Identifier("string"),
+                    tag: Default::default(),
+                }))),
+                value: Some(Box::new(Ast {
+                    span: 57..61,
+                    kind: StringLiteral("hi"),
+                    tag: Default::default(),
+                })),
+            },
+            tag: Default::default(),
+        })
+    );
 
     Ok(())
 }
@@ -1353,6 +1875,8 @@ fn test_parser_stmt() -> anyhow::Result<()> {
         use db d\
         break\
         continue\
+        return $x + 1\
+        return\
         "#,
         20,
     );
@@ -1360,10 +1884,12 @@ fn test_parser_stmt() -> anyhow::Result<()> {
     let result_b = parser.parse_stmt()?;
     let result_c = parser.parse_stmt()?;
     let result_d = parser.parse_stmt()?;
+    let result_e = parser.parse_stmt()?;
+    let result_f = parser.parse_stmt()?;
 
     info!(
-        r#"input = {:?} | parse_stmt parse_stmt parse_stmt parse_stmt = {:#?} {:#?} {:#?} {:#?}"#,
-        parser.lexer.string, result_a, result_b, result_c, result_d,
+        r#"input = {:?} | parse_stmt parse_stmt parse_stmt parse_stmt parse_stmt parse_stmt = {:#?} {:#?} {:#?} {:#?} {:#?} {:#?}"#,
+        parser.lexer.string, result_a, result_b, result_c, result_d, result_e, result_f,
     );
 
     assert_eq!(
@@ -1415,5 +1941,38 @@ fn test_parser_stmt() -> anyhow::Result<()> {
         },)
     );
 
+    assert_eq!(
+        result_e,
+        Some(Ast {
+            span: 96..109,
+            kind: Return(Some(Box::new(Ast {
+                span: 103..109,
+                kind: AdditionOp(
+                    Box::new(Ast {
+                        span: 103..105,
+                        kind: Variable("x"),
+                        tag: Default::default(),
+                    }),
+                    Box::new(Ast {
+                        span: 108..109,
+                        kind: IntegerLiteral(1),
+                        tag: Default::default(),
+                    }),
+                ),
+                tag: Default::default(),
+            }))),
+            tag: Default::default(),
+        })
+    );
+
+    assert_eq!(
+        result_f,
+        Some(Ast {
+            span: 119..125,
+            kind: Return(None),
+            tag: Default::default(),
+        })
+    );
+
     Ok(())
 }
@@ -0,0 +1,322 @@
+//! Signatures of the built-in functions.
+//!
+//! Parameters that take values of more than one type, like the numbers of `math::` that may be
+//! integers or floats, have the [`Type::Any`] type.
+
+use super::{FunctionSignature, Type};
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Gets the signatures of the built-in functions.
+pub(super) fn builtins() -> Vec<FunctionSignature> {
+    [
+        graph(),
+        index(),
+        search(),
+        string(),
+        math(),
+        time(),
+        duration(),
+        array(),
+        object(),
+        rand(),
+        uuid(),
+        crypto(),
+        aggregates(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn graph() -> Vec<FunctionSignature> {
+    let records = || list(Type::Any);
+
+    vec![
+        FunctionSignature::new("graph::shortest_path")
+            .with_param("source", Type::Any)
+            .with_param("target", Type::Any)
+            .with_param("kind", Type::Any)
+            .with_returns(Type::Option(Box::new(records()))),
+        FunctionSignature::new("graph::all_paths")
+            .with_param("source", Type::Any)
+            .with_param("target", Type::Any)
+            .with_param("kind", Type::Any)
+            .with_returns(list(records())),
+        FunctionSignature::new("graph::components")
+            .with_param("kind", Type::Any)
+            .with_returns(list(records())),
+        FunctionSignature::new("graph::pagerank")
+            .with_param("kind", Type::Any)
+            .with_optional_param("damping", Type::F64, "0.85")
+            .with_optional_param("iterations", Type::U64, "20")
+            .with_returns(list(Type::Object(vec![
+                ("record".to_string(), Type::Any),
+                ("score".to_string(), Type::F64),
+            ]))),
+        FunctionSignature::new("graph::degree")
+            .with_param("record", Type::Any)
+            .with_optional_param("kind", Type::Any, "NONE")
+            .with_optional_param("direction", Type::String, "\"both\"")
+            .with_returns(Type::I64),
+    ]
+}
+
+fn index() -> Vec<FunctionSignature> {
+    vec![
+        FunctionSignature::new("index::fulltext")
+            .with_optional_param("tokenizer", Type::String, "\"simple\"")
+            .with_optional_param("lowercase", Type::Bool, "true")
+            .with_optional_param("stopwords", Type::Any, "NONE")
+            .with_optional_param("stemmer", Type::Option(Box::new(Type::String)), "NONE")
+            .with_optional_param("k1", Type::F64, "1.2")
+            .with_optional_param("b", Type::F64, "0.75"),
+        FunctionSignature::new("index::hnsw")
+            .with_optional_param("m", Type::U32, "16")
+            .with_optional_param("ef", Type::U32, "64"),
+    ]
+}
+
+fn search() -> Vec<FunctionSignature> {
+    vec![FunctionSignature::new("search::score").with_returns(Type::F64)]
+}
+
+fn string() -> Vec<FunctionSignature> {
+    let unary = |name: &str, returns: Type| {
+        FunctionSignature::new(format!("string::{name}"))
+            .with_param("string", Type::String)
+            .with_returns(returns)
+    };
+    let binary = |name: &str, param: &str, returns: Type| {
+        unary(name, returns).with_param(param, Type::String)
+    };
+
+    vec![
+        unary("len", Type::U64),
+        unary("lowercase", Type::String),
+        unary("uppercase", Type::String),
+        unary("trim", Type::String),
+        unary("reverse", Type::String),
+        binary("contains", "search", Type::Bool),
+        binary("starts_with", "prefix", Type::Bool),
+        binary("ends_with", "suffix", Type::Bool),
+        binary("split", "separator", list(Type::String)),
+        binary("replace", "search", Type::String).with_param("replacement", Type::String),
+        unary("slice", Type::String)
+            .with_param("offset", Type::I64)
+            .with_optional_param("until", Type::Option(Box::new(Type::I64)), "NONE"),
+        unary("repeat", Type::String).with_param("count", Type::U64),
+        FunctionSignature::new("string::join")
+            .with_param("separator", Type::String)
+            .with_variadic("strings", Type::String)
+            .with_returns(Type::String),
+        FunctionSignature::new("string::concat")
+            .with_variadic("values", Type::Any)
+            .with_returns(Type::String),
+    ]
+}
+
+fn math() -> Vec<FunctionSignature> {
+    let unary = |name: &str, returns: Type| {
+        FunctionSignature::new(format!("math::{name}"))
+            .with_param("number", Type::Any)
+            .with_returns(returns)
+    };
+    let variadic = |name: &str| {
+        FunctionSignature::new(format!("math::{name}")).with_variadic("numbers", Type::Any)
+    };
+
+    vec![
+        unary("abs", Type::Any),
+        unary("ceil", Type::Any),
+        unary("floor", Type::Any),
+        unary("round", Type::Any),
+        unary("sqrt", Type::F64),
+        unary("exp", Type::F64),
+        unary("ln", Type::F64),
+        unary("log", Type::F64).with_optional_param("base", Type::Any, "10"),
+        unary("pow", Type::F64).with_param("exponent", Type::Any),
+        unary("clamp", Type::Any)
+            .with_param("min", Type::Any)
+            .with_param("max", Type::Any),
+        variadic("min"),
+        variadic("max"),
+        FunctionSignature::new("math::pi").with_returns(Type::F64),
+        FunctionSignature::new("math::e").with_returns(Type::F64),
+    ]
+}
+
+fn time() -> Vec<FunctionSignature> {
+    let part = |name: &str| {
+        FunctionSignature::new(format!("time::{name}"))
+            .with_param("datetime", Type::Datetime)
+            .with_returns(Type::I64)
+    };
+
+    vec![
+        FunctionSignature::new("time::now").with_returns(Type::Datetime),
+        FunctionSignature::new("time::parse")
+            .with_param("string", Type::String)
+            .with_returns(Type::Datetime),
+        FunctionSignature::new("time::format")
+            .with_param("datetime", Type::Datetime)
+            .with_returns(Type::String),
+        FunctionSignature::new("time::from_unix")
+            .with_param("seconds", Type::I64)
+            .with_returns(Type::Datetime),
+        FunctionSignature::new("time::floor")
+            .with_param("datetime", Type::Datetime)
+            .with_param("duration", Type::Duration)
+            .with_returns(Type::Datetime),
+        part("unix"),
+        part("year"),
+        part("month"),
+        part("day"),
+        part("hour"),
+        part("minute"),
+        part("second"),
+        part("weekday"),
+    ]
+}
+
+fn duration() -> Vec<FunctionSignature> {
+    let from = |name: &str| {
+        FunctionSignature::new(format!("duration::{name}"))
+            .with_param("count", Type::I64)
+            .with_returns(Type::Duration)
+    };
+    let to = |name: &str| {
+        FunctionSignature::new(format!("duration::{name}"))
+            .with_param("duration", Type::Duration)
+            .with_returns(Type::I64)
+    };
+
+    vec![
+        from("nanos"),
+        from("micros"),
+        from("millis"),
+        from("secs"),
+        from("mins"),
+        from("hours"),
+        from("days"),
+        from("weeks"),
+        to("as_nanos"),
+        to("as_millis"),
+        to("as_secs"),
+        FunctionSignature::new("duration::parse")
+            .with_param("string", Type::String)
+            .with_returns(Type::Duration),
+    ]
+}
+
+fn array() -> Vec<FunctionSignature> {
+    let unary = |name: &str, returns: Type| {
+        FunctionSignature::new(format!("array::{name}"))
+            .with_param("array", list(Type::Any))
+            .with_returns(returns)
+    };
+
+    vec![
+        unary("len", Type::U64),
+        unary("first", Type::Any),
+        unary("last", Type::Any),
+        unary("distinct", list(Type::Any)),
+        unary("flatten", list(Type::Any)),
+        unary("reverse", list(Type::Any)),
+        unary("sort", list(Type::Any)),
+        unary("push", list(Type::Any)).with_param("item", Type::Any),
+        unary("contains", Type::Bool).with_param("item", Type::Any),
+        unary("join", Type::String).with_param("separator", Type::String),
+        unary("slice", list(Type::Any))
+            .with_param("offset", Type::I64)
+            .with_optional_param("until", Type::Option(Box::new(Type::I64)), "NONE"),
+        FunctionSignature::new("array::concat")
+            .with_variadic("arrays", list(Type::Any))
+            .with_returns(list(Type::Any)),
+        FunctionSignature::new("array::range")
+            .with_param("low", Type::I64)
+            .with_param("high", Type::I64)
+            .with_returns(list(Type::I64)),
+    ]
+}
+
+fn object() -> Vec<FunctionSignature> {
+    let unary = |name: &str, returns: Type| {
+        FunctionSignature::new(format!("object::{name}"))
+            .with_param("object", Type::Any)
+            .with_returns(returns)
+    };
+
+    vec![
+        unary("keys", list(Type::String)),
+        unary("values", list(Type::Any)),
+        unary("entries", list(Type::Tuple(vec![Type::String, Type::Any]))),
+        unary("len", Type::U64),
+        unary("has", Type::Bool).with_param("key", Type::String),
+        FunctionSignature::new("object::merge").with_variadic("objects", Type::Any),
+    ]
+}
+
+fn rand() -> Vec<FunctionSignature> {
+    vec![
+        FunctionSignature::new("rand::float").with_returns(Type::F64),
+        FunctionSignature::new("rand::bool").with_returns(Type::Bool),
+        FunctionSignature::new("rand::int")
+            .with_param("min", Type::I64)
+            .with_param("max", Type::I64)
+            .with_returns(Type::I64),
+        FunctionSignature::new("rand::string")
+            .with_optional_param("length", Type::U64, "16")
+            .with_returns(Type::String),
+        FunctionSignature::new("rand::choice").with_param("array", list(Type::Any)),
+    ]
+}
+
+fn uuid() -> Vec<FunctionSignature> {
+    vec![
+        FunctionSignature::new("uuid::v4").with_returns(Type::String),
+        FunctionSignature::new("uuid::nil").with_returns(Type::String),
+        FunctionSignature::new("uuid::is_valid")
+            .with_param("string", Type::String)
+            .with_returns(Type::Bool),
+    ]
+}
+
+fn crypto() -> Vec<FunctionSignature> {
+    ["md5", "sha1", "sha256", "sha512"]
+        .into_iter()
+        .map(|name| {
+            FunctionSignature::new(format!("crypto::{name}"))
+                .with_param("data", Type::Any)
+                .with_returns(Type::String)
+        })
+        .collect()
+}
+
+/// Aggregates fold the values of their argument across the rows of a group, e.g. `sum(age)` in
+/// `SELECT FOLD sum(age) FROM person`.
+fn aggregates() -> Vec<FunctionSignature> {
+    let aggregate = |name: &str, returns: Type| {
+        FunctionSignature::new(name)
+            .with_param("input", Type::Any)
+            .with_returns(returns)
+    };
+
+    vec![
+        FunctionSignature::new("count")
+            .with_optional_param("input", Type::Any, "NONE")
+            .with_returns(Type::U64),
+        aggregate("sum", Type::Any),
+        aggregate("avg", Type::F64),
+        aggregate("min", Type::Any),
+        aggregate("max", Type::Any),
+        aggregate("distinct", list(Type::Any)),
+        aggregate("array_agg", list(Type::Any)),
+    ]
+}
+
+fn list(element: Type) -> Type {
+    Type::List(Box::new(element))
+}
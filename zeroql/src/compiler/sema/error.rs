@@ -1,6 +1,8 @@
 use thiserror::Error;
 use zeroutils_path::Path;
 
+use crate::{sema::Type, Span};
+
 //--------------------------------------------------------------------------------------------------
 // Types
 //--------------------------------------------------------------------------------------------------
@@ -9,37 +11,385 @@ use zeroutils_path::Path;
 pub type SemaResult<T> = Result<T, SemaError>;
 
 /// An error that occurred during semantic analysis.
+///
+/// Every variant carries the span of the source the error is about so it can be reported as a
+/// [`Diagnostic`](crate::diagnostic::Diagnostic).
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum SemaError {
     /// An unexpected AST kind.
-    #[error("unexpected ast kind: {0}")]
-    UnexpectedAstKind(String),
+    #[error("unexpected ast kind: {kind}")]
+    UnexpectedAstKind {
+        /// The kind of the unexpected node.
+        kind: String,
+
+        /// The span of the unexpected node.
+        span: Span,
+    },
 
     /// An undefined variable.
-    #[error("undefined variable or parameter: {0}")]
-    UndefinedVariableOrParam(String),
+    #[error("undefined variable or parameter: {name}")]
+    UndefinedVariableOrParam {
+        /// The name of the variable or parameter.
+        name: String,
+
+        /// The span of the usage.
+        span: Span,
+    },
 
     /// An undefined schema item.
-    #[error("undefined schema item: {0}")]
-    UndefinedSchemaItem(Path),
+    #[error("undefined schema item: {path}")]
+    UndefinedSchemaItem {
+        /// The path of the schema item.
+        path: Path,
+
+        /// The span of the usage.
+        span: Span,
+    },
 
     /// An undefined database.
-    #[error("undefined database: {0}")]
-    UndefinedDatabase(Path),
+    #[error("undefined database: {path}")]
+    UndefinedDatabase {
+        /// The path of the database.
+        path: Path,
+
+        /// The span of the usage.
+        span: Span,
+    },
 
     /// An unspecified database.
     #[error("unspecified database")]
-    UnspecifiedDatabase,
+    UnspecifiedDatabase {
+        /// The span of the statement that needs a database.
+        span: Span,
+    },
 
     /// A duplicate table or edge definition.
-    #[error("duplicate schema item definition: {0}")]
-    DuplicateSchemaItemDefinition(Path),
+    #[error("duplicate schema item definition: {path}")]
+    DuplicateSchemaItemDefinition {
+        /// The path of the schema item.
+        path: Path,
+
+        /// The span of the duplicate name.
+        span: Span,
+    },
 
     /// A duplicate type or enum definition.
-    #[error("duplicate type or enum definition: {0}")]
-    DuplicateTypeOrEnumDefinition(String),
+    #[error("duplicate type or enum definition: {name}")]
+    DuplicateTypeOrEnumDefinition {
+        /// The name of the type or enum.
+        name: String,
+
+        /// The span of the duplicate name.
+        span: Span,
+    },
 
     /// A path error.
-    #[error("path error: {0}")]
-    PathError(#[from] zeroutils_path::PathError),
+    #[error("path error: {error}")]
+    PathError {
+        /// The underlying path error.
+        #[source]
+        error: zeroutils_path::PathError,
+
+        /// The span of the name that could not be turned into a path.
+        span: Span,
+    },
+
+    /// The AST nests deeper than the pass allows.
+    #[error("nesting too deep, the maximum depth is {max_depth}")]
+    NestingTooDeep {
+        /// The maximum depth the pass allows.
+        max_depth: usize,
+
+        /// The span of the node that went too deep.
+        span: Span,
+    },
+
+    /// A value does not have the type its context expects.
+    #[error("mismatched types: expected {expected}, found {found}")]
+    MismatchedTypes {
+        /// The type the context expects.
+        expected: Type,
+
+        /// The type of the value.
+        found: Type,
+
+        /// The span of the value.
+        span: Span,
+    },
+
+    /// A unary operator cannot be applied to its operand.
+    #[error("cannot apply `{operator}` to {found}")]
+    InvalidOperandType {
+        /// The operator.
+        operator: &'static str,
+
+        /// The type of the operand.
+        found: Type,
+
+        /// The span of the operation.
+        span: Span,
+    },
+
+    /// A binary operator cannot be applied to its operands.
+    #[error("cannot apply `{operator}` to {left} and {right}")]
+    InvalidOperandTypes {
+        /// The operator.
+        operator: &'static str,
+
+        /// The type of the left operand.
+        left: Type,
+
+        /// The type of the right operand.
+        right: Type,
+
+        /// The span of the operation.
+        span: Span,
+    },
+
+    /// A value that is iterated over cannot be iterated over.
+    #[error("{found} is not iterable")]
+    NotIterable {
+        /// The type of the value.
+        found: Type,
+
+        /// The span of the value.
+        span: Span,
+    },
+
+    /// A value cannot be indexed with the given index.
+    #[error("cannot index {subject} with {index}")]
+    InvalidIndex {
+        /// The type of the value being indexed.
+        subject: Type,
+
+        /// The type of the index.
+        index: Type,
+
+        /// The span of the index operation.
+        span: Span,
+    },
+
+    /// An integer literal does not fit in the integer type it is used as.
+    #[error("integer literal {value} does not fit in {r#type}")]
+    IntegerOutOfRange {
+        /// The value of the literal, including its sign.
+        value: String,
+
+        /// The type the literal is used as.
+        r#type: Type,
+
+        /// The span of the literal.
+        span: Span,
+    },
+
+    /// A field that the type of the value does not have.
+    #[error("no field `{field}` on {r#type}")]
+    UnknownField {
+        /// The name of the field.
+        field: String,
+
+        /// The type of the value.
+        r#type: Type,
+
+        /// A field of the type with a similar name.
+        suggestion: Option<String>,
+
+        /// The span of the field name.
+        span: Span,
+    },
+
+    /// A variant that the enum does not have.
+    #[error("no variant `{variant}` in enum {r#enum}")]
+    UnknownVariant {
+        /// The name of the variant.
+        variant: String,
+
+        /// The name of the enum.
+        r#enum: String,
+
+        /// A variant of the enum with a similar name.
+        suggestion: Option<String>,
+
+        /// The span of the variant name.
+        span: Span,
+    },
+
+    /// An `IF` that matches a value against variants of an enum, without an `ELSE`, leaves some
+    /// variants out.
+    #[error("if over enum {r#enum} does not match variants {}", missing.join(", "))]
+    NonExhaustiveMatch {
+        /// The name of the enum.
+        r#enum: String,
+
+        /// The variants that are not matched, in declaration order.
+        missing: Vec<String>,
+
+        /// The span of the if expression.
+        span: Span,
+    },
+
+    /// A field accessed with `.` on a value that may be `NONE`.
+    #[error("field `{field}` accessed on {r#type}, which may be none")]
+    OptionalFieldAccess {
+        /// The name of the field.
+        field: String,
+
+        /// The optional type of the value.
+        r#type: Type,
+
+        /// The span of the access.
+        span: Span,
+    },
+
+    /// A call to a function that is not defined.
+    #[error("undefined function: {name}")]
+    UndefinedFunction {
+        /// The full name of the function.
+        name: String,
+
+        /// A function of the same namespace with a similar name.
+        suggestion: Option<String>,
+
+        /// The span of the function name.
+        span: Span,
+    },
+
+    /// A named argument that the function has no parameter for.
+    #[error("`{function}` has no parameter `{argument}`")]
+    UnknownArgument {
+        /// The full name of the function.
+        function: String,
+
+        /// The name of the argument.
+        argument: String,
+
+        /// A parameter of the function with a similar name.
+        suggestion: Option<String>,
+
+        /// The span of the argument name.
+        span: Span,
+    },
+
+    /// A call with more positional arguments than the function has parameters.
+    #[error("`{function}` takes at most {max} arguments but {found} were given")]
+    TooManyArguments {
+        /// The full name of the function.
+        function: String,
+
+        /// The number of parameters of the function.
+        max: usize,
+
+        /// The number of arguments given.
+        found: usize,
+
+        /// The span of the first argument too many.
+        span: Span,
+    },
+
+    /// A call that gives the same parameter more than once.
+    #[error("argument `{argument}` of `{function}` is given more than once")]
+    DuplicateArgument {
+        /// The full name of the function.
+        function: String,
+
+        /// The name of the parameter.
+        argument: String,
+
+        /// The span of the argument given again.
+        span: Span,
+    },
+
+    /// A call that does not give a parameter without a default.
+    #[error("missing argument `{param}` of `{function}`")]
+    MissingArgument {
+        /// The full name of the function.
+        function: String,
+
+        /// The name of the parameter.
+        param: String,
+
+        /// The span of the call.
+        span: Span,
+    },
+
+    /// A `RETURN` outside the body of a `DEFINE FUNCTION`.
+    #[error("return outside of a function")]
+    ReturnOutsideFunction {
+        /// The span of the `RETURN`.
+        span: Span,
+    },
+
+    /// A `DEFINE FUNCTION` whose body can end without a `RETURN`, though its return type does not
+    /// take `NONE`.
+    #[error("`{function}` can end without returning a value of type {r#type}")]
+    MissingReturn {
+        /// The full name of the function.
+        function: String,
+
+        /// The declared return type of the function.
+        r#type: Type,
+
+        /// The span of the function body.
+        span: Span,
+    },
+
+    /// A field accessed on a value whose type has no fields.
+    #[error("{r#type} has no fields")]
+    NoFields {
+        /// The type of the value.
+        r#type: Type,
+
+        /// The span of the access.
+        span: Span,
+    },
+
+    /// A `REDEFINE ENUM` that moves or removes a variant the enum already has.
+    #[error("variant `{variant}` cannot be moved or removed")]
+    MovedVariant {
+        /// The name of the variant.
+        variant: String,
+
+        /// The span of the variant found in its place.
+        span: Span,
+    },
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl SemaError {
+    /// Gets the span of the source the error is about.
+    pub fn span(&self) -> &Span {
+        match self {
+            SemaError::UnexpectedAstKind { span, .. }
+            | SemaError::UndefinedVariableOrParam { span, .. }
+            | SemaError::UndefinedSchemaItem { span, .. }
+            | SemaError::UndefinedDatabase { span, .. }
+            | SemaError::UnspecifiedDatabase { span }
+            | SemaError::DuplicateSchemaItemDefinition { span, .. }
+            | SemaError::DuplicateTypeOrEnumDefinition { span, .. }
+            | SemaError::PathError { span, .. }
+            | SemaError::NestingTooDeep { span, .. }
+            | SemaError::MismatchedTypes { span, .. }
+            | SemaError::InvalidOperandType { span, .. }
+            | SemaError::InvalidOperandTypes { span, .. }
+            | SemaError::NotIterable { span, .. }
+            | SemaError::InvalidIndex { span, .. }
+            | SemaError::IntegerOutOfRange { span, .. }
+            | SemaError::UnknownField { span, .. }
+            | SemaError::UnknownVariant { span, .. }
+            | SemaError::NonExhaustiveMatch { span, .. }
+            | SemaError::OptionalFieldAccess { span, .. }
+            | SemaError::NoFields { span, .. }
+            | SemaError::UndefinedFunction { span, .. }
+            | SemaError::UnknownArgument { span, .. }
+            | SemaError::TooManyArguments { span, .. }
+            | SemaError::DuplicateArgument { span, .. }
+            | SemaError::MissingArgument { span, .. }
+            | SemaError::ReturnOutsideFunction { span }
+            | SemaError::MissingReturn { span, .. }
+            | SemaError::MovedVariant { span, .. } => span,
+        }
+    }
 }
@@ -0,0 +1,637 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use crate::{
+    ast::{Ast, AstKind, TypeSig},
+    sema::{suggest, SemaError, SemaResult, Type},
+    Span,
+};
+
+use super::builtins::builtins;
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// A parameter of a [`FunctionSignature`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    /// The name of the parameter, which named arguments refer to.
+    pub name: String,
+
+    /// The type of the arguments the parameter takes.
+    pub r#type: Type,
+
+    /// The value the parameter takes when no argument is given for it, as zeroql source, e.g.
+    /// `0.85`. Parameters without a default must always be given.
+    pub default: Option<String>,
+}
+
+/// The signature of a function that can be called from zeroql, e.g. `graph::pagerank`.
+///
+/// Arguments bind to the parameters by position or by name, e.g. `graph::pagerank(knows,
+/// damping = 0.5)`. Positional arguments beyond the parameters bind to the variadic parameter, if
+/// the function has one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionSignature {
+    /// The full name of the function, e.g. `graph::pagerank`.
+    pub name: String,
+
+    /// The parameters of the function, in order.
+    pub params: Vec<Param>,
+
+    /// The parameter that takes any number of trailing positional arguments.
+    pub variadic: Option<Param>,
+
+    /// The type of the values the function returns.
+    pub returns: Type,
+}
+
+/// A registry of the signatures of the functions that can be called, by full name.
+///
+/// It holds the built-in functions, the functions exported by `DEFINE MODULE` and the functions
+/// defined with `DEFINE FUNCTION`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FunctionRegistry {
+    functions: BTreeMap<String, FunctionSignature>,
+
+    /// The full names of the functions removed with `REMOVE FUNCTION`, which are known not to
+    /// exist even in namespaces the registry does not know all functions of.
+    removed: BTreeSet<String>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// The namespace of the functions defined with `DEFINE FUNCTION`, e.g. `fn::discount`.
+pub const DEFINED_FUNCTION_NAMESPACE: &str = "fn";
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Param {
+    /// Creates a new parameter without a default.
+    pub fn new(name: impl Into<String>, r#type: Type) -> Self {
+        Self {
+            name: name.into(),
+            r#type,
+            default: None,
+        }
+    }
+
+    /// Sets the value the parameter takes when no argument is given for it.
+    pub fn with_default(mut self, default: impl Into<String>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+}
+
+impl FunctionSignature {
+    /// Creates a new signature of a function without parameters that returns [`Type::Any`].
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            params: vec![],
+            variadic: None,
+            returns: Type::Any,
+        }
+    }
+
+    /// Adds a parameter that must always be given.
+    pub fn with_param(mut self, name: impl Into<String>, r#type: Type) -> Self {
+        self.params.push(Param::new(name, r#type));
+        self
+    }
+
+    /// Adds a parameter that takes the given default when no argument is given for it.
+    pub fn with_optional_param(
+        mut self,
+        name: impl Into<String>,
+        r#type: Type,
+        default: impl Into<String>,
+    ) -> Self {
+        self.params
+            .push(Param::new(name, r#type).with_default(default));
+        self
+    }
+
+    /// Sets the parameter that takes any number of trailing positional arguments.
+    pub fn with_variadic(mut self, name: impl Into<String>, r#type: Type) -> Self {
+        self.variadic = Some(Param::new(name, r#type));
+        self
+    }
+
+    /// Sets the type of the values the function returns.
+    pub fn with_returns(mut self, returns: Type) -> Self {
+        self.returns = returns;
+        self
+    }
+
+    /// Gets the signatures of the functions exported by the code block of a `DEFINE MODULE`.
+    ///
+    /// Exports are declared as `export function name(a: string, b?: number, ...c: string[]): T`.
+    /// Parameters marked `?` or given a default are optional, and types that have no zeroql
+    /// counterpart are [`Type::Any`].
+    pub fn from_module(module: &str, block: &str) -> Vec<Self> {
+        let mut signatures = vec![];
+        let mut rest = block;
+
+        while let Some(start) = rest.find("export function") {
+            rest = rest[start + "export function".len()..].trim_start();
+
+            let name_end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let name = &rest[..name_end];
+            rest = rest[name_end..].trim_start();
+
+            let Some(params) = rest.strip_prefix('(') else {
+                continue;
+            };
+            let Some(params_end) = closing_paren(params) else {
+                break;
+            };
+            rest = params[params_end + 1..].trim_start();
+
+            let mut signature = Self::new(format!("{module}::{name}"));
+            for param in split_top_level(&params[..params_end]) {
+                let (param, default) = match param.split_once('=') {
+                    Some((param, default)) => (param.trim(), Some(default.trim())),
+                    None => (param, None),
+                };
+                let (name, r#type) = match param.split_once(':') {
+                    Some((name, r#type)) => (name.trim(), Type::from_module_type(r#type)),
+                    None => (param, Type::Any),
+                };
+
+                if let Some(name) = name.strip_prefix("...") {
+                    signature = signature.with_variadic(name, r#type.element().unwrap_or_default());
+                } else if let Some(name) = name.strip_suffix('?') {
+                    let default = default.unwrap_or("NONE");
+                    signature = signature.with_optional_param(name, r#type, default);
+                } else if let Some(default) = default {
+                    signature = signature.with_optional_param(name, r#type, default);
+                } else {
+                    signature = signature.with_param(name, r#type);
+                }
+            }
+
+            if let Some(returns) = rest.strip_prefix(':') {
+                let end = returns.find('{').unwrap_or(returns.len());
+                signature = signature.with_returns(Type::from_module_type(&returns[..end]));
+            }
+
+            signatures.push(signature);
+        }
+
+        signatures
+    }
+
+    /// Gets the signature of a function defined with `DEFINE FUNCTION`, which is called as
+    /// `fn::name(...)`.
+    ///
+    /// Functions without a declared return type return [`Type::Any`].
+    pub fn from_definition(
+        name: &str,
+        params: &[(Ast, TypeSig)],
+        returns: Option<&TypeSig>,
+    ) -> SemaResult<Self> {
+        let mut signature = Self::new(format!("{DEFINED_FUNCTION_NAMESPACE}::{name}"));
+        for (param, r#type) in params {
+            let AstKind::Variable(param) = param.kind else {
+                return Err(SemaError::UnexpectedAstKind {
+                    kind: param.kind.to_string(),
+                    span: param.span.clone(),
+                });
+            };
+
+            signature = signature.with_param(param, Type::from_type_sig(r#type)?);
+        }
+
+        if let Some(returns) = returns {
+            signature = signature.with_returns(Type::from_type_sig(returns)?);
+        }
+
+        Ok(signature)
+    }
+
+    /// Binds the arguments of a call to the parameters they are given for, by position and then by
+    /// name, and checks that every parameter without a default is given.
+    ///
+    /// Gets each parameter that is given along with the index of its argument.
+    pub fn bind(&self, args: &[Ast], span: &Span) -> SemaResult<Vec<(&Param, usize)>> {
+        let mut bound = vec![None; self.params.len()];
+        let mut rest = vec![];
+
+        for (position, arg) in args.iter().enumerate() {
+            let name = match &arg.kind {
+                AstKind::FunctionArg { name, .. } => name.as_deref(),
+                _ => None,
+            };
+
+            let index = match name {
+                Some(Ast {
+                    kind: AstKind::Identifier(name),
+                    span,
+                    ..
+                }) => self
+                    .params
+                    .iter()
+                    .position(|param| param.name == *name)
+                    .ok_or_else(|| SemaError::UnknownArgument {
+                        function: self.name.clone(),
+                        argument: name.to_string(),
+                        suggestion: suggest(name, self.params.iter().map(|p| p.name.as_str())),
+                        span: span.clone(),
+                    })?,
+                Some(name) => {
+                    return Err(SemaError::UnexpectedAstKind {
+                        kind: name.kind.to_string(),
+                        span: name.span.clone(),
+                    })
+                }
+                None if position < self.params.len() => position,
+                None => match &self.variadic {
+                    Some(variadic) => {
+                        rest.push((variadic, position));
+                        continue;
+                    }
+                    None => {
+                        return Err(SemaError::TooManyArguments {
+                            function: self.name.clone(),
+                            max: self.params.len(),
+                            found: args.len(),
+                            span: arg.span.clone(),
+                        })
+                    }
+                },
+            };
+
+            if bound[index].replace(position).is_some() {
+                return Err(SemaError::DuplicateArgument {
+                    function: self.name.clone(),
+                    argument: self.params[index].name.clone(),
+                    span: arg.span.clone(),
+                });
+            }
+        }
+
+        let mut args = vec![];
+        for (param, value) in self.params.iter().zip(bound) {
+            match value {
+                Some(value) => args.push((param, value)),
+                None if param.default.is_some() => {}
+                None => {
+                    return Err(SemaError::MissingArgument {
+                        function: self.name.clone(),
+                        param: param.name.clone(),
+                        span: span.clone(),
+                    })
+                }
+            }
+        }
+
+        args.extend(rest);
+        Ok(args)
+    }
+}
+
+impl FunctionRegistry {
+    /// Creates a registry without any functions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry of the built-in functions.
+    pub fn builtins() -> Self {
+        let mut registry = Self::new();
+        for signature in builtins() {
+            registry.register(signature);
+        }
+
+        registry
+    }
+
+    /// Registers the signature of a function, replacing any function of the same name.
+    pub fn register(&mut self, signature: FunctionSignature) {
+        self.removed.remove(&signature.name);
+        self.functions.insert(signature.name.clone(), signature);
+    }
+
+    /// Removes the function with the given full name, which is then known not to exist.
+    pub fn remove(&mut self, name: &str) {
+        self.functions.remove(name);
+        self.removed.insert(name.to_string());
+    }
+
+    /// Registers the functions exported by the code block of a `DEFINE MODULE`.
+    pub fn register_module(&mut self, module: &str, block: &str) {
+        for signature in FunctionSignature::from_module(module, block) {
+            self.register(signature);
+        }
+    }
+
+    /// Gets the signature of the function with the given full name.
+    pub fn get(&self, name: &str) -> Option<&FunctionSignature> {
+        self.functions.get(name)
+    }
+
+    /// Gets the signatures of every function in the registry, ordered by name.
+    pub fn functions(&self) -> impl Iterator<Item = &FunctionSignature> {
+        self.functions.values()
+    }
+
+    /// Whether the registry knows every function of the namespace of the given full name.
+    ///
+    /// Functions without a namespace are all built-in, while the functions of other namespaces may
+    /// come from a module or a `DEFINE FUNCTION` in the database.
+    pub fn knows_namespace_of(&self, name: &str) -> bool {
+        match split_name(name) {
+            (Some(DEFINED_FUNCTION_NAMESPACE), _) => false,
+            (Some(namespace), _) => self
+                .functions
+                .keys()
+                .any(|function| split_name(function).0 == Some(namespace)),
+            (None, _) => true,
+        }
+    }
+
+    /// Whether the function with the given full name was removed and not registered again since.
+    pub fn is_removed(&self, name: &str) -> bool {
+        self.removed.contains(name)
+    }
+
+    /// Gets the function of the same namespace with the name closest to the given full name.
+    pub fn suggest(&self, name: &str) -> Option<String> {
+        let (namespace, name) = split_name(name);
+        let suggestion = suggest(
+            name,
+            self.functions
+                .keys()
+                .map(|function| split_name(function))
+                .filter(|(n, _)| *n == namespace)
+                .map(|(_, function)| function),
+        )?;
+
+        Some(match namespace {
+            Some(namespace) => format!("{namespace}::{suggestion}"),
+            None => suggestion,
+        })
+    }
+
+    /// Gets the full name of the function a call's subject names, e.g. `graph::pagerank`.
+    pub fn name_of(subject: &Ast) -> Option<String> {
+        match &subject.kind {
+            AstKind::Identifier(name) => Some(name.to_string()),
+            AstKind::ScopedIdentifier(segments) => segments
+                .iter()
+                .map(|segment| match segment.kind {
+                    AstKind::Identifier(name) => Some(name),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|segments| segments.join("::")),
+            _ => None,
+        }
+    }
+}
+
+impl Type {
+    /// Gets the type of a module function's type annotation, e.g. `string[]`.
+    fn from_module_type(annotation: &str) -> Self {
+        let annotation = annotation.trim();
+        if let Some(element) = annotation.strip_suffix("[]") {
+            return Type::List(Box::new(Self::from_module_type(element)));
+        }
+
+        let variants = split_top_level_on(annotation, '|');
+        if variants.len() > 1 {
+            let (empty, present): (Vec<_>, Vec<_>) = variants
+                .into_iter()
+                .partition(|variant| matches!(*variant, "null" | "undefined"));
+
+            return match present.as_slice() {
+                [present] if !empty.is_empty() => {
+                    Type::Option(Box::new(Self::from_module_type(present)))
+                }
+                _ => Type::Any,
+            };
+        }
+
+        match annotation {
+            "string" | "String" => Type::String,
+            "number" | "Number" => Type::F64,
+            "bigint" | "BigInt" => Type::I64,
+            "boolean" | "Boolean" => Type::Bool,
+            "Uint8Array" => Type::ByteString,
+            "void" | "null" | "undefined" => Type::None,
+            _ => match annotation
+                .strip_prefix("Array<")
+                .and_then(|element| element.strip_suffix('>'))
+            {
+                Some(element) => Type::List(Box::new(Self::from_module_type(element))),
+                None => Type::Any,
+            },
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Gets the value of an argument of a function call, without its name.
+pub(crate) fn arg_value<'b, 'a>(arg: &'b Ast<'a>) -> &'b Ast<'a> {
+    match &arg.kind {
+        AstKind::FunctionArg { value, .. } => value,
+        _ => arg,
+    }
+}
+
+/// Gets the value of an argument of a function call, without its name, to change it.
+pub(crate) fn arg_value_mut<'b, 'a>(arg: &'b mut Ast<'a>) -> &'b mut Ast<'a> {
+    match arg.kind {
+        AstKind::FunctionArg { ref mut value, .. } => value,
+        _ => arg,
+    }
+}
+
+/// Splits a full function name into its namespace, if it has one, and its name.
+fn split_name(name: &str) -> (Option<&str>, &str) {
+    match name.rsplit_once("::") {
+        Some((namespace, name)) => (Some(namespace), name),
+        None => (None, name),
+    }
+}
+
+/// Gets the index of the parenthesis that closes the one just before the given source.
+fn closing_paren(source: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (index, c) in source.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(index),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Splits a parameter list on the commas that are not nested in brackets.
+fn split_top_level(source: &str) -> Vec<&str> {
+    split_top_level_on(source, ',')
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Splits the source on the separators that are not nested in brackets, trimming each part.
+fn split_top_level_on(source: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (index, c) in source.char_indices() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            ')' | ']' | '}' | '>' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                parts.push(source[start..index].trim());
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(source[start..].trim());
+    parts
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Display for FunctionSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (index, param) in self.params.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}: {}", param.name, param.r#type)?;
+            if let Some(default) = &param.default {
+                write!(f, " = {default}")?;
+            }
+        }
+
+        if let Some(variadic) = &self.variadic {
+            if !self.params.is_empty() {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "...{}: {}", variadic.name, variadic.r#type)?;
+        }
+
+        write!(f, ") -> {}", self.returns)
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::is_keyword;
+
+    use super::*;
+
+    #[test]
+    fn test_sema_function_signature_from_module() {
+        let signatures = FunctionSignature::from_module(
+            "test",
+            r#"
+            function helper(x) { return x; }
+
+            export function name(): String {
+                return "Alice"
+            }
+
+            export function greet(name: string, greeting?: string, times: number = 1): string {
+                return greeting + name;
+            }
+
+            export function join(separator: string, ...parts: string[]): string | null {
+                return parts.join(separator);
+            }
+            "#,
+        );
+
+        assert_eq!(
+            signatures,
+            vec![
+                FunctionSignature::new("test::name").with_returns(Type::String),
+                FunctionSignature::new("test::greet")
+                    .with_param("name", Type::String)
+                    .with_optional_param("greeting", Type::String, "NONE")
+                    .with_optional_param("times", Type::F64, "1")
+                    .with_returns(Type::String),
+                FunctionSignature::new("test::join")
+                    .with_param("separator", Type::String)
+                    .with_variadic("parts", Type::String)
+                    .with_returns(Type::Option(Box::new(Type::String))),
+            ]
+        );
+
+        assert_eq!(
+            signatures[2].to_string(),
+            "test::join(separator: string, ...parts: string) -> string?"
+        );
+    }
+
+    #[test]
+    fn test_sema_function_registry() {
+        let mut registry = FunctionRegistry::builtins();
+        registry.register_module("test", "export function name(): String {}");
+
+        assert!(registry.get("graph::pagerank").is_some());
+        assert!(registry.get("test::name").is_some());
+        assert!(registry.knows_namespace_of("graph::page_rank"));
+        assert!(registry.knows_namespace_of("test::other"));
+        assert!(!registry.knows_namespace_of("http::get"));
+
+        // Functions defined in the database may not be in the registry.
+        registry.register(FunctionSignature::new("fn::discount"));
+        assert!(registry.get("fn::discount").is_some());
+        assert!(!registry.knows_namespace_of("fn::other"));
+        assert_eq!(
+            registry.suggest("graph::page_rank"),
+            Some("graph::pagerank".to_string())
+        );
+        assert_eq!(
+            registry.suggest("index::hsnw"),
+            Some("index::hnsw".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sema_function_builtin_params_are_not_keywords() {
+        for signature in FunctionRegistry::builtins().functions() {
+            for param in &signature.params {
+                assert!(
+                    !is_keyword(&param.name),
+                    "`{}` of {} cannot be passed by name",
+                    param.name,
+                    signature.name
+                );
+            }
+        }
+    }
+}
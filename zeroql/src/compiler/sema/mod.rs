@@ -9,19 +9,24 @@
 //! - Dead code elimination
 //! - ...and more
 
+mod builtins;
 mod error;
+mod functions;
 mod pass;
 mod schema;
 mod sema;
 mod symbols;
 mod traits;
+mod types;
 
 //--------------------------------------------------------------------------------------------------
 // Exports
 //--------------------------------------------------------------------------------------------------
 
 pub use error::*;
+pub use functions::*;
 pub use pass::*;
 pub use schema::*;
 pub use sema::*;
 pub use symbols::*;
+pub use types::*;
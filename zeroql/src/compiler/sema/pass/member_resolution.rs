@@ -1,8 +1,768 @@
 //! - type member resolution
 
+use crate::{
+    ast::{walk_ast, Ast, AstKind::*, Visitor},
+    compiler::stack,
+    fmt::{FormatOptions, Formatter},
+    sema::{DatabaseSchema, SemaError, SemaResult, TableModeMeta, Type, DEFAULT_MAX_DEPTH},
+    Span,
+};
+
+use super::schema_tracking::SchemaTracker;
+
 //--------------------------------------------------------------------------------------------------
 // Types
 //--------------------------------------------------------------------------------------------------
 
-/// TODO: implement
-pub struct MemberResolver {}
+/// `MemberResolver` pass resolves field accesses against the fields of the tables, edges and types
+/// defined in the program, and enum variants against the variants of the enums.
+///
+/// It resolves `.`, `?.` and `.*` accesses and indexing with a string literal, also through lists
+/// and arrays, e.g. `addresses[0].coords` or `addresses.coords`. Fields of a value that may be
+/// `NONE` must be accessed with `?.`.
+///
+/// ## Note
+///
+/// The types of the accessed values are read from the tags set by
+/// [`TypeInferencer`](super::TypeInferencer), which must run first. Values whose type is not known
+/// pass every check.
+pub struct MemberResolver {
+    /// The tables, edges, types and enums whose fields and variants can be accessed.
+    schema: SchemaTracker,
+
+    /// How deeply the node being resolved is nested.
+    depth: usize,
+
+    /// How deeply nodes may nest before resolution fails.
+    max_depth: usize,
+}
+
+/// `VariantMatch` is an `IF` whose conditions all test one value for variants of one enum.
+///
+/// ```txt
+/// IF $c IS color::red THEN 'warm' ELSE IF $c == color::green OR $c IS color::blue THEN 'cool' END
+/// ```
+///
+/// An `IF` with `ELSE IF` parts that is a match must match every variant of the enum, or have an
+/// `ELSE`. One that does match every variant has a value even without an `ELSE`.
+pub(crate) struct VariantMatch {
+    /// The name of the enum.
+    pub(crate) r#enum: String,
+
+    /// The variants the conditions test for, in the order they are tested.
+    pub(crate) variants: Vec<String>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl MemberResolver {
+    /// Creates a new member resolution pass.
+    pub fn new() -> Self {
+        Self {
+            schema: SchemaTracker::default(),
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Sets how deeply nodes may nest before resolution fails.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the schema items that are already persisted by the database, so accesses of the
+    /// fields of their records and of their variants are resolved too.
+    pub fn with_db_schema(mut self, db_schema: DatabaseSchema) -> Self {
+        self.schema = SchemaTracker::new(db_schema);
+        self
+    }
+
+    /// Runs member resolution on the given ast.
+    #[inline]
+    pub fn analyze(&mut self, ast: &Ast) -> SemaResult<()> {
+        self.visit_ast(ast)
+    }
+
+    /// Resolves the members accessed by a single node, then the nodes inside it.
+    fn resolve_node(&mut self, ast: &Ast) -> SemaResult<()> {
+        self.schema.track(ast)?;
+
+        match &ast.kind {
+            // == Matches ==
+            If {
+                condition,
+                else_ifs,
+                r#else: None,
+                ..
+            } if !else_ifs.is_empty() => {
+                // Unknown variants are reported before the variants a match leaves out.
+                walk_ast(self, ast)?;
+
+                let conditions = std::iter::once(&**condition)
+                    .chain(else_ifs.iter().map(|part| &*part.condition))
+                    .collect::<Vec<_>>();
+                if let Some(variant_match) =
+                    VariantMatch::from_conditions(&conditions, &self.schema)
+                {
+                    let missing = variant_match.missing(&self.schema);
+                    if !missing.is_empty() {
+                        return Err(SemaError::NonExhaustiveMatch {
+                            r#enum: variant_match.r#enum,
+                            missing,
+                            span: ast.span.clone(),
+                        });
+                    }
+                }
+
+                return Ok(());
+            }
+
+            // == Member Accesses ==
+            DotAccessOp { subject, field } => match self.enum_name(subject) {
+                Some(name) => self.resolve_variant(name, field)?,
+                None => self.resolve_field(&type_of(subject), field, &ast.span)?,
+            },
+            SafeNavigationAccessOp { subject, field } => {
+                let subject = match type_of(subject) {
+                    Type::Option(inner) => *inner,
+                    subject => subject,
+                };
+
+                self.resolve_field(&subject, field, &ast.span)?;
+            }
+            DotAccessWildcardOp { subject } => {
+                let subject = match type_of(subject) {
+                    Type::Option(inner) => *inner,
+                    subject => subject,
+                };
+
+                if !self.has_fields(element_of(&subject)) {
+                    return Err(SemaError::NoFields {
+                        r#type: subject,
+                        span: ast.span.clone(),
+                    });
+                }
+            }
+            Index { subject, index } if matches!(index.kind, StringLiteral(_)) => {
+                let subject = type_of(subject);
+                if matches!(subject, Type::Object(_) | Type::Named(_)) {
+                    self.resolve_field(&subject, index, &ast.span)?;
+                }
+            }
+            ScopedIdentifier(parts) => {
+                if let [Ast {
+                    kind: Identifier(name),
+                    ..
+                }, variant] = parts.as_slice()
+                {
+                    if self.schema.is_enum(name) {
+                        self.resolve_variant(name, variant)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        walk_ast(self, ast)
+    }
+
+    /// Resolves a field of a value of the given type.
+    fn resolve_field(&self, subject: &Type, field: &Ast, span: &Span) -> SemaResult<()> {
+        let name = match &field.kind {
+            Identifier(name) | StringLiteral(name) => *name,
+            _ => return Ok(()),
+        };
+
+        let fields = match subject {
+            Type::Option(_) => {
+                return Err(SemaError::OptionalFieldAccess {
+                    field: name.to_string(),
+                    r#type: subject.clone(),
+                    span: span.clone(),
+                })
+            }
+            Type::List(element) | Type::Array(element, _) => {
+                return self.resolve_field(element, field, span)
+            }
+            Type::Named(table) => match self.schema.object_type(table) {
+                Some(Type::Object(fields)) => fields,
+                _ if self.schema.is_enum(table) => {
+                    return Err(SemaError::NoFields {
+                        r#type: subject.clone(),
+                        span: span.clone(),
+                    })
+                }
+                _ => return Ok(()),
+            },
+            Type::Object(fields) => fields.clone(),
+            Type::Any | Type::Generic(..) | Type::Tuple(_) => return Ok(()),
+            _ => {
+                return Err(SemaError::NoFields {
+                    r#type: subject.clone(),
+                    span: span.clone(),
+                })
+            }
+        };
+
+        let mode = match subject {
+            Type::Named(table) => self.schema.mode(table),
+            _ => None,
+        };
+
+        check_field(subject, &fields, mode.as_ref(), name, &field.span)
+    }
+
+    /// Resolves a variant of the enum with the given name.
+    fn resolve_variant(&self, name: &str, variant: &Ast) -> SemaResult<()> {
+        let Identifier(variant_name) = variant.kind else {
+            return Ok(());
+        };
+
+        let variants = self.schema.variants(name).unwrap_or_default();
+        if variants.iter().any(|variant| variant == variant_name) {
+            return Ok(());
+        }
+
+        Err(SemaError::UnknownVariant {
+            variant: variant_name.to_string(),
+            r#enum: name.to_string(),
+            suggestion: suggest(variant_name, variants.iter().map(String::as_str)),
+            span: variant.span.clone(),
+        })
+    }
+
+    /// Gets the name of the enum the subject of a `.` access names, if it names one.
+    ///
+    /// A name that is also a field of the current table has a known type and so is not taken for
+    /// an enum.
+    fn enum_name<'s>(&self, subject: &Ast<'s>) -> Option<&'s str> {
+        match subject.kind {
+            Identifier(name)
+                if self.schema.is_enum(name) && matches!(type_of(subject), Type::Any) =>
+            {
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether values of the given type may have fields.
+    fn has_fields(&self, r#type: &Type) -> bool {
+        match r#type {
+            Type::Named(name) => !self.schema.is_enum(name),
+            Type::Object(_) | Type::Any | Type::Generic(..) | Type::Tuple(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl VariantMatch {
+    /// Gets the match the conditions of an `IF` make, if they make one.
+    pub(crate) fn from_conditions(conditions: &[&Ast], schema: &SchemaTracker) -> Option<Self> {
+        let mut tested = None;
+        let mut r#enum = None;
+        let mut variants = vec![];
+        for condition in conditions {
+            if !variant_tests(condition, schema, &mut tested, &mut r#enum, &mut variants) {
+                return None;
+            }
+        }
+
+        Some(Self {
+            r#enum: r#enum?,
+            variants,
+        })
+    }
+
+    /// Gets the variants of the enum that the match leaves out, in declaration order.
+    pub(crate) fn missing(&self, schema: &SchemaTracker) -> Vec<String> {
+        schema
+            .variants(&self.r#enum)
+            .into_iter()
+            .flatten()
+            .filter(|variant| !self.variants.contains(variant))
+            .cloned()
+            .collect()
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Collects the variants a condition tests for, e.g. `$c IS color::red OR $c == color::green`.
+///
+/// Every test must be of the same value, written the same way, against variants of the same enum.
+/// Returns `false` if the condition is not made of such tests.
+fn variant_tests(
+    condition: &Ast,
+    schema: &SchemaTracker,
+    tested: &mut Option<String>,
+    r#enum: &mut Option<String>,
+    variants: &mut Vec<String>,
+) -> bool {
+    let (left, right) = match &condition.kind {
+        LogicalOrOp(left, right) => {
+            return variant_tests(left, schema, tested, r#enum, variants)
+                && variant_tests(right, schema, tested, r#enum, variants)
+        }
+        IsOp(left, right) | EqualToOp(left, right) => (left, right),
+        _ => return false,
+    };
+
+    let (value, (name, variant)) = match (variant_of(left, schema), variant_of(right, schema)) {
+        (None, Some(variant)) => (left, variant),
+        (Some(variant), None) => (right, variant),
+        _ => return false,
+    };
+
+    let value = Formatter::new(FormatOptions::default())
+        .format(value)
+        .trim_end()
+        .to_string();
+    if *tested.get_or_insert_with(|| value.clone()) != value
+        || *r#enum.get_or_insert_with(|| name.to_string()) != name
+    {
+        return false;
+    }
+
+    variants.push(variant.to_string());
+    true
+}
+
+/// Gets the enum and variant a node like `color::red` names, if it names a variant of a known enum.
+fn variant_of<'s>(ast: &Ast<'s>, schema: &SchemaTracker) -> Option<(&'s str, &'s str)> {
+    let ScopedIdentifier(parts) = &ast.kind else {
+        return None;
+    };
+
+    match parts.as_slice() {
+        [Ast {
+            kind: Identifier(name),
+            ..
+        }, Ast {
+            kind: Identifier(variant),
+            ..
+        }] if schema.is_enum(name) => Some((name, variant)),
+        _ => None,
+    }
+}
+
+/// Gets the inferred type of a node, or [`Type::Any`] if it has none.
+pub(super) fn type_of(ast: &Ast) -> Type {
+    ast.get_type().cloned().unwrap_or_default()
+}
+
+/// Gets the type of the elements of lists and arrays, and any other type as it is.
+fn element_of(r#type: &Type) -> &Type {
+    match r#type {
+        Type::List(element) | Type::Array(element, _) => element_of(element),
+        r#type => r#type,
+    }
+}
+
+/// Checks if a value of the given type has a field, given the fields its type declares and, for
+/// tables, the mode of the table.
+///
+/// Schemaless and flexible tables take fields they do not declare, while schemafull tables, edges,
+/// types and objects only have the fields they declare.
+pub(crate) fn check_field(
+    r#type: &Type,
+    fields: &[(String, Type)],
+    mode: Option<&TableModeMeta>,
+    name: &str,
+    span: &Span,
+) -> SemaResult<()> {
+    if fields.iter().any(|(field, _)| field == name)
+        || mode.is_some_and(|mode| mode.catch_all().is_some())
+    {
+        return Ok(());
+    }
+
+    Err(SemaError::UnknownField {
+        field: name.to_string(),
+        r#type: r#type.clone(),
+        suggestion: suggest(name, fields.iter().map(|(field, _)| field.as_str())),
+        span: span.clone(),
+    })
+}
+
+/// Gets the candidate closest to the given name, if it is close enough to be a likely typo.
+pub(crate) fn suggest<'c>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'c str>,
+) -> Option<String> {
+    let threshold = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Gets the edit distance between two strings, counting swapped adjacent characters as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j - 1] + cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Default for MemberResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Visitor<'a> for MemberResolver {
+    type Error = SemaError;
+
+    fn visit_ast(&mut self, ast: &Ast<'a>) -> SemaResult<()> {
+        if self.depth >= self.max_depth {
+            return Err(SemaError::NestingTooDeep {
+                max_depth: self.max_depth,
+                span: ast.span.clone(),
+            });
+        }
+
+        self.depth += 1;
+        let result = stack::ensure_stack(|| self.resolve_node(ast));
+        self.depth -= 1;
+
+        result
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zeroutils_path::Path;
+
+    use crate::{
+        ast::AstKind,
+        parser::Parser,
+        sema::{FieldMeta, SchemaMeta, SemanticAnalyzer},
+    };
+
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        DEFINE TYPE coords FIELDS lat TYPE f64, lng TYPE f64;
+        DEFINE TYPE address FIELDS street TYPE string, coords TYPE coords;
+        DEFINE ENUM color VARIANTS red, green, blue;
+        DEFINE TABLE person FIELDS name TYPE string, main_address TYPE address, other_address TYPE address?, addresses TYPE [address], favorite TYPE color;
+        DEFINE TABLE pet FIELDS owner TYPE person;
+        DEFINE TABLE document SCHEMALESS FIELDS title TYPE string;
+        DEFINE TABLE event FLEXIBLE TYPE string FIELDS starts TYPE u64;
+    "#;
+
+    /// Gets a persisted param at the given path, which stands for a value of any type.
+    fn any_param(path: &str) -> anyhow::Result<(Path, SchemaMeta)> {
+        let value = Ast::new(0..4, AstKind::NoneLiteral);
+        Ok((path.parse()?, SchemaMeta::param(None, &value)?))
+    }
+
+    fn resolve(source: &str) -> SemaResult<()> {
+        let source = format!("{SCHEMA}\n{source}");
+        let mut ast = Parser::new(&source, 50)
+            .parse_program()
+            .into_result()
+            .expect("program should parse");
+        SemanticAnalyzer::new(&mut ast)
+            .with_db_schema(DatabaseSchema::new(HashMap::from([
+                any_param("x").expect("param should be valid")
+            ])))
+            .analyze()
+    }
+
+    #[test]
+    fn test_sema_member_resolution_fields() -> anyhow::Result<()> {
+        resolve("LET $p TYPE person = $x; LET $c = $p.main_address.coords.lat;")?;
+        resolve("SELECT main_address.coords, addresses[0].coords.lat FROM person;")?;
+        resolve("LET $p TYPE person = $x; LET $c = $p.addresses.coords;")?;
+        resolve("SELECT main_address['street'] FROM person;")?;
+        resolve("SELECT main_address.* FROM person WHERE name == 'Ann';")?;
+        resolve("LET $p TYPE pet = $x; LET $o = $p.owner.main_address.*;")?;
+        resolve("LET $v = $x.anything.goes;")?;
+
+        let result = resolve("LET $p TYPE person = $x; LET $c = $p.main_adress;");
+        assert!(matches!(
+            result,
+            Err(SemaError::UnknownField { field, suggestion: Some(suggestion), .. })
+                if field == "main_adress" && suggestion == "main_address"
+        ));
+
+        let result = resolve("SELECT addresses[0].coord.lat FROM person;");
+        assert!(matches!(
+            result,
+            Err(SemaError::UnknownField { field, suggestion: Some(suggestion), .. })
+                if field == "coord" && suggestion == "coords"
+        ));
+
+        let result = resolve("SELECT main_address['zip'] FROM person;");
+        assert!(matches!(
+            result,
+            Err(SemaError::UnknownField { field, suggestion: None, .. }) if field == "zip"
+        ));
+
+        let result = resolve("LET $p TYPE person = $x; LET $c = $p.name.first;");
+        assert!(matches!(
+            result,
+            Err(SemaError::NoFields {
+                r#type: Type::String,
+                ..
+            })
+        ));
+
+        let result = resolve("LET $p TYPE person = $x; LET $c = $p.name.*;");
+        assert!(matches!(
+            result,
+            Err(SemaError::NoFields {
+                r#type: Type::String,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_member_resolution_optional_fields() -> anyhow::Result<()> {
+        resolve("LET $p TYPE person = $x; LET $s = $p.other_address?.street;")?;
+        resolve("LET $p TYPE person = $x; LET $l = $p.other_address?.coords?.lat;")?;
+
+        let result = resolve("LET $p TYPE person = $x; LET $l = $p.other_address?.coords.lat;");
+        assert!(matches!(
+            result,
+            Err(SemaError::OptionalFieldAccess { field, .. }) if field == "lat"
+        ));
+
+        let result = resolve("LET $p TYPE person = $x; LET $s = $p.other_address.street;");
+        assert!(matches!(
+            result,
+            Err(SemaError::OptionalFieldAccess { field, r#type: Type::Option(_), .. })
+                if field == "street"
+        ));
+
+        let result = resolve("LET $p TYPE person? = $x; LET $s = $p.name;");
+        assert!(matches!(
+            result,
+            Err(SemaError::OptionalFieldAccess { field, .. }) if field == "name"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_member_resolution_table_modes() -> anyhow::Result<()> {
+        // Schemaless tables take any field, and flexible ones take fields of their catch-all type.
+        resolve("LET $d TYPE document = $x; LET $a = $d.author.name;")?;
+        resolve("LET $e TYPE event = $x; LET $t = $e.tag;")?;
+
+        let result = resolve("LET $e TYPE event = $x; LET $t = $e.tag.name;");
+        assert!(
+            matches!(result, Err(SemaError::NoFields { r#type, .. }) if r#type == Type::String)
+        );
+
+        let result = resolve("LET $p TYPE pet = $x; LET $t = $p.tag;");
+        assert!(matches!(
+            result,
+            Err(SemaError::UnknownField { field, .. }) if field == "tag"
+        ));
+
+        // Renaming a table keeps its mode.
+        resolve("REDEFINE TABLE document AS doc; LET $d TYPE doc = $x; LET $a = $d.author;")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_member_resolution_enum_variants() -> anyhow::Result<()> {
+        resolve("LET $c = color::red;")?;
+        resolve("LET $c = color.blue;")?;
+
+        let result = resolve("LET $c = color::gren;");
+        assert!(matches!(
+            result,
+            Err(SemaError::UnknownVariant { variant, r#enum, suggestion: Some(suggestion), .. })
+                if variant == "gren" && r#enum == "color" && suggestion == "green"
+        ));
+
+        let result = resolve("LET $c = color.purple;");
+        assert!(matches!(
+            result,
+            Err(SemaError::UnknownVariant { variant, suggestion: None, .. }) if variant == "purple"
+        ));
+
+        let result = resolve("LET $p TYPE person = $x; LET $c = $p.favorite.red;");
+        assert!(matches!(result, Err(SemaError::NoFields { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_member_resolution_variant_matches() -> anyhow::Result<()> {
+        resolve(
+            "LET $c = color::red; IF $c IS color::red THEN 1 ELSE IF $c == color::green OR $c IS color::blue THEN 2 END;",
+        )?;
+        resolve("LET $c = color::red; IF $c IS color::red THEN 1 ELSE IF $c IS color::green THEN 2 ELSE 3 END;")?;
+        resolve("LET $c = color::red; IF $c IS color::red THEN 1 END;")?;
+        resolve("LET $c = color::red; IF $c IS color::red THEN 1 ELSE IF $x > 1 THEN 2 END;")?;
+
+        let result = resolve(
+            "LET $c = color::red; IF $c IS color::red THEN 1 ELSE IF $c IS color::green THEN 2 END;",
+        );
+        assert!(matches!(
+            result,
+            Err(SemaError::NonExhaustiveMatch { r#enum, missing, .. })
+                if r#enum == "color" && missing == ["blue"]
+        ));
+
+        // Unknown variants are reported before the match is checked.
+        let result = resolve(
+            "LET $c = color::red; IF $c IS color::red THEN 1 ELSE IF $c IS color::purple THEN 2 END;",
+        );
+        assert!(matches!(
+            result,
+            Err(SemaError::UnknownVariant { variant, .. }) if variant == "purple"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_member_resolution_redefinitions() -> anyhow::Result<()> {
+        resolve("REDEFINE TABLE person AS people FIELDS name AS full_name; SELECT full_name FROM people;")?;
+        resolve(
+            "REDEFINE TYPE coords FIELDS alt TYPE f64; LET $c TYPE coords = $x; LET $a = $c.alt;",
+        )?;
+        resolve("REDEFINE ENUM color AS colour VARIANTS red AS crimson, green, blue; LET $c = colour::crimson;")?;
+
+        let result =
+            resolve("REDEFINE TABLE person FIELDS name AS full_name; LET $p TYPE person = $x; LET $n = $p.name;");
+        assert!(matches!(
+            result,
+            Err(SemaError::UnknownField { field, .. }) if field == "name"
+        ));
+
+        let result =
+            resolve("REDEFINE ENUM color VARIANTS red, green, blue AS navy; LET $c = color::blue;");
+        assert!(matches!(
+            result,
+            Err(SemaError::UnknownVariant { variant, .. }) if variant == "blue"
+        ));
+
+        // Variants keep their positions, as stored values are the positions of their variants.
+        let result = resolve("REDEFINE ENUM color VARIANTS green, red, blue;");
+        assert!(matches!(
+            result,
+            Err(SemaError::MovedVariant { variant, .. }) if variant == "red"
+        ));
+        let result = resolve("REDEFINE ENUM color VARIANTS red, green;");
+        assert!(matches!(
+            result,
+            Err(SemaError::MovedVariant { variant, .. }) if variant == "blue"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_member_resolution_suggestions() {
+        assert_eq!(suggest("nmae", ["name", "age"]), Some("name".to_string()));
+        assert_eq!(suggest("x", ["name", "age"]), None);
+        assert_eq!(suggest("ag", ["name", "age"]), Some("age".to_string()));
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("nmae", "name"), 1);
+    }
+
+    #[test]
+    fn test_sema_member_resolution_db_schema() -> anyhow::Result<()> {
+        let db_schema = DatabaseSchema::new(HashMap::from([
+            ("shop".parse::<Path>()?, SchemaMeta::Database()),
+            (
+                "shop/person".parse()?,
+                SchemaMeta::Table {
+                    mode: TableModeMeta::Schemafull,
+                    fields: vec![FieldMeta {
+                        r#type: Type::String,
+                        ..FieldMeta::new("name")
+                    }],
+                },
+            ),
+            (
+                "shop/document".parse()?,
+                SchemaMeta::Table {
+                    mode: TableModeMeta::Schemaless,
+                    fields: vec![],
+                },
+            ),
+            (
+                "color".parse()?,
+                SchemaMeta::Enum {
+                    variants: vec!["red".into(), "green".into()],
+                },
+            ),
+            any_param("x")?,
+            any_param("shop/x")?,
+        ]));
+        let resolve = |source: &str| -> anyhow::Result<SemaResult<()>> {
+            let mut ast = Parser::new(source, 50).parse_program().into_result()?;
+            Ok(SemanticAnalyzer::new(&mut ast)
+                .with_db_schema(db_schema.clone())
+                .analyze())
+        };
+
+        // The fields of persisted tables and the variants of persisted enums are known.
+        resolve("USE DB shop; LET $p TYPE person = $x; LET $n = $p.name;")??;
+        resolve("LET $c = color::green;")??;
+        resolve("USE DB shop; LET $d TYPE document = $x; LET $t = $d.title;")??;
+
+        let result = resolve("USE DB shop; LET $p TYPE person = $x; LET $n = $p.nme;")?;
+        assert!(matches!(
+            result,
+            Err(SemaError::UnknownField { field, .. }) if field == "nme"
+        ));
+
+        let result = resolve("LET $c = color::purple;")?;
+        assert!(matches!(result, Err(SemaError::UnknownVariant { .. })));
+
+        Ok(())
+    }
+}
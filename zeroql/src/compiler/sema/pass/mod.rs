@@ -1,5 +1,6 @@
 mod member_resolution;
 mod name_resolution;
+mod schema_tracking;
 mod type_checking;
 mod type_inference;
 
@@ -1,11 +1,18 @@
 use crate::{
-    ast::{Ast, AstKind::*, ElseIfPart, SelectColumn, SelectTransform, TypeSig},
+    ast::{
+        walk_ast_mut, Ast, AstKind::*, ElseIfPart, RedefineField, SelectColumn, SelectTransform,
+        TableMode, TypeSig, VisitorMut,
+    },
+    compiler::stack,
     sema::{
-        error::SemaResult, symbols::Symbols, DatabaseSchema, SchemaMeta, SemaError, SymbolMeta,
-        VersionedSchema,
+        error::SemaResult, symbols::Symbols, DatabaseSchema, FunctionRegistry, SchemaMeta,
+        SemaError, SymbolMeta, Type, VersionedSchema, DEFAULT_MAX_DEPTH,
     },
+    Span,
 };
 
+use super::{member_resolution::check_field, type_checking::table_name};
+
 //--------------------------------------------------------------------------------------------------
 // Types
 //--------------------------------------------------------------------------------------------------
@@ -17,12 +24,15 @@ use crate::{
 /// shadowed.
 ///
 /// In this context, "schema" refers to top-level structures that can be stored within a database.
-/// This includes tables, edges, types, enums, indexes, modules, parameters and databases themselves.
+/// This includes tables, edges, types, enums, indexes, modules, parameters, functions and databases
+/// themselves.
 ///
 /// ## Note
 ///
 /// This pass does not analyze names of members of structs, enums, or tables. It only concerns itself
-/// with names of top-level schema items.
+/// with names of top-level schema items, and with the bare identifiers of `SELECT`, `CREATE`,
+/// `UPDATE` and `DELETE`, which name fields of the table of the statement. Schemafull tables only
+/// have the fields they declare.
 ///
 /// Variable shadowing is permitted, allowing variables to be redefined within the same and nested
 /// scopes. However, schema definitions must remain unique.
@@ -51,6 +61,21 @@ pub struct NameResolver {
 
     /// Used to get information about the schemas that are already persisted by the database.
     db_schema: DatabaseSchema,
+
+    /// The signatures of the functions that can be called.
+    functions: FunctionRegistry,
+
+    /// How deeply the node being analyzed is nested.
+    depth: usize,
+
+    /// How deeply nodes may nest before analysis fails.
+    max_depth: usize,
+
+    /// Whether the next node is checked for schema item existence if it is an identifier.
+    analyze_identifier: bool,
+
+    /// The table bare identifiers refer to the fields of, e.g. in the `WHERE` guard of an `UPDATE`.
+    current_table: Option<String>,
 }
 
 //--------------------------------------------------------------------------------------------------
@@ -67,25 +92,61 @@ impl NameResolver {
             has_untagged_symbols: true,
             current_database: Path::default(),
             db_schema: DatabaseSchema::default(),
+            functions: FunctionRegistry::builtins(),
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            analyze_identifier: true,
+            current_table: None,
         }
     }
 
+    /// Sets how deeply nodes may nest before analysis fails.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the schema items that are already persisted by the database.
+    pub fn with_db_schema(mut self, db_schema: DatabaseSchema) -> Self {
+        self.db_schema = db_schema;
+        self
+    }
+
+    /// Sets the signatures of the functions that can be called, in place of the built-ins.
+    pub fn with_functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
     /// Runs name resolution analysis on the given ast.
     #[inline]
     pub fn analyze(&mut self, ast: &mut Ast) -> SemaResult<()> {
-        self.analyze_with_options(ast, true)
+        self.visit_ast(ast)
     }
 
-    /// Runs name resolution analysis on the given ast.
+    /// Runs name resolution analysis on the given ast without checking it for schema item
+    /// existence if it is an identifier.
     ///
-    /// If `analyze_identifier` is true, the identifier is analyzed for schema item existence.
     /// This is useful in statements like SELECT where we need to analyze sub-expressions
     /// in the column selection part but not the identifiers because they are not top-level
     /// schema items.
-    fn analyze_with_options(&mut self, ast: &mut Ast, analyze_identifiers: bool) -> SemaResult<()> {
+    fn analyze_without_identifier(&mut self, ast: &mut Ast) -> SemaResult<()> {
+        self.analyze_identifier = false;
+        self.visit_ast(ast)
+    }
+
+    /// Analyzes a single node, leaving the nodes it does not care about to the default walk.
+    fn analyze_node(&mut self, ast: &mut Ast, analyze_identifier: bool) -> SemaResult<()> {
         // Tag the schema to the ast node if any
         self.tag_schema(ast);
 
+        // Tag the symbols to the first statement of a program if any
+        if let Program(asts) = &mut ast.kind {
+            if let Some(ast) = asts.first_mut() {
+                self.tag_symbols(ast);
+            }
+        }
+
         match &mut ast.kind {
             // == Variable Declarations ==
             Let {
@@ -102,7 +163,7 @@ impl NameResolver {
 
                 // Analyze the type
                 if let Some(r#type) = r#type {
-                    self.analyze_type(r#type)?;
+                    self.visit_type_sig(r#type)?;
                 }
 
                 // Analyze the value
@@ -141,27 +202,29 @@ impl NameResolver {
             } => {
                 self.analyze_scope(|r| r.analyze(condition), |r| r.analyze(then))?;
 
-                for ElseIfPart { condition, body } in else_ifs {
-                    self.analyze_scope(|r| r.analyze(condition), |r| r.analyze(body))?;
+                for else_if in else_ifs {
+                    self.visit_else_if_part(else_if)?;
                 }
 
                 if let Some(r#else) = r#else {
                     self.analyze_scope(|_| Ok(()), |r| r.analyze(r#else))?;
                 }
             }
-            Program(asts) => {
-                // Tag the symbols to the ast node if any
-                if let Some(ast) = asts.first_mut() {
-                    self.tag_symbols(ast);
-                }
-
-                // Analyze the asts
-                for ast in asts {
-                    self.analyze(ast)?;
-                }
-            }
 
             // == Schema Definitions ==
+            DefineNamespace {
+                name,
+                if_not_exists,
+            } => {
+                let namespace_name = ast_as!(**name, Identifier(name));
+                let namespace_path = parse_path(namespace_name, &name.span)?;
+                self.register_schema_path_definition(
+                    namespace_path,
+                    &name.span,
+                    *if_not_exists,
+                    SchemaMeta::Namespace(),
+                )?;
+            }
             DefineDatabase {
                 name,
                 if_not_exists,
@@ -179,108 +242,254 @@ impl NameResolver {
                 name,
                 if_not_exists,
                 database,
-                ..
+                mode,
+                fields,
             } => {
-                self.register_schema_item_definition(
-                    name,
-                    database,
-                    *if_not_exists,
-                    SchemaMeta::Table(),
-                )?;
+                let meta = SchemaMeta::table(mode, fields)?;
+                self.register_schema_item_definition(name, database, *if_not_exists, meta)?;
+
+                // Fields may have the type of the table itself, so they come after it.
+                if let TableMode::Flexible(Some(r#type)) = mode {
+                    self.visit_type_sig(r#type)?;
+                }
+
+                for field in fields {
+                    self.visit_type_sig(&mut field.r#type)?;
+                }
             }
             DefineEdge {
                 name,
                 if_not_exists,
                 database,
+                fields,
                 ..
             } => {
-                self.register_schema_item_definition(
-                    name,
-                    database,
-                    *if_not_exists,
-                    SchemaMeta::Edge(),
-                )?;
+                let meta = SchemaMeta::edge(fields)?;
+                self.register_schema_item_definition(name, database, *if_not_exists, meta)?;
+
+                for field in fields {
+                    self.visit_type_sig(&mut field.r#type)?;
+                }
             }
             DefineType {
                 name,
                 if_not_exists,
                 database,
+                fields,
                 ..
             } => {
-                self.register_schema_item_definition(
-                    name,
-                    database,
-                    *if_not_exists,
-                    SchemaMeta::Type(),
-                )?;
+                let meta = SchemaMeta::r#type(fields)?;
+                self.register_schema_item_definition(name, database, *if_not_exists, meta)?;
+
+                for (_, r#type) in fields {
+                    self.visit_type_sig(r#type)?;
+                }
             }
             DefineEnum {
                 name,
                 if_not_exists,
                 database,
+                variants,
                 ..
             } => {
-                self.register_schema_item_definition(
-                    name,
-                    database,
-                    *if_not_exists,
-                    SchemaMeta::Enum(),
-                )?;
+                let meta = SchemaMeta::r#enum(variants)?;
+                self.register_schema_item_definition(name, database, *if_not_exists, meta)?;
             }
             DefineIndex {
                 name,
                 if_not_exists,
                 database,
+                table,
+                columns,
+                function,
                 ..
             } => {
-                self.register_schema_item_definition(
-                    name,
-                    database,
-                    *if_not_exists,
-                    SchemaMeta::Index(),
-                )?;
+                let meta = SchemaMeta::index(table, columns, function.as_deref())?;
+                self.register_schema_item_definition(name, database, *if_not_exists, meta)?;
             }
             DefineModule {
                 name,
                 if_not_exists,
                 database,
+                block,
                 ..
             } => {
-                self.register_schema_item_definition(
+                let meta = SchemaMeta::module(block)?;
+                self.register_schema_item_definition(name, database, *if_not_exists, meta)?;
+            }
+            DefineParam {
+                name,
+                if_not_exists,
+                database,
+                r#type,
+                value,
+            } => {
+                let meta = SchemaMeta::param(r#type.as_ref(), value)?;
+                self.register_schema_item_definition(name, database, *if_not_exists, meta)?;
+
+                if let Some(r#type) = r#type {
+                    self.visit_type_sig(r#type)?;
+                }
+            }
+            DefineFunction {
+                name,
+                if_not_exists,
+                database,
+                params,
+                r#type,
+                body,
+            } => {
+                let meta = SchemaMeta::function(params, r#type.as_ref(), body)?;
+                self.register_schema_item_definition(name, database, *if_not_exists, meta)?;
+
+                for (_, r#type) in params.iter_mut() {
+                    self.visit_type_sig(r#type)?;
+                }
+
+                if let Some(r#type) = r#type {
+                    self.visit_type_sig(r#type)?;
+                }
+
+                // The body only sees the params and the names outside the function.
+                self.analyze_scope(
+                    |_| Ok(()),
+                    |r| {
+                        for (param, _) in params.iter() {
+                            let name = ast_as!(*param, Variable(name));
+                            r.current_symbols
+                                .insert(name.to_string(), SymbolMeta::default());
+                        }
+
+                        r.analyze(body)
+                    },
+                )?;
+            }
+
+            // == Schema Redefinitions ==
+            RedefineTable {
+                name,
+                if_exists,
+                new_name,
+                database,
+                fields,
+            } => {
+                self.register_schema_item_redefinition(
                     name,
+                    new_name,
                     database,
-                    *if_not_exists,
-                    SchemaMeta::Module(),
+                    *if_exists,
+                    "table",
+                    |meta| meta.redefine_fields(fields),
                 )?;
+                self.analyze_redefined_field_types(fields)?;
             }
-            DefineParam {
+            RedefineEdge {
                 name,
-                if_not_exists,
+                if_exists,
+                new_name,
                 database,
+                fields,
+            } => {
+                self.register_schema_item_redefinition(
+                    name,
+                    new_name,
+                    database,
+                    *if_exists,
+                    "edge",
+                    |meta| meta.redefine_fields(fields),
+                )?;
+                self.analyze_redefined_field_types(fields)?;
+            }
+            RedefineType {
+                name,
+                if_exists,
+                new_name,
+                database,
+                fields,
+            } => {
+                self.register_schema_item_redefinition(
+                    name,
+                    new_name,
+                    database,
+                    *if_exists,
+                    "type",
+                    |meta| meta.redefine_fields(fields),
+                )?;
+                self.analyze_redefined_field_types(fields)?;
+            }
+            RedefineEnum {
+                name,
+                if_exists,
+                new_name,
+                database,
+                variants,
+            } => {
+                self.register_schema_item_redefinition(
+                    name,
+                    new_name,
+                    database,
+                    *if_exists,
+                    "enum",
+                    |meta| match variants {
+                        Some(variants) => meta.redefine_variants(variants),
+                        None => Ok(()),
+                    },
+                )?;
+            }
+            RedefineIndex {
+                name,
+                if_exists,
+                new_name,
+                database,
+                columns,
+                function,
                 ..
             } => {
-                self.register_schema_item_definition(
+                self.register_schema_item_redefinition(
                     name,
+                    new_name,
                     database,
-                    *if_not_exists,
-                    SchemaMeta::Param(),
+                    *if_exists,
+                    "index",
+                    |meta| meta.redefine_index(columns.as_deref(), function.as_deref()),
                 )?;
             }
+            RedefineParam {
+                name,
+                if_exists,
+                new_name,
+                database,
+                r#type,
+                value,
+            } => {
+                self.register_schema_item_redefinition(
+                    name,
+                    new_name,
+                    database,
+                    *if_exists,
+                    "param",
+                    |meta| meta.redefine_param(r#type.as_ref(), value.as_deref()),
+                )?;
+
+                if let Some(r#type) = r#type {
+                    self.visit_type_sig(r#type)?;
+                }
+
+                if let Some(value) = value {
+                    self.analyze(value)?;
+                }
+            }
 
             // == Usage ==
             Identifier(name) => {
-                if analyze_identifiers {
-                    // Check if the identifier is a schema item
-                    self.check_schema_item_exists(name, &None)?;
+                if analyze_identifier {
+                    // Check if the identifier is a field of the current table or a schema item
+                    self.check_field_or_schema_item_exists(name, &ast.span)?;
                 }
             }
             Variable(name) => {
                 // Check if the variable or param exists
-                self.check_variable_or_param_exists(name)?;
-            }
-            IdOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
+                self.check_variable_or_param_exists(name, &ast.span)?;
             }
             ScopedIdentifier(identifiers) => {
                 if let [ast] = &mut identifiers[..] {
@@ -288,214 +497,65 @@ impl NameResolver {
                 }
             }
 
-            ListLiteral(asts) => {
-                for ast in asts {
-                    self.analyze(ast)?;
-                }
-            }
-            TupleLiteral(asts) => {
-                for ast in asts {
-                    self.analyze(ast)?;
+            // Names of members, arguments, aliases and record ids are not top-level names.
+            IdOp(table, id) => {
+                // The table of a record id is a schema item, even where names are fields.
+                match table.kind {
+                    Identifier(name) => self.check_schema_item_exists(name, &None, &table.span)?,
+                    _ => self.analyze(table)?,
                 }
+                self.analyze_without_identifier(id)?;
             }
             ObjectLiteral(asts) => {
                 for (_, ast) in asts {
                     self.analyze(ast)?;
                 }
             }
-            Index { subject, index } => {
-                self.analyze(subject)?;
-                self.analyze(index)?;
-            }
             FunctionArg { value, .. } => {
                 self.analyze(value)?;
             }
             FunctionCall { subject, args } => {
-                self.analyze(subject)?;
+                // Function names are resolved against the function registry, not the schema.
+                match FunctionRegistry::name_of(subject) {
+                    Some(name) => self.check_function_exists(name, &subject.span)?,
+                    None => self.analyze(subject)?,
+                }
+
                 for arg in args {
                     self.analyze(arg)?;
                 }
             }
-            LogicalNotOp(ast) => {
-                self.analyze(ast)?;
-            }
-            BitwiseNotOp(ast) => {
-                self.analyze(ast)?;
-            }
-            PlusSignOp(ast) => {
-                self.analyze(ast)?;
-            }
-            MinusSignOp(ast) => {
-                self.analyze(ast)?;
-            }
             DotAccessOp { subject, .. } => {
-                self.analyze(subject)?;
-            }
-            SafeNavigationAccessOp { subject, .. } => {
-                self.analyze(subject)?;
-            }
-            DotAccessWildcardOp { subject, .. } => {
-                self.analyze(subject)?;
-            }
-            ExponentiationOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            MultiplicationOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            DivisionOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            ModulusOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            AdditionOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            SubtractionOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            LeftShiftOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            RightShiftOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            MatchOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            NotMatchOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            SimilarityOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            LessThanOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            GreaterThanOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            LessThanEqualToOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            GreaterThanEqualToOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            InOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            NotInOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            ContainsOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            NotContainsOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            ContainsNoneOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            ContainsAllOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            ContainsAnyOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            EqualToOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            IsOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            IsNotOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            BitwiseAndOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            BitwiseXorOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            BitwiseOrOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            LogicalAndOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            LogicalOrOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            NullCoalesceOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            RangeOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            RangeInclusiveOp(a, b) => {
-                self.analyze(a)?;
-                self.analyze(b)?;
-            }
-            SingleRelateId { subject, .. } => {
-                self.analyze(subject)?;
-            }
-            RelateEdgeId { subject, .. } => {
-                self.analyze(subject)?;
-            }
-            RelateOp {
-                left, edge, right, ..
-            } => {
-                self.analyze(left)?;
-                self.analyze(edge)?;
-                self.analyze(right)?;
+                // Variants are accessed on the enum, even where names are fields.
+                match subject.kind {
+                    Identifier(name) if self.is_enum(name, &subject.span)? => {}
+                    _ => self.analyze(subject)?,
+                }
             }
-            AliasOp { subject, .. } => {
+            SafeNavigationAccessOp { subject, .. }
+            | SingleRelateId { subject, .. }
+            | RelateEdgeId { subject, .. }
+            | AliasOp { subject, .. } => {
                 self.analyze(subject)?;
             }
             Create {
-                subject, values, ..
+                subject,
+                columns,
+                values,
             } => {
                 // Analyze the subject.
                 self.analyze(subject)?;
 
+                // Analyze the columns
+                self.with_table(table_name(subject), |r| {
+                    columns
+                        .iter_mut()
+                        .try_for_each(|column| r.analyze_column(column))
+                })?;
+
                 // Analyze the values
-                for value in values {
-                    for ast in value {
-                        self.analyze(ast)?;
-                    }
+                for ast in values.iter_mut().flatten() {
+                    self.analyze(ast)?;
                 }
             }
             Relate {
@@ -509,34 +569,39 @@ impl NameResolver {
                     self.analyze(ast)?;
                 }
             }
-            Delete {
+            Update {
                 target,
                 where_guard,
+                column_ops,
             } => {
                 // Analyze the target
                 self.analyze(target)?;
 
-                // Analyze the where guard
-                if let Some(where_guard) = where_guard {
-                    self.analyze(where_guard)?;
-                }
+                self.with_table(table_name(target), |r| {
+                    // Analyze the where guard
+                    if let Some(where_guard) = where_guard {
+                        r.analyze(where_guard)?;
+                    }
+
+                    // Analyze the column ops
+                    for (column, _, value) in column_ops {
+                        r.analyze_column(column)?;
+                        r.analyze(value)?;
+                    }
+
+                    Ok(())
+                })?;
             }
-            Update {
+            Delete {
                 target,
                 where_guard,
-                column_ops,
             } => {
                 // Analyze the target
                 self.analyze(target)?;
 
                 // Analyze the where guard
                 if let Some(where_guard) = where_guard {
-                    self.analyze(where_guard)?;
-                }
-
-                // Analyze the column ops
-                for (_, _, value) in column_ops {
-                    self.analyze(value)?;
+                    self.with_table(table_name(target), |r| r.analyze(where_guard))?;
                 }
             }
             Select {
@@ -545,186 +610,121 @@ impl NameResolver {
                 from,
                 transforms,
             } => {
-                // Analyze the fields
-                for field in fields {
-                    match field {
-                        SelectColumn::Column(ast) => self.analyze_with_options(ast, false)?,
-                        SelectColumn::Fold { subject, .. } => {
-                            self.analyze_with_options(subject, false)?
-                        }
+                // Analyze the from, whose identifiers are never fields of an outer table
+                self.with_table(None, |r| from.iter_mut().try_for_each(|ast| r.analyze(ast)))?;
+
+                let table = match &from[..] {
+                    [from] => table_name(from),
+                    _ => None,
+                };
+
+                self.with_table(table, |r| {
+                    // Analyze the fields
+                    for field in fields {
+                        r.visit_select_column(field)?;
                     }
-                }
 
-                // Analyze the omit
-                for omit in omit {
-                    self.analyze_with_options(omit, false)?;
-                }
-
-                // Analyze the from
-                for ast in from {
-                    self.analyze(ast)?;
-                }
+                    // Analyze the omit
+                    for omit in omit {
+                        r.analyze_in_table(omit)?;
+                    }
 
-                // Analyze the transforms
-                for transform in transforms {
-                    match transform {
-                        SelectTransform::WhereGuard(ast) => {
-                            self.analyze_with_options(ast, false)?
-                        }
-                        SelectTransform::WithIndexes(asts) => {
-                            for ast in asts {
-                                self.analyze_with_options(ast, false)?
-                            }
-                        }
-                        SelectTransform::GroupBy(asts) => {
-                            for ast in asts {
-                                self.analyze_with_options(ast, false)?
-                            }
-                        }
-                        SelectTransform::LimitTo(ast) => self.analyze_with_options(ast, false)?,
-                        SelectTransform::StartAt(ast) => self.analyze_with_options(ast, false)?,
-                        SelectTransform::OrderBy { .. } | SelectTransform::WithNoIndex => {}
+                    // Analyze the transforms
+                    for transform in transforms {
+                        r.visit_select_transform(transform)?;
                     }
-                }
+
+                    Ok(())
+                })?;
             }
             RemoveDatabase {
                 subject,
                 if_exists,
-                namespace,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, namespace)?
-                }
+                namespace: database,
             }
-            RemoveTable {
+            | RemoveTable {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            RemoveEdge {
+            | RemoveEdge {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            RemoveType {
+            | RemoveType {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            RemoveEnum {
+            | RemoveEnum {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            RemoveIndex {
+            | RemoveIndex {
                 subject,
                 if_exists,
                 database,
                 ..
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            RemoveModule {
+            | RemoveModule {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            RemoveParam {
+            | RemoveParam {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            DescribeDatabase {
+            | RemoveFunction {
                 subject,
                 if_exists,
-                namespace,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, namespace)?
-                }
+                database,
+            }
+            | DescribeDatabase {
+                subject,
+                if_exists,
+                namespace: database,
             }
-            DescribeTable {
+            | DescribeTable {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            DescribeEdge {
+            | DescribeEdge {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            DescribeType {
+            | DescribeType {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            DescribeEnum {
+            | DescribeEnum {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            DescribeIndex {
+            | DescribeIndex {
                 subject,
                 if_exists,
                 database,
                 ..
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            DescribeModule {
+            | DescribeModule {
+                subject,
+                if_exists,
+                database,
+            }
+            | DescribeParam {
                 subject,
                 if_exists,
                 database,
-            } => {
-                if !*if_exists {
-                    self.analyze_schema_item(subject, database)?
-                }
             }
-            DescribeParam {
+            | DescribeFunction {
                 subject,
                 if_exists,
                 database,
@@ -738,46 +738,27 @@ impl NameResolver {
             } => {
                 // Check if the variable exists
                 let var = ast_as!(**variable, Variable(name));
-                self.check_variable_or_param_exists(var)?;
+                self.check_variable_or_param_exists(var, &variable.span)?;
 
                 // Analyze the value
                 self.analyze(value)?;
             }
             Use { database, .. } => {
                 // Check if the database actually exists
-                let database_path = ast_as!(**database, Identifier(name)).parse()?;
-                let db_not_exists = !self.current_schema.as_ref().map_or(false, |s| {
-                    matches!(s.get(&database_path), Some(SchemaMeta::Database()))
-                }) && !matches!(
-                    self.db_schema.get(&database_path),
-                    Some(SchemaMeta::Database())
-                );
-
-                if db_not_exists {
-                    return Err(SemaError::UndefinedDatabase(database_path));
+                let database_name = ast_as!(**database, Identifier(name));
+                let database_path = parse_path(database_name, &database.span)?;
+                if self.get_schema_item(&database_path, "database").is_none() {
+                    return Err(SemaError::UndefinedDatabase {
+                        path: database_path,
+                        span: database.span.clone(),
+                    });
                 }
 
                 // Set the current database
                 self.current_database = database_path;
             }
-            Wildcard
-            | Temp(_)
-            | IntegerLiteral(_)
-            | FloatLiteral(_)
-            | StringLiteral(_)
-            | ByteStringLiteral(_)
-            | RegexLiteral { .. }
-            | BooleanLiteral(_)
-            | ModuleBlock(_)
-            | NoneLiteral
-            | Continue
-            | DescribeNamespace { .. }
-            | DefineNamespace { .. }
-            | RemoveNamespace { .. }
-            | BeginTransaction
-            | CommitTransaction
-            | CancelTransaction
-            | Break => {}
+            DescribeNamespace { .. } | RemoveNamespace { .. } => {}
+            _ => return walk_ast_mut(self, ast),
         }
 
         Ok(())
@@ -826,45 +807,166 @@ impl NameResolver {
         Ok(())
     }
 
+    /// Analyzes with bare identifiers referring to the fields of the given table.
+    fn with_table(
+        &mut self,
+        table: Option<String>,
+        f: impl FnOnce(&mut Self) -> SemaResult<()>,
+    ) -> SemaResult<()> {
+        let outer = std::mem::replace(&mut self.current_table, table);
+        let result = f(self);
+        self.current_table = outer;
+
+        result
+    }
+
+    /// Analyzes a part of a `SELECT` whose bare identifiers are fields of the table selected from,
+    /// if there is one.
+    fn analyze_in_table(&mut self, ast: &mut Ast) -> SemaResult<()> {
+        match self.current_table {
+            Some(_) => self.analyze(ast),
+            None => self.analyze_without_identifier(ast),
+        }
+    }
+
+    /// Analyzes a column set by an `UPDATE` or `CREATE`, which must be a field of the table.
+    fn analyze_column(&mut self, column: &mut Ast) -> SemaResult<()> {
+        match (&self.current_table, &column.kind) {
+            (None, _) => Ok(()),
+            (Some(table), Identifier(name)) => self.check_table_field(table, name, &column.span),
+            (Some(_), _) => self.analyze(column),
+        }
+    }
+
+    /// Checks if a name is a field of the current table inside of one, or a schema item outside of
+    /// one.
+    fn check_field_or_schema_item_exists(&self, name: &str, span: &Span) -> SemaResult<()> {
+        match &self.current_table {
+            Some(table) => self.check_table_field(table, name, span),
+            None => self.check_schema_item_exists(name, &None, span),
+        }
+    }
+
+    /// Checks if a name is a field of a table or edge.
+    ///
+    /// Schemaless and flexible tables take any name as a field, while schemafull tables and edges
+    /// only take the fields they declare. Tables that are not defined are reported where they are
+    /// named, so any name is taken as one of their fields.
+    fn check_table_field(&self, table: &str, name: &str, span: &Span) -> SemaResult<()> {
+        let table_path = self.create_schema_item_path(table, &None, span)?;
+        let Some(meta) = self
+            .get_schema_item(&table_path, "table")
+            .or_else(|| self.get_schema_item(&table_path, "edge"))
+        else {
+            return Ok(());
+        };
+
+        let fields = match meta.object_type() {
+            Some(Type::Object(fields)) => fields,
+            _ => vec![],
+        };
+
+        check_field(
+            &Type::Named(table.to_string()),
+            &fields,
+            Some(&meta.mode()),
+            name,
+            span,
+        )
+    }
+
+    /// Whether a name is the name of an enum, like `color` in `color.red`.
+    fn is_enum(&self, name: &str, span: &Span) -> SemaResult<bool> {
+        let path = self.create_schema_item_path(name, &None, span)?;
+        Ok(self.get_schema_item(&path, "enum").is_some())
+    }
+
+    /// Analyzes the new types of the fields of a `REDEFINE TABLE`, `REDEFINE EDGE` or
+    /// `REDEFINE TYPE`.
+    fn analyze_redefined_field_types(&mut self, fields: &mut [RedefineField]) -> SemaResult<()> {
+        fields
+            .iter_mut()
+            .filter_map(|field| field.r#type.as_mut())
+            .try_for_each(|r#type| self.visit_type_sig(r#type))
+    }
+
+    /// Checks if a named type, like `person` in `TYPE [person]`, is a table, edge, type or enum.
+    fn check_named_type_exists(&self, name: &str, span: &Span) -> SemaResult<()> {
+        let path = self.create_schema_item_path(name, &None, span)?;
+        if ["table", "edge", "type", "enum"]
+            .iter()
+            .all(|kind| self.get_schema_item(&path, kind).is_none())
+        {
+            return Err(SemaError::UndefinedSchemaItem {
+                path,
+                span: span.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
     fn analyze_schema_item(&self, name: &Ast, in_database: &Option<Box<Ast>>) -> SemaResult<()> {
         let schema_name = ast_as!(name, Identifier(name));
-        self.check_schema_item_exists(schema_name, in_database)
+        self.check_schema_item_exists(schema_name, in_database, &name.span)
     }
 
     fn check_schema_item_exists(
         &self,
         name: &str,
         in_database: &Option<Box<Ast>>,
+        span: &Span,
     ) -> SemaResult<()> {
         // Construct schema item path.
-        let item_path = self.create_schema_item_path(name, in_database)?;
+        let item_path = self.create_schema_item_path(name, in_database, span)?;
 
         // Check if the schema item exists in the current schema or database schema
-        let item_not_exists =
-            !self.current_schema.as_ref().map_or(false, |s| {
-                matches!(s.get(&item_path), Some(SchemaMeta::Database()))
-            }) && !matches!(self.db_schema.get(&item_path), Some(SchemaMeta::Database()));
-
-        if item_not_exists {
-            return Err(SemaError::UndefinedSchemaItem(item_path));
+        let item_exists = match self.current_schema.as_ref().and_then(|s| s.get(&item_path)) {
+            Some(meta) => !matches!(meta, SchemaMeta::Renamed(_)),
+            None => self.db_schema.contains(&item_path),
+        };
+
+        if !item_exists {
+            return Err(SemaError::UndefinedSchemaItem {
+                path: item_path,
+                span: span.clone(),
+            });
         }
 
         Ok(())
     }
 
     /// Checks if a variable or parameter exists
-    pub fn check_variable_or_param_exists(&mut self, name: &str) -> SemaResult<()> {
+    pub fn check_variable_or_param_exists(&mut self, name: &str, span: &Span) -> SemaResult<()> {
         if !self.current_symbols.contains(name) {
-            let param_path = self.create_schema_item_path(name, &None)?;
-            if !matches!(self.db_schema.get(&param_path), Some(SchemaMeta::Param())) {
-                return Err(SemaError::UndefinedVariableOrParam(name.to_owned()));
+            let param_path = self.create_schema_item_path(name, &None, span)?;
+            if !matches!(
+                self.db_schema.get(&param_path),
+                Some(SchemaMeta::Param { .. })
+            ) {
+                return Err(SemaError::UndefinedVariableOrParam {
+                    name: name.to_owned(),
+                    span: span.clone(),
+                });
             }
         }
 
         Ok(())
     }
 
-    /// Checks if database exists
+    /// Checks if a function exists, unless it is in a namespace whose functions may be defined in
+    /// the database, like the functions of a module or of a `DEFINE FUNCTION`.
+    fn check_function_exists(&self, name: String, span: &Span) -> SemaResult<()> {
+        if self.functions.get(&name).is_none() && self.functions.knows_namespace_of(&name) {
+            return Err(SemaError::UndefinedFunction {
+                suggestion: self.functions.suggest(&name),
+                name,
+                span: span.clone(),
+            });
+        }
+
+        Ok(())
+    }
 
     /// Registers a schema item definition
     fn register_schema_item_definition(
@@ -876,13 +978,25 @@ impl NameResolver {
     ) -> SemaResult<()> {
         // Construct the schema name
         let schema_name = ast_as!(name, Identifier(name));
-        let schema_path = self.create_schema_item_path(schema_name, in_database)?;
+        let schema_path = self.create_schema_item_path(schema_name, in_database, &name.span)?;
 
+        self.register_schema_path_definition(schema_path, &name.span, if_not_exists, meta)
+    }
+
+    /// Registers a schema item definition at the given path.
+    fn register_schema_path_definition(
+        &mut self,
+        schema_path: Path,
+        span: &Span,
+        if_not_exists: bool,
+        meta: SchemaMeta,
+    ) -> SemaResult<()> {
         // Check if the table already exists in the database schema
         if self.db_schema.contains(&schema_path) && !if_not_exists {
-            return Err(SemaError::DuplicateSchemaItemDefinition(
-                schema_path.clone(),
-            ));
+            return Err(SemaError::DuplicateSchemaItemDefinition {
+                path: schema_path,
+                span: span.clone(),
+            });
         }
 
         // Create the schema
@@ -895,16 +1009,104 @@ impl NameResolver {
         Ok(())
     }
 
+    /// Registers a schema item redefinition.
+    ///
+    /// The redefinition is a new version of the schema item, with the definition `redefine` makes
+    /// of the existing one. A renamed item also leaves a [`SchemaMeta::Renamed`] version behind
+    /// under its old name, so the old name no longer resolves to it.
+    fn register_schema_item_redefinition(
+        &mut self,
+        name: &Ast,
+        new_name: &Option<Box<Ast>>,
+        in_database: &Option<Box<Ast>>,
+        if_exists: bool,
+        kind: &str,
+        redefine: impl FnOnce(&mut SchemaMeta) -> SemaResult<()>,
+    ) -> SemaResult<()> {
+        // Construct the schema name
+        let schema_name = ast_as!(name, Identifier(name));
+        let schema_path = self.create_schema_item_path(schema_name, in_database, &name.span)?;
+
+        // Check the schema item exists and is of the same kind
+        let Some(mut meta) = self.get_schema_item(&schema_path, kind) else {
+            if if_exists {
+                return Ok(());
+            }
+
+            return Err(SemaError::UndefinedSchemaItem {
+                path: schema_path,
+                span: name.span.clone(),
+            });
+        };
+
+        redefine(&mut meta)?;
+
+        let schema = match new_name {
+            Some(new_name) => {
+                let new_schema_name = ast_as!(**new_name, Identifier(name));
+                let new_schema_path =
+                    self.create_schema_item_path(new_schema_name, in_database, &new_name.span)?;
+
+                // Check the new name is not taken by another schema item
+                let new_name_taken = match self
+                    .current_schema
+                    .as_ref()
+                    .and_then(|s| s.get(&new_schema_path))
+                {
+                    Some(meta) => !matches!(meta, SchemaMeta::Renamed(_)),
+                    None => self.db_schema.contains(&new_schema_path),
+                };
+
+                if new_name_taken {
+                    return Err(SemaError::DuplicateSchemaItemDefinition {
+                        path: new_schema_path,
+                        span: new_name.span.clone(),
+                    });
+                }
+
+                let renamed = VersionedSchema::new(
+                    schema_path,
+                    SchemaMeta::Renamed(new_schema_path.clone()),
+                    self.current_schema.as_ref(),
+                );
+
+                VersionedSchema::with_previous(new_schema_path, meta, &renamed)
+            }
+            None => VersionedSchema::new(schema_path, meta, self.current_schema.as_ref()),
+        };
+
+        // Set the current schema
+        self.current_schema = Some(schema);
+        self.has_untagged_schema = true;
+
+        Ok(())
+    }
+
+    /// Gets the schema item of the given kind, e.g. `table`, defined at the given path, either in
+    /// the current schema or in the database schema.
+    fn get_schema_item(&self, path: &Path, kind: &str) -> Option<SchemaMeta> {
+        match self.current_schema.as_ref().and_then(|s| s.get(path)) {
+            Some(found) => Some(found.clone()),
+            None => self.db_schema.get(path),
+        }
+        .filter(|found| found.kind() == kind)
+    }
+
     fn create_schema_item_path(
         &self,
         name: &str,
         in_database: &Option<Box<Ast>>,
+        span: &Span,
     ) -> SemaResult<Path> {
-        let segment = name.parse()?;
+        let segment = name.parse().map_err(|error| SemaError::PathError {
+            error,
+            span: span.clone(),
+        })?;
 
         // Try and use the specified database first
         if let Some(database) = in_database {
-            let mut path: Path = ast_as!(**database, Identifier(database)).parse()?;
+            let database_name = ast_as!(**database, Identifier(database));
+            let mut path = parse_path(database_name, &database.span)?;
             path.push(segment);
             return Ok(path);
         }
@@ -915,10 +1117,17 @@ impl NameResolver {
 
         Ok(path)
     }
+}
 
-    fn analyze_type(&mut self, _typesig: &TypeSig) -> SemaResult<()> {
-        Ok(()) // TODO: Analyze the type
-    }
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn parse_path(name: &str, span: &Span) -> SemaResult<Path> {
+    name.parse().map_err(|error| SemaError::PathError {
+        error,
+        span: span.clone(),
+    })
 }
 
 //--------------------------------------------------------------------------------------------------
@@ -931,6 +1140,78 @@ impl Default for NameResolver {
     }
 }
 
+impl<'a> VisitorMut<'a> for NameResolver {
+    type Error = SemaError;
+
+    fn visit_ast(&mut self, ast: &mut Ast<'a>) -> SemaResult<()> {
+        let analyze_identifier = std::mem::replace(&mut self.analyze_identifier, true);
+
+        if self.depth >= self.max_depth {
+            return Err(SemaError::NestingTooDeep {
+                max_depth: self.max_depth,
+                span: ast.span.clone(),
+            });
+        }
+
+        self.depth += 1;
+        let result = stack::ensure_stack(|| self.analyze_node(ast, analyze_identifier));
+        self.depth -= 1;
+
+        result
+    }
+
+    fn visit_type_sig(&mut self, type_sig: &mut TypeSig<'a>) -> SemaResult<()> {
+        match type_sig {
+            // Built-in types like `u8` need no definition, other names must be defined.
+            TypeSig::Basic(name) => match name.kind {
+                Identifier(type_name) if matches!(Type::from_name(type_name), Type::Named(_)) => {
+                    self.check_named_type_exists(type_name, &name.span)
+                }
+                _ => Ok(()),
+            },
+            TypeSig::Array { r#type, .. } | TypeSig::List(r#type) | TypeSig::Option(r#type) => {
+                self.visit_type_sig(r#type)
+            }
+            TypeSig::Tuple(types)
+            | TypeSig::Generic {
+                parameters: types, ..
+            } => types
+                .iter_mut()
+                .try_for_each(|r#type| self.visit_type_sig(r#type)),
+        }
+    }
+
+    fn visit_select_column(&mut self, column: &mut SelectColumn<'a>) -> SemaResult<()> {
+        match column {
+            SelectColumn::Column(ast) | SelectColumn::Fold { subject: ast, .. } => {
+                self.analyze_in_table(ast)
+            }
+        }
+    }
+
+    fn visit_select_transform(&mut self, transform: &mut SelectTransform<'a>) -> SemaResult<()> {
+        match transform {
+            SelectTransform::WhereGuard(ast) => self.analyze_in_table(ast)?,
+            SelectTransform::LimitTo(ast) | SelectTransform::StartAt(ast) => {
+                self.analyze_without_identifier(ast)?
+            }
+            SelectTransform::WithIndexes(asts) | SelectTransform::GroupBy(asts) => {
+                for ast in asts {
+                    self.analyze_without_identifier(ast)?;
+                }
+            }
+            SelectTransform::OrderBy { .. } | SelectTransform::WithNoIndex => {}
+        }
+
+        Ok(())
+    }
+
+    fn visit_else_if_part(&mut self, else_if: &mut ElseIfPart<'a>) -> SemaResult<()> {
+        let ElseIfPart { condition, body } = else_if;
+        self.analyze_scope(|r| r.analyze(condition), |r| r.analyze(body))
+    }
+}
+
 //--------------------------------------------------------------------------------------------------
 // Macros
 //--------------------------------------------------------------------------------------------------
@@ -944,7 +1225,10 @@ macro_rules! ast_as {
         {
             ($($param),*)
         } else {
-            return Err($crate::sema::error::SemaError::UnexpectedAstKind($ast.kind.to_string()));
+            return Err($crate::sema::error::SemaError::UnexpectedAstKind {
+                kind: $ast.kind.to_string(),
+                span: $ast.span.clone(),
+            });
         }
     };
     ($ast:expr, $name:ident { $($param:ident),* }) => {
@@ -955,7 +1239,10 @@ macro_rules! ast_as {
         {
             ($($param),*)
         } else {
-            return Err($crate::sema::error::SemaError::UnexpectedAstKind($ast.kind.to_string()));
+            return Err($crate::sema::error::SemaError::UnexpectedAstKind {
+                kind: $ast.kind.to_string(),
+                span: $ast.span.clone(),
+            });
         }
     };
 }
@@ -969,9 +1256,14 @@ use zeroutils_path::Path;
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use tracing::info;
 
-    use crate::parser::Parser;
+    use crate::{
+        parser::Parser,
+        sema::{FieldMeta, TableModeMeta},
+    };
 
     use super::*;
 
@@ -979,8 +1271,8 @@ mod tests {
     fn test_sema_name_resolution_variable() -> anyhow::Result<()> {
         // Accessing variables within the same scope
         let mut ast = Parser::new("LET $a = 5; LET $b = 10; SET $a += 1; $a - $b", 50)
-            .parse_program()?
-            .unwrap();
+            .parse_program()
+            .into_result()?;
 
         let mut resolver = NameResolver::new();
         resolver.analyze(&mut ast)?;
@@ -1015,8 +1307,8 @@ mod tests {
             "#,
             50,
         )
-        .parse_program()?
-        .unwrap();
+        .parse_program()
+        .into_result()?;
 
         let mut resolver = NameResolver::new();
         resolver.analyze(&mut ast)?;
@@ -1045,4 +1337,315 @@ mod tests {
 
         Ok(())
     }
+
+    #[test_log::test]
+    fn test_sema_name_resolution_function_scope() -> anyhow::Result<()> {
+        // The params and the outer variables are visible in the body.
+        let mut ast = Parser::new(
+            r#"
+            LET $rate = 0.9;
+            DEFINE FUNCTION discount($price TYPE f64) TYPE f64 DO
+                LET $discounted = $price * $rate;
+                RETURN $discounted
+            END
+            "#,
+            50,
+        )
+        .parse_program()
+        .into_result()?;
+
+        NameResolver::new().analyze(&mut ast)?;
+
+        // The params and the variables of the body are not visible outside the function.
+        for source in [
+            "DEFINE FUNCTION f($a TYPE u8) DO RETURN $a END; $a",
+            "DEFINE FUNCTION f() DO LET $b = 1; RETURN $b END; $b",
+        ] {
+            let mut ast = Parser::new(source, 50).parse_program().into_result()?;
+            let result = NameResolver::new().analyze(&mut ast);
+
+            assert!(matches!(
+                result,
+                Err(SemaError::UndefinedVariableOrParam { name, .. }) if name == "a" || name == "b"
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_sema_name_resolution_table_fields() -> anyhow::Result<()> {
+        let schema = r#"
+            DEFINE TYPE address FIELDS city TYPE string;
+            DEFINE TABLE person FIELDS age TYPE u8, addr TYPE address, addrs TYPE [address];
+            DEFINE TABLE document SCHEMALESS;
+        "#;
+
+        // Bare and dotted identifiers name the fields of the table of a statement.
+        for source in [
+            "SELECT addr.city FROM person",
+            "SELECT addrs[0].city FROM person",
+            "SELECT addr.* FROM person",
+            "SELECT * FROM person WHERE age > 3",
+            "SELECT * FROM document WHERE author.name = 'Ann'",
+            "UPDATE person SET age = age + 1 WHERE addr.city = 'Wellington'",
+            "DELETE person WHERE age < 18",
+            "UPDATE person:alice SET age = 30",
+        ] {
+            let source = format!("{schema}{source}");
+            let mut ast = Parser::new(&source, 50).parse_program().into_result()?;
+            NameResolver::new().analyze(&mut ast)?;
+        }
+
+        // Outside the statement, they still name schema items.
+        for source in [
+            "SELECT * FROM person WHERE age > 3; age",
+            "UPDATE person SET age = 1; LET $a = age",
+        ] {
+            let source = format!("{schema}{source}");
+            let mut ast = Parser::new(&source, 50).parse_program().into_result()?;
+            let result = NameResolver::new().analyze(&mut ast);
+
+            assert!(matches!(
+                result,
+                Err(SemaError::UndefinedSchemaItem { path, .. }) if path == "age".parse()?
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_sema_name_resolution_schemafull_fields() -> anyhow::Result<()> {
+        let schema = r#"
+            DEFINE ENUM color VARIANTS red, green;
+            DEFINE TABLE t SCHEMAFULL FIELDS a TYPE u8, c TYPE color;
+            DEFINE TABLE s SCHEMALESS FIELDS a TYPE u8;
+            DEFINE TABLE f FLEXIBLE TYPE u8 FIELDS a TYPE u8;
+        "#;
+
+        // Schemaless and flexible tables take fields they do not declare.
+        for source in [
+            "UPDATE t SET a = 1 WHERE c == color.red",
+            "CREATE t SET a = 1",
+            "SELECT a, c FROM t WHERE a > 1",
+            "UPDATE s SET b = 1",
+            "CREATE f SET b = 1",
+            "SELECT b FROM s WHERE b > 1",
+        ] {
+            let source = format!("{schema}{source}");
+            let mut ast = Parser::new(&source, 50).parse_program().into_result()?;
+            NameResolver::new().analyze(&mut ast)?;
+        }
+
+        // Schemafull tables only take the fields they declare.
+        for source in [
+            "UPDATE t SET b = 1",
+            "CREATE t SET a = 1, b = 2",
+            "SELECT b FROM t",
+            "SELECT * OMIT b FROM t",
+            "SELECT * FROM t WHERE b",
+            "DELETE t WHERE b > 1",
+        ] {
+            let source = format!("{schema}{source}");
+            let mut ast = Parser::new(&source, 50).parse_program().into_result()?;
+            let result = NameResolver::new().analyze(&mut ast);
+
+            assert!(
+                matches!(
+                    &result,
+                    Err(SemaError::UnknownField { field, r#type: Type::Named(table), .. })
+                        if field == "b" && table == "t"
+                ),
+                "{source}: {result:?}"
+            );
+        }
+
+        // Names of other schema items are not fields of a schemafull table.
+        for source in [
+            "SELECT color FROM t",
+            "SELECT * FROM t WHERE s",
+            "UPDATE t SET f = 1",
+        ] {
+            let source = format!("{schema}{source}");
+            let mut ast = Parser::new(&source, 50).parse_program().into_result()?;
+            assert!(
+                matches!(
+                    NameResolver::new().analyze(&mut ast),
+                    Err(SemaError::UnknownField { .. })
+                ),
+                "{source}"
+            );
+        }
+
+        // Record ids and enum variants still name schema items.
+        let source = format!("{schema}SELECT * FROM t WHERE a == s:1 AND c == color.green");
+        let mut ast = Parser::new(&source, 50).parse_program().into_result()?;
+        NameResolver::new().analyze(&mut ast)?;
+
+        let source = format!("{schema}SELECT aa FROM t");
+        let mut ast = Parser::new(&source, 50).parse_program().into_result()?;
+        assert!(matches!(
+            NameResolver::new().analyze(&mut ast),
+            Err(SemaError::UnknownField { suggestion: Some(suggestion), .. }) if suggestion == "a"
+        ));
+
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_sema_name_resolution_type_names() -> anyhow::Result<()> {
+        // Named types are tables, edges, types or enums, and a table may have fields of its type.
+        for source in [
+            "DEFINE ENUM color VARIANTS red; DEFINE TABLE t FIELDS c TYPE color?, cs TYPE [color]",
+            "DEFINE TABLE person FIELDS friends TYPE [person]",
+            "DEFINE TYPE coords FIELDS lat TYPE f64; LET $c TYPE (coords, u8)? = NONE",
+            "DEFINE ENUM color VARIANTS red; DEFINE FUNCTION f($c TYPE color) TYPE color DO RETURN $c END",
+        ] {
+            let mut ast = Parser::new(source, 50).parse_program().into_result()?;
+            NameResolver::new().analyze(&mut ast)?;
+        }
+
+        for (source, name) in [
+            (
+                "DEFINE ENUM color VARIANTS red; DEFINE TABLE t FIELDS c TYPE colr",
+                "colr",
+            ),
+            ("LET $a TYPE [colr] = []", "colr"),
+            ("DEFINE PARAM p VALUE 1; LET $a TYPE p = 1", "p"),
+            ("DEFINE FUNCTION f() TYPE colr DO RETURN NONE END", "colr"),
+            (
+                "DEFINE TABLE t; REDEFINE TABLE t FIELDS c TYPE colr",
+                "colr",
+            ),
+        ] {
+            let mut ast = Parser::new(source, 50).parse_program().into_result()?;
+            let result = NameResolver::new().analyze(&mut ast);
+            assert!(
+                matches!(
+                    &result,
+                    Err(SemaError::UndefinedSchemaItem { path, .. }) if *path == name.parse()?
+                ),
+                "{source}: {result:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_sema_name_resolution_redefinitions() -> anyhow::Result<()> {
+        // A renamed schema item is only known by its new name.
+        let mut ast = Parser::new(
+            "DEFINE TABLE person; REDEFINE TABLE person AS people FIELDS name AS full_name",
+            50,
+        )
+        .parse_program()
+        .into_result()?;
+
+        let mut resolver = NameResolver::new();
+        resolver.analyze(&mut ast)?;
+
+        let schema = resolver.current_schema.unwrap();
+        let people = "people".parse::<Path>()?;
+        assert_eq!(
+            schema.get(&people),
+            Some(&SchemaMeta::Table {
+                mode: TableModeMeta::Schemafull,
+                fields: vec![FieldMeta::new("full_name")]
+            })
+        );
+        assert_eq!(
+            schema.get(&"person".parse()?),
+            Some(&SchemaMeta::Renamed(people))
+        );
+
+        // Redefining a schema item that does not exist fails, unless `IF EXISTS` is given.
+        for source in [
+            "REDEFINE TABLE person",
+            "DEFINE ENUM person; REDEFINE TABLE person",
+            "DEFINE TABLE person; REDEFINE TABLE person AS people; REDEFINE TABLE person",
+        ] {
+            let mut ast = Parser::new(source, 50).parse_program().into_result()?;
+            let result = NameResolver::new().analyze(&mut ast);
+
+            assert!(matches!(result, Err(SemaError::UndefinedSchemaItem { .. })));
+        }
+
+        let mut ast = Parser::new("REDEFINE PARAM IF EXISTS p AS q", 50)
+            .parse_program()
+            .into_result()?;
+        NameResolver::new().analyze(&mut ast)?;
+
+        // A schema item cannot be renamed to a name that is taken.
+        let mut ast = Parser::new(
+            "DEFINE TABLE person; DEFINE EDGE people; REDEFINE TABLE person AS people",
+            50,
+        )
+        .parse_program()
+        .into_result()?;
+        let result = NameResolver::new().analyze(&mut ast);
+
+        assert!(matches!(
+            result,
+            Err(SemaError::DuplicateSchemaItemDefinition { path, .. }) if path == "people".parse()?
+        ));
+
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_sema_name_resolution_db_schema() -> anyhow::Result<()> {
+        let db_schema = DatabaseSchema::new(HashMap::from([
+            ("shop".parse::<Path>()?, SchemaMeta::Database()),
+            (
+                "shop/person".parse()?,
+                SchemaMeta::Table {
+                    mode: TableModeMeta::Schemafull,
+                    fields: vec![],
+                },
+            ),
+            (
+                "rate".parse()?,
+                SchemaMeta::param(None, &Ast::new(0..3, FloatLiteral(0.9)))?,
+            ),
+        ]));
+
+        // Persisted schema items can be used, removed and redefined.
+        let mut ast = Parser::new(
+            "$rate; USE DB shop; REDEFINE TABLE person AS people; REMOVE TABLE people",
+            50,
+        )
+        .parse_program()
+        .into_result()?;
+
+        NameResolver::new()
+            .with_db_schema(db_schema.clone())
+            .analyze(&mut ast)?;
+
+        // Persisted schema items cannot be defined again.
+        let mut ast = Parser::new("USE DB shop; DEFINE TABLE person", 50)
+            .parse_program()
+            .into_result()?;
+        let result = NameResolver::new()
+            .with_db_schema(db_schema.clone())
+            .analyze(&mut ast);
+
+        assert!(matches!(
+            result,
+            Err(SemaError::DuplicateSchemaItemDefinition { path, .. }) if path == "shop/person".parse()?
+        ));
+
+        // Schema items that are not persisted stay undefined.
+        let mut ast = Parser::new("USE DB shop; REMOVE TABLE invoice", 50)
+            .parse_program()
+            .into_result()?;
+        let result = NameResolver::new()
+            .with_db_schema(db_schema)
+            .analyze(&mut ast);
+
+        assert!(matches!(result, Err(SemaError::UndefinedSchemaItem { .. })));
+
+        Ok(())
+    }
 }
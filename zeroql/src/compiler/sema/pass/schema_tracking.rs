@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use zeroutils_path::Path;
+
+use crate::{
+    ast::{Ast, AstKind::*},
+    sema::{DatabaseSchema, SchemaMeta, SemaResult, TableModeMeta, Type},
+};
+
+use super::name_resolution::ast_as;
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// `SchemaTracker` follows the tables, edges, types and enums a program can use as its statements
+/// run, for the passes that need their fields and variants.
+///
+/// It starts out with the items persisted in the root database. A `DEFINE` or `REDEFINE` in the
+/// program changes the items, and a `USE` makes the items persisted in the database it uses known,
+/// unless the program already defines ones with the same names.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SchemaTracker {
+    /// The tables, edges, types and enums by name.
+    items: HashMap<String, SchemaMeta>,
+
+    /// The schema items that are already persisted by the database.
+    db_schema: DatabaseSchema,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl SchemaTracker {
+    /// Creates a tracker that knows the items persisted in the root database of `db_schema`.
+    pub(crate) fn new(db_schema: DatabaseSchema) -> Self {
+        let mut tracker = Self {
+            items: HashMap::new(),
+            db_schema,
+        };
+
+        tracker.use_database(&Path::default());
+        tracker
+    }
+
+    /// Applies the changes a statement makes to the known items, if it makes any.
+    pub(crate) fn track(&mut self, ast: &Ast) -> SemaResult<()> {
+        match &ast.kind {
+            DefineTable { name, .. }
+            | DefineEdge { name, .. }
+            | DefineType { name, .. }
+            | DefineEnum { name, .. } => {
+                let name = ast_as!(&**name, Identifier(name));
+                if let Some(meta) = SchemaMeta::from_definition(ast)? {
+                    self.items.insert(name.to_string(), meta);
+                }
+            }
+            RedefineTable { name, new_name, .. }
+            | RedefineEdge { name, new_name, .. }
+            | RedefineType { name, new_name, .. }
+            | RedefineEnum { name, new_name, .. } => {
+                let name = ast_as!(&**name, Identifier(name));
+                let new_name = match new_name {
+                    Some(new_name) => ast_as!(&**new_name, Identifier(name)),
+                    None => name,
+                };
+
+                if let Some(mut meta) = self.items.remove(*name) {
+                    meta.redefine(ast)?;
+                    self.items.insert(new_name.to_string(), meta);
+                }
+            }
+            Use { database } => {
+                let database = ast_as!(&**database, Identifier(name));
+                if let Ok(database) = database.parse() {
+                    self.use_database(&database);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Gets the object type of the fields a table, edge or type declares.
+    pub(crate) fn object_type(&self, name: &str) -> Option<Type> {
+        self.items.get(name).and_then(SchemaMeta::object_type)
+    }
+
+    /// Gets the mode of a table, edge or type, which says if it takes fields it does not declare.
+    pub(crate) fn mode(&self, name: &str) -> Option<TableModeMeta> {
+        self.items
+            .get(name)
+            .filter(|meta| meta.object_type().is_some())
+            .map(SchemaMeta::mode)
+    }
+
+    /// Gets the type of a field of a table, edge or type.
+    ///
+    /// Fields a schemaless or flexible table does not declare have its catch-all type. Fields of
+    /// unknown tables, and fields a schemafull table does not declare, have the [`Type::Any`] type.
+    pub(crate) fn field_type(&self, table: &str, name: &str) -> Type {
+        let Some(meta) = self.items.get(table) else {
+            return Type::Any;
+        };
+
+        match meta.object_type() {
+            Some(Type::Object(fields)) => match fields.into_iter().find(|(field, _)| field == name)
+            {
+                Some((_, r#type)) => r#type,
+                None => meta.mode().catch_all().unwrap_or_default(),
+            },
+            _ => Type::Any,
+        }
+    }
+
+    /// Gets the variants of an enum.
+    pub(crate) fn variants(&self, name: &str) -> Option<&[String]> {
+        match self.items.get(name) {
+            Some(SchemaMeta::Enum { variants }) => Some(variants),
+            _ => None,
+        }
+    }
+
+    /// Whether the name is the name of an enum.
+    pub(crate) fn is_enum(&self, name: &str) -> bool {
+        self.variants(name).is_some()
+    }
+
+    /// Makes the items persisted in `database` known, unless the program already defines ones with
+    /// the same names.
+    fn use_database(&mut self, database: &Path) {
+        for (name, meta) in self.db_schema.items(database) {
+            if matches!(meta, SchemaMeta::Enum { .. }) || meta.object_type().is_some() {
+                self.items.entry(name).or_insert(meta);
+            }
+        }
+    }
+}
@@ -1,9 +1,1349 @@
 //! - contextual validity
 //! - type checking
 
+use crate::{
+    ast::{
+        walk_ast, walk_select_transform, Ast, AstKind::*, ElseIfPart, Field, SelectTransform,
+        TypeSig, UpdateAssign, Visitor,
+    },
+    compiler::stack,
+    sema::{
+        arg_value, FunctionRegistry, FunctionSignature, SemaError, SemaResult, Type,
+        DEFAULT_MAX_DEPTH, DEFINED_FUNCTION_NAMESPACE,
+    },
+    Span,
+};
+
+use super::{member_resolution::type_of, name_resolution::ast_as};
+
 //--------------------------------------------------------------------------------------------------
 // Types
 //--------------------------------------------------------------------------------------------------
 
-/// TODO: implement
-pub struct TypeChecker {}
+/// `TypeChecker` pass checks that values are only used in ways their types allow.
+///
+/// It checks the operands of unary and binary operators, values against the type annotations of
+/// `LET` and `DEFINE PARAM` and the field types of `DEFINE TABLE` and `DEFINE EDGE`, `SET` and
+/// `UPDATE ... SET` assignments, that conditions are boolean, and that the values of `RETURN` fit
+/// the declared return type of the `DEFINE FUNCTION` they are in. A function body may only end
+/// without a `RETURN` if its return type takes `NONE`.
+///
+/// Function calls are checked against the signatures in its [`FunctionRegistry`]: the arguments
+/// must bind to the parameters and fit their types. Calls to unknown functions are only reported
+/// in namespaces the registry knows all functions of, as other namespaces may belong to modules
+/// defined in the database.
+///
+/// ## Note
+///
+/// The types of variables, fields, and the values of `IF` and `SELECT`, are read from the tags set
+/// by [`TypeInferencer`](super::TypeInferencer), which must run first. Values whose type is only
+/// known at runtime, like the fields of a table that is not defined, have the [`Type::Any`] type
+/// and pass every check.
+pub struct TypeChecker {
+    /// The signatures of the functions that can be called.
+    functions: FunctionRegistry,
+
+    /// The return type of the function whose body is being checked, if any.
+    current_returns: Option<Type>,
+
+    /// How deeply the node being checked is nested.
+    depth: usize,
+
+    /// How deeply nodes may nest before checking fails.
+    max_depth: usize,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl TypeChecker {
+    /// Creates a new type checking pass.
+    pub fn new() -> Self {
+        Self {
+            functions: FunctionRegistry::builtins(),
+            current_returns: None,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Sets how deeply nodes may nest before checking fails.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the signatures of the functions that can be called, in place of the built-ins.
+    pub fn with_functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    /// Runs type checking on the given ast.
+    #[inline]
+    pub fn analyze(&mut self, ast: &Ast) -> SemaResult<()> {
+        self.visit_ast(ast)
+    }
+
+    /// Checks a node and gets its type.
+    fn synthesize(&mut self, ast: &Ast) -> SemaResult<Type> {
+        if self.depth >= self.max_depth {
+            return Err(SemaError::NestingTooDeep {
+                max_depth: self.max_depth,
+                span: ast.span.clone(),
+            });
+        }
+
+        self.depth += 1;
+        let result = stack::ensure_stack(|| self.check_node(ast));
+        self.depth -= 1;
+
+        result
+    }
+
+    /// Checks a single node, leaving the nodes it does not care about to the default walk.
+    fn check_node(&mut self, ast: &Ast) -> SemaResult<Type> {
+        if let Some((left, operator, right)) = binary_operation!(&ast.kind) {
+            let left = self.synthesize(left)?;
+            let right = self.synthesize(right)?;
+            return check_binary(operator, left, right, &ast.span);
+        }
+
+        let r#type = match &ast.kind {
+            // == Literals ==
+            IntegerLiteral(_) => Type::Integer,
+            FloatLiteral(_) => Type::Float,
+            StringLiteral(_) => Type::String,
+            ByteStringLiteral(_) => Type::ByteString,
+            RegexLiteral { .. } => Type::Regex,
+            BooleanLiteral(_) => Type::Bool,
+            NoneLiteral => Type::None,
+            ListLiteral(asts) => {
+                let mut element: Option<Type> = None;
+                for ast in asts {
+                    let r#type = self.synthesize(ast)?;
+                    element = Some(match element {
+                        Some(element) => element.unify(r#type),
+                        None => r#type,
+                    });
+                }
+
+                Type::Array(Box::new(element.unwrap_or_default()), asts.len())
+            }
+            TupleLiteral(asts) => Type::Tuple(
+                asts.iter()
+                    .map(|ast| self.synthesize(ast))
+                    .collect::<SemaResult<_>>()?,
+            ),
+            ObjectLiteral(entries) => Type::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| Ok((key_name(key), self.synthesize(value)?)))
+                    .collect::<SemaResult<_>>()?,
+            ),
+
+            // == Names ==
+            Variable(_) | Identifier(_) => type_of(ast),
+
+            // A variant like `color::red` has the type of its enum, so it only fits where that
+            // enum is expected and only compares with variants of the same enum.
+            ScopedIdentifier(_) => type_of(ast),
+            AliasOp { subject, .. } => self.synthesize(subject)?,
+
+            // == Unary Operations ==
+            LogicalNotOp(operand) => {
+                self.check_unary("NOT", operand, &ast.span, |t| *t == Type::Bool)?;
+                Type::Bool
+            }
+            BitwiseNotOp(operand) => self.check_unary("~", operand, &ast.span, Type::is_integer)?,
+            PlusSignOp(operand) => self.check_unary("+", operand, &ast.span, Type::is_numeric)?,
+            MinusSignOp(operand) => self.check_unary("-", operand, &ast.span, Type::is_numeric)?,
+            UnboundedRangeOp(start) => match start {
+                Some(start) => {
+                    let start = self.check_unary("..", start, &ast.span, Type::is_integer)?;
+                    Type::Range(Box::new(start))
+                }
+                None => Type::Range(Box::new(Type::Any)),
+            },
+            Index { subject, index } => {
+                let subject_type = self.synthesize(subject)?;
+                let index_type = self.synthesize(index)?;
+                check_index(subject_type, index, index_type, &ast.span)?
+            }
+            FunctionCall { subject, args } => self.check_call(subject, args, &ast.span)?,
+
+            // == Variable Declarations ==
+            Let { r#type, value, .. } => {
+                self.check_declaration(r#type.as_deref(), value)?;
+                Type::Any
+            }
+            DefineParam { r#type, value, .. }
+            | RedefineParam {
+                r#type,
+                value: Some(value),
+                ..
+            } => {
+                self.check_declaration(r#type.as_ref(), value)?;
+                Type::Any
+            }
+            For { iterator, body, .. } => {
+                let iterator_type = self.synthesize(iterator)?;
+                if iterator_type.element().is_none() {
+                    return Err(SemaError::NotIterable {
+                        found: iterator_type,
+                        span: iterator.span.clone(),
+                    });
+                }
+
+                self.synthesize(body)?;
+                Type::Any
+            }
+
+            // == Conditions ==
+            While { condition, body } => {
+                self.check_condition(condition)?;
+                self.synthesize(body)?;
+                Type::Any
+            }
+            If {
+                condition,
+                then,
+                else_ifs,
+                r#else,
+            } => {
+                self.check_condition(condition)?;
+                self.synthesize(then)?;
+
+                for else_if in else_ifs {
+                    self.visit_else_if_part(else_if)?;
+                }
+
+                if let Some(r#else) = r#else {
+                    self.synthesize(r#else)?;
+                }
+
+                // The value of an `IF` is the join of the values of its branches.
+                type_of(ast)
+            }
+
+            // == Assignments ==
+            Set {
+                variable,
+                op,
+                value,
+            } => {
+                let target = self.synthesize(variable)?;
+                let found = self.synthesize(value)?;
+                check_assignment(&target, op, value, found)?;
+                Type::Any
+            }
+            Update {
+                target,
+                where_guard,
+                column_ops,
+            } => {
+                self.synthesize(target)?;
+                if let Some(where_guard) = where_guard {
+                    self.check_condition(where_guard)?;
+                }
+
+                for (column, op, value) in column_ops {
+                    let target = self.synthesize(column)?;
+                    let found = self.synthesize(value)?;
+                    check_assignment(&target, op, value, found)?;
+                }
+
+                Type::Any
+            }
+            Delete {
+                target,
+                where_guard,
+            } => {
+                self.synthesize(target)?;
+                if let Some(where_guard) = where_guard {
+                    self.check_condition(where_guard)?;
+                }
+
+                Type::Any
+            }
+
+            // == Schema Definitions ==
+            DefineTable { fields, .. } | DefineEdge { fields, .. } => {
+                fields
+                    .iter()
+                    .try_for_each(|field| self.visit_field(field))?;
+                Type::Any
+            }
+            DefineModule { name, block, .. } => {
+                let name = ast_as!(**name, Identifier(name));
+                let block = ast_as!(**block, ModuleBlock(block));
+                self.functions.register_module(name, block);
+                Type::Any
+            }
+            DefineFunction {
+                name,
+                params,
+                r#type,
+                body,
+                ..
+            } => {
+                let name = ast_as!(**name, Identifier(name));
+                let signature = FunctionSignature::from_definition(name, params, r#type.as_ref())?;
+                self.functions.register(signature.clone());
+
+                let outer = self.current_returns.replace(signature.returns.clone());
+                let result = self.synthesize(body);
+                self.current_returns = outer;
+                result?;
+
+                // Falling through the end of the body returns `NONE`.
+                if !always_returns(body) && !Type::None.is_assignable_to(&signature.returns) {
+                    return Err(SemaError::MissingReturn {
+                        function: signature.name,
+                        r#type: signature.returns,
+                        span: body.span.clone(),
+                    });
+                }
+
+                Type::Any
+            }
+            RemoveFunction { subject, .. } => {
+                let name = ast_as!(**subject, Identifier(name));
+                self.functions
+                    .remove(&format!("{DEFINED_FUNCTION_NAMESPACE}::{name}"));
+                Type::Any
+            }
+            Return(value) => {
+                let Some(expected) = self.current_returns.clone() else {
+                    return Err(SemaError::ReturnOutsideFunction {
+                        span: ast.span.clone(),
+                    });
+                };
+
+                match value {
+                    Some(value) => {
+                        let found = self.synthesize(value)?;
+                        check_value(value, &found, &expected)?;
+                    }
+                    None => check_value(ast, &Type::None, &expected)?,
+                }
+
+                Type::Any
+            }
+
+            _ => {
+                walk_ast(self, ast)?;
+                type_of(ast)
+            }
+        };
+
+        Ok(r#type)
+    }
+
+    /// Checks the arguments of a function call against the function's signature and gets the type
+    /// it returns.
+    fn check_call(&mut self, subject: &Ast, args: &[Ast], span: &Span) -> SemaResult<Type> {
+        let name = FunctionRegistry::name_of(subject);
+        let Some(signature) = name
+            .as_ref()
+            .and_then(|name| self.functions.get(name))
+            .cloned()
+        else {
+            if let Some(name) = name.filter(|name| {
+                self.functions.knows_namespace_of(name) || self.functions.is_removed(name)
+            }) {
+                return Err(SemaError::UndefinedFunction {
+                    suggestion: self.functions.suggest(&name),
+                    name,
+                    span: subject.span.clone(),
+                });
+            }
+
+            for arg in args {
+                self.synthesize(arg_value(arg))?;
+            }
+
+            return Ok(Type::Any);
+        };
+
+        for (param, index) in signature.bind(args, span)? {
+            let value = arg_value(&args[index]);
+            let found = self.synthesize(value)?;
+            check_value(value, &found, &param.r#type)?;
+        }
+
+        Ok(signature.returns)
+    }
+
+    /// Checks the operand of a unary operator and gets its type.
+    fn check_unary(
+        &mut self,
+        operator: &'static str,
+        operand: &Ast,
+        span: &Span,
+        accepts: impl Fn(&Type) -> bool,
+    ) -> SemaResult<Type> {
+        let r#type = self.synthesize(operand)?;
+        if r#type != Type::Any && !accepts(&r#type) {
+            return Err(SemaError::InvalidOperandType {
+                operator,
+                found: r#type,
+                span: span.clone(),
+            });
+        }
+
+        Ok(r#type)
+    }
+
+    /// Checks the value of a declaration against its type annotation if it has one, and gets the
+    /// type of the declared name.
+    fn check_declaration(&mut self, type_sig: Option<&TypeSig>, value: &Ast) -> SemaResult<Type> {
+        let found = self.synthesize(value)?;
+        let Some(type_sig) = type_sig else {
+            return Ok(found);
+        };
+
+        let expected = Type::from_type_sig(type_sig)?;
+        check_value(value, &found, &expected)?;
+
+        Ok(expected)
+    }
+
+    /// Checks that a condition is boolean.
+    fn check_condition(&mut self, condition: &Ast) -> SemaResult<()> {
+        let found = self.synthesize(condition)?;
+        if !found.is_assignable_to(&Type::Bool) {
+            return Err(SemaError::MismatchedTypes {
+                expected: Type::Bool,
+                found,
+                span: condition.span.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Checks the operands of a binary operator and gets the type of the operation.
+pub(super) fn check_binary(
+    operator: &'static str,
+    left: Type,
+    right: Type,
+    span: &Span,
+) -> SemaResult<Type> {
+    let both = |accepts: fn(&Type) -> bool| is(&left, accepts) && is(&right, accepts);
+
+    let r#type = match operator {
+        "**" | "*" | "/" | "%" | "+" | "-" if both(Type::is_numeric) => {
+            Some(Type::promote(&left, &right))
+        }
+        "+" | "-" | "*" | "/" if is_temporal(&left) || is_temporal(&right) => {
+            temporal_arithmetic(operator, &left, &right)
+        }
+        "+" if both(|t| *t == Type::String) => Some(Type::String),
+        "+" if both(|t| *t == Type::ByteString) => Some(Type::ByteString),
+        "<<" | ">>" => both(Type::is_integer).then(|| left.clone()),
+        "&" | "^" | "|" | "~" if both(Type::is_integer) => Some(Type::promote(&left, &right)),
+        "&" | "^" | "|" if both(|t| *t == Type::Bool) => Some(Type::Bool),
+        "<" | ">" | "<=" | ">=" => left.is_comparable_with(&right).then_some(Type::Bool),
+        "==" | "IS" | "IS NOT" => {
+            (left.is_comparable_with(&right) || left == Type::None || right == Type::None)
+                .then_some(Type::Bool)
+        }
+        "AND" | "OR" => both(|t| *t == Type::Bool).then_some(Type::Bool),
+        "MATCH" | "NOT MATCH" => (is(&left, |t| *t == Type::String)
+            && is(&right, |t| matches!(t, Type::String | Type::Regex)))
+        .then_some(Type::Bool),
+        "<>" => both(|t| *t == Type::String).then_some(Type::Bool),
+        "IN" | "NOT IN" => right
+            .element()
+            .filter(|element| left.is_comparable_with(element))
+            .map(|_| Type::Bool),
+        "CONTAINS" | "NOT CONTAINS" => left
+            .element()
+            .filter(|element| element.is_comparable_with(&right))
+            .map(|_| Type::Bool),
+        "CONTAINS NONE" | "CONTAINS ALL" | "CONTAINS ANY" => {
+            match (left.element(), right.element()) {
+                (Some(l), Some(r)) => l.is_comparable_with(&r).then_some(Type::Bool),
+                _ => None,
+            }
+        }
+        "?:" => Some(match &left {
+            Type::Option(inner) => (**inner).clone().unify(right.clone()),
+            left => left.clone().unify(right.clone()),
+        }),
+        ".." | "..=" if both(Type::is_integer) => {
+            Some(Type::Range(Box::new(Type::promote(&left, &right))))
+        }
+        _ => None,
+    };
+
+    r#type.ok_or_else(|| SemaError::InvalidOperandTypes {
+        operator,
+        left,
+        right,
+        span: span.clone(),
+    })
+}
+
+/// Gets the type of an arithmetic operation on datetimes and durations.
+///
+/// Durations can be added to and subtracted from datetimes and each other, and scaled by numbers,
+/// and subtracting two datetimes gets the duration between them.
+fn temporal_arithmetic(operator: &str, left: &Type, right: &Type) -> Option<Type> {
+    match (operator, left, right) {
+        (_, Type::Any, _) | (_, _, Type::Any) => Some(Type::Any),
+        ("+", Type::Datetime, Type::Duration)
+        | ("+", Type::Duration, Type::Datetime)
+        | ("-", Type::Datetime, Type::Duration) => Some(Type::Datetime),
+        ("-", Type::Datetime, Type::Datetime) | ("+" | "-", Type::Duration, Type::Duration) => {
+            Some(Type::Duration)
+        }
+        ("*" | "/", Type::Duration, number) | ("*", number, Type::Duration)
+            if number.is_numeric() =>
+        {
+            Some(Type::Duration)
+        }
+        _ => None,
+    }
+}
+
+/// Whether the type is a datetime or a duration.
+fn is_temporal(r#type: &Type) -> bool {
+    matches!(r#type, Type::Datetime | Type::Duration)
+}
+
+/// Checks an index operation and gets the type of the element it gets.
+pub(super) fn check_index(
+    subject: Type,
+    index: &Ast,
+    index_type: Type,
+    span: &Span,
+) -> SemaResult<Type> {
+    let literal = match index.kind {
+        IntegerLiteral(i) => Some(i),
+        _ => None,
+    };
+
+    let r#type = match (&subject, &index_type) {
+        (Type::Any | Type::Object(_) | Type::Named(_) | Type::Generic(..), _) => Some(Type::Any),
+        (Type::Array(element, _) | Type::List(element), Type::Range(_)) => {
+            Some(Type::List(element.clone()))
+        }
+        (Type::String | Type::ByteString, Type::Range(_)) => Some(subject.clone()),
+        (_, index) if !is(index, Type::is_integer) => None,
+        (Type::Array(_, length), _) if literal.is_some_and(|i| i >= *length as u128) => None,
+        (Type::Array(element, _) | Type::List(element), _) => Some(*element.clone()),
+        (Type::String, _) => Some(Type::String),
+        (Type::ByteString, _) => Some(Type::U8),
+        (Type::Tuple(elements), _) => match literal {
+            Some(i) => elements.get(i as usize).cloned(),
+            None => Some(Type::Any),
+        },
+        _ => None,
+    };
+
+    r#type.ok_or_else(|| SemaError::InvalidIndex {
+        subject,
+        index: index_type,
+        span: span.clone(),
+    })
+}
+
+/// Checks an assignment of a value to a variable or field of the `target` type.
+fn check_assignment(target: &Type, op: &UpdateAssign, value: &Ast, found: Type) -> SemaResult<()> {
+    let operator = match op {
+        UpdateAssign::Direct => return check_value(value, &found, target),
+        UpdateAssign::Plus => "+",
+        UpdateAssign::Minus => "-",
+        UpdateAssign::Mul => "*",
+        UpdateAssign::Div => "/",
+        UpdateAssign::Mod => "%",
+        UpdateAssign::Pow => "**",
+        UpdateAssign::BitAnd => "&",
+        UpdateAssign::BitOr => "|",
+        UpdateAssign::BitXor => "^",
+        UpdateAssign::BitNot => "~",
+        UpdateAssign::Shl => "<<",
+        UpdateAssign::Shr => ">>",
+    };
+
+    let result = check_binary(operator, target.clone(), found, &value.span)?;
+    check_value(value, &result, target)
+}
+
+/// Checks that a value of the `found` type can be used where the `expected` type is expected.
+pub(super) fn check_value(value: &Ast, found: &Type, expected: &Type) -> SemaResult<()> {
+    if !found.is_assignable_to(expected) {
+        return Err(SemaError::MismatchedTypes {
+            expected: expected.clone(),
+            found: found.clone(),
+            span: value.span.clone(),
+        });
+    }
+
+    check_integer_range(value, expected)
+}
+
+/// Whether every way through a function body ends in a `RETURN`.
+fn always_returns(body: &Ast) -> bool {
+    match &body.kind {
+        Return(_) => true,
+        Program(statements) => statements.iter().any(always_returns),
+        If {
+            then,
+            else_ifs,
+            r#else: Some(r#else),
+            ..
+        } => {
+            always_returns(then)
+                && else_ifs.iter().all(|part| always_returns(&part.body))
+                && always_returns(r#else)
+        }
+        _ => false,
+    }
+}
+
+/// Checks that the integer literals in a value fit in the integer types they are used as.
+fn check_integer_range(value: &Ast, expected: &Type) -> SemaResult<()> {
+    let (magnitude, negative) = match (&value.kind, expected) {
+        (_, Type::Option(inner)) => return check_integer_range(value, inner),
+        (ListLiteral(values), Type::Array(element, _) | Type::List(element)) => {
+            return values
+                .iter()
+                .try_for_each(|value| check_integer_range(value, element));
+        }
+        (TupleLiteral(values), Type::Tuple(types)) => {
+            return values
+                .iter()
+                .zip(types)
+                .try_for_each(|(value, r#type)| check_integer_range(value, r#type));
+        }
+        (IntegerLiteral(magnitude), _) => (*magnitude, false),
+        (MinusSignOp(operand), _) => match operand.kind {
+            IntegerLiteral(magnitude) => (magnitude, true),
+            _ => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+
+    if !expected.fits_integer(magnitude, negative) {
+        return Err(SemaError::IntegerOutOfRange {
+            value: format!("{}{magnitude}", if negative { "-" } else { "" }),
+            r#type: expected.clone(),
+            span: value.span.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Whether a type is [`Type::Any`] or accepted by the given predicate.
+fn is(r#type: &Type, accepts: impl Fn(&Type) -> bool) -> bool {
+    *r#type == Type::Any || accepts(r#type)
+}
+
+/// Gets the name of the table a statement like `UPDATE` operates on, e.g. `person` in `person:1`.
+pub(super) fn table_name(target: &Ast) -> Option<String> {
+    match &target.kind {
+        Identifier(name) => Some(name.to_string()),
+        IdOp(table, _) => table_name(table),
+        _ => None,
+    }
+}
+
+/// Gets the name of an object key or field.
+pub(super) fn key_name(key: &Ast) -> String {
+    match &key.kind {
+        Identifier(name) | StringLiteral(name) => name.to_string(),
+        kind => kind.to_string(),
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Visitor<'a> for TypeChecker {
+    type Error = SemaError;
+
+    fn visit_ast(&mut self, ast: &Ast<'a>) -> SemaResult<()> {
+        self.synthesize(ast)?;
+        Ok(())
+    }
+
+    fn visit_field(&mut self, field: &Field<'a>) -> SemaResult<()> {
+        let r#type = Type::from_type_sig(&field.r#type)?;
+
+        if let Some(default) = &field.default {
+            let found = self.synthesize(default)?;
+            check_value(default, &found, &r#type)?;
+        }
+
+        field
+            .assertions
+            .iter()
+            .try_for_each(|assertion| self.check_condition(assertion))
+    }
+
+    fn visit_select_transform(&mut self, transform: &SelectTransform<'a>) -> SemaResult<()> {
+        match transform {
+            SelectTransform::WhereGuard(condition) => self.check_condition(condition),
+            SelectTransform::LimitTo(count) | SelectTransform::StartAt(count) => {
+                let found = self.synthesize(count)?;
+                check_value(count, &found, &Type::U64)
+            }
+            transform => walk_select_transform(self, transform),
+        }
+    }
+
+    fn visit_else_if_part(&mut self, else_if: &ElseIfPart<'a>) -> SemaResult<()> {
+        let ElseIfPart { condition, body } = else_if;
+        self.check_condition(condition)?;
+        self.synthesize(body)?;
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Macros
+//--------------------------------------------------------------------------------------------------
+
+/// Splits a binary operation into its operands and operator.
+///
+/// The operands are borrowed the same way as the given kind, so it works for `&AstKind` and
+/// `&mut AstKind` alike.
+macro_rules! binary_operation {
+    ($kind:expr) => {
+        match $kind {
+            ExponentiationOp(l, r) => Some((l, "**", r)),
+            MultiplicationOp(l, r) => Some((l, "*", r)),
+            DivisionOp(l, r) => Some((l, "/", r)),
+            ModulusOp(l, r) => Some((l, "%", r)),
+            AdditionOp(l, r) => Some((l, "+", r)),
+            SubtractionOp(l, r) => Some((l, "-", r)),
+            LeftShiftOp(l, r) => Some((l, "<<", r)),
+            RightShiftOp(l, r) => Some((l, ">>", r)),
+            MatchOp(l, r) => Some((l, "MATCH", r)),
+            NotMatchOp(l, r) => Some((l, "NOT MATCH", r)),
+            SimilarityOp(l, r) => Some((l, "<>", r)),
+            LessThanOp(l, r) => Some((l, "<", r)),
+            GreaterThanOp(l, r) => Some((l, ">", r)),
+            LessThanEqualToOp(l, r) => Some((l, "<=", r)),
+            GreaterThanEqualToOp(l, r) => Some((l, ">=", r)),
+            InOp(l, r) => Some((l, "IN", r)),
+            NotInOp(l, r) => Some((l, "NOT IN", r)),
+            ContainsOp(l, r) => Some((l, "CONTAINS", r)),
+            NotContainsOp(l, r) => Some((l, "NOT CONTAINS", r)),
+            ContainsNoneOp(l, r) => Some((l, "CONTAINS NONE", r)),
+            ContainsAllOp(l, r) => Some((l, "CONTAINS ALL", r)),
+            ContainsAnyOp(l, r) => Some((l, "CONTAINS ANY", r)),
+            EqualToOp(l, r) => Some((l, "==", r)),
+            IsOp(l, r) => Some((l, "IS", r)),
+            IsNotOp(l, r) => Some((l, "IS NOT", r)),
+            BitwiseAndOp(l, r) => Some((l, "&", r)),
+            BitwiseXorOp(l, r) => Some((l, "^", r)),
+            BitwiseOrOp(l, r) => Some((l, "|", r)),
+            LogicalAndOp(l, r) => Some((l, "AND", r)),
+            LogicalOrOp(l, r) => Some((l, "OR", r)),
+            NullCoalesceOp(l, r) => Some((l, "?:", r)),
+            RangeOp(l, r) => Some((l, "..", r)),
+            RangeInclusiveOp(l, r) => Some((l, "..=", r)),
+            _ => None,
+        }
+    };
+}
+
+pub(crate) use binary_operation;
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::sema::sema::tests::analyze;
+
+    use super::*;
+
+    #[test]
+    fn test_sema_type_checking_operators() -> anyhow::Result<()> {
+        analyze(
+            r#"
+            LET $a TYPE u8 = 1;
+            LET $b TYPE f64 = 2.5;
+            LET $c = ($a + 1) * $b ** 2 % 3;
+            LET $d = NOT ($a < 10 AND $b >= 1.0) OR $a == NONE;
+            LET $e = ~$a << 2 | 1;
+            LET $f = 'a' + 'b';
+            LET $g = $f MATCH //^a//i AND $f <> 'ab' AND 'a' IN $f;
+            LET $h = [1, 2, 3] CONTAINS $a AND $a IN (1..=10);
+            LET $i = [1, 2][0] + -$b;
+            LET $j = (1, 'a')[1] + $f;
+            "#,
+        )??;
+
+        assert_eq!(
+            analyze("LET $a = 1 + 'a'")?,
+            Err(SemaError::InvalidOperandTypes {
+                operator: "+",
+                left: Type::Integer,
+                right: Type::String,
+                span: 9..16,
+            })
+        );
+
+        assert!(matches!(
+            analyze("LET $a = NOT 1")?,
+            Err(SemaError::InvalidOperandType { operator, found: Type::Integer, .. }) if operator == "NOT"
+        ));
+        assert!(matches!(
+            analyze("LET $a = -'a'")?,
+            Err(SemaError::InvalidOperandType {
+                found: Type::String,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("LET $a TYPE f32 = 1.5; LET $b = $a << 1")?,
+            Err(SemaError::InvalidOperandTypes {
+                left: Type::F32,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("LET $a = true AND 1")?,
+            Err(SemaError::InvalidOperandTypes { .. })
+        ));
+        assert!(matches!(
+            analyze("LET $a = 1 MATCH //a//")?,
+            Err(SemaError::InvalidOperandTypes { .. })
+        ));
+        assert!(matches!(
+            analyze("LET $a = 'a' IN [1, 2]")?,
+            Err(SemaError::InvalidOperandTypes { .. })
+        ));
+        assert!(matches!(
+            analyze("LET $a = (1, 'a')[2]")?,
+            Err(SemaError::InvalidIndex { .. })
+        ));
+        assert!(matches!(
+            analyze("LET $a = true['a']")?,
+            Err(SemaError::InvalidIndex {
+                subject: Type::Bool,
+                index: Type::String,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_checking_let_annotations() -> anyhow::Result<()> {
+        analyze(
+            r#"
+            LET $a TYPE u8 = 255;
+            LET $b TYPE i8 = -128;
+            LET $c TYPE u8? = NONE;
+            LET $d TYPE [string] = ['a', 'b'];
+            LET $e TYPE [u8 4] = b'abcd';
+            LET $f TYPE (string, u8) = ('a', 1);
+            DEFINE TABLE person FIELDS name TYPE string;
+            LET $g TYPE person = { name: 'a' };
+            LET $h TYPE u16 = $a;
+            "#,
+        )??;
+
+        assert_eq!(
+            analyze("LET $a TYPE string = 1")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::String,
+                found: Type::Integer,
+                span: 21..22,
+            })
+        );
+
+        assert!(matches!(
+            analyze("LET $a TYPE u8 = 256")?,
+            Err(SemaError::IntegerOutOfRange { value, r#type: Type::U8, .. }) if value == "256"
+        ));
+        assert!(matches!(
+            analyze("LET $a TYPE u8 = -1")?,
+            Err(SemaError::IntegerOutOfRange { value, .. }) if value == "-1"
+        ));
+        assert!(matches!(
+            analyze("LET $a TYPE [i8] = [1, 128]")?,
+            Err(SemaError::IntegerOutOfRange {
+                r#type: Type::I8,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("LET $a TYPE [u8 4] = [1, 2, 3]")?,
+            Err(SemaError::MismatchedTypes { .. })
+        ));
+        assert!(matches!(
+            analyze("LET $a TYPE u8 = 1.5")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::Float,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("LET $a TYPE u8 = 1; SET $a += 'a'")?,
+            Err(SemaError::InvalidOperandTypes { .. })
+        ));
+        assert!(matches!(
+            analyze("LET $a TYPE u8 = 1; SET $a = NONE")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::None,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_checking_update_fields() -> anyhow::Result<()> {
+        analyze(
+            r#"
+            DEFINE TABLE person FIELDS name TYPE string? ASSERT name != '', age TYPE u8 VALUE 0;
+            UPDATE person WHERE age > 18 SET age += 1, name = 'a';
+            UPDATE person:1 SET name = NONE, age = age ?: 0;
+            DEFINE TABLE animal SCHEMALESS;
+            UPDATE animal SET age = 'old';
+            "#,
+        )??;
+
+        assert_eq!(
+            analyze("DEFINE TABLE person FIELDS age TYPE u8; UPDATE person SET age = 'old'")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::String,
+                span: 64..69,
+            })
+        );
+
+        assert!(matches!(
+            analyze("DEFINE TABLE person FIELDS age TYPE u8?; UPDATE person SET age += 1")?,
+            Err(SemaError::InvalidOperandTypes { .. })
+        ));
+        assert!(matches!(
+            analyze("DEFINE TABLE person FIELDS age TYPE u8; UPDATE person:1 SET age *= 1.5")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::Float,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("DEFINE TABLE person FIELDS age TYPE u8; UPDATE person WHERE age SET age = 1")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::Bool,
+                found: Type::U8,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("DEFINE TABLE person FIELDS age TYPE u8 VALUE 'a'")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::String,
+                ..
+            })
+        ));
+        // Fields a flexible table does not declare take values of its catch-all type.
+        analyze("DEFINE TABLE t FLEXIBLE TYPE u8 FIELDS a TYPE string; UPDATE t SET b = 1")??;
+        assert!(matches!(
+            analyze("DEFINE TABLE t FLEXIBLE TYPE u8 FIELDS a TYPE string; UPDATE t SET b = 'x'")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::String,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("DEFINE TABLE person FIELDS age TYPE u8 ASSERT $value + 1")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::Bool,
+                found: Type::U8,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_checking_redefinitions() -> anyhow::Result<()> {
+        analyze(
+            r#"
+            DEFINE TABLE person FIELDS age TYPE u8;
+            REDEFINE TABLE person AS people FIELDS age TYPE string;
+            UPDATE people SET age = 'old';
+            REDEFINE PARAM IF EXISTS max_age TYPE u8 VALUE 10;
+            "#,
+        )??;
+
+        assert!(matches!(
+            analyze("DEFINE TABLE person FIELDS age TYPE u8; REDEFINE TABLE person FIELDS age AS years; UPDATE person SET years = 'old'")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::String,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("DEFINE PARAM max_age VALUE 1; REDEFINE PARAM max_age TYPE u8 VALUE 'a'")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::String,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_checking_enums() -> anyhow::Result<()> {
+        let schema = "DEFINE ENUM color VARIANTS red, green; DEFINE ENUM mood VARIANTS red, sad; \
+            DEFINE TABLE t FIELDS c TYPE color, n TYPE string;";
+        analyze(&format!(
+            "{schema} LET $c TYPE color = color::red; UPDATE t SET c = color::green; \
+            LET $b = $c == color::green"
+        ))??;
+
+        // Variants only fit where their own enum is expected.
+        for (source, expected, found) in [
+            (
+                "LET $c TYPE color = mood::sad",
+                Type::Named("color".into()),
+                Type::Named("mood".into()),
+            ),
+            (
+                "LET $c TYPE u8 = color::red",
+                Type::U8,
+                Type::Named("color".into()),
+            ),
+            (
+                "UPDATE t SET n = color::red",
+                Type::String,
+                Type::Named("color".into()),
+            ),
+        ] {
+            let result = analyze(&format!("{schema} {source}"))?;
+            assert!(
+                matches!(
+                    &result,
+                    Err(SemaError::MismatchedTypes { expected: e, found: f, .. })
+                        if *e == expected && *f == found
+                ),
+                "{source}: {result:?}"
+            );
+        }
+
+        assert!(matches!(
+            analyze(&format!("{schema} LET $b = color::red == mood::red"))?,
+            Err(SemaError::InvalidOperandTypes {
+                left: Type::Named(left),
+                right: Type::Named(right),
+                ..
+            }) if left == "color" && right == "mood"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_checking_conditions() -> anyhow::Result<()> {
+        analyze(
+            r#"
+            DEFINE TABLE person;
+            LET $a = 5;
+            WHILE $a > 0 DO
+                IF $a == 1 THEN
+                    SET $a -= 1;
+                ELSE IF $a IS 2 THEN
+                    SET $a -= 2;
+                END
+            END
+            FOR $b IN [1, 2] DO
+                IF $b > 1 THEN
+                    SELECT * FROM person WHERE $b < 2;
+                END
+            END
+            "#,
+        )??;
+
+        assert_eq!(
+            analyze("IF 1 THEN 2 END")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::Bool,
+                found: Type::Integer,
+                span: 3..4,
+            })
+        );
+
+        assert!(matches!(
+            analyze("WHILE 'a' DO BREAK END")?,
+            Err(SemaError::MismatchedTypes {
+                found: Type::String,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("IF true THEN 1 ELSE IF 2 THEN 3 END")?,
+            Err(SemaError::MismatchedTypes {
+                found: Type::Integer,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("DEFINE TABLE person; SELECT * FROM person WHERE 1")?,
+            Err(SemaError::MismatchedTypes {
+                found: Type::Integer,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("FOR $a IN true DO BREAK END")?,
+            Err(SemaError::NotIterable {
+                found: Type::Bool,
+                ..
+            })
+        ));
+
+        // The value of an `IF` has the type of its branches.
+        assert!(matches!(
+            analyze("LET $a TYPE u8 = IF true THEN 'x' ELSE 'y' END")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::String,
+                ..
+            })
+        ));
+
+        // Variables declared in a scope have the type the scope gives them.
+        assert!(matches!(
+            analyze("FOR $a IN ['a'] DO LET $b = $a + 1 END")?,
+            Err(SemaError::InvalidOperandTypes {
+                left: Type::String,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_checking_function_calls() -> anyhow::Result<()> {
+        let graph =
+            |source: &str| analyze(&format!("DEFINE TABLE person; DEFINE EDGE knows; {source}"));
+
+        graph(
+            r#"
+            LET $a = graph::pagerank(knows, damping = 0.5, iterations = 10);
+            LET $b = graph::degree(person:alice, direction = 'out') + 1;
+            LET $c = http::get('https://example.com');
+            DEFINE MODULE test WITH export function greet(name: string, times?: number): string { return name; } END;
+            LET $d = test::greet('alice') + '!';
+            LET $e = test::greet(times = 2, name = 'bob');
+            "#,
+        )??;
+
+        assert!(matches!(
+            graph("LET $a = graph::page_rank(knows)")?,
+            Err(SemaError::UndefinedFunction { name, suggestion: Some(suggestion), .. })
+                if name == "graph::page_rank" && suggestion == "graph::pagerank"
+        ));
+        assert!(matches!(
+            graph("LET $a = graph::pagerank(knows, dampening = 0.5)")?,
+            Err(SemaError::UnknownArgument { argument, suggestion: Some(suggestion), .. })
+                if argument == "dampening" && suggestion == "damping"
+        ));
+        assert!(matches!(
+            graph("LET $a = graph::components(knows, 1)")?,
+            Err(SemaError::TooManyArguments {
+                max: 1,
+                found: 2,
+                ..
+            })
+        ));
+        assert!(matches!(
+            graph("LET $a = graph::pagerank(knows, 0.5, damping = 0.5)")?,
+            Err(SemaError::DuplicateArgument { argument, .. }) if argument == "damping"
+        ));
+        assert!(matches!(
+            graph("LET $a = graph::shortest_path(person:alice, kind = knows)")?,
+            Err(SemaError::MissingArgument { param, .. }) if param == "target"
+        ));
+        assert!(matches!(
+            graph("LET $a = graph::pagerank(knows, iterations = 'many')")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U64,
+                found: Type::String,
+                ..
+            })
+        ));
+        assert!(matches!(
+            graph("LET $a = graph::pagerank(knows, iterations = -1)")?,
+            Err(SemaError::IntegerOutOfRange {
+                r#type: Type::U64,
+                ..
+            })
+        ));
+        assert!(matches!(
+            graph("LET $a TYPE string = graph::degree(person:alice)")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::String,
+                found: Type::I64,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_checking_defined_functions() -> anyhow::Result<()> {
+        analyze(
+            r#"
+            DEFINE FUNCTION discount($price TYPE f64, $rate TYPE f64?) TYPE f64 DO
+                IF $rate == NONE THEN
+                    RETURN $price
+                END
+                RETURN $price * 0.9
+            END;
+            LET $a TYPE f64 = fn::discount(10.0, NONE);
+            LET $b = fn::discount(rate = 0.5, price = 2.0) + 1.0;
+            LET $c = fn::defined_in_the_database(1);
+            DEFINE FUNCTION log($message TYPE string) DO RETURN END;
+            "#,
+        )??;
+
+        assert!(matches!(
+            analyze("DEFINE FUNCTION name() TYPE u8 DO RETURN 'zero' END")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::String,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("DEFINE FUNCTION name() TYPE u8 DO RETURN END")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::None,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("DEFINE FUNCTION name() TYPE u8 DO RETURN 256 END")?,
+            Err(SemaError::IntegerOutOfRange {
+                r#type: Type::U8,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze(
+                "DEFINE FUNCTION double($a TYPE u8) TYPE u8 DO RETURN $a * 2 END; fn::double('a')"
+            )?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::U8,
+                found: Type::String,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("DEFINE FUNCTION double($a TYPE u8) TYPE u8 DO RETURN $a * 2 END; fn::double()")?,
+            Err(SemaError::MissingArgument { param, .. }) if param == "a"
+        ));
+        assert!(matches!(
+            analyze("RETURN 1")?,
+            Err(SemaError::ReturnOutsideFunction { .. })
+        ));
+
+        // Every way through the body of a function that returns a value must return one.
+        analyze("DEFINE FUNCTION sign($a TYPE i8) TYPE i8 DO IF $a < 0 THEN RETURN -1 ELSE RETURN 1 END END")??;
+        analyze(
+            "DEFINE FUNCTION first($a TYPE [u8]) TYPE u8? DO FOR $b IN $a DO RETURN $b END END",
+        )??;
+        assert!(matches!(
+            analyze("DEFINE FUNCTION discount($price TYPE f64) TYPE f64 DO 1 END")?,
+            Err(SemaError::MissingReturn { function, r#type: Type::F64, .. })
+                if function == "fn::discount"
+        ));
+        assert!(matches!(
+            analyze(
+                "DEFINE FUNCTION sign($a TYPE i8) TYPE i8 DO IF $a < 0 THEN RETURN -1 END END"
+            )?,
+            Err(SemaError::MissingReturn { .. })
+        ));
+
+        // Removed functions can no longer be called, until they are defined again.
+        let discount = "DEFINE FUNCTION discount($price TYPE f64) TYPE f64 DO RETURN $price END";
+        analyze(&format!(
+            "{discount}; REMOVE FUNCTION discount; {discount}; fn::discount(1.0)"
+        ))??;
+        assert!(matches!(
+            analyze(&format!("{discount}; REMOVE FUNCTION discount; fn::discount(1.0)"))?,
+            Err(SemaError::UndefinedFunction { name, .. }) if name == "fn::discount"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_checking_temporal_arithmetic() -> anyhow::Result<()> {
+        analyze(
+            r#"
+            LET $start TYPE datetime = time::now();
+            LET $end TYPE datetime = $start + duration::weeks(2) - duration::hours(1);
+            LET $elapsed TYPE duration = $end - $start;
+            LET $half TYPE duration = $elapsed / 2 + 3 * duration::mins(5);
+            LET $day TYPE datetime = time::floor($end, duration::days(1));
+            LET $year TYPE i64 = time::year(time::parse('2024-05-01T12:00:00Z'));
+            "#,
+        )??;
+
+        assert!(matches!(
+            analyze("LET $a = time::now() + time::now()")?,
+            Err(SemaError::InvalidOperandTypes {
+                left: Type::Datetime,
+                right: Type::Datetime,
+                ..
+            })
+        ));
+        assert!(matches!(
+            analyze("LET $a = 2 / duration::secs(1)")?,
+            Err(SemaError::InvalidOperandTypes { .. })
+        ));
+        assert!(matches!(
+            analyze("LET $a = time::year(duration::days(1))")?,
+            Err(SemaError::MismatchedTypes {
+                expected: Type::Datetime,
+                found: Type::Duration,
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+}
@@ -1,12 +1,69 @@
-use crate::{ast::Ast, sema::SemaResult};
+use std::convert::Infallible;
+
+use crate::{
+    ast::{
+        walk_ast_mut, walk_select_transform_mut, Ast, AstKind::*, ElseIfPart, Field, SelectColumn,
+        SelectTransform, TypeSig, VisitorMut,
+    },
+    compiler::stack,
+    fmt::{FormatOptions, Formatter},
+    sema::{
+        arg_value_mut, DatabaseSchema, FunctionRegistry, FunctionSignature, SemaError, SemaResult,
+        SymbolMeta, Symbols, Type, DEFAULT_MAX_DEPTH,
+    },
+};
+
+use super::{
+    member_resolution::VariantMatch,
+    name_resolution::ast_as,
+    schema_tracking::SchemaTracker,
+    type_checking::{binary_operation, check_binary, check_index, key_name, table_name},
+};
 
 //--------------------------------------------------------------------------------------------------
 // Types
 //--------------------------------------------------------------------------------------------------
 
-/// A type inference implementation for the zeroql compiler.
-#[derive(Debug, Default)]
-pub struct TypeInferencer {}
+/// `TypeInferencer` pass tags every expression node with its type.
+///
+/// Variables declared without a type annotation get the type of their value, and the rows of a
+/// `SELECT` get an object type with a field for every column, so the shape of a result is known
+/// before the query runs.
+///
+/// Integer and float literals get their width from where they are used, e.g. `1` is a `u16` in
+/// `$a + 1` if `$a` is a `u16`. Literals that nothing decides the width of are `i64` and `f64`.
+///
+/// ## Note
+///
+/// This pass only reports integer literals that do not fit the type they are used as, e.g. `300`
+/// in `$a + 300` if `$a` is a `u8`. An operation whose operands do not fit it gets the
+/// [`Type::Any`] type, and [`TypeChecker`](super::TypeChecker) reports the error.
+#[derive(Debug)]
+pub struct TypeInferencer {
+    /// Symbol table for the current scope.
+    current_symbols: Symbols,
+
+    /// The tables, edges, types and enums known at the node being inferred.
+    schema: SchemaTracker,
+
+    /// The table bare identifiers refer to the fields of, e.g. in the columns of a `SELECT`.
+    current_table: Option<String>,
+
+    /// The signatures of the functions that can be called.
+    functions: FunctionRegistry,
+
+    /// The return type of the function whose body is being inferred, if any.
+    current_returns: Option<Type>,
+
+    /// How deeply the node being inferred is nested.
+    depth: usize,
+
+    /// How deeply nodes may nest before inference fails.
+    max_depth: usize,
+}
+
+/// Decides the widths of the literals that are left after inference.
+struct LiteralDefaulter;
 
 //--------------------------------------------------------------------------------------------------
 // Methods
@@ -15,15 +72,1215 @@ pub struct TypeInferencer {}
 impl TypeInferencer {
     /// Creates a new type inferencer.
     pub fn new() -> Self {
-        Self {}
+        Self {
+            current_symbols: Symbols::default(),
+            schema: SchemaTracker::default(),
+            current_table: None,
+            functions: FunctionRegistry::builtins(),
+            current_returns: None,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Sets how deeply nodes may nest before inference fails.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the signatures of the functions that can be called, in place of the built-ins.
+    pub fn with_functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    /// Sets the schema items that are already persisted by the database, whose fields give the
+    /// types of the values read from their records.
+    pub fn with_db_schema(mut self, db_schema: DatabaseSchema) -> Self {
+        self.schema = SchemaTracker::new(db_schema);
+        self
     }
 
     /// Analyzes the given AST.
-    pub fn analyze(&mut self, _ast: &mut Ast) -> SemaResult<()> {
+    pub fn analyze(&mut self, ast: &mut Ast) -> SemaResult<()> {
+        self.visit_ast(ast)?;
+
+        let Ok(()) = LiteralDefaulter.visit_ast(ast);
+        Ok(())
+    }
+
+    /// Infers the type of a node and tags the node with it.
+    fn infer(&mut self, ast: &mut Ast) -> SemaResult<Type> {
+        if self.depth >= self.max_depth {
+            return Err(SemaError::NestingTooDeep {
+                max_depth: self.max_depth,
+                span: ast.span.clone(),
+            });
+        }
+
+        self.depth += 1;
+        let result = stack::ensure_stack(|| self.infer_node(ast));
+        self.depth -= 1;
+
+        let r#type = result?;
+        ast.set_tag_type(r#type.clone());
+
+        Ok(r#type)
+    }
+
+    /// Infers the type of a single node, leaving the nodes it does not care about to the default
+    /// walk.
+    fn infer_node(&mut self, ast: &mut Ast) -> SemaResult<Type> {
+        self.schema.track(ast)?;
+
+        let span = ast.span.clone();
+        if let Some((left, operator, right)) = binary_operation!(&mut ast.kind) {
+            let mut left_type = self.infer(left)?;
+            let mut right_type = self.infer(right)?;
+
+            // Give literals the type of the other operand, e.g. `1` in `$a + 1`.
+            match operator {
+                "IN" | "NOT IN" => {
+                    if let Some(element) = right_type.element() {
+                        left_type = resolve_with(left, &left_type, &element)?;
+                    }
+                    let list = Type::List(Box::new(left_type.clone()));
+                    right_type = resolve_with(right, &right_type, &list)?;
+                }
+                "CONTAINS" | "NOT CONTAINS" => {
+                    if let Some(element) = left_type.element() {
+                        right_type = resolve_with(right, &right_type, &element)?;
+                    }
+                    let list = Type::List(Box::new(right_type.clone()));
+                    left_type = resolve_with(left, &left_type, &list)?;
+                }
+                "<<" | ">>" => {}
+                _ => {
+                    left_type = resolve_with(left, &left_type, &right_type)?;
+                    right_type = resolve_with(right, &right_type, &left_type)?;
+                }
+            }
+
+            return Ok(check_binary(operator, left_type, right_type, &span).unwrap_or_default());
+        }
+
+        let r#type = match &mut ast.kind {
+            // == Literals ==
+            IntegerLiteral(_) => Type::Integer,
+            FloatLiteral(_) => Type::Float,
+            StringLiteral(_) => Type::String,
+            ByteStringLiteral(_) => Type::ByteString,
+            RegexLiteral { .. } => Type::Regex,
+            BooleanLiteral(_) => Type::Bool,
+            NoneLiteral => Type::None,
+            ListLiteral(asts) => {
+                let mut element: Option<Type> = None;
+                for ast in asts.iter_mut() {
+                    let r#type = self.infer(ast)?;
+                    element = Some(match element {
+                        Some(element) => element.unify(r#type),
+                        None => r#type,
+                    });
+                }
+
+                let element = element.unwrap_or_default();
+                for ast in asts.iter_mut() {
+                    resolve(ast, &element)?;
+                }
+
+                Type::Array(Box::new(element), asts.len())
+            }
+            TupleLiteral(asts) => Type::Tuple(
+                asts.iter_mut()
+                    .map(|ast| self.infer(ast))
+                    .collect::<SemaResult<_>>()?,
+            ),
+            ObjectLiteral(entries) => Type::Object(
+                entries
+                    .iter_mut()
+                    .map(|(key, value)| Ok((key_name(key), self.infer(value)?)))
+                    .collect::<SemaResult<_>>()?,
+            ),
+
+            // == Names ==
+            Variable(name) => self
+                .current_symbols
+                .get(name)
+                .map(|meta| meta.r#type)
+                .unwrap_or_default(),
+            Identifier(name) => self.field_type(name),
+            ScopedIdentifier(parts) => match parts.as_slice() {
+                [Ast {
+                    kind: Identifier(name),
+                    ..
+                }, _]
+                    if self.schema.is_enum(name) =>
+                {
+                    Type::Named(name.to_string())
+                }
+                _ => {
+                    walk_ast_mut(self, ast)?;
+                    Type::Any
+                }
+            },
+            AliasOp { subject, .. } => self.infer(subject)?,
+            DotAccessOp { subject, field } => {
+                let subject = self.infer(subject)?;
+                self.member_type(&subject, field)
+            }
+            SafeNavigationAccessOp { subject, field } => {
+                let subject = match self.infer(subject)? {
+                    Type::Option(inner) => *inner,
+                    subject => subject,
+                };
+
+                match self.member_type(&subject, field) {
+                    Type::Any => Type::Any,
+                    Type::Option(inner) => Type::Option(inner),
+                    member => Type::Option(Box::new(member)),
+                }
+            }
+
+            // == Unary Operations ==
+            LogicalNotOp(operand) => {
+                self.infer(operand)?;
+                Type::Bool
+            }
+            BitwiseNotOp(operand) | PlusSignOp(operand) | MinusSignOp(operand) => {
+                self.infer(operand)?
+            }
+            UnboundedRangeOp(start) => match start {
+                Some(start) => Type::Range(Box::new(self.infer(start)?)),
+                None => Type::Range(Box::new(Type::Any)),
+            },
+            FunctionArg { value, .. } => self.infer(value)?,
+            FunctionCall { subject, args } => {
+                for arg in args.iter_mut() {
+                    self.infer(arg)?;
+                }
+
+                let signature = FunctionRegistry::name_of(subject)
+                    .and_then(|name| self.functions.get(&name))
+                    .cloned();
+
+                match signature {
+                    Some(signature) => {
+                        for (param, index) in signature.bind(args, &span).unwrap_or_default() {
+                            let value = arg_value_mut(&mut args[index]);
+                            let found = value.get_type().cloned().unwrap_or_default();
+                            resolve_with(value, &found, &param.r#type)?;
+                        }
+
+                        signature.returns
+                    }
+                    None => Type::Any,
+                }
+            }
+            Index { subject, index } => {
+                let subject_type = self.infer(subject)?;
+                let index_type = match self.infer(index)? {
+                    Type::Range(element) => {
+                        let element = resolve_with(index, &element, &Type::U64)?;
+                        Type::Range(Box::new(element))
+                    }
+                    index_type => resolve_with(index, &index_type, &Type::U64)?,
+                };
+
+                check_index(subject_type, index, index_type, &span).unwrap_or_default()
+            }
+
+            // == Variable Declarations ==
+            Let {
+                name,
+                r#type,
+                value,
+            } => {
+                let name = ast_as!(**name, Variable(name));
+                let r#type = self.infer_declaration(r#type.as_deref(), value)?;
+
+                self.current_symbols
+                    .insert(name.to_string(), SymbolMeta::with_type(r#type));
+
+                Type::None
+            }
+            DefineParam {
+                name,
+                r#type,
+                value,
+                ..
+            } => {
+                let name = ast_as!(**name, Identifier(name));
+                let r#type = self.infer_declaration(r#type.as_ref(), value)?;
+
+                self.current_symbols
+                    .insert(name.to_string(), SymbolMeta::with_type(r#type));
+
+                Type::None
+            }
+            RedefineParam {
+                name,
+                new_name,
+                r#type,
+                value,
+                ..
+            } => {
+                let name = ast_as!(**new_name.as_ref().unwrap_or(name), Identifier(name));
+                let r#type = match value {
+                    Some(value) => self.infer_declaration(r#type.as_ref(), value)?,
+                    None => r#type
+                        .as_ref()
+                        .map(Type::from_type_sig)
+                        .transpose()?
+                        .unwrap_or_default(),
+                };
+
+                self.current_symbols
+                    .insert(name.to_string(), SymbolMeta::with_type(r#type));
+
+                Type::None
+            }
+            For {
+                variable,
+                iterator,
+                body,
+            } => {
+                let name = ast_as!(**variable, Variable(name));
+                let iterator_type = self.infer(iterator)?;
+                let iterator_type = resolve(iterator, &iterator_type.defaulted())?;
+                let element = iterator_type.element().unwrap_or_default();
+
+                self.infer_scope(|i| {
+                    i.current_symbols
+                        .insert(name.to_string(), SymbolMeta::with_type(element));
+                    i.infer(body)
+                })?;
+
+                Type::None
+            }
+
+            // == Conditions ==
+            While { condition, body } => {
+                self.infer(condition)?;
+                self.infer_scope(|i| i.infer(body))?;
+                Type::None
+            }
+            If {
+                condition,
+                then,
+                else_ifs,
+                r#else,
+            } => {
+                self.infer(condition)?;
+                let mut r#type = self.infer_scope(|i| i.infer(then))?;
+
+                for ElseIfPart { condition, body } in else_ifs.iter_mut() {
+                    self.infer(condition)?;
+                    r#type = r#type.unify(self.infer_scope(|i| i.infer(body))?);
+                }
+
+                // A match on every variant of an enum has a value without an `ELSE`.
+                let conditions = std::iter::once(&**condition)
+                    .chain(else_ifs.iter().map(|part| &*part.condition))
+                    .collect::<Vec<_>>();
+                let exhaustive = VariantMatch::from_conditions(&conditions, &self.schema)
+                    .is_some_and(|variant_match| variant_match.missing(&self.schema).is_empty());
+
+                match r#else {
+                    Some(r#else) => r#type.unify(self.infer_scope(|i| i.infer(r#else))?),
+                    None => match r#type {
+                        _ if exhaustive => r#type,
+                        Type::Any | Type::None | Type::Option(_) => r#type,
+                        r#type => Type::Option(Box::new(r#type)),
+                    },
+                }
+            }
+            Program(asts) => {
+                let mut r#type = Type::None;
+                for ast in asts {
+                    r#type = self.infer(ast)?;
+                }
+
+                r#type
+            }
+
+            // == Assignments ==
+            Set {
+                variable, value, ..
+            } => {
+                let target = self.infer(variable)?;
+                let found = self.infer(value)?;
+                resolve_with(value, &found, &target)?;
+                Type::None
+            }
+            Update {
+                target,
+                where_guard,
+                column_ops,
+            } => {
+                self.infer(target)?;
+                self.with_table(table_name(target), |i| {
+                    if let Some(where_guard) = where_guard {
+                        i.infer(where_guard)?;
+                    }
+
+                    for (column, _, value) in column_ops {
+                        let target = i.infer(column)?;
+                        let found = i.infer(value)?;
+                        resolve_with(value, &found, &target)?;
+                    }
+
+                    Ok(())
+                })?;
+
+                Type::Any
+            }
+            Delete {
+                target,
+                where_guard,
+            } => {
+                self.infer(target)?;
+                let table = table_name(target);
+                if let Some(where_guard) = where_guard {
+                    self.with_table(table, |i| i.infer(where_guard))?;
+                }
+
+                Type::Any
+            }
+            Select {
+                fields,
+                omit,
+                from,
+                transforms,
+            } => {
+                self.with_table(None, |i| {
+                    from.iter_mut().try_for_each(|from| i.infer(from).map(drop))
+                })?;
+
+                let table = match &from[..] {
+                    [from] => table_name(from),
+                    _ => None,
+                };
+
+                self.with_table(table.clone(), |i| {
+                    for transform in transforms.iter_mut() {
+                        i.visit_select_transform(transform)?;
+                    }
+
+                    i.infer_row(table.as_deref(), fields, omit)
+                })?
+            }
+
+            // == Schema Definitions ==
+            DefineTable { name, fields, .. } | DefineEdge { name, fields, .. } => {
+                let name = ast_as!(**name, Identifier(name));
+                self.with_table(Some(name.to_string()), |i| {
+                    fields.iter_mut().try_for_each(|field| i.visit_field(field))
+                })?;
+
+                Type::None
+            }
+            DefineType { .. }
+            | DefineEnum { .. }
+            | RedefineTable { .. }
+            | RedefineEdge { .. }
+            | RedefineType { .. }
+            | RedefineEnum { .. }
+            | Use { .. } => Type::None,
+            DefineModule { name, block, .. } => {
+                let name = ast_as!(**name, Identifier(name));
+                let block = ast_as!(**block, ModuleBlock(block));
+                self.functions.register_module(name, block);
+
+                Type::None
+            }
+            DefineFunction {
+                name,
+                params,
+                r#type,
+                body,
+                ..
+            } => {
+                let name = ast_as!(**name, Identifier(name));
+                let signature = FunctionSignature::from_definition(name, params, r#type.as_ref())?;
+                self.functions.register(signature.clone());
+
+                let outer = self.current_returns.replace(signature.returns);
+                let result = self.infer_scope(|i| {
+                    for param in signature.params {
+                        i.current_symbols
+                            .insert(param.name, SymbolMeta::with_type(param.r#type));
+                    }
+
+                    i.infer(body)
+                });
+                self.current_returns = outer;
+                result?;
+
+                Type::None
+            }
+            Return(value) => {
+                if let Some(value) = value {
+                    let found = self.infer(value)?;
+                    if let Some(returns) = &self.current_returns {
+                        resolve_with(value, &found, returns)?;
+                    }
+                }
+
+                Type::None
+            }
+
+            _ => {
+                walk_ast_mut(self, ast)?;
+                Type::Any
+            }
+        };
+
+        Ok(r#type)
+    }
+
+    /// Infers the type of the value of a declaration and gets the type of the declared name,
+    /// which is the type annotation if there is one.
+    fn infer_declaration(
+        &mut self,
+        type_sig: Option<&TypeSig>,
+        value: &mut Ast,
+    ) -> SemaResult<Type> {
+        let found = self.infer(value)?;
+        let declared = match type_sig {
+            Some(type_sig) => Type::from_type_sig(type_sig)?,
+            None => found.defaulted(),
+        };
+
+        resolve_with(value, &found, &declared)?;
+        Ok(declared)
+    }
+
+    /// Infers the type of the rows of a `SELECT`.
+    ///
+    /// A row has a field for each column, named after its alias if it has one. The fields of
+    /// `OMIT` are left out. Rows with all fields of a table whose fields are not known have the
+    /// [`Type::Any`] type.
+    fn infer_row(
+        &mut self,
+        table: Option<&str>,
+        columns: &mut [SelectColumn],
+        omit: &[Ast],
+    ) -> SemaResult<Type> {
+        let mut row = Some(vec![]);
+
+        for column in columns {
+            let (name, r#type) = match column {
+                SelectColumn::Column(ast) if matches!(ast.kind, Wildcard) => {
+                    // The records of tables that take undeclared fields may have any fields.
+                    let fields = table
+                        .filter(|table| {
+                            self.schema
+                                .mode(table)
+                                .and_then(|mode| mode.catch_all())
+                                .is_none()
+                        })
+                        .and_then(|table| self.schema.object_type(table));
+                    match (fields, &mut row) {
+                        (Some(Type::Object(fields)), Some(row)) => row.extend(fields),
+                        _ => row = None,
+                    }
+                    continue;
+                }
+                SelectColumn::Column(ast) => match &mut ast.kind {
+                    AliasOp { subject, alias } => {
+                        let alias = key_name(alias);
+                        let r#type = self.infer(subject)?;
+                        ast.set_tag_type(r#type.clone());
+                        (alias, r#type)
+                    }
+                    _ => (column_name(ast), self.infer(ast)?),
+                },
+                SelectColumn::Fold { subject, alias } => {
+                    let name = match alias {
+                        Some(alias) => key_name(alias),
+                        None => column_name(subject),
+                    };
+                    (name, self.infer(subject)?)
+                }
+            };
+
+            if let Some(row) = &mut row {
+                row.retain(|(field, _)| *field != name);
+                row.push((name, r#type));
+            }
+        }
+
+        let row = match row {
+            Some(mut row) => {
+                let omit = omit.iter().map(column_name).collect::<Vec<_>>();
+                row.retain(|(field, _)| !omit.contains(field));
+                Type::Object(row)
+            }
+            None => Type::Any,
+        };
+
+        Ok(Type::List(Box::new(row)))
+    }
+
+    /// Infers in a new scope.
+    fn infer_scope<T>(&mut self, f: impl FnOnce(&mut Self) -> SemaResult<T>) -> SemaResult<T> {
+        self.current_symbols = Symbols::with_parent(&self.current_symbols);
+        let result = f(self);
+        self.current_symbols = self.current_symbols.parent().unwrap().clone();
+
+        result
+    }
+
+    /// Infers with bare identifiers referring to the fields of the given table.
+    fn with_table<T>(
+        &mut self,
+        table: Option<String>,
+        f: impl FnOnce(&mut Self) -> SemaResult<T>,
+    ) -> SemaResult<T> {
+        let outer = std::mem::replace(&mut self.current_table, table);
+        let result = f(self);
+        self.current_table = outer;
+
+        result
+    }
+
+    /// Gets the type of a field of the current table, or [`Type::Any`] if it is not known.
+    fn field_type(&self, name: &str) -> Type {
+        match &self.current_table {
+            Some(table) => self.schema.field_type(table, name),
+            None => Type::Any,
+        }
+    }
+
+    /// Gets the type of a member of a value, or [`Type::Any`] if it is not known.
+    ///
+    /// Accessing a member of a list or an array gets the list of the members of its elements.
+    fn member_type(&self, subject: &Type, field: &Ast) -> Type {
+        let Identifier(name) = field.kind else {
+            return Type::Any;
+        };
+
+        let subject = match subject {
+            Type::List(element) | Type::Array(element, _) => {
+                return match self.member_type(element, field) {
+                    Type::Any => Type::Any,
+                    member => Type::List(Box::new(member)),
+                };
+            }
+            Type::Named(table) => return self.schema.field_type(table, name),
+            subject => subject,
+        };
+
+        subject.field(name).cloned().unwrap_or_default()
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Decides the widths of the literals in a node of the `found` type from the `target` type it is
+/// used as, if the node's type is not decided yet and fits the target, and gets the new type.
+fn resolve_with(ast: &mut Ast, found: &Type, target: &Type) -> SemaResult<Type> {
+    if !found.is_literal() {
+        return Ok(found.clone());
+    }
+
+    resolve(ast, target)
+}
+
+/// Decides the widths of the literals in a node from the `target` type it is used as and tags the
+/// nodes with their new types.
+///
+/// Literals of another kind than the target are left for [`LiteralDefaulter`], and integer literals
+/// whose value the target can't hold are an error. The literals of an `IF` are decided in every
+/// branch it may take its value from.
+fn resolve(ast: &mut Ast, target: &Type) -> SemaResult<Type> {
+    let Some(found) = ast.get_type().cloned() else {
+        return Ok(Type::Any);
+    };
+
+    let target = match (&found, target) {
+        (Type::Option(_), _) => target,
+        (_, Type::Option(inner)) => inner,
+        _ => target,
+    };
+
+    if !found.is_literal()
+        || target.is_literal()
+        || *target == Type::Any
+        || !found.is_assignable_to(target)
+    {
+        return Ok(found);
+    }
+
+    if let Some((magnitude, negative)) = integer_literal(ast) {
+        if !target.fits_integer(magnitude, negative) {
+            return Err(SemaError::IntegerOutOfRange {
+                value: format!("{}{magnitude}", if negative { "-" } else { "" }),
+                r#type: target.clone(),
+                span: ast.span.clone(),
+            });
+        }
+    }
+
+    let resolved = match (&mut ast.kind, target) {
+        (IntegerLiteral(_) | FloatLiteral(_), target) => target.clone(),
+        (ListLiteral(asts), Type::Array(element, _) | Type::List(element)) => {
+            let element = asts
+                .iter_mut()
+                .map(|ast| resolve(ast, element))
+                .reduce(|left, right| Ok(left?.unify(right?)))
+                .unwrap_or_else(|| Ok(*element.clone()))?;
+
+            Type::Array(Box::new(element), asts.len())
+        }
+        (TupleLiteral(asts), Type::Tuple(elements)) => Type::Tuple(
+            asts.iter_mut()
+                .zip(elements)
+                .map(|(ast, element)| resolve(ast, element))
+                .collect::<SemaResult<_>>()?,
+        ),
+        (RangeOp(start, end) | RangeInclusiveOp(start, end), Type::Range(element)) => {
+            let start = resolve(start, element)?;
+            let end = resolve(end, element)?;
+            Type::Range(Box::new(start.unify(end)))
+        }
+        (UnboundedRangeOp(Some(start)), Type::Range(element)) => {
+            Type::Range(Box::new(resolve(start, element)?))
+        }
+        // The sign of a negative literal was checked with it, e.g. `-128` as an `i8`.
+        (MinusSignOp(operand), target) if integer_literal(operand).is_some() => {
+            operand.set_tag_type(target.clone());
+            target.clone()
+        }
+        (
+            AliasOp { subject: ast, .. }
+            | PlusSignOp(ast)
+            | MinusSignOp(ast)
+            | BitwiseNotOp(ast)
+            | LeftShiftOp(ast, _)
+            | RightShiftOp(ast, _),
+            target,
+        ) => resolve(ast, target)?,
+        (
+            ExponentiationOp(left, right)
+            | MultiplicationOp(left, right)
+            | DivisionOp(left, right)
+            | ModulusOp(left, right)
+            | AdditionOp(left, right)
+            | SubtractionOp(left, right)
+            | BitwiseAndOp(left, right)
+            | BitwiseXorOp(left, right)
+            | BitwiseOrOp(left, right)
+            | NullCoalesceOp(left, right),
+            target,
+        ) => {
+            let left = resolve(left, target)?;
+            let right = resolve(right, target)?;
+            Type::promote(&left, &right)
+        }
+        (Program(asts), target) => match asts.last_mut() {
+            Some(last) => resolve(last, target)?,
+            None => return Ok(found),
+        },
+        (
+            If {
+                then,
+                else_ifs,
+                r#else,
+                ..
+            },
+            target,
+        ) => {
+            // An `IF` without an `ELSE` may have no value, which its branches don't.
+            let branch = match target {
+                Type::Option(inner) => &**inner,
+                target => target,
+            };
+
+            let mut resolved = resolve(then, branch)?;
+            for ElseIfPart { body, .. } in else_ifs.iter_mut() {
+                resolved = resolved.unify(resolve(body, branch)?);
+            }
+
+            match (r#else, found) {
+                (Some(r#else), _) => resolved.unify(resolve(r#else, branch)?),
+                (None, Type::Option(_)) => Type::Option(Box::new(resolved)),
+                (None, _) => resolved,
+            }
+        }
+        _ => return Ok(found),
+    };
+
+    ast.set_tag_type(resolved.clone());
+    Ok(resolved)
+}
+
+/// Gets the magnitude of an integer literal and whether it is negated, e.g. `-1`.
+fn integer_literal(ast: &Ast) -> Option<(u128, bool)> {
+    match &ast.kind {
+        IntegerLiteral(magnitude) => Some((*magnitude, false)),
+        MinusSignOp(operand) => match operand.kind {
+            IntegerLiteral(magnitude) => Some((magnitude, true)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Gets the name of the field a column of a `SELECT` produces, e.g. `address.city`.
+fn column_name(ast: &Ast) -> String {
+    match &ast.kind {
+        Identifier(name) => name.to_string(),
+        AliasOp { alias, .. } => key_name(alias),
+        _ => Formatter::new(FormatOptions::default())
+            .format(ast)
+            .trim_end()
+            .to_string(),
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Default for TypeInferencer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> VisitorMut<'a> for TypeInferencer {
+    type Error = SemaError;
+
+    fn visit_ast(&mut self, ast: &mut Ast<'a>) -> SemaResult<()> {
+        self.infer(ast)?;
         Ok(())
     }
+
+    fn visit_field(&mut self, field: &mut Field<'a>) -> SemaResult<()> {
+        let r#type = Type::from_type_sig(&field.r#type)?;
+
+        if let Some(default) = &mut field.default {
+            let found = self.infer(default)?;
+            resolve_with(default, &found, &r#type)?;
+        }
+
+        self.infer_scope(|i| {
+            i.current_symbols
+                .insert("value".to_string(), SymbolMeta::with_type(r#type));
+            field
+                .assertions
+                .iter_mut()
+                .try_for_each(|assertion| i.infer(assertion).map(drop))
+        })
+    }
+
+    fn visit_select_transform(&mut self, transform: &mut SelectTransform<'a>) -> SemaResult<()> {
+        match transform {
+            SelectTransform::LimitTo(count) | SelectTransform::StartAt(count) => {
+                let found = self.infer(count)?;
+                resolve_with(count, &found, &Type::U64)?;
+                Ok(())
+            }
+            transform => walk_select_transform_mut(self, transform),
+        }
+    }
+}
+
+impl<'a> VisitorMut<'a> for LiteralDefaulter {
+    type Error = Infallible;
+
+    fn visit_ast(&mut self, ast: &mut Ast<'a>) -> Result<(), Infallible> {
+        if let Some(r#type) = ast.get_type().filter(|r#type| r#type.is_literal()) {
+            ast.set_tag_type(r#type.defaulted());
+        }
+
+        stack::ensure_stack(|| walk_ast_mut(self, ast))
+    }
 }
 
 //--------------------------------------------------------------------------------------------------
 // Tests
 //--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        parser::Parser,
+        sema::{arg_value, SchemaMeta, SemanticAnalyzer},
+    };
+
+    use super::*;
+
+    fn infer(source: &str) -> anyhow::Result<Vec<Ast<'_>>> {
+        // `$any` is a persisted param, whose type is only known at runtime.
+        let any = SchemaMeta::param(None, &Ast::new(0..4, NoneLiteral))?;
+        let db_schema = DatabaseSchema::new(HashMap::from([("any".parse()?, any)]));
+
+        let mut ast = Parser::new(source, 50).parse_program().into_result()?;
+        SemanticAnalyzer::new(&mut ast)
+            .with_db_schema(db_schema)
+            .analyze()?;
+        Ok(ast.kind.unwrap_program())
+    }
+
+    fn let_value<'b, 'a>(ast: &'b Ast<'a>) -> &'b Ast<'a> {
+        let Let { value, .. } = &ast.kind else {
+            panic!("let expected");
+        };
+
+        value
+    }
+
+    #[test]
+    fn test_sema_type_inference_variables() -> anyhow::Result<()> {
+        let statements = infer(
+            r#"
+            LET $a = 'a';
+            LET $b = $a + 'b';
+            LET $c TYPE u8? = 1;
+            $a;
+            $b;
+            $c;
+            $any;
+            "#,
+        )?;
+
+        assert_eq!(statements[0].get_type(), Some(&Type::None));
+        assert_eq!(statements[3].get_type(), Some(&Type::String));
+        assert_eq!(statements[4].get_type(), Some(&Type::String));
+        assert_eq!(
+            statements[5].get_type(),
+            Some(&Type::Option(Box::new(Type::U8)))
+        );
+        assert_eq!(statements[6].get_type(), Some(&Type::Any));
+
+        // Variables declared in a scope have the type the scope gives them.
+        let statements = infer("FOR $a IN ['a', 'b'] DO $a END; FOR $b IN 1..10 DO $b END")?;
+        let For { body, .. } = &statements[0].kind else {
+            panic!("for expected");
+        };
+        assert_eq!(body.get_type(), Some(&Type::String));
+
+        let For { iterator, body, .. } = &statements[1].kind else {
+            panic!("for expected");
+        };
+        assert_eq!(iterator.get_type(), Some(&Type::Range(Box::new(Type::I64))));
+        assert_eq!(body.get_type(), Some(&Type::I64));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_inference_literal_widths() -> anyhow::Result<()> {
+        let statements = infer(
+            r#"
+            LET $a TYPE u8 = 1 + 2;
+            LET $b = 1;
+            LET $c = 2.5;
+            LET $d TYPE u16 = 3;
+            $d + 1;
+            LET $e TYPE f32 = 1;
+            -1 * $e;
+            "#,
+        )?;
+
+        let AdditionOp(left, right) = &let_value(&statements[0]).kind else {
+            panic!("addition expected");
+        };
+        assert_eq!(let_value(&statements[0]).get_type(), Some(&Type::U8));
+        assert_eq!(left.get_type(), Some(&Type::U8));
+        assert_eq!(right.get_type(), Some(&Type::U8));
+
+        assert_eq!(let_value(&statements[1]).get_type(), Some(&Type::I64));
+        assert_eq!(let_value(&statements[2]).get_type(), Some(&Type::F64));
+
+        let AdditionOp(_, right) = &statements[4].kind else {
+            panic!("addition expected");
+        };
+        assert_eq!(statements[4].get_type(), Some(&Type::U16));
+        assert_eq!(right.get_type(), Some(&Type::U16));
+
+        assert_eq!(let_value(&statements[5]).get_type(), Some(&Type::F32));
+
+        let MultiplicationOp(left, _) = &statements[6].kind else {
+            panic!("multiplication expected");
+        };
+        let MinusSignOp(one) = &left.kind else {
+            panic!("minus sign expected");
+        };
+        assert_eq!(one.get_type(), Some(&Type::F32));
+
+        // Literals in conditions get the type of the field they are compared with.
+        let statements = infer(
+            r#"
+            DEFINE TABLE person FIELDS age TYPE u8;
+            UPDATE person WHERE age > 18 SET age = 1;
+            "#,
+        )?;
+
+        let Update {
+            where_guard,
+            column_ops,
+            ..
+        } = &statements[1].kind
+        else {
+            panic!("update expected");
+        };
+        let Some(GreaterThanOp(_, right)) = where_guard.as_ref().map(|guard| &guard.kind) else {
+            panic!("greater than expected");
+        };
+        assert_eq!(right.get_type(), Some(&Type::U8));
+        assert_eq!(column_ops[0].2.get_type(), Some(&Type::U8));
+
+        // Literal arguments get the type of their parameter, and calls the type they return.
+        let statements =
+            infer("DEFINE EDGE knows; LET $a = graph::pagerank(knows, iterations = 10);")?;
+
+        let value = let_value(&statements[1]);
+        let FunctionCall { args, .. } = &value.kind else {
+            panic!("function call expected");
+        };
+        let FunctionArg {
+            value: iterations, ..
+        } = &args[1].kind
+        else {
+            panic!("function argument expected");
+        };
+        assert_eq!(iterations.get_type(), Some(&Type::U64));
+        assert!(matches!(value.get_type(), Some(Type::List(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_inference_literal_ranges() -> anyhow::Result<()> {
+        // Literals only take the type of the other operand if it can hold them.
+        for source in [
+            "LET $x TYPE u8 = 1; LET $y = $x + 300;",
+            "LET $x TYPE i8 = 1; LET $y = $x - 200;",
+            "LET $x TYPE u8 = 1; LET $y = $x == 1000;",
+            "LET $x TYPE u16 = 1; LET $y = $x * -1;",
+            "LET $x TYPE u8 = IF true THEN 1 ELSE 256 END;",
+        ] {
+            let error = infer(source).unwrap_err();
+            assert!(
+                matches!(
+                    error.downcast_ref::<SemaError>(),
+                    Some(SemaError::IntegerOutOfRange { .. })
+                ),
+                "{source}: {error}"
+            );
+        }
+
+        let statements = infer("LET $x TYPE i8 = 1; $x - 100; $x == -128;")?;
+        assert_eq!(statements[1].get_type(), Some(&Type::I8));
+        let EqualToOp(_, right) = &statements[2].kind else {
+            panic!("equal to expected");
+        };
+        assert_eq!(right.get_type(), Some(&Type::I8));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_inference_if_branches() -> anyhow::Result<()> {
+        let statements = infer(
+            r#"
+            LET $a TYPE u8 = IF true THEN 1 ELSE IF false THEN 2 ELSE 3 END;
+            LET $b TYPE u8? = IF true THEN 1 END;
+            "#,
+        )?;
+
+        // The literals of every branch take the type the value of the `IF` is used as.
+        let value = let_value(&statements[0]);
+        let If {
+            then,
+            else_ifs,
+            r#else: Some(r#else),
+            ..
+        } = &value.kind
+        else {
+            panic!("if with else expected");
+        };
+        assert_eq!(value.get_type(), Some(&Type::U8));
+        assert_eq!(then.get_type(), Some(&Type::U8));
+        assert_eq!(else_ifs[0].body.get_type(), Some(&Type::U8));
+        assert_eq!(r#else.get_type(), Some(&Type::U8));
+
+        let value = let_value(&statements[1]);
+        let If { then, .. } = &value.kind else {
+            panic!("if expected");
+        };
+        assert_eq!(value.get_type(), Some(&Type::Option(Box::new(Type::U8))));
+        assert_eq!(then.get_type(), Some(&Type::U8));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_inference_defined_functions() -> anyhow::Result<()> {
+        let statements = infer(
+            r#"
+            DEFINE FUNCTION half($a TYPE f32) TYPE f32 DO
+                RETURN 1
+            END;
+            LET $b = fn::half(2);
+            "#,
+        )?;
+
+        let DefineFunction { body, .. } = &statements[0].kind else {
+            panic!("define function expected");
+        };
+        let Program(body) = &body.kind else {
+            panic!("program expected");
+        };
+        let Return(Some(value)) = &body[0].kind else {
+            panic!("return expected");
+        };
+        assert_eq!(value.get_type(), Some(&Type::F32));
+
+        let FunctionCall { args, .. } = &let_value(&statements[1]).kind else {
+            panic!("function call expected");
+        };
+        assert_eq!(let_value(&statements[1]).get_type(), Some(&Type::F32));
+        assert_eq!(arg_value(&args[0]).get_type(), Some(&Type::F32));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_inference_enums() -> anyhow::Result<()> {
+        let statements = infer(
+            r#"
+            DEFINE ENUM color VARIANTS red, green, blue;
+            LET $c = color::green;
+            IF $c IS color::red THEN 1 ELSE IF $c IS color::green OR $c IS color::blue THEN 2 END;
+            IF $c IS color::red THEN 1 END;
+            "#,
+        )?;
+
+        assert_eq!(
+            let_value(&statements[1]).get_type(),
+            Some(&Type::Named("color".into()))
+        );
+
+        // A match on every variant always has a value.
+        assert_eq!(statements[2].get_type(), Some(&Type::I64));
+        assert_eq!(
+            statements[3].get_type(),
+            Some(&Type::Option(Box::new(Type::I64)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_inference_literals() -> anyhow::Result<()> {
+        let statements = infer(
+            r#"
+            [1, 2, 3];
+            LET $a TYPE [u8] = [1, 2];
+            ('a', 1, true);
+            { name: 'a', age: 1, tags: [] };
+            [1, 'a'];
+            [1, 2][0];
+            "#,
+        )?;
+
+        assert_eq!(
+            statements[0].get_type(),
+            Some(&Type::Array(Box::new(Type::I64), 3))
+        );
+        assert_eq!(
+            let_value(&statements[1]).get_type(),
+            Some(&Type::Array(Box::new(Type::U8), 2))
+        );
+        assert_eq!(
+            statements[2].get_type(),
+            Some(&Type::Tuple(vec![Type::String, Type::I64, Type::Bool]))
+        );
+        assert_eq!(
+            statements[3].get_type(),
+            Some(&Type::Object(vec![
+                ("name".to_string(), Type::String),
+                ("age".to_string(), Type::I64),
+                ("tags".to_string(), Type::Array(Box::new(Type::Any), 0)),
+            ]))
+        );
+        assert_eq!(
+            statements[4].get_type(),
+            Some(&Type::Array(Box::new(Type::Any), 2))
+        );
+        assert_eq!(statements[5].get_type(), Some(&Type::I64));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sema_type_inference_select_rows() -> anyhow::Result<()> {
+        let statements = infer(
+            r#"
+            DEFINE TYPE address FIELDS city TYPE string;
+            DEFINE TABLE person FIELDS name TYPE string, age TYPE u8, address TYPE address;
+            SELECT * FROM person;
+            SELECT * OMIT age FROM person;
+            SELECT name AS n, age + 1 AS next, address.city FROM person:1;
+            SELECT fold count(age) AS total, fold name FROM person;
+            DEFINE TABLE animal SCHEMALESS FIELDS legs TYPE u8;
+            SELECT * FROM animal;
+            SELECT name FROM animal;
+            "#,
+        )?;
+
+        let rows = |ast: &Ast| match ast.get_type() {
+            Some(Type::List(row)) => (**row).clone(),
+            r#type => panic!("list expected, found {type:?}"),
+        };
+        let object = |fields: &[(&str, Type)]| {
+            Type::Object(
+                fields
+                    .iter()
+                    .map(|(name, r#type)| (name.to_string(), r#type.clone()))
+                    .collect(),
+            )
+        };
+
+        assert_eq!(
+            rows(&statements[2]),
+            object(&[
+                ("name", Type::String),
+                ("age", Type::U8),
+                ("address", Type::Named("address".to_string())),
+            ])
+        );
+        assert_eq!(
+            rows(&statements[3]),
+            object(&[
+                ("name", Type::String),
+                ("address", Type::Named("address".to_string())),
+            ])
+        );
+        assert_eq!(
+            rows(&statements[4]),
+            object(&[
+                ("n", Type::String),
+                ("next", Type::U8),
+                ("address.city", Type::String),
+            ])
+        );
+        assert_eq!(
+            rows(&statements[5]),
+            object(&[("total", Type::U64), ("name", Type::String)])
+        );
+        assert_eq!(rows(&statements[7]), Type::Any);
+        assert_eq!(rows(&statements[8]), object(&[("name", Type::Any)]));
+
+        Ok(())
+    }
+}
@@ -1,7 +1,37 @@
-use std::sync::Arc;
+use std::{collections::HashMap, fmt, sync::Arc};
 
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use zeroutils_path::Path;
 
+use crate::{
+    ast::{Ast, AstKind, EncodedAst, Field, RedefineField, TableMode, TypeSig},
+    fmt::{FormatOptions, Formatter},
+};
+
+use super::{
+    types::{type_name, unexpected},
+    SemaError, SemaResult, Type,
+};
+
+//--------------------------------------------------------------------------------------------------
+// Traits
+//--------------------------------------------------------------------------------------------------
+
+/// `SchemaSource` is where a [`DatabaseSchema`] looks up the schema items that are already
+/// persisted by the database.
+///
+/// The database implements it over its catalog, so the analyzer does not need to know how or where
+/// the catalog is stored.
+pub trait SchemaSource: fmt::Debug + Send + Sync {
+    /// Gets the schema of a persisted schema item.
+    fn get(&self, name: &Path) -> Option<SchemaMeta>;
+
+    /// Gets the names and schemas of the persisted schema items directly inside `database`.
+    fn items(&self, database: &Path) -> Vec<(String, SchemaMeta)>;
+}
+
 //--------------------------------------------------------------------------------------------------
 // Types
 //--------------------------------------------------------------------------------------------------
@@ -22,36 +52,149 @@ pub struct VersionedSchemaInner {
 }
 
 /// `SchemaMeta` represents the metadata of a schema.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Each variant carries the definition of the schema item, as given by the `DEFINE` statement that
+/// created it and the `REDEFINE` statements that changed it since. Expressions are kept as
+/// [`EncodedAst`]s, so a definition does not borrow from the source it was parsed from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SchemaMeta {
+    /// A namespace schema.
+    Namespace(),
+
     /// A database schema.
     Database(),
 
     /// A table schema.
-    Table(),
+    Table {
+        /// What the table does with the fields it does not declare.
+        mode: TableModeMeta,
+
+        /// The fields of the table.
+        fields: Vec<FieldMeta>,
+    },
 
     /// An edge schema.
-    Edge(),
+    Edge {
+        /// The fields of the edge.
+        fields: Vec<FieldMeta>,
+    },
 
     /// A type schema.
-    Type(),
+    Type {
+        /// The fields of the type.
+        fields: Vec<(String, Type)>,
+    },
 
     /// An enum schema.
-    Enum(),
+    Enum {
+        /// The variants of the enum, in declaration order.
+        variants: Vec<String>,
+    },
 
     /// An index schema.
-    Index(),
+    Index {
+        /// The table the index belongs to.
+        table: String,
+
+        /// The columns of the index.
+        columns: Vec<String>,
+
+        /// The kind of the index.
+        kind: IndexKind,
+    },
 
     /// A module schema.
-    Module(),
+    Module {
+        /// The names of the functions the module exports.
+        exports: Vec<String>,
+
+        /// The source of the module block.
+        source: String,
+    },
 
     /// A param schema.
-    Param(),
+    Param {
+        /// The declared type of the param.
+        r#type: Option<Type>,
+
+        /// The value of the param.
+        value: EncodedAst,
+    },
+
+    /// A function schema.
+    Function {
+        /// The params of the function and their types.
+        params: Vec<(String, Type)>,
+
+        /// The type of the values the function returns.
+        r#type: Option<Type>,
+
+        /// The body of the function.
+        body: EncodedAst,
+    },
+
+    /// A schema item that was renamed by a `REDEFINE` to the given path.
+    ///
+    /// These only live in a [`VersionedSchema`] and are never persisted.
+    #[serde(skip)]
+    Renamed(Path),
+}
+
+/// `FieldMeta` is a field of a table or edge schema.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldMeta {
+    /// The name of the field.
+    pub name: String,
+
+    /// The type of the field.
+    pub r#type: Type,
+
+    /// The value the field gets when a write does not give one.
+    pub default: Option<EncodedAst>,
+
+    /// The assertions values of the field must pass.
+    pub assertions: Vec<EncodedAst>,
+
+    /// Whether the field cannot change once it is written.
+    pub readonly: bool,
+
+    /// Whether no two records may have the same value for the field.
+    pub unique: bool,
+}
+
+/// `TableModeMeta` is what a table schema does with the fields a record has but the table does not
+/// declare.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+pub enum TableModeMeta {
+    /// Undeclared fields are rejected.
+    #[default]
+    Schemafull,
+
+    /// Undeclared fields are stored as they are given.
+    Schemaless,
+
+    /// Undeclared fields are stored as values of the catch-all type.
+    Flexible(Type),
+}
+
+/// `IndexKind` is how an index is built.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+pub enum IndexKind {
+    /// A regular index over the values of the indexed columns.
+    #[default]
+    Standard,
+
+    /// An index built by the function call given with `WITH`, e.g. `index::fulltext(...)`.
+    Function(EncodedAst),
 }
 
 /// `DatabaseSchema` represents the schema of a database.
-#[derive(Debug, Clone, PartialEq, Default)]
-pub struct DatabaseSchema {}
+///
+/// It is a view over a [`SchemaSource`]. Without one, no schema item is persisted.
+#[derive(Debug, Clone, Default)]
+pub struct DatabaseSchema {
+    source: Option<Arc<dyn SchemaSource>>,
+}
 
 //--------------------------------------------------------------------------------------------------
 // Methods
@@ -111,17 +254,509 @@ impl VersionedSchema {
     }
 }
 
+impl SchemaMeta {
+    /// Creates the schema of a `DEFINE TABLE` from its mode and fields.
+    pub fn table(mode: &TableMode, fields: &[Field]) -> SemaResult<Self> {
+        Ok(Self::Table {
+            mode: TableModeMeta::from_mode(mode)?,
+            fields: fields
+                .iter()
+                .map(FieldMeta::from_field)
+                .collect::<SemaResult<_>>()?,
+        })
+    }
+
+    /// Creates the schema of a `DEFINE EDGE` from its fields.
+    pub fn edge(fields: &[Field]) -> SemaResult<Self> {
+        Ok(Self::Edge {
+            fields: fields
+                .iter()
+                .map(FieldMeta::from_field)
+                .collect::<SemaResult<_>>()?,
+        })
+    }
+
+    /// Creates the schema of a `DEFINE TYPE` from its fields.
+    pub fn r#type(fields: &[(Ast, TypeSig)]) -> SemaResult<Self> {
+        Ok(Self::Type {
+            fields: typed_names(fields)?,
+        })
+    }
+
+    /// Creates the schema of a `DEFINE ENUM` from its variants.
+    pub fn r#enum(variants: &[Ast]) -> SemaResult<Self> {
+        Ok(Self::Enum {
+            variants: variants.iter().map(type_name).collect::<SemaResult<_>>()?,
+        })
+    }
+
+    /// Creates the schema of a `DEFINE INDEX` from its table, columns and `WITH` function.
+    pub fn index(table: &Ast, columns: &[Ast], function: Option<&Ast>) -> SemaResult<Self> {
+        Ok(Self::Index {
+            table: type_name(table)?,
+            columns: columns.iter().map(column_name).collect(),
+            kind: match function {
+                Some(function) => IndexKind::Function(encode(function)?),
+                None => IndexKind::Standard,
+            },
+        })
+    }
+
+    /// Creates the schema of a `DEFINE MODULE` from its module block.
+    pub fn module(block: &Ast) -> SemaResult<Self> {
+        let AstKind::ModuleBlock(source) = block.kind else {
+            return Err(unexpected(block));
+        };
+
+        Ok(Self::Module {
+            exports: MODULE_FUNCTION_REGEX
+                .captures_iter(source)
+                .map(|captures| captures[1].to_string())
+                .collect(),
+            source: source.to_string(),
+        })
+    }
+
+    /// Creates the schema of a `DEFINE PARAM` from its type and value.
+    pub fn param(r#type: Option<&TypeSig>, value: &Ast) -> SemaResult<Self> {
+        Ok(Self::Param {
+            r#type: r#type.map(Type::from_type_sig).transpose()?,
+            value: encode(value)?,
+        })
+    }
+
+    /// Creates the schema of a `DEFINE FUNCTION` from its params, return type and body.
+    pub fn function(
+        params: &[(Ast, TypeSig)],
+        r#type: Option<&TypeSig>,
+        body: &Ast,
+    ) -> SemaResult<Self> {
+        Ok(Self::Function {
+            params: typed_names(params)?,
+            r#type: r#type.map(Type::from_type_sig).transpose()?,
+            body: encode(body)?,
+        })
+    }
+
+    /// Creates the schema a `DEFINE` statement defines, or `None` if the statement is not a
+    /// `DEFINE`.
+    pub fn from_definition(ast: &Ast) -> SemaResult<Option<Self>> {
+        use AstKind::*;
+
+        let meta = match &ast.kind {
+            DefineNamespace { .. } => Self::Namespace(),
+            DefineDatabase { .. } => Self::Database(),
+            DefineTable { mode, fields, .. } => Self::table(mode, fields)?,
+            DefineEdge { fields, .. } => Self::edge(fields)?,
+            DefineType { fields, .. } => Self::r#type(fields)?,
+            DefineEnum { variants, .. } => Self::r#enum(variants)?,
+            DefineIndex {
+                table,
+                columns,
+                function,
+                ..
+            } => Self::index(table, columns, function.as_deref())?,
+            DefineModule { block, .. } => Self::module(block)?,
+            DefineParam { r#type, value, .. } => Self::param(r#type.as_ref(), value)?,
+            DefineFunction {
+                params,
+                r#type,
+                body,
+                ..
+            } => Self::function(params, r#type.as_ref(), body)?,
+            _ => return Ok(None),
+        };
+
+        Ok(Some(meta))
+    }
+
+    /// Gets the name of the kind of schema item, e.g. `table`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Namespace() => "namespace",
+            Self::Database() => "database",
+            Self::Table { .. } => "table",
+            Self::Edge { .. } => "edge",
+            Self::Type { .. } => "type",
+            Self::Enum { .. } => "enum",
+            Self::Index { .. } => "index",
+            Self::Module { .. } => "module",
+            Self::Param { .. } => "param",
+            Self::Function { .. } => "function",
+            Self::Renamed(_) => "renamed",
+        }
+    }
+
+    /// Gets the object type of the fields of a table, edge or type schema.
+    pub fn object_type(&self) -> Option<Type> {
+        match self {
+            Self::Table { fields, .. } | Self::Edge { fields } => Some(Type::Object(
+                fields
+                    .iter()
+                    .map(|field| (field.name.clone(), field.r#type.clone()))
+                    .collect(),
+            )),
+            Self::Type { fields } => Some(Type::Object(fields.clone())),
+            _ => None,
+        }
+    }
+
+    /// Gets the mode of a table schema. Edges and types reject the fields they do not declare, as
+    /// schemafull tables do.
+    pub fn mode(&self) -> TableModeMeta {
+        match self {
+            Self::Table { mode, .. } => mode.clone(),
+            _ => TableModeMeta::Schemafull,
+        }
+    }
+
+    /// Gets the `DEFINE` statement that defines the schema item as it is now, named `name`.
+    ///
+    /// The statement defines the item in the current database, so it can be run after a `USE` of
+    /// the database the item is in. Renamed items have no definition.
+    pub fn definition(&self, name: &str) -> SemaResult<Option<String>> {
+        let name = identifier(name);
+        let definition = match self {
+            Self::Namespace() => format!("DEFINE NAMESPACE {name}"),
+            Self::Database() => format!("DEFINE DATABASE {name}"),
+            Self::Table { fields, .. } | Self::Edge { fields } => {
+                let object = match self {
+                    Self::Table { .. } => "TABLE",
+                    _ => "EDGE",
+                };
+
+                let mut definition = format!("DEFINE {object} {name}");
+                match self.mode() {
+                    TableModeMeta::Schemafull => {}
+                    TableModeMeta::Schemaless => definition.push_str(" SCHEMALESS"),
+                    TableModeMeta::Flexible(Type::Any) => definition.push_str(" FLEXIBLE"),
+                    TableModeMeta::Flexible(r#type) => {
+                        definition.push_str(&format!(" FLEXIBLE TYPE {type}"))
+                    }
+                }
+
+                if !fields.is_empty() {
+                    let fields = fields
+                        .iter()
+                        .map(FieldMeta::definition)
+                        .collect::<SemaResult<Vec<_>>>()?;
+                    definition.push_str(&format!(" FIELDS {}", fields.join(", ")));
+                }
+
+                definition
+            }
+            Self::Type { fields } => {
+                let mut definition = format!("DEFINE TYPE {name}");
+                if !fields.is_empty() {
+                    let fields = fields
+                        .iter()
+                        .map(|(field, r#type)| format!("{} TYPE {type}", identifier(field)))
+                        .collect::<Vec<_>>();
+                    definition.push_str(&format!(" FIELDS {}", fields.join(", ")));
+                }
+
+                definition
+            }
+            Self::Enum { variants } => {
+                let variants = variants
+                    .iter()
+                    .map(|variant| identifier(variant))
+                    .collect::<Vec<_>>();
+                format!("DEFINE ENUM {name} VARIANTS {}", variants.join(", "))
+            }
+            Self::Index {
+                table,
+                columns,
+                kind,
+            } => {
+                let mut definition = format!(
+                    "DEFINE INDEX {name} ON TABLE {} FIELDS {}",
+                    identifier(table),
+                    columns.join(", ")
+                );
+                if let IndexKind::Function(function) = kind {
+                    definition.push_str(&format!(" WITH {}", decode(function)?));
+                }
+
+                definition
+            }
+            Self::Module { source, .. } => format!("DEFINE MODULE {name} WITH{source}END"),
+            Self::Param { r#type, value } => {
+                let mut definition = format!("DEFINE PARAM {name}");
+                if let Some(r#type) = r#type {
+                    definition.push_str(&format!(" TYPE {type}"));
+                }
+
+                definition.push_str(&format!(" VALUE {}", decode(value)?));
+                definition
+            }
+            Self::Function {
+                params,
+                r#type,
+                body,
+            } => {
+                let params = params
+                    .iter()
+                    .map(|(param, r#type)| {
+                        let param = source(&Ast::new(0..0, AstKind::Variable(param)));
+                        format!("{param} TYPE {type}")
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut definition = format!("DEFINE FUNCTION {name}({})", params.join(", "));
+                if let Some(r#type) = r#type {
+                    definition.push_str(&format!(" TYPE {type}"));
+                }
+
+                definition.push_str(&format!(" DO {} END", decode(body)?));
+                definition
+            }
+            Self::Renamed(_) => return Ok(None),
+        };
+
+        Ok(Some(definition))
+    }
+
+    /// Renames and retypes the fields of a table, edge or type schema as a `REDEFINE` does.
+    /// Fields that are not there yet are added.
+    pub fn redefine_fields(&mut self, redefined: &[RedefineField]) -> SemaResult<()> {
+        for field in redefined {
+            let name = type_name(&field.name)?;
+            let new_name = field.new_name.as_deref().map(type_name).transpose()?;
+            let r#type = field.r#type.as_ref().map(Type::from_type_sig).transpose()?;
+
+            match self {
+                Self::Table { fields, .. } | Self::Edge { fields } => {
+                    let index = match fields.iter().position(|existing| existing.name == name) {
+                        Some(index) => index,
+                        None => {
+                            fields.push(FieldMeta::new(name));
+                            fields.len() - 1
+                        }
+                    };
+
+                    if let Some(new_name) = new_name {
+                        fields[index].name = new_name;
+                    }
+
+                    if let Some(r#type) = r#type {
+                        fields[index].r#type = r#type;
+                    }
+                }
+                Self::Type { fields } => {
+                    let index = match fields.iter().position(|(existing, _)| *existing == name) {
+                        Some(index) => index,
+                        None => {
+                            fields.push((name, Type::Any));
+                            fields.len() - 1
+                        }
+                    };
+
+                    if let Some(new_name) = new_name {
+                        fields[index].0 = new_name;
+                    }
+
+                    if let Some(r#type) = r#type {
+                        fields[index].1 = r#type;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the variants of an enum schema with the ones of a `REDEFINE ENUM`, e.g.
+    /// `VARIANTS red AS crimson, green, blue`.
+    ///
+    /// Stored values of an enum are the positions of their variants, so the variants the enum
+    /// already has must stay where they are. They can be renamed, and new ones added after them.
+    pub fn redefine_variants(&mut self, redefined: &[(Ast, Option<Ast>)]) -> SemaResult<()> {
+        if let Self::Enum { variants } = self {
+            for (index, variant) in variants.iter().enumerate() {
+                let found = redefined.get(index);
+                if found.map(|(name, _)| type_name(name)).transpose()?.as_ref() != Some(variant) {
+                    return Err(SemaError::MovedVariant {
+                        variant: variant.clone(),
+                        span: found
+                            .or(redefined.last())
+                            .map(|(name, _)| name.span.clone())
+                            .unwrap_or_default(),
+                    });
+                }
+            }
+
+            *variants = redefined
+                .iter()
+                .map(|(name, new_name)| type_name(new_name.as_ref().unwrap_or(name)))
+                .collect::<SemaResult<_>>()?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the columns and the `WITH` function of an index schema with the ones of a
+    /// `REDEFINE INDEX` that gives them.
+    pub fn redefine_index(
+        &mut self,
+        redefined_columns: Option<&[Ast]>,
+        function: Option<&Ast>,
+    ) -> SemaResult<()> {
+        if let Self::Index { columns, kind, .. } = self {
+            if let Some(redefined_columns) = redefined_columns {
+                *columns = redefined_columns.iter().map(column_name).collect();
+            }
+
+            if let Some(function) = function {
+                *kind = IndexKind::Function(encode(function)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the type and the value of a param schema with the ones of a `REDEFINE PARAM`
+    /// that gives them.
+    pub fn redefine_param(
+        &mut self,
+        redefined_type: Option<&TypeSig>,
+        redefined_value: Option<&Ast>,
+    ) -> SemaResult<()> {
+        if let Self::Param { r#type, value } = self {
+            if let Some(redefined_type) = redefined_type {
+                *r#type = Some(Type::from_type_sig(redefined_type)?);
+            }
+
+            if let Some(redefined_value) = redefined_value {
+                *value = encode(redefined_value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the changes of a `REDEFINE` statement to the schema.
+    ///
+    /// The rename of the schema item itself is left to the caller, since the schema does not know
+    /// its own name.
+    pub fn redefine(&mut self, ast: &Ast) -> SemaResult<()> {
+        match &ast.kind {
+            AstKind::RedefineTable { fields, .. }
+            | AstKind::RedefineEdge { fields, .. }
+            | AstKind::RedefineType { fields, .. } => self.redefine_fields(fields),
+            AstKind::RedefineEnum {
+                variants: Some(variants),
+                ..
+            } => self.redefine_variants(variants),
+            AstKind::RedefineIndex {
+                columns, function, ..
+            } => self.redefine_index(columns.as_deref(), function.as_deref()),
+            AstKind::RedefineParam { r#type, value, .. } => {
+                self.redefine_param(r#type.as_ref(), value.as_deref())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FieldMeta {
+    /// Creates a field of any type, without a default, assertions or flags.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            r#type: Type::Any,
+            default: None,
+            assertions: vec![],
+            readonly: false,
+            unique: false,
+        }
+    }
+
+    /// Creates the schema of a field of a `DEFINE TABLE` or `DEFINE EDGE`.
+    pub fn from_field(field: &Field) -> SemaResult<Self> {
+        Ok(Self {
+            name: type_name(&field.name)?,
+            r#type: Type::from_type_sig(&field.r#type)?,
+            default: field.default.as_deref().map(encode).transpose()?,
+            assertions: field
+                .assertions
+                .iter()
+                .map(encode)
+                .collect::<SemaResult<_>>()?,
+            readonly: field.readonly,
+            unique: field.unique,
+        })
+    }
+
+    /// Gets the field as it is written in the `FIELDS` clause of a `DEFINE`.
+    fn definition(&self) -> SemaResult<String> {
+        let mut definition = format!("{} TYPE {}", identifier(&self.name), self.r#type);
+        for assertion in &self.assertions {
+            definition.push_str(&format!(" ASSERT {}", decode(assertion)?));
+        }
+
+        if let Some(default) = &self.default {
+            definition.push_str(&format!(" VALUE {}", decode(default)?));
+        }
+
+        if self.unique {
+            definition.push_str(" UNIQUE");
+        }
+
+        if self.readonly {
+            definition.push_str(" READONLY");
+        }
+
+        Ok(definition)
+    }
+}
+
+impl TableModeMeta {
+    /// Creates the mode of a table from the mode of its `DEFINE TABLE`.
+    pub fn from_mode(mode: &TableMode) -> SemaResult<Self> {
+        Ok(match mode {
+            TableMode::Schemafull => Self::Schemafull,
+            TableMode::Schemaless => Self::Schemaless,
+            TableMode::Flexible(None) => Self::Flexible(Type::Any),
+            TableMode::Flexible(Some(r#type)) => Self::Flexible(Type::from_type_sig(r#type)?),
+        })
+    }
+
+    /// Gets the type of the fields a table does not declare, or `None` if it rejects them.
+    pub fn catch_all(&self) -> Option<Type> {
+        match self {
+            Self::Schemafull => None,
+            Self::Schemaless => Some(Type::Any),
+            Self::Flexible(r#type) => Some(r#type.clone()),
+        }
+    }
+}
+
 impl DatabaseSchema {
+    /// Creates a new `DatabaseSchema` that looks up schema items in `source`.
+    pub fn new(source: impl SchemaSource + 'static) -> Self {
+        Self {
+            source: Some(Arc::new(source)),
+        }
+    }
+
     /// Gets the schema of a database entity.
-    pub fn get(&self, _name: &Path) -> Option<SchemaMeta> {
-        // TODO: Implement
-        None
+    pub fn get(&self, name: &Path) -> Option<SchemaMeta> {
+        self.source.as_ref().and_then(|source| source.get(name))
     }
 
     /// Checks if a schema contains a schema entity.
-    pub fn contains(&self, _name: &Path) -> bool {
-        // TODO: Implement
-        false
+    pub fn contains(&self, name: &Path) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Gets the names and schemas of the schema items directly inside `database`.
+    pub fn items(&self, database: &Path) -> Vec<(String, SchemaMeta)> {
+        self.source
+            .as_ref()
+            .map(|source| source.items(database))
+            .unwrap_or_default()
     }
 }
 
@@ -129,6 +764,35 @@ impl DatabaseSchema {
 // Trait Implementations
 //--------------------------------------------------------------------------------------------------
 
+impl SchemaSource for HashMap<Path, SchemaMeta> {
+    fn get(&self, name: &Path) -> Option<SchemaMeta> {
+        HashMap::get(self, name).cloned()
+    }
+
+    fn items(&self, database: &Path) -> Vec<(String, SchemaMeta)> {
+        let database = database.to_string();
+        let database = database.trim_start_matches('/');
+        self.iter()
+            .filter_map(|(path, meta)| {
+                let path = path.to_string();
+                let path = path.trim_start_matches('/');
+                let (parent, name) = path.rsplit_once('/').unwrap_or(("", path));
+                (parent == database).then(|| (name.to_string(), meta.clone()))
+            })
+            .collect()
+    }
+}
+
+impl PartialEq for DatabaseSchema {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.source, &other.source) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
 impl Clone for VersionedSchema {
     fn clone(&self) -> Self {
         Self {
@@ -136,3 +800,136 @@ impl Clone for VersionedSchema {
         }
     }
 }
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+fn typed_names(fields: &[(Ast, TypeSig)]) -> SemaResult<Vec<(String, Type)>> {
+    fields
+        .iter()
+        .map(|(name, r#type)| {
+            let name = match name.kind {
+                AstKind::Variable(name) => name.to_string(),
+                _ => type_name(name)?,
+            };
+
+            Ok((name, Type::from_type_sig(r#type)?))
+        })
+        .collect()
+}
+
+fn column_name(column: &Ast) -> String {
+    match column.kind {
+        AstKind::Identifier(name) => name.to_string(),
+        _ => source(column),
+    }
+}
+
+fn identifier(name: &str) -> String {
+    source(&Ast::new(0..0, AstKind::Identifier(name)))
+}
+
+fn source(ast: &Ast) -> String {
+    Formatter::new(FormatOptions::default())
+        .format(ast)
+        .trim_end()
+        .to_string()
+}
+
+fn decode(encoded: &EncodedAst) -> SemaResult<String> {
+    let ast = encoded
+        .decode()
+        .map_err(|error| SemaError::UnexpectedAstKind {
+            kind: error.to_string(),
+            span: 0..0,
+        })?;
+
+    Ok(source(&ast))
+}
+
+fn encode(ast: &Ast) -> SemaResult<EncodedAst> {
+    EncodedAst::encode(ast).map_err(|error| SemaError::UnexpectedAstKind {
+        kind: error.to_string(),
+        span: ast.span.clone(),
+    })
+}
+
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+lazy_static! {
+    static ref MODULE_FUNCTION_REGEX: Regex =
+        Regex::new(r"(?m)^\s*export\s+(?:async\s+)?function\s+([A-Za-z_$][A-Za-z0-9_$]*)").unwrap();
+}
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_sema_schema_definition_round_trip() -> anyhow::Result<()> {
+        let definitions = [
+            ("acme", "DEFINE NAMESPACE acme"),
+            ("shop", "DEFINE DATABASE shop"),
+            (
+                "person",
+                "DEFINE TABLE person FIELDS name TYPE string ASSERT $value != '' READONLY, email TYPE string? UNIQUE, age TYPE u8 VALUE 0",
+            ),
+            ("document", "DEFINE TABLE document SCHEMALESS"),
+            ("note", "DEFINE TABLE note FLEXIBLE"),
+            (
+                "event",
+                "DEFINE TABLE event FLEXIBLE TYPE string FIELDS starts TYPE u64",
+            ),
+            ("knows", "DEFINE EDGE knows"),
+            ("coords", "DEFINE TYPE coords FIELDS lat TYPE f64, lng TYPE f64"),
+            ("color", "DEFINE ENUM color VARIANTS red, green, blue"),
+            (
+                "idx_name",
+                "DEFINE INDEX idx_name ON TABLE person FIELDS name, age",
+            ),
+            (
+                "rate",
+                "DEFINE PARAM rate TYPE f64 VALUE 0.9",
+            ),
+            (
+                "greetings",
+                "DEFINE MODULE greetings WITH function greet(name) { return name; } END",
+            ),
+            (
+                "discount",
+                "DEFINE FUNCTION discount($price TYPE f64) TYPE f64 DO RETURN $price * 0.9 END",
+            ),
+        ];
+
+        for (name, definition) in definitions {
+            let ast = Parser::new(definition, 50).parse_program().into_result()?;
+            let meta = SchemaMeta::from_definition(&ast.kind.unwrap_program()[0])?.unwrap();
+
+            // The definition of a schema item defines the same schema item again. Spans are
+            // encoded along with expressions, so definitions are compared as text.
+            let redefinition = meta.definition(name)?.unwrap();
+            let ast = Parser::new(&redefinition, 50)
+                .parse_program()
+                .into_result()?;
+            let remeta = SchemaMeta::from_definition(&ast.kind.unwrap_program()[0])?.unwrap();
+            assert_eq!(remeta.kind(), meta.kind());
+            assert_eq!(remeta.definition(name)?.unwrap(), redefinition);
+        }
+
+        assert_eq!(
+            SchemaMeta::Renamed("colour".parse()?).definition("color")?,
+            None
+        );
+
+        Ok(())
+    }
+}
@@ -1,31 +1,92 @@
 use crate::ast::Ast;
 
-use super::{NameResolver, SemaResult, TypeInferencer};
+use super::{
+    DatabaseSchema, FunctionRegistry, MemberResolver, NameResolver, SemaResult, TypeChecker,
+    TypeInferencer,
+};
 
 //--------------------------------------------------------------------------------------------------
 // Types
 //--------------------------------------------------------------------------------------------------
 
 /// The semantic analyzer.
-pub struct SemanticAnalyzer<'a> {
+pub struct SemanticAnalyzer<'s, 'a> {
     /// The AST to analyze.
-    ast: &'a mut Ast<'a>,
+    ast: &'s mut Ast<'a>,
+
+    /// How deeply the passes may recurse into the AST.
+    max_depth: usize,
+
+    /// The signatures of the functions that can be called.
+    functions: FunctionRegistry,
+
+    /// The schema items that are already persisted by the database.
+    db_schema: DatabaseSchema,
 }
 
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// How deeply the semantic analysis passes may recurse into an AST by default.
+///
+/// This is well above what the parser produces under its own default limit, so it only rejects
+/// ASTs built some other way.
+pub const DEFAULT_MAX_DEPTH: usize = 1024;
+
 //--------------------------------------------------------------------------------------------------
 // Methods
 //--------------------------------------------------------------------------------------------------
 
-impl<'a> SemanticAnalyzer<'a> {
+impl<'s, 'a> SemanticAnalyzer<'s, 'a> {
     /// Creates a new semantic analyzer.
-    pub fn new(ast: &'a mut Ast<'a>) -> Self {
-        Self { ast }
+    pub fn new(ast: &'s mut Ast<'a>) -> Self {
+        Self {
+            ast,
+            max_depth: DEFAULT_MAX_DEPTH,
+            functions: FunctionRegistry::builtins(),
+            db_schema: DatabaseSchema::default(),
+        }
+    }
+
+    /// Sets how deeply the passes may recurse into the AST.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the signatures of the functions that can be called, in place of the built-ins.
+    pub fn with_functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    /// Sets the schema items that are already persisted by the database.
+    pub fn with_db_schema(mut self, db_schema: DatabaseSchema) -> Self {
+        self.db_schema = db_schema;
+        self
     }
 
     /// Analyzes the AST.
     pub fn analyze(&mut self) -> SemaResult<()> {
-        NameResolver::new().analyze(self.ast)?;
-        TypeInferencer::new().analyze(self.ast)?;
+        NameResolver::new()
+            .with_max_depth(self.max_depth)
+            .with_functions(self.functions.clone())
+            .with_db_schema(self.db_schema.clone())
+            .analyze(self.ast)?;
+        TypeInferencer::new()
+            .with_max_depth(self.max_depth)
+            .with_functions(self.functions.clone())
+            .with_db_schema(self.db_schema.clone())
+            .analyze(self.ast)?;
+        MemberResolver::new()
+            .with_max_depth(self.max_depth)
+            .with_db_schema(self.db_schema.clone())
+            .analyze(self.ast)?;
+        TypeChecker::new()
+            .with_max_depth(self.max_depth)
+            .with_functions(self.functions.clone())
+            .analyze(self.ast)?;
         Ok(())
     }
 
@@ -34,3 +95,45 @@ impl<'a> SemanticAnalyzer<'a> {
         self.ast
     }
 }
+
+//--------------------------------------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+pub(super) mod tests {
+    use crate::{parser::Parser, sema::SemaError};
+
+    use super::*;
+
+    pub(crate) fn analyze(source: &str) -> anyhow::Result<SemaResult<()>> {
+        let mut ast = Parser::new(source, 50).parse_program().into_result()?;
+        Ok(SemanticAnalyzer::new(&mut ast).analyze())
+    }
+
+    #[test]
+    fn test_sema_analyzer_function_calls() -> anyhow::Result<()> {
+        // Called functions are looked up in the function registry rather than the schema.
+        for select in [
+            "SELECT FOLD sum(age) FROM person",
+            "SELECT FOLD count() FROM person",
+            "SELECT FOLD distinct(age) FROM person",
+            "SELECT string::len(name) FROM person",
+        ] {
+            analyze(&format!(
+                "DEFINE TABLE person FIELDS name TYPE string, age TYPE u8; {select}"
+            ))??;
+        }
+
+        assert!(matches!(
+            analyze("LET $a = summ([1, 2])")?,
+            Err(SemaError::UndefinedFunction { name, suggestion: Some(suggestion), .. })
+                if name == "summ" && suggestion == "sum"
+        ));
+
+        // Functions of namespaces the registry does not know all of may be defined in the database.
+        analyze("LET $a = fn::discount(1.0)")??;
+
+        Ok(())
+    }
+}
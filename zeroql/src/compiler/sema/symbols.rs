@@ -3,6 +3,8 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use super::Type;
+
 //--------------------------------------------------------------------------------------------------
 // Types
 //--------------------------------------------------------------------------------------------------
@@ -34,7 +36,10 @@ pub struct SymbolsInner {
 
 /// Metadata associated with a symbol.
 #[derive(Clone, Debug, PartialEq, Default)]
-pub struct SymbolMeta {}
+pub struct SymbolMeta {
+    /// The type of the symbol, [`Type::Any`] until type checking knows better.
+    pub r#type: Type,
+}
 
 //--------------------------------------------------------------------------------------------------
 // Methods
@@ -79,6 +84,19 @@ impl Symbols {
             .map_or(false, |parent| parent.contains(name))
     }
 
+    /// Gets a copy of a symbol metadata from the current table or the nearest parent table that
+    /// has it.
+    pub fn get(&self, name: &str) -> Option<SymbolMeta> {
+        if let Some(meta) = self.inner.table.lock().unwrap().get(name) {
+            return Some(meta.clone());
+        }
+
+        self.inner
+            .parent
+            .as_ref()
+            .and_then(|parent| parent.get(name))
+    }
+
     /// Gets a symbol metadata from the table and calls the provided closure with it.
     pub fn get_with<T>(&self, name: String, f: impl FnOnce(Option<&SymbolMeta>) -> T) -> T {
         let table = self.inner.table.lock().unwrap();
@@ -99,6 +117,13 @@ impl Symbols {
     }
 }
 
+impl SymbolMeta {
+    /// Creates the metadata of a symbol of the given type.
+    pub fn with_type(r#type: Type) -> Self {
+        Self { r#type }
+    }
+}
+
 //--------------------------------------------------------------------------------------------------
 // Trait Implementations
 //--------------------------------------------------------------------------------------------------
@@ -0,0 +1,547 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::{Ast, AstKind, Field, RedefineField, TypeSig},
+    sema::{SemaError, SemaResult},
+};
+
+//--------------------------------------------------------------------------------------------------
+// Types
+//--------------------------------------------------------------------------------------------------
+
+/// The type of a value, as far as semantic analysis knows it.
+///
+/// Literals without a suffix get the [`Type::Integer`] and [`Type::Float`] types, whose width is
+/// decided by where they are used, so `LET $a TYPE u8 = 1` type checks without a cast.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Type {
+    /// A type that is only known at runtime, e.g. the type of a table column without a schema.
+    /// It is compatible with every other type.
+    #[default]
+    Any,
+
+    /// The type of `NONE`.
+    None,
+
+    /// A boolean.
+    Bool,
+
+    /// An integer literal whose width is decided by where it is used.
+    Integer,
+
+    /// An 8-bit unsigned integer.
+    U8,
+
+    /// A 16-bit unsigned integer.
+    U16,
+
+    /// A 32-bit unsigned integer.
+    U32,
+
+    /// A 64-bit unsigned integer.
+    U64,
+
+    /// A 128-bit unsigned integer.
+    U128,
+
+    /// An 8-bit signed integer.
+    I8,
+
+    /// A 16-bit signed integer.
+    I16,
+
+    /// A 32-bit signed integer.
+    I32,
+
+    /// A 64-bit signed integer.
+    I64,
+
+    /// A 128-bit signed integer.
+    I128,
+
+    /// A float literal whose width is decided by where it is used.
+    Float,
+
+    /// A 32-bit float.
+    F32,
+
+    /// A 64-bit float.
+    F64,
+
+    /// A string.
+    String,
+
+    /// A byte string.
+    ByteString,
+
+    /// A regular expression.
+    Regex,
+
+    /// A point in time, e.g. the result of `time::now()`.
+    Datetime,
+
+    /// A span of time, e.g. the result of `duration::weeks(2)`.
+    Duration,
+
+    /// A fixed-length array, e.g. `[u8 10]`.
+    Array(Box<Type>, usize),
+
+    /// A list, e.g. `[string]`.
+    List(Box<Type>),
+
+    /// A tuple, e.g. `(string, u8)`.
+    Tuple(Vec<Type>),
+
+    /// An optional value, e.g. `u8?`.
+    Option(Box<Type>),
+
+    /// An object with the given fields, e.g. the type of an object literal.
+    Object(Vec<(String, Type)>),
+
+    /// A range of values, e.g. the type of `1..10`.
+    Range(Box<Type>),
+
+    /// A generic type, e.g. `map<string, u8>`.
+    Generic(String, Box<[Type]>),
+
+    /// A type defined in the schema, like a table or a `DEFINE TYPE`.
+    Named(String),
+}
+
+//--------------------------------------------------------------------------------------------------
+// Methods
+//--------------------------------------------------------------------------------------------------
+
+impl Type {
+    /// Gets the type a type signature stands for.
+    pub fn from_type_sig(type_sig: &TypeSig) -> SemaResult<Self> {
+        let r#type = match type_sig {
+            TypeSig::Array { r#type, length } => {
+                let AstKind::IntegerLiteral(length) = length.kind else {
+                    return Err(unexpected(length));
+                };
+
+                Type::Array(Box::new(Self::from_type_sig(r#type)?), length as usize)
+            }
+            TypeSig::List(r#type) => Type::List(Box::new(Self::from_type_sig(r#type)?)),
+            TypeSig::Tuple(types) => Type::Tuple(
+                types
+                    .iter()
+                    .map(Self::from_type_sig)
+                    .collect::<SemaResult<_>>()?,
+            ),
+            TypeSig::Option(r#type) => Type::Option(Box::new(Self::from_type_sig(r#type)?)),
+            TypeSig::Generic { name, parameters } => Type::Generic(
+                type_name(name)?,
+                parameters
+                    .iter()
+                    .map(Self::from_type_sig)
+                    .collect::<SemaResult<_>>()?,
+            ),
+            TypeSig::Basic(name) => Self::from_name(&type_name(name)?),
+        };
+
+        Ok(r#type)
+    }
+
+    /// Gets the object type of the fields of a `DEFINE TABLE` or `DEFINE EDGE`.
+    pub fn from_fields(fields: &[Field]) -> SemaResult<Self> {
+        Ok(Type::Object(
+            fields
+                .iter()
+                .map(|field| Ok((type_name(&field.name)?, Self::from_type_sig(&field.r#type)?)))
+                .collect::<SemaResult<_>>()?,
+        ))
+    }
+
+    /// Gets the object type of the fields of a `DEFINE TYPE`.
+    pub fn from_type_fields(fields: &[(Ast, TypeSig)]) -> SemaResult<Self> {
+        Ok(Type::Object(
+            fields
+                .iter()
+                .map(|(name, r#type)| Ok((type_name(name)?, Self::from_type_sig(r#type)?)))
+                .collect::<SemaResult<_>>()?,
+        ))
+    }
+
+    /// Gets the object type left after the fields of a `REDEFINE TABLE`, `REDEFINE EDGE` or
+    /// `REDEFINE TYPE` are renamed and retyped. Fields that are not there yet are added.
+    pub fn redefine_fields(self, redefined: &[RedefineField]) -> SemaResult<Self> {
+        let Type::Object(mut fields) = self else {
+            return Ok(self);
+        };
+
+        for field in redefined {
+            let name = type_name(&field.name)?;
+            let r#type = field.r#type.as_ref().map(Self::from_type_sig).transpose()?;
+
+            let index = match fields.iter().position(|(existing, _)| *existing == name) {
+                Some(index) => index,
+                None => {
+                    fields.push((name, Type::Any));
+                    fields.len() - 1
+                }
+            };
+
+            if let Some(new_name) = &field.new_name {
+                fields[index].0 = type_name(new_name)?;
+            }
+
+            if let Some(r#type) = r#type {
+                fields[index].1 = r#type;
+            }
+        }
+
+        Ok(Type::Object(fields))
+    }
+
+    /// Gets the type with the given name, e.g. `u8` or `person`.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "bool" => Type::Bool,
+            "u8" => Type::U8,
+            "u16" => Type::U16,
+            "u32" => Type::U32,
+            "u64" => Type::U64,
+            "u128" => Type::U128,
+            "i8" => Type::I8,
+            "i16" => Type::I16,
+            "i32" => Type::I32,
+            "i64" => Type::I64,
+            "i128" => Type::I128,
+            "f32" => Type::F32,
+            "f64" => Type::F64,
+            "string" => Type::String,
+            "bytes" => Type::ByteString,
+            "regex" => Type::Regex,
+            "datetime" => Type::Datetime,
+            "duration" => Type::Duration,
+            name => Type::Named(name.to_string()),
+        }
+    }
+
+    /// Gets the type of a field of an object type.
+    pub fn field(&self, name: &str) -> Option<&Type> {
+        match self {
+            Type::Object(fields) => fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, r#type)| r#type),
+            _ => None,
+        }
+    }
+
+    /// Whether the type is an integer type.
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            Type::Integer
+                | Type::U8
+                | Type::U16
+                | Type::U32
+                | Type::U64
+                | Type::U128
+                | Type::I8
+                | Type::I16
+                | Type::I32
+                | Type::I64
+                | Type::I128
+        )
+    }
+
+    /// Whether the type is a float type.
+    pub fn is_float(&self) -> bool {
+        matches!(self, Type::Float | Type::F32 | Type::F64)
+    }
+
+    /// Whether the type is an integer or float type.
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    /// Whether the type is or contains the type of a literal whose width is not decided yet.
+    pub fn is_literal(&self) -> bool {
+        match self {
+            Type::Integer | Type::Float => true,
+            Type::Array(element, _)
+            | Type::List(element)
+            | Type::Option(element)
+            | Type::Range(element) => element.is_literal(),
+            Type::Tuple(elements) => elements.iter().any(Type::is_literal),
+            Type::Object(fields) => fields.iter().any(|(_, r#type)| r#type.is_literal()),
+            _ => false,
+        }
+    }
+
+    /// Gets the type with the widths of literals decided as they are when nothing else decides
+    /// them, i.e. `i64` for integers and `f64` for floats.
+    pub fn defaulted(&self) -> Type {
+        match self {
+            Type::Integer => Type::I64,
+            Type::Float => Type::F64,
+            Type::Array(element, length) => Type::Array(Box::new(element.defaulted()), *length),
+            Type::List(element) => Type::List(Box::new(element.defaulted())),
+            Type::Option(element) => Type::Option(Box::new(element.defaulted())),
+            Type::Range(element) => Type::Range(Box::new(element.defaulted())),
+            Type::Tuple(elements) => Type::Tuple(elements.iter().map(Type::defaulted).collect()),
+            Type::Object(fields) => Type::Object(
+                fields
+                    .iter()
+                    .map(|(name, r#type)| (name.clone(), r#type.defaulted()))
+                    .collect(),
+            ),
+            r#type => r#type.clone(),
+        }
+    }
+
+    /// Whether a value of this type can be used where a value of the `target` type is expected.
+    ///
+    /// Integers and floats can be used as wider types of their kind that hold all of their values.
+    pub fn is_assignable_to(&self, target: &Type) -> bool {
+        match (self, target) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (source, target) if source == target => true,
+            (Type::None, Type::Option(_)) => true,
+            (Type::Option(source), Type::Option(target)) => source.is_assignable_to(target),
+            (source, Type::Option(target)) => source.is_assignable_to(target),
+            (Type::Integer, target) => target.is_numeric(),
+            (Type::Float, target) => target.is_float(),
+            (source, target) if source.is_integer() && target.is_integer() => {
+                let (source_bits, source_signed) = source.integer_width();
+                let (target_bits, target_signed) = target.integer_width();
+                match (source_signed, target_signed) {
+                    (false, true) => source_bits < target_bits,
+                    (true, false) => false,
+                    _ => source_bits <= target_bits,
+                }
+            }
+            (Type::F32, Type::F64) => true,
+            (Type::Array(source, n), Type::Array(target, m)) => {
+                n == m && source.is_assignable_to(target)
+            }
+            (Type::Array(source, _) | Type::List(source), Type::List(target)) => {
+                source.is_assignable_to(target)
+            }
+            (Type::ByteString, Type::Array(target, _) | Type::List(target)) => {
+                Type::U8.is_assignable_to(target)
+            }
+            (Type::Tuple(sources), Type::Tuple(targets)) => {
+                sources.len() == targets.len()
+                    && sources
+                        .iter()
+                        .zip(targets)
+                        .all(|(source, target)| source.is_assignable_to(target))
+            }
+            (Type::Range(source), Type::Range(target)) => source.is_assignable_to(target),
+            (Type::Object(_), Type::Named(_) | Type::Generic(..)) => true,
+            _ => false,
+        }
+    }
+
+    /// Whether an integer literal with the given magnitude and sign fits in this type.
+    ///
+    /// Every literal fits in a type that is not an integer type.
+    pub fn fits_integer(&self, value: u128, negative: bool) -> bool {
+        if !self.is_integer() || *self == Type::Integer {
+            return true;
+        }
+
+        let (bits, signed) = self.integer_width();
+        match (signed, negative) {
+            (false, true) => value == 0,
+            (false, false) => bits == 128 || value < 1 << bits,
+            (true, true) => value <= 1 << (bits - 1),
+            (true, false) => value < 1 << (bits - 1),
+        }
+    }
+
+    /// Whether values of the two types can be compared with each other.
+    pub fn is_comparable_with(&self, other: &Type) -> bool {
+        self.is_assignable_to(other)
+            || other.is_assignable_to(self)
+            || (self.is_numeric() && other.is_numeric())
+    }
+
+    /// Gets the type of the elements produced by iterating over a value of this type.
+    ///
+    /// Returns `None` if the type cannot be iterated over.
+    pub fn element(&self) -> Option<Type> {
+        match self {
+            Type::Array(element, _) | Type::List(element) | Type::Range(element) => {
+                Some(*element.clone())
+            }
+            Type::String => Some(Type::String),
+            Type::ByteString => Some(Type::U8),
+            Type::Tuple(_) | Type::Object(_) | Type::Generic(..) | Type::Named(_) | Type::Any => {
+                Some(Type::Any)
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the type both types can be used as, like the element type of `[1, 2.5]`.
+    ///
+    /// Falls back to [`Type::Any`] if there is no such type.
+    pub fn unify(self, other: Type) -> Type {
+        if other.is_assignable_to(&self) {
+            self
+        } else if self.is_assignable_to(&other) {
+            other
+        } else if self.is_numeric() && other.is_numeric() {
+            Self::promote(&self, &other)
+        } else {
+            Type::Any
+        }
+    }
+
+    /// Gets the type of an arithmetic operation on two numeric types.
+    ///
+    /// Literals take the type of the other operand. Otherwise floats win over integers, and the
+    /// wider of two integers wins, becoming signed if either is.
+    pub fn promote(left: &Type, right: &Type) -> Type {
+        match (left, right) {
+            (Type::Any, _) | (_, Type::Any) => Type::Any,
+            (left, right) if left == right => left.clone(),
+            (Type::Integer, other) | (other, Type::Integer) => other.clone(),
+            (Type::Float, other) | (other, Type::Float) if other.is_float() => other.clone(),
+            (Type::Float, _) | (_, Type::Float) => Type::Float,
+            (Type::F64, _) | (_, Type::F64) => Type::F64,
+            (Type::F32, _) | (_, Type::F32) => Type::F32,
+            (left, right) => {
+                let (left_bits, left_signed) = left.integer_width();
+                let (right_bits, right_signed) = right.integer_width();
+                Self::integer(left_bits.max(right_bits), left_signed || right_signed)
+            }
+        }
+    }
+
+    /// Gets the width of an integer type and whether it is signed.
+    fn integer_width(&self) -> (u32, bool) {
+        match self {
+            Type::U8 => (8, false),
+            Type::U16 => (16, false),
+            Type::U32 => (32, false),
+            Type::U64 => (64, false),
+            Type::I8 => (8, true),
+            Type::I16 => (16, true),
+            Type::I32 => (32, true),
+            Type::I64 => (64, true),
+            Type::I128 => (128, true),
+            _ => (128, false),
+        }
+    }
+
+    /// Gets the integer type of the given width.
+    fn integer(bits: u32, signed: bool) -> Type {
+        match (bits, signed) {
+            (8, false) => Type::U8,
+            (16, false) => Type::U16,
+            (32, false) => Type::U32,
+            (64, false) => Type::U64,
+            (8, true) => Type::I8,
+            (16, true) => Type::I16,
+            (32, true) => Type::I32,
+            (64, true) => Type::I64,
+            (_, true) => Type::I128,
+            (_, false) => Type::U128,
+        }
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Gets a name in a type signature or definition, joining the segments of a scoped name with `::`.
+pub(crate) fn type_name(name: &Ast) -> SemaResult<String> {
+    match &name.kind {
+        AstKind::Identifier(name) => Ok(name.to_string()),
+        AstKind::ScopedIdentifier(segments) => Ok(segments
+            .iter()
+            .map(type_name)
+            .collect::<SemaResult<Vec<_>>>()?
+            .join("::")),
+        _ => Err(unexpected(name)),
+    }
+}
+
+/// Creates an error for a node that should not be in a type signature.
+pub(crate) fn unexpected(ast: &Ast) -> SemaError {
+    SemaError::UnexpectedAstKind {
+        kind: ast.kind.to_string(),
+        span: ast.span.clone(),
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Trait Implementations
+//--------------------------------------------------------------------------------------------------
+
+impl Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Any => write!(f, "any"),
+            Type::None => write!(f, "none"),
+            Type::Bool => write!(f, "bool"),
+            Type::Integer => write!(f, "integer"),
+            Type::U8 => write!(f, "u8"),
+            Type::U16 => write!(f, "u16"),
+            Type::U32 => write!(f, "u32"),
+            Type::U64 => write!(f, "u64"),
+            Type::U128 => write!(f, "u128"),
+            Type::I8 => write!(f, "i8"),
+            Type::I16 => write!(f, "i16"),
+            Type::I32 => write!(f, "i32"),
+            Type::I64 => write!(f, "i64"),
+            Type::I128 => write!(f, "i128"),
+            Type::Float => write!(f, "float"),
+            Type::F32 => write!(f, "f32"),
+            Type::F64 => write!(f, "f64"),
+            Type::String => write!(f, "string"),
+            Type::ByteString => write!(f, "bytes"),
+            Type::Regex => write!(f, "regex"),
+            Type::Datetime => write!(f, "datetime"),
+            Type::Duration => write!(f, "duration"),
+            Type::Array(element, length) => write!(f, "[{element} {length}]"),
+            Type::List(element) => write!(f, "[{element}]"),
+            Type::Tuple(elements) => {
+                write!(f, "(")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                if elements.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Type::Option(inner) => write!(f, "{inner}?"),
+            Type::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, r#type)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {name}: {type}")?;
+                }
+                write!(f, " }}")
+            }
+            Type::Range(element) => write!(f, "range<{element}>"),
+            Type::Generic(name, parameters) => {
+                write!(f, "{name}<")?;
+                for (i, parameter) in parameters.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{parameter}")?;
+                }
+                write!(f, ">")
+            }
+            Type::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//--------------------------------------------------------------------------------------------------
+// Constants
+//--------------------------------------------------------------------------------------------------
+
+/// How much stack must be left for [`ensure_stack`] to run its function on the current stack.
+///
+/// This has to cover the deepest stretch of calls between two calls to [`ensure_stack`]. For the
+/// parser that includes cloning and dropping whole subtrees, which recurse as deep as the AST and
+/// take a few megabytes in an unoptimized build.
+const RED_ZONE: usize = 4 * 1024 * 1024;
+
+/// The size of the stack segments allocated by [`ensure_stack`].
+const SEGMENT_SIZE: usize = 16 * 1024 * 1024;
+
+//--------------------------------------------------------------------------------------------------
+// Functions
+//--------------------------------------------------------------------------------------------------
+
+/// Runs `f`, moving it onto a new stack segment on the heap if the current stack is running low.
+///
+/// The parser and the semantic analysis passes call this every time they go one level deeper so
+/// that their nesting limits, not the stack size of the calling thread, decide how deeply an input
+/// can nest.
+pub(crate) fn ensure_stack<R>(f: impl FnOnce() -> R) -> R {
+    stacker::maybe_grow(RED_ZONE, SEGMENT_SIZE, f)
+}